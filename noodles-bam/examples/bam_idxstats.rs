@@ -4,31 +4,85 @@
 //! tab-delimited record with the following columns for a region: reference sequence name,
 //! reference sequence length, number of mapped records, and number of unmapped records.
 //!
+//! The record counts are read from the index metadata (pseudo-bin). If the index does not have any
+//! metadata, the records are counted by reading the entire BAM file.
+//!
 //! The result matches the output of `samtools idxstats <src>`.
 
-use std::{env, fs::File, path::PathBuf};
+use std::{env, fs::File, io, path::PathBuf};
 
 use noodles_bam::{self as bam, bai};
 use noodles_sam as sam;
 
+fn count_records<R>(
+    reader: &mut bam::Reader<R>,
+    reference_sequence_count: usize,
+) -> io::Result<(Vec<(u64, u64)>, u64)>
+where
+    R: io::Read,
+{
+    let mut counts = vec![(0, 0); reference_sequence_count];
+    let mut unplaced_unmapped_record_count = 0;
+
+    let mut record = bam::Record::default();
+
+    while reader.read_record(&mut record)? != 0 {
+        let count = match record.reference_sequence_id() {
+            Some(id) => counts.get_mut(i32::from(id) as usize).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid reference sequence ID")
+            })?,
+            None => {
+                unplaced_unmapped_record_count += 1;
+                continue;
+            }
+        };
+
+        if record.flags().is_unmapped() {
+            count.1 += 1;
+        } else {
+            count.0 += 1;
+        }
+    }
+
+    Ok((counts, unplaced_unmapped_record_count))
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let src = env::args().nth(1).map(PathBuf::from).expect("missing src");
 
     let mut reader = File::open(&src).map(bam::Reader::new)?;
     let header: sam::Header = reader.read_header()?.parse()?;
+    reader.read_reference_sequences()?;
 
     let index = bai::read(src.with_extension("bam.bai"))?;
 
-    for (reference_sequence, index_reference_sequence) in header
+    let has_metadata = index
         .reference_sequences()
-        .values()
-        .zip(index.reference_sequences())
-    {
-        let (mapped_record_count, unmapped_record_count) = index_reference_sequence
-            .metadata()
-            .map(|m| (m.mapped_record_count(), m.unmapped_record_count()))
-            .unwrap_or_default();
+        .iter()
+        .any(|reference_sequence| reference_sequence.metadata().is_some());
+
+    let (counts, unplaced_unmapped_record_count) = if has_metadata {
+        let counts = index
+            .reference_sequences()
+            .iter()
+            .map(|reference_sequence| {
+                reference_sequence
+                    .metadata()
+                    .map(|m| (m.mapped_record_count(), m.unmapped_record_count()))
+                    .unwrap_or_default()
+            })
+            .collect();
 
+        let n = index.unplaced_unmapped_read_count().unwrap_or_default();
+
+        (counts, n)
+    } else {
+        count_records(&mut reader, header.reference_sequences().len())?
+    };
+
+    for (reference_sequence, (mapped_record_count, unmapped_record_count)) in
+        header.reference_sequences().values().zip(counts)
+    {
         println!(
             "{}\t{}\t{}\t{}",
             reference_sequence.name(),
@@ -38,8 +92,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
-    let unmapped_record_count = index.unplaced_unmapped_read_count().unwrap_or_default();
-    println!("*\t0\t0\t{}", unmapped_record_count);
+    println!("*\t0\t0\t{}", unplaced_unmapped_record_count);
 
     Ok(())
 }
@@ -39,8 +39,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .get_index(reference_sequence_id as usize)
             .ok_or("invalid reference sequence id")?;
 
-        let start = record.position().map(i32::from).expect("missing position");
-        let len = record.cigar().reference_len().map(|len| len as i32)?;
+        let start = record.position().map(i64::from).expect("missing position");
+        let len = record.cigar().reference_len().map(i64::from)?;
         let end = start + len - 1;
 
         println!(
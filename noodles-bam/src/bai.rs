@@ -29,11 +29,17 @@ mod writer;
 
 pub use self::{index::Index, reader::Reader, writer::Writer};
 
+// The BAI binning scheme covers 0-based positions in [0, 2^29), i.e., 1-based positions up to and
+// including 2^29.
+pub(crate) const MAX_POSITION: i64 = 1 << 29;
+
 use std::{fs::File, io, path::Path};
 
+use noodles::{partition, Region, RegionSet};
 use noodles_bgzf::VirtualPosition;
+use noodles_sam::header::ReferenceSequences;
 
-use self::index::reference_sequence::{bin::Chunk, Bin};
+use self::index::reference_sequence::{self, bin::Chunk, Bin};
 
 static MAGIC_NUMBER: &[u8] = b"BAI\x01";
 
@@ -174,6 +180,60 @@ pub fn optimize_chunks(chunks: &[Chunk], min_offset: VirtualPosition) -> Vec<Chu
     merged_chunks
 }
 
+/// Partitions reference sequences into shards of roughly equal estimated record counts.
+///
+/// Record counts are estimated per 16 kbp window using only the index (see
+/// [`index::ReferenceSequence::estimate_record_counts`]). Reference sequences without metadata
+/// have no weight. `reference_sequences` must be the dictionary of the associated BAM file.
+///
+/// If `targets` is given, only the target regions are partitioned. This returns at most `n`
+/// shards. See [`noodles::partition`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use noodles::Region;
+/// use noodles_bam::bai;
+/// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+///
+/// let reference_sequences: ReferenceSequences = vec![
+///     (String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 8)),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// let index = bai::Index::default();
+///
+/// assert_eq!(
+///     bai::partition(&index, &reference_sequences, None, 2),
+///     [vec![Region::mapped("sq0", 1, 8)]]
+/// );
+/// ```
+pub fn partition(
+    index: &Index,
+    reference_sequences: &ReferenceSequences,
+    targets: Option<&RegionSet>,
+    n: usize,
+) -> Vec<Vec<Region>> {
+    let weights: Vec<_> = index
+        .reference_sequences()
+        .iter()
+        .map(|reference_sequence| {
+            reference_sequence
+                .estimate_record_counts()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    partition::by_window_weights(
+        reference_sequences,
+        targets,
+        i64::from(reference_sequence::WINDOW_SIZE),
+        &weights,
+        n,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,4 +282,50 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_partition() {
+        use noodles_sam::header::ReferenceSequence;
+
+        use self::index::reference_sequence::Metadata;
+
+        let reference_sequences: ReferenceSequences = vec![
+            (
+                String::from("sq0"),
+                ReferenceSequence::new(String::from("sq0"), 32768),
+            ),
+            (
+                String::from("sq1"),
+                ReferenceSequence::new(String::from("sq1"), 8),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        // The estimated record counts of the windows of sq0 are [6, 2].
+        let index = Index::new(
+            vec![index::ReferenceSequence::new(
+                Vec::new(),
+                vec![VirtualPosition::from(0), VirtualPosition::from(3 << 16)],
+                Some(Metadata::new(
+                    VirtualPosition::from(0),
+                    VirtualPosition::from(4 << 16),
+                    8,
+                    0,
+                )),
+            )],
+            None,
+        );
+
+        assert_eq!(
+            partition(&index, &reference_sequences, None, 2),
+            [
+                vec![Region::mapped("sq0", 1, 10923)],
+                vec![
+                    Region::mapped("sq0", 10924, 32768),
+                    Region::mapped("sq1", 1, 8)
+                ],
+            ]
+        );
+    }
 }
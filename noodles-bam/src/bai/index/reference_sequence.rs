@@ -11,7 +11,7 @@ pub use self::{bin::Bin, metadata::Metadata};
 use bit_vec::BitVec;
 use noodles_bgzf as bgzf;
 
-const WINDOW_SIZE: i32 = 16384;
+pub(crate) const WINDOW_SIZE: i32 = 16384;
 
 /// A reference sequence in the BAM index.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -136,6 +136,70 @@ impl ReferenceSequence {
         let i = ((start - 1) / WINDOW_SIZE) as usize;
         self.intervals.get(i).copied().unwrap_or_default()
     }
+
+    /// Estimates the number of records in each 16 kbp window of the linear index.
+    ///
+    /// This uses only the index: the record count in the metadata is distributed over the windows
+    /// proportional to the distance between consecutive linear index offsets, i.e., the size of
+    /// the compressed data that starts in each window. No records are read, making this suitable
+    /// for quick coverage previews. The estimate assumes records compress uniformly.
+    ///
+    /// The returned list has the same length as [`Self::intervals`]. This returns `None` if the
+    /// reference sequence has no metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::bai::index::{reference_sequence::Metadata, ReferenceSequence};
+    /// use noodles_bgzf::VirtualPosition;
+    ///
+    /// let reference_sequence = ReferenceSequence::new(
+    ///     Vec::new(),
+    ///     vec![VirtualPosition::from(0), VirtualPosition::from(3 << 16)],
+    ///     Some(Metadata::new(VirtualPosition::from(0), VirtualPosition::from(4 << 16), 8, 0)),
+    /// );
+    ///
+    /// assert_eq!(reference_sequence.estimate_record_counts(), Some(vec![6, 2]));
+    /// ```
+    pub fn estimate_record_counts(&self) -> Option<Vec<u64>> {
+        let metadata = self.metadata()?;
+        let record_count = metadata.mapped_record_count() + metadata.unmapped_record_count();
+
+        let mut positions = self.intervals.clone();
+        positions.push(metadata.end_position());
+
+        // Offsets in the same BGZF block only differ by their uncompressed positions.
+        let total_compressed_len = metadata
+            .end_position()
+            .compressed()
+            .saturating_sub(metadata.start_position().compressed());
+
+        let distance = |a: bgzf::VirtualPosition, b: bgzf::VirtualPosition| {
+            if total_compressed_len > 0 {
+                b.compressed().saturating_sub(a.compressed())
+            } else {
+                u64::from(b.uncompressed().saturating_sub(a.uncompressed()))
+            }
+        };
+
+        let weights: Vec<_> = positions.windows(2).map(|w| distance(w[0], w[1])).collect();
+
+        let total_weight: u64 = weights.iter().sum();
+
+        if total_weight == 0 {
+            return Some(vec![0; weights.len()]);
+        }
+
+        let counts = weights
+            .iter()
+            .map(|&weight| {
+                let n = (record_count as f64) * (weight as f64) / (total_weight as f64);
+                n.round() as u64
+            })
+            .collect();
+
+        Some(counts)
+    }
 }
 
 // 0-based, [start, end)
@@ -190,4 +254,44 @@ mod tests {
         }
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_estimate_record_counts() {
+        let reference_sequence = ReferenceSequence::new(Vec::new(), Vec::new(), None);
+        assert!(reference_sequence.estimate_record_counts().is_none());
+
+        let intervals = vec![
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(10 << 16),
+        ];
+        let metadata = Metadata::new(
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(40 << 16),
+            100,
+            0,
+        );
+        let reference_sequence = ReferenceSequence::new(Vec::new(), intervals, Some(metadata));
+        assert_eq!(
+            reference_sequence.estimate_record_counts(),
+            Some(vec![0, 25, 75])
+        );
+
+        // All records are in a single BGZF block.
+        let intervals = vec![
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(30),
+        ];
+        let metadata = Metadata::new(
+            bgzf::VirtualPosition::from(0),
+            bgzf::VirtualPosition::from(40),
+            4,
+            0,
+        );
+        let reference_sequence = ReferenceSequence::new(Vec::new(), intervals, Some(metadata));
+        assert_eq!(
+            reference_sequence.estimate_record_counts(),
+            Some(vec![3, 1])
+        );
+    }
 }
@@ -2,7 +2,7 @@ use std::{cmp, collections::HashMap, io};
 
 use noodles_bgzf as bgzf;
 
-use crate::Record;
+use crate::{bai::MAX_POSITION, Record};
 
 use super::{
     bin::{self, Chunk},
@@ -64,12 +64,20 @@ impl Builder {
     }
 
     fn update_linear_index(&mut self, record: &Record, chunk: Chunk) -> io::Result<()> {
-        let start = record.position().map(i32::from).expect("missing position");
-        let reference_len = record.cigar().reference_len().map(|len| len as i32)?;
+        let start = record.position().map(i64::from).expect("missing position");
+        let reference_len = record.cigar().reference_len().map(i64::from)?;
         let end = start + reference_len - 1;
 
-        let linear_index_start_offset = ((start - 1) / WINDOW_SIZE) as usize;
-        let linear_index_end_offset = ((end - 1) / WINDOW_SIZE) as usize;
+        if end > MAX_POSITION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("record end position is out of range for BAI: {}", end),
+            ));
+        }
+
+        let window_size = i64::from(WINDOW_SIZE);
+        let linear_index_start_offset = ((start - 1) / window_size) as usize;
+        let linear_index_end_offset = ((end - 1) / window_size) as usize;
 
         if linear_index_end_offset >= self.intervals.len() {
             self.intervals.resize(
@@ -211,4 +211,43 @@ mod tests {
         let mut reader = Reader::new(&data[..]);
         assert!(reader.read_header().is_err());
     }
+
+    #[test]
+    fn test_read_bins_with_metadata_pseudo_bin() -> io::Result<()> {
+        use byteorder::WriteBytesExt;
+
+        let mut data = Vec::new();
+        // n_bin
+        data.write_u32::<LittleEndian>(2)?;
+        // bin
+        data.write_u32::<LittleEndian>(4681)?;
+        data.write_u32::<LittleEndian>(1)?;
+        data.write_u64::<LittleEndian>(55)?;
+        data.write_u64::<LittleEndian>(89)?;
+        // pseudo-bin
+        data.write_u32::<LittleEndian>(reference_sequence::metadata::MAGIC_NUMBER)?;
+        data.write_u32::<LittleEndian>(2)?;
+        data.write_u64::<LittleEndian>(55)?;
+        data.write_u64::<LittleEndian>(89)?;
+        data.write_u64::<LittleEndian>(1)?;
+        data.write_u64::<LittleEndian>(0)?;
+
+        let mut reader = &data[..];
+        let (bins, metadata) = read_bins(&mut reader)?;
+
+        assert_eq!(bins.len(), 1);
+        assert_eq!(bins[0].id(), 4681);
+
+        assert_eq!(
+            metadata,
+            Some(Metadata::new(
+                bgzf::VirtualPosition::from(55),
+                bgzf::VirtualPosition::from(89),
+                1,
+                0
+            ))
+        );
+
+        Ok(())
+    }
 }
@@ -224,4 +224,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_index_with_metadata() -> io::Result<()> {
+        use crate::bai::index::reference_sequence::Metadata;
+
+        let metadata = Metadata::new(
+            bgzf::VirtualPosition::from(55),
+            bgzf::VirtualPosition::from(89),
+            1,
+            0,
+        );
+        let reference_sequences = vec![ReferenceSequence::new(
+            Vec::new(),
+            Vec::new(),
+            Some(metadata),
+        )];
+        let index = Index::new(reference_sequences, Some(0));
+
+        let mut actual_writer = Writer::new(Vec::new());
+        actual_writer.write_index(&index)?;
+
+        let mut expected = Vec::new();
+        // n_ref
+        expected.write_u32::<LittleEndian>(1)?;
+        // n_bin
+        expected.write_u32::<LittleEndian>(1)?;
+        // bin
+        expected.write_u32::<LittleEndian>(37450)?;
+        // n_chunk
+        expected.write_u32::<LittleEndian>(2)?;
+        // ref_beg, ref_end
+        expected.write_u64::<LittleEndian>(55)?;
+        expected.write_u64::<LittleEndian>(89)?;
+        // n_mapped, n_unmapped
+        expected.write_u64::<LittleEndian>(1)?;
+        expected.write_u64::<LittleEndian>(0)?;
+        // n_intv
+        expected.write_u32::<LittleEndian>(0)?;
+        // n_no_coor
+        expected.write_u64::<LittleEndian>(0)?;
+
+        assert_eq!(actual_writer.get_ref(), &expected);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,635 @@
+//! BAM conformance validation.
+//!
+//! [`validate`] reads a BAM file from the start and checks its structure and records against the
+//! BAM specification. [`validate_with_index`] additionally checks that an associated BAM index
+//! (BAI) is consistent with the file.
+//!
+//! Issues are reported with the following codes:
+//!
+//!   * `invalid_header`: the SAM header cannot be parsed.
+//!   * `reference_sequences_mismatch`: the binary reference sequences do not match the `@SQ`
+//!     records in the SAM header.
+//!   * `invalid_record`: the record layout is inconsistent with its block size.
+//!   * `invalid_reference_sequence_id`: the reference sequence ID or mate reference sequence ID is
+//!     not in the list of reference sequences.
+//!   * `position_out_of_bounds`: the position is beyond the end of the reference sequence.
+//!   * `invalid_read_name`: the read name is not NUL-terminated or is empty.
+//!   * `cigar_sequence_mismatch`: the CIGAR read length differs from the sequence length.
+//!   * `invalid_bin`: the bin is not the one computed from the alignment interval.
+//!   * `invalid_data`: a data field cannot be parsed.
+//!   * `unsorted`: the file is sorted by coordinate (`SO:coordinate`), but a record is out of
+//!     order.
+//!   * `truncated`: the file ends in the middle of a header or record.
+//!   * `index_*`: the index is inconsistent with the file.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use byteorder::{ByteOrder, LittleEndian};
+use noodles_core::conformance::Report;
+use noodles_sam::{self as sam, header::header::SortOrder, record::cigar::op::Kind};
+
+use super::{
+    bai,
+    writer::record::{region_to_bin, UNMAPPED_BIN},
+    Reader, Record,
+};
+
+/// Validates a BAM file.
+///
+/// The reader is expected to be at the start of the stream.
+///
+/// Malformed or truncated input is recorded in the report. Any other I/O error is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, conformance};
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let report = conformance::validate(&mut reader)?;
+///
+/// print!("{}", report);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate<R>(reader: &mut Reader<R>) -> io::Result<Report>
+where
+    R: Read,
+{
+    let mut report = Report::default();
+    validate_inner(reader, &mut report)?;
+    Ok(report)
+}
+
+/// Validates a BAM file and its associated index.
+///
+/// In addition to the checks in [`validate`], this checks that the index has an entry for each
+/// reference sequence and that its record counts, if present, match the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, bai, conformance};
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let index = bai::read("sample.bam.bai")?;
+/// let report = conformance::validate_with_index(&mut reader, &index)?;
+///
+/// print!("{}", report);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate_with_index<R>(reader: &mut Reader<R>, index: &bai::Index) -> io::Result<Report>
+where
+    R: Read,
+{
+    let mut report = Report::default();
+
+    if let Some(counts) = validate_inner(reader, &mut report)? {
+        validate_index(index, &counts, &mut report);
+    }
+
+    Ok(report)
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    reference_sequence_count: usize,
+    // (mapped, unmapped) for each reference sequence ID
+    placed: HashMap<usize, (u64, u64)>,
+    unplaced_unmapped: u64,
+}
+
+// Returns `None` if reading stopped early.
+fn validate_inner<R>(reader: &mut Reader<R>, report: &mut Report) -> io::Result<Option<Counts>>
+where
+    R: Read,
+{
+    let raw_header = match reader.read_header() {
+        Ok(s) => s,
+        Err(e) if is_format_error(&e) => {
+            report.add_error(error_code(&e, "invalid_header"), None, e.to_string());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let header = match raw_header.parse::<sam::Header>() {
+        Ok(header) => Some(header),
+        Err(e) => {
+            report.add_error("invalid_header", None, e.to_string());
+            None
+        }
+    };
+
+    let reference_sequences = match reader.read_reference_sequences() {
+        Ok(reference_sequences) => reference_sequences,
+        Err(e) if is_format_error(&e) => {
+            report.add_error(error_code(&e, "invalid_header"), None, e.to_string());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    if let Some(header) = &header {
+        validate_reference_sequences(header, &reference_sequences, report);
+    }
+
+    let is_coordinate_sorted = header
+        .as_ref()
+        .and_then(|h| h.header())
+        .and_then(|h| h.sort_order())
+        .map(|sort_order| sort_order == SortOrder::Coordinate)
+        .unwrap_or(false);
+
+    let reference_sequence_lens: Vec<_> = reference_sequences.iter().map(|rs| rs.len()).collect();
+
+    let mut counts = Counts {
+        reference_sequence_count: reference_sequence_lens.len(),
+        ..Default::default()
+    };
+
+    let mut record = Record::default();
+    let mut last_key = None;
+    let mut i = 0;
+
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) if is_format_error(&e) => {
+                report.add_error(error_code(&e, "invalid_record"), Some(i), e.to_string());
+                *report.record_count_mut() = i;
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        }
+
+        if let Some(key) =
+            validate_record(&record, i, &reference_sequence_lens, &mut counts, report)
+        {
+            if is_coordinate_sorted {
+                if let Some(last_key) = last_key {
+                    if key < last_key {
+                        report.add_error("unsorted", Some(i), "record is out of coordinate order");
+                    }
+                }
+
+                last_key = Some(key);
+            }
+        }
+
+        i += 1;
+    }
+
+    *report.record_count_mut() = i;
+
+    Ok(Some(counts))
+}
+
+fn validate_reference_sequences(
+    header: &sam::Header,
+    reference_sequences: &[sam::header::ReferenceSequence],
+    report: &mut Report,
+) {
+    let header_reference_sequences = header.reference_sequences();
+
+    // A SAM header without `@SQ` records is allowed; the binary reference sequences are used.
+    if header_reference_sequences.is_empty() {
+        return;
+    }
+
+    if header_reference_sequences.len() != reference_sequences.len() {
+        report.add_error(
+            "reference_sequences_mismatch",
+            None,
+            format!(
+                "SAM header has {} reference sequences, but the BAM has {}",
+                header_reference_sequences.len(),
+                reference_sequences.len()
+            ),
+        );
+
+        return;
+    }
+
+    for (a, b) in header_reference_sequences.values().zip(reference_sequences) {
+        if a.name() != b.name() || a.len() != b.len() {
+            report.add_error(
+                "reference_sequences_mismatch",
+                None,
+                format!(
+                    "SAM header reference sequence {}:{} does not match BAM reference sequence {}:{}",
+                    a.name(),
+                    a.len(),
+                    b.name(),
+                    b.len()
+                ),
+            );
+        }
+    }
+}
+
+// Returns the sort key of the record, i.e., the reference sequence ID (unmapped last) and 0-based
+// position.
+fn validate_record(
+    record: &Record,
+    i: u64,
+    reference_sequence_lens: &[i32],
+    counts: &mut Counts,
+    report: &mut Report,
+) -> Option<(usize, i32)> {
+    let reference_sequence_count = reference_sequence_lens.len();
+
+    let ref_id = read_reference_sequence_id(record, 0, i, reference_sequence_count, report)?;
+    read_reference_sequence_id(record, 20, i, reference_sequence_count, report)?;
+
+    let pos = LittleEndian::read_i32(&record[4..]);
+    let flags = record.flags();
+
+    match ref_id {
+        Some(id) => {
+            let len = reference_sequence_lens[id];
+
+            if pos < -1 || pos >= len {
+                report.add_error(
+                    "position_out_of_bounds",
+                    Some(i),
+                    format!("position {} is out of bounds for length {}", pos + 1, len),
+                );
+            }
+
+            let entry = counts.placed.entry(id).or_default();
+
+            if flags.is_unmapped() {
+                entry.1 += 1;
+            } else {
+                entry.0 += 1;
+            }
+        }
+        None => counts.unplaced_unmapped += 1,
+    }
+
+    match record.read_name() {
+        Ok(name) if name.to_bytes().is_empty() => {
+            report.add_error("invalid_read_name", Some(i), "read name is empty");
+        }
+        Ok(_) => {}
+        Err(e) => report.add_error("invalid_read_name", Some(i), e.to_string()),
+    }
+
+    let reference_len = validate_cigar(record, i, report)?;
+
+    validate_bin(record, pos, reference_len, i, report);
+
+    for result in record.data().fields() {
+        if let Err(e) = result {
+            report.add_error("invalid_data", Some(i), e.to_string());
+            break;
+        }
+    }
+
+    let key = (ref_id.unwrap_or(usize::MAX), pos);
+
+    Some(key)
+}
+
+fn read_reference_sequence_id(
+    record: &Record,
+    offset: usize,
+    i: u64,
+    reference_sequence_count: usize,
+    report: &mut Report,
+) -> Option<Option<usize>> {
+    let id = LittleEndian::read_i32(&record[offset..]);
+
+    if id == -1 {
+        return Some(None);
+    }
+
+    if id >= 0 && (id as usize) < reference_sequence_count {
+        Some(Some(id as usize))
+    } else {
+        report.add_error(
+            "invalid_reference_sequence_id",
+            Some(i),
+            format!(
+                "invalid reference sequence ID {} (reference sequence count = {})",
+                id, reference_sequence_count
+            ),
+        );
+
+        None
+    }
+}
+
+// Returns the reference length of the alignment.
+fn validate_cigar(record: &Record, i: u64, report: &mut Report) -> Option<u32> {
+    let mut read_len = 0;
+    let mut reference_len = 0;
+    let mut is_empty = true;
+
+    for result in record.cigar().ops() {
+        let op = match result {
+            Ok(op) => op,
+            Err(e) => {
+                report.add_error("invalid_cigar", Some(i), e.to_string());
+                return None;
+            }
+        };
+
+        is_empty = false;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                read_len += op.len();
+                reference_len += op.len();
+            }
+            Kind::Insertion | Kind::SoftClip => read_len += op.len(),
+            Kind::Deletion | Kind::Skip => reference_len += op.len(),
+            Kind::HardClip | Kind::Pad => {}
+        }
+    }
+
+    let base_count = record.sequence().base_count();
+
+    if !is_empty && base_count > 0 && read_len as usize != base_count {
+        report.add_error(
+            "cigar_sequence_mismatch",
+            Some(i),
+            format!(
+                "CIGAR read length {} does not match sequence length {}",
+                read_len, base_count
+            ),
+        );
+    }
+
+    Some(reference_len)
+}
+
+fn validate_bin(record: &Record, pos: i32, reference_len: u32, i: u64, report: &mut Report) {
+    let actual = record.bin();
+
+    let expected = if pos < 0 {
+        UNMAPPED_BIN
+    } else {
+        // An alignment without reference bases occupies a 1-base interval.
+        let start = i64::from(pos);
+        let end = start + i64::from(reference_len.max(1));
+        region_to_bin(start, end) as u16
+    };
+
+    if actual != expected {
+        report.add_error(
+            "invalid_bin",
+            Some(i),
+            format!("expected bin {}, got {}", expected, actual),
+        );
+    }
+}
+
+fn validate_index(index: &bai::Index, counts: &Counts, report: &mut Report) {
+    let index_reference_sequences = index.reference_sequences();
+
+    if index_reference_sequences.len() != counts.reference_sequence_count {
+        report.add_error(
+            "index_reference_sequence_count_mismatch",
+            None,
+            format!(
+                "index has {} reference sequences, but the BAM has {}",
+                index_reference_sequences.len(),
+                counts.reference_sequence_count
+            ),
+        );
+    }
+
+    for (id, reference_sequence) in index_reference_sequences.iter().enumerate() {
+        let metadata = match reference_sequence.metadata() {
+            Some(metadata) => metadata,
+            None => continue,
+        };
+
+        let (mapped, unmapped) = counts.placed.get(&id).copied().unwrap_or_default();
+
+        if metadata.mapped_record_count() != mapped || metadata.unmapped_record_count() != unmapped
+        {
+            report.add_error(
+                "index_record_count_mismatch",
+                None,
+                format!(
+                    "index has {} mapped and {} unmapped records for reference sequence {}, but the BAM has {} and {}",
+                    metadata.mapped_record_count(),
+                    metadata.unmapped_record_count(),
+                    id,
+                    mapped,
+                    unmapped
+                ),
+            );
+        }
+    }
+
+    if let Some(n) = index.unplaced_unmapped_read_count() {
+        if n != counts.unplaced_unmapped {
+            report.add_error(
+                "index_record_count_mismatch",
+                None,
+                format!(
+                    "index has {} unplaced, unmapped records, but the BAM has {}",
+                    n, counts.unplaced_unmapped
+                ),
+            );
+        }
+    }
+}
+
+fn is_format_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+    )
+}
+
+fn error_code(e: &io::Error, code: &'static str) -> &'static str {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        "truncated"
+    } else {
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use noodles_sam::header::{self as sam_header, ReferenceSequence};
+
+    use super::*;
+    use crate::{bai::index::reference_sequence::bin::Chunk, Writer};
+
+    fn build_header(sort_order: SortOrder) -> sam::Header {
+        sam::Header::builder()
+            .set_header(
+                sam_header::header::Header::builder()
+                    .set_sort_order(sort_order)
+                    .build(),
+            )
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 13))
+            .build()
+    }
+
+    fn build_records(header: &sam::Header, keys: &[(&str, i32)]) -> io::Result<Vec<Record>> {
+        keys.iter()
+            .map(|&(name, pos)| {
+                let sam_record = sam::Record::builder()
+                    .set_read_name("r0".parse().unwrap())
+                    .set_flags(sam::record::Flags::empty())
+                    .set_reference_sequence_name(name.parse().unwrap())
+                    .set_position(sam::record::Position::try_from(pos).unwrap())
+                    .set_cigar("4M".parse().unwrap())
+                    .set_sequence("ACGT".parse().unwrap())
+                    .build();
+
+                Record::try_from_sam_record(header.reference_sequences(), &sam_record)
+            })
+            .collect()
+    }
+
+    fn write(header: &sam::Header, records: &[Record]) -> io::Result<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+
+        for record in records {
+            writer.write_record(record)?;
+        }
+
+        writer.finish()
+    }
+
+    fn codes(report: &Report) -> Vec<&'static str> {
+        report.issues().iter().map(|issue| issue.code()).collect()
+    }
+
+    #[test]
+    fn test_validate() -> io::Result<()> {
+        let header = build_header(SortOrder::Coordinate);
+        let records = build_records(&header, &[("sq0", 1), ("sq0", 5), ("sq1", 2)])?;
+        let data = write(&header, &records)?;
+
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert!(report.issues().is_empty(), "{}", report);
+        assert_eq!(report.record_count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_unsorted_records() -> io::Result<()> {
+        let keys = [("sq1", 2), ("sq0", 1)];
+
+        let header = build_header(SortOrder::Coordinate);
+        let data = write(&header, &build_records(&header, &keys)?)?;
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert_eq!(codes(&report), ["unsorted"]);
+        assert_eq!(report.issues()[0].record(), Some(1));
+
+        let header = build_header(SortOrder::Unsorted);
+        let data = write(&header, &build_records(&header, &keys)?)?;
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert!(report.is_valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_records() -> io::Result<()> {
+        let header = build_header(SortOrder::Unsorted);
+        let mut records = build_records(&header, &[("sq0", 1), ("sq0", 2)])?;
+
+        // bin = 0
+        records[0].as_mut_bytes()[10] = 0x00;
+        records[0].as_mut_bytes()[11] = 0x00;
+
+        // pos = 8 (0-based)
+        records[1].as_mut_bytes()[4] = 0x08;
+
+        let data = write(&header, &records)?;
+        let report = validate(&mut Reader::new(&data[..]))?;
+
+        assert_eq!(codes(&report), ["invalid_bin", "position_out_of_bounds"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_truncated_input() -> io::Result<()> {
+        let header = build_header(SortOrder::Unsorted);
+        let records = build_records(&header, &[("sq0", 1)])?;
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_record(&records[0])?;
+        writer.flush()?;
+        let data = writer.get_ref().clone();
+
+        let mut data: Vec<u8> = {
+            let mut reader = noodles_bgzf::Reader::new(&data[..]);
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            buf
+        };
+
+        data.truncate(data.len() - 4);
+
+        let mut writer = noodles_bgzf::Writer::new(Vec::new());
+        io::Write::write_all(&mut writer, &data)?;
+        let data = writer.finish()?;
+
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert_eq!(codes(&report), ["truncated"]);
+        assert_eq!(report.record_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_index() -> io::Result<()> {
+        let header = build_header(SortOrder::Coordinate);
+        let records = build_records(&header, &[("sq0", 1), ("sq1", 2)])?;
+        let data = write(&header, &records)?;
+
+        let chunk = Chunk::new(
+            noodles_bgzf::VirtualPosition::from(0),
+            noodles_bgzf::VirtualPosition::from(1),
+        );
+
+        let mut builder = bai::Index::builder();
+        builder.add_record(&records[0], chunk)?;
+        builder.add_record(&records[1], chunk)?;
+        let index = builder.build(2);
+
+        let report = validate_with_index(&mut Reader::new(&data[..]), &index)?;
+        assert!(report.issues().is_empty(), "{}", report);
+
+        let mut builder = bai::Index::builder();
+        builder.add_record(&records[0], chunk)?;
+        builder.add_record(&records[0], chunk)?;
+        let index = builder.build(3);
+
+        let report = validate_with_index(&mut Reader::new(&data[..]), &index)?;
+        assert_eq!(
+            codes(&report),
+            [
+                "index_reference_sequence_count_mismatch",
+                "index_record_count_mismatch"
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! BAM record framing for message streams.
+//!
+//! A BAM record frame is the same as the record in a BAM stream: the block size followed by the
+//! encoded record. Use [`noodles_core::framing`] to write and read frames, and [`fingerprint`]
+//! to check that producers and consumers agree on the reference sequence dictionary.
+
+use noodles_core::framing::Fingerprint;
+use noodles_sam::header::ReferenceSequences;
+
+/// Computes the fingerprint of a reference sequence dictionary.
+///
+/// BAM records refer to reference sequences by index, so the fingerprint includes the names and
+/// lengths of the reference sequences, in order. Other header records are not included.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_bam as bam;
+/// use noodles_sam::{self as sam, header::ReferenceSequence};
+///
+/// let header = sam::Header::builder()
+///     .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+///     .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 13))
+///     .build();
+///
+/// let fingerprint = bam::framing::fingerprint(header.reference_sequences());
+/// assert_ne!(fingerprint, bam::framing::fingerprint(&Default::default()));
+/// ```
+pub fn fingerprint(reference_sequences: &ReferenceSequences) -> Fingerprint {
+    let lens: Vec<_> = reference_sequences
+        .values()
+        .map(|reference_sequence| reference_sequence.len().to_le_bytes())
+        .collect();
+
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(2 * lens.len());
+
+    for (reference_sequence, len) in reference_sequences.values().zip(&lens) {
+        parts.push(reference_sequence.name().as_bytes());
+        parts.push(&len[..]);
+    }
+
+    Fingerprint::from_parts(&parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use noodles_core::framing;
+    use noodles_sam::{self as sam, header::ReferenceSequence};
+
+    use super::*;
+    use crate::Record;
+
+    fn build_reference_sequences(entries: &[(&str, i32)]) -> ReferenceSequences {
+        entries
+            .iter()
+            .map(|&(name, len)| (name.into(), ReferenceSequence::new(String::from(name), len)))
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let reference_sequences = build_reference_sequences(&[("sq0", 8), ("sq1", 13)]);
+        let fingerprint = fingerprint(&reference_sequences);
+
+        let header: sam::Header = "@HD\tVN:1.6\n@SQ\tSN:sq0\tLN:8\n@SQ\tSN:sq1\tLN:13\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            super::fingerprint(header.reference_sequences()),
+            fingerprint
+        );
+
+        let reordered = build_reference_sequences(&[("sq1", 13), ("sq0", 8)]);
+        assert_ne!(super::fingerprint(&reordered), fingerprint);
+
+        let resized = build_reference_sequences(&[("sq0", 8), ("sq1", 21)]);
+        assert_ne!(super::fingerprint(&resized), fingerprint);
+    }
+
+    #[test]
+    fn test_frame_round_trip() -> io::Result<()> {
+        let record = Record::default();
+
+        let mut buf = Vec::new();
+        framing::write_frame(&mut buf, &record)?;
+        assert_eq!(buf[..4], record.block_size().to_le_bytes());
+
+        let (actual, n) = framing::read_frame::<Record>(&buf)?.expect("missing frame");
+        assert_eq!(actual, record);
+        assert_eq!(n, buf.len());
+
+        Ok(())
+    }
+}
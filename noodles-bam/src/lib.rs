@@ -50,8 +50,12 @@
 //! ```
 
 pub mod bai;
+pub mod conformance;
+pub mod framing;
 pub mod reader;
 pub mod record;
+pub mod reheader;
+pub mod split;
 mod writer;
 
 pub use self::{reader::Reader, record::Record, writer::Writer};
@@ -1,22 +1,35 @@
 //! BAM reader and iterators.
 
+#[cfg(feature = "metrics")]
+mod metrics;
 mod query;
 mod records;
 mod unmapped_records;
 
 pub use self::{query::Query, records::Records, unmapped_records::UnmappedRecords};
 
+#[cfg(feature = "metrics")]
+pub use self::metrics::Metrics;
+
 use std::{
     ffi::CStr,
     io::{self, Read, Seek},
+    mem,
 };
 
 use byteorder::{LittleEndian, ReadBytesExt};
 use noodles::Region;
 use noodles_bgzf::{self as bgzf, VirtualPosition};
+use noodles_core::{
+    limits::{self, Limit, Limits},
+    progress::{self, ProgressObserver},
+};
 use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
 
-use super::{bai, Record, MAGIC_NUMBER};
+use super::{
+    bai::{self, index::reference_sequence::bin::Chunk},
+    Record, MAGIC_NUMBER,
+};
 
 /// A BAM reader.
 ///
@@ -30,6 +43,26 @@ use super::{bai, Record, MAGIC_NUMBER};
 /// The reader reads records sequentially but can use virtual positions to seek to offsets from the
 /// start of a seekable stream.
 ///
+/// Sequential reading (the header, reference sequences, and records) only requires the inner
+/// reader to implement [`Read`], so a BAM can be streamed from, e.g., stdin or a network socket.
+/// Seeking and querying require the inner reader to also implement [`Seek`].
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_bam as bam;
+///
+/// let stdin = io::stdin();
+/// let mut reader = bam::Reader::new(stdin.lock());
+/// reader.read_header()?;
+/// reader.read_reference_sequences()?;
+///
+/// for result in reader.records() {
+///     let record = result?;
+///     println!("{:?}", record);
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+///
 /// # Examples
 ///
 /// ```no_run
@@ -52,6 +85,8 @@ where
     R: Read,
 {
     inner: bgzf::Reader<R>,
+    progress: progress::Tracker,
+    limits: Limits,
 }
 
 impl<R> Reader<R>
@@ -73,9 +108,65 @@ where
     pub fn new(reader: R) -> Self {
         Self {
             inner: bgzf::Reader::new(reader),
+            progress: progress::Tracker::default(),
+            limits: Limits::default(),
         }
     }
 
+    /// Sets the limits on the header size, record size, and data fields size.
+    ///
+    /// The header size limit applies separately to the SAM header text and each reference
+    /// sequence name. Sizes are checked before buffers are allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_core::limits::Limits;
+    ///
+    /// let data = Vec::new();
+    /// let mut reader = bam::Reader::new(&data[..]);
+    /// reader.set_limits(Limits::default().set_max_record_size(1 << 20));
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Sets an observer that is notified after each record is read.
+    ///
+    /// The reported number of bytes read includes the block size of each record. The compressed
+    /// position is the position of the underlying BGZF stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_bam as bam;
+    /// use noodles_core::progress::Progress;
+    ///
+    /// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+    ///
+    /// reader.set_progress_observer(|progress: Progress| {
+    ///     if let Some(position) = progress.compressed_position() {
+    ///         eprintln!("{} bytes read", position);
+    ///     }
+    /// });
+    ///
+    /// reader.read_header()?;
+    /// reader.read_reference_sequences()?;
+    ///
+    /// for result in reader.records() {
+    ///     let _record = result?;
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_progress_observer<O>(&mut self, observer: O)
+    where
+        O: ProgressObserver + Send + 'static,
+    {
+        self.progress.set_observer(Box::new(observer));
+    }
+
     /// Reads the raw SAM header.
     ///
     /// The BAM magic number is also checked.
@@ -104,7 +195,7 @@ where
             ));
         }
 
-        read_header(&mut self.inner)
+        read_header(&mut self.inner, self.limits.max_header_size())
     }
 
     /// Reads the binary reference sequences after the SAM header.
@@ -129,11 +220,16 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn read_reference_sequences(&mut self) -> io::Result<Vec<ReferenceSequence>> {
+        let max_header_size = self.limits.max_header_size();
+
         let n_ref = self.inner.read_u32::<LittleEndian>()?;
+        let min_size = (n_ref as usize).saturating_mul(MIN_REFERENCE_SEQUENCE_SIZE);
+        limits::check(Limit::HeaderSize, max_header_size, min_size)?;
+
         let mut reference_sequences = Vec::with_capacity(n_ref as usize);
 
         for _ in 0..n_ref {
-            let reference_sequence = read_reference_sequence(&mut self.inner)?;
+            let reference_sequence = read_reference_sequence(&mut self.inner, max_header_size)?;
             reference_sequences.push(reference_sequence);
         }
 
@@ -177,12 +273,92 @@ where
             Err(e) => return Err(e),
         };
 
+        limits::check(Limit::RecordSize, self.limits.max_record_size(), block_size)?;
+
         record.resize(block_size);
-        self.inner.read_exact(record)?;
+        self.inner.read_exact(record.as_mut_bytes())?;
+        record.index()?;
+
+        limits::check(
+            Limit::AuxDataSize,
+            self.limits.max_aux_data_size(),
+            record.data_len(),
+        )?;
+
+        let len = mem::size_of::<u32>() + block_size;
+        self.progress.record_read(len, Some(self.inner.position()));
 
         Ok(block_size)
     }
 
+    /// Reads the raw frame of a single record.
+    ///
+    /// The frame is the record block size (`block_size`) followed by `block_size` bytes of the
+    /// undecoded record. It is appended to the given buffer as is, e.g., to compute a checksum,
+    /// pass records to other workers, or decode them with a custom decoder. The frame without its
+    /// first 4 bytes can be converted to a [`Record`].
+    ///
+    /// The stream is expected to be directly after the reference sequences or at the start of
+    /// another record.
+    ///
+    /// If successful, the size of the frame is returned. If the size is 0, the stream reached EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{convert::TryFrom, fs::File, io};
+    /// use noodles_bam as bam;
+    ///
+    /// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+    /// reader.read_header()?;
+    /// reader.read_reference_sequences()?;
+    ///
+    /// let mut buf = Vec::new();
+    ///
+    /// loop {
+    ///     buf.clear();
+    ///
+    ///     if reader.read_record_bytes(&mut buf)? == 0 {
+    ///         break;
+    ///     }
+    ///
+    ///     let record = bam::Record::try_from(buf[4..].to_vec())?;
+    ///     println!("{:?}", record);
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_record_bytes(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let block_size = match self.inner.read_u32::<LittleEndian>() {
+            Ok(bs) => bs,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        limits::check(
+            Limit::RecordSize,
+            self.limits.max_record_size(),
+            block_size as usize,
+        )?;
+
+        let start = buf.len();
+        let len = mem::size_of::<u32>() + block_size as usize;
+
+        buf.extend_from_slice(&block_size.to_le_bytes());
+        buf.resize(start + len, 0);
+
+        if let Err(e) = self
+            .inner
+            .read_exact(&mut buf[start + mem::size_of::<u32>()..])
+        {
+            buf.truncate(start);
+            return Err(e);
+        }
+
+        self.progress.record_read(len, Some(self.inner.position()));
+
+        Ok(len)
+    }
+
     /// Returns an iterator over records starting from the current stream position.
     ///
     /// The stream is expected to be directly after the reference sequences or at the start of
@@ -227,6 +403,27 @@ where
     pub fn virtual_position(&self) -> VirtualPosition {
         self.inner.virtual_position()
     }
+
+    /// Returns the metrics collected by this reader.
+    ///
+    /// This includes the number of records decoded and the metrics of the underlying BGZF reader.
+    /// This is only available with the `metrics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let data = Vec::new();
+    /// let reader = bam::Reader::new(&data[..]);
+    ///
+    /// let metrics = reader.metrics();
+    /// assert_eq!(metrics.records(), 0);
+    /// assert_eq!(metrics.bgzf().blocks(), 0);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Metrics {
+        Metrics::new(self.progress.records_read(), *self.inner.metrics())
+    }
 }
 
 impl<R> Reader<R>
@@ -294,30 +491,80 @@ where
         region: &Region,
     ) -> io::Result<Query<'_, R>> {
         let (i, start, end) = resolve_region(reference_sequences, region)?;
+        let chunks = query_chunks(reference_sequences, index, i, start, end)?;
+        Ok(Query::new(self, chunks, i, start, end))
+    }
 
-        let index_reference_sequence = index.reference_sequences().get(i).ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!(
-                    "could not find reference in index: {} >= {}",
-                    i,
-                    reference_sequences.len()
-                ),
-            )
-        })?;
+    /// Counts the records that intersect the given region.
+    ///
+    /// This is equivalent to counting the records returned by [`Self::query`] but only decodes
+    /// the fields needed to test for intersection, i.e., the reference sequence ID, position, and
+    /// CIGAR. Records are not cloned, and read names, sequences, quality scores, and data fields
+    /// are not parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::fs::File;
+    /// use noodles::Region;
+    /// use noodles_bam::{self as bam, bai};
+    /// use noodles_sam as sam;
+    ///
+    /// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+    /// let header: sam::Header = reader.read_header()?.parse()?;
+    ///
+    /// let reference_sequences = header.reference_sequences();
+    /// let index = bai::read("sample.bam.bai")?;
+    /// let region = Region::mapped("sq0", 17711, 28657);
+    /// let n = reader.count(&reference_sequences, &index, &region)?;
+    ///
+    /// println!("{}", n);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn count(
+        &mut self,
+        reference_sequences: &ReferenceSequences,
+        index: &bai::Index,
+        region: &Region,
+    ) -> io::Result<u64> {
+        let (i, start, end) = resolve_region(reference_sequences, region)?;
+        let chunks = query_chunks(reference_sequences, index, i, start, end)?;
 
-        let query_bins = index_reference_sequence.query(start, end);
+        let mut record = Record::default();
+        let mut n = 0;
 
-        let chunks: Vec<_> = query_bins
-            .iter()
-            .flat_map(|bin| bin.chunks())
-            .cloned()
-            .collect();
+        for chunk in chunks {
+            self.seek(chunk.start())?;
+
+            while self.virtual_position() < chunk.end() {
+                if self.read_record(&mut record)? == 0 {
+                    break;
+                }
+
+                let reference_sequence_id = match record.reference_sequence_id() {
+                    Some(id) => i32::from(id) as usize,
+                    None => continue,
+                };
+
+                if reference_sequence_id != i {
+                    continue;
+                }
+
+                let record_start = match record.position() {
+                    Some(position) => i64::from(position),
+                    None => continue,
+                };
 
-        let min_offset = index_reference_sequence.min_offset(start);
-        let merged_chunks = bai::optimize_chunks(&chunks, min_offset);
+                let record_reference_len = i64::from(record.cigar().reference_len()?);
+                let record_end = record_start + record_reference_len - 1;
 
-        Ok(Query::new(self, merged_chunks, i, start, end))
+                if query::in_interval(record_start, record_end, start, end) {
+                    n += 1;
+                }
+            }
+        }
+
+        Ok(n)
     }
 
     /// Returns an iterator of unmapped records after querying for the unmapped region.
@@ -339,14 +586,23 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn query_unmapped(&mut self, index: &bai::Index) -> io::Result<UnmappedRecords<'_, R>> {
+        // The metadata pseudo-bin end position of the last mapped reference sequence is the
+        // position directly after its last record, i.e., the start of the unplaced unmapped
+        // records. When metadata is not available, fall back to the last linear index interval.
+        let last_end_position = index
+            .reference_sequences()
+            .iter()
+            .rev()
+            .find_map(|rs| rs.metadata().map(|m| m.end_position()));
+
         let last_interval = index
             .reference_sequences()
             .iter()
             .rev()
-            .find_map(|rs| rs.intervals().last());
+            .find_map(|rs| rs.intervals().last().copied());
 
-        if let Some(interval) = last_interval {
-            self.seek(*interval)?;
+        if let Some(pos) = last_end_position.or(last_interval) {
+            self.seek(pos)?;
         } else {
             self.seek_to_first_record()?;
         }
@@ -355,6 +611,9 @@ where
     }
 }
 
+// l_name (4) + name (at least NUL) (1) + l_ref (4)
+const MIN_REFERENCE_SEQUENCE_SIZE: usize = 9;
+
 fn read_magic<R>(reader: &mut R) -> io::Result<[u8; 4]>
 where
     R: Read,
@@ -364,11 +623,12 @@ where
     Ok(magic)
 }
 
-fn read_header<R>(reader: &mut R) -> io::Result<String>
+fn read_header<R>(reader: &mut R, max_header_size: Option<usize>) -> io::Result<String>
 where
     R: Read,
 {
     let l_text = reader.read_u32::<LittleEndian>()?;
+    limits::check(Limit::HeaderSize, max_header_size, l_text as usize)?;
 
     let mut c_text = vec![0; l_text as usize];
     reader.read_exact(&mut c_text)?;
@@ -379,11 +639,15 @@ where
     })
 }
 
-fn read_reference_sequence<R>(reader: &mut R) -> io::Result<ReferenceSequence>
+fn read_reference_sequence<R>(
+    reader: &mut R,
+    max_header_size: Option<usize>,
+) -> io::Result<ReferenceSequence>
 where
     R: Read,
 {
     let l_name = reader.read_u32::<LittleEndian>()?;
+    limits::check(Limit::HeaderSize, max_header_size, l_name as usize)?;
 
     let mut c_name = vec![0; l_name as usize];
     reader.read_exact(&mut c_name)?;
@@ -408,7 +672,7 @@ fn bytes_with_nul_to_string(buf: &[u8]) -> io::Result<String> {
 fn resolve_region(
     reference_sequences: &ReferenceSequences,
     region: &Region,
-) -> io::Result<(usize, i32, i32)> {
+) -> io::Result<(usize, i64, i64)> {
     match region {
         Region::Mapped { name, start, end } => {
             let i = reference_sequences.get_index_of(name).ok_or_else(|| {
@@ -429,3 +693,276 @@ fn resolve_region(
         )),
     }
 }
+
+// Returns the optimized index chunks that may contain records that intersect the given interval
+// of the reference sequence at index `i`.
+fn query_chunks(
+    reference_sequences: &ReferenceSequences,
+    index: &bai::Index,
+    i: usize,
+    start: i64,
+    end: i64,
+) -> io::Result<Vec<Chunk>> {
+    let index_reference_sequence = index.reference_sequences().get(i).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "could not find reference in index: {} >= {}",
+                i,
+                reference_sequences.len()
+            ),
+        )
+    })?;
+
+    let (bin_start, bin_end) = region_interval_to_i32(start, end)?;
+    let query_bins = index_reference_sequence.query(bin_start, bin_end);
+
+    let chunks: Vec<_> = query_bins
+        .iter()
+        .flat_map(|bin| bin.chunks())
+        .cloned()
+        .collect();
+
+    let min_offset = index_reference_sequence.min_offset(bin_start);
+
+    Ok(bai::optimize_chunks(&chunks, min_offset))
+}
+
+// BAI bins cannot represent positions > 2^29.
+fn region_interval_to_i32(start: i64, end: i64) -> io::Result<(i32, i32)> {
+    let range = 0..=bai::MAX_POSITION;
+
+    if range.contains(&start) && range.contains(&end) {
+        Ok((start as i32, end as i32))
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("region interval is out of range for BAI: {}-{}", start, end),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, ffi::CString};
+
+    use noodles_sam as sam;
+
+    use crate::Writer;
+
+    use super::*;
+
+    // A reader that does not implement `Seek`, e.g., a pipe.
+    struct Stream<'a>(&'a [u8]);
+
+    impl<'a> Read for Stream<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_records_with_non_seekable_reader() -> io::Result<()> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .build();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_sam_record(header.reference_sequences(), &sam::Record::default())?;
+        writer.write_sam_record(header.reference_sequences(), &sam::Record::default())?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(Stream(writer.get_ref()));
+        reader.read_header()?;
+
+        let reference_sequences = reader.read_reference_sequences()?;
+        assert_eq!(reference_sequences.len(), 1);
+
+        let records: Vec<_> = reader.records().collect::<io::Result<_>>()?;
+        assert_eq!(records.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record_bytes() -> io::Result<()> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .build();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_record(&Record::default())?;
+        writer.write_record(&Record::default())?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(&writer.get_ref()[..]);
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let mut buf = Vec::new();
+        assert_eq!(reader.read_record_bytes(&mut buf)?, 38);
+        assert_eq!(reader.read_record_bytes(&mut buf)?, 38);
+        assert_eq!(reader.read_record_bytes(&mut buf)?, 0);
+
+        let record = Record::default();
+        let mut expected = 34u32.to_le_bytes().to_vec();
+        expected.extend_from_slice(&record);
+        expected.extend(expected.clone());
+
+        assert_eq!(buf, expected);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics() -> io::Result<()> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .build();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_record(&Record::default())?;
+        writer.write_record(&Record::default())?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(&writer.get_ref()[..]);
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+        reader.records().collect::<io::Result<Vec<_>>>()?;
+
+        let metrics = reader.metrics();
+        assert_eq!(metrics.records(), 2);
+        assert_eq!(metrics.bgzf().blocks(), 2); // data block + EOF block
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_interval_to_i32() {
+        assert!(matches!(region_interval_to_i32(8, 13), Ok((8, 13))));
+        assert!(matches!(
+            region_interval_to_i32(1, 536870912),
+            Ok((1, 536870912))
+        ));
+
+        assert!(matches!(
+            region_interval_to_i32(1, 536870913),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+        assert!(matches!(
+            region_interval_to_i32(4294967296, 4294967297),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+
+    #[test]
+    fn test_query_unmapped() -> Result<(), Box<dyn std::error::Error>> {
+        use bai::index::reference_sequence::bin::Chunk;
+        use sam::record::Flags;
+
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .build();
+        let reference_sequences = header.reference_sequences();
+
+        let sam_records = [
+            sam::Record::builder()
+                .set_read_name("r0".parse()?)
+                .set_flags(Flags::empty())
+                .set_reference_sequence_name("sq0".parse()?)
+                .set_position(sam::record::Position::try_from(1)?)
+                .set_cigar("4M".parse()?)
+                .build(),
+            // placed unmapped
+            sam::Record::builder()
+                .set_read_name("r1".parse()?)
+                .set_flags(Flags::UNMAPPED)
+                .set_reference_sequence_name("sq0".parse()?)
+                .set_position(sam::record::Position::try_from(1)?)
+                .build(),
+            // unplaced unmapped
+            sam::Record::builder()
+                .set_read_name("r2".parse()?)
+                .set_flags(Flags::UNMAPPED)
+                .build(),
+            sam::Record::builder()
+                .set_read_name("r3".parse()?)
+                .set_flags(Flags::UNMAPPED)
+                .build(),
+        ];
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(reference_sequences)?;
+
+        let mut index_builder = bai::Index::builder();
+
+        for sam_record in &sam_records {
+            let record = Record::try_from_sam_record(reference_sequences, sam_record)?;
+
+            let start = writer.virtual_position();
+            writer.write_record(&record)?;
+            let end = writer.virtual_position();
+
+            index_builder.add_record(&record, Chunk::new(start, end))?;
+        }
+
+        writer.try_finish()?;
+
+        let index = index_builder.build(reference_sequences.len());
+        let metadata_end_position = index.reference_sequences()[0]
+            .metadata()
+            .map(|metadata| metadata.end_position());
+
+        let mut reader = Reader::new(io::Cursor::new(writer.get_ref().clone()));
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        reader.query_unmapped(&index)?;
+        assert_eq!(Some(reader.virtual_position()), metadata_end_position);
+
+        let read_names: Vec<_> = reader
+            .query_unmapped(&index)?
+            .map(|result| result.map(|record| record.read_name().map(|name| name.to_owned())))
+            .collect::<io::Result<Result<_, _>>>()??;
+
+        assert_eq!(read_names, [CString::new("r2")?, CString::new("r3")?]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_limits() -> io::Result<()> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .build();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_sam_record(header.reference_sequences(), &sam::Record::default())?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(&writer.get_ref()[..]);
+        reader.set_limits(Limits::default().set_max_header_size(2));
+        assert!(reader.read_header().is_err());
+
+        let mut reader = Reader::new(&writer.get_ref()[..]);
+        reader.set_limits(Limits::default().set_max_record_size(8));
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let mut record = Record::default();
+        let e = reader.read_record(&mut record).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use noodles_bgzf as bgzf;
+
+/// Counters collected by a BAM reader.
+///
+/// This is created by calling [`super::Reader::metrics`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Metrics {
+    records: u64,
+    bgzf: bgzf::Metrics,
+}
+
+impl Metrics {
+    pub(crate) fn new(records: u64, bgzf: bgzf::Metrics) -> Self {
+        Self { records, bgzf }
+    }
+
+    /// Returns the number of records decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::reader::Metrics;
+    /// let metrics = Metrics::default();
+    /// assert_eq!(metrics.records(), 0);
+    /// ```
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Returns the metrics collected by the underlying BGZF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::reader::Metrics;
+    /// let metrics = Metrics::default();
+    /// assert_eq!(metrics.bgzf().blocks(), 0);
+    /// ```
+    pub fn bgzf(&self) -> &bgzf::Metrics {
+        &self.bgzf
+    }
+}
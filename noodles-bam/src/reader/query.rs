@@ -22,8 +22,8 @@ where
     reader: &'a mut Reader<R>,
     chunks: Vec<Chunk>,
     reference_sequence_id: usize,
-    start: i32,
-    end: i32,
+    start: i64,
+    end: i64,
     i: usize,
     state: State,
     record: Record,
@@ -37,8 +37,8 @@ where
         reader: &'a mut Reader<R>,
         chunks: Vec<Chunk>,
         reference_sequence_id: usize,
-        start: i32,
-        end: i32,
+        start: i64,
+        end: i64,
     ) -> Self {
         Self {
             reader,
@@ -104,10 +104,10 @@ where
                                         i32::from(reference_sequence_id) as usize;
 
                                     let record_start =
-                                        record.position().map(i32::from).expect("missing position");
+                                        record.position().map(i64::from).expect("missing position");
                                     let record_reference_len = match record.cigar().reference_len()
                                     {
-                                        Ok(len) => len as i32,
+                                        Ok(len) => i64::from(len),
                                         Err(e) => return Some(Err(e)),
                                     };
                                     let record_end = record_start + record_reference_len - 1;
@@ -137,6 +137,6 @@ where
     }
 }
 
-fn in_interval(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> bool {
+pub(super) fn in_interval(a_start: i64, a_end: i64, b_start: i64, b_end: i64) -> bool {
     a_start <= b_end && b_start <= a_end
 }
@@ -1,29 +1,39 @@
 //! BAM record and fields.
 
+mod canonical;
 pub mod cigar;
 mod convert;
 pub mod data;
 pub mod quality_scores;
 pub mod reference_sequence_id;
 pub mod sequence;
+mod tracked;
 
 pub use self::{
     cigar::Cigar, data::Data, quality_scores::QualityScores,
-    reference_sequence_id::ReferenceSequenceId, sequence::Sequence,
+    reference_sequence_id::ReferenceSequenceId, sequence::Sequence, tracked::Tracked,
 };
 
 use std::{
     convert::TryFrom,
     ffi::{self, CStr},
-    fmt, mem,
-    ops::{Deref, DerefMut},
+    fmt, io, mem,
+    ops::Deref,
 };
 
 use byteorder::{ByteOrder, LittleEndian};
+use noodles_core::{
+    checksum,
+    codec::{Decode, Encode},
+    scan,
+};
 use noodles_sam as sam;
 
 pub(crate) const UNMAPPED_POSITION: i32 = -1;
 
+// The total length of the fixed-length fields, from `ref_id` to `tlen`.
+const FIXED_FIELDS_LEN: usize = 32;
+
 /// A BAM record.
 ///
 /// A BAM record encodes the same fields as a SAM record:
@@ -44,16 +54,115 @@ pub(crate) const UNMAPPED_POSITION: i32 = -1;
 /// Additionally, it encodes the BAM index bin (`bin`).
 ///
 /// A `bam::Record` wraps a raw byte buffer, and the fields should be considered immutable.
+///
+/// The offsets of the variable-length fields (read name, CIGAR, sequence, quality scores, and
+/// data) are computed once when the record is created or read, so field accessors do not rescan
+/// the buffer.
 #[derive(Clone, Eq, PartialEq)]
-pub struct Record(Vec<u8>);
+pub struct Record {
+    buf: Vec<u8>,
+    bounds: Bounds,
+}
+
+// The end offsets of the variable-length fields. The data fields end at the end of the buffer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Bounds {
+    read_name_end: usize,
+    cigar_end: usize,
+    sequence_end: usize,
+    quality_scores_end: usize,
+}
+
+impl Bounds {
+    fn new(buf: &[u8]) -> io::Result<Self> {
+        if buf.len() < FIXED_FIELDS_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "record is too short to hold its fixed-length fields",
+            ));
+        }
+
+        let l_read_name = usize::from(buf[8]);
+        let n_cigar_op = usize::from(LittleEndian::read_u16(&buf[12..]));
+        let l_seq = usize::try_from(LittleEndian::read_u32(&buf[16..]))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let read_name_end = FIXED_FIELDS_LEN + l_read_name;
+        let cigar_end = read_name_end + mem::size_of::<u32>() * n_cigar_op;
+
+        let quality_scores_end = cigar_end
+            .checked_add(l_seq.div_ceil(2))
+            .and_then(|n| n.checked_add(l_seq))
+            .filter(|&n| n <= buf.len())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "record fields do not fit in block size",
+                )
+            })?;
+
+        let sequence_end = quality_scores_end - l_seq;
+
+        Ok(Self {
+            read_name_end,
+            cigar_end,
+            sequence_end,
+            quality_scores_end,
+        })
+    }
+}
 
 impl Record {
     pub(crate) fn resize(&mut self, new_len: usize) {
-        self.0.resize(new_len, Default::default());
+        self.buf.resize(new_len, Default::default());
+    }
+
+    // Returns the raw record buffer for writing. If the lengths of the variable-length fields
+    // change, the field offsets must be recomputed using `index`.
+    pub(crate) fn as_mut_bytes(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    // Recomputes the field offsets after the buffer is overwritten.
+    pub(crate) fn index(&mut self) -> io::Result<()> {
+        self.bounds = Bounds::new(&self.buf)?;
+        Ok(())
+    }
+
+    pub(crate) fn quality_scores_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[self.bounds.sequence_end..self.bounds.quality_scores_end]
+    }
+
+    /// Returns the size of the encoded record in bytes.
+    ///
+    /// This is the record block size (`block_size`), which does not include the size of the
+    /// block size field itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let record = bam::Record::default();
+    /// assert_eq!(record.block_size(), 34);
+    /// ```
+    pub fn block_size(&self) -> u32 {
+        self.buf.len() as u32
     }
 
-    pub(crate) fn block_size(&self) -> u32 {
-        self.0.len() as u32
+    /// Returns the approximate number of bytes of memory used by the record.
+    ///
+    /// This includes the capacity of the record buffer, which may be larger than the block size
+    /// when a record is reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let record = bam::Record::default();
+    /// assert!(record.size() >= record.block_size() as usize);
+    /// ```
+    pub fn size(&self) -> usize {
+        mem::size_of::<Self>() + self.buf.capacity()
     }
 
     /// Returns the reference sequence ID of this record.
@@ -69,7 +178,7 @@ impl Record {
     /// assert!(record.reference_sequence_id().is_none());
     /// ```
     pub fn reference_sequence_id(&self) -> Option<ReferenceSequenceId> {
-        let id = LittleEndian::read_i32(&self.0);
+        let id = LittleEndian::read_i32(&self.buf);
 
         if id == reference_sequence_id::UNMAPPED {
             None
@@ -91,7 +200,7 @@ impl Record {
     /// ```
     pub fn position(&self) -> Option<sam::record::Position> {
         let offset = 4;
-        let pos = LittleEndian::read_i32(&self.0[offset..]);
+        let pos = LittleEndian::read_i32(&self.buf[offset..]);
 
         if pos == UNMAPPED_POSITION {
             None
@@ -102,7 +211,7 @@ impl Record {
 
     fn l_read_name(&self) -> u8 {
         let offset = 8;
-        self.0[offset]
+        self.buf[offset]
     }
 
     /// Returns the mapping quality of this record.
@@ -117,7 +226,7 @@ impl Record {
     /// ```
     pub fn mapping_quality(&self) -> sam::record::MappingQuality {
         let offset = 9;
-        sam::record::MappingQuality::from(self.0[offset])
+        sam::record::MappingQuality::from(self.buf[offset])
     }
 
     /// Returns the index bin that includes this record.
@@ -131,12 +240,12 @@ impl Record {
     /// ```
     pub fn bin(&self) -> u16 {
         let offset = 10;
-        LittleEndian::read_u16(&self.0[offset..])
+        LittleEndian::read_u16(&self.buf[offset..])
     }
 
     fn n_cigar_op(&self) -> u16 {
         let offset = 12;
-        LittleEndian::read_u16(&self.0[offset..])
+        LittleEndian::read_u16(&self.buf[offset..])
     }
 
     /// Returns the SAM flags of this record.
@@ -151,13 +260,13 @@ impl Record {
     /// ```
     pub fn flags(&self) -> sam::record::Flags {
         let offset = 14;
-        let value = LittleEndian::read_u16(&self.0[offset..]);
+        let value = LittleEndian::read_u16(&self.buf[offset..]);
         sam::record::Flags::from(value)
     }
 
     fn l_seq(&self) -> u32 {
         let offset = 16;
-        LittleEndian::read_u32(&self.0[offset..])
+        LittleEndian::read_u32(&self.buf[offset..])
     }
 
     /// Returns the reference sequence ID of the mate of this record.
@@ -174,7 +283,7 @@ impl Record {
     /// ```
     pub fn mate_reference_sequence_id(&self) -> Option<ReferenceSequenceId> {
         let offset = 20;
-        let id = LittleEndian::read_i32(&self.0[offset..]);
+        let id = LittleEndian::read_i32(&self.buf[offset..]);
 
         if id == reference_sequence_id::UNMAPPED {
             None
@@ -196,7 +305,7 @@ impl Record {
     /// ```
     pub fn mate_position(&self) -> Option<sam::record::Position> {
         let offset = 24;
-        let pos = LittleEndian::read_i32(&self.0[offset..]);
+        let pos = LittleEndian::read_i32(&self.buf[offset..]);
 
         if pos == UNMAPPED_POSITION {
             None
@@ -216,7 +325,7 @@ impl Record {
     /// ```
     pub fn template_length(&self) -> i32 {
         let offset = 28;
-        LittleEndian::read_i32(&self.0[offset..])
+        LittleEndian::read_i32(&self.buf[offset..])
     }
 
     /// Returns the read name of this record.
@@ -233,14 +342,15 @@ impl Record {
     /// # Ok::<(), ffi::FromBytesWithNulError>(())
     /// ```
     pub fn read_name(&self) -> Result<&CStr, ffi::FromBytesWithNulError> {
-        let offset = 32;
-        let len = self.l_read_name() as usize;
-        let data = &self.0[offset..offset + len];
+        let data = &self.buf[FIXED_FIELDS_LEN..self.bounds.read_name_end];
         CStr::from_bytes_with_nul(data)
     }
 
     /// Returns the CIGAR operations that describe how the read was mapped.
     ///
+    /// If the CIGAR field is the long CIGAR placeholder (`<l_seq>S<reference_len>N`) and the data
+    /// has a `CG` field, the real CIGAR is read from the `CG` field.
+    ///
     /// # Examples
     ///
     /// ```
@@ -249,12 +359,27 @@ impl Record {
     /// assert!(record.cigar().is_empty());
     /// ```
     pub fn cigar(&self) -> Cigar<'_> {
-        let offset = 32 + (self.l_read_name() as usize);
-        let len = mem::size_of::<u32>() * (self.n_cigar_op() as usize);
-        let bytes = &self.0[offset..offset + len];
+        let bytes = self
+            .long_cigar()
+            .unwrap_or(&self.buf[self.bounds.read_name_end..self.bounds.cigar_end]);
+
         Cigar::new(bytes)
     }
 
+    // § 4.2.2 N_CIGAR_OP field (2020-07-19)
+    //
+    // A CIGAR with more than 65535 operations is stored in the `CG` data field (`CG:B,I`), and
+    // the CIGAR field is set to the placeholder `<l_seq>S<reference_len>N`.
+    pub(crate) fn long_cigar(&self) -> Option<&[u8]> {
+        let bytes = &self.buf[self.bounds.read_name_end..self.bounds.cigar_end];
+
+        if is_long_cigar_placeholder(bytes, self.l_seq()) {
+            find_long_cigar_field(&self.buf[self.bounds.quality_scores_end..])
+        } else {
+            None
+        }
+    }
+
     /// Returns the bases in the sequence of this record.
     ///
     /// # Examples
@@ -265,12 +390,7 @@ impl Record {
     /// assert!(record.sequence().is_empty());
     /// ```
     pub fn sequence(&self) -> Sequence<'_> {
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize);
-        let len = ((self.l_seq() + 1) / 2) as usize;
-
-        let bytes = &self.0[offset..offset + len];
+        let bytes = &self.buf[self.bounds.cigar_end..self.bounds.sequence_end];
         let base_count = self.l_seq() as usize;
         Sequence::new(bytes, base_count)
     }
@@ -285,15 +405,7 @@ impl Record {
     /// assert!(record.quality_scores().is_empty());
     /// ```
     pub fn quality_scores(&self) -> QualityScores<'_> {
-        let l_seq = self.l_seq();
-
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize)
-            + ((self.l_seq() + 1) / 2) as usize;
-        let len = l_seq as usize;
-
-        let bytes = &self.0[offset..offset + len];
+        let bytes = &self.buf[self.bounds.sequence_end..self.bounds.quality_scores_end];
         QualityScores::new(bytes)
     }
 
@@ -307,23 +419,77 @@ impl Record {
     /// assert!(record.data().is_empty());
     /// ```
     pub fn data(&self) -> Data<'_> {
-        let l_seq = self.l_seq();
+        let bytes = &self.buf[self.bounds.quality_scores_end..];
+        Data::new(bytes)
+    }
 
-        let offset = 32
-            + (self.l_read_name() as usize)
-            + mem::size_of::<u32>() * (self.n_cigar_op() as usize)
-            + ((self.l_seq() + 1) / 2) as usize
-            + l_seq as usize;
-        let len = self.block_size() as usize;
+    /// Returns the record encoded in a canonical form.
+    ///
+    /// The canonical form is the raw record (without the block size) with its data fields sorted
+    /// by tag, integers stored as their smallest type, preferring unsigned types for nonnegative
+    /// integers, and integer arrays stored as the smallest subtype that holds all their values.
+    ///
+    /// Two records with the same canonical form are semantically equal. See
+    /// [`Self::normalized_eq`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    /// let record = bam::Record::default();
+    /// assert_eq!(record.to_canonical_bytes()?, &record[..]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn to_canonical_bytes(&self) -> io::Result<Vec<u8>> {
+        canonical::encode(self)
+    }
 
-        let bytes = &self.0[offset..len];
-        Data::new(bytes)
+    /// Returns whether two records are semantically equal.
+    ///
+    /// Unlike `==`, this ignores the order of data fields and differences in the widths of
+    /// integer types. This is useful to compare records written by different tools, e.g., when
+    /// testing output against samtools.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{convert::TryFrom, io};
+    /// use noodles_bam as bam;
+    ///
+    /// let data = [
+    ///     0x4e, 0x48, 0x43, 0x01, // NH:C:1
+    ///     0x4e, 0x4d, 0x69, 0x00, 0x00, 0x00, 0x00, // NM:i:0
+    /// ];
+    /// let mut buf = bam::Record::default().to_vec();
+    /// buf.extend(&data);
+    /// let a = bam::Record::try_from(buf)?;
+    ///
+    /// let data = [
+    ///     0x4e, 0x4d, 0x43, 0x00, // NM:C:0
+    ///     0x4e, 0x48, 0x73, 0x01, 0x00, // NH:s:1
+    /// ];
+    /// let mut buf = bam::Record::default().to_vec();
+    /// buf.extend(&data);
+    /// let b = bam::Record::try_from(buf)?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.normalized_eq(&b)?);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn normalized_eq(&self, other: &Self) -> io::Result<bool> {
+        Ok(self.to_canonical_bytes()? == other.to_canonical_bytes()?)
+    }
+
+    // Returns the length of the data fields.
+    pub(crate) fn data_len(&self) -> usize {
+        self.buf.len() - self.bounds.quality_scores_end
     }
 }
 
 impl Default for Record {
     fn default() -> Self {
-        Self::from(vec![
+        let buf = vec![
             0xff, 0xff, 0xff, 0xff, // ref_id = -1
             0xff, 0xff, 0xff, 0xff, // pos = -1
             0x02, // l_read_name = 2
@@ -336,7 +502,16 @@ impl Default for Record {
             0xff, 0xff, 0xff, 0xff, // next_pos = -1
             0x00, 0x00, 0x00, 0x00, // tlen = 0
             0x2a, 0x00, // read_name = "*\x00"
-        ])
+        ];
+
+        let bounds = Bounds {
+            read_name_end: 34,
+            cigar_end: 34,
+            sequence_end: 34,
+            quality_scores_end: 34,
+        };
+
+        Self { buf, bounds }
     }
 }
 
@@ -344,13 +519,7 @@ impl Deref for Record {
     type Target = [u8];
 
     fn deref(&self) -> &[u8] {
-        &self.0
-    }
-}
-
-impl DerefMut for Record {
-    fn deref_mut(&mut self) -> &mut [u8] {
-        &mut self.0
+        &self.buf
     }
 }
 
@@ -377,9 +546,130 @@ impl fmt::Debug for Record {
     }
 }
 
-impl From<Vec<u8>> for Record {
-    fn from(bytes: Vec<u8>) -> Self {
-        Self(bytes)
+fn is_long_cigar_placeholder(bytes: &[u8], read_len: u32) -> bool {
+    const SOFT_CLIP: u32 = 4;
+    const SKIP: u32 = 3;
+
+    match bytes.len() {
+        8 => {
+            let s = LittleEndian::read_u32(&bytes[..4]);
+            let n = LittleEndian::read_u32(&bytes[4..]);
+            s == (read_len << 4 | SOFT_CLIP) && n & 0x0f == SKIP
+        }
+        _ => false,
+    }
+}
+
+// Returns the raw values of the `CG:B,I` data field, if present.
+fn find_long_cigar_field(mut data: &[u8]) -> Option<&[u8]> {
+    fn value_size(ty: u8) -> Option<usize> {
+        match ty {
+            b'A' | b'c' | b'C' => Some(1),
+            b's' | b'S' => Some(2),
+            b'i' | b'I' | b'f' => Some(4),
+            _ => None,
+        }
+    }
+
+    while let Some(header) = data.get(..3) {
+        let (tag, ty) = (&header[..2], header[2]);
+        data = &data[3..];
+
+        let len = match ty {
+            b'Z' | b'H' => data.iter().position(|&b| b == 0)? + 1,
+            b'B' => {
+                let subtype = *data.first()?;
+                let n = data.get(1..5).map(LittleEndian::read_u32)? as usize;
+                let values_len = n.checked_mul(value_size(subtype)?)?;
+                let values = data.get(5..5 + values_len)?;
+
+                if tag == b"CG" && subtype == b'I' {
+                    return Some(values);
+                }
+
+                5 + values_len
+            }
+            _ => value_size(ty)?,
+        };
+
+        data = data.get(len..)?;
+    }
+
+    None
+}
+
+impl sam::AlignmentRecord for Record {
+    fn alignment_start(&self) -> Option<sam::record::Position> {
+        self.position()
+    }
+
+    fn alignment_span(&self) -> io::Result<u32> {
+        self.cigar().reference_len()
+    }
+}
+
+impl scan::Locus for Record {
+    fn interval(&self) -> io::Result<Option<(i64, i64)>> {
+        match self.position() {
+            Some(position) => {
+                let start = i64::from(position);
+                let end = start + i64::from(self.cigar().reference_len()?) - 1;
+                Ok(Some((start, end)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl checksum::Record for Record {
+    fn checksum_fields(&self) -> io::Result<checksum::Fields<'_>> {
+        let read_name = self
+            .read_name()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let sequence: Vec<_> = self
+            .sequence()
+            .bases()
+            .map(|base| char::from(base) as u8)
+            .collect();
+
+        let quality_scores = &self.buf[self.bounds.sequence_end..self.bounds.quality_scores_end];
+
+        // Missing quality scores are filled with 0xff.
+        let quality_scores = if quality_scores.iter().all(|&score| score == 0xff) {
+            &[]
+        } else {
+            quality_scores
+        };
+
+        Ok(checksum::Fields::new(
+            read_name.to_bytes().into(),
+            u16::from(self.flags()),
+            sequence.into(),
+            quality_scores.into(),
+        ))
+    }
+}
+
+impl Encode for Record {
+    fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+        dst.extend_from_slice(&self.buf);
+        Ok(())
+    }
+}
+
+impl Decode for Record {
+    fn decode(src: &[u8]) -> io::Result<Self> {
+        Self::try_from(src.to_vec())
+    }
+}
+
+impl TryFrom<Vec<u8>> for Record {
+    type Error = io::Error;
+
+    fn try_from(buf: Vec<u8>) -> Result<Self, Self::Error> {
+        let bounds = Bounds::new(&buf)?;
+        Ok(Self { buf, bounds })
     }
 }
 
@@ -441,8 +731,8 @@ mod tests {
 
         writer
             .into_inner()
-            .map(Record::from)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+            .and_then(Record::try_from)
     }
 
     #[test]
@@ -452,6 +742,13 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_size() -> io::Result<()> {
+        let record = build_record()?;
+        assert!(record.size() >= mem::size_of::<Record>() + 64);
+        Ok(())
+    }
+
     #[test]
     fn test_reference_sequence_id() -> io::Result<()> {
         let record = build_record()?;
@@ -462,7 +759,18 @@ mod tests {
     #[test]
     fn test_position() -> io::Result<()> {
         let record = build_record()?;
-        assert_eq!(record.position().map(i32::from), Some(61062));
+        assert_eq!(record.position().map(i64::from), Some(61062));
+        Ok(())
+    }
+
+    #[test]
+    fn test_alignment_end() -> io::Result<()> {
+        use sam::AlignmentRecord;
+
+        let record = build_record()?;
+        assert_eq!(record.alignment_span()?, 4);
+        assert_eq!(record.alignment_end()?.map(i64::from), Some(61065));
+
         Ok(())
     }
 
@@ -522,7 +830,7 @@ mod tests {
     #[test]
     fn test_mate_position() -> io::Result<()> {
         let record = build_record()?;
-        assert_eq!(record.mate_position().map(i32::from), Some(61153));
+        assert_eq!(record.mate_position().map(i64::from), Some(61153));
         Ok(())
     }
 
@@ -573,4 +881,73 @@ mod tests {
         assert_eq!(*record.data(), expected);
         Ok(())
     }
+
+    #[test]
+    fn test_checksum_fields() -> Result<(), Box<dyn std::error::Error>> {
+        use noodles_core::checksum::Checksum;
+
+        let reference_sequences = sam::header::ReferenceSequences::default();
+
+        for raw_record in &[
+            "r0\t4\t*\t0\t255\t*\t*\t0\t0\tACgT\tNDLS",
+            "r1\t4\t*\t0\t255\t*\t*\t0\t0\tACGT\t*",
+            "*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*",
+        ] {
+            let sam_record: sam::Record = raw_record.parse()?;
+            let record = Record::try_from_sam_record(&reference_sequences, &sam_record)?;
+
+            let mut expected = Checksum::default();
+            expected.add(&sam_record)?;
+
+            let mut actual = Checksum::default();
+            actual.add(&record)?;
+
+            assert_eq!(actual, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_and_decode() -> io::Result<()> {
+        let record = build_record()?;
+
+        let mut buf = Vec::new();
+        record.encode(&mut buf)?;
+        assert_eq!(buf.len(), 64);
+        assert_eq!(Record::decode(&buf)?, record);
+
+        assert_eq!(
+            Record::decode(&buf[..31]).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        assert_eq!(
+            Record::decode(&buf[..40]).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_vec_u8() -> io::Result<()> {
+        let buf = Record::default().to_vec();
+        assert_eq!(Record::try_from(buf.clone())?, Record::default());
+
+        assert_eq!(
+            Record::try_from(buf[..31].to_vec()).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        // l_seq = 4294967295
+        let mut buf = buf;
+        buf[16..20].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(
+            Record::try_from(buf).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        Ok(())
+    }
 }
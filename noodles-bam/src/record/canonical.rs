@@ -0,0 +1,220 @@
+use std::{convert::TryFrom, ffi::CString, io};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use super::{
+    data::{field::Value, Field},
+    Record,
+};
+
+// Data fields are sorted by tag, integers use their canonical type (see `Value::try_from`), and
+// integer arrays use the smallest subtype that can represent all of their values.
+pub(super) fn encode(record: &Record) -> io::Result<Vec<u8>> {
+    let mut fields = record
+        .data()
+        .fields()
+        .map(|result| result.map(canonicalize_field))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    fields.sort_by(|a, b| a.tag().as_ref().cmp(b.tag().as_ref()));
+
+    let data_start = record.len() - record.data().len();
+    let mut buf = record[..data_start].to_vec();
+
+    for field in &fields {
+        write_field(&mut buf, field)?;
+    }
+
+    Ok(buf)
+}
+
+fn canonicalize_field(field: Field) -> Field {
+    let value = match field.value() {
+        Value::Int8(n) => int_value(i64::from(*n)),
+        Value::UInt8(n) => int_value(i64::from(*n)),
+        Value::Int16(n) => int_value(i64::from(*n)),
+        Value::UInt16(n) => int_value(i64::from(*n)),
+        Value::Int32(n) => int_value(i64::from(*n)),
+        Value::UInt32(n) => int_value(i64::from(*n)),
+        Value::Int8Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        Value::UInt8Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        Value::Int16Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        Value::UInt16Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        Value::Int32Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        Value::UInt32Array(values) => int_array_value(values.iter().map(|&n| i64::from(n))),
+        value => value.clone(),
+    };
+
+    Field::new(field.tag().clone(), value)
+}
+
+fn int_value(n: i64) -> Value {
+    // BAM integers are always in the range of a canonical integer.
+    Value::try_from(n).expect("invalid BAM integer")
+}
+
+fn int_array_value<I>(values: I) -> Value
+where
+    I: Iterator<Item = i64> + Clone,
+{
+    let min = values.clone().min().unwrap_or_default();
+    let max = values.clone().max().unwrap_or_default();
+
+    // BAM integers are always in the range of a 32-bit integer or 32-bit unsigned integer.
+    if min >= 0 {
+        if u8::try_from(max).is_ok() {
+            Value::UInt8Array(values.map(|n| n as u8).collect())
+        } else if u16::try_from(max).is_ok() {
+            Value::UInt16Array(values.map(|n| n as u16).collect())
+        } else {
+            Value::UInt32Array(values.map(|n| n as u32).collect())
+        }
+    } else if i8::try_from(min).is_ok() && i8::try_from(max).is_ok() {
+        Value::Int8Array(values.map(|n| n as i8).collect())
+    } else if i16::try_from(min).is_ok() && i16::try_from(max).is_ok() {
+        Value::Int16Array(values.map(|n| n as i16).collect())
+    } else {
+        Value::Int32Array(values.map(|n| n as i32).collect())
+    }
+}
+
+fn write_field(buf: &mut Vec<u8>, field: &Field) -> io::Result<()> {
+    let value = field.value();
+
+    buf.extend(field.tag().as_ref().as_bytes());
+    buf.push(char::from(value.ty()) as u8);
+
+    if let Some(subtype) = value.subtype() {
+        buf.push(char::from(subtype) as u8);
+    }
+
+    match value {
+        Value::Char(c) => buf.push(*c as u8),
+        Value::Int8(n) => buf.write_i8(*n)?,
+        Value::UInt8(n) => buf.push(*n),
+        Value::Int16(n) => buf.write_i16::<LittleEndian>(*n)?,
+        Value::UInt16(n) => buf.write_u16::<LittleEndian>(*n)?,
+        Value::Int32(n) => buf.write_i32::<LittleEndian>(*n)?,
+        Value::UInt32(n) => buf.write_u32::<LittleEndian>(*n)?,
+        Value::Float(n) => buf.write_f32::<LittleEndian>(*n)?,
+        Value::String(s) | Value::Hex(s) => {
+            let c_str = CString::new(s.as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            buf.extend(c_str.as_bytes_with_nul());
+        }
+        Value::Int8Array(values) => {
+            write_array_len(buf, values.len())?;
+            values.iter().try_for_each(|&n| buf.write_i8(n))?;
+        }
+        Value::UInt8Array(values) => {
+            write_array_len(buf, values.len())?;
+            buf.extend(values);
+        }
+        Value::Int16Array(values) => {
+            write_array_len(buf, values.len())?;
+            values
+                .iter()
+                .try_for_each(|&n| buf.write_i16::<LittleEndian>(n))?;
+        }
+        Value::UInt16Array(values) => {
+            write_array_len(buf, values.len())?;
+            values
+                .iter()
+                .try_for_each(|&n| buf.write_u16::<LittleEndian>(n))?;
+        }
+        Value::Int32Array(values) => {
+            write_array_len(buf, values.len())?;
+            values
+                .iter()
+                .try_for_each(|&n| buf.write_i32::<LittleEndian>(n))?;
+        }
+        Value::UInt32Array(values) => {
+            write_array_len(buf, values.len())?;
+            values
+                .iter()
+                .try_for_each(|&n| buf.write_u32::<LittleEndian>(n))?;
+        }
+        Value::FloatArray(values) => {
+            write_array_len(buf, values.len())?;
+            values
+                .iter()
+                .try_for_each(|&n| buf.write_f32::<LittleEndian>(n))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_array_len(buf: &mut Vec<u8>, len: usize) -> io::Result<()> {
+    let n = u32::try_from(len).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    buf.write_u32::<LittleEndian>(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_int_array_value() {
+        assert_eq!(
+            int_array_value(Vec::new().into_iter()),
+            Value::UInt8Array(Vec::new())
+        );
+        assert_eq!(
+            int_array_value(vec![0, 255].into_iter()),
+            Value::UInt8Array(vec![0, 255])
+        );
+        assert_eq!(
+            int_array_value(vec![0, 256].into_iter()),
+            Value::UInt16Array(vec![0, 256])
+        );
+        assert_eq!(
+            int_array_value(vec![0, 65536].into_iter()),
+            Value::UInt32Array(vec![0, 65536])
+        );
+        assert_eq!(
+            int_array_value(vec![-1, 127].into_iter()),
+            Value::Int8Array(vec![-1, 127])
+        );
+        assert_eq!(
+            int_array_value(vec![-1, 128].into_iter()),
+            Value::Int16Array(vec![-1, 128])
+        );
+        assert_eq!(
+            int_array_value(vec![-32769, 0].into_iter()),
+            Value::Int32Array(vec![-32769, 0])
+        );
+    }
+
+    fn build_record(data: &[u8]) -> Record {
+        let mut buf = Record::default().to_vec();
+        buf.extend(data);
+        Record::try_from(buf).unwrap()
+    }
+
+    #[test]
+    fn test_encode() -> io::Result<()> {
+        let a = build_record(&[
+            0x5a, 0x42, 0x42, 0x69, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00,
+            0x00, 0x00, // ZB:B:i,1,2
+            0x5a, 0x41, 0x5a, 0x6e, 0x64, 0x6c, 0x73, 0x00, // ZA:Z:ndls
+        ]);
+
+        let b = build_record(&[
+            0x5a, 0x41, 0x5a, 0x6e, 0x64, 0x6c, 0x73, 0x00, // ZA:Z:ndls
+            0x5a, 0x42, 0x42, 0x43, 0x02, 0x00, 0x00, 0x00, 0x01, 0x02, // ZB:B:C,1,2
+        ]);
+
+        assert_eq!(encode(&a)?, encode(&b)?);
+        assert_eq!(encode(&b)?, &b[..]);
+
+        let c = build_record(&[
+            0x5a, 0x41, 0x5a, 0x6e, 0x64, 0x6c, 0x73, 0x00, // ZA:Z:ndls
+            0x5a, 0x42, 0x42, 0x43, 0x02, 0x00, 0x00, 0x00, 0x01, 0x03, // ZB:B:C,1,3
+        ]);
+
+        assert_ne!(encode(&a)?, encode(&c)?);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use std::{convert::TryInto, io, mem};
+use std::{
+    convert::{TryFrom, TryInto},
+    io, mem,
+};
 
 use noodles_sam as sam;
 
@@ -31,7 +34,7 @@ impl Record {
         writer::record::write_sam_record(&mut buf, reference_sequences, record)?;
         // Remove the prepending block size.
         let start = mem::size_of::<u32>();
-        Ok(Self::from(buf[start..].to_vec()))
+        Self::try_from(buf[start..].to_vec())
     }
 
     /// Converts this record to a SAM record.
@@ -99,9 +102,21 @@ impl Record {
             builder = builder.set_position(position);
         }
 
+        let cigar: sam::record::Cigar = self.cigar().try_into()?;
+
+        let mut data: sam::record::Data = self
+            .data()
+            .try_into()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // The real CIGAR of a long CIGAR is read from the `CG` data field, which is then dropped.
+        if self.long_cigar().is_some() {
+            data = writer::record::remove_long_cigar_field(&data);
+        }
+
         builder = builder
             .set_mapping_quality(self.mapping_quality())
-            .set_cigar(self.cigar().try_into()?);
+            .set_cigar(cigar);
 
         if let Some(mate_reference_sequence_name) =
             get_reference_sequence_name(reference_sequences, self.mate_reference_sequence_id())?
@@ -128,11 +143,6 @@ impl Record {
             builder = builder.set_quality_scores(quality_scores);
         }
 
-        let data = self
-            .data()
-            .try_into()
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
         builder = builder.set_data(data);
 
         Ok(builder.build())
@@ -231,8 +241,8 @@ mod tests {
 
         writer
             .into_inner()
-            .map(Record::from)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+            .and_then(Record::try_from)
     }
 
     #[test]
@@ -271,4 +281,94 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_from_sam_record_with_long_cigar() -> Result<(), Box<dyn std::error::Error>> {
+        use sam::record::cigar::{op::Kind, Op};
+
+        let ops: Vec<_> = (0..65536)
+            .map(|i| {
+                let kind = if i % 2 == 0 {
+                    Kind::Match
+                } else {
+                    Kind::Deletion
+                };
+                Op::new(kind, 1)
+            })
+            .collect();
+        let cigar = sam::record::Cigar::from(ops);
+
+        let sam_record = sam::Record::builder()
+            .set_cigar(cigar)
+            .set_sequence("A".repeat(32768).parse()?)
+            .set_quality_scores("N".repeat(32768).parse()?)
+            .build();
+
+        let reference_sequences = build_reference_sequences();
+        let bam_record = Record::try_from_sam_record(&reference_sequences, &sam_record)?;
+
+        assert_eq!(bam_record.n_cigar_op(), 2);
+        assert_eq!(bam_record.cigar().ops().count(), 65536);
+        assert_eq!(sam::AlignmentRecord::alignment_span(&bam_record)?, 65536);
+        assert_eq!(bam_record.data().fields().count(), 1);
+
+        let actual = bam_record.try_into_sam_record(&reference_sequences)?;
+        assert_eq!(actual, sam_record);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_sam_record_with_long_cigar_and_cigar_data_field(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use sam::record::{
+            cigar::{op::Kind, Op},
+            data::{
+                field::{Tag, Value},
+                Field,
+            },
+            Data,
+        };
+
+        let ops: Vec<_> = (0..65536)
+            .map(|i| {
+                let kind = if i % 2 == 0 {
+                    Kind::Match
+                } else {
+                    Kind::Deletion
+                };
+                Op::new(kind, 1)
+            })
+            .collect();
+        let cigar = sam::record::Cigar::from(ops);
+
+        let sam_record = sam::Record::builder()
+            .set_cigar(cigar.clone())
+            .set_sequence("A".repeat(32768).parse()?)
+            .set_quality_scores("N".repeat(32768).parse()?)
+            .set_data(Data::from(vec![Field::new(
+                Tag::Cigar,
+                Value::UInt32Array(vec![0x84]),
+            )]))
+            .build();
+
+        let reference_sequences = build_reference_sequences();
+        let bam_record = Record::try_from_sam_record(&reference_sequences, &sam_record)?;
+
+        let fields: Vec<_> = bam_record.data().fields().collect::<io::Result<_>>()?;
+        assert_eq!(fields.len(), 1);
+        assert_eq!(
+            fields[0]
+                .value()
+                .as_uint32_array()
+                .map(|values| values.len()),
+            Some(65536)
+        );
+
+        let actual = bam_record.try_into_sam_record(&reference_sequences)?;
+        assert_eq!(actual.cigar(), &cigar);
+        assert!(actual.data().is_empty());
+
+        Ok(())
+    }
 }
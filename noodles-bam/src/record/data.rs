@@ -87,8 +87,6 @@ impl<'a> fmt::Debug for Data<'a> {
 pub enum TryFromDataError {
     /// A field is invalid.
     InvalidField,
-    /// A BAM u32 value is out of range for a SAM i32 value.
-    OutOfRange(u32),
 }
 
 impl error::Error for TryFromDataError {}
@@ -97,9 +95,6 @@ impl fmt::Display for TryFromDataError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidField => write!(f, "invalid field"),
-            Self::OutOfRange(value) => {
-                write!(f, "value is out of range: {}", value)
-            }
         }
     }
 }
@@ -108,36 +103,12 @@ impl<'a> TryFrom<Data<'a>> for sam::record::Data {
     type Error = TryFromDataError;
 
     fn try_from(data: Data<'_>) -> Result<Self, Self::Error> {
-        use field::Value as BamValue;
-        use sam::record::data::field::Value as SamValue;
-
         let mut sam_fields = Vec::new();
 
         for result in data.fields() {
             let field = result.map_err(|_| TryFromDataError::InvalidField)?;
             let tag = field.tag();
-
-            let value = match field.value() {
-                BamValue::Char(c) => SamValue::Char(*c),
-                BamValue::Int8(n) => SamValue::Int32(i32::from(*n)),
-                BamValue::UInt8(n) => SamValue::Int32(i32::from(*n)),
-                BamValue::Int16(n) => SamValue::Int32(i32::from(*n)),
-                BamValue::UInt16(n) => SamValue::Int32(i32::from(*n)),
-                BamValue::Int32(n) => SamValue::Int32(*n),
-                BamValue::UInt32(n) => i32::try_from(*n)
-                    .map(SamValue::Int32)
-                    .map_err(|_| TryFromDataError::OutOfRange(*n))?,
-                BamValue::Float(n) => SamValue::Float(*n),
-                BamValue::String(s) => SamValue::String(s.clone()),
-                BamValue::Hex(s) => SamValue::Hex(s.clone()),
-                BamValue::Int8Array(values) => SamValue::Int8Array(values.clone()),
-                BamValue::UInt8Array(values) => SamValue::UInt8Array(values.clone()),
-                BamValue::Int16Array(values) => SamValue::Int16Array(values.clone()),
-                BamValue::UInt16Array(values) => SamValue::UInt16Array(values.clone()),
-                BamValue::Int32Array(values) => SamValue::Int32Array(values.clone()),
-                BamValue::UInt32Array(values) => SamValue::UInt32Array(values.clone()),
-                BamValue::FloatArray(values) => SamValue::FloatArray(values.clone()),
-            };
+            let value = sam::record::data::field::Value::from(field.value().clone());
 
             let sam_field = sam::record::data::Field::new(tag.clone(), value);
             sam_fields.push(sam_field);
@@ -176,15 +147,27 @@ mod tests {
     }
 
     #[test]
-    fn test_try_from_data_for_sam_record_data_with_out_of_range_u32_value() {
+    fn test_try_from_data_for_sam_record_data_with_large_u32_value() -> Result<(), TryFromDataError>
+    {
+        use sam::record::data::{
+            field::{Tag, Value},
+            Field,
+        };
+
         let raw_data = [
             0x5a, 0x4e, 0x49, 0xff, 0xff, 0xff, 0xff, // ZN:I:4294967295
         ];
         let data = Data::new(&raw_data);
 
-        assert_eq!(
-            sam::record::Data::try_from(data),
-            Err(TryFromDataError::OutOfRange(u32::MAX))
-        );
+        let actual = sam::record::Data::try_from(data)?;
+        let expected = sam::record::Data::from(vec![Field::new(
+            Tag::Other(String::from("ZN")),
+            Value::UInt32(u32::MAX),
+        )]);
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual.to_string(), "ZN:i:4294967295");
+
+        Ok(())
     }
 }
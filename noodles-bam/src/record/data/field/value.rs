@@ -3,7 +3,7 @@
 pub mod subtype;
 pub mod ty;
 
-use std::{convert::TryFrom, error, fmt, num};
+use std::{convert::TryFrom, error, fmt};
 
 use noodles_sam as sam;
 
@@ -626,47 +626,108 @@ impl Value {
     }
 }
 
-/// An error returned when a BAM data field value fails to covert to a SAM data field value.
+/// An error returned when an integer fails to convert to a BAM data field value.
+///
+/// BAM integers are in the range [-2^31, 2^32).
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum TryFromValueError {
-    /// The BAM data u32 value exceeds the range of a SAM data i32 value.
-    InvalidUInt32(num::TryFromIntError),
-}
+pub struct TryFromIntError(i64);
 
-impl error::Error for TryFromValueError {}
+impl error::Error for TryFromIntError {}
 
-impl fmt::Display for TryFromValueError {
+impl fmt::Display for TryFromIntError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::InvalidUInt32(e) => write!(f, "invalid u32 value: {}", e),
+        write!(f, "integer value is out of range: {}", self.0)
+    }
+}
+
+impl TryFrom<i64> for Value {
+    type Error = TryFromIntError;
+
+    /// Converts an integer to its canonical integer value.
+    ///
+    /// The canonical type is the smallest type that can represent the integer. Unsigned types
+    /// are used for nonnegative integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_bam::record::data::field::Value;
+    ///
+    /// assert_eq!(Value::try_from(-8), Ok(Value::Int8(-8)));
+    /// assert_eq!(Value::try_from(300), Ok(Value::UInt16(300)));
+    /// assert_eq!(Value::try_from(3000000000), Ok(Value::UInt32(3000000000)));
+    /// assert!(Value::try_from(5000000000).is_err());
+    /// ```
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
+        if n >= 0 {
+            if let Ok(m) = u8::try_from(n) {
+                Ok(Self::UInt8(m))
+            } else if let Ok(m) = u16::try_from(n) {
+                Ok(Self::UInt16(m))
+            } else if let Ok(m) = u32::try_from(n) {
+                Ok(Self::UInt32(m))
+            } else {
+                Err(TryFromIntError(n))
+            }
+        } else if let Ok(m) = i8::try_from(n) {
+            Ok(Self::Int8(m))
+        } else if let Ok(m) = i16::try_from(n) {
+            Ok(Self::Int16(m))
+        } else if let Ok(m) = i32::try_from(n) {
+            Ok(Self::Int32(m))
+        } else {
+            Err(TryFromIntError(n))
         }
     }
 }
 
-impl TryFrom<Value> for sam::record::data::field::Value {
-    type Error = TryFromValueError;
+impl From<Value> for sam::record::data::field::Value {
+    /// Converts a BAM data field value to a SAM data field value.
+    ///
+    /// BAM integers are converted to canonical SAM integers, i.e., a 32-bit unsigned integer
+    /// (`I`) greater than `i32::MAX` is kept as a SAM 32-bit unsigned integer.
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Char(c) => Self::Char(c),
+            Value::Int8(n) => Self::Int32(i32::from(n)),
+            Value::UInt8(n) => Self::Int32(i32::from(n)),
+            Value::Int16(n) => Self::Int32(i32::from(n)),
+            Value::UInt16(n) => Self::Int32(i32::from(n)),
+            Value::Int32(n) => Self::Int32(n),
+            Value::UInt32(n) => i32::try_from(n).map(Self::Int32).unwrap_or(Self::UInt32(n)),
+            Value::Float(n) => Self::Float(n),
+            Value::String(s) => Self::String(s),
+            Value::Hex(s) => Self::Hex(s),
+            Value::Int8Array(a) => Self::Int8Array(a),
+            Value::UInt8Array(a) => Self::UInt8Array(a),
+            Value::Int16Array(a) => Self::Int16Array(a),
+            Value::UInt16Array(a) => Self::UInt16Array(a),
+            Value::Int32Array(a) => Self::Int32Array(a),
+            Value::UInt32Array(a) => Self::UInt32Array(a),
+            Value::FloatArray(a) => Self::FloatArray(a),
+        }
+    }
+}
+
+impl From<sam::record::data::field::Value> for Value {
+    fn from(value: sam::record::data::field::Value) -> Self {
+        use sam::record::data::field::Value as SamValue;
 
-    fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Char(c) => Ok(Self::Char(c)),
-            Value::Int8(n) => Ok(Self::Int32(i32::from(n))),
-            Value::UInt8(n) => Ok(Self::Int32(i32::from(n))),
-            Value::Int16(n) => Ok(Self::Int32(i32::from(n))),
-            Value::UInt16(n) => Ok(Self::Int32(i32::from(n))),
-            Value::Int32(n) => Ok(Self::Int32(n)),
-            Value::UInt32(n) => i32::try_from(n)
-                .map(Self::Int32)
-                .map_err(TryFromValueError::InvalidUInt32),
-            Value::Float(n) => Ok(Self::Float(n)),
-            Value::String(s) => Ok(Self::String(s)),
-            Value::Hex(s) => Ok(Self::Hex(s)),
-            Value::Int8Array(a) => Ok(Self::Int8Array(a)),
-            Value::UInt8Array(a) => Ok(Self::UInt8Array(a)),
-            Value::Int16Array(a) => Ok(Self::Int16Array(a)),
-            Value::UInt16Array(a) => Ok(Self::UInt16Array(a)),
-            Value::Int32Array(a) => Ok(Self::Int32Array(a)),
-            Value::UInt32Array(a) => Ok(Self::UInt32Array(a)),
-            Value::FloatArray(a) => Ok(Self::FloatArray(a)),
+            SamValue::Char(c) => Self::Char(c),
+            SamValue::Int32(n) => Self::Int32(n),
+            SamValue::UInt32(n) => Self::UInt32(n),
+            SamValue::Float(n) => Self::Float(n),
+            SamValue::String(s) => Self::String(s),
+            SamValue::Hex(s) => Self::Hex(s),
+            SamValue::Int8Array(a) => Self::Int8Array(a),
+            SamValue::UInt8Array(a) => Self::UInt8Array(a),
+            SamValue::Int16Array(a) => Self::Int16Array(a),
+            SamValue::UInt16Array(a) => Self::UInt16Array(a),
+            SamValue::Int32Array(a) => Self::Int32Array(a),
+            SamValue::UInt32Array(a) => Self::UInt32Array(a),
+            SamValue::FloatArray(a) => Self::FloatArray(a),
         }
     }
 }
@@ -718,69 +779,117 @@ mod tests {
     }
 
     #[test]
-    fn test_try_from_value_for_sam_record_data_field_value() {
+    fn test_try_from_i64_for_value() {
+        assert_eq!(
+            Value::try_from(-2147483649),
+            Err(TryFromIntError(-2147483649))
+        );
+        assert_eq!(Value::try_from(-2147483648), Ok(Value::Int32(i32::MIN)));
+        assert_eq!(Value::try_from(-32769), Ok(Value::Int32(-32769)));
+        assert_eq!(Value::try_from(-32768), Ok(Value::Int16(i16::MIN)));
+        assert_eq!(Value::try_from(-129), Ok(Value::Int16(-129)));
+        assert_eq!(Value::try_from(-128), Ok(Value::Int8(i8::MIN)));
+        assert_eq!(Value::try_from(-1), Ok(Value::Int8(-1)));
+        assert_eq!(Value::try_from(0), Ok(Value::UInt8(0)));
+        assert_eq!(Value::try_from(255), Ok(Value::UInt8(u8::MAX)));
+        assert_eq!(Value::try_from(256), Ok(Value::UInt16(256)));
+        assert_eq!(Value::try_from(65535), Ok(Value::UInt16(u16::MAX)));
+        assert_eq!(Value::try_from(65536), Ok(Value::UInt32(65536)));
+        assert_eq!(Value::try_from(4294967295), Ok(Value::UInt32(u32::MAX)));
+        assert_eq!(
+            Value::try_from(4294967296),
+            Err(TryFromIntError(4294967296))
+        );
+    }
+
+    #[test]
+    fn test_from_value_for_sam_record_data_field_value() {
         use sam::record::data::field::Value as SamValue;
 
+        assert_eq!(SamValue::from(Value::Char('m')), SamValue::Char('m'));
+
+        assert_eq!(SamValue::from(Value::Int8(0)), SamValue::Int32(0));
+        assert_eq!(SamValue::from(Value::UInt8(0)), SamValue::Int32(0));
+        assert_eq!(SamValue::from(Value::Int16(0)), SamValue::Int32(0));
+        assert_eq!(SamValue::from(Value::UInt16(0)), SamValue::Int32(0));
+        assert_eq!(SamValue::from(Value::Int32(0)), SamValue::Int32(0));
+        assert_eq!(SamValue::from(Value::UInt32(0)), SamValue::Int32(0));
         assert_eq!(
-            SamValue::try_from(Value::Char('m')),
-            Ok(SamValue::Char('m'))
+            SamValue::from(Value::UInt32(u32::MAX)),
+            SamValue::UInt32(u32::MAX)
         );
 
-        assert_eq!(SamValue::try_from(Value::Int8(0)), Ok(SamValue::Int32(0)));
-        assert_eq!(SamValue::try_from(Value::UInt8(0)), Ok(SamValue::Int32(0)));
-        assert_eq!(SamValue::try_from(Value::Int16(0)), Ok(SamValue::Int32(0)));
-        assert_eq!(SamValue::try_from(Value::UInt16(0)), Ok(SamValue::Int32(0)));
-        assert_eq!(SamValue::try_from(Value::Int32(0)), Ok(SamValue::Int32(0)));
-        assert_eq!(SamValue::try_from(Value::UInt32(0)), Ok(SamValue::Int32(0)));
+        assert_eq!(
+            SamValue::from(Value::String(String::from("noodles"))),
+            SamValue::String(String::from("noodles"))
+        );
 
         assert_eq!(
-            SamValue::try_from(Value::String(String::from("noodles"))),
-            Ok(SamValue::String(String::from("noodles")))
+            SamValue::from(Value::Hex(String::from("cafe"))),
+            SamValue::Hex(String::from("cafe"))
         );
 
         assert_eq!(
-            SamValue::try_from(Value::Hex(String::from("cafe"))),
-            Ok(SamValue::Hex(String::from("cafe")))
+            SamValue::from(Value::Int8Array(vec![0])),
+            SamValue::Int8Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::Int8Array(vec![0])),
-            Ok(SamValue::Int8Array(vec![0]))
+            SamValue::from(Value::UInt8Array(vec![0])),
+            SamValue::UInt8Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::UInt8Array(vec![0])),
-            Ok(SamValue::UInt8Array(vec![0]))
+            SamValue::from(Value::Int16Array(vec![0])),
+            SamValue::Int16Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::Int16Array(vec![0])),
-            Ok(SamValue::Int16Array(vec![0]))
+            SamValue::from(Value::UInt16Array(vec![0])),
+            SamValue::UInt16Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::UInt16Array(vec![0])),
-            Ok(SamValue::UInt16Array(vec![0]))
+            SamValue::from(Value::Int32Array(vec![0])),
+            SamValue::Int32Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::Int32Array(vec![0])),
-            Ok(SamValue::Int32Array(vec![0]))
+            SamValue::from(Value::UInt32Array(vec![0])),
+            SamValue::UInt32Array(vec![0])
         );
 
         assert_eq!(
-            SamValue::try_from(Value::UInt32Array(vec![0])),
-            Ok(SamValue::UInt32Array(vec![0]))
+            SamValue::from(Value::FloatArray(vec![0.0])),
+            SamValue::FloatArray(vec![0.0])
         );
+    }
+
+    #[test]
+    fn test_from_sam_record_data_field_value_for_value() {
+        use sam::record::data::field::Value as SamValue;
+
+        assert_eq!(Value::from(SamValue::Char('m')), Value::Char('m'));
+        assert_eq!(Value::from(SamValue::Int32(13)), Value::Int32(13));
+        assert_eq!(
+            Value::from(SamValue::UInt32(3000000000)),
+            Value::UInt32(3000000000)
+        );
+        assert_eq!(Value::from(SamValue::Float(0.0)), Value::Float(0.0));
 
         assert_eq!(
-            SamValue::try_from(Value::FloatArray(vec![0.0])),
-            Ok(SamValue::FloatArray(vec![0.0]))
+            Value::from(SamValue::String(String::from("noodles"))),
+            Value::String(String::from("noodles"))
         );
 
-        assert!(matches!(
-            SamValue::try_from(Value::UInt32(u32::MAX)),
-            Err(TryFromValueError::InvalidUInt32(_))
-        ));
+        assert_eq!(
+            Value::from(SamValue::UInt16Array(vec![0])),
+            Value::UInt16Array(vec![0])
+        );
+
+        assert_eq!(
+            Value::from(SamValue::FloatArray(vec![0.0])),
+            Value::FloatArray(vec![0.0])
+        );
     }
 }
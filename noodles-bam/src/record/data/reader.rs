@@ -170,7 +170,7 @@ where
         Subtype::try_from(b).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     })?;
 
-    let len = reader.read_i32::<LittleEndian>()? as usize;
+    let len = reader.read_u32::<LittleEndian>().map(|n| n as usize)?;
 
     match subtype {
         Subtype::Int8 => {
@@ -309,6 +309,10 @@ mod tests {
         assert!(field.value().is_uint32_array());
         assert_eq!(field.value().as_uint32_array(), Some(&[27015][..]));
 
+        let field = read_one_field(&b"ZEBc\x00\x00\x00\x00"[..]);
+        assert_eq!(field.tag(), &Tag::Other(String::from("ZE")));
+        assert_eq!(field.value().as_int8_array(), Some(&[][..]));
+
         let field = read_one_field(&b"EQBf\x01\x00\x00\x00\xa1\xf8\x2d\x40"[..]);
         assert_eq!(field.tag(), &Tag::Other(String::from("EQ")));
         assert!(field.value().is_float_array());
@@ -0,0 +1,162 @@
+use std::io;
+
+use noodles_sam as sam;
+
+use super::Record;
+
+/// A BAM record that tracks whether it was mutated.
+///
+/// The original raw record is kept alongside a SAM record that is only decoded when mutable
+/// access is requested. Unchanged records can then be written by copying the original bytes,
+/// avoiding a decode/encode cycle. This speeds up pass-through filters, where most records are
+/// written as read.
+///
+/// See [`crate::Writer::write_tracked_record`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bam as bam;
+/// use noodles_sam as sam;
+///
+/// let reference_sequences = sam::header::ReferenceSequences::default();
+/// let mut record = bam::record::Tracked::from(bam::Record::default());
+/// assert!(!record.is_dirty());
+///
+/// record.get_mut(&reference_sequences)?;
+/// assert!(record.is_dirty());
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tracked {
+    raw: Record,
+    record: Option<sam::Record>,
+}
+
+impl Tracked {
+    /// Returns the original raw record.
+    ///
+    /// Its fields can be read without decoding, e.g., to decide whether to keep the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let record = bam::record::Tracked::from(bam::Record::default());
+    /// assert_eq!(record.raw(), &bam::Record::default());
+    /// ```
+    pub fn raw(&self) -> &Record {
+        &self.raw
+    }
+
+    /// Returns whether mutable access to the record was requested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let record = bam::record::Tracked::from(bam::Record::default());
+    /// assert!(!record.is_dirty());
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        self.record.is_some()
+    }
+
+    /// Returns a mutable reference to the record as a SAM record.
+    ///
+    /// The record is decoded on the first call and marked as dirty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let reference_sequences = sam::header::ReferenceSequences::default();
+    /// let mut record = bam::record::Tracked::from(bam::Record::default());
+    ///
+    /// let sam_record = record.get_mut(&reference_sequences)?;
+    /// assert_eq!(sam_record, &mut sam::Record::default());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn get_mut(
+        &mut self,
+        reference_sequences: &sam::header::ReferenceSequences,
+    ) -> io::Result<&mut sam::Record> {
+        let record = match self.record.take() {
+            Some(record) => record,
+            None => self.raw.try_into_sam_record(reference_sequences)?,
+        };
+
+        Ok(self.record.get_or_insert(record))
+    }
+
+    /// Converts this record to a SAM record.
+    ///
+    /// If the record is dirty, the mutated SAM record is returned; otherwise, the original record
+    /// is decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let reference_sequences = sam::header::ReferenceSequences::default();
+    /// let record = bam::record::Tracked::from(bam::Record::default());
+    ///
+    /// let sam_record = record.try_into_sam_record(&reference_sequences)?;
+    /// assert_eq!(sam_record, sam::Record::default());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn try_into_sam_record(
+        self,
+        reference_sequences: &sam::header::ReferenceSequences,
+    ) -> io::Result<sam::Record> {
+        match self.record {
+            Some(record) => Ok(record),
+            None => self.raw.try_into_sam_record(reference_sequences),
+        }
+    }
+
+    pub(crate) fn dirty_record(&self) -> Option<&sam::Record> {
+        self.record.as_ref()
+    }
+}
+
+impl From<Record> for Tracked {
+    fn from(raw: Record) -> Self {
+        Self { raw, record: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_mut() -> io::Result<()> {
+        let reference_sequences = sam::header::ReferenceSequences::default();
+
+        let mut record = Tracked::from(Record::default());
+        assert!(!record.is_dirty());
+        assert!(record.dirty_record().is_none());
+
+        let sam_record = record.get_mut(&reference_sequences)?;
+        *sam_record = sam::Record::builder()
+            .set_mapping_quality(sam::record::MappingQuality::from(8))
+            .build();
+        assert!(record.is_dirty());
+
+        let sam_record = record.try_into_sam_record(&reference_sequences)?;
+        assert_eq!(
+            sam_record.mapping_quality(),
+            sam::record::MappingQuality::from(8)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+//! Reference sequence renaming and record remapping.
+//!
+//! This supports the `reheader` workflow: reference sequences in a header are renamed (e.g.,
+//! `chr1` to `1`) or removed, and the reference sequence IDs of records are rewritten to match
+//! the new reference sequence dictionary.
+
+use std::{collections::HashSet, convert::TryFrom, error, fmt, io, mem};
+
+use byteorder::{ByteOrder, LittleEndian};
+use noodles_sam as sam;
+
+use super::{record::ReferenceSequenceId, Record};
+
+const REFERENCE_SEQUENCE_ID_OFFSET: usize = 0;
+const MATE_REFERENCE_SEQUENCE_ID_OFFSET: usize = 20;
+
+/// An error returned when reference sequences fail to be renamed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RenameError {
+    /// A new reference sequence name is used more than once.
+    DuplicateName(String),
+}
+
+impl error::Error for RenameError {}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateName(name) => write!(f, "duplicate reference sequence name: {}", name),
+        }
+    }
+}
+
+/// A map of old reference sequence IDs to new reference sequence IDs.
+///
+/// This is created by calling [`rename_reference_sequences`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReferenceSequenceIdMap(Vec<Option<ReferenceSequenceId>>);
+
+impl ReferenceSequenceIdMap {
+    /// Returns the new reference sequence ID of an old reference sequence ID.
+    ///
+    /// This returns `None` if the old reference sequence ID is out of range or its reference
+    /// sequence was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use noodles_bam::{record::ReferenceSequenceId, reheader::ReferenceSequenceIdMap};
+    /// let map = ReferenceSequenceIdMap::default();
+    /// assert!(map.get(ReferenceSequenceId::try_from(0)?).is_none());
+    /// # Ok::<_, noodles_bam::record::reference_sequence_id::TryFromIntError>(())
+    /// ```
+    pub fn get(&self, id: ReferenceSequenceId) -> Option<ReferenceSequenceId> {
+        usize::try_from(i32::from(id))
+            .ok()
+            .and_then(|i| self.0.get(i))
+            .copied()
+            .flatten()
+    }
+
+    /// Rewrites the reference sequence ID and mate reference sequence ID of a record.
+    ///
+    /// Unmapped reference sequence IDs are left as is. It is an error if a record refers to a
+    /// reference sequence that is not in the map, e.g., because it was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, reheader::ReferenceSequenceIdMap};
+    ///
+    /// let map = ReferenceSequenceIdMap::default();
+    /// let mut record = bam::Record::default();
+    /// map.remap(&mut record)?;
+    /// assert!(record.reference_sequence_id().is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn remap(&self, record: &mut Record) -> io::Result<()> {
+        let reference_sequence_id = record
+            .reference_sequence_id()
+            .map(|id| self.try_get(id))
+            .transpose()?;
+
+        let mate_reference_sequence_id = record
+            .mate_reference_sequence_id()
+            .map(|id| self.try_get(id))
+            .transpose()?;
+
+        if let Some(id) = reference_sequence_id {
+            write_reference_sequence_id(record, REFERENCE_SEQUENCE_ID_OFFSET, id);
+        }
+
+        if let Some(id) = mate_reference_sequence_id {
+            write_reference_sequence_id(record, MATE_REFERENCE_SEQUENCE_ID_OFFSET, id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns an iterator that rewrites the reference sequence IDs of records.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+    /// let mut header: sam::Header = reader.read_header()?.parse()?;
+    /// reader.read_reference_sequences()?;
+    ///
+    /// let map = bam::reheader::rename_reference_sequences(&mut header, |name| {
+    ///     Some(name.trim_start_matches("chr").into())
+    /// })?;
+    ///
+    /// let mut writer = bam::Writer::new(io::stdout());
+    /// writer.write_header(&header)?;
+    /// writer.write_reference_sequences(header.reference_sequences())?;
+    ///
+    /// for result in map.remap_records(reader.records()) {
+    ///     let record = result?;
+    ///     writer.write_record(&record)?;
+    /// }
+    ///
+    /// writer.try_finish()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn remap_records<I>(&self, records: I) -> Remap<'_, I>
+    where
+        I: Iterator<Item = io::Result<Record>>,
+    {
+        Remap { records, map: self }
+    }
+
+    fn try_get(&self, id: ReferenceSequenceId) -> io::Result<ReferenceSequenceId> {
+        self.get(id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("missing reference sequence ID in map: {}", i32::from(id)),
+            )
+        })
+    }
+}
+
+/// An iterator that rewrites the reference sequence IDs of records.
+///
+/// This is created by calling [`ReferenceSequenceIdMap::remap_records`].
+pub struct Remap<'a, I> {
+    records: I,
+    map: &'a ReferenceSequenceIdMap,
+}
+
+impl<'a, I> Iterator for Remap<'a, I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.next().map(|result| {
+            result.and_then(|mut record| {
+                self.map.remap(&mut record)?;
+                Ok(record)
+            })
+        })
+    }
+}
+
+/// Renames the reference sequences in a header.
+///
+/// `f` is called with the name of each reference sequence and returns its new name or `None` to
+/// remove it from the header. The order of the remaining reference sequences is kept.
+///
+/// This returns a map of old reference sequence IDs to new reference sequence IDs, which is used
+/// to rewrite records. The header is unchanged if a new name is used more than once.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_bam::{record::ReferenceSequenceId, reheader};
+/// use noodles_sam::{self as sam, header::ReferenceSequence};
+///
+/// let mut header = sam::Header::builder()
+///     .add_reference_sequence(ReferenceSequence::new(String::from("chr1"), 8))
+///     .add_reference_sequence(ReferenceSequence::new(String::from("chrUn"), 13))
+///     .add_reference_sequence(ReferenceSequence::new(String::from("chr2"), 21))
+///     .build();
+///
+/// let map = reheader::rename_reference_sequences(&mut header, |name| match name {
+///     "chrUn" => None,
+///     _ => Some(name.trim_start_matches("chr").into()),
+/// })?;
+///
+/// let names: Vec<_> = header.reference_sequences().keys().collect();
+/// assert_eq!(names, ["1", "2"]);
+///
+/// let id = |n: i32| ReferenceSequenceId::try_from(n);
+/// assert_eq!(map.get(id(2)?), Some(id(1)?));
+/// assert!(map.get(id(1)?).is_none());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn rename_reference_sequences<F>(
+    header: &mut sam::Header,
+    mut f: F,
+) -> Result<ReferenceSequenceIdMap, RenameError>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let reference_sequences = header.reference_sequences();
+
+    let mut new_names = Vec::with_capacity(reference_sequences.len());
+    let mut names = HashSet::with_capacity(reference_sequences.len());
+
+    for name in reference_sequences.keys() {
+        let new_name = f(name);
+
+        if let Some(new_name) = &new_name {
+            if !names.insert(new_name.clone()) {
+                return Err(RenameError::DuplicateName(new_name.clone()));
+            }
+        }
+
+        new_names.push(new_name);
+    }
+
+    let old_reference_sequences = mem::take(header.reference_sequences_mut());
+    let new_reference_sequences = header.reference_sequences_mut();
+    let mut ids = Vec::with_capacity(new_names.len());
+
+    for ((_, mut reference_sequence), new_name) in
+        old_reference_sequences.into_iter().zip(new_names)
+    {
+        match new_name {
+            Some(new_name) => {
+                let id = i32::try_from(new_reference_sequences.len())
+                    .ok()
+                    .and_then(|n| ReferenceSequenceId::try_from(n).ok());
+
+                *reference_sequence.name_mut() = new_name.clone();
+                new_reference_sequences.insert(new_name, reference_sequence);
+
+                ids.push(id);
+            }
+            None => ids.push(None),
+        }
+    }
+
+    Ok(ReferenceSequenceIdMap(ids))
+}
+
+fn write_reference_sequence_id(record: &mut Record, offset: usize, id: ReferenceSequenceId) {
+    LittleEndian::write_i32(&mut record.as_mut_bytes()[offset..], i32::from(id));
+}
+
+#[cfg(test)]
+mod tests {
+    use sam::header::ReferenceSequence;
+
+    use super::*;
+
+    fn build_header() -> sam::Header {
+        sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("chr1"), 8))
+            .add_reference_sequence(ReferenceSequence::new(String::from("chr2"), 13))
+            .add_reference_sequence(ReferenceSequence::new(String::from("chr3"), 21))
+            .build()
+    }
+
+    #[test]
+    fn test_rename_reference_sequences() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header = build_header();
+
+        let map = rename_reference_sequences(&mut header, |name| match name {
+            "chr2" => None,
+            _ => Some(name.trim_start_matches("chr").into()),
+        })?;
+
+        let reference_sequences = header.reference_sequences();
+        assert_eq!(reference_sequences.len(), 2);
+        assert_eq!(reference_sequences["1"].name(), "1");
+        assert_eq!(reference_sequences["3"].name(), "3");
+        assert_eq!(reference_sequences["3"].len(), 21);
+
+        let id = |n: i32| ReferenceSequenceId::try_from(n);
+        assert_eq!(map.get(id(0)?), Some(id(0)?));
+        assert_eq!(map.get(id(1)?), None);
+        assert_eq!(map.get(id(2)?), Some(id(1)?));
+        assert_eq!(map.get(id(3)?), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_reference_sequences_with_duplicate_name() {
+        let mut header = build_header();
+
+        assert_eq!(
+            rename_reference_sequences(&mut header, |_| Some(String::from("sq0"))),
+            Err(RenameError::DuplicateName(String::from("sq0")))
+        );
+
+        let names: Vec<_> = header.reference_sequences().keys().collect();
+        assert_eq!(names, ["chr1", "chr2", "chr3"]);
+    }
+
+    #[test]
+    fn test_remap_records() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header = build_header();
+
+        let sam_record = sam::Record::builder()
+            .set_reference_sequence_name("chr3".parse()?)
+            .set_position(sam::record::Position::try_from(1)?)
+            .set_mate_reference_sequence_name("chr1".parse()?)
+            .set_mate_position(sam::record::Position::try_from(5)?)
+            .build();
+        let record = Record::try_from_sam_record(header.reference_sequences(), &sam_record)?;
+
+        let map = rename_reference_sequences(&mut header, |name| match name {
+            "chr2" => None,
+            _ => Some(name.trim_start_matches("chr").into()),
+        })?;
+
+        let records: Vec<_> = map
+            .remap_records(vec![Ok(record.clone())].into_iter())
+            .collect::<io::Result<_>>()?;
+
+        let id = |n: i32| ReferenceSequenceId::try_from(n);
+        assert_eq!(records[0].reference_sequence_id(), Some(id(1)?));
+        assert_eq!(records[0].mate_reference_sequence_id(), Some(id(0)?));
+        assert_eq!(records[0].position(), record.position());
+
+        let actual = records[0].try_into_sam_record(header.reference_sequences())?;
+        assert_eq!(
+            actual.reference_sequence_name().map(|name| &name[..]),
+            Some("3")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remap_with_removed_reference_sequence() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header = build_header();
+
+        let sam_record = sam::Record::builder()
+            .set_reference_sequence_name("chr2".parse()?)
+            .set_position(sam::record::Position::try_from(1)?)
+            .build();
+        let mut record = Record::try_from_sam_record(header.reference_sequences(), &sam_record)?;
+
+        let map = rename_reference_sequences(&mut header, |name| match name {
+            "chr2" => None,
+            _ => Some(name.into()),
+        })?;
+
+        assert!(map.remap(&mut record).is_err());
+
+        Ok(())
+    }
+}
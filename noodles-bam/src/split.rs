@@ -0,0 +1,808 @@
+//! Record splitting by read group and reference sequence.
+//!
+//! A [`Splitter`] demultiplexes records into separate BAM outputs by the value of a data field,
+//! typically the read group (`RG`). Each output gets a copy of the input header that only
+//! includes the relevant read group.
+//!
+//! The number of open outputs is bounded. When the limit is reached, the least recently used
+//! output is finished and closed. It is reopened in [`Mode::Append`] if a later record is routed
+//! to it. Each reopening appends a BGZF EOF marker (an empty block) to the middle of the output,
+//! which readers skip.
+//!
+//! A [`ReferenceSequenceSplitter`] splits coordinate-sorted records into one output per
+//! reference sequence, optionally building a BAM index (BAI) for each output.
+
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+};
+
+use noodles_sam::{self as sam, record::data::field::Tag};
+
+use super::{
+    bai::{self, index::reference_sequence::bin::Chunk},
+    record::{data::field::Value, ReferenceSequenceId},
+    Record, Writer,
+};
+
+const DEFAULT_CAPACITY: usize = 64;
+
+/// The mode an output is opened in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// The output is new and should be created or truncated.
+    Create,
+    /// The output was previously closed and should be appended to.
+    Append,
+}
+
+/// A demultiplexing BAM writer.
+///
+/// Records are routed to outputs by the value of a data field, which is read group (`RG`) by
+/// default. Outputs are opened on demand by calling `open` with the field value and [`Mode`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::{File, OpenOptions}, io};
+/// use noodles_bam::{self as bam, split::{Mode, Splitter}};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let header: sam::Header = reader.read_header()?.parse()?;
+/// reader.read_reference_sequences()?;
+///
+/// let mut splitter = Splitter::new(header, |read_group_id, mode| {
+///     OpenOptions::new()
+///         .write(true)
+///         .create(true)
+///         .truncate(mode == Mode::Create)
+///         .append(mode == Mode::Append)
+///         .open(format!("sample.{}.bam", read_group_id))
+/// });
+///
+/// for result in reader.records() {
+///     let record = result?;
+///     splitter.write_record(&record)?;
+/// }
+///
+/// splitter.try_finish()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Splitter<W, F>
+where
+    W: Write,
+{
+    header: sam::Header,
+    open: F,
+    tag: Tag,
+    capacity: usize,
+    unmatched_key: Option<String>,
+    // Ordered from least to most recently used.
+    writers: Vec<(String, Writer<W>)>,
+    keys: HashSet<String>,
+}
+
+impl<W, F> Splitter<W, F>
+where
+    W: Write,
+    F: FnMut(&str, Mode) -> io::Result<W>,
+{
+    /// Creates a splitter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam as sam;
+    ///
+    /// let splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()));
+    /// ```
+    pub fn new(header: sam::Header, open: F) -> Self {
+        Self {
+            header,
+            open,
+            tag: Tag::ReadGroup,
+            capacity: DEFAULT_CAPACITY,
+            unmatched_key: None,
+            writers: Vec::new(),
+            keys: HashSet::new(),
+        }
+    }
+
+    /// Sets the data field tag used to route records.
+    ///
+    /// Output headers only include the matching read group when the tag is read group (`RG`).
+    /// For other tags, e.g., a cell barcode, all read groups are kept.
+    ///
+    /// By default, this is read group (`RG`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam::{self as sam, record::data::field::Tag};
+    ///
+    /// let splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()))
+    ///     .set_tag(Tag::CellBarcodeId);
+    /// ```
+    pub fn set_tag(mut self, tag: Tag) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Sets the maximum number of open outputs.
+    ///
+    /// This is clamped to at least 1.
+    ///
+    /// By default, this is 64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam as sam;
+    ///
+    /// let splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()))
+    ///     .set_capacity(16);
+    /// ```
+    pub fn set_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets the output key of records that do not have the routing field.
+    ///
+    /// By default, writing such a record is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam as sam;
+    ///
+    /// let splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()))
+    ///     .set_unmatched_key(String::from("unknown"));
+    /// ```
+    pub fn set_unmatched_key(mut self, key: String) -> Self {
+        self.unmatched_key = Some(key);
+        self
+    }
+
+    /// Returns the number of open outputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam as sam;
+    /// let splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()));
+    /// assert_eq!(splitter.open_len(), 0);
+    /// ```
+    pub fn open_len(&self) -> usize {
+        self.writers.len()
+    }
+
+    /// Writes a record to the output of its routing field value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, split::Splitter};
+    /// use noodles_sam as sam;
+    ///
+    /// let mut splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()))
+    ///     .set_unmatched_key(String::from("unknown"));
+    ///
+    /// splitter.write_record(&bam::Record::default())?;
+    /// assert_eq!(splitter.open_len(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        let key = match (self.find_key(record)?, &self.unmatched_key) {
+            (Some(key), _) => key,
+            (None, Some(key)) => key.clone(),
+            (None, None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("missing {} field", self.tag),
+                ))
+            }
+        };
+
+        let writer = self.get_or_open(key)?;
+        writer.write_record(record)
+    }
+
+    /// Finishes all open outputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::split::Splitter;
+    /// use noodles_sam as sam;
+    /// let mut splitter = Splitter::new(sam::Header::default(), |_, _| Ok(Vec::new()));
+    /// splitter.try_finish()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn try_finish(&mut self) -> io::Result<()> {
+        for (_, mut writer) in self.writers.drain(..) {
+            writer.try_finish()?;
+        }
+
+        Ok(())
+    }
+
+    fn find_key(&self, record: &Record) -> io::Result<Option<String>> {
+        for result in record.data().fields() {
+            let field = result?;
+
+            if field.tag() != &self.tag {
+                continue;
+            }
+
+            let key = match field.value() {
+                Value::Char(c) => c.to_string(),
+                Value::String(s) | Value::Hex(s) => s.clone(),
+                Value::Int8(n) => n.to_string(),
+                Value::UInt8(n) => n.to_string(),
+                Value::Int16(n) => n.to_string(),
+                Value::UInt16(n) => n.to_string(),
+                Value::Int32(n) => n.to_string(),
+                Value::UInt32(n) => n.to_string(),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid {} field value type", self.tag),
+                    ))
+                }
+            };
+
+            return Ok(Some(key));
+        }
+
+        Ok(None)
+    }
+
+    fn get_or_open(&mut self, key: String) -> io::Result<&mut Writer<W>> {
+        if let Some(i) = self.writers.iter().position(|(k, _)| *k == key) {
+            let entry = self.writers.remove(i);
+            self.writers.push(entry);
+        } else {
+            if self.writers.len() >= self.capacity {
+                let (_, mut writer) = self.writers.remove(0);
+                writer.try_finish()?;
+            }
+
+            let writer = if self.keys.contains(&key) {
+                (self.open)(&key, Mode::Append).map(Writer::new)?
+            } else {
+                let mut writer = (self.open)(&key, Mode::Create).map(Writer::new)?;
+
+                let header = self.build_header(&key);
+                writer.write_header(&header)?;
+                writer.write_reference_sequences(header.reference_sequences())?;
+
+                self.keys.insert(key.clone());
+
+                writer
+            };
+
+            self.writers.push((key, writer));
+        }
+
+        // SAFETY: `writers` is nonempty.
+        Ok(&mut self.writers.last_mut().unwrap().1)
+    }
+
+    fn build_header(&self, key: &str) -> sam::Header {
+        let mut header = self.header.clone();
+
+        if self.tag == Tag::ReadGroup {
+            header.read_groups_mut().retain(|id, _| id == key);
+        }
+
+        header
+    }
+}
+
+/// An output written by [`ReferenceSequenceSplitter::split`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Part {
+    reference_sequence_name: Option<String>,
+    record_count: u64,
+    index: Option<bai::Index>,
+}
+
+impl Part {
+    /// Returns the reference sequence name of the records in this part.
+    ///
+    /// This is `None` for the part of unplaced unmapped records.
+    pub fn reference_sequence_name(&self) -> Option<&str> {
+        self.reference_sequence_name.as_deref()
+    }
+
+    /// Returns the number of records written to this part.
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    /// Returns the index of this part, if it was built.
+    pub fn index(&self) -> Option<&bai::Index> {
+        self.index.as_ref()
+    }
+}
+
+/// A per-reference sequence BAM splitter.
+///
+/// Coordinate-sorted records are split into one output per reference sequence, and unplaced
+/// unmapped records are written to a final output. Outputs are opened by calling `open` with the
+/// reference sequence name, or `None` for unplaced unmapped records. Reference sequences without
+/// records do not get an output.
+///
+/// Each output header is the input header. All reference sequences are kept so that reference
+/// sequence IDs, including those of mates on other reference sequences, stay valid.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bam::{self as bam, bai, split::ReferenceSequenceSplitter};
+/// use noodles_sam as sam;
+///
+/// let mut reader = File::open("sample.bam").map(bam::Reader::new)?;
+/// let header: sam::Header = reader.read_header()?.parse()?;
+/// reader.read_reference_sequences()?;
+///
+/// let mut splitter = ReferenceSequenceSplitter::new(header, |name| {
+///     File::create(format!("sample.{}.bam", name.unwrap_or("unmapped")))
+/// })
+/// .set_build_index(true);
+///
+/// for part in splitter.split(reader.records())? {
+///     if let (Some(name), Some(index)) = (part.reference_sequence_name(), part.index()) {
+///         bai::write(format!("sample.{}.bam.bai", name), index)?;
+///     }
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ReferenceSequenceSplitter<F> {
+    header: sam::Header,
+    open: F,
+    build_index: bool,
+}
+
+impl<W, F> ReferenceSequenceSplitter<F>
+where
+    W: Write,
+    F: FnMut(Option<&str>) -> io::Result<W>,
+{
+    /// Creates a per-reference sequence splitter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::ReferenceSequenceSplitter;
+    /// use noodles_sam as sam;
+    ///
+    /// let splitter = ReferenceSequenceSplitter::new(sam::Header::default(), |_| Ok(Vec::new()));
+    /// ```
+    pub fn new(header: sam::Header, open: F) -> Self {
+        Self {
+            header,
+            open,
+            build_index: false,
+        }
+    }
+
+    /// Sets whether to build a BAM index for each output.
+    ///
+    /// The index is returned in the output's [`Part`] and is not written.
+    ///
+    /// By default, indices are not built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam::split::ReferenceSequenceSplitter;
+    /// use noodles_sam as sam;
+    ///
+    /// let splitter = ReferenceSequenceSplitter::new(sam::Header::default(), |_| Ok(Vec::new()))
+    ///     .set_build_index(true);
+    /// ```
+    pub fn set_build_index(mut self, build_index: bool) -> Self {
+        self.build_index = build_index;
+        self
+    }
+
+    /// Splits coordinate-sorted records.
+    ///
+    /// This returns the parts in the order they were written. It is an error if the records are
+    /// not sorted by reference sequence, with unplaced unmapped records last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam::{self as bam, split::ReferenceSequenceSplitter};
+    /// use noodles_sam as sam;
+    ///
+    /// let mut splitter = ReferenceSequenceSplitter::new(sam::Header::default(), |_| Ok(Vec::new()));
+    /// let parts = splitter.split(vec![Ok(bam::Record::default())])?;
+    ///
+    /// assert_eq!(parts.len(), 1);
+    /// assert!(parts[0].reference_sequence_name().is_none());
+    /// assert_eq!(parts[0].record_count(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn split<I>(&mut self, records: I) -> io::Result<Vec<Part>>
+    where
+        I: IntoIterator<Item = io::Result<Record>>,
+    {
+        let mut parts = Vec::new();
+        let mut current: Option<OpenPart<W>> = None;
+
+        for result in records {
+            let record = result?;
+            let reference_sequence_id = record.reference_sequence_id();
+
+            match current.as_ref().map(|part| part.reference_sequence_id) {
+                Some(id) if id == reference_sequence_id => {}
+                prev_id => {
+                    if let Some(prev_id) = prev_id {
+                        if sort_key(reference_sequence_id) < sort_key(prev_id) {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "records are not coordinate-sorted",
+                            ));
+                        }
+                    }
+
+                    if let Some(part) = current.take() {
+                        parts.push(self.finish_part(part)?);
+                    }
+
+                    current = Some(self.open_part(reference_sequence_id)?);
+                }
+            }
+
+            // SAFETY: `current` is set above.
+            let part = current.as_mut().unwrap();
+
+            let start = part.writer.virtual_position();
+            part.writer.write_record(&record)?;
+            let end = part.writer.virtual_position();
+
+            if let Some(builder) = part.index_builder.as_mut() {
+                builder.add_record(&record, Chunk::new(start, end))?;
+            }
+
+            part.record_count += 1;
+        }
+
+        if let Some(part) = current.take() {
+            parts.push(self.finish_part(part)?);
+        }
+
+        Ok(parts)
+    }
+
+    fn open_part(
+        &mut self,
+        reference_sequence_id: Option<ReferenceSequenceId>,
+    ) -> io::Result<OpenPart<W>> {
+        let reference_sequence_name = match reference_sequence_id {
+            Some(id) => {
+                let name = self
+                    .header
+                    .reference_sequences()
+                    .get_index(i32::from(id) as usize)
+                    .map(|(name, _)| name.clone())
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("invalid reference sequence ID: {}", i32::from(id)),
+                        )
+                    })?;
+
+                Some(name)
+            }
+            None => None,
+        };
+
+        let mut writer = (self.open)(reference_sequence_name.as_deref()).map(Writer::new)?;
+
+        writer.write_header(&self.header)?;
+        writer.write_reference_sequences(self.header.reference_sequences())?;
+
+        let index_builder = if self.build_index && !self.header.reference_sequences().is_empty() {
+            Some(bai::Index::builder())
+        } else {
+            None
+        };
+
+        Ok(OpenPart {
+            reference_sequence_id,
+            reference_sequence_name,
+            writer,
+            index_builder,
+            record_count: 0,
+        })
+    }
+
+    fn finish_part(&self, mut part: OpenPart<W>) -> io::Result<Part> {
+        part.writer.try_finish()?;
+
+        let reference_sequence_count = self.header.reference_sequences().len();
+        let index = part
+            .index_builder
+            .map(|builder| builder.build(reference_sequence_count));
+
+        Ok(Part {
+            reference_sequence_name: part.reference_sequence_name,
+            record_count: part.record_count,
+            index,
+        })
+    }
+}
+
+struct OpenPart<W>
+where
+    W: Write,
+{
+    reference_sequence_id: Option<ReferenceSequenceId>,
+    reference_sequence_name: Option<String>,
+    writer: Writer<W>,
+    index_builder: Option<bai::index::Builder>,
+    record_count: u64,
+}
+
+// Unplaced unmapped records are sorted last.
+fn sort_key(reference_sequence_id: Option<ReferenceSequenceId>) -> i64 {
+    reference_sequence_id
+        .map(|id| i64::from(i32::from(id)))
+        .unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use sam::{
+        header::{ReadGroup, ReferenceSequence},
+        record::data::{field::Value as SamValue, Field},
+    };
+
+    use super::*;
+    use crate::Reader;
+
+    type Outputs = Rc<RefCell<HashMap<String, Vec<u8>>>>;
+
+    struct SharedWriter {
+        key: String,
+        outputs: Outputs,
+    }
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let mut outputs = self.outputs.borrow_mut();
+            outputs.entry(self.key.clone()).or_default().extend(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn build_record(header: &sam::Header, read_group_id: &str) -> io::Result<Record> {
+        let sam_record = sam::Record::builder()
+            .set_data(sam::record::Data::from(vec![Field::new(
+                Tag::ReadGroup,
+                SamValue::String(read_group_id.into()),
+            )]))
+            .build();
+
+        Record::try_from_sam_record(header.reference_sequences(), &sam_record)
+    }
+
+    fn read_outputs(outputs: &Outputs) -> io::Result<HashMap<String, (sam::Header, usize)>> {
+        let mut result = HashMap::new();
+
+        for (key, data) in outputs.borrow().iter() {
+            let mut reader = Reader::new(&data[..]);
+            let header: sam::Header = reader
+                .read_header()?
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            reader.read_reference_sequences()?;
+            let n = reader.records().collect::<io::Result<Vec<_>>>()?.len();
+            result.insert(key.clone(), (header, n));
+        }
+
+        Ok(result)
+    }
+
+    #[test]
+    fn test_write_record() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_read_group(ReadGroup::new(String::from("rg0")))
+            .add_read_group(ReadGroup::new(String::from("rg1")))
+            .add_read_group(ReadGroup::new(String::from("rg2")))
+            .build();
+
+        let outputs = Outputs::default();
+        let modes = Rc::new(RefCell::new(Vec::new()));
+
+        let mut splitter = {
+            let outputs = outputs.clone();
+            let modes = modes.clone();
+
+            Splitter::new(header.clone(), move |key, mode| {
+                modes.borrow_mut().push((key.to_string(), mode));
+                Ok(SharedWriter {
+                    key: key.into(),
+                    outputs: outputs.clone(),
+                })
+            })
+            .set_capacity(1)
+        };
+
+        for id in &["rg0", "rg1", "rg0", "rg0", "rg1"] {
+            splitter.write_record(&build_record(&header, id)?)?;
+        }
+
+        assert_eq!(splitter.open_len(), 1);
+        splitter.try_finish()?;
+        assert_eq!(splitter.open_len(), 0);
+
+        assert_eq!(
+            *modes.borrow(),
+            [
+                (String::from("rg0"), Mode::Create),
+                (String::from("rg1"), Mode::Create),
+                (String::from("rg0"), Mode::Append),
+                (String::from("rg1"), Mode::Append),
+            ]
+        );
+
+        let actual = read_outputs(&outputs)?;
+        assert_eq!(actual.len(), 2);
+
+        let (rg0_header, rg0_len) = &actual["rg0"];
+        let read_group_ids: Vec<_> = rg0_header.read_groups().keys().collect();
+        assert_eq!(read_group_ids, ["rg0"]);
+        assert_eq!(*rg0_len, 3);
+
+        let (rg1_header, rg1_len) = &actual["rg1"];
+        let read_group_ids: Vec<_> = rg1_header.read_groups().keys().collect();
+        assert_eq!(read_group_ids, ["rg1"]);
+        assert_eq!(*rg1_len, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_record_with_missing_field() {
+        let header = sam::Header::default();
+        let mut splitter = Splitter::new(header, |_, _| Ok(Vec::new()));
+        assert!(splitter.write_record(&Record::default()).is_err());
+    }
+
+    #[test]
+    fn test_reference_sequence_splitter_split() -> Result<(), Box<dyn std::error::Error>> {
+        use std::{convert::TryFrom, io::Cursor};
+
+        use noodles::Region;
+
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 1000))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 1000))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq2"), 1000))
+            .build();
+
+        let build =
+            |name: Option<&str>, position: i32| -> Result<Record, Box<dyn std::error::Error>> {
+                let mut builder = sam::Record::builder();
+
+                if let Some(name) = name {
+                    builder = builder
+                        .set_flags(sam::record::Flags::empty())
+                        .set_reference_sequence_name(name.parse()?)
+                        .set_position(sam::record::Position::try_from(position)?)
+                        .set_cigar("4M".parse()?);
+                }
+
+                let sam_record = builder.build();
+                Ok(Record::try_from_sam_record(
+                    header.reference_sequences(),
+                    &sam_record,
+                )?)
+            };
+
+        let records = vec![
+            build(Some("sq0"), 8)?,
+            build(Some("sq0"), 13)?,
+            build(Some("sq2"), 21)?,
+            build(None, 0)?,
+        ];
+
+        let outputs = Outputs::default();
+
+        let mut splitter = {
+            let outputs = outputs.clone();
+
+            ReferenceSequenceSplitter::new(header.clone(), move |name| {
+                Ok(SharedWriter {
+                    key: name.unwrap_or("*").into(),
+                    outputs: outputs.clone(),
+                })
+            })
+            .set_build_index(true)
+        };
+
+        let parts = splitter.split(records.into_iter().map(Ok))?;
+
+        let names: Vec<_> = parts.iter().map(|p| p.reference_sequence_name()).collect();
+        assert_eq!(names, [Some("sq0"), Some("sq2"), None]);
+
+        let counts: Vec<_> = parts.iter().map(|p| p.record_count()).collect();
+        assert_eq!(counts, [2, 1, 1]);
+
+        let actual = read_outputs(&outputs)?;
+        assert_eq!(actual["sq0"].1, 2);
+        assert_eq!(actual["sq2"].1, 1);
+        assert_eq!(actual["*"].1, 1);
+
+        let index = parts[0].index().ok_or("missing index")?;
+        assert_eq!(index.reference_sequences().len(), 3);
+        assert_eq!(index.unplaced_unmapped_read_count(), Some(0));
+
+        let data = outputs.borrow()["sq0"].clone();
+        let mut reader = Reader::new(Cursor::new(data));
+        reader.read_header()?;
+        reader.read_reference_sequences()?;
+
+        let region = Region::mapped("sq0", 10, 20);
+        let query = reader.query(header.reference_sequences(), index, &region)?;
+        let positions: Vec<_> = query
+            .map(|result| result.map(|record| record.position().map(i64::from)))
+            .collect::<io::Result<_>>()?;
+        assert_eq!(positions, [Some(8), Some(13)]);
+
+        assert_eq!(
+            parts[2]
+                .index()
+                .and_then(|index| index.unplaced_unmapped_read_count()),
+            Some(1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reference_sequence_splitter_split_with_unsorted_records(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 8))
+            .add_reference_sequence(ReferenceSequence::new(String::from("sq1"), 8))
+            .build();
+
+        let mut records = Vec::new();
+
+        for name in &["sq1", "sq0"] {
+            let sam_record = sam::Record::builder()
+                .set_reference_sequence_name(name.parse()?)
+                .build();
+            let record = Record::try_from_sam_record(header.reference_sequences(), &sam_record)?;
+            records.push(Ok(record));
+        }
+
+        let mut splitter = ReferenceSequenceSplitter::new(header, |_| Ok(Vec::new()));
+        assert!(splitter.split(records).is_err());
+
+        Ok(())
+    }
+}
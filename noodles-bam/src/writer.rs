@@ -1,18 +1,21 @@
 pub(crate) mod record;
 
 use std::{
+    convert::TryFrom,
     ffi::CString,
     io::{self, Write},
+    mem,
 };
 
 use byteorder::{LittleEndian, WriteBytesExt};
 use noodles_bgzf as bgzf;
+use noodles_core::QualityBinning;
 use noodles_sam::{
     self as sam,
     header::{ReferenceSequence, ReferenceSequences},
 };
 
-use super::{Record, MAGIC_NUMBER};
+use super::{record::Tracked, Record, MAGIC_NUMBER};
 
 /// A BAM writer.
 ///
@@ -41,6 +44,8 @@ where
     W: Write,
 {
     inner: bgzf::Writer<W>,
+    align_records: bool,
+    quality_binning: Option<QualityBinning>,
 }
 
 impl<W> Writer<W>
@@ -60,9 +65,50 @@ where
     pub fn new(writer: W) -> Self {
         Self {
             inner: bgzf::Writer::new(writer),
+            align_records: false,
+            quality_binning: None,
         }
     }
 
+    /// Sets whether to avoid splitting records across BGZF blocks.
+    ///
+    /// When enabled, the current block is flushed before a record that would not fit in it, so
+    /// that each block starts at a record boundary. This simplifies chunk-level parallel readers.
+    /// A record larger than a block is still split.
+    ///
+    /// By default, records are not aligned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::new(Vec::new()).set_align_records(true);
+    /// ```
+    pub fn set_align_records(mut self, align_records: bool) -> Self {
+        self.align_records = align_records;
+        self
+    }
+
+    /// Sets a quality score binning scheme.
+    ///
+    /// When set, the quality scores of each record are binned as the record is written. Missing
+    /// quality scores are kept as is.
+    ///
+    /// By default, quality scores are not binned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_core::QualityBinning;
+    ///
+    /// let writer = bam::Writer::new(Vec::new()).set_quality_binning(QualityBinning::illumina());
+    /// ```
+    pub fn set_quality_binning(mut self, quality_binning: QualityBinning) -> Self {
+        self.quality_binning = Some(quality_binning);
+        self
+    }
+
     /// Returns a reference to the underlying writer.
     ///
     /// # Examples
@@ -76,10 +122,41 @@ where
         self.inner.get_ref()
     }
 
+    /// Returns the metrics collected by the underlying BGZF writer.
+    ///
+    /// This is only available with the `metrics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// let writer = bam::Writer::new(Vec::new());
+    /// assert_eq!(writer.metrics().blocks(), 0);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> &bgzf::Metrics {
+        self.inner.metrics()
+    }
+
+    /// Returns the current virtual position of the underlying BGZF writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bam as bam;
+    /// use noodles_bgzf as bgzf;
+    /// let writer = bam::Writer::new(Vec::new());
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(0));
+    /// ```
+    pub fn virtual_position(&self) -> bgzf::VirtualPosition {
+        self.inner.virtual_position()
+    }
+
     /// Attempts to finish the output stream.
     ///
-    /// This is typically only manually called if the underlying stream is needed before the writer
-    /// is dropped.
+    /// This flushes the current block and writes the BGZF EOF marker. It is also called when the
+    /// writer is dropped, but any error is then ignored. Call this (or [`Self::finish`]) before
+    /// dropping the writer to detect write errors, e.g., when writing to a closed pipe.
     ///
     /// # Examples
     ///
@@ -94,6 +171,46 @@ where
         self.inner.try_finish()
     }
 
+    /// Finishes the output stream and returns the underlying writer.
+    ///
+    /// See [`Self::try_finish`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    ///
+    /// let writer = bam::Writer::new(Vec::new());
+    /// let data = writer.finish()?;
+    ///
+    /// // BGZF EOF block
+    /// assert_eq!(data.len(), 28);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn finish(self) -> io::Result<W> {
+        self.inner.finish()
+    }
+
+    /// Flushes the current BGZF block.
+    ///
+    /// The next write starts a new block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    ///
+    /// let mut writer = bam::Writer::new(Vec::new());
+    /// writer.write_record(&bam::Record::default())?;
+    /// writer.flush()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
     /// Writes a SAM header.
     ///
     /// # Examples
@@ -171,9 +288,14 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
-        let block_size = record.len() as u32;
-        self.inner.write_u32::<LittleEndian>(block_size)?;
-        self.inner.write_all(record)
+        match self.quality_binning {
+            Some(quality_binning) => {
+                let mut record = record.clone();
+                quality_binning.apply(record.quality_scores_mut());
+                self.write_raw_record(&record)
+            }
+            None => self.write_raw_record(record),
+        }
     }
 
     /// Writes a SAM record.
@@ -197,7 +319,72 @@ where
         reference_sequences: &ReferenceSequences,
         record: &sam::Record,
     ) -> io::Result<()> {
-        record::write_sam_record(&mut self.inner, reference_sequences, record)
+        if let Some(quality_binning) = self.quality_binning {
+            let mut buf = Vec::new();
+            record::write_sam_record(&mut buf, reference_sequences, record)?;
+
+            // Skip the block size.
+            let mut record = Record::try_from(buf.split_off(mem::size_of::<u32>()))?;
+            quality_binning.apply(record.quality_scores_mut());
+
+            self.write_raw_record(&record)
+        } else if self.align_records {
+            let mut buf = Vec::new();
+            record::write_sam_record(&mut buf, reference_sequences, record)?;
+            self.align_block(buf.len())?;
+            self.inner.write_all(&buf)
+        } else {
+            record::write_sam_record(&mut self.inner, reference_sequences, record)
+        }
+    }
+
+    /// Writes a tracked BAM record.
+    ///
+    /// If the record was not mutated, the original record is copied as is. Otherwise, the mutated
+    /// record is encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bam as bam;
+    /// use noodles_sam as sam;
+    ///
+    /// let mut writer = bam::Writer::new(Vec::new());
+    ///
+    /// let reference_sequences = sam::header::ReferenceSequences::new();
+    /// let record = bam::record::Tracked::from(bam::Record::default());
+    /// writer.write_tracked_record(&reference_sequences, &record)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_tracked_record(
+        &mut self,
+        reference_sequences: &ReferenceSequences,
+        record: &Tracked,
+    ) -> io::Result<()> {
+        match record.dirty_record() {
+            Some(sam_record) => self.write_sam_record(reference_sequences, sam_record),
+            None => self.write_record(record.raw()),
+        }
+    }
+
+    fn write_raw_record(&mut self, record: &Record) -> io::Result<()> {
+        self.align_block(mem::size_of::<u32>() + record.len())?;
+
+        let block_size = record.len() as u32;
+        self.inner.write_u32::<LittleEndian>(block_size)?;
+        self.inner.write_all(record)
+    }
+
+    fn align_block(&mut self, len: usize) -> io::Result<()> {
+        if self.align_records
+            && self.inner.block_len() > 0
+            && len > self.inner.remaining_block_capacity()
+        {
+            self.inner.flush()
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -400,6 +587,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_sam_record_with_quality_binning() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = Writer::new(Vec::new()).set_quality_binning(QualityBinning::illumina());
+
+        let header = sam::Header::default();
+
+        let sam_record = sam::Record::builder()
+            .set_sequence("ATCG".parse()?)
+            .set_quality_scores("NDLS".parse()?)
+            .build();
+        writer.write_sam_record(header.reference_sequences(), &sam_record)?;
+
+        let sam_record = sam::Record::builder().set_sequence("AT".parse()?).build();
+        writer.write_sam_record(header.reference_sequences(), &sam_record)?;
+
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(writer.get_ref().as_slice());
+        let mut record = Record::default();
+
+        reader.read_record(&mut record)?;
+        assert_eq!(*record.quality_scores(), [40, 37, 40, 40]);
+
+        reader.read_record(&mut record)?;
+        assert_eq!(*record.quality_scores(), [255, 255]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_write_sam_record_with_data() -> io::Result<()> {
         use noodles_sam::record::data::{
@@ -447,4 +663,69 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_tracked_record() -> io::Result<()> {
+        let header = sam::Header::default();
+        let reference_sequences = header.reference_sequences();
+
+        let mut raw_record = Record::default();
+        // Set a mapping quality (MAPQ = 8) that is only visible if the raw bytes are copied.
+        raw_record.as_mut_bytes()[9] = 8;
+
+        let clean_record = Tracked::from(raw_record.clone());
+
+        let mut dirty_record = Tracked::from(raw_record);
+        *dirty_record.get_mut(reference_sequences)? = sam::Record::default();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_tracked_record(reference_sequences, &clean_record)?;
+        writer.write_tracked_record(reference_sequences, &dirty_record)?;
+        writer.try_finish()?;
+
+        let mut reader = Reader::new(writer.get_ref().as_slice());
+        let mut record = Record::default();
+
+        reader.read_record(&mut record)?;
+        assert_eq!(&record, clean_record.raw());
+
+        reader.read_record(&mut record)?;
+        assert_eq!(
+            record.mapping_quality(),
+            sam::record::MappingQuality::from(255)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_record_with_aligned_records() -> io::Result<()> {
+        use byteorder::ByteOrder;
+
+        // The contents of the record are irrelevant, only that two do not fit in one block.
+        let record = Record::try_from(vec![0; 40000])?;
+
+        let mut writer = Writer::new(Vec::new()).set_align_records(true);
+        writer.write_record(&record)?;
+        writer.write_record(&record)?;
+        writer.try_finish()?;
+
+        let data = writer.get_ref();
+        let mut uncompressed_block_sizes = Vec::new();
+        let mut i = 0;
+
+        while i < data.len() {
+            // BSIZE is the total block size minus 1.
+            let bsize = LittleEndian::read_u16(&data[i + 16..]) as usize;
+            let block_end = i + bsize + 1;
+            let isize = LittleEndian::read_u32(&data[block_end - 4..]) as usize;
+            uncompressed_block_sizes.push(isize);
+            i = block_end;
+        }
+
+        let expected_len = mem::size_of::<u32>() + record.len();
+        assert_eq!(uncompressed_block_sizes, [expected_len, expected_len, 0]);
+
+        Ok(())
+    }
 }
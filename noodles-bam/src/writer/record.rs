@@ -1,5 +1,7 @@
 use std::{
+    borrow::Cow,
     cmp,
+    convert::TryFrom,
     ffi::CString,
     io::{self, Write},
     mem,
@@ -12,7 +14,7 @@ use noodles_sam::{
     record::{Cigar, Data, QualityScores, Sequence},
 };
 
-use crate::record::sequence::Base;
+use crate::{bai, record::sequence::Base};
 
 // § 4.2 The BAM format (2020-04-30)
 //
@@ -21,11 +23,17 @@ use crate::record::sequence::Base;
 const BLOCK_HEADER_SIZE: usize = 32;
 
 // § 4.2.1 BIN field calculation (2020-04-30)
-const UNMAPPED_BIN: u16 = 4680;
+pub(crate) const UNMAPPED_BIN: u16 = 4680;
 
 // § 4.2.3 SEQ and QUAL encoding (2020-04-30)
 const NULL_QUALITY_SCORE: u8 = 255;
 
+// § 4.2.2 N_CIGAR_OP field (2020-07-19)
+const MAX_CIGAR_OP_COUNT: usize = 65535;
+
+// tag (2) + val_type (1) + subtype (1) + count (4)
+const LONG_CIGAR_FIELD_HEADER_SIZE: usize = 8;
+
 pub fn write_sam_record<W>(
     writer: &mut W,
     reference_sequences: &ReferenceSequences,
@@ -60,9 +68,27 @@ where
 
     let read_name = c_read_name.as_bytes_with_nul();
     let l_read_name = read_name.len() as u8;
-    let n_cigar_op = record.cigar().len() as u16;
     let l_seq = record.sequence().len() as i32;
-    let data_len = calculate_data_len(record.data()) as i32;
+
+    let cigar = record.cigar();
+    let is_long_cigar = cigar.len() > MAX_CIGAR_OP_COUNT;
+
+    // The real CIGAR replaces any existing `CG` data field.
+    let data = if is_long_cigar {
+        Cow::Owned(remove_long_cigar_field(record.data()))
+    } else {
+        Cow::Borrowed(record.data())
+    };
+
+    let (n_cigar_op, data_len) = if is_long_cigar {
+        let long_cigar_field_len =
+            LONG_CIGAR_FIELD_HEADER_SIZE + mem::size_of::<u32>() * cigar.len();
+        (2, calculate_data_len(&data) + long_cigar_field_len)
+    } else {
+        (cigar.len() as u16, calculate_data_len(&data))
+    };
+
+    let data_len = data_len as i32;
 
     let block_size = BLOCK_HEADER_SIZE as i32
         + i32::from(l_read_name)
@@ -78,7 +104,8 @@ where
 
     let pos = record
         .position()
-        .map(|v| i32::from(v) - 1)
+        .map(|v| position_to_i32(v).map(|n| n - 1))
+        .transpose()?
         .unwrap_or(crate::record::UNMAPPED_POSITION);
     writer.write_i32::<LittleEndian>(pos)?;
 
@@ -89,12 +116,13 @@ where
 
     let bin = record
         .position()
-        .map(i32::from)
+        .map(position_to_i32)
+        .transpose()?
         .map(|start| {
             // 0-based, [start, end)
-            let reference_len = record.cigar().reference_len() as i32;
-            let end = start + reference_len;
-            region_to_bin(start, end) as u16
+            let reference_len = i64::from(record.cigar().reference_len());
+            let end = i64::from(start) + reference_len;
+            region_to_bin(i64::from(start), end) as u16
         })
         .unwrap_or(UNMAPPED_BIN);
 
@@ -112,7 +140,8 @@ where
 
     let next_pos = record
         .mate_position()
-        .map(|v| i32::from(v) - 1)
+        .map(|v| position_to_i32(v).map(|n| n - 1))
+        .transpose()?
         .unwrap_or(crate::record::UNMAPPED_POSITION);
     writer.write_i32::<LittleEndian>(next_pos)?;
 
@@ -121,7 +150,11 @@ where
 
     writer.write_all(read_name)?;
 
-    write_cigar(writer, record.cigar())?;
+    if is_long_cigar {
+        write_long_cigar_placeholder(writer, l_seq as u32, cigar.reference_len())?;
+    } else {
+        write_cigar(writer, cigar)?;
+    }
 
     // § 4.2.3 SEQ and QUAL encoding (2020-04-30)
     let sequence = record.sequence();
@@ -153,11 +186,29 @@ where
         }
     }
 
-    write_data(writer, record.data())?;
+    write_data(writer, &data)?;
+
+    if is_long_cigar {
+        write_long_cigar_field(writer, cigar)?;
+    }
 
     Ok(())
 }
 
+// BAM bins cannot represent positions > 2^29.
+fn position_to_i32(position: sam::record::Position) -> io::Result<i32> {
+    let n = i64::from(position);
+
+    if n <= bai::MAX_POSITION {
+        Ok(n as i32)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("position is out of range for BAM: {}", n),
+        ))
+    }
+}
+
 fn write_cigar<W>(writer: &mut W, cigar: &Cigar) -> io::Result<()>
 where
     W: Write,
@@ -172,6 +223,55 @@ where
     Ok(())
 }
 
+// § 4.2.2 N_CIGAR_OP field (2020-07-19)
+//
+// When a CIGAR has more than 65535 operations, the CIGAR field is set to the placeholder
+// `<l_seq>S<reference_len>N`, and the real CIGAR is stored in the `CG` data field.
+fn write_long_cigar_placeholder<W>(
+    writer: &mut W,
+    read_len: u32,
+    reference_len: u32,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    use noodles_sam::record::cigar::{op::Kind, Op};
+
+    let cigar = Cigar::from(vec![
+        Op::new(Kind::SoftClip, read_len),
+        Op::new(Kind::Skip, reference_len),
+    ]);
+
+    write_cigar(writer, &cigar)
+}
+
+pub(crate) fn remove_long_cigar_field(data: &Data) -> Data {
+    use noodles_sam::record::data::field::Tag;
+
+    let fields = data
+        .iter()
+        .filter(|field| field.tag() != &Tag::Cigar)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    Data::from(fields)
+}
+
+fn write_long_cigar_field<W>(writer: &mut W, cigar: &Cigar) -> io::Result<()>
+where
+    W: Write,
+{
+    use noodles_sam::record::data::field::Tag;
+
+    use crate::record::data::field::value::{Subtype, Type};
+
+    writer.write_all(Tag::Cigar.as_ref().as_bytes())?;
+    writer.write_u8(char::from(Type::Array) as u8)?;
+    writer.write_u8(char::from(Subtype::UInt32) as u8)?;
+    writer.write_u32::<LittleEndian>(cigar.len() as u32)?;
+    write_cigar(writer, cigar)
+}
+
 fn write_seq<W>(writer: &mut W, sequence: &Sequence) -> io::Result<()>
 where
     W: Write,
@@ -208,6 +308,8 @@ where
 fn calculate_data_len(data: &Data) -> usize {
     use noodles_sam::record::data::field::Value;
 
+    use crate::record::data::field::Value as BamValue;
+
     let mut len = 0;
 
     for field in data.iter() {
@@ -229,22 +331,14 @@ fn calculate_data_len(data: &Data) -> usize {
             Value::Char(_) => {
                 len += mem::size_of::<u8>();
             }
-            Value::Int32(n) => {
-                if *n >= 0 {
-                    if *n <= i32::from(u8::MAX) {
-                        len += mem::size_of::<u8>();
-                    } else if *n <= i32::from(u16::MAX) {
-                        len += mem::size_of::<u16>();
-                    } else {
-                        len += mem::size_of::<u32>();
-                    }
-                } else if *n >= i32::from(i8::MIN) {
-                    len += mem::size_of::<i8>();
-                } else if *n >= i32::from(i16::MIN) {
-                    len += mem::size_of::<i16>();
-                } else {
-                    len += mem::size_of::<i32>();
-                }
+            Value::Int32(_) | Value::UInt32(_) => {
+                let n = value.as_int().unwrap_or_default();
+
+                len += match BamValue::try_from(n) {
+                    Ok(BamValue::Int8(_)) | Ok(BamValue::UInt8(_)) => mem::size_of::<u8>(),
+                    Ok(BamValue::Int16(_)) | Ok(BamValue::UInt16(_)) => mem::size_of::<u16>(),
+                    _ => mem::size_of::<u32>(),
+                };
             }
             Value::Float(_) => {
                 len += mem::size_of::<f32>();
@@ -290,8 +384,8 @@ where
 
         let value = field.value();
 
-        if let Value::Int32(n) = value {
-            write_data_i32_value(writer, *n)?;
+        if let Some(n) = value.as_int() {
+            write_data_int_value(writer, n)?;
             continue;
         }
 
@@ -305,7 +399,7 @@ where
             Value::Char(c) => {
                 writer.write_u8(*c as u8)?;
             }
-            Value::Int32(_) => unreachable!(),
+            Value::Int32(_) | Value::UInt32(_) => unreachable!(),
             Value::Float(n) => {
                 writer.write_f32::<LittleEndian>(*n)?;
             }
@@ -315,49 +409,49 @@ where
                 writer.write_all(c_str.as_bytes_with_nul())?;
             }
             Value::Int8Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_i8(n)?;
                 }
             }
             Value::UInt8Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_u8(n)?;
                 }
             }
             Value::Int16Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_i16::<LittleEndian>(n)?;
                 }
             }
             Value::UInt16Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_u16::<LittleEndian>(n)?;
                 }
             }
             Value::Int32Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_i32::<LittleEndian>(n)?;
                 }
             }
             Value::UInt32Array(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_u32::<LittleEndian>(n)?;
                 }
             }
             Value::FloatArray(values) => {
-                writer.write_u32::<LittleEndian>(values.len() as u32)?;
+                write_data_array_len(writer, values.len())?;
 
                 for &n in values {
                     writer.write_f32::<LittleEndian>(n)?;
@@ -369,42 +463,42 @@ where
     Ok(())
 }
 
-fn write_data_i32_value<W>(writer: &mut W, n: i32) -> io::Result<()>
+fn write_data_array_len<W>(writer: &mut W, len: usize) -> io::Result<()>
 where
     W: Write,
 {
-    use crate::record::data::field::value::Type;
-
-    if n >= 0 {
-        if n <= i32::from(u8::MAX) {
-            writer.write_u8(char::from(Type::UInt8) as u8)?;
-            writer.write_u8(n as u8)
-        } else if n <= i32::from(u16::MAX) {
-            writer.write_u8(char::from(Type::UInt16) as u8)?;
-            writer.write_u16::<LittleEndian>(n as u16)
-        } else {
-            writer.write_u8(char::from(Type::UInt32) as u8)?;
-            writer.write_u32::<LittleEndian>(n as u32)
-        }
-    } else if n >= i32::from(i8::MIN) {
-        writer.write_u8(char::from(Type::Int8) as u8)?;
-        writer.write_i8(n as i8)
-    } else if n >= i32::from(i16::MIN) {
-        writer.write_u8(char::from(Type::Int16) as u8)?;
-        writer.write_i16::<LittleEndian>(n as i16)
-    } else {
-        writer.write_u8(char::from(Type::Int32) as u8)?;
-        writer.write_i32::<LittleEndian>(n)
+    let count = u32::try_from(len).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    writer.write_u32::<LittleEndian>(count)
+}
+
+fn write_data_int_value<W>(writer: &mut W, n: i64) -> io::Result<()>
+where
+    W: Write,
+{
+    use crate::record::data::field::Value;
+
+    let value = Value::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    writer.write_u8(char::from(value.ty()) as u8)?;
+
+    match value {
+        Value::Int8(m) => writer.write_i8(m),
+        Value::UInt8(m) => writer.write_u8(m),
+        Value::Int16(m) => writer.write_i16::<LittleEndian>(m),
+        Value::UInt16(m) => writer.write_u16::<LittleEndian>(m),
+        Value::Int32(m) => writer.write_i32::<LittleEndian>(m),
+        Value::UInt32(m) => writer.write_u32::<LittleEndian>(m),
+        _ => unreachable!(),
     }
 }
 
 // § 5.3 C source code for computing bin number and overlapping bins (2020-04-30)
 // 0-based, [start, end)
 #[allow(clippy::eq_op)]
-fn region_to_bin(start: i32, mut end: i32) -> i32 {
+pub(crate) fn region_to_bin(start: i64, mut end: i64) -> i32 {
     end -= 1;
 
-    if start >> 14 == end >> 14 {
+    let bin = if start >> 14 == end >> 14 {
         ((1 << 15) - 1) / 7 + (start >> 14)
     } else if start >> 17 == end >> 17 {
         ((1 << 12) - 1) / 7 + (start >> 17)
@@ -416,7 +510,9 @@ fn region_to_bin(start: i32, mut end: i32) -> i32 {
         ((1 << 3) - 1) / 7 + (start >> 26)
     } else {
         0
-    }
+    };
+
+    bin as i32
 }
 
 #[cfg(test)]
@@ -424,10 +520,52 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_write_data_i32_value() -> io::Result<()> {
-        fn t(buf: &mut Vec<u8>, n: i32, expected: &[u8]) -> io::Result<()> {
+    fn test_write_data_with_arrays() -> io::Result<()> {
+        use noodles_sam::record::data::{
+            field::{Tag, Value},
+            Field,
+        };
+
+        let data = Data::from(vec![
+            Field::new(
+                Tag::Other(String::from("X0")),
+                Value::Int8Array(vec![-3, 5]),
+            ),
+            Field::new(Tag::Other(String::from("X1")), Value::UInt8Array(vec![8])),
+            Field::new(Tag::Other(String::from("X2")), Value::Int16Array(vec![-13])),
+            Field::new(Tag::Other(String::from("X3")), Value::UInt16Array(vec![21])),
+            Field::new(Tag::Other(String::from("X4")), Value::Int32Array(vec![-34])),
+            Field::new(Tag::Other(String::from("X5")), Value::UInt32Array(vec![55])),
+            Field::new(Tag::Other(String::from("X6")), Value::FloatArray(vec![0.5])),
+        ]);
+
+        let mut buf = Vec::new();
+        write_data(&mut buf, &data)?;
+
+        let expected = [
+            b'X', b'0', b'B', b'c', 0x02, 0x00, 0x00, 0x00, 0xfd, 0x05, // X0:B:c,-3,5
+            b'X', b'1', b'B', b'C', 0x01, 0x00, 0x00, 0x00, 0x08, // X1:B:C,8
+            b'X', b'2', b'B', b's', 0x01, 0x00, 0x00, 0x00, 0xf3, 0xff, // X2:B:s,-13
+            b'X', b'3', b'B', b'S', 0x01, 0x00, 0x00, 0x00, 0x15, 0x00, // X3:B:S,21
+            b'X', b'4', b'B', b'i', 0x01, 0x00, 0x00, 0x00, 0xde, 0xff, 0xff,
+            0xff, // X4:B:i,-34
+            b'X', b'5', b'B', b'I', 0x01, 0x00, 0x00, 0x00, 0x37, 0x00, 0x00,
+            0x00, // X5:B:I,55
+            b'X', b'6', b'B', b'f', 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x3f, // X6:B:f,0.5
+        ];
+
+        assert_eq!(buf, &expected[..]);
+        assert_eq!(calculate_data_len(&data), expected.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_data_int_value() -> io::Result<()> {
+        fn t(buf: &mut Vec<u8>, n: i64, expected: &[u8]) -> io::Result<()> {
             buf.clear();
-            write_data_i32_value(buf, n)?;
+            write_data_int_value(buf, n)?;
             assert_eq!(&buf[..], expected, "n = {}", n);
             Ok(())
         }
@@ -492,6 +630,38 @@ mod tests {
         t(&mut buf, 2147483646, &[b'I', 0xfe, 0xff, 0xff, 0x7f])?;
         // i32::MAX
         t(&mut buf, 2147483647, &[b'I', 0xff, 0xff, 0xff, 0x7f])?;
+        // i32::MAX + 1
+        t(&mut buf, 2147483648, &[b'I', 0x00, 0x00, 0x00, 0x80])?;
+        // u32::MAX
+        t(&mut buf, 4294967295, &[b'I', 0xff, 0xff, 0xff, 0xff])?;
+
+        // u32::MAX + 1
+        assert!(write_data_int_value(&mut buf, 4294967296).is_err());
+        // i32::MIN - 1
+        assert!(write_data_int_value(&mut buf, -2147483649).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_sam_record_with_out_of_range_position() -> Result<(), Box<dyn std::error::Error>>
+    {
+        use std::convert::TryFrom;
+
+        use noodles_sam::record::Position;
+
+        let reference_sequences = ReferenceSequences::default();
+
+        for n in &[536870913i64, 4294967296] {
+            let record = sam::Record::builder()
+                .set_position(Position::try_from(*n)?)
+                .build();
+
+            let mut buf = Vec::new();
+            let result = write_sam_record(&mut buf, &reference_sequences, &record);
+
+            assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::InvalidInput));
+        }
 
         Ok(())
     }
@@ -0,0 +1,358 @@
+//! Convenience functions to compress and decompress entire streams.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    num::NonZeroUsize,
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use flate2::Compression;
+
+use super::{
+    writer::{deflate_block, BGZF_EOF},
+    Reader,
+};
+
+// The max size of uncompressed data in a block. This matches the block size used by `bgzip`,
+// leaving room for incompressible data to fit in a block after deflation.
+const MAX_UNCOMPRESSED_DATA_LENGTH: usize = 0xff00;
+
+const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+/// Options for compressing a stream as BGZF.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CompressOptions {
+    compression_level: u32,
+    worker_count: NonZeroUsize,
+}
+
+impl CompressOptions {
+    /// Sets the compression level.
+    ///
+    /// The level is clamped to be between 0 (no compression) and 9 (best compression). The
+    /// default is 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let options = bgzf::CompressOptions::default().set_compression_level(9);
+    /// assert_eq!(options.compression_level(), 9);
+    /// ```
+    pub fn set_compression_level(mut self, compression_level: u32) -> Self {
+        self.compression_level = compression_level.min(MAX_COMPRESSION_LEVEL);
+        self
+    }
+
+    /// Sets the number of threads used to compress blocks.
+    ///
+    /// The default is 1, i.e., blocks are compressed on the calling thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let worker_count = NonZeroUsize::new(4).unwrap();
+    /// let options = bgzf::CompressOptions::default().set_worker_count(worker_count);
+    ///
+    /// assert_eq!(options.worker_count(), worker_count);
+    /// ```
+    pub fn set_worker_count(mut self, worker_count: NonZeroUsize) -> Self {
+        self.worker_count = worker_count;
+        self
+    }
+
+    /// Returns the compression level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let options = bgzf::CompressOptions::default();
+    /// assert_eq!(options.compression_level(), 6);
+    /// ```
+    pub fn compression_level(&self) -> u32 {
+        self.compression_level
+    }
+
+    /// Returns the number of threads used to compress blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let options = bgzf::CompressOptions::default();
+    /// assert_eq!(options.worker_count().get(), 1);
+    /// ```
+    pub fn worker_count(&self) -> NonZeroUsize {
+        self.worker_count
+    }
+}
+
+impl Default for CompressOptions {
+    fn default() -> Self {
+        Self {
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            worker_count: NonZeroUsize::new(1).unwrap(),
+        }
+    }
+}
+
+/// Compresses an entire stream as BGZF.
+///
+/// The input is read until EOF and written as a list of BGZF blocks, followed by the BGZF EOF
+/// block. This is similar to `bgzip --stdout`. The output can subsequently be indexed, e.g., by
+/// tabix.
+///
+/// With more than one worker (see [`CompressOptions::set_worker_count`]), the workers are started
+/// once and compress blocks as they are read.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bgzf as bgzf;
+///
+/// let data = b"noodles-bgzf";
+///
+/// let mut compressed_data = Vec::new();
+/// bgzf::compress(&mut &data[..], &mut compressed_data, &bgzf::CompressOptions::default())?;
+///
+/// let mut decompressed_data = Vec::new();
+/// bgzf::decompress(&mut &compressed_data[..], &mut decompressed_data)?;
+///
+/// assert_eq!(decompressed_data, data);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn compress<R, W>(reader: &mut R, writer: &mut W, options: &CompressOptions) -> io::Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let compression = Compression::new(options.compression_level());
+    let worker_count = options.worker_count().get();
+
+    if worker_count == 1 {
+        let mut buf = Vec::with_capacity(MAX_UNCOMPRESSED_DATA_LENGTH);
+
+        while read_block_data(reader, &mut buf)? > 0 {
+            let block = deflate_block(&buf, compression)?;
+            writer.write_all(&block)?;
+        }
+    } else {
+        compress_with_workers(reader, writer, compression, worker_count)?;
+    }
+
+    writer.write_all(BGZF_EOF)
+}
+
+type DeflateJob = (Vec<u8>, SyncSender<io::Result<Vec<u8>>>);
+
+// Blocks are compressed by a fixed pool of worker threads that receive jobs from a shared queue.
+// Each job carries its own result channel, which is kept in read order so that blocks are written
+// in the same order they were read.
+fn compress_with_workers<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    compression: Compression,
+    worker_count: usize,
+) -> io::Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let (job_tx, job_rx) = mpsc::sync_channel(worker_count);
+    let job_rx = Arc::new(Mutex::new(job_rx));
+
+    let handles: Vec<_> = (0..worker_count)
+        .map(|_| {
+            let job_rx = Arc::clone(&job_rx);
+            thread::spawn(move || deflate_blocks(&job_rx, compression))
+        })
+        .collect();
+
+    // The job queue is closed when `job_tx` is dropped, which stops the workers.
+    let result = write_blocks(reader, writer, job_tx, worker_count);
+
+    for handle in handles {
+        handle.join().map_err(|_| worker_panicked_error())?;
+    }
+
+    result
+}
+
+fn deflate_blocks(job_rx: &Mutex<Receiver<DeflateJob>>, compression: Compression) {
+    loop {
+        let job = match job_rx.lock() {
+            Ok(rx) => rx.recv(),
+            Err(_) => return,
+        };
+
+        match job {
+            Ok((buf, result_tx)) => {
+                let _ = result_tx.send(deflate_block(&buf, compression));
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+fn write_blocks<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    job_tx: SyncSender<DeflateJob>,
+    worker_count: usize,
+) -> io::Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let mut pending = VecDeque::with_capacity(worker_count + 1);
+
+    loop {
+        let mut buf = Vec::with_capacity(MAX_UNCOMPRESSED_DATA_LENGTH);
+
+        if read_block_data(reader, &mut buf)? == 0 {
+            break;
+        }
+
+        let (result_tx, result_rx) = mpsc::sync_channel(1);
+        job_tx
+            .send((buf, result_tx))
+            .map_err(|_| worker_panicked_error())?;
+        pending.push_back(result_rx);
+
+        if pending.len() > worker_count {
+            write_next_block(writer, &mut pending)?;
+        }
+    }
+
+    while !pending.is_empty() {
+        write_next_block(writer, &mut pending)?;
+    }
+
+    Ok(())
+}
+
+fn write_next_block<W>(
+    writer: &mut W,
+    pending: &mut VecDeque<Receiver<io::Result<Vec<u8>>>>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    if let Some(result_rx) = pending.pop_front() {
+        let block = result_rx.recv().map_err(|_| worker_panicked_error())??;
+        writer.write_all(&block)?;
+    }
+
+    Ok(())
+}
+
+fn worker_panicked_error() -> io::Error {
+    io::Error::other("compression worker panicked")
+}
+
+/// Decompresses an entire BGZF stream.
+///
+/// This is similar to `bgzip --decompress --stdout`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_bgzf as bgzf;
+///
+/// let mut reader = File::open("sample.vcf.gz")?;
+/// let mut writer = File::create("sample.vcf")?;
+/// bgzf::decompress(&mut reader, &mut writer)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn decompress<R, W>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+where
+    R: Read,
+    W: Write,
+{
+    let mut reader = Reader::new(reader);
+    io::copy(&mut reader, writer)
+}
+
+fn read_block_data<R>(reader: &mut R, buf: &mut Vec<u8>) -> io::Result<usize>
+where
+    R: Read,
+{
+    buf.clear();
+    reader
+        .by_ref()
+        .take(MAX_UNCOMPRESSED_DATA_LENGTH as u64)
+        .read_to_end(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_data() -> Vec<u8> {
+        (0..3 * MAX_UNCOMPRESSED_DATA_LENGTH + 8)
+            .map(|i| (i % 251) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_compress() -> io::Result<()> {
+        let data = build_data();
+
+        let mut compressed_data = Vec::new();
+        compress(
+            &mut &data[..],
+            &mut compressed_data,
+            &CompressOptions::default(),
+        )?;
+
+        let eof_start = compressed_data.len() - BGZF_EOF.len();
+        assert_eq!(&compressed_data[eof_start..], BGZF_EOF);
+
+        let mut actual = Vec::new();
+        decompress(&mut &compressed_data[..], &mut actual)?;
+
+        assert_eq!(actual, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_multiple_workers() -> io::Result<()> {
+        let data = build_data();
+
+        let mut expected = Vec::new();
+        compress(&mut &data[..], &mut expected, &CompressOptions::default())?;
+
+        let options = CompressOptions::default().set_worker_count(NonZeroUsize::new(2).unwrap());
+        let mut actual = Vec::new();
+        compress(&mut &data[..], &mut actual, &options)?;
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_empty_input() -> io::Result<()> {
+        let mut compressed_data = Vec::new();
+        compress(
+            &mut io::empty(),
+            &mut compressed_data,
+            &CompressOptions::default(),
+        )?;
+        assert_eq!(compressed_data, BGZF_EOF);
+        Ok(())
+    }
+}
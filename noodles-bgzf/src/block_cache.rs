@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+#[derive(Debug)]
+struct Entry {
+    len: u64,
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+/// A least recently used (LRU) cache of uncompressed BGZF blocks.
+///
+/// Blocks are keyed by their position in the compressed stream.
+#[derive(Debug)]
+pub(crate) struct BlockCache {
+    capacity: usize,
+    entries: HashMap<u64, Entry>,
+    tick: u64,
+}
+
+impl BlockCache {
+    /// Creates a block cache that holds up to `capacity` blocks.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            tick: 0,
+        }
+    }
+
+    /// Returns the number of cached blocks.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the compressed length and uncompressed data of the block at the given position.
+    pub fn get(&mut self, position: u64) -> Option<(u64, &[u8])> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        self.entries.get_mut(&position).map(|entry| {
+            entry.last_used = tick;
+            (entry.len, &entry.data[..])
+        })
+    }
+
+    /// Adds a block, evicting the least recently used block if the cache is full.
+    pub fn insert(&mut self, position: u64, len: u64, data: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+
+        if !self.entries.contains_key(&position) && self.entries.len() >= self.capacity {
+            let lru_position = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(&position, _)| position);
+
+            if let Some(lru_position) = lru_position {
+                self.entries.remove(&lru_position);
+            }
+        }
+
+        self.entries.insert(
+            position,
+            Entry {
+                len,
+                data,
+                last_used: self.tick,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut cache = BlockCache::new(2);
+
+        cache.insert(0, 8, b"noodles".to_vec());
+        cache.insert(8, 5, b"-bgzf".to_vec());
+        assert_eq!(cache.len(), 2);
+
+        // Make the block at 8 the least recently used.
+        assert_eq!(cache.get(0), Some((8, &b"noodles"[..])));
+
+        cache.insert(13, 3, b"sam".to_vec());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(8).is_none());
+        assert_eq!(cache.get(0), Some((8, &b"noodles"[..])));
+        assert_eq!(cache.get(13), Some((3, &b"sam"[..])));
+    }
+
+    #[test]
+    fn test_insert_with_no_capacity() {
+        let mut cache = BlockCache::new(0);
+        cache.insert(0, 8, b"noodles".to_vec());
+        assert_eq!(cache.len(), 0);
+        assert!(cache.get(0).is_none());
+    }
+}
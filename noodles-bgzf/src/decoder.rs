@@ -0,0 +1,334 @@
+//! Push-based BGZF decoder.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read},
+};
+
+use byteorder::{ByteOrder, LittleEndian};
+use flate2::read::DeflateDecoder;
+
+use super::{gz, VirtualPosition, BGZF_HEADER_SIZE};
+
+// SI1 (1) + SI2 (1)
+const BGZF_SUBFIELD_ID: [u8; 2] = [b'B', b'C'];
+
+/// A push-based BGZF decoder.
+///
+/// Unlike [`crate::Reader`], the decoder does not pull data from a [`std::io::Read`] source.
+/// Compressed data is instead pushed as it becomes available, e.g., as chunks from a network
+/// stream, and blocks are decoded once they are complete. Chunks do not need to be aligned to
+/// block boundaries.
+///
+/// Like the reader, CRC32 validation is disabled when decompressing data.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io::{self, Write};
+/// use noodles_bgzf as bgzf;
+///
+/// let mut writer = bgzf::Writer::new(Vec::new());
+/// writer.write_all(b"noodles")?;
+/// let data = writer.finish()?;
+///
+/// let mut decoder = bgzf::Decoder::new();
+/// let mut buf = Vec::new();
+///
+/// for chunk in data.chunks(8) {
+///     decoder.push(chunk);
+///
+///     while let Some(block) = decoder.decode()? {
+///         buf.extend_from_slice(block.data());
+///     }
+/// }
+///
+/// decoder.finish()?;
+///
+/// assert_eq!(buf, b"noodles");
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buf: Vec<u8>,
+    position: u64,
+}
+
+impl Decoder {
+    /// Creates a push-based BGZF decoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let decoder = bgzf::Decoder::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the position of the next block in the compressed stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let decoder = bgzf::Decoder::new();
+    /// assert_eq!(decoder.position(), 0);
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the number of compressed bytes that are buffered but not yet decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut decoder = bgzf::Decoder::new();
+    /// decoder.push(&[0x1f, 0x8b]);
+    ///
+    /// assert_eq!(decoder.buffered_len(), 2);
+    /// ```
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Adds compressed data to the decoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let mut decoder = bgzf::Decoder::new();
+    /// decoder.push(&[0x1f, 0x8b]);
+    /// ```
+    pub fn push(&mut self, src: &[u8]) {
+        self.buf.extend_from_slice(src);
+    }
+
+    /// Decodes the next block.
+    ///
+    /// This returns `None` if the buffered data does not yet contain a complete block. Otherwise,
+    /// the block is removed from the buffer and its uncompressed data is returned. An empty block,
+    /// e.g., the BGZF EOF marker, returns a block with no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// writer.write_all(b"noodles")?;
+    /// let data = writer.finish()?;
+    ///
+    /// let mut decoder = bgzf::Decoder::new();
+    /// decoder.push(&data[..8]);
+    /// assert!(decoder.decode()?.is_none());
+    ///
+    /// decoder.push(&data[8..]);
+    /// let block = decoder.decode()?.expect("missing block");
+    /// assert_eq!(block.position(), 0);
+    /// assert_eq!(block.data(), b"noodles");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn decode(&mut self) -> io::Result<Option<Block>> {
+        if self.buf.len() < BGZF_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let block_size = read_block_size(&self.buf[..BGZF_HEADER_SIZE])?;
+
+        if self.buf.len() < block_size {
+            return Ok(None);
+        }
+
+        let data = inflate_block(&self.buf[..block_size])?;
+        self.buf.drain(..block_size);
+
+        let block = Block {
+            position: self.position,
+            len: block_size,
+            data,
+        };
+
+        self.position += block_size as u64;
+
+        Ok(Some(block))
+    }
+
+    /// Checks that all buffered data was decoded.
+    ///
+    /// This should be called after the last chunk of compressed data is pushed and decoded. It
+    /// returns an error if a partial block remains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut decoder = bgzf::Decoder::new();
+    /// assert!(decoder.finish().is_ok());
+    ///
+    /// decoder.push(&[0x1f, 0x8b]);
+    /// assert!(decoder.finish().is_err());
+    /// ```
+    pub fn finish(&self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete BGZF block",
+            ))
+        }
+    }
+}
+
+/// A decoded BGZF block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    position: u64,
+    len: usize,
+    data: Vec<u8>,
+}
+
+impl Block {
+    /// Returns the position of the block in the compressed stream.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the size of the compressed block.
+    pub fn compressed_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the block has no uncompressed data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the virtual position of the start of the block.
+    pub fn virtual_position(&self) -> VirtualPosition {
+        VirtualPosition::try_from((self.position, 0)).unwrap_or_default()
+    }
+
+    /// Returns the uncompressed data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the uncompressed data, consuming the block.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+fn read_block_size(header: &[u8]) -> io::Result<usize> {
+    if header[..2] != gz::MAGIC_NUMBER || header[12..14] != BGZF_SUBFIELD_ID {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid BGZF header",
+        ));
+    }
+
+    let bsize = &header[16..18];
+
+    // Add 1 because BSIZE is "total Block SIZE minus 1".
+    let block_size = usize::from(LittleEndian::read_u16(bsize)) + 1;
+
+    if block_size < BGZF_HEADER_SIZE + gz::TRAILER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid BGZF block size",
+        ));
+    }
+
+    Ok(block_size)
+}
+
+fn inflate_block(src: &[u8]) -> io::Result<Vec<u8>> {
+    let trailer_start = src.len() - gz::TRAILER_SIZE;
+    let cdata = &src[BGZF_HEADER_SIZE..trailer_start];
+    let isize = LittleEndian::read_u32(&src[trailer_start + 4..]) as usize;
+
+    let mut data = Vec::with_capacity(isize);
+    DeflateDecoder::new(cdata).read_to_end(&mut data)?;
+
+    if data.len() != isize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "BGZF block size mismatch: expected {}, got {}",
+                isize,
+                data.len()
+            ),
+        ));
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::Writer;
+
+    #[test]
+    fn test_decode() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        writer.write_all(b"-")?;
+        writer.flush()?;
+        writer.write_all(b"bgzf")?;
+        let data = writer.finish()?;
+
+        let mut decoder = Decoder::new();
+        let mut blocks = Vec::new();
+
+        for chunk in data.chunks(5) {
+            decoder.push(chunk);
+
+            while let Some(block) = decoder.decode()? {
+                blocks.push(block);
+            }
+        }
+
+        decoder.finish()?;
+
+        // 3 data blocks + EOF block
+        assert_eq!(blocks.len(), 4);
+
+        let actual: Vec<u8> = blocks.iter().flat_map(|b| b.data().to_vec()).collect();
+        assert_eq!(actual, b"noodles-bgzf");
+
+        assert_eq!(blocks[0].position(), 0);
+        assert_eq!(blocks[1].position(), blocks[0].compressed_len() as u64);
+        assert_eq!(
+            blocks[1].virtual_position(),
+            VirtualPosition::try_from((blocks[0].compressed_len() as u64, 0)).unwrap()
+        );
+        assert!(blocks[3].is_empty());
+        assert_eq!(decoder.position(), data.len() as u64);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_invalid_header() {
+        let mut decoder = Decoder::new();
+        decoder.push(&[0; BGZF_HEADER_SIZE]);
+
+        assert!(matches!(
+            decoder.decode(),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
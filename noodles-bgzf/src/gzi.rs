@@ -0,0 +1,86 @@
+//! gzip index (GZI).
+//!
+//! A gzip index (GZI) maps uncompressed positions to compressed positions in a BGZF file. It
+//! allows seeking to an uncompressed position, e.g., to read a bgzipped FASTA using the offsets
+//! in its FASTA index (FAI).
+//!
+//! The index is a list of `(compressed position, uncompressed position)` pairs, one for the start
+//! of each block, excluding the first block, which always starts at `(0, 0)`.
+//!
+//! # Examples
+//!
+//! ## Reading a gzip index
+//!
+//! ```no_run
+//! # use std::io;
+//! use noodles_bgzf::gzi;
+//! let index = gzi::read("reference.fa.gz.gzi")?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+mod reader;
+
+pub use self::reader::Reader;
+
+use std::{fs::File, io, path::Path};
+
+/// A gzip index.
+///
+/// Each entry is a `(compressed position, uncompressed position)` pair of the start of a block.
+pub type Index = Vec<(u64, u64)>;
+
+/// Reads the entire contents of a gzip index.
+///
+/// This is a convenience function and is equivalent to opening the file at the given path and
+/// reading the index.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// use noodles_bgzf::gzi;
+/// let index = gzi::read("reference.fa.gz.gzi")?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read<P>(src: P) -> io::Result<Index>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = File::open(src).map(Reader::new)?;
+    reader.read_index()
+}
+
+// Returns the block that contains the given uncompressed position.
+//
+// This is the `(compressed position, uncompressed position)` pair of the block start.
+pub(crate) fn find_block(index: &[(u64, u64)], pos: u64) -> (u64, u64) {
+    let i = match index.binary_search_by_key(&pos, |&(_, uncompressed_pos)| uncompressed_pos) {
+        Ok(i) => i + 1,
+        Err(i) => i,
+    };
+
+    if i == 0 {
+        (0, 0)
+    } else {
+        index[i - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_block() {
+        let index = vec![(32, 65280), (64, 130560)];
+
+        assert_eq!(find_block(&index, 0), (0, 0));
+        assert_eq!(find_block(&index, 65279), (0, 0));
+        assert_eq!(find_block(&index, 65280), (32, 65280));
+        assert_eq!(find_block(&index, 130559), (32, 65280));
+        assert_eq!(find_block(&index, 130560), (64, 130560));
+        assert_eq!(find_block(&index, 200000), (64, 130560));
+
+        assert_eq!(find_block(&[], 8), (0, 0));
+    }
+}
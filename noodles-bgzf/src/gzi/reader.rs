@@ -0,0 +1,81 @@
+use std::io::{self, Read};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use super::Index;
+
+/// A gzip index (GZI) reader.
+pub struct Reader<R> {
+    inner: R,
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Creates a gzip index reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf::gzi;
+    /// let data = [0; 8];
+    /// let reader = gzi::Reader::new(&data[..]);
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Reads a gzip index.
+    ///
+    /// The position of the stream is expected to be at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf::gzi;
+    ///
+    /// let data = [
+    ///     0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // number_entries = 1
+    ///     0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // compressed_offset = 32
+    ///     0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // uncompressed_offset = 65280
+    /// ];
+    /// let mut reader = gzi::Reader::new(&data[..]);
+    ///
+    /// let index = reader.read_index()?;
+    /// assert_eq!(index, [(32, 65280)]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_index(&mut self) -> io::Result<Index> {
+        let len = self.inner.read_u64::<LittleEndian>()?;
+
+        let mut index = Vec::with_capacity(len as usize);
+
+        for _ in 0..len {
+            let compressed_pos = self.inner.read_u64::<LittleEndian>()?;
+            let uncompressed_pos = self.inner.read_u64::<LittleEndian>()?;
+            index.push((compressed_pos, uncompressed_pos));
+        }
+
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_index() -> io::Result<()> {
+        let data = [0; 8];
+        let mut reader = Reader::new(&data[..]);
+        assert!(reader.read_index()?.is_empty());
+
+        let data = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let mut reader = Reader::new(&data[..]);
+        assert!(reader.read_index().is_err());
+
+        Ok(())
+    }
+}
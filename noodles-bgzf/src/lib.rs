@@ -33,14 +33,79 @@
 //! writer.write_all(b"noodles-bgzf")?;
 //! # Ok::<(), io::Error>(())
 //! ```
+//!
+//! ## Read a remote BGZF file
+//!
+//! Any source that can read byte ranges can be read using a [`RangeReader`]. With the `http`
+//! feature, `HttpFile` reads files using HTTP range requests, fetching only the blocks that are
+//! needed.
+//!
+//! ```ignore
+//! # use std::io::{self, Read};
+//! use noodles_bgzf::{self as bgzf, HttpFile, RangeReader};
+//! let file = HttpFile::new("https://example.com/data.gz")?;
+//! let mut reader = bgzf::Reader::new(RangeReader::new(file));
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data)?;
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Compress an entire file
+//!
+//! ```no_run
+//! # use std::{fs::File, io};
+//! use noodles_bgzf as bgzf;
+//! let mut reader = File::open("sample.vcf")?;
+//! let mut writer = File::create("sample.vcf.gz")?;
+//! bgzf::compress(&mut reader, &mut writer, &bgzf::CompressOptions::default())?;
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Decode chunks of compressed data
+//!
+//! When compressed data arrives in chunks, e.g., from a network stream, a [`Decoder`] decodes
+//! blocks as they become complete, without needing a [`std::io::Read`] source.
+//!
+//! ```no_run
+//! # use std::io;
+//! use noodles_bgzf as bgzf;
+//! # let chunks: Vec<Vec<u8>> = Vec::new();
+//! let mut decoder = bgzf::Decoder::new();
+//!
+//! for chunk in chunks {
+//!     decoder.push(&chunk);
+//!
+//!     while let Some(block) = decoder.decode()? {
+//!         println!("{}", block.data().len());
+//!     }
+//! }
+//!
+//! decoder.finish()?;
+//! # Ok::<(), io::Error>(())
+//! ```
 
+mod bgzip;
 mod block;
+mod block_cache;
+pub mod decoder;
 mod gz;
+pub mod gzi;
+mod line_reader;
+mod metrics;
+mod range_reader;
 mod reader;
 pub mod virtual_position;
 mod writer;
 
-pub use self::{reader::Reader, virtual_position::VirtualPosition, writer::Writer};
+pub use self::{
+    bgzip::{compress, decompress, CompressOptions},
+    decoder::Decoder,
+    line_reader::LineReader,
+    range_reader::{RangeRead, RangeReader},
+    reader::Reader,
+    virtual_position::VirtualPosition,
+    writer::Writer,
+};
 
 use self::block::Block;
 
@@ -52,6 +117,12 @@ const BGZF_XLEN: usize = 6;
 
 pub(crate) const BGZF_HEADER_SIZE: usize = gz::HEADER_SIZE + GZIP_XLEN_SIZE + BGZF_XLEN;
 
+#[cfg(feature = "http")]
+pub use self::range_reader::HttpFile;
+
+#[cfg(feature = "metrics")]
+pub use self::metrics::Metrics;
+
 #[cfg(test)]
 mod tests {
     use std::io::{self, Read, Write};
@@ -0,0 +1,151 @@
+use std::io::{self, BufRead, Read};
+
+use super::{Reader, VirtualPosition};
+
+const LINE_FEED: char = '\n';
+const CARRIAGE_RETURN: char = '\r';
+
+/// A BGZF line reader.
+///
+/// This reads lines from a BGZF stream and reports the virtual position of the start of each line,
+/// e.g., to build an index of a bgzipped text file.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io::{self, Write};
+/// use noodles_bgzf as bgzf;
+///
+/// let mut writer = bgzf::Writer::new(Vec::new());
+/// writer.write_all(b"noodles\nbgzf\n")?;
+/// let data = writer.finish()?;
+///
+/// let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+/// let mut buf = String::new();
+///
+/// let virtual_position = reader.read_line(&mut buf)?;
+/// assert_eq!(virtual_position, Some(bgzf::VirtualPosition::from(0)));
+/// assert_eq!(buf, "noodles");
+///
+/// let virtual_position = reader.read_line(&mut buf)?;
+/// assert_eq!(virtual_position, Some(bgzf::VirtualPosition::from(8)));
+/// assert_eq!(buf, "bgzf");
+///
+/// assert!(reader.read_line(&mut buf)?.is_none());
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct LineReader<R> {
+    inner: Reader<R>,
+}
+
+impl<R> LineReader<R>
+where
+    R: Read,
+{
+    /// Creates a BGZF line reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+    /// ```
+    pub fn new(inner: Reader<R>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &Reader<R> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// This can be used to seek the underlying reader.
+    pub fn get_mut(&mut self) -> &mut Reader<R> {
+        &mut self.inner
+    }
+
+    /// Unwraps and returns the underlying reader.
+    pub fn into_inner(self) -> Reader<R> {
+        self.inner
+    }
+
+    /// Returns the current virtual position of the stream.
+    ///
+    /// After a line is read, this is the virtual position of the end of the line, including the
+    /// line ending.
+    pub fn virtual_position(&self) -> VirtualPosition {
+        self.inner.virtual_position()
+    }
+
+    /// Reads a line and returns the virtual position of its start.
+    ///
+    /// The given buffer is cleared, and the line is read into it without the line ending (`\n` or
+    /// `\r\n`). This returns `None` at the end of the stream.
+    pub fn read_line(&mut self, buf: &mut String) -> io::Result<Option<VirtualPosition>> {
+        buf.clear();
+
+        // The stream is filled first so that the virtual position of a line starting at a block
+        // boundary refers to the start of the next block.
+        if self.inner.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        let virtual_position = self.inner.virtual_position();
+
+        match self.inner.read_line(buf)? {
+            0 => Ok(None),
+            _ => {
+                if buf.ends_with(LINE_FEED) {
+                    buf.pop();
+
+                    if buf.ends_with(CARRIAGE_RETURN) {
+                        buf.pop();
+                    }
+                }
+
+                Ok(Some(virtual_position))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, io::Write};
+
+    use crate::Writer;
+
+    use super::*;
+
+    #[test]
+    fn test_read_line() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles\r\n")?;
+        writer.flush()?;
+        let second_block_position = writer.get_ref().len() as u64;
+        writer.write_all(b"bgzf\nvcf")?;
+        let data = writer.finish()?;
+
+        let mut reader = LineReader::new(Reader::new(&data[..]));
+        let mut buf = String::new();
+
+        assert_eq!(reader.read_line(&mut buf)?, Some(VirtualPosition::from(0)));
+        assert_eq!(buf, "noodles");
+
+        let expected = VirtualPosition::try_from((second_block_position, 0)).unwrap();
+        assert_eq!(reader.read_line(&mut buf)?, Some(expected));
+        assert_eq!(buf, "bgzf");
+
+        let expected = VirtualPosition::try_from((second_block_position, 5)).unwrap();
+        assert_eq!(reader.read_line(&mut buf)?, Some(expected));
+        assert_eq!(buf, "vcf");
+
+        assert!(reader.read_line(&mut buf)?.is_none());
+        assert!(buf.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! BGZF reader and writer metrics.
+//!
+//! Metrics are only collected when the `metrics` feature is enabled. Otherwise, `Metrics` is a
+//! no-op, and readers and writers do not time their codecs.
+
+#[cfg(feature = "metrics")]
+use std::time::{Duration, Instant};
+
+/// Counters collected by a BGZF reader or writer.
+///
+/// For a reader, blocks are the blocks read and decompressed, and the codec time is the time
+/// spent inflating. For a writer, blocks are the blocks compressed and written, and the codec time
+/// is the time spent deflating.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io::{self, Read, Write};
+/// use noodles_bgzf as bgzf;
+///
+/// let mut writer = bgzf::Writer::new(Vec::new());
+/// writer.write_all(b"noodles")?;
+/// writer.flush()?;
+/// assert_eq!(writer.metrics().blocks(), 1);
+///
+/// let data = writer.finish()?;
+///
+/// let mut reader = bgzf::Reader::new(&data[..]);
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf)?;
+///
+/// let metrics = reader.metrics();
+/// assert_eq!(metrics.blocks(), 2); // data block + EOF block
+/// assert_eq!(metrics.uncompressed_bytes(), 7);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Metrics {
+    blocks: u64,
+    compressed_bytes: u64,
+    uncompressed_bytes: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+    codec_time: Duration,
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    /// Returns the number of blocks decompressed or compressed, including the BGZF EOF block.
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Returns the number of compressed bytes read or written, including block headers and
+    /// trailers.
+    pub fn compressed_bytes(&self) -> u64 {
+        self.compressed_bytes
+    }
+
+    /// Returns the number of uncompressed bytes decompressed or compressed.
+    pub fn uncompressed_bytes(&self) -> u64 {
+        self.uncompressed_bytes
+    }
+
+    /// Returns the number of block cache hits when seeking.
+    ///
+    /// This is always 0 for a reader without a block cache and for a writer.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits
+    }
+
+    /// Returns the number of block cache misses when seeking.
+    ///
+    /// This is always 0 for a reader without a block cache and for a writer.
+    pub fn cache_misses(&self) -> u64 {
+        self.cache_misses
+    }
+
+    /// Returns the total time spent inflating or deflating block data.
+    pub fn codec_time(&self) -> Duration {
+        self.codec_time
+    }
+
+    pub(crate) fn add_block(&mut self, compressed_len: usize, uncompressed_len: usize) {
+        self.blocks += 1;
+        self.compressed_bytes += compressed_len as u64;
+        self.uncompressed_bytes += uncompressed_len as u64;
+    }
+
+    pub(crate) fn add_cache_hit(&mut self) {
+        self.cache_hits += 1;
+    }
+
+    pub(crate) fn add_cache_miss(&mut self) {
+        self.cache_misses += 1;
+    }
+
+    pub(crate) fn time_codec<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let result = f();
+        self.codec_time += start.elapsed();
+        result
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Metrics {}
+
+#[cfg(not(feature = "metrics"))]
+impl Metrics {
+    pub(crate) fn add_block(&mut self, _: usize, _: usize) {}
+
+    pub(crate) fn add_cache_hit(&mut self) {}
+
+    pub(crate) fn add_cache_miss(&mut self) {}
+
+    pub(crate) fn time_codec<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        f()
+    }
+}
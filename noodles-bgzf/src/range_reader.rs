@@ -0,0 +1,293 @@
+#[cfg(feature = "http")]
+mod http;
+
+#[cfg(feature = "http")]
+pub use self::http::HttpFile;
+
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+};
+
+// 256 KiB
+const DEFAULT_CHUNK_SIZE: usize = 1 << 18;
+
+/// A source that can read byte ranges, e.g., a remote file.
+pub trait RangeRead {
+    /// Reads the given byte range.
+    ///
+    /// The returned data may be shorter than the range if the range extends past the end of the
+    /// source. An empty buffer indicates the end of the source.
+    fn read_range(&mut self, range: Range<u64>) -> io::Result<Vec<u8>>;
+}
+
+/// A reader over a source that reads byte ranges.
+///
+/// This implements [`std::io::Read`] and [`std::io::Seek`] by fetching fixed-size chunks from the
+/// source as needed, so it can be wrapped by a [`crate::Reader`] for random access, e.g., over
+/// HTTP or object storage. Only the chunks that contain the requested data are fetched.
+///
+/// Seeking relative to the end of the source is not supported.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{io::{self, Read}, ops::Range};
+/// use noodles_bgzf::{self as bgzf, RangeRead, RangeReader};
+///
+/// struct Source(Vec<u8>);
+///
+/// impl RangeRead for Source {
+///     fn read_range(&mut self, range: Range<u64>) -> io::Result<Vec<u8>> {
+///         let start = (range.start as usize).min(self.0.len());
+///         let end = (range.end as usize).min(self.0.len());
+///         Ok(self.0[start..end].to_vec())
+///     }
+/// }
+///
+/// let data = bgzf::Writer::new(Vec::new()).finish()?;
+/// let mut reader = bgzf::Reader::new(RangeReader::new(Source(data)));
+///
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf)?;
+/// assert!(buf.is_empty());
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct RangeReader<T> {
+    inner: T,
+    position: u64,
+    buf: Vec<u8>,
+    buf_start: u64,
+    chunk_size: usize,
+}
+
+impl<T> RangeReader<T>
+where
+    T: RangeRead,
+{
+    /// Creates a range reader with a default chunk size of 256 KiB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{io, ops::Range};
+    /// use noodles_bgzf::{RangeRead, RangeReader};
+    ///
+    /// struct Empty;
+    ///
+    /// impl RangeRead for Empty {
+    ///     fn read_range(&mut self, _: Range<u64>) -> io::Result<Vec<u8>> {
+    ///         Ok(Vec::new())
+    ///     }
+    /// }
+    ///
+    /// let reader = RangeReader::new(Empty);
+    /// ```
+    pub fn new(inner: T) -> Self {
+        Self::with_chunk_size(inner, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a range reader with the given chunk size.
+    ///
+    /// The chunk size is the number of bytes fetched from the source at a time. It is at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{io, ops::Range};
+    /// use noodles_bgzf::{RangeRead, RangeReader};
+    ///
+    /// struct Empty;
+    ///
+    /// impl RangeRead for Empty {
+    ///     fn read_range(&mut self, _: Range<u64>) -> io::Result<Vec<u8>> {
+    ///         Ok(Vec::new())
+    ///     }
+    /// }
+    ///
+    /// let reader = RangeReader::with_chunk_size(Empty, 1 << 20);
+    /// ```
+    pub fn with_chunk_size(inner: T, chunk_size: usize) -> Self {
+        Self {
+            inner,
+            position: 0,
+            buf: Vec::new(),
+            buf_start: 0,
+            chunk_size: chunk_size.max(1),
+        }
+    }
+
+    /// Returns a reference to the underlying source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{io, ops::Range};
+    /// use noodles_bgzf::{RangeRead, RangeReader};
+    ///
+    /// struct Empty;
+    ///
+    /// impl RangeRead for Empty {
+    ///     fn read_range(&mut self, _: Range<u64>) -> io::Result<Vec<u8>> {
+    ///         Ok(Vec::new())
+    ///     }
+    /// }
+    ///
+    /// let reader = RangeReader::new(Empty);
+    /// let _source = reader.get_ref();
+    /// ```
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    fn buf_end(&self) -> u64 {
+        self.buf_start + self.buf.len() as u64
+    }
+
+    fn fill_buf(&mut self) -> io::Result<()> {
+        if self.position >= self.buf_start && self.position < self.buf_end() {
+            return Ok(());
+        }
+
+        let end = self.position.saturating_add(self.chunk_size as u64);
+        self.buf = self.inner.read_range(self.position..end)?;
+        self.buf_start = self.position;
+
+        Ok(())
+    }
+}
+
+impl<T> Read for RangeReader<T>
+where
+    T: RangeRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.fill_buf()?;
+
+        if self.position >= self.buf_end() {
+            return Ok(0);
+        }
+
+        let i = (self.position - self.buf_start) as usize;
+        let src = &self.buf[i..];
+
+        let n = src.len().min(buf.len());
+        buf[..n].copy_from_slice(&src[..n]);
+
+        self.position += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl<T> Seek for RangeReader<T>
+where
+    T: RangeRead,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::Current(n) => {
+                if n >= 0 {
+                    self.position.checked_add(n as u64)
+                } else {
+                    self.position.checked_sub(n.wrapping_neg() as u64)
+                }
+            }
+            SeekFrom::End(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "seeking from the end is not supported",
+                ))
+            }
+        };
+
+        match position {
+            Some(n) => {
+                self.position = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, io::Write};
+
+    use crate::{Reader, VirtualPosition, Writer};
+
+    use super::*;
+
+    struct Source {
+        data: Vec<u8>,
+        requests: Vec<Range<u64>>,
+    }
+
+    impl RangeRead for Source {
+        fn read_range(&mut self, range: Range<u64>) -> io::Result<Vec<u8>> {
+            self.requests.push(range.clone());
+            let start = (range.start as usize).min(self.data.len());
+            let end = (range.end as usize).min(self.data.len());
+            Ok(self.data[start..end].to_vec())
+        }
+    }
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        let source = Source {
+            data: b"noodles-bgzf".to_vec(),
+            requests: Vec::new(),
+        };
+
+        let mut reader = RangeReader::with_chunk_size(source, 5);
+
+        let mut buf = [0; 3];
+        reader.read_exact(&mut buf)?;
+        assert_eq!(&buf, b"noo");
+        reader.read_exact(&mut buf)?;
+        assert_eq!(&buf, b"dle");
+
+        reader.seek(SeekFrom::Start(8))?;
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"bgzf");
+
+        assert_eq!(reader.get_ref().requests, [0..5, 5..10, 10..15, 12..17]);
+
+        assert!(reader.seek(SeekFrom::End(0)).is_err());
+        assert!(reader.seek(SeekFrom::Current(-13)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_bgzf_reader() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.get_ref().len() as u64;
+        writer.write_all(b"-bgzf")?;
+        let data = writer.finish()?;
+
+        let source = Source {
+            data,
+            requests: Vec::new(),
+        };
+
+        let mut reader = Reader::new(RangeReader::with_chunk_size(source, 16));
+
+        let virtual_position = VirtualPosition::try_from((second_block_position, 1)).unwrap();
+        reader.seek(virtual_position)?;
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"bgzf");
+
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
-use std::io::{self, Read, Seek, SeekFrom};
+use std::{
+    convert::TryFrom,
+    io::{self, BufRead, Read, Seek, SeekFrom},
+};
 
 use byteorder::{ByteOrder, LittleEndian};
 use flate2::read::DeflateDecoder;
 
-use super::{gz, Block, VirtualPosition, BGZF_HEADER_SIZE};
+use super::{
+    block_cache::BlockCache, gz, gzi, metrics::Metrics, Block, VirtualPosition, BGZF_HEADER_SIZE,
+};
 
 /// A BGZF reader.
 ///
@@ -27,6 +32,8 @@ pub struct Reader<R> {
     position: u64,
     cdata: Vec<u8>,
     block: Block,
+    cache: Option<BlockCache>,
+    metrics: Metrics,
 }
 
 impl<R> Reader<R>
@@ -48,9 +55,35 @@ where
             position: 0,
             cdata: Vec::new(),
             block: Block::default(),
+            cache: None,
+            metrics: Metrics::default(),
         }
     }
 
+    /// Creates a BGZF reader with a cache of decompressed blocks.
+    ///
+    /// The cache holds up to `capacity` blocks and evicts the least recently used block when
+    /// full. Only blocks that are seeked to are cached and looked up, so repeated seeks to the
+    /// same blocks, e.g., many small overlapping region queries, do not decompress them again.
+    /// Sequential reads do not fill the cache. A capacity of 0 disables the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::with_block_cache(&data[..], 64);
+    /// ```
+    pub fn with_block_cache(inner: R, capacity: usize) -> Self {
+        let mut reader = Self::new(inner);
+
+        if capacity > 0 {
+            reader.cache = Some(BlockCache::new(capacity));
+        }
+
+        reader
+    }
+
     /// Returns the current position of the stream.
     ///
     /// # Examples
@@ -78,6 +111,39 @@ where
     pub fn virtual_position(&self) -> VirtualPosition {
         self.block.virtual_position()
     }
+
+    /// Returns the metrics collected by this reader.
+    ///
+    /// This is only available with the `metrics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// let data = [];
+    /// let reader = bgzf::Reader::new(&data[..]);
+    /// assert_eq!(reader.metrics().blocks(), 0);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    fn read_next_block(&mut self) -> io::Result<usize> {
+        let block_size = read_block(
+            &mut self.inner,
+            &mut self.cdata,
+            &mut self.block,
+            &mut self.metrics,
+        )?;
+
+        if block_size > 0 {
+            self.block.set_position(self.position);
+            self.position += block_size as u64;
+        }
+
+        Ok(block_size)
+    }
 }
 
 impl<R> Reader<R>
@@ -89,6 +155,9 @@ where
     /// The underlying stream's cursor is first moved the the compressed position. A block is read,
     /// decompressed, and has its own cursor moved to the uncompressed position.
     ///
+    /// If the reader has a block cache (see [`Self::with_block_cache`]) that contains the block,
+    /// the block is not read nor decompressed again.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -102,10 +171,40 @@ where
     pub fn seek(&mut self, pos: VirtualPosition) -> io::Result<VirtualPosition> {
         let (compressed_pos, uncompressed_pos) = pos.into();
 
-        self.inner.seek(SeekFrom::Start(compressed_pos))?;
-        self.position = compressed_pos;
+        let cached_block = self
+            .cache
+            .as_mut()
+            .and_then(|cache| cache.get(compressed_pos));
+
+        if let Some((block_size, data)) = cached_block {
+            self.metrics.add_cache_hit();
+
+            let udata_buf = self.block.data_mut().get_mut();
+            udata_buf.clear();
+            udata_buf.extend_from_slice(data);
+
+            self.block.set_len(block_size);
+            self.block.set_position(compressed_pos);
+            self.position = compressed_pos + block_size;
+
+            self.inner.seek(SeekFrom::Start(self.position))?;
+        } else {
+            if self.cache.is_some() {
+                self.metrics.add_cache_miss();
+            }
+
+            self.inner.seek(SeekFrom::Start(compressed_pos))?;
+            self.position = compressed_pos;
+
+            let block_size = self.read_next_block()?;
 
-        read_block(&mut self.inner, &mut self.cdata, &mut self.block)?;
+            if block_size == 0 {
+                self.block.set_position(compressed_pos);
+            } else if let Some(cache) = self.cache.as_mut() {
+                let data = self.block.data_mut().get_ref().clone();
+                cache.insert(compressed_pos, block_size as u64, data);
+            }
+        }
 
         self.block
             .data_mut()
@@ -113,6 +212,51 @@ where
 
         Ok(pos)
     }
+
+    /// Seeks the stream to the given uncompressed position.
+    ///
+    /// The gzip index (GZI) is used to find the block that contains the uncompressed position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Cursor, Read, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// writer.write_all(b"noodles")?;
+    /// let data = writer.finish()?;
+    ///
+    /// let index = bgzf::gzi::Index::new();
+    /// let mut reader = bgzf::Reader::new(Cursor::new(data));
+    /// reader.seek_by_uncompressed_position(&index, 3)?;
+    ///
+    /// let mut buf = String::new();
+    /// reader.read_to_string(&mut buf)?;
+    /// assert_eq!(buf, "dles");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn seek_by_uncompressed_position(
+        &mut self,
+        index: &gzi::Index,
+        pos: u64,
+    ) -> io::Result<u64> {
+        let (compressed_pos, block_start) = gzi::find_block(index, pos);
+
+        let uncompressed_pos = u16::try_from(pos - block_start).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid uncompressed position: {}", pos),
+            )
+        })?;
+
+        let virtual_position = VirtualPosition::try_from((compressed_pos, uncompressed_pos))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        self.seek(virtual_position)?;
+
+        Ok(pos)
+    }
 }
 
 impl<R> Read for Reader<R>
@@ -121,13 +265,9 @@ where
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match self.block.data_mut().read(buf) {
-            Ok(0) => match read_block(&mut self.inner, &mut self.cdata, &mut self.block) {
+            Ok(0) => match self.read_next_block() {
                 Ok(0) => Ok(0),
-                Ok(bs) => {
-                    self.block.set_position(self.position);
-                    self.position += bs as u64;
-                    Err(io::Error::from(io::ErrorKind::Interrupted))
-                }
+                Ok(_) => Err(io::Error::from(io::ErrorKind::Interrupted)),
                 Err(e) => Err(e),
             },
             Ok(n) => Ok(n),
@@ -136,6 +276,37 @@ where
     }
 }
 
+impl<R> BufRead for Reader<R>
+where
+    R: Read,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        loop {
+            let data = self.block.data_mut();
+
+            if data.position() < data.get_ref().len() as u64 {
+                break;
+            }
+
+            if self.read_next_block()? == 0 {
+                break;
+            }
+        }
+
+        let data = self.block.data_mut();
+        let pos = data.position() as usize;
+        let buf = data.get_ref();
+
+        Ok(&buf[pos.min(buf.len())..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let data = self.block.data_mut();
+        let pos = data.position() + amt as u64;
+        data.set_position(pos);
+    }
+}
+
 fn read_block_size<R>(reader: &mut R) -> io::Result<u16>
 where
     R: Read,
@@ -168,7 +339,12 @@ where
     decoder.read_to_end(writer)
 }
 
-fn read_block<R>(reader: &mut R, cdata: &mut Vec<u8>, block: &mut Block) -> io::Result<usize>
+fn read_block<R>(
+    reader: &mut R,
+    cdata: &mut Vec<u8>,
+    block: &mut Block,
+    metrics: &mut Metrics,
+) -> io::Result<usize>
 where
     R: Read,
 {
@@ -190,9 +366,108 @@ where
     let udata_buf = udata.get_mut();
     udata_buf.clear();
 
-    inflate_data(&cdata[..], udata_buf)?;
+    let udata_len = metrics.time_codec(|| inflate_data(&cdata[..], udata_buf))?;
+    metrics.add_block(block_size, udata_len);
 
     udata.set_position(0);
 
     Ok(block_size)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, io::Write};
+
+    use super::*;
+    use crate::Writer;
+
+    #[test]
+    fn test_fill_buf() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        writer.write_all(b"-bgzf")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(&data[..]);
+
+        assert_eq!(reader.fill_buf()?, b"noodles");
+        reader.consume(3);
+        assert_eq!(reader.fill_buf()?, b"dles");
+        reader.consume(4);
+        assert_eq!(reader.fill_buf()?, b"-bgzf");
+        reader.consume(5);
+        assert!(reader.fill_buf()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.get_ref().len() as u64;
+        writer.write_all(b"-bgzf")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::new(io::Cursor::new(data));
+
+        let virtual_position = VirtualPosition::try_from((second_block_position, 1)).unwrap();
+        reader.seek(virtual_position)?;
+        assert_eq!(reader.virtual_position(), virtual_position);
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"bgzf");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_with_block_cache() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let second_block_position = writer.get_ref().len() as u64;
+        writer.write_all(b"-bgzf")?;
+        let data = writer.finish()?;
+
+        let mut reader = Reader::with_block_cache(io::Cursor::new(data), 3);
+
+        let first_virtual_position = VirtualPosition::try_from((0, 3)).unwrap();
+        let second_virtual_position =
+            VirtualPosition::try_from((second_block_position, 1)).unwrap();
+
+        for _ in 0..2 {
+            reader.seek(first_virtual_position)?;
+            assert_eq!(reader.virtual_position(), first_virtual_position);
+
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf)?;
+            assert_eq!(&buf, b"dles");
+
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            assert_eq!(buf, b"-bgzf");
+
+            reader.seek(second_virtual_position)?;
+            assert_eq!(reader.virtual_position(), second_virtual_position);
+
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            assert_eq!(buf, b"bgzf");
+        }
+
+        // Only blocks that are seeked to are cached.
+        assert_eq!(reader.cache.as_ref().map(|cache| cache.len()), Some(2));
+
+        #[cfg(feature = "metrics")]
+        {
+            assert_eq!(reader.metrics().cache_hits(), 2);
+            assert_eq!(reader.metrics().cache_misses(), 2);
+        }
+
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use byteorder::{LittleEndian, WriteBytesExt};
 use flate2::{write::DeflateEncoder, Compression, Crc};
 
-use super::{block, gz, BGZF_HEADER_SIZE};
+use super::{block, gz, metrics::Metrics, VirtualPosition, BGZF_HEADER_SIZE};
 
 const MAX_COMPRESSED_DATA_LENGTH: usize = block::MAX_LENGTH + 1; // bytes
 
@@ -19,7 +19,7 @@ const BGZF_SI2: u8 = 0x43;
 const BGZF_SLEN: u16 = 2;
 
 // Sequence Alignment/Map Format Specification § 4.1.2 (accessed 2020-04-15)
-static BGZF_EOF: &[u8] = &[
+pub(crate) static BGZF_EOF: &[u8] = &[
     0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06, 0x00, 0x42, 0x43, 0x02, 0x00,
     0x1b, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
 ];
@@ -40,6 +40,9 @@ static BGZF_EOF: &[u8] = &[
 /// let data = writer.finish()?;
 /// # Ok::<(), io::Error>(())
 /// ```
+///
+/// Calling [`std::io::Write::flush`] finishes the current block, i.e., the next write starts a new
+/// block. This can be used to align blocks with logical boundaries in the uncompressed data.
 #[derive(Debug)]
 pub struct Writer<W>
 where
@@ -48,6 +51,9 @@ where
     inner: Option<W>,
     encoder: DeflateEncoder<Vec<u8>>,
     crc: Crc,
+    is_finished: bool,
+    position: u64,
+    metrics: Metrics,
 }
 
 impl<W> Writer<W>
@@ -67,6 +73,9 @@ where
             inner: Some(inner),
             encoder: DeflateEncoder::new(Vec::new(), Compression::default()),
             crc: Crc::new(),
+            is_finished: false,
+            position: 0,
+            metrics: Metrics::default(),
         }
     }
 
@@ -83,8 +92,101 @@ where
         self.inner.as_ref().unwrap()
     }
 
+    /// Returns the number of uncompressed bytes in the current block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// assert_eq!(writer.block_len(), 0);
+    ///
+    /// writer.write_all(b"noodles")?;
+    /// assert_eq!(writer.block_len(), 7);
+    ///
+    /// writer.flush()?;
+    /// assert_eq!(writer.block_len(), 0);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn block_len(&self) -> usize {
+        self.crc.amount() as usize
+    }
+
+    /// Returns the number of uncompressed bytes that can be written to the current block before it
+    /// is flushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// let capacity = writer.remaining_block_capacity();
+    ///
+    /// writer.write_all(b"noodles")?;
+    /// assert_eq!(writer.remaining_block_capacity(), capacity - 7);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn remaining_block_capacity(&self) -> usize {
+        MAX_COMPRESSED_DATA_LENGTH.saturating_sub(self.block_len())
+    }
+
+    /// Returns the current virtual position.
+    ///
+    /// This is the position of the next uncompressed byte to be written. The compressed position
+    /// is relative to the start of the stream written by this writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// writer.write_all(b"noodles")?;
+    /// assert_eq!(writer.virtual_position(), bgzf::VirtualPosition::from(7));
+    ///
+    /// writer.flush()?;
+    /// assert_eq!(writer.virtual_position().compressed(), writer.get_ref().len() as u64);
+    /// assert_eq!(writer.virtual_position().uncompressed(), 0);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn virtual_position(&self) -> VirtualPosition {
+        // A full block is flushed on write, so the block length always fits in a u16.
+        let compressed_pos = self.position;
+        let uncompressed_pos = self.block_len() as u64;
+        VirtualPosition::from(compressed_pos << 16 | uncompressed_pos)
+    }
+
+    /// Returns the metrics collected by this writer.
+    ///
+    /// This is only available with the `metrics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf as bgzf;
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// writer.write_all(b"noodles")?;
+    /// writer.flush()?;
+    ///
+    /// assert_eq!(writer.metrics().blocks(), 1);
+    /// assert_eq!(writer.metrics().uncompressed_bytes(), 7);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
     fn flush_block(&mut self) -> io::Result<()> {
-        self.encoder.try_finish()?;
+        let encoder = &mut self.encoder;
+        self.metrics.time_codec(|| encoder.try_finish())?;
 
         let inner = self.inner.as_mut().unwrap();
         let data = self.encoder.get_ref();
@@ -93,6 +195,11 @@ where
         inner.write_all(&data[..])?;
         write_trailer(inner, self.crc.sum(), self.crc.amount())?;
 
+        let block_size = BGZF_HEADER_SIZE + data.len() + gz::TRAILER_SIZE;
+        self.position += block_size as u64;
+        self.metrics
+            .add_block(block_size, self.crc.amount() as usize);
+
         self.encoder.reset(Vec::new())?;
         self.crc.reset();
 
@@ -101,7 +208,11 @@ where
 
     /// Attempts to finish the output stream by flushing any remaining buffers.
     ///
-    /// This then appends the final BGZF EOF block.
+    /// This then appends the final BGZF EOF block. The EOF block is only written once, i.e.,
+    /// calling this again without writing more data, or dropping the writer afterward, is a no-op.
+    ///
+    /// The writer also tries to finish the stream when it is dropped, but errors are then
+    /// ignored. Call this (or [`Self::finish`]) explicitly to handle them.
     ///
     /// # Examples
     ///
@@ -116,9 +227,19 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn try_finish(&mut self) -> io::Result<()> {
+        if self.is_finished {
+            return Ok(());
+        }
+
         self.flush()?;
         let inner = self.inner.as_mut().unwrap();
-        inner.write_all(BGZF_EOF)
+        inner.write_all(BGZF_EOF)?;
+        self.position += BGZF_EOF.len() as u64;
+        self.metrics.add_block(BGZF_EOF.len(), 0);
+
+        self.is_finished = true;
+
+        Ok(())
     }
 
     /// Returns the underlying writer after finishing the output stream.
@@ -160,7 +281,7 @@ where
     W: Write,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let total_uncompressed_bytes_written = self.crc.amount() as usize;
+        let total_uncompressed_bytes_written = self.block_len();
 
         // Only the uncompressed size is tracked, and the assumption is that the uncompressed size
         // will always be less than the compressed size.
@@ -173,14 +294,25 @@ where
             (MAX_COMPRESSED_DATA_LENGTH - total_uncompressed_bytes_written) as usize,
             buf.len(),
         );
-        let bytes_written = self.encoder.write(&buf[..bytes_to_be_written])?;
+        let encoder = &mut self.encoder;
+        let bytes_written = self
+            .metrics
+            .time_codec(|| encoder.write(&buf[..bytes_to_be_written]))?;
         self.crc.update(&buf[..bytes_written]);
 
+        if bytes_written > 0 {
+            self.is_finished = false;
+        }
+
+        if self.block_len() >= MAX_COMPRESSED_DATA_LENGTH {
+            self.flush()?;
+        }
+
         Ok(bytes_written)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        if self.crc.amount() > 0 {
+        if self.block_len() > 0 {
             self.flush_block()
         } else {
             Ok(())
@@ -188,6 +320,32 @@ where
     }
 }
 
+/// Compresses the given data as a single, complete BGZF block.
+///
+/// The uncompressed data is expected to be small enough that the compressed data fits in a block.
+pub(crate) fn deflate_block(data: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), compression);
+    encoder.write_all(data)?;
+    let cdata = encoder.finish()?;
+
+    if cdata.len() + BGZF_HEADER_SIZE + gz::TRAILER_SIZE > block::MAX_LENGTH + 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "compressed data is too large for a BGZF block",
+        ));
+    }
+
+    let mut crc = Crc::new();
+    crc.update(data);
+
+    let mut block = Vec::with_capacity(BGZF_HEADER_SIZE + cdata.len() + gz::TRAILER_SIZE);
+    write_header(&mut block, cdata.len())?;
+    block.extend_from_slice(&cdata);
+    write_trailer(&mut block, crc.sum(), crc.amount())?;
+
+    Ok(block)
+}
+
 fn write_header<W>(writer: &mut W, cdata_len: usize) -> io::Result<()>
 where
     W: Write,
@@ -235,4 +393,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_finish() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_all(b"noodles")?;
+
+        writer.try_finish()?;
+        let len = writer.get_ref().len();
+
+        // The EOF block is not written again.
+        writer.try_finish()?;
+        assert_eq!(writer.get_ref().len(), len);
+
+        let data = writer.finish()?;
+        assert_eq!(data.len(), len);
+        assert_eq!(&data[len - BGZF_EOF.len()..], BGZF_EOF);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+
+        writer.write_all(b"noodles")?;
+        writer.flush()?;
+        let first_block_len = writer.get_ref().len();
+        assert!(first_block_len > 0);
+
+        // Flushing an empty block is a no-op.
+        writer.flush()?;
+        assert_eq!(writer.get_ref().len(), first_block_len);
+
+        writer.write_all(b"-bgzf")?;
+        assert_eq!(writer.block_len(), 5);
+        writer.flush()?;
+        assert!(writer.get_ref().len() > first_block_len);
+        assert_eq!(writer.block_len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_virtual_position_with_full_block() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+
+        writer.write_all(&vec![0; MAX_COMPRESSED_DATA_LENGTH])?;
+        let compressed_pos = writer.get_ref().len() as u64;
+        assert!(compressed_pos > 0);
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::from(compressed_pos << 16)
+        );
+
+        writer.write_all(b"noodles")?;
+        assert_eq!(
+            writer.virtual_position(),
+            VirtualPosition::from(compressed_pos << 16 | 7)
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,753 @@
+//! Shared bigWig/bigBed (BBI) file layout.
+//!
+//! A BBI file is written in the following order: header, zoom headers, autoSql (bigBed only),
+//! total summary, chromosome B+ tree, data sections, data R-tree index, and for each zoom level,
+//! its data sections and R-tree index. The file ends with the file magic number.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use flate2::{write::ZlibEncoder, Compression};
+
+pub(crate) const BIGWIG_MAGIC: u32 = 0x888f_fc26;
+pub(crate) const BIGBED_MAGIC: u32 = 0x8789_f2eb;
+
+pub(crate) const ITEMS_PER_SLOT: usize = 1024;
+
+const VERSION: u16 = 4;
+
+const CHROMOSOME_TREE_MAGIC: u32 = 0x78ca_8c91;
+const R_TREE_MAGIC: u32 = 0x2468_ace0;
+
+const BLOCK_SIZE: usize = 256;
+const MAX_ZOOM_LEVELS: usize = 10;
+
+const HEADER_SIZE: usize = 64;
+const ZOOM_HEADER_SIZE: usize = 24;
+const NODE_HEADER_SIZE: usize = 4;
+const R_TREE_LEAF_ITEM_SIZE: usize = 32;
+const R_TREE_NODE_ITEM_SIZE: usize = 24;
+
+/// A reference sequence name and length.
+pub(crate) type Chromosome = (String, u32);
+
+/// An uncompressed data section.
+#[derive(Debug)]
+pub(crate) struct Block {
+    pub chromosome_id: u32,
+    pub start: u32,
+    pub end: u32,
+    pub data: Vec<u8>,
+}
+
+/// A 0-based, half-open interval with a value.
+///
+/// This is used to build the total summary and zoom levels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Interval {
+    pub chromosome_id: u32,
+    pub start: u32,
+    pub end: u32,
+    pub value: f32,
+}
+
+/// The contents of a BBI file.
+pub(crate) struct File<'a> {
+    pub magic: u32,
+    pub chromosomes: &'a [Chromosome],
+    pub field_count: u16,
+    pub defined_field_count: u16,
+    pub auto_sql: Option<&'a str>,
+    pub data_count: u64,
+    pub blocks: Vec<Block>,
+    pub intervals: Vec<Interval>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Summary {
+    bases_covered: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    sum_squares: f64,
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self {
+            bases_covered: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            sum_squares: 0.0,
+        }
+    }
+}
+
+impl Summary {
+    fn add(&mut self, value: f32, len: u64) {
+        let value = f64::from(value);
+
+        self.bases_covered += len;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value * len as f64;
+        self.sum_squares += value * value * len as f64;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ZoomRecord {
+    chromosome_id: u32,
+    start: u32,
+    end: u32,
+    summary: Summary,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Bounds {
+    start_chromosome_id: u32,
+    start: u32,
+    end_chromosome_id: u32,
+    end: u32,
+}
+
+impl Bounds {
+    fn union(self, other: Self) -> Self {
+        let (start_chromosome_id, start) =
+            (self.start_chromosome_id, self.start).min((other.start_chromosome_id, other.start));
+        let (end_chromosome_id, end) =
+            (self.end_chromosome_id, self.end).max((other.end_chromosome_id, other.end));
+
+        Self {
+            start_chromosome_id,
+            start,
+            end_chromosome_id,
+            end,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct IndexEntry {
+    bounds: Bounds,
+    offset: u64,
+    size: u64,
+}
+
+/// Writes a BBI file.
+pub(crate) fn write_file<W>(writer: &mut W, file: File<'_>) -> io::Result<()>
+where
+    W: Write,
+{
+    let zoom_levels = build_zoom_levels(file.chromosomes, &file.intervals);
+
+    let mut buf = vec![0; HEADER_SIZE + ZOOM_HEADER_SIZE * zoom_levels.len()];
+
+    let auto_sql_offset = match file.auto_sql {
+        Some(auto_sql) => {
+            let offset = buf.len() as u64;
+            buf.extend_from_slice(auto_sql.as_bytes());
+            buf.push(0);
+            offset
+        }
+        None => 0,
+    };
+
+    let total_summary_offset = buf.len() as u64;
+    let total_summary = file
+        .intervals
+        .iter()
+        .fold(Summary::default(), |mut summary, interval| {
+            summary.add(interval.value, u64::from(interval.end - interval.start));
+            summary
+        });
+    write_total_summary(&mut buf, &total_summary)?;
+
+    let chromosome_tree_offset = buf.len() as u64;
+    write_chromosome_tree(&mut buf, file.chromosomes)?;
+
+    let mut max_block_size = 0;
+
+    let full_data_offset = buf.len() as u64;
+    buf.write_u64::<LittleEndian>(file.data_count)?;
+    let entries = write_blocks(&mut buf, &file.blocks, &mut max_block_size)?;
+
+    let full_index_offset = buf.len() as u64;
+    write_r_tree(&mut buf, &entries, full_index_offset)?;
+
+    let mut zoom_headers = Vec::with_capacity(zoom_levels.len());
+
+    for (reduction_level, records) in &zoom_levels {
+        let data_offset = buf.len() as u64;
+        buf.write_u32::<LittleEndian>(records.len() as u32)?;
+
+        let blocks = build_zoom_blocks(records)?;
+        let entries = write_blocks(&mut buf, &blocks, &mut max_block_size)?;
+
+        let index_offset = buf.len() as u64;
+        write_r_tree(&mut buf, &entries, index_offset)?;
+
+        zoom_headers.push((*reduction_level, data_offset, index_offset));
+    }
+
+    buf.write_u32::<LittleEndian>(file.magic)?;
+
+    let mut header = &mut buf[..HEADER_SIZE + ZOOM_HEADER_SIZE * zoom_headers.len()];
+
+    header.write_u32::<LittleEndian>(file.magic)?;
+    header.write_u16::<LittleEndian>(VERSION)?;
+    header.write_u16::<LittleEndian>(zoom_headers.len() as u16)?;
+    header.write_u64::<LittleEndian>(chromosome_tree_offset)?;
+    header.write_u64::<LittleEndian>(full_data_offset)?;
+    header.write_u64::<LittleEndian>(full_index_offset)?;
+    header.write_u16::<LittleEndian>(file.field_count)?;
+    header.write_u16::<LittleEndian>(file.defined_field_count)?;
+    header.write_u64::<LittleEndian>(auto_sql_offset)?;
+    header.write_u64::<LittleEndian>(total_summary_offset)?;
+    header.write_u32::<LittleEndian>(max_block_size)?;
+    // extension offset
+    header.write_u64::<LittleEndian>(0)?;
+
+    for (reduction_level, data_offset, index_offset) in zoom_headers {
+        header.write_u32::<LittleEndian>(reduction_level)?;
+        // reserved
+        header.write_u32::<LittleEndian>(0)?;
+        header.write_u64::<LittleEndian>(data_offset)?;
+        header.write_u64::<LittleEndian>(index_offset)?;
+    }
+
+    writer.write_all(&buf)
+}
+
+fn write_total_summary(buf: &mut Vec<u8>, summary: &Summary) -> io::Result<()> {
+    buf.write_u64::<LittleEndian>(summary.bases_covered)?;
+
+    if summary.bases_covered == 0 {
+        buf.write_f64::<LittleEndian>(0.0)?;
+        buf.write_f64::<LittleEndian>(0.0)?;
+    } else {
+        buf.write_f64::<LittleEndian>(summary.min)?;
+        buf.write_f64::<LittleEndian>(summary.max)?;
+    }
+
+    buf.write_f64::<LittleEndian>(summary.sum)?;
+    buf.write_f64::<LittleEndian>(summary.sum_squares)?;
+
+    Ok(())
+}
+
+// Writes a B+ tree of chromosome names to chromosome IDs and lengths.
+//
+// Chromosome IDs are the indices of the given list, and keys are sorted by name.
+fn write_chromosome_tree(buf: &mut Vec<u8>, chromosomes: &[Chromosome]) -> io::Result<()> {
+    let mut items: Vec<_> = chromosomes
+        .iter()
+        .enumerate()
+        .map(|(id, (name, len))| (name.as_bytes(), id as u32, *len))
+        .collect();
+
+    items.sort_by(|a, b| a.0.cmp(b.0));
+
+    let block_size = items.len().clamp(1, BLOCK_SIZE);
+    let key_size = items.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+    let item_size = key_size + 8;
+
+    buf.write_u32::<LittleEndian>(CHROMOSOME_TREE_MAGIC)?;
+    buf.write_u32::<LittleEndian>(block_size as u32)?;
+    buf.write_u32::<LittleEndian>(key_size as u32)?;
+    // value size
+    buf.write_u32::<LittleEndian>(8)?;
+    buf.write_u64::<LittleEndian>(items.len() as u64)?;
+    // reserved
+    buf.write_u64::<LittleEndian>(0)?;
+
+    // Each level holds the number of children of each node. The first level is the leaves.
+    let mut levels = vec![chunk_lens(items.len(), block_size)];
+
+    while levels[levels.len() - 1].len() > 1 {
+        let len = levels[levels.len() - 1].len();
+        levels.push(chunk_lens(len, block_size));
+    }
+
+    let offsets = node_offsets(buf.len() as u64, &levels, |_| item_size);
+
+    let write_key = |buf: &mut Vec<u8>, key: &[u8]| {
+        buf.extend_from_slice(key);
+        buf.resize(buf.len() + key_size - key.len(), 0);
+    };
+
+    for (depth, level) in levels.iter().enumerate().rev() {
+        let is_leaf = depth == 0;
+
+        // The index of the first item (or child node) of the next node.
+        let mut first = 0;
+
+        for &len in level {
+            buf.write_u8(u8::from(is_leaf))?;
+            // reserved
+            buf.write_u8(0)?;
+            buf.write_u16::<LittleEndian>(len as u16)?;
+
+            for i in first..first + len {
+                if is_leaf {
+                    let (key, id, chromosome_len) = items[i];
+                    write_key(buf, key);
+                    buf.write_u32::<LittleEndian>(id)?;
+                    buf.write_u32::<LittleEndian>(chromosome_len)?;
+                } else {
+                    let item_index = first_item_index(&levels[..depth], i);
+                    write_key(buf, items[item_index].0);
+                    buf.write_u64::<LittleEndian>(offsets[depth - 1][i])?;
+                }
+            }
+
+            first += len;
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the index of the first leaf item under the given node at the top of the given levels.
+fn first_item_index(levels: &[Vec<usize>], node_index: usize) -> usize {
+    levels
+        .iter()
+        .rev()
+        .fold(node_index, |i, level| level[..i].iter().sum())
+}
+
+// Splits `len` items into chunks of at most `block_size`, returning the length of each chunk.
+//
+// There is always at least one chunk, i.e., a tree with no items has an empty root leaf.
+fn chunk_lens(len: usize, block_size: usize) -> Vec<usize> {
+    if len == 0 {
+        return vec![0];
+    }
+
+    let mut lens = vec![block_size; len / block_size];
+
+    if !len.is_multiple_of(block_size) {
+        lens.push(len % block_size);
+    }
+
+    lens
+}
+
+// Calculates the file offset of each node when levels are written from the root down.
+fn node_offsets<F>(start: u64, levels: &[Vec<usize>], item_size: F) -> Vec<Vec<u64>>
+where
+    F: Fn(usize) -> usize,
+{
+    let mut offsets = vec![Vec::new(); levels.len()];
+    let mut offset = start;
+
+    for (depth, level) in levels.iter().enumerate().rev() {
+        for &len in level {
+            offsets[depth].push(offset);
+            offset += (NODE_HEADER_SIZE + len * item_size(depth)) as u64;
+        }
+    }
+
+    offsets
+}
+
+// Compresses and writes data sections, returning their index entries.
+fn write_blocks(
+    buf: &mut Vec<u8>,
+    blocks: &[Block],
+    max_block_size: &mut u32,
+) -> io::Result<Vec<IndexEntry>> {
+    let mut entries = Vec::with_capacity(blocks.len());
+
+    for block in blocks {
+        *max_block_size = (*max_block_size).max(block.data.len() as u32);
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&block.data)?;
+        let data = encoder.finish()?;
+
+        let offset = buf.len() as u64;
+        buf.extend_from_slice(&data);
+
+        entries.push(IndexEntry {
+            bounds: Bounds {
+                start_chromosome_id: block.chromosome_id,
+                start: block.start,
+                end_chromosome_id: block.chromosome_id,
+                end: block.end,
+            },
+            offset,
+            size: data.len() as u64,
+        });
+    }
+
+    Ok(entries)
+}
+
+// Writes a cluster R-tree (cirTree) over the given data sections.
+fn write_r_tree(buf: &mut Vec<u8>, entries: &[IndexEntry], end_file_offset: u64) -> io::Result<()> {
+    let bounds = entries
+        .iter()
+        .map(|entry| entry.bounds)
+        .fold(None, |acc: Option<Bounds>, bounds| {
+            Some(acc.map(|b| b.union(bounds)).unwrap_or(bounds))
+        })
+        .unwrap_or(Bounds {
+            start_chromosome_id: 0,
+            start: 0,
+            end_chromosome_id: 0,
+            end: 0,
+        });
+
+    buf.write_u32::<LittleEndian>(R_TREE_MAGIC)?;
+    buf.write_u32::<LittleEndian>(BLOCK_SIZE as u32)?;
+    buf.write_u64::<LittleEndian>(entries.len() as u64)?;
+    buf.write_u32::<LittleEndian>(bounds.start_chromosome_id)?;
+    buf.write_u32::<LittleEndian>(bounds.start)?;
+    buf.write_u32::<LittleEndian>(bounds.end_chromosome_id)?;
+    buf.write_u32::<LittleEndian>(bounds.end)?;
+    buf.write_u64::<LittleEndian>(end_file_offset)?;
+    buf.write_u32::<LittleEndian>(ITEMS_PER_SLOT as u32)?;
+    // reserved
+    buf.write_u32::<LittleEndian>(0)?;
+
+    let mut levels = vec![chunk_lens(entries.len(), BLOCK_SIZE)];
+    let mut level_bounds = vec![entries.iter().map(|entry| entry.bounds).collect::<Vec<_>>()];
+
+    loop {
+        let children = &levels[levels.len() - 1];
+        let child_bounds = &level_bounds[level_bounds.len() - 1];
+
+        let mut node_bounds = Vec::with_capacity(children.len());
+        let mut first = 0;
+
+        for &len in children {
+            let b = child_bounds[first..first + len]
+                .iter()
+                .fold(None, |acc: Option<Bounds>, &b| {
+                    Some(acc.map(|a| a.union(b)).unwrap_or(b))
+                })
+                .unwrap_or(bounds);
+
+            node_bounds.push(b);
+            first += len;
+        }
+
+        level_bounds.push(node_bounds);
+
+        if children.len() <= 1 {
+            break;
+        }
+
+        levels.push(chunk_lens(children.len(), BLOCK_SIZE));
+    }
+
+    let offsets = node_offsets(buf.len() as u64, &levels, |depth| {
+        if depth == 0 {
+            R_TREE_LEAF_ITEM_SIZE
+        } else {
+            R_TREE_NODE_ITEM_SIZE
+        }
+    });
+
+    for (depth, level) in levels.iter().enumerate().rev() {
+        let is_leaf = depth == 0;
+        let mut first = 0;
+
+        for &len in level {
+            buf.write_u8(u8::from(is_leaf))?;
+            // reserved
+            buf.write_u8(0)?;
+            buf.write_u16::<LittleEndian>(len as u16)?;
+
+            for i in first..first + len {
+                let b = level_bounds[depth][i];
+
+                buf.write_u32::<LittleEndian>(b.start_chromosome_id)?;
+                buf.write_u32::<LittleEndian>(b.start)?;
+                buf.write_u32::<LittleEndian>(b.end_chromosome_id)?;
+                buf.write_u32::<LittleEndian>(b.end)?;
+
+                if is_leaf {
+                    buf.write_u64::<LittleEndian>(entries[i].offset)?;
+                    buf.write_u64::<LittleEndian>(entries[i].size)?;
+                } else {
+                    buf.write_u64::<LittleEndian>(offsets[depth - 1][i])?;
+                }
+            }
+
+            first += len;
+        }
+    }
+
+    Ok(())
+}
+
+// Builds successively coarser zoom levels, starting at 10 times the mean interval length and
+// growing by a factor of 4. A level is only added if it has fewer records than the previous one.
+fn build_zoom_levels(
+    chromosomes: &[Chromosome],
+    intervals: &[Interval],
+) -> Vec<(u32, Vec<ZoomRecord>)> {
+    let mut zoom_levels = Vec::new();
+
+    if intervals.is_empty() {
+        return zoom_levels;
+    }
+
+    let total_len: u64 = intervals
+        .iter()
+        .map(|interval| u64::from(interval.end - interval.start))
+        .sum();
+    let mean_len = total_len / intervals.len() as u64;
+
+    let mut reduction_level = (mean_len * 10).max(1).min(u64::from(u32::MAX)) as u32;
+    let mut prev_len = intervals.len();
+
+    for _ in 0..MAX_ZOOM_LEVELS {
+        let records = reduce(chromosomes, intervals, reduction_level);
+
+        if records.len() >= prev_len {
+            break;
+        }
+
+        prev_len = records.len();
+        zoom_levels.push((reduction_level, records));
+
+        reduction_level = match reduction_level.checked_mul(4) {
+            Some(n) => n,
+            None => break,
+        };
+    }
+
+    zoom_levels
+}
+
+// Summarizes intervals into fixed-size bins of `reduction_level` bases.
+fn reduce(
+    chromosomes: &[Chromosome],
+    intervals: &[Interval],
+    reduction_level: u32,
+) -> Vec<ZoomRecord> {
+    let reduction_level = u64::from(reduction_level);
+    let mut bins: BTreeMap<(u32, u64), Summary> = BTreeMap::new();
+
+    for interval in intervals {
+        let end = u64::from(interval.end);
+        let mut start = u64::from(interval.start);
+
+        while start < end {
+            let bin = start / reduction_level;
+            let bin_end = ((bin + 1) * reduction_level).min(end);
+
+            bins.entry((interval.chromosome_id, bin))
+                .or_default()
+                .add(interval.value, bin_end - start);
+
+            start = bin_end;
+        }
+    }
+
+    bins.into_iter()
+        .map(|((chromosome_id, bin), summary)| {
+            let chromosome_len = u64::from(chromosomes[chromosome_id as usize].1);
+            let start = bin * reduction_level;
+            let end = ((bin + 1) * reduction_level).min(chromosome_len).max(start);
+
+            ZoomRecord {
+                chromosome_id,
+                start: start as u32,
+                end: end as u32,
+                summary,
+            }
+        })
+        .collect()
+}
+
+fn build_zoom_blocks(records: &[ZoomRecord]) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+
+    while start < records.len() {
+        let chromosome_id = records[start].chromosome_id;
+
+        let end = records[start..]
+            .iter()
+            .take(ITEMS_PER_SLOT)
+            .take_while(|record| record.chromosome_id == chromosome_id)
+            .count()
+            + start;
+
+        let chunk = &records[start..end];
+        let mut data = Vec::with_capacity(chunk.len() * 32);
+
+        for record in chunk {
+            data.write_u32::<LittleEndian>(record.chromosome_id)?;
+            data.write_u32::<LittleEndian>(record.start)?;
+            data.write_u32::<LittleEndian>(record.end)?;
+            data.write_u32::<LittleEndian>(record.summary.bases_covered as u32)?;
+            data.write_f32::<LittleEndian>(record.summary.min as f32)?;
+            data.write_f32::<LittleEndian>(record.summary.max as f32)?;
+            data.write_f32::<LittleEndian>(record.summary.sum as f32)?;
+            data.write_f32::<LittleEndian>(record.summary.sum_squares as f32)?;
+        }
+
+        blocks.push(Block {
+            chromosome_id,
+            start: chunk[0].start,
+            end: chunk.iter().map(|record| record.end).max().unwrap_or(0),
+            data,
+        });
+
+        start = end;
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+
+    fn read_u16(buf: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(buf[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn read_u64(buf: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_chunk_lens() {
+        assert_eq!(chunk_lens(0, 2), [0]);
+        assert_eq!(chunk_lens(4, 2), [2, 2]);
+        assert_eq!(chunk_lens(5, 2), [2, 2, 1]);
+    }
+
+    #[test]
+    fn test_first_item_index() {
+        let levels = vec![vec![2, 2, 1], vec![2, 1]];
+        assert_eq!(first_item_index(&levels, 0), 0);
+        assert_eq!(first_item_index(&levels, 1), 4);
+        assert_eq!(first_item_index(&levels[..1], 2), 4);
+    }
+
+    #[test]
+    fn test_write_chromosome_tree() -> io::Result<()> {
+        let chromosomes = vec![(String::from("sq1"), 13), (String::from("sq0"), 8)];
+
+        let mut buf = Vec::new();
+        write_chromosome_tree(&mut buf, &chromosomes)?;
+
+        assert_eq!(read_u32(&buf, 0), CHROMOSOME_TREE_MAGIC);
+        assert_eq!(read_u32(&buf, 4), 2); // block size
+        assert_eq!(read_u32(&buf, 8), 3); // key size
+        assert_eq!(read_u64(&buf, 16), 2); // item count
+
+        // root leaf
+        assert_eq!(buf[32], 1);
+        assert_eq!(read_u16(&buf, 34), 2);
+        assert_eq!(&buf[36..39], b"sq0");
+        assert_eq!(read_u32(&buf, 39), 1);
+        assert_eq!(read_u32(&buf, 43), 8);
+        assert_eq!(&buf[47..50], b"sq1");
+        assert_eq!(read_u32(&buf, 50), 0);
+        assert_eq!(read_u32(&buf, 54), 13);
+
+        assert_eq!(buf.len(), 58);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reduce() {
+        let chromosomes = vec![(String::from("sq0"), 15)];
+
+        let intervals = [
+            Interval {
+                chromosome_id: 0,
+                start: 0,
+                end: 4,
+                value: 1.0,
+            },
+            Interval {
+                chromosome_id: 0,
+                start: 8,
+                end: 12,
+                value: 3.0,
+            },
+        ];
+
+        let records = reduce(&chromosomes, &intervals, 10);
+
+        assert_eq!(records.len(), 2);
+
+        assert_eq!((records[0].start, records[0].end), (0, 10));
+        assert_eq!(records[0].summary.bases_covered, 6);
+        assert_eq!(records[0].summary.min, 1.0);
+        assert_eq!(records[0].summary.max, 3.0);
+        assert_eq!(records[0].summary.sum, 10.0);
+        assert_eq!(records[0].summary.sum_squares, 22.0);
+
+        assert_eq!((records[1].start, records[1].end), (10, 15));
+        assert_eq!(records[1].summary.bases_covered, 2);
+        assert_eq!(records[1].summary.sum, 6.0);
+    }
+
+    #[test]
+    fn test_write_r_tree() -> io::Result<()> {
+        let entries: Vec<_> = (0..300)
+            .map(|i| IndexEntry {
+                bounds: Bounds {
+                    start_chromosome_id: 0,
+                    start: i * 10,
+                    end_chromosome_id: 0,
+                    end: i * 10 + 5,
+                },
+                offset: u64::from(i) * 100,
+                size: 100,
+            })
+            .collect();
+
+        let mut buf = Vec::new();
+        write_r_tree(&mut buf, &entries, 30000)?;
+
+        assert_eq!(read_u32(&buf, 0), R_TREE_MAGIC);
+        assert_eq!(read_u64(&buf, 8), 300);
+        assert_eq!(read_u32(&buf, 20), 0);
+        assert_eq!(read_u32(&buf, 28), 2995);
+
+        // The root has two children: a full leaf and a leaf with the remaining 44 entries.
+        assert_eq!(buf[48], 0);
+        assert_eq!(read_u16(&buf, 50), 2);
+
+        let first_leaf_offset = read_u64(&buf, 52 + 16) as usize;
+        let second_leaf_offset = read_u64(&buf, 52 + 24 + 16) as usize;
+        assert_eq!(first_leaf_offset, 52 + 2 * R_TREE_NODE_ITEM_SIZE);
+
+        assert_eq!(buf[first_leaf_offset], 1);
+        assert_eq!(read_u16(&buf, first_leaf_offset + 2), 256);
+
+        assert_eq!(buf[second_leaf_offset], 1);
+        assert_eq!(read_u16(&buf, second_leaf_offset + 2), 44);
+        assert_eq!(read_u32(&buf, second_leaf_offset + 4 + 4), 2560);
+        assert_eq!(read_u64(&buf, second_leaf_offset + 4 + 16), 25600);
+
+        assert_eq!(
+            buf.len(),
+            second_leaf_offset + 4 + 44 * R_TREE_LEAF_ITEM_SIZE
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+//! bigBed writer.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use super::{
+    bbi::{self, Block, Chromosome, Interval},
+    writer::{build_chromosome_ids, resolve_chromosome_id},
+};
+
+const BED3_FIELD_COUNT: u16 = 3;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Record {
+    chromosome_id: u32,
+    start: u32,
+    end: u32,
+    rest: String,
+}
+
+/// A bigBed writer.
+///
+/// Records can be written in any order. They are buffered in memory and written, along with the
+/// index and zoom levels, when the writer is finished. Zoom levels summarize the coverage depth of
+/// the records.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bigwig::bigbed;
+///
+/// let mut writer = bigbed::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+/// writer.write_record("sq0", 0, 5, "r0\t0\t+")?;
+/// writer.write_record("sq0", 3, 8, "r1\t0\t-")?;
+///
+/// let data = writer.finish()?;
+/// assert_eq!(&data[..4], [0xeb, 0xf2, 0x89, 0x87]);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Writer<W>
+where
+    W: Write,
+{
+    inner: W,
+    chromosomes: Vec<Chromosome>,
+    chromosome_ids: HashMap<String, u32>,
+    auto_sql: Option<String>,
+    field_count: Option<u16>,
+    records: Vec<Record>,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Creates a bigBed writer.
+    ///
+    /// `chromosomes` is the list of reference sequence names and lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bigwig::bigbed;
+    /// let writer = bigbed::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// ```
+    pub fn new(inner: W, chromosomes: Vec<(String, u32)>) -> Self {
+        let chromosome_ids = build_chromosome_ids(&chromosomes);
+
+        Self {
+            inner,
+            chromosomes,
+            chromosome_ids,
+            auto_sql: None,
+            field_count: None,
+            records: Vec::new(),
+        }
+    }
+
+    /// Sets the autoSql definition of the record fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bigwig::bigbed;
+    ///
+    /// let auto_sql = r#"table bed3
+    /// "Browser extensible data"
+    /// (
+    /// string chrom; "Reference sequence chromosome or scaffold"
+    /// uint chromStart; "Start position in chromosome"
+    /// uint chromEnd; "End position in chromosome"
+    /// )"#;
+    ///
+    /// let writer = bigbed::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)])
+    ///     .set_auto_sql(auto_sql.into());
+    /// ```
+    pub fn set_auto_sql(mut self, auto_sql: String) -> Self {
+        self.auto_sql = Some(auto_sql);
+        self
+    }
+
+    /// Adds a BED record.
+    ///
+    /// The interval is 0-based and half-open. `rest` is the tab-delimited list of fields after
+    /// the first three BED fields and may be empty. All records must have the same number of
+    /// fields.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the reference sequence name is not in the chromosome list, the
+    /// interval is empty, the interval ends past the end of the reference sequence, or the number
+    /// of fields differs from previous records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bigwig::bigbed;
+    /// let mut writer = bigbed::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// writer.write_record("sq0", 0, 5, "r0")?;
+    /// assert!(writer.write_record("sq0", 0, 5, "r1\t0").is_err());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_record(&mut self, name: &str, start: u32, end: u32, rest: &str) -> io::Result<()> {
+        let chromosome_id =
+            resolve_chromosome_id(&self.chromosomes, &self.chromosome_ids, name, start, end)?;
+
+        let field_count = count_fields(rest);
+
+        match self.field_count {
+            Some(n) if n != field_count => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("field count mismatch: expected {}, got {}", n, field_count),
+                ));
+            }
+            Some(_) => {}
+            None => self.field_count = Some(field_count),
+        }
+
+        self.records.push(Record {
+            chromosome_id,
+            start,
+            end,
+            rest: rest.into(),
+        });
+
+        Ok(())
+    }
+
+    /// Writes the bigBed file and returns the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bigwig::bigbed;
+    /// let writer = bigbed::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// let data = writer.finish()?;
+    /// assert!(!data.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn finish(mut self) -> io::Result<W> {
+        self.records
+            .sort_by_key(|record| (record.chromosome_id, record.start, record.end));
+
+        let blocks = build_blocks(&self.records)?;
+        let intervals = build_depth_intervals(&self.records);
+        let field_count = self.field_count.unwrap_or(BED3_FIELD_COUNT);
+
+        let file = bbi::File {
+            magic: bbi::BIGBED_MAGIC,
+            chromosomes: &self.chromosomes,
+            field_count,
+            defined_field_count: field_count,
+            auto_sql: self.auto_sql.as_deref(),
+            data_count: self.records.len() as u64,
+            blocks,
+            intervals,
+        };
+
+        bbi::write_file(&mut self.inner, file)?;
+
+        Ok(self.inner)
+    }
+}
+
+fn count_fields(rest: &str) -> u16 {
+    if rest.is_empty() {
+        BED3_FIELD_COUNT
+    } else {
+        BED3_FIELD_COUNT + rest.split('\t').count() as u16
+    }
+}
+
+fn build_blocks(records: &[Record]) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+
+    for chunk in records.chunks(bbi::ITEMS_PER_SLOT) {
+        let mut start = 0;
+
+        while start < chunk.len() {
+            let chromosome_id = chunk[start].chromosome_id;
+
+            let end = chunk[start..]
+                .iter()
+                .take_while(|record| record.chromosome_id == chromosome_id)
+                .count()
+                + start;
+
+            let records = &chunk[start..end];
+            let mut data = Vec::new();
+
+            for record in records {
+                data.write_u32::<LittleEndian>(record.chromosome_id)?;
+                data.write_u32::<LittleEndian>(record.start)?;
+                data.write_u32::<LittleEndian>(record.end)?;
+                data.extend_from_slice(record.rest.as_bytes());
+                data.push(0);
+            }
+
+            blocks.push(Block {
+                chromosome_id,
+                start: records[0].start,
+                end: records.iter().map(|record| record.end).max().unwrap_or(0),
+                data,
+            });
+
+            start = end;
+        }
+    }
+
+    Ok(blocks)
+}
+
+// Converts sorted records to intervals of constant, nonzero coverage depth.
+fn build_depth_intervals(records: &[Record]) -> Vec<Interval> {
+    let mut intervals = Vec::new();
+    let mut start = 0;
+
+    while start < records.len() {
+        let chromosome_id = records[start].chromosome_id;
+
+        let end = records[start..]
+            .iter()
+            .take_while(|record| record.chromosome_id == chromosome_id)
+            .count()
+            + start;
+
+        let mut events: Vec<(u32, i32)> = records[start..end]
+            .iter()
+            .flat_map(|record| vec![(record.start, 1), (record.end, -1)])
+            .collect();
+
+        events.sort_unstable();
+
+        let mut depth = 0;
+        let mut prev_position = 0;
+
+        for (position, delta) in events {
+            if depth > 0 && position > prev_position {
+                intervals.push(Interval {
+                    chromosome_id,
+                    start: prev_position,
+                    end: position,
+                    value: depth as f32,
+                });
+            }
+
+            depth += delta;
+            prev_position = position;
+        }
+
+        start = end;
+    }
+
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_record(start: u32, end: u32) -> Record {
+        Record {
+            chromosome_id: 0,
+            start,
+            end,
+            rest: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_count_fields() {
+        assert_eq!(count_fields(""), 3);
+        assert_eq!(count_fields("r0"), 4);
+        assert_eq!(count_fields("r0\t0\t+"), 6);
+    }
+
+    #[test]
+    fn test_build_depth_intervals() {
+        let records = [build_record(0, 5), build_record(3, 8), build_record(10, 13)];
+
+        let actual: Vec<_> = build_depth_intervals(&records)
+            .into_iter()
+            .map(|interval| (interval.start, interval.end, interval.value))
+            .collect();
+
+        let expected = [(0, 3, 1.0), (3, 5, 2.0), (5, 8, 1.0), (10, 13, 1.0)];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_finish() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+        writer.write_record("sq0", 0, 5, "r0\t0\t+")?;
+        writer.write_record("sq0", 3, 8, "r1\t0\t-")?;
+
+        let data = writer.finish()?;
+
+        assert_eq!(&data[..4], &bbi::BIGBED_MAGIC.to_le_bytes());
+        assert_eq!(&data[data.len() - 4..], &bbi::BIGBED_MAGIC.to_le_bytes());
+
+        // field count, defined field count
+        assert_eq!(&data[32..36], [6, 0, 6, 0]);
+
+        Ok(())
+    }
+}
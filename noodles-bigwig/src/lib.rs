@@ -0,0 +1,31 @@
+#![deny(missing_docs)]
+
+//! **noodles-bigwig** handles the writing of the bigWig and bigBed formats.
+//!
+//! bigWig and bigBed files are indexed, compressed binary forms of bedGraph and BED files,
+//! respectively. Each file includes a chromosome B+ tree, an R-tree index of its data sections,
+//! and precomputed zoom levels that summarize the data at lower resolutions.
+//!
+//! # Examples
+//!
+//! ## Write a bigWig file
+//!
+//! ```no_run
+//! # use std::{fs::File, io};
+//! use noodles_bigwig as bigwig;
+//!
+//! let chromosomes = vec![(String::from("sq0"), 13)];
+//! let mut writer = File::create("sample.bw").map(|f| bigwig::Writer::new(f, chromosomes))?;
+//!
+//! writer.write_interval("sq0", 0, 5, 2.0)?;
+//! writer.write_interval("sq0", 5, 13, 1.0)?;
+//!
+//! writer.finish()?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+mod bbi;
+pub mod bigbed;
+mod writer;
+
+pub use self::writer::Writer;
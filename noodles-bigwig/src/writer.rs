@@ -0,0 +1,385 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use super::bbi::{self, Block, Chromosome, Interval};
+
+const SECTION_HEADER_SIZE: usize = 24;
+const BED_GRAPH_ITEM_SIZE: usize = 12;
+
+// bedGraph section type
+const BED_GRAPH: u8 = 1;
+
+/// A bigWig writer.
+///
+/// Intervals can be written in any order. They are buffered in memory and written, along with
+/// the index and zoom levels, when the writer is finished.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_bigwig as bigwig;
+///
+/// let mut writer = bigwig::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+/// writer.write_interval("sq0", 0, 5, 2.0)?;
+/// writer.write_interval("sq0", 5, 13, 1.0)?;
+///
+/// let data = writer.finish()?;
+/// assert_eq!(&data[..4], [0x26, 0xfc, 0x8f, 0x88]);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Writer<W>
+where
+    W: Write,
+{
+    inner: W,
+    chromosomes: Vec<Chromosome>,
+    chromosome_ids: HashMap<String, u32>,
+    intervals: Vec<Interval>,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Creates a bigWig writer.
+    ///
+    /// `chromosomes` is the list of reference sequence names and lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bigwig as bigwig;
+    /// let writer = bigwig::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// ```
+    pub fn new(inner: W, chromosomes: Vec<(String, u32)>) -> Self {
+        let chromosome_ids = build_chromosome_ids(&chromosomes);
+
+        Self {
+            inner,
+            chromosomes,
+            chromosome_ids,
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bigwig as bigwig;
+    /// let writer = bigwig::Writer::new(Vec::new(), Vec::new());
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Adds an interval with a value.
+    ///
+    /// The interval is 0-based and half-open, i.e., the same as a bedGraph record.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the reference sequence name is not in the chromosome list, the
+    /// interval is empty, or the interval ends past the end of the reference sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bigwig as bigwig;
+    /// let mut writer = bigwig::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// writer.write_interval("sq0", 0, 5, 2.0)?;
+    /// assert!(writer.write_interval("sq1", 0, 5, 2.0).is_err());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_interval(
+        &mut self,
+        name: &str,
+        start: u32,
+        end: u32,
+        value: f32,
+    ) -> io::Result<()> {
+        let chromosome_id =
+            resolve_chromosome_id(&self.chromosomes, &self.chromosome_ids, name, start, end)?;
+
+        self.intervals.push(Interval {
+            chromosome_id,
+            start,
+            end,
+            value,
+        });
+
+        Ok(())
+    }
+
+    /// Writes the bigWig file and returns the underlying writer.
+    ///
+    /// Intervals are sorted before they are written. This fails if any intervals on the same
+    /// chromosome overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bigwig as bigwig;
+    /// let writer = bigwig::Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+    /// let data = writer.finish()?;
+    /// assert!(!data.is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn finish(mut self) -> io::Result<W> {
+        self.intervals
+            .sort_by_key(|interval| (interval.chromosome_id, interval.start, interval.end));
+
+        validate_intervals(&self.chromosomes, &self.intervals)?;
+
+        let blocks = build_blocks(&self.intervals)?;
+
+        let file = bbi::File {
+            magic: bbi::BIGWIG_MAGIC,
+            chromosomes: &self.chromosomes,
+            field_count: 0,
+            defined_field_count: 0,
+            auto_sql: None,
+            data_count: blocks.len() as u64,
+            blocks,
+            intervals: self.intervals,
+        };
+
+        bbi::write_file(&mut self.inner, file)?;
+
+        Ok(self.inner)
+    }
+}
+
+// Checks that sorted intervals do not overlap.
+fn validate_intervals(chromosomes: &[Chromosome], intervals: &[Interval]) -> io::Result<()> {
+    for pair in intervals.windows(2) {
+        let (previous, interval) = (&pair[0], &pair[1]);
+
+        if interval.chromosome_id == previous.chromosome_id && interval.start < previous.end {
+            let (name, _) = &chromosomes[interval.chromosome_id as usize];
+
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "overlapping intervals: [{}, {}) and [{}, {}): {}",
+                    previous.start, previous.end, interval.start, interval.end, name
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn build_chromosome_ids(chromosomes: &[Chromosome]) -> HashMap<String, u32> {
+    chromosomes
+        .iter()
+        .enumerate()
+        .map(|(id, (name, _))| (name.clone(), id as u32))
+        .collect()
+}
+
+pub(crate) fn resolve_chromosome_id(
+    chromosomes: &[Chromosome],
+    chromosome_ids: &HashMap<String, u32>,
+    name: &str,
+    start: u32,
+    end: u32,
+) -> io::Result<u32> {
+    let chromosome_id = chromosome_ids.get(name).copied().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid chromosome name: {}", name),
+        )
+    })?;
+
+    if start >= end {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid interval: [{}, {})", start, end),
+        ));
+    }
+
+    let len = chromosomes[chromosome_id as usize].1;
+
+    if end > len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "interval end ({}) exceeds chromosome length ({}): {}",
+                end, len, name
+            ),
+        ));
+    }
+
+    Ok(chromosome_id)
+}
+
+// Groups sorted intervals into bedGraph sections of the same chromosome.
+fn build_blocks(intervals: &[Interval]) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+
+    for chunk in intervals.chunks(bbi::ITEMS_PER_SLOT) {
+        let mut start = 0;
+
+        while start < chunk.len() {
+            let chromosome_id = chunk[start].chromosome_id;
+
+            let end = chunk[start..]
+                .iter()
+                .take_while(|interval| interval.chromosome_id == chromosome_id)
+                .count()
+                + start;
+
+            blocks.push(build_block(&chunk[start..end])?);
+
+            start = end;
+        }
+    }
+
+    Ok(blocks)
+}
+
+fn build_block(intervals: &[Interval]) -> io::Result<Block> {
+    let chromosome_id = intervals[0].chromosome_id;
+    let start = intervals[0].start;
+    let end = intervals
+        .iter()
+        .map(|interval| interval.end)
+        .max()
+        .unwrap_or(start);
+
+    let mut data = Vec::with_capacity(SECTION_HEADER_SIZE + BED_GRAPH_ITEM_SIZE * intervals.len());
+
+    data.write_u32::<LittleEndian>(chromosome_id)?;
+    data.write_u32::<LittleEndian>(start)?;
+    data.write_u32::<LittleEndian>(end)?;
+    // item step
+    data.write_u32::<LittleEndian>(0)?;
+    // item span
+    data.write_u32::<LittleEndian>(0)?;
+    data.write_u8(BED_GRAPH)?;
+    // reserved
+    data.write_u8(0)?;
+    data.write_u16::<LittleEndian>(intervals.len() as u16)?;
+
+    for interval in intervals {
+        data.write_u32::<LittleEndian>(interval.start)?;
+        data.write_u32::<LittleEndian>(interval.end)?;
+        data.write_f32::<LittleEndian>(interval.value)?;
+    }
+
+    Ok(Block {
+        chromosome_id,
+        start,
+        end,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryInto, io::Read};
+
+    use flate2::read::ZlibDecoder;
+
+    use super::*;
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn read_u64(buf: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_write_interval_with_invalid_intervals() {
+        let mut writer = Writer::new(Vec::new(), vec![(String::from("sq0"), 13)]);
+
+        assert!(writer.write_interval("sq1", 0, 5, 1.0).is_err());
+        assert!(writer.write_interval("sq0", 5, 5, 1.0).is_err());
+        assert!(writer.write_interval("sq0", 5, 21, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_finish_with_overlapping_intervals() -> io::Result<()> {
+        let mut writer = Writer::new(
+            Vec::new(),
+            vec![(String::from("sq0"), 13), (String::from("sq1"), 21)],
+        );
+
+        writer.write_interval("sq0", 5, 13, 1.0)?;
+        writer.write_interval("sq0", 0, 8, 2.0)?;
+
+        assert!(matches!(
+            writer.finish(),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        // Adjacent intervals and intervals on different chromosomes do not overlap.
+        let mut writer = Writer::new(
+            Vec::new(),
+            vec![(String::from("sq0"), 13), (String::from("sq1"), 21)],
+        );
+
+        writer.write_interval("sq0", 5, 13, 1.0)?;
+        writer.write_interval("sq0", 0, 5, 2.0)?;
+        writer.write_interval("sq1", 0, 8, 3.0)?;
+
+        assert!(writer.finish().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish() -> io::Result<()> {
+        let mut writer = Writer::new(
+            Vec::new(),
+            vec![(String::from("sq0"), 13), (String::from("sq1"), 21)],
+        );
+
+        writer.write_interval("sq1", 0, 8, 3.0)?;
+        writer.write_interval("sq0", 5, 13, 1.0)?;
+        writer.write_interval("sq0", 0, 5, 2.0)?;
+
+        let data = writer.finish()?;
+
+        assert_eq!(read_u32(&data, 0), bbi::BIGWIG_MAGIC);
+        assert_eq!(read_u32(&data, data.len() - 4), bbi::BIGWIG_MAGIC);
+
+        // total summary
+        let total_summary_offset = read_u64(&data, 44) as usize;
+        assert_eq!(read_u64(&data, total_summary_offset), 21);
+
+        // section count
+        let full_data_offset = read_u64(&data, 16) as usize;
+        assert_eq!(read_u64(&data, full_data_offset), 2);
+
+        // The first section is sq0, sorted by start.
+        let mut decoder = ZlibDecoder::new(&data[full_data_offset + 8..]);
+        let mut section = Vec::new();
+        decoder.read_to_end(&mut section)?;
+
+        assert_eq!(read_u32(&section, 0), 0);
+        assert_eq!(read_u32(&section, 4), 0);
+        assert_eq!(read_u32(&section, 8), 13);
+        assert_eq!(section[20], BED_GRAPH);
+        assert_eq!(u16::from_le_bytes([section[22], section[23]]), 2);
+        assert_eq!(read_u32(&section, 24), 0);
+        assert_eq!(read_u32(&section, 28), 5);
+        assert_eq!(read_u32(&section, 36), 5);
+
+        Ok(())
+    }
+}
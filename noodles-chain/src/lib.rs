@@ -0,0 +1,36 @@
+#![deny(missing_docs)]
+
+//! **noodles-chain** handles the reading of the UCSC chain format and coordinate liftover.
+//!
+//! A chain file describes pairwise alignments between two assemblies. They are used to lift
+//! positions, intervals, regions, and VCF records from an original assembly to a new assembly.
+//!
+//! # Examples
+//!
+//! ## Lift a region
+//!
+//! ```no_run
+//! # use std::{fs::File, io::{self, BufReader}};
+//! use noodles::Region;
+//! use noodles_chain::{self as chain, Liftover};
+//!
+//! let mut reader = File::open("hg19ToHg38.over.chain")
+//!     .map(BufReader::new)
+//!     .map(chain::Reader::new)?;
+//!
+//! let records = reader.records().collect::<io::Result<_>>()?;
+//! let liftover = Liftover::new(records);
+//!
+//! let region = Region::mapped("chr1", 1000000, 1000100);
+//!
+//! if let Some(lifted_region) = liftover.lift_region(&region) {
+//!     println!("{}", lifted_region);
+//! }
+//! # Ok::<(), io::Error>(())
+//! ```
+
+pub mod liftover;
+pub mod reader;
+pub mod record;
+
+pub use self::{liftover::Liftover, reader::Reader, record::Record};
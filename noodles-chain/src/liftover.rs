@@ -0,0 +1,700 @@
+//! Coordinate liftover between assemblies.
+
+use std::{
+    cmp::{self, Ordering, Reverse},
+    collections::HashMap,
+};
+
+use noodles::Region;
+use noodles_core::Strand;
+use noodles_vcf::{
+    self as vcf,
+    record::{alternate_bases::Allele, reference_bases::Base, AlternateBases, Chromosome},
+};
+
+use super::Record;
+
+const DEFAULT_MIN_MATCH: f64 = 0.95;
+
+/// A lifted position.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Position {
+    name: String,
+    position: i64,
+    strand: Strand,
+}
+
+impl Position {
+    /// Returns the name of the reference sequence in the new assembly.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the 1-based position in the new assembly.
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns the strand of the new position relative to the original position.
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+}
+
+/// A lifted interval.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interval {
+    name: String,
+    start: i64,
+    end: i64,
+    strand: Strand,
+}
+
+impl Interval {
+    /// Returns the name of the reference sequence in the new assembly.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the 1-based start position in the new assembly.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the 1-based end position in the new assembly.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns the strand of the new interval relative to the original interval.
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+}
+
+// An ungapped block in absolute coordinates, 0-based.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    target_start: i64,
+    query_start: i64,
+    len: i64,
+}
+
+impl Segment {
+    fn target_end(&self) -> i64 {
+        self.target_start + self.len
+    }
+}
+
+#[derive(Debug)]
+struct Chain {
+    score: i64,
+    target_start: i64,
+    target_end: i64,
+    query_name: String,
+    query_size: i64,
+    strand: Strand,
+    segments: Vec<Segment>,
+}
+
+impl Chain {
+    fn from_record(record: Record) -> Self {
+        let target = record.target();
+        let query = record.query();
+
+        let mut target_position = target.start();
+        let mut query_position = query.start();
+        let mut segments = Vec::with_capacity(record.blocks().len());
+
+        for block in record.blocks() {
+            segments.push(Segment {
+                target_start: target_position,
+                query_start: query_position,
+                len: block.size(),
+            });
+
+            target_position += block.size() + block.target_gap();
+            query_position += block.size() + block.query_gap();
+        }
+
+        Self {
+            score: record.score(),
+            target_start: target.start(),
+            target_end: target.end(),
+            query_name: query.name().into(),
+            query_size: query.size(),
+            strand: query.strand(),
+            segments,
+        }
+    }
+
+    // Returns the index of the first segment that ends after the 0-based position.
+    fn first_segment_ending_after(&self, position: i64) -> usize {
+        self.segments
+            .binary_search_by(|segment| {
+                if segment.target_end() <= position {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .unwrap_or_else(|i| i)
+    }
+
+    // Converts a 0-based, half-open query interval on the chain strand to the forward strand.
+    fn to_forward_strand(&self, start: i64, end: i64) -> (i64, i64) {
+        if self.strand == Strand::Reverse {
+            (self.query_size - end, self.query_size - start)
+        } else {
+            (start, end)
+        }
+    }
+}
+
+// The alignment of a target interval by a single chain.
+struct Mapping<'a> {
+    chain: &'a Chain,
+    query_start: i64,
+    query_end: i64,
+    matched_len: i64,
+}
+
+/// A liftover engine.
+///
+/// This maps coordinates in an original (target) assembly to a new (query) assembly using chain
+/// alignments. When multiple chains align a position, the chain with the highest score is used.
+#[derive(Debug)]
+pub struct Liftover {
+    chains: HashMap<String, Vec<Chain>>,
+    min_match: f64,
+}
+
+impl Liftover {
+    /// Creates a liftover engine from chain records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain::Liftover;
+    /// let liftover = Liftover::new(Vec::new());
+    /// ```
+    pub fn new(records: Vec<Record>) -> Self {
+        let mut chains: HashMap<String, Vec<Chain>> = HashMap::new();
+
+        for record in records {
+            let name = record.target().name().to_string();
+            chains
+                .entry(name)
+                .or_default()
+                .push(Chain::from_record(record));
+        }
+
+        for name_chains in chains.values_mut() {
+            name_chains.sort_by_key(|chain| Reverse(chain.score));
+        }
+
+        Self {
+            chains,
+            min_match: DEFAULT_MIN_MATCH,
+        }
+    }
+
+    /// Sets the minimum fraction of bases of an interval that must be aligned by a chain.
+    ///
+    /// By default, this is 0.95.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain::Liftover;
+    /// let liftover = Liftover::new(Vec::new()).set_min_match(0.5);
+    /// ```
+    pub fn set_min_match(mut self, min_match: f64) -> Self {
+        self.min_match = min_match;
+        self
+    }
+
+    /// Lifts a position to the new assembly.
+    ///
+    /// `position` is 1-based. This returns `None` if the position is not aligned, i.e., it is not
+    /// covered by a chain or it falls into a gap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_chain::{self as chain, Liftover};
+    /// use noodles_core::Strand;
+    ///
+    /// let data = b"chain 13 chr1 13 + 0 13 1 13 - 0 13 1\n13\n";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// let records = reader.records().collect::<io::Result<_>>()?;
+    ///
+    /// let liftover = Liftover::new(records);
+    ///
+    /// let position = liftover.lift_position("chr1", 3).unwrap();
+    /// assert_eq!(position.name(), "1");
+    /// assert_eq!(position.position(), 11);
+    /// assert_eq!(position.strand(), Strand::Reverse);
+    ///
+    /// assert!(liftover.lift_position("chr2", 3).is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn lift_position(&self, name: &str, position: i64) -> Option<Position> {
+        let p = position - 1;
+
+        for chain in self.chains.get(name)? {
+            if p < chain.target_start || p >= chain.target_end {
+                continue;
+            }
+
+            let i = chain.first_segment_ending_after(p);
+
+            if let Some(segment) = chain.segments.get(i) {
+                if segment.target_start <= p {
+                    let q = segment.query_start + (p - segment.target_start);
+                    let (start, _) = chain.to_forward_strand(q, q + 1);
+
+                    return Some(Position {
+                        name: chain.query_name.clone(),
+                        position: start + 1,
+                        strand: chain.strand,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Lifts an interval to the new assembly.
+    ///
+    /// `start` and `end` are 1-based and inclusive. The chain that aligns the most bases of the
+    /// interval is used, and the lifted interval spans the first to last aligned base. This
+    /// returns `None` if the fraction of aligned bases is less than the minimum match (see
+    /// [`Self::set_min_match`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_chain::{self as chain, Liftover};
+    /// use noodles_core::Strand;
+    ///
+    /// let data = b"chain 13 chr1 13 + 0 13 1 15 + 0 15 1\n5\t0\t2\n8\n";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// let records = reader.records().collect::<io::Result<_>>()?;
+    ///
+    /// let liftover = Liftover::new(records);
+    ///
+    /// let interval = liftover.lift_interval("chr1", 4, 8).unwrap();
+    /// assert_eq!(interval.name(), "1");
+    /// assert_eq!((interval.start(), interval.end()), (4, 10));
+    /// assert_eq!(interval.strand(), Strand::Forward);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn lift_interval(&self, name: &str, start: i64, end: i64) -> Option<Interval> {
+        let len = end - start + 1;
+
+        if len <= 0 {
+            return None;
+        }
+
+        let mapping = self.map_interval(name, start - 1, end)?;
+
+        if (mapping.matched_len as f64) < self.min_match * (len as f64) {
+            return None;
+        }
+
+        Some(build_interval(&mapping))
+    }
+
+    /// Lifts a region to the new assembly.
+    ///
+    /// Mapped regions are lifted as intervals (see [`Self::lift_interval`]). Unmapped and all
+    /// regions are independent of the assembly and are returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles::Region;
+    /// use noodles_chain::{self as chain, Liftover};
+    ///
+    /// let data = b"chain 13 chr1 13 + 0 13 1 13 + 0 13 1\n13\n";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// let records = reader.records().collect::<io::Result<_>>()?;
+    ///
+    /// let liftover = Liftover::new(records);
+    ///
+    /// let region = Region::mapped("chr1", 5, 8);
+    /// assert_eq!(liftover.lift_region(&region), Some(Region::mapped("1", 5, 8)));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn lift_region(&self, region: &Region) -> Option<Region> {
+        match region {
+            Region::Mapped { name, start, end } => self
+                .lift_interval(name, *start, *end)
+                .map(|interval| Region::mapped(interval.name, interval.start, interval.end)),
+            Region::Unmapped | Region::All => Some(region.clone()),
+        }
+    }
+
+    /// Lifts a VCF record to the new assembly.
+    ///
+    /// All reference bases must be aligned without gaps. On the reverse strand, the position is
+    /// moved to the start of the reverse complemented reference bases, and the reference and
+    /// alternate bases are reverse complemented.
+    ///
+    /// This returns `None` if the record cannot be lifted: the reference bases are not fully
+    /// aligned, the chromosome is a symbol, or the record is lifted to the reverse strand and has
+    /// an allele that is not a base substitution of the same length, e.g., an indel, which must
+    /// be renormalized against the new reference sequence. Other fields are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_chain::{self as chain, Liftover};
+    /// use noodles_vcf as vcf;
+    ///
+    /// let data = b"chain 13 chr1 13 + 0 13 1 13 - 0 13 1\n13\n";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// let records = reader.records().collect::<io::Result<_>>()?;
+    ///
+    /// let liftover = Liftover::new(records);
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("chr1".parse()?)
+    ///     .set_position(3)
+    ///     .set_reference_bases("AC".parse()?)
+    ///     .set_alternate_bases("GT".parse()?)
+    ///     .build()?;
+    ///
+    /// let lifted_record = liftover.lift_record(&record).unwrap();
+    /// assert_eq!(lifted_record.chromosome().to_string(), "1");
+    /// assert_eq!(lifted_record.position(), 10);
+    /// assert_eq!(lifted_record.reference_bases().to_string(), "GT");
+    /// assert_eq!(lifted_record.alternate_bases().to_string(), "AC");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lift_record(&self, record: &vcf::Record) -> Option<vcf::Record> {
+        let name = match record.chromosome() {
+            Chromosome::Name(name) => name,
+            Chromosome::Symbol(_) => return None,
+        };
+
+        let len = record.reference_bases().len() as i64;
+        let start = record.position() - 1;
+        let mapping = self.map_interval(name, start, start + len)?;
+
+        if mapping.matched_len != len || mapping.query_end - mapping.query_start != len {
+            return None;
+        }
+
+        let interval = build_interval(&mapping);
+
+        let (reference_bases, alternate_bases) = if interval.strand == Strand::Reverse {
+            let reference_bases = reverse_complement(record.reference_bases());
+
+            let alleles = record
+                .alternate_bases()
+                .iter()
+                .map(|allele| match allele {
+                    Allele::Bases(bases) if bases.len() == reference_bases.len() => {
+                        Some(Allele::Bases(reverse_complement(bases)))
+                    }
+                    Allele::OverlappingDeletion => Some(Allele::OverlappingDeletion),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            (reference_bases, AlternateBases::from(alleles))
+        } else {
+            (
+                record.reference_bases().to_vec(),
+                record.alternate_bases().clone(),
+            )
+        };
+
+        let mut builder = vcf::Record::builder()
+            .set_chromosome(Chromosome::Name(interval.name))
+            .set_position(interval.start)
+            .set_ids(record.ids().clone())
+            .set_alternate_bases(alternate_bases)
+            .set_quality_score(record.quality_score())
+            .set_filter_status(record.filter_status().clone())
+            .set_info(record.info().clone())
+            .set_genotypes(record.genotypes().to_vec());
+
+        for base in reference_bases {
+            builder = builder.add_reference_base(base);
+        }
+
+        if let Some(format) = record.format() {
+            builder = builder.set_format(format.clone());
+        }
+
+        builder.build().ok()
+    }
+
+    // Finds the chain that aligns the most bases of a 0-based, half-open interval.
+    fn map_interval(&self, name: &str, start: i64, end: i64) -> Option<Mapping<'_>> {
+        let mut best: Option<Mapping<'_>> = None;
+
+        for chain in self.chains.get(name)? {
+            if end <= chain.target_start || start >= chain.target_end {
+                continue;
+            }
+
+            let mut query_start = i64::MAX;
+            let mut query_end = i64::MIN;
+            let mut matched_len = 0;
+
+            let i = chain.first_segment_ending_after(start);
+
+            for segment in &chain.segments[i..] {
+                if segment.target_start >= end {
+                    break;
+                }
+
+                let overlap_start = cmp::max(start, segment.target_start);
+                let overlap_end = cmp::min(end, segment.target_end());
+
+                let offset = segment.query_start - segment.target_start;
+                query_start = cmp::min(query_start, overlap_start + offset);
+                query_end = cmp::max(query_end, overlap_end + offset);
+                matched_len += overlap_end - overlap_start;
+            }
+
+            let is_better = match &best {
+                Some(mapping) => matched_len > mapping.matched_len,
+                None => matched_len > 0,
+            };
+
+            if is_better {
+                best = Some(Mapping {
+                    chain,
+                    query_start,
+                    query_end,
+                    matched_len,
+                });
+            }
+        }
+
+        best
+    }
+}
+
+fn build_interval(mapping: &Mapping<'_>) -> Interval {
+    let chain = mapping.chain;
+    let (start, end) = chain.to_forward_strand(mapping.query_start, mapping.query_end);
+
+    Interval {
+        name: chain.query_name.clone(),
+        start: start + 1,
+        end,
+        strand: chain.strand,
+    }
+}
+
+fn reverse_complement(bases: &[Base]) -> Vec<Base> {
+    bases
+        .iter()
+        .rev()
+        .map(|base| match base {
+            Base::A => Base::T,
+            Base::C => Base::G,
+            Base::G => Base::C,
+            Base::T => Base::A,
+            Base::N => Base::N,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Block, Sequence};
+
+    use super::*;
+
+    fn build_record(
+        score: i64,
+        query_strand: Strand,
+        query_size: i64,
+        query_start: i64,
+        blocks: Vec<Block>,
+    ) -> Record {
+        let (target_len, query_len) = blocks.iter().fold((0, 0), |(t, q), block| {
+            (
+                t + block.size() + block.target_gap(),
+                q + block.size() + block.query_gap(),
+            )
+        });
+
+        Record::new(
+            score,
+            Sequence::new(
+                String::from("chr1"),
+                100,
+                Strand::Forward,
+                10,
+                10 + target_len,
+            ),
+            Sequence::new(
+                String::from("1"),
+                query_size,
+                query_strand,
+                query_start,
+                query_start + query_len,
+            ),
+            None,
+            blocks,
+        )
+    }
+
+    #[test]
+    fn test_lift_position() {
+        // target [10, 20) -> query [0, 10), target gap [20, 25), target [25, 35) -> query
+        // [10, 20)
+        let record = build_record(
+            100,
+            Strand::Forward,
+            20,
+            0,
+            vec![Block::new(10, 5, 0), Block::new(10, 0, 0)],
+        );
+        let liftover = Liftover::new(vec![record]);
+
+        let lift = |position| {
+            liftover
+                .lift_position("chr1", position)
+                .map(|p| p.position())
+        };
+
+        assert_eq!(lift(10), None);
+        assert_eq!(lift(11), Some(1));
+        assert_eq!(lift(20), Some(10));
+        assert_eq!(lift(21), None);
+        assert_eq!(lift(25), None);
+        assert_eq!(lift(26), Some(11));
+        assert_eq!(lift(35), Some(20));
+        assert_eq!(lift(36), None);
+    }
+
+    #[test]
+    fn test_lift_position_on_reverse_strand() {
+        let record = build_record(100, Strand::Reverse, 50, 5, vec![Block::new(10, 0, 0)]);
+        let liftover = Liftover::new(vec![record]);
+
+        // target 0-based 10 -> query (reverse) 5 -> query (forward) 50 - 5 - 1 = 44
+        let position = liftover.lift_position("chr1", 11).unwrap();
+        assert_eq!(position.position(), 45);
+        assert_eq!(position.strand(), Strand::Reverse);
+    }
+
+    #[test]
+    fn test_lift_position_with_overlapping_chains() {
+        let low = build_record(10, Strand::Forward, 100, 50, vec![Block::new(10, 0, 0)]);
+        let high = build_record(20, Strand::Forward, 100, 0, vec![Block::new(10, 0, 0)]);
+        let liftover = Liftover::new(vec![low, high]);
+
+        let position = liftover.lift_position("chr1", 11).unwrap();
+        assert_eq!(position.position(), 1);
+    }
+
+    #[test]
+    fn test_lift_interval() {
+        let record = build_record(
+            100,
+            Strand::Forward,
+            20,
+            0,
+            vec![Block::new(10, 5, 0), Block::new(10, 0, 0)],
+        );
+
+        let liftover = Liftover::new(vec![record]);
+
+        // 20 of 25 bases are aligned.
+        assert!(liftover.lift_interval("chr1", 11, 35).is_none());
+
+        let liftover = liftover.set_min_match(0.8);
+        let interval = liftover.lift_interval("chr1", 11, 35).unwrap();
+        assert_eq!((interval.start(), interval.end()), (1, 20));
+
+        assert!(liftover.lift_interval("chr1", 21, 25).is_none());
+        assert!(liftover.lift_interval("chr1", 8, 5).is_none());
+    }
+
+    #[test]
+    fn test_lift_interval_on_reverse_strand() {
+        let record = build_record(100, Strand::Reverse, 50, 5, vec![Block::new(10, 0, 0)]);
+        let liftover = Liftover::new(vec![record]);
+
+        // target [10, 15) -> query (reverse) [5, 10) -> query (forward) [40, 45)
+        let interval = liftover.lift_interval("chr1", 11, 15).unwrap();
+        assert_eq!((interval.start(), interval.end()), (41, 45));
+        assert_eq!(interval.strand(), Strand::Reverse);
+    }
+
+    #[test]
+    fn test_lift_record() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_record(
+            100,
+            Strand::Forward,
+            20,
+            0,
+            vec![Block::new(10, 5, 0), Block::new(10, 0, 0)],
+        );
+        let liftover = Liftover::new(vec![record]);
+
+        let record = vcf::Record::builder()
+            .set_chromosome("chr1".parse()?)
+            .set_position(12)
+            .set_reference_bases("A".parse()?)
+            .set_alternate_bases("AT".parse()?)
+            .build()?;
+
+        let lifted_record = liftover.lift_record(&record).unwrap();
+        assert_eq!(lifted_record.chromosome().to_string(), "1");
+        assert_eq!(lifted_record.position(), 2);
+        assert_eq!(lifted_record.reference_bases(), record.reference_bases());
+        assert_eq!(lifted_record.alternate_bases(), record.alternate_bases());
+
+        // The reference bases span a gap.
+        let record = vcf::Record::builder()
+            .set_chromosome("chr1".parse()?)
+            .set_position(20)
+            .set_reference_bases("AC".parse()?)
+            .build()?;
+
+        assert!(liftover.lift_record(&record).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lift_record_with_indel_on_reverse_strand() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_record(100, Strand::Reverse, 50, 5, vec![Block::new(10, 0, 0)]);
+        let liftover = Liftover::new(vec![record]);
+
+        let record = vcf::Record::builder()
+            .set_chromosome("chr1".parse()?)
+            .set_position(12)
+            .set_reference_bases("A".parse()?)
+            .set_alternate_bases("AT".parse()?)
+            .build()?;
+
+        assert!(liftover.lift_record(&record).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_complement() {
+        assert_eq!(
+            reverse_complement(&[Base::A, Base::C, Base::G, Base::T, Base::N]),
+            [Base::N, Base::A, Base::C, Base::G, Base::T]
+        );
+    }
+}
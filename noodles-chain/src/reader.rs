@@ -0,0 +1,222 @@
+//! Chain reader and iterators.
+
+mod records;
+
+pub use self::records::Records;
+
+use std::io::{self, BufRead};
+
+use crate::record::{self, Block, Record};
+
+/// A chain reader.
+pub struct Reader<R> {
+    inner: R,
+    buf: String,
+}
+
+impl<R> Reader<R>
+where
+    R: BufRead,
+{
+    /// Creates a chain reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain as chain;
+    /// let data = b"chain 13 sq0 8 + 0 8 sq0 8 + 0 8 1\n8\n";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: String::new(),
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain as chain;
+    /// let data = b"chain 13 sq0 8 + 0 8 sq0 8 + 0 8 1\n8\n";
+    /// let reader = chain::Reader::new(&data[..]);
+    /// assert_eq!(reader.get_ref(), &&data[..]);
+    /// ```
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Reads a chain record.
+    ///
+    /// This reads the header line and all block lines of the next chain. Blank lines and comments
+    /// (`#`) before the header are skipped. The block sizes and gaps are validated against the
+    /// spans of the target and query sequences.
+    ///
+    /// If the stream reached EOF, this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_chain as chain;
+    ///
+    /// let data = b"chain 13 sq0 13 + 0 13 sq0 13 + 0 12 1
+    /// 5\t1\t0
+    /// 7
+    /// ";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    ///
+    /// let record = reader.read_record()?.unwrap();
+    /// assert_eq!(record.blocks().len(), 2);
+    ///
+    /// assert!(reader.read_record()?.is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let mut record = loop {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+
+            let line = self.buf.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            break record::parse_header(line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        };
+
+        loop {
+            if !self.read_line()? {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "unexpected EOF in chain",
+                ));
+            }
+
+            let line = self.buf.trim();
+            let is_last = line.split_whitespace().count() == 1;
+
+            let block: Block = line
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            record.blocks_mut().push(block);
+
+            if is_last {
+                break;
+            }
+        }
+
+        validate(&record)?;
+
+        Ok(Some(record))
+    }
+
+    /// Returns an iterator over chain records starting from the current stream position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_chain as chain;
+    ///
+    /// let data = b"chain 8 sq0 8 + 0 8 sq0 8 + 0 8 1
+    /// 8
+    ///
+    /// chain 5 sq1 5 + 0 5 sq1 5 - 0 5 2
+    /// 5
+    /// ";
+    /// let mut reader = chain::Reader::new(&data[..]);
+    /// let mut records = reader.records();
+    ///
+    /// assert_eq!(records.next().transpose()?.map(|r| r.id()), Some(Some(1)));
+    /// assert_eq!(records.next().transpose()?.map(|r| r.id()), Some(Some(2)));
+    /// assert!(records.next().is_none());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records::new(self)
+    }
+
+    fn read_line(&mut self) -> io::Result<bool> {
+        self.buf.clear();
+        self.inner.read_line(&mut self.buf).map(|n| n > 0)
+    }
+}
+
+fn validate(record: &Record) -> io::Result<()> {
+    let (target_len, query_len) = record.blocks().iter().fold((0, 0), |(t, q), block| {
+        (
+            t + block.size() + block.target_gap(),
+            q + block.size() + block.query_gap(),
+        )
+    });
+
+    let target = record.target();
+    let query = record.query();
+
+    if target_len != target.end() - target.start() || query_len != query.end() - query.start() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "chain blocks do not match the alignment spans",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_record() -> io::Result<()> {
+        let data = b"\
+# comment
+chain 4900 chrY 58368225 + 25985403 25985638 chr5 151006098 - 43257292 43257528 1
+9\t1\t0
+10\t0\t5
+61\t4\t0
+16\t0\t4
+42\t3\t0
+16\t0\t8
+14\t1\t0
+3\t7\t0
+48
+
+";
+
+        let mut reader = Reader::new(&data[..]);
+
+        let record = reader.read_record()?.unwrap();
+        assert_eq!(record.score(), 4900);
+        assert_eq!(record.target().name(), "chrY");
+        assert_eq!(record.query().name(), "chr5");
+        assert_eq!(record.blocks().len(), 9);
+        assert_eq!(record.blocks()[0], Block::new(9, 1, 0));
+        assert_eq!(record.blocks()[8], Block::new(48, 0, 0));
+
+        assert!(reader.read_record()?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record_with_invalid_blocks() {
+        let data = b"chain 8 sq0 8 + 0 8 sq0 8 + 0 8 1\n5\n";
+        let mut reader = Reader::new(&data[..]);
+        assert!(reader.read_record().is_err());
+
+        let data = b"chain 8 sq0 8 + 0 8 sq0 8 + 0 8 1\n5\t1\t1\n";
+        let mut reader = Reader::new(&data[..]);
+        assert!(matches!(
+            reader.read_record(),
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+}
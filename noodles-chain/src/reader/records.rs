@@ -0,0 +1,32 @@
+use std::io::{self, BufRead};
+
+use crate::Record;
+
+use super::Reader;
+
+/// An iterator over records of a chain reader.
+///
+/// This is created by calling [`Reader::records`].
+pub struct Records<'a, R> {
+    reader: &'a mut Reader<R>,
+}
+
+impl<'a, R> Records<'a, R>
+where
+    R: BufRead,
+{
+    pub(crate) fn new(reader: &'a mut Reader<R>) -> Self {
+        Self { reader }
+    }
+}
+
+impl<'a, R> Iterator for Records<'a, R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_record().transpose()
+    }
+}
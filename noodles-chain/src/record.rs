@@ -0,0 +1,355 @@
+//! Chain record and fields.
+
+use std::{error, fmt, num, str::FromStr};
+
+use noodles_core::Strand;
+
+const HEADER_PREFIX: &str = "chain";
+
+/// A chain record.
+///
+/// A chain describes a pairwise alignment between a target (`t`, the original assembly) and a
+/// query (`q`, the new assembly) as a list of ungapped blocks separated by gaps.
+///
+/// Coordinates are 0-based and half-open. Query coordinates of a chain on the reverse strand are
+/// relative to the reverse complement of the query sequence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    score: i64,
+    target: Sequence,
+    query: Sequence,
+    id: Option<u64>,
+    blocks: Vec<Block>,
+}
+
+impl Record {
+    /// Creates a chain record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain::record::{Block, Record, Sequence};
+    /// use noodles_core::Strand;
+    ///
+    /// let target = Sequence::new(String::from("chr1"), 21, Strand::Forward, 0, 13);
+    /// let query = Sequence::new(String::from("1"), 21, Strand::Forward, 0, 13);
+    /// let record = Record::new(8, target, query, None, vec![Block::new(13, 0, 0)]);
+    /// ```
+    pub fn new(
+        score: i64,
+        target: Sequence,
+        query: Sequence,
+        id: Option<u64>,
+        blocks: Vec<Block>,
+    ) -> Self {
+        Self {
+            score,
+            target,
+            query,
+            id,
+            blocks,
+        }
+    }
+
+    /// Returns the alignment score.
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// Returns the target (original) sequence of the alignment.
+    pub fn target(&self) -> &Sequence {
+        &self.target
+    }
+
+    /// Returns the query (new) sequence of the alignment.
+    pub fn query(&self) -> &Sequence {
+        &self.query
+    }
+
+    /// Returns the chain ID.
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    /// Returns the ungapped blocks of the alignment.
+    ///
+    /// The gaps of the last block are 0.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    pub(crate) fn blocks_mut(&mut self) -> &mut Vec<Block> {
+        &mut self.blocks
+    }
+}
+
+/// A chain sequence, i.e., one side of the alignment.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sequence {
+    name: String,
+    size: i64,
+    strand: Strand,
+    start: i64,
+    end: i64,
+}
+
+impl Sequence {
+    /// Creates a chain sequence.
+    ///
+    /// `start` and `end` are 0-based and relative to `strand`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain::record::Sequence;
+    /// use noodles_core::Strand;
+    /// let sequence = Sequence::new(String::from("chr1"), 21, Strand::Forward, 0, 13);
+    /// ```
+    pub fn new(name: String, size: i64, strand: Strand, start: i64, end: i64) -> Self {
+        Self {
+            name,
+            size,
+            strand,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the name of the sequence.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the length of the entire sequence.
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    /// Returns the strand of the alignment.
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+
+    /// Returns the 0-based start position of the alignment.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the 0-based, exclusive end position of the alignment.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+}
+
+/// A chain ungapped block.
+///
+/// A block is followed by a gap in the target (`dt`) and a gap in the query (`dq`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Block {
+    size: i64,
+    target_gap: i64,
+    query_gap: i64,
+}
+
+impl Block {
+    /// Creates a chain block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_chain::record::Block;
+    /// let block = Block::new(13, 2, 0);
+    /// ```
+    pub fn new(size: i64, target_gap: i64, query_gap: i64) -> Self {
+        Self {
+            size,
+            target_gap,
+            query_gap,
+        }
+    }
+
+    /// Returns the length of the ungapped block.
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    /// Returns the length of the gap in the target following the block.
+    pub fn target_gap(&self) -> i64 {
+        self.target_gap
+    }
+
+    /// Returns the length of the gap in the query following the block.
+    pub fn query_gap(&self) -> i64 {
+        self.query_gap
+    }
+}
+
+/// An error returned when a raw chain line fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The header prefix (`chain`) is missing.
+    MissingPrefix,
+    /// A field is missing.
+    MissingField(&'static str),
+    /// A field is invalid.
+    InvalidField(&'static str, String),
+    /// A strand is invalid.
+    InvalidStrand(noodles_core::strand::ParseError),
+    /// An interval does not fit the sequence size.
+    InvalidInterval,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => write!(f, "missing prefix: expected {}", HEADER_PREFIX),
+            Self::MissingField(name) => write!(f, "missing field: {}", name),
+            Self::InvalidField(name, s) => write!(f, "invalid {}: {}", name, s),
+            Self::InvalidStrand(e) => write!(f, "invalid strand: {}", e),
+            Self::InvalidInterval => f.write_str("invalid interval"),
+        }
+    }
+}
+
+impl FromStr for Block {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let size = parse_int(fields.next(), "size")?;
+
+        let (target_gap, query_gap) = match fields.next() {
+            Some(t) => {
+                let target_gap = parse_int(Some(t), "dt")?;
+                let query_gap = parse_int(fields.next(), "dq")?;
+                (target_gap, query_gap)
+            }
+            None => (0, 0),
+        };
+
+        Ok(Self::new(size, target_gap, query_gap))
+    }
+}
+
+// Parses a chain header line, i.e., a record without blocks.
+//
+// `chain score tName tSize tStrand tStart tEnd qName qSize qStrand qStart qEnd [id]`
+pub(crate) fn parse_header(s: &str) -> Result<Record, ParseError> {
+    let mut fields = s.split_whitespace();
+
+    if fields.next() != Some(HEADER_PREFIX) {
+        return Err(ParseError::MissingPrefix);
+    }
+
+    let score = parse_int(fields.next(), "score")?;
+    let target = parse_sequence(&mut fields, "t")?;
+    let query = parse_sequence(&mut fields, "q")?;
+
+    let id = fields
+        .next()
+        .map(|t| {
+            t.parse()
+                .map_err(|_| ParseError::InvalidField("id", t.into()))
+        })
+        .transpose()?;
+
+    Ok(Record::new(score, target, query, id, Vec::new()))
+}
+
+fn parse_sequence<'a, I>(fields: &mut I, prefix: &'static str) -> Result<Sequence, ParseError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let (name_field, size_field, strand_field, start_field, end_field) = if prefix == "t" {
+        ("tName", "tSize", "tStrand", "tStart", "tEnd")
+    } else {
+        ("qName", "qSize", "qStrand", "qStart", "qEnd")
+    };
+
+    let name = fields
+        .next()
+        .map(|s| s.to_string())
+        .ok_or(ParseError::MissingField(name_field))?;
+
+    let size = parse_int(fields.next(), size_field)?;
+
+    let strand = fields
+        .next()
+        .ok_or(ParseError::MissingField(strand_field))
+        .and_then(|s| s.parse().map_err(ParseError::InvalidStrand))?;
+
+    let start = parse_int(fields.next(), start_field)?;
+    let end = parse_int(fields.next(), end_field)?;
+
+    if start < 0 || start > end || end > size {
+        return Err(ParseError::InvalidInterval);
+    }
+
+    Ok(Sequence::new(name, size, strand, start, end))
+}
+
+fn parse_int(s: Option<&str>, name: &'static str) -> Result<i64, ParseError> {
+    s.ok_or(ParseError::MissingField(name)).and_then(|t| {
+        t.parse()
+            .map_err(|_: num::ParseIntError| ParseError::InvalidField(name, t.into()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let actual = parse_header(
+            "chain 4900 chrY 58368225 + 25985403 25985638 chr5 151006098 - 43257292 43257528 1",
+        );
+
+        let expected = Record::new(
+            4900,
+            Sequence::new(
+                String::from("chrY"),
+                58368225,
+                Strand::Forward,
+                25985403,
+                25985638,
+            ),
+            Sequence::new(
+                String::from("chr5"),
+                151006098,
+                Strand::Reverse,
+                43257292,
+                43257528,
+            ),
+            Some(1),
+            Vec::new(),
+        );
+
+        assert_eq!(actual, Ok(expected));
+
+        assert_eq!(parse_header(""), Err(ParseError::MissingPrefix));
+        assert_eq!(
+            parse_header("chain 1 sq0 8 + 0 8"),
+            Err(ParseError::MissingField("qName"))
+        );
+        assert_eq!(
+            parse_header("chain 1 sq0 8 + 0 13 sq0 8 + 0 8"),
+            Err(ParseError::InvalidInterval)
+        );
+        assert_eq!(
+            parse_header("chain 1 sq0 8 + 0 8 sq0 8 + 0 8 ndls"),
+            Err(ParseError::InvalidField("id", String::from("ndls")))
+        );
+    }
+
+    #[test]
+    fn test_from_str_for_block() {
+        assert_eq!("9\t1\t0".parse(), Ok(Block::new(9, 1, 0)));
+        assert_eq!("48".parse(), Ok(Block::new(48, 0, 0)));
+        assert_eq!("9 1".parse::<Block>(), Err(ParseError::MissingField("dq")));
+        assert_eq!("".parse::<Block>(), Err(ParseError::MissingField("size")));
+    }
+}
@@ -0,0 +1,199 @@
+//! Cooperative cancellation.
+//!
+//! A [`Token`] is shared between the code iterating over records, e.g., a server request handler,
+//! and the code that may want to stop it. Wrapping any record iterator, such as a reader's
+//! `records` or `query` iterator, with [`cancellable`] checks the token before each record is read.
+
+use std::{
+    error, fmt, io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A cancellation token.
+///
+/// Clones share the same state, i.e., cancelling one cancels all of them.
+#[derive(Clone, Debug, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    /// Creates a token that is not cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::cancel::Token;
+    /// let token = Token::new();
+    /// assert!(!token.is_cancelled());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::cancel::Token;
+    ///
+    /// let token = Token::new();
+    /// let handle = token.clone();
+    /// handle.cancel();
+    ///
+    /// assert!(token.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether cancellation was requested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::cancel::Token;
+    /// let token = Token::new();
+    /// assert!(!token.is_cancelled());
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// An error returned when an operation is cancelled.
+///
+/// This is wrapped in an I/O error of kind [`io::ErrorKind::Other`]. It is not
+/// [`io::ErrorKind::Interrupted`], which readers and retry loops treat as a transient error. Use
+/// [`is_cancelled`] to test an I/O error.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cancelled;
+
+impl error::Error for Cancelled {}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl From<Cancelled> for io::Error {
+    fn from(e: Cancelled) -> Self {
+        io::Error::other(e)
+    }
+}
+
+/// Returns whether an I/O error was caused by cancellation.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use noodles_core::cancel::{self, Cancelled};
+///
+/// assert!(cancel::is_cancelled(&io::Error::from(Cancelled)));
+/// assert!(!cancel::is_cancelled(&io::Error::from(io::ErrorKind::Interrupted)));
+/// ```
+pub fn is_cancelled(error: &io::Error) -> bool {
+    error
+        .get_ref()
+        .map(|e| e.is::<Cancelled>())
+        .unwrap_or(false)
+}
+
+/// Wraps a record iterator to stop when the given token is cancelled.
+///
+/// The token is checked before each record is read. Once it is cancelled, the iterator returns a
+/// single [`Cancelled`] error and then ends.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use noodles_core::cancel::{self, Token};
+///
+/// let records = vec![Ok(1), Ok(2), Ok(3)];
+/// let token = Token::new();
+///
+/// let mut iter = cancel::cancellable(records, token.clone());
+/// assert_eq!(iter.next().transpose()?, Some(1));
+///
+/// token.cancel();
+///
+/// let result = iter.next().transpose();
+/// assert!(matches!(result, Err(e) if cancel::is_cancelled(&e)));
+/// assert!(iter.next().is_none());
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn cancellable<I, T>(records: I, token: Token) -> Cancellable<I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<T>>,
+{
+    Cancellable {
+        records: records.into_iter(),
+        token,
+        is_done: false,
+    }
+}
+
+/// An iterator that stops when its token is cancelled.
+///
+/// This is created by calling [`cancellable`].
+pub struct Cancellable<I> {
+    records: I,
+    token: Token,
+    is_done: bool,
+}
+
+impl<I, T> Iterator for Cancellable<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        if self.token.is_cancelled() {
+            self.is_done = true;
+            return Some(Err(Cancelled.into()));
+        }
+
+        self.records.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellable() {
+        let token = Token::new();
+        let records: Vec<io::Result<i32>> = vec![Ok(1), Ok(2)];
+        let values: Vec<_> = cancellable(records, token)
+            .map(|result| result.unwrap())
+            .collect();
+        assert_eq!(values, [1, 2]);
+
+        let token = Token::new();
+        token.cancel();
+        let records: Vec<io::Result<i32>> = vec![Ok(1), Ok(2)];
+        let mut iter = cancellable(records, token);
+        assert!(matches!(iter.next(), Some(Err(e)) if is_cancelled(&e)));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_is_cancelled() {
+        let error = io::Error::from(Cancelled);
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert!(is_cancelled(&error));
+
+        assert!(!is_cancelled(&io::Error::other("cancelled")));
+    }
+}
@@ -0,0 +1,322 @@
+//! Order-independent record checksums.
+//!
+//! A [`Checksum`] digests the read name, flags, sequence, and quality scores of each record and
+//! combines the digests with wrapping addition. The result does not depend on the order of the
+//! records, so it can be used to verify that a lossless transformation, e.g., sorting or
+//! converting between formats, kept all reads intact.
+//!
+//! Alignment fields (reference sequence, position, CIGAR, etc.) and data fields are not included,
+//! as they may be legitimately changed by such transformations.
+//!
+//! Checksums of disjoint sets of records can be computed independently, e.g., by multiple workers,
+//! and merged with [`Checksum::merge`].
+
+use std::{borrow::Cow, fmt, io};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A record that can be added to a checksum.
+pub trait Record {
+    /// Returns the fields of the record that are digested.
+    fn checksum_fields(&self) -> io::Result<Fields<'_>>;
+}
+
+/// The fields of a record that are digested.
+///
+/// The sequence is uppercase ASCII bases, and quality scores are raw Phred scores, i.e., without
+/// an ASCII offset. Missing sequences and quality scores are empty.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fields<'a> {
+    read_name: Cow<'a, [u8]>,
+    flags: u16,
+    sequence: Cow<'a, [u8]>,
+    quality_scores: Cow<'a, [u8]>,
+}
+
+impl<'a> Fields<'a> {
+    /// Creates record fields to digest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Fields;
+    /// let fields = Fields::new(b"r0"[..].into(), 0x04, b"ACGT"[..].into(), vec![45, 35, 43, 50].into());
+    /// ```
+    pub fn new(
+        read_name: Cow<'a, [u8]>,
+        flags: u16,
+        sequence: Cow<'a, [u8]>,
+        quality_scores: Cow<'a, [u8]>,
+    ) -> Self {
+        Self {
+            read_name,
+            flags,
+            sequence,
+            quality_scores,
+        }
+    }
+}
+
+/// An order-independent checksum of records.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Checksum {
+    record_count: u64,
+    read_names: u64,
+    sequences: u64,
+    quality_scores: u64,
+    combined: u64,
+}
+
+impl Checksum {
+    /// Adds a record to the checksum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_core::checksum::{Checksum, Fields, Record};
+    ///
+    /// struct Read(&'static [u8], &'static [u8]);
+    ///
+    /// impl Record for Read {
+    ///     fn checksum_fields(&self) -> io::Result<Fields<'_>> {
+    ///         Ok(Fields::new(self.0.into(), 0x04, self.1.into(), Vec::new().into()))
+    ///     }
+    /// }
+    ///
+    /// let mut a = Checksum::default();
+    /// a.add(&Read(b"r0", b"ACGT"))?;
+    /// a.add(&Read(b"r1", b"TTTT"))?;
+    ///
+    /// let mut b = Checksum::default();
+    /// b.add(&Read(b"r1", b"TTTT"))?;
+    /// b.add(&Read(b"r0", b"ACGT"))?;
+    ///
+    /// assert_eq!(a, b);
+    /// assert_eq!(a.record_count(), 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn add<R>(&mut self, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let fields = record.checksum_fields()?;
+        self.add_fields(&fields);
+        Ok(())
+    }
+
+    fn add_fields(&mut self, fields: &Fields<'_>) {
+        let flags = fields.flags.to_le_bytes();
+
+        self.record_count += 1;
+
+        self.read_names = self
+            .read_names
+            .wrapping_add(digest(&[&flags, &fields.read_name]));
+
+        self.sequences = self
+            .sequences
+            .wrapping_add(digest(&[&flags, &fields.sequence]));
+
+        self.quality_scores = self
+            .quality_scores
+            .wrapping_add(digest(&[&flags, &fields.quality_scores]));
+
+        self.combined = self.combined.wrapping_add(digest(&[
+            &flags,
+            &fields.read_name,
+            &fields.sequence,
+            &fields.quality_scores,
+        ]));
+    }
+
+    /// Merges another checksum into this one.
+    ///
+    /// This is the same as adding each record of the other checksum to this one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    ///
+    /// let mut a = Checksum::default();
+    /// a.merge(&Checksum::default());
+    ///
+    /// assert_eq!(a, Checksum::default());
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.record_count += other.record_count;
+        self.read_names = self.read_names.wrapping_add(other.read_names);
+        self.sequences = self.sequences.wrapping_add(other.sequences);
+        self.quality_scores = self.quality_scores.wrapping_add(other.quality_scores);
+        self.combined = self.combined.wrapping_add(other.combined);
+    }
+
+    /// Returns the number of records added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    /// let checksum = Checksum::default();
+    /// assert_eq!(checksum.record_count(), 0);
+    /// ```
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    /// Returns the checksum of the read names and flags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    /// let checksum = Checksum::default();
+    /// assert_eq!(checksum.read_names(), 0);
+    /// ```
+    pub fn read_names(&self) -> u64 {
+        self.read_names
+    }
+
+    /// Returns the checksum of the sequences and flags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    /// let checksum = Checksum::default();
+    /// assert_eq!(checksum.sequences(), 0);
+    /// ```
+    pub fn sequences(&self) -> u64 {
+        self.sequences
+    }
+
+    /// Returns the checksum of the quality scores and flags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    /// let checksum = Checksum::default();
+    /// assert_eq!(checksum.quality_scores(), 0);
+    /// ```
+    pub fn quality_scores(&self) -> u64 {
+        self.quality_scores
+    }
+
+    /// Returns the checksum of all digested fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::checksum::Checksum;
+    /// let checksum = Checksum::default();
+    /// assert_eq!(checksum.combined(), 0);
+    /// ```
+    pub fn combined(&self) -> u64 {
+        self.combined
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{:016x}\t{:016x}\t{:016x}\t{:016x}",
+            self.record_count, self.read_names, self.sequences, self.quality_scores, self.combined
+        )
+    }
+}
+
+// FNV-1a over the length-prefixed parts, followed by a finalizer to mix the bits, since the
+// digests are summed.
+pub(crate) fn digest(parts: &[&[u8]]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+
+    for part in parts {
+        let len = (part.len() as u64).to_le_bytes();
+
+        for &b in len.iter().chain(part.iter()) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_fields(read_name: &[u8], sequence: &[u8]) -> Fields<'static> {
+        Fields::new(
+            read_name.to_vec().into(),
+            0x04,
+            sequence.to_vec().into(),
+            Vec::new().into(),
+        )
+    }
+
+    #[test]
+    fn test_add_fields() {
+        let mut a = Checksum::default();
+        a.add_fields(&build_fields(b"r0", b"ACGT"));
+        a.add_fields(&build_fields(b"r1", b"TTTT"));
+
+        let mut b = Checksum::default();
+        b.add_fields(&build_fields(b"r1", b"TTTT"));
+        b.add_fields(&build_fields(b"r0", b"ACGT"));
+
+        assert_eq!(a, b);
+
+        let mut c = Checksum::default();
+        c.add_fields(&build_fields(b"r1", b"TTTT"));
+        c.add_fields(&build_fields(b"r0", b"ACGA"));
+
+        assert_eq!(c.record_count(), a.record_count());
+        assert_eq!(c.read_names(), a.read_names());
+        assert_ne!(c.sequences(), a.sequences());
+        assert_eq!(c.quality_scores(), a.quality_scores());
+        assert_ne!(c.combined(), a.combined());
+    }
+
+    #[test]
+    fn test_add_fields_with_duplicates() {
+        let mut a = Checksum::default();
+        a.add_fields(&build_fields(b"r0", b"ACGT"));
+        a.add_fields(&build_fields(b"r0", b"ACGT"));
+
+        let mut b = Checksum::default();
+        b.add_fields(&build_fields(b"r0", b"ACGT"));
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = Checksum::default();
+        a.add_fields(&build_fields(b"r0", b"ACGT"));
+
+        let mut b = Checksum::default();
+        b.add_fields(&build_fields(b"r1", b"TTTT"));
+
+        let mut expected = Checksum::default();
+        expected.add_fields(&build_fields(b"r0", b"ACGT"));
+        expected.add_fields(&build_fields(b"r1", b"TTTT"));
+
+        a.merge(&b);
+        assert_eq!(a, expected);
+    }
+
+    #[test]
+    fn test_digest() {
+        // Field boundaries are part of the digest.
+        assert_ne!(digest(&[b"AC", b"GT"]), digest(&[b"A", b"CGT"]));
+    }
+}
@@ -0,0 +1,89 @@
+//! Record encoding and decoding.
+//!
+//! [`Encode`] and [`Decode`] convert records to and from their serialized representation in a
+//! byte buffer, independent of any reader or writer. This allows generic code, e.g., caches,
+//! message queues, or custom containers, to store and pass around records of any format.
+//!
+//! Text formats (e.g., SAM and VCF) encode a record as a single line without the line terminator.
+//! Binary formats (e.g., BAM) encode a record as its raw block without the block size prefix.
+
+use std::io;
+
+/// A record that can be encoded.
+pub trait Encode {
+    /// Appends the encoded record to the given buffer.
+    fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// A record that can be decoded.
+pub trait Decode: Sized {
+    /// Decodes a record from the given buffer.
+    ///
+    /// The buffer must contain exactly one encoded record.
+    fn decode(src: &[u8]) -> io::Result<Self>;
+}
+
+/// Encodes a record to a new buffer.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_core::codec::{self, Encode};
+///
+/// struct Record(u32);
+///
+/// impl Encode for Record {
+///     fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+///         dst.extend_from_slice(&self.0.to_le_bytes());
+///         Ok(())
+///     }
+/// }
+///
+/// assert_eq!(codec::encode(&Record(8))?, [0x08, 0x00, 0x00, 0x00]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn encode<R>(record: &R) -> io::Result<Vec<u8>>
+where
+    R: Encode + ?Sized,
+{
+    let mut buf = Vec::new();
+    record.encode(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Record(u32);
+
+    impl Encode for Record {
+        fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+            dst.extend_from_slice(&self.0.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    impl Decode for Record {
+        fn decode(src: &[u8]) -> io::Result<Self> {
+            <[u8; 4]>::try_from(src)
+                .map(|buf| Self(u32::from_le_bytes(buf)))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+    }
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let record = Record(8);
+        let buf = encode(&record)?;
+        assert_eq!(Record::decode(&buf)?, record);
+
+        assert!(Record::decode(&buf[..3]).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+//! Conformance reports.
+//!
+//! A [`Report`] collects the issues found while validating a file against its format
+//! specification, e.g., by `noodles_bam::conformance::validate`. Each issue has a stable code
+//! that can be matched on by tools, and the report formats as tab-delimited lines for
+//! machine-readable output.
+
+use std::fmt;
+
+/// The severity of a conformance issue.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// The file is readable, but the issue may cause problems with other tools.
+    Warning,
+    /// The file does not conform to the specification.
+    Error,
+}
+
+impl AsRef<str> for Severity {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// A conformance issue.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Issue {
+    severity: Severity,
+    code: &'static str,
+    record: Option<u64>,
+    message: String,
+}
+
+impl Issue {
+    /// Creates a conformance issue.
+    ///
+    /// `record` is the 0-based index of the record that caused the issue, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Error, "unsorted", Some(8), "record is out of order");
+    /// ```
+    pub fn new<M>(severity: Severity, code: &'static str, record: Option<u64>, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self {
+            severity,
+            code,
+            record,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the severity of the issue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Error, "unsorted", Some(8), "record is out of order");
+    /// assert_eq!(issue.severity(), Severity::Error);
+    /// ```
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the issue code.
+    ///
+    /// Codes are short, stable identifiers, e.g., `unsorted`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Error, "unsorted", Some(8), "record is out of order");
+    /// assert_eq!(issue.code(), "unsorted");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Returns the 0-based index of the record that caused the issue.
+    ///
+    /// This is `None` if the issue is not associated with a record, e.g., a header or index
+    /// issue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Error, "unsorted", Some(8), "record is out of order");
+    /// assert_eq!(issue.record(), Some(8));
+    /// ```
+    pub fn record(&self) -> Option<u64> {
+        self.record
+    }
+
+    /// Returns a human-readable description of the issue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Error, "unsorted", Some(8), "record is out of order");
+    /// assert_eq!(issue.message(), "record is out of order");
+    /// ```
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t", self.severity, self.code)?;
+
+        match self.record {
+            Some(i) => write!(f, "{}", i)?,
+            None => f.write_str(".")?,
+        }
+
+        // Keep the output one issue per line.
+        let message = self.message.replace(&['\t', '\n'][..], " ");
+        write!(f, "\t{}", message)
+    }
+}
+
+/// A conformance report.
+///
+/// The report formats as one tab-delimited line per issue: the severity, code, record index (`.`
+/// if missing), and message.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    issues: Vec<Issue>,
+    record_count: u64,
+}
+
+impl Report {
+    /// Adds an issue to the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::{Issue, Report, Severity};
+    ///
+    /// let mut report = Report::default();
+    /// report.add_issue(Issue::new(Severity::Warning, "undefined_contig", Some(0), "sq9"));
+    ///
+    /// assert_eq!(report.issues().len(), 1);
+    /// ```
+    pub fn add_issue(&mut self, issue: Issue) {
+        self.issues.push(issue);
+    }
+
+    /// Adds an error to the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    ///
+    /// let mut report = Report::default();
+    /// report.add_error("unsorted", Some(8), "record is out of order");
+    ///
+    /// assert!(!report.is_valid());
+    /// ```
+    pub fn add_error<M>(&mut self, code: &'static str, record: Option<u64>, message: M)
+    where
+        M: Into<String>,
+    {
+        self.add_issue(Issue::new(Severity::Error, code, record, message));
+    }
+
+    /// Adds a warning to the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    ///
+    /// let mut report = Report::default();
+    /// report.add_warning("undefined_contig", Some(0), "sq9");
+    ///
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn add_warning<M>(&mut self, code: &'static str, record: Option<u64>, message: M)
+    where
+        M: Into<String>,
+    {
+        self.add_issue(Issue::new(Severity::Warning, code, record, message));
+    }
+
+    /// Returns the issues in the order they were found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    /// let report = Report::default();
+    /// assert!(report.issues().is_empty());
+    /// ```
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Returns the number of errors in the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    ///
+    /// let mut report = Report::default();
+    /// report.add_error("unsorted", Some(8), "record is out of order");
+    /// report.add_warning("undefined_contig", Some(0), "sq9");
+    ///
+    /// assert_eq!(report.error_count(), 1);
+    /// ```
+    pub fn error_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+            .count()
+    }
+
+    /// Returns whether the report has no errors.
+    ///
+    /// Warnings do not make a file invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    /// let report = Report::default();
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.error_count() == 0
+    }
+
+    /// Returns the number of records that were read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    /// let report = Report::default();
+    /// assert_eq!(report.record_count(), 0);
+    /// ```
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    /// Returns a mutable reference to the number of records that were read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::conformance::Report;
+    ///
+    /// let mut report = Report::default();
+    /// *report.record_count_mut() = 13;
+    ///
+    /// assert_eq!(report.record_count(), 13);
+    /// ```
+    pub fn record_count_mut(&mut self) -> &mut u64 {
+        &mut self.record_count
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt() {
+        let mut report = Report::default();
+        report.add_error("unsorted", Some(8), "record is out of order");
+        report.add_warning("undefined_contig", None, "sq9\tsq10");
+
+        assert_eq!(
+            report.to_string(),
+            "error\tunsorted\t8\trecord is out of order\nwarning\tundefined_contig\t.\tsq9 sq10\n"
+        );
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let mut report = Report::default();
+        assert!(report.is_valid());
+
+        report.add_warning("undefined_contig", Some(0), "sq9");
+        assert!(report.is_valid());
+
+        report.add_error("unsorted", Some(8), "record is out of order");
+        assert!(!report.is_valid());
+    }
+}
@@ -0,0 +1,332 @@
+//! Record framing for message streams.
+//!
+//! A frame is a single encoded record prefixed with its length (`u32`, little-endian). Frames
+//! carry no header, so they can be sent as individual messages, e.g., on a message bus, while the
+//! header is distributed separately.
+//!
+//! To detect when a consumer decodes records with a header other than the one they were encoded
+//! with, producers can attach a header [`Fingerprint`] to each message, which consumers compare
+//! with the fingerprint of their copy of the header.
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    io::{self, Read},
+    mem,
+};
+
+use super::{
+    checksum,
+    codec::{Decode, Encode},
+};
+
+const LENGTH_SIZE: usize = mem::size_of::<u32>();
+
+/// Appends a record frame to the given buffer.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_core::{codec::Encode, framing};
+///
+/// struct Record(u8);
+///
+/// impl Encode for Record {
+///     fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+///         dst.push(self.0);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buf = Vec::new();
+/// framing::write_frame(&mut buf, &Record(8))?;
+/// assert_eq!(buf, [0x01, 0x00, 0x00, 0x00, 0x08]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn write_frame<R>(dst: &mut Vec<u8>, record: &R) -> io::Result<()>
+where
+    R: Encode + ?Sized,
+{
+    let start = dst.len();
+    dst.extend_from_slice(&[0; LENGTH_SIZE]);
+
+    if let Err(e) = record.encode(dst) {
+        dst.truncate(start);
+        return Err(e);
+    }
+
+    let payload_start = start + LENGTH_SIZE;
+
+    let len = match u32::try_from(dst.len() - payload_start) {
+        Ok(len) => len,
+        Err(e) => {
+            dst.truncate(start);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
+        }
+    };
+
+    dst[start..payload_start].copy_from_slice(&len.to_le_bytes());
+
+    Ok(())
+}
+
+/// Splits the first frame from the given buffer.
+///
+/// This returns the payload of the first frame and the remainder of the buffer. If the buffer
+/// does not hold a complete frame, this returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::framing;
+///
+/// let src = [0x01, 0x00, 0x00, 0x00, 0x08, 0x02];
+/// assert_eq!(framing::split_frame(&src), Some((&[0x08][..], &[0x02][..])));
+/// assert_eq!(framing::split_frame(&src[..4]), None);
+/// ```
+pub fn split_frame(src: &[u8]) -> Option<(&[u8], &[u8])> {
+    if src.len() < LENGTH_SIZE {
+        return None;
+    }
+
+    let (len_buf, rest) = src.split_at(LENGTH_SIZE);
+    let len = u32::from_le_bytes([len_buf[0], len_buf[1], len_buf[2], len_buf[3]]) as usize;
+
+    if rest.len() < len {
+        None
+    } else {
+        Some(rest.split_at(len))
+    }
+}
+
+/// Decodes the record of the first frame in the given buffer.
+///
+/// This returns the record and the number of bytes consumed. If the buffer does not hold a
+/// complete frame, this returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_core::{codec::Decode, framing};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Record(Vec<u8>);
+///
+/// impl Decode for Record {
+///     fn decode(src: &[u8]) -> io::Result<Self> {
+///         Ok(Self(src.to_vec()))
+///     }
+/// }
+///
+/// let src = [0x01, 0x00, 0x00, 0x00, 0x08, 0x02];
+/// assert_eq!(framing::read_frame(&src)?, Some((Record(vec![0x08]), 5)));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read_frame<R>(src: &[u8]) -> io::Result<Option<(R, usize)>>
+where
+    R: Decode,
+{
+    match split_frame(src) {
+        Some((payload, rest)) => {
+            let record = R::decode(payload)?;
+            Ok(Some((record, src.len() - rest.len())))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Reads and decodes a single frame from a reader.
+///
+/// If the reader is at EOF, this returns `None`. A frame that is cut short is an error.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_core::{codec::Decode, framing};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Record(Vec<u8>);
+///
+/// impl Decode for Record {
+///     fn decode(src: &[u8]) -> io::Result<Self> {
+///         Ok(Self(src.to_vec()))
+///     }
+/// }
+///
+/// let mut reader = &[0x01, 0x00, 0x00, 0x00, 0x08][..];
+/// assert_eq!(framing::read_frame_from(&mut reader, &mut Vec::new())?, Some(Record(vec![0x08])));
+/// assert_eq!(framing::read_frame_from::<_, Record>(&mut reader, &mut Vec::new())?, None);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read_frame_from<T, R>(reader: &mut T, buf: &mut Vec<u8>) -> io::Result<Option<R>>
+where
+    T: Read,
+    R: Decode,
+{
+    let mut len_buf = [0; LENGTH_SIZE];
+
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let len = u32::from_le_bytes(len_buf) as usize;
+
+    buf.resize(len, 0);
+    reader.read_exact(buf)?;
+
+    R::decode(buf).map(Some)
+}
+
+/// A header fingerprint.
+///
+/// A fingerprint is a 64-bit digest of the parts of a header that records depend on. It is not
+/// cryptographically secure; it is meant to detect accidental mismatches.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    /// Computes a fingerprint from a list of parts.
+    ///
+    /// Part boundaries are included in the fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::framing::Fingerprint;
+    ///
+    /// let fingerprint = Fingerprint::from_parts(&[b"sq0", b"8"]);
+    /// assert_eq!(fingerprint, Fingerprint::from_parts(&[b"sq0", b"8"]));
+    /// assert_ne!(fingerprint, Fingerprint::from_parts(&[b"sq08"]));
+    /// ```
+    pub fn from_parts(parts: &[&[u8]]) -> Self {
+        Self(checksum::digest(parts))
+    }
+
+    /// Returns an error if this fingerprint does not match the expected one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::framing::Fingerprint;
+    ///
+    /// let fingerprint = Fingerprint::from_parts(&[b"sq0"]);
+    /// assert!(fingerprint.verify(fingerprint).is_ok());
+    /// assert!(fingerprint.verify(Fingerprint::from_parts(&[b"sq1"])).is_err());
+    /// ```
+    pub fn verify(self, expected: Self) -> io::Result<()> {
+        if self == expected {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "header fingerprint mismatch: expected {}, got {}",
+                    expected, self
+                ),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl From<u64> for Fingerprint {
+    fn from(n: u64) -> Self {
+        Self(n)
+    }
+}
+
+impl From<Fingerprint> for u64 {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Record(Vec<u8>);
+
+    impl Encode for Record {
+        fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+            if self.0.is_empty() {
+                dst.push(0xff);
+                Err(io::Error::from(io::ErrorKind::InvalidInput))
+            } else {
+                dst.extend_from_slice(&self.0);
+                Ok(())
+            }
+        }
+    }
+
+    impl Decode for Record {
+        fn decode(src: &[u8]) -> io::Result<Self> {
+            Ok(Self(src.to_vec()))
+        }
+    }
+
+    #[test]
+    fn test_write_frame() -> io::Result<()> {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &Record(vec![0x08, 0x0d]))?;
+        write_frame(&mut buf, &Record(vec![0x05]))?;
+        assert_eq!(
+            buf,
+            [0x02, 0x00, 0x00, 0x00, 0x08, 0x0d, 0x01, 0x00, 0x00, 0x00, 0x05]
+        );
+
+        // A failed encoding leaves the buffer unchanged.
+        assert!(write_frame(&mut buf, &Record(Vec::new())).is_err());
+        assert_eq!(buf.len(), 11);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_frame() -> io::Result<()> {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &Record(vec![0x08, 0x0d]))?;
+        write_frame(&mut buf, &Record(vec![0x05]))?;
+
+        let (record, n) = read_frame::<Record>(&buf)?.expect("missing frame");
+        assert_eq!(record, Record(vec![0x08, 0x0d]));
+        assert_eq!(n, 6);
+
+        let (record, n) = read_frame::<Record>(&buf[n..])?.expect("missing frame");
+        assert_eq!(record, Record(vec![0x05]));
+        assert_eq!(n, 5);
+
+        assert!(read_frame::<Record>(&buf[..5])?.is_none());
+        assert!(read_frame::<Record>(&[])?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_frame_from() -> io::Result<()> {
+        let mut reader = &[0x02, 0x00, 0x00, 0x00, 0x08][..];
+
+        assert!(matches!(
+            read_frame_from::<_, Record>(&mut reader, &mut Vec::new()),
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(Fingerprint::from(0x0d).to_string(), "000000000000000d");
+    }
+}
@@ -0,0 +1,17 @@
+#![deny(missing_docs)]
+
+//! **noodles-core** contains shared types used across noodles crates.
+
+pub mod cancel;
+pub mod checksum;
+pub mod codec;
+pub mod conformance;
+pub mod framing;
+pub mod limits;
+pub mod progress;
+pub mod quality_binning;
+pub mod scan;
+pub mod sequence;
+pub mod strand;
+
+pub use self::{quality_binning::QualityBinning, sequence::Sequence, strand::Strand};
@@ -0,0 +1,249 @@
+//! Reader limits.
+//!
+//! Readers allocate buffers based on sizes given in the input, e.g., a line length or a BAM block
+//! size. [`Limits`] bounds these sizes so that untrusted input cannot cause unbounded memory
+//! allocation. When a limit is exceeded, the reader returns an [`io::Error`] of kind
+//! [`io::ErrorKind::InvalidData`] that wraps a [`LimitExceeded`] error.
+//!
+//! By default, no limits are set.
+
+use std::{error, fmt, io};
+
+/// Limits on sizes read from the input.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Limits {
+    max_line_length: Option<usize>,
+    max_record_size: Option<usize>,
+    max_header_size: Option<usize>,
+    max_aux_data_size: Option<usize>,
+}
+
+impl Limits {
+    /// Returns the maximum length of a line in bytes, including the line ending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_line_length(1 << 20);
+    /// assert_eq!(limits.max_line_length(), Some(1 << 20));
+    /// ```
+    pub fn max_line_length(&self) -> Option<usize> {
+        self.max_line_length
+    }
+
+    /// Sets the maximum length of a line in bytes, including the line ending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_line_length(1 << 20);
+    /// ```
+    pub fn set_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = Some(max_line_length);
+        self
+    }
+
+    /// Returns the maximum size of a binary record in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_record_size(1 << 20);
+    /// assert_eq!(limits.max_record_size(), Some(1 << 20));
+    /// ```
+    pub fn max_record_size(&self) -> Option<usize> {
+        self.max_record_size
+    }
+
+    /// Sets the maximum size of a binary record in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_record_size(1 << 20);
+    /// ```
+    pub fn set_max_record_size(mut self, max_record_size: usize) -> Self {
+        self.max_record_size = Some(max_record_size);
+        self
+    }
+
+    /// Returns the maximum size of a header in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_header_size(1 << 24);
+    /// assert_eq!(limits.max_header_size(), Some(1 << 24));
+    /// ```
+    pub fn max_header_size(&self) -> Option<usize> {
+        self.max_header_size
+    }
+
+    /// Sets the maximum size of a header in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_header_size(1 << 24);
+    /// ```
+    pub fn set_max_header_size(mut self, max_header_size: usize) -> Self {
+        self.max_header_size = Some(max_header_size);
+        self
+    }
+
+    /// Returns the maximum size of the auxiliary data (optional fields) of a record in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_aux_data_size(1 << 16);
+    /// assert_eq!(limits.max_aux_data_size(), Some(1 << 16));
+    /// ```
+    pub fn max_aux_data_size(&self) -> Option<usize> {
+        self.max_aux_data_size
+    }
+
+    /// Sets the maximum size of the auxiliary data (optional fields) of a record in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::Limits;
+    /// let limits = Limits::default().set_max_aux_data_size(1 << 16);
+    /// ```
+    pub fn set_max_aux_data_size(mut self, max_aux_data_size: usize) -> Self {
+        self.max_aux_data_size = Some(max_aux_data_size);
+        self
+    }
+}
+
+/// A kind of limit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Limit {
+    /// The maximum line length.
+    LineLength,
+    /// The maximum record size.
+    RecordSize,
+    /// The maximum header size.
+    HeaderSize,
+    /// The maximum auxiliary data size.
+    AuxDataSize,
+}
+
+/// An error returned when a size read from the input exceeds a limit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LimitExceeded {
+    limit: Limit,
+    max: usize,
+}
+
+impl LimitExceeded {
+    /// Creates a limit exceeded error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::{Limit, LimitExceeded};
+    /// let error = LimitExceeded::new(Limit::LineLength, 1024);
+    /// ```
+    pub fn new(limit: Limit, max: usize) -> Self {
+        Self { limit, max }
+    }
+
+    /// Returns the limit that was exceeded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::{Limit, LimitExceeded};
+    /// let error = LimitExceeded::new(Limit::LineLength, 1024);
+    /// assert_eq!(error.limit(), Limit::LineLength);
+    /// ```
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// Returns the value of the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::limits::{Limit, LimitExceeded};
+    /// let error = LimitExceeded::new(Limit::LineLength, 1024);
+    /// assert_eq!(error.max(), 1024);
+    /// ```
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl error::Error for LimitExceeded {}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.limit {
+            Limit::LineLength => "line length",
+            Limit::RecordSize => "record size",
+            Limit::HeaderSize => "header size",
+            Limit::AuxDataSize => "auxiliary data size",
+        };
+
+        write!(f, "{} exceeds limit of {} bytes", name, self.max)
+    }
+}
+
+impl From<LimitExceeded> for io::Error {
+    fn from(e: LimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Checks that a size is within an optional limit.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::limits::{self, Limit};
+///
+/// assert!(limits::check(Limit::RecordSize, Some(8), 5).is_ok());
+/// assert!(limits::check(Limit::RecordSize, None, 13).is_ok());
+///
+/// let e = limits::check(Limit::RecordSize, Some(8), 13).unwrap_err();
+/// assert_eq!(e.limit(), Limit::RecordSize);
+/// ```
+pub fn check(limit: Limit, max: Option<usize>, size: usize) -> Result<(), LimitExceeded> {
+    match max {
+        Some(max) if size > max => Err(LimitExceeded::new(limit, max)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt() {
+        let error = LimitExceeded::new(Limit::HeaderSize, 8);
+        assert_eq!(error.to_string(), "header size exceeds limit of 8 bytes");
+    }
+
+    #[test]
+    fn test_from_limit_exceeded_for_io_error() {
+        let error = io::Error::from(LimitExceeded::new(Limit::LineLength, 8));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let inner = error
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<LimitExceeded>());
+
+        assert_eq!(inner, Some(&LimitExceeded::new(Limit::LineLength, 8)));
+    }
+}
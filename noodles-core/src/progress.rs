@@ -0,0 +1,171 @@
+//! Progress reporting.
+//!
+//! Readers that support progress reporting notify a [`ProgressObserver`] after each record is
+//! read. This can be used to, e.g., drive a progress bar without wrapping the inner reader.
+
+use std::fmt;
+
+/// A snapshot of reader progress.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Progress {
+    compressed_position: Option<u64>,
+    bytes_read: u64,
+    records_read: u64,
+}
+
+impl Progress {
+    /// Creates a progress snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::Progress;
+    /// let progress = Progress::new(Some(8), 13, 2);
+    /// ```
+    pub fn new(compressed_position: Option<u64>, bytes_read: u64, records_read: u64) -> Self {
+        Self {
+            compressed_position,
+            bytes_read,
+            records_read,
+        }
+    }
+
+    /// Returns the position of the underlying compressed stream, i.e., the number of compressed
+    /// bytes consumed from the start of the stream.
+    ///
+    /// This is `None` if the reader does not know whether its input is compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::Progress;
+    /// let progress = Progress::new(Some(8), 13, 2);
+    /// assert_eq!(progress.compressed_position(), Some(8));
+    /// ```
+    pub fn compressed_position(&self) -> Option<u64> {
+        self.compressed_position
+    }
+
+    /// Returns the number of uncompressed bytes of records read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::Progress;
+    /// let progress = Progress::new(Some(8), 13, 2);
+    /// assert_eq!(progress.bytes_read(), 13);
+    /// ```
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Returns the number of records read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::Progress;
+    /// let progress = Progress::new(Some(8), 13, 2);
+    /// assert_eq!(progress.records_read(), 2);
+    /// ```
+    pub fn records_read(&self) -> u64 {
+        self.records_read
+    }
+}
+
+/// An observer of reader progress.
+///
+/// This is implemented for closures that take a [`Progress`].
+pub trait ProgressObserver {
+    /// Called after a record is read.
+    fn on_progress(&mut self, progress: Progress);
+}
+
+impl<F> ProgressObserver for F
+where
+    F: FnMut(Progress),
+{
+    fn on_progress(&mut self, progress: Progress) {
+        self(progress)
+    }
+}
+
+/// A progress counter that notifies an optional observer.
+///
+/// This is used by readers to track their progress.
+#[derive(Default)]
+pub struct Tracker {
+    observer: Option<Box<dyn ProgressObserver + Send>>,
+    bytes_read: u64,
+    records_read: u64,
+}
+
+impl Tracker {
+    /// Sets the observer to notify.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::{Progress, Tracker};
+    /// let mut tracker = Tracker::default();
+    /// tracker.set_observer(Box::new(|progress: Progress| println!("{:?}", progress)));
+    /// ```
+    pub fn set_observer(&mut self, observer: Box<dyn ProgressObserver + Send>) {
+        self.observer = Some(observer);
+    }
+
+    /// Counts a read record of `len` bytes and notifies the observer, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use noodles_core::progress::{Progress, Tracker};
+    ///
+    /// let last_progress = Arc::new(Mutex::new(None));
+    ///
+    /// let mut tracker = Tracker::default();
+    /// let observed = Arc::clone(&last_progress);
+    /// tracker.set_observer(Box::new(move |progress: Progress| {
+    ///     *observed.lock().unwrap() = Some(progress);
+    /// }));
+    ///
+    /// tracker.record_read(5, None);
+    /// tracker.record_read(8, None);
+    ///
+    /// assert_eq!(*last_progress.lock().unwrap(), Some(Progress::new(None, 13, 2)));
+    /// ```
+    pub fn record_read(&mut self, len: usize, compressed_position: Option<u64>) {
+        self.bytes_read += len as u64;
+        self.records_read += 1;
+
+        if let Some(observer) = self.observer.as_mut() {
+            let progress = Progress::new(compressed_position, self.bytes_read, self.records_read);
+            observer.on_progress(progress);
+        }
+    }
+
+    /// Returns the number of records read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::progress::Tracker;
+    /// let mut tracker = Tracker::default();
+    /// tracker.record_read(5, None);
+    /// assert_eq!(tracker.records_read(), 1);
+    /// ```
+    pub fn records_read(&self) -> u64 {
+        self.records_read
+    }
+}
+
+impl fmt::Debug for Tracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracker")
+            .field("has_observer", &self.observer.is_some())
+            .field("bytes_read", &self.bytes_read)
+            .field("records_read", &self.records_read)
+            .finish()
+    }
+}
@@ -0,0 +1,176 @@
+//! Quality score binning.
+//!
+//! Binning maps base quality scores to a small set of representative values, which greatly
+//! improves the compression of quality scores at a small cost in accuracy. This is commonly done
+//! when archiving alignments.
+//!
+//! Scores are raw Phred quality scores, i.e., without an ASCII offset. A score of 255 marks
+//! missing quality scores in BAM and CRAM and is never binned.
+
+use std::{fmt, ops::RangeInclusive};
+
+const MISSING_SCORE: u8 = 255;
+
+/// A quality score binning scheme.
+///
+/// Scores that are not in a bin are kept as is.
+#[derive(Clone, Copy)]
+pub struct QualityBinning {
+    table: [u8; 256],
+}
+
+impl QualityBinning {
+    /// Creates a binning scheme with no bins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    /// let binning = QualityBinning::new();
+    /// assert_eq!(binning.bin(32), 32);
+    /// ```
+    pub fn new() -> Self {
+        let mut table = [0; 256];
+
+        for (score, value) in table.iter_mut().enumerate() {
+            *value = score as u8;
+        }
+
+        Self { table }
+    }
+
+    /// Creates the Illumina 8-level binning scheme.
+    ///
+    /// | scores | value |
+    /// |-------:|------:|
+    /// |  2–9   |     6 |
+    /// | 10–19  |    15 |
+    /// | 20–24  |    22 |
+    /// | 25–29  |    27 |
+    /// | 30–34  |    33 |
+    /// | 35–39  |    37 |
+    /// |  ≥ 40  |    40 |
+    ///
+    /// Scores of 0 and 1, e.g., for no-calls, are kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    /// let binning = QualityBinning::illumina();
+    /// assert_eq!(binning.bin(1), 1);
+    /// assert_eq!(binning.bin(12), 15);
+    /// assert_eq!(binning.bin(41), 40);
+    /// ```
+    pub fn illumina() -> Self {
+        Self::new()
+            .set_bin(2..=9, 6)
+            .set_bin(10..=19, 15)
+            .set_bin(20..=24, 22)
+            .set_bin(25..=29, 27)
+            .set_bin(30..=34, 33)
+            .set_bin(35..=39, 37)
+            .set_bin(40..=254, 40)
+    }
+
+    /// Maps a range of scores to a single value.
+    ///
+    /// A later bin overrides earlier bins where their ranges overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    ///
+    /// let binning = QualityBinning::new()
+    ///     .set_bin(0..=19, 10)
+    ///     .set_bin(20..=254, 30);
+    ///
+    /// assert_eq!(binning.bin(8), 10);
+    /// assert_eq!(binning.bin(37), 30);
+    /// ```
+    pub fn set_bin(mut self, range: RangeInclusive<u8>, value: u8) -> Self {
+        for score in range {
+            self.table[usize::from(score)] = value;
+        }
+
+        self
+    }
+
+    /// Returns the binned value of a score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    /// let binning = QualityBinning::illumina();
+    /// assert_eq!(binning.bin(23), 22);
+    /// assert_eq!(binning.bin(255), 255);
+    /// ```
+    pub fn bin(&self, score: u8) -> u8 {
+        if score == MISSING_SCORE {
+            score
+        } else {
+            self.table[usize::from(score)]
+        }
+    }
+
+    /// Bins a list of scores in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    ///
+    /// let mut scores = [8, 13, 21, 40];
+    /// QualityBinning::illumina().apply(&mut scores);
+    ///
+    /// assert_eq!(scores, [6, 15, 22, 40]);
+    /// ```
+    pub fn apply(&self, scores: &mut [u8]) {
+        for score in scores {
+            *score = self.bin(*score);
+        }
+    }
+}
+
+impl Default for QualityBinning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for QualityBinning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QualityBinning").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_illumina() {
+        let binning = QualityBinning::illumina();
+
+        let mut scores: Vec<u8> = (0..=255).collect();
+        binning.apply(&mut scores);
+
+        let mut values = scores.clone();
+        values.dedup();
+        assert_eq!(values, [0, 1, 6, 15, 22, 27, 33, 37, 40, 255]);
+
+        assert_eq!(scores[9], 6);
+        assert_eq!(scores[10], 15);
+        assert_eq!(scores[39], 37);
+        assert_eq!(scores[254], 40);
+    }
+
+    #[test]
+    fn test_set_bin_with_missing_score() {
+        let binning = QualityBinning::new().set_bin(0..=255, 2);
+        assert_eq!(binning.bin(93), 2);
+        assert_eq!(binning.bin(255), 255);
+    }
+}
@@ -0,0 +1,336 @@
+//! Multi-track region scanning.
+//!
+//! [`scan`] walks a region of a single reference sequence and merges alignments, variants, and
+//! features into one stream of events ordered by start position, e.g., for a genome browser
+//! backend or a variant review tool. Each track is any record iterator whose records implement
+//! [`Locus`], typically the result of querying an indexed BAM or VCF file, or a filtered GFF
+//! reader.
+//!
+//! Each track must be sorted by start position. Records that do not overlap the region are
+//! skipped, and a track stops being read once a record starts after the end of the region.
+
+use std::io;
+
+/// A record that covers an interval of a reference sequence.
+pub trait Locus {
+    /// Returns the 1-based start and end positions (inclusive) of the record.
+    ///
+    /// This is `None` if the record is not placed on a reference sequence, in which case the
+    /// scanner skips it.
+    fn interval(&self) -> io::Result<Option<(i64, i64)>>;
+}
+
+/// A record from one of the tracks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Item<A, V, F> {
+    /// An alignment record.
+    Alignment(A),
+    /// A variant record.
+    Variant(V),
+    /// A feature record, e.g., an annotation.
+    Feature(F),
+}
+
+/// A record and the interval it covers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event<A, V, F> {
+    start: i64,
+    end: i64,
+    item: Item<A, V, F>,
+}
+
+impl<A, V, F> Event<A, V, F> {
+    /// Returns the 1-based start position of the record.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the 1-based end position (inclusive) of the record.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns the record.
+    pub fn item(&self) -> &Item<A, V, F> {
+        &self.item
+    }
+
+    /// Returns the record, consuming the event.
+    pub fn into_item(self) -> Item<A, V, F> {
+        self.item
+    }
+}
+
+/// Scans a region of alignments, variants, and features.
+///
+/// The region is given by the 1-based positions `start` and `end` (inclusive). Events are ordered
+/// by start position. Events that start at the same position are ordered by track (features,
+/// variants, then alignments) and then by input order.
+///
+/// If a record in a track starts before the previous record in the same track, the scanner
+/// returns an error of kind [`io::ErrorKind::InvalidData`] and stops reading that track.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_core::scan::{self, Item, Locus};
+///
+/// struct Interval(i64, i64);
+///
+/// impl Locus for Interval {
+///     fn interval(&self) -> io::Result<Option<(i64, i64)>> {
+///         Ok(Some((self.0, self.1)))
+///     }
+/// }
+///
+/// let alignments = vec![Ok(Interval(3, 10)), Ok(Interval(8, 15))];
+/// let variants = vec![Ok(Interval(5, 5))];
+/// let features = vec![Ok(Interval(1, 20))];
+///
+/// let starts: Vec<_> = scan::scan(2, 12, alignments, variants, features)
+///     .map(|result| result.map(|event| event.start()))
+///     .collect::<io::Result<_>>()?;
+///
+/// assert_eq!(starts, [1, 3, 5, 8]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn scan<A, V, F, RA, RV, RF>(
+    start: i64,
+    end: i64,
+    alignments: A,
+    variants: V,
+    features: F,
+) -> Events<A::IntoIter, V::IntoIter, F::IntoIter, RA, RV, RF>
+where
+    A: IntoIterator<Item = io::Result<RA>>,
+    V: IntoIterator<Item = io::Result<RV>>,
+    F: IntoIterator<Item = io::Result<RF>>,
+    RA: Locus,
+    RV: Locus,
+    RF: Locus,
+{
+    Events {
+        alignments: Track::new(alignments.into_iter()),
+        variants: Track::new(variants.into_iter()),
+        features: Track::new(features.into_iter()),
+        start,
+        end,
+    }
+}
+
+/// An iterator over position-ordered events of a region.
+///
+/// This is created by calling [`scan`].
+pub struct Events<IA, IV, IF, RA, RV, RF> {
+    alignments: Track<IA, RA>,
+    variants: Track<IV, RV>,
+    features: Track<IF, RF>,
+    start: i64,
+    end: i64,
+}
+
+impl<IA, IV, IF, RA, RV, RF> Iterator for Events<IA, IV, IF, RA, RV, RF>
+where
+    IA: Iterator<Item = io::Result<RA>>,
+    IV: Iterator<Item = io::Result<RV>>,
+    IF: Iterator<Item = io::Result<RF>>,
+    RA: Locus,
+    RV: Locus,
+    RF: Locus,
+{
+    type Item = io::Result<Event<RA, RV, RF>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = (self.start, self.end);
+
+        let feature_start = match self.features.peek(start, end) {
+            Ok(s) => s,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let variant_start = match self.variants.peek(start, end) {
+            Ok(s) => s,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let alignment_start = match self.alignments.peek(start, end) {
+            Ok(s) => s,
+            Err(e) => return Some(Err(e)),
+        };
+
+        // Ties go to the earlier track, i.e., features, then variants, then alignments.
+        let mut min = (feature_start, 0);
+
+        for candidate in [(variant_start, 1), (alignment_start, 2)].iter() {
+            if is_before(candidate.0, min.0) {
+                min = *candidate;
+            }
+        }
+
+        let event = match min.1 {
+            0 => self.features.take().map(|e| map_event(e, Item::Feature)),
+            1 => self.variants.take().map(|e| map_event(e, Item::Variant)),
+            _ => self
+                .alignments
+                .take()
+                .map(|e| map_event(e, Item::Alignment)),
+        };
+
+        event.map(Ok)
+    }
+}
+
+fn is_before(a: Option<i64>, b: Option<i64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a < b,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+fn map_event<R, A, V, F, G>((start, end, record): (i64, i64, R), f: G) -> Event<A, V, F>
+where
+    G: FnOnce(R) -> Item<A, V, F>,
+{
+    Event {
+        start,
+        end,
+        item: f(record),
+    }
+}
+
+struct Track<I, R> {
+    records: I,
+    next: Option<(i64, i64, R)>,
+    last_start: Option<i64>,
+    is_done: bool,
+}
+
+impl<I, R> Track<I, R>
+where
+    I: Iterator<Item = io::Result<R>>,
+    R: Locus,
+{
+    fn new(records: I) -> Self {
+        Self {
+            records,
+            next: None,
+            last_start: None,
+            is_done: false,
+        }
+    }
+
+    // Reads records until one overlaps the region and returns its start position.
+    fn peek(&mut self, start: i64, end: i64) -> io::Result<Option<i64>> {
+        while self.next.is_none() && !self.is_done {
+            let record = match self.records.next() {
+                Some(Ok(record)) => record,
+                Some(Err(e)) => {
+                    self.is_done = true;
+                    return Err(e);
+                }
+                None => {
+                    self.is_done = true;
+                    break;
+                }
+            };
+
+            let (record_start, record_end) = match record.interval() {
+                Ok(Some(interval)) => interval,
+                Ok(None) => continue,
+                Err(e) => {
+                    self.is_done = true;
+                    return Err(e);
+                }
+            };
+
+            if let Some(last_start) = self.last_start {
+                if record_start < last_start {
+                    self.is_done = true;
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "track is not sorted by start position",
+                    ));
+                }
+            }
+
+            self.last_start = Some(record_start);
+
+            if record_start > end {
+                self.is_done = true;
+            } else if record_end >= start {
+                self.next = Some((record_start, record_end, record));
+            }
+        }
+
+        Ok(self.next.as_ref().map(|(record_start, _, _)| *record_start))
+    }
+
+    fn take(&mut self) -> Option<(i64, i64, R)> {
+        self.next.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Interval(i64, i64);
+
+    impl Locus for Interval {
+        fn interval(&self) -> io::Result<Option<(i64, i64)>> {
+            if self.0 == 0 {
+                Ok(None)
+            } else {
+                Ok(Some((self.0, self.1)))
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan() -> io::Result<()> {
+        let alignments = vec![
+            Ok(Interval(0, 0)),
+            Ok(Interval(1, 4)),
+            Ok(Interval(5, 9)),
+            Ok(Interval(13, 20)),
+        ];
+        let variants = vec![Ok(Interval(5, 5)), Ok(Interval(21, 21))];
+        let features = vec![Ok(Interval(2, 30)), Ok(Interval(5, 8))];
+
+        let items: Vec<_> = scan(5, 20, alignments, variants, features)
+            .map(|result| result.map(Event::into_item))
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(
+            items,
+            [
+                Item::Feature(Interval(2, 30)),
+                Item::Feature(Interval(5, 8)),
+                Item::Variant(Interval(5, 5)),
+                Item::Alignment(Interval(5, 9)),
+                Item::Alignment(Interval(13, 20)),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_with_unsorted_track() {
+        let alignments = vec![Ok(Interval(8, 10)), Ok(Interval(3, 5))];
+        let variants: Vec<io::Result<Interval>> = Vec::new();
+        let features: Vec<io::Result<Interval>> = Vec::new();
+
+        let mut events = scan(1, 20, alignments, variants, features);
+
+        assert!(matches!(events.next(), Some(Ok(_))));
+        assert!(matches!(
+            events.next(),
+            Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(events.next().is_none());
+    }
+}
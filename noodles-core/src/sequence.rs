@@ -0,0 +1,378 @@
+//! Biological sequence and alphabet.
+
+use std::{error, fmt, ops::Deref};
+
+/// A sequence alphabet.
+///
+/// Alphabets are case-insensitive.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Alphabet {
+    /// DNA bases (`A`, `C`, `G`, `T`) and `N`.
+    Dna,
+    /// RNA bases (`A`, `C`, `G`, `U`) and `N`.
+    Rna,
+    /// IUPAC nucleotide codes, including ambiguity codes, `U`, and gaps (`-`, `.`).
+    Iupac,
+    /// IUPAC amino acid codes, including `*` (stop) and `-` (gap).
+    Protein,
+}
+
+impl Alphabet {
+    /// Returns whether the alphabet contains the given symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::sequence::Alphabet;
+    ///
+    /// assert!(Alphabet::Dna.contains(b'a'));
+    /// assert!(!Alphabet::Dna.contains(b'U'));
+    /// assert!(Alphabet::Iupac.contains(b'R'));
+    /// assert!(Alphabet::Protein.contains(b'W'));
+    /// ```
+    pub fn contains(self, symbol: u8) -> bool {
+        let symbol = symbol.to_ascii_uppercase();
+
+        match self {
+            Self::Dna => matches!(symbol, b'A' | b'C' | b'G' | b'T' | b'N'),
+            Self::Rna => matches!(symbol, b'A' | b'C' | b'G' | b'U' | b'N'),
+            Self::Iupac => matches!(
+                symbol,
+                b'A' | b'C'
+                    | b'G'
+                    | b'T'
+                    | b'U'
+                    | b'R'
+                    | b'Y'
+                    | b'S'
+                    | b'W'
+                    | b'K'
+                    | b'M'
+                    | b'B'
+                    | b'D'
+                    | b'H'
+                    | b'V'
+                    | b'N'
+                    | b'-'
+                    | b'.'
+            ),
+            Self::Protein => matches!(symbol, b'A'..=b'Z' | b'*' | b'-'),
+        }
+    }
+}
+
+/// An error returned when a sequence fails validation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidateError {
+    position: usize,
+    symbol: u8,
+}
+
+impl ValidateError {
+    /// Returns the 0-based position of the invalid symbol.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the invalid symbol.
+    pub fn symbol(&self) -> u8 {
+        self.symbol
+    }
+}
+
+impl error::Error for ValidateError {}
+
+impl fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid symbol at position {}: {}",
+            self.position,
+            char::from(self.symbol)
+        )
+    }
+}
+
+/// A biological sequence.
+///
+/// A sequence is a list of raw symbols, e.g., the sequence of a FASTA or FASTQ record. It is not
+/// validated on creation; use [`Self::validate`] to check it against an [`Alphabet`].
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::{sequence::Alphabet, Sequence};
+///
+/// let sequence = Sequence::from(b"ACGTN".to_vec());
+/// assert!(sequence.validate(Alphabet::Dna).is_ok());
+/// assert_eq!(&sequence.reverse_complement()[..], b"NACGT");
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Sequence(Vec<u8>);
+
+impl Sequence {
+    /// Validates the sequence against an alphabet.
+    ///
+    /// This returns an error with the first symbol not in the alphabet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::{sequence::Alphabet, Sequence};
+    ///
+    /// let sequence = Sequence::from(b"ACGU".to_vec());
+    /// assert!(sequence.validate(Alphabet::Rna).is_ok());
+    ///
+    /// let error = sequence.validate(Alphabet::Dna).unwrap_err();
+    /// assert_eq!(error.position(), 3);
+    /// assert_eq!(error.symbol(), b'U');
+    /// ```
+    pub fn validate(&self, alphabet: Alphabet) -> Result<(), ValidateError> {
+        match self.0.iter().position(|&b| !alphabet.contains(b)) {
+            Some(i) => Err(ValidateError {
+                position: i,
+                symbol: self.0[i],
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the reverse complement of the sequence.
+    ///
+    /// IUPAC ambiguity codes are complemented, and case is preserved. `U` is complemented to `A`.
+    /// Symbols without a complement, e.g., gaps, are kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Sequence;
+    /// let sequence = Sequence::from(b"ACgtRN".to_vec());
+    /// assert_eq!(&sequence.reverse_complement()[..], b"NYacGT");
+    /// ```
+    pub fn reverse_complement(&self) -> Self {
+        Self(self.0.iter().rev().map(|&b| complement(b)).collect())
+    }
+
+    /// Transcribes a DNA sequence to RNA, i.e., replaces `T` with `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Sequence;
+    /// let sequence = Sequence::from(b"ACGTt".to_vec());
+    /// assert_eq!(&sequence.transcribe()[..], b"ACGUu");
+    /// ```
+    pub fn transcribe(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|&b| match b {
+                    b'T' => b'U',
+                    b't' => b'u',
+                    _ => b,
+                })
+                .collect(),
+        )
+    }
+
+    /// Reverse transcribes an RNA sequence to DNA, i.e., replaces `U` with `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Sequence;
+    /// let sequence = Sequence::from(b"ACGUu".to_vec());
+    /// assert_eq!(&sequence.back_transcribe()[..], b"ACGTt");
+    /// ```
+    pub fn back_transcribe(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|&b| match b {
+                    b'U' => b'T',
+                    b'u' => b't',
+                    _ => b,
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the fraction of G and C bases.
+    ///
+    /// `S` (G or C) is counted as G/C. Only unambiguous bases (A, C, G, T, U) and `S` and `W` are
+    /// counted in the total. This returns `None` if there are no such bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Sequence;
+    ///
+    /// let sequence = Sequence::from(b"ACGGNN".to_vec());
+    /// assert_eq!(sequence.gc_content(), Some(0.75));
+    ///
+    /// let sequence = Sequence::from(b"NN".to_vec());
+    /// assert!(sequence.gc_content().is_none());
+    /// ```
+    pub fn gc_content(&self) -> Option<f64> {
+        let (gc_count, total) =
+            self.0
+                .iter()
+                .fold((0u64, 0u64), |(gc, n), &b| match b.to_ascii_uppercase() {
+                    b'G' | b'C' | b'S' => (gc + 1, n + 1),
+                    b'A' | b'T' | b'U' | b'W' => (gc, n + 1),
+                    _ => (gc, n),
+                });
+
+        if total == 0 {
+            None
+        } else {
+            Some(gc_count as f64 / total as f64)
+        }
+    }
+
+    /// Returns an iterator over all k-mers of the sequence, i.e., overlapping subsequences of
+    /// length `k`.
+    ///
+    /// The iterator is empty if `k` is 0 or larger than the length of the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Sequence;
+    ///
+    /// let sequence = Sequence::from(b"ACGT".to_vec());
+    /// let kmers: Vec<_> = sequence.kmers(3).collect();
+    /// assert_eq!(kmers, [&b"ACG"[..], &b"CGT"[..]]);
+    /// ```
+    pub fn kmers(&self, k: usize) -> Kmers<'_> {
+        Kmers {
+            sequence: &self.0,
+            k,
+            i: 0,
+        }
+    }
+}
+
+impl Deref for Sequence {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Sequence {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Sequence {
+    fn from(symbols: Vec<u8>) -> Self {
+        Self(symbols)
+    }
+}
+
+impl From<Sequence> for Vec<u8> {
+    fn from(sequence: Sequence) -> Self {
+        sequence.0
+    }
+}
+
+/// An iterator over the k-mers of a sequence.
+///
+/// This is created by calling [`Sequence::kmers`].
+pub struct Kmers<'a> {
+    sequence: &'a [u8],
+    k: usize,
+    i: usize,
+}
+
+impl<'a> Iterator for Kmers<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.k == 0 {
+            return None;
+        }
+
+        let kmer = self.sequence.get(self.i..self.i + self.k)?;
+        self.i += 1;
+        Some(kmer)
+    }
+}
+
+fn complement(symbol: u8) -> u8 {
+    let complement = match symbol.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' | b'U' => b'A',
+        b'R' => b'Y',
+        b'Y' => b'R',
+        b'K' => b'M',
+        b'M' => b'K',
+        b'B' => b'V',
+        b'V' => b'B',
+        b'D' => b'H',
+        b'H' => b'D',
+        _ => return symbol,
+    };
+
+    if symbol.is_ascii_lowercase() {
+        complement.to_ascii_lowercase()
+    } else {
+        complement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let sequence = Sequence::from(b"ACGTRYN-".to_vec());
+        assert!(sequence.validate(Alphabet::Iupac).is_ok());
+        assert_eq!(
+            sequence.validate(Alphabet::Dna),
+            Err(ValidateError {
+                position: 4,
+                symbol: b'R'
+            })
+        );
+
+        let sequence = Sequence::from(b"MKV*".to_vec());
+        assert!(sequence.validate(Alphabet::Protein).is_ok());
+        assert!(sequence.validate(Alphabet::Iupac).is_err());
+
+        assert!(Sequence::default().validate(Alphabet::Dna).is_ok());
+    }
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(complement(b'A'), b'T');
+        assert_eq!(complement(b'u'), b'a');
+        assert_eq!(complement(b'S'), b'S');
+        assert_eq!(complement(b'W'), b'W');
+        assert_eq!(complement(b'N'), b'N');
+        assert_eq!(complement(b'-'), b'-');
+    }
+
+    #[test]
+    fn test_gc_content() {
+        let sequence = Sequence::from(b"GCSW".to_vec());
+        assert_eq!(sequence.gc_content(), Some(0.75));
+        assert!(Sequence::default().gc_content().is_none());
+    }
+
+    #[test]
+    fn test_kmers() {
+        let sequence = Sequence::from(b"ACGT".to_vec());
+
+        assert_eq!(sequence.kmers(4).count(), 1);
+        assert_eq!(sequence.kmers(1).count(), 4);
+        assert_eq!(sequence.kmers(5).count(), 0);
+        assert_eq!(sequence.kmers(0).count(), 0);
+    }
+}
@@ -0,0 +1,182 @@
+//! Strand.
+
+use std::{convert::TryFrom, error, fmt, str::FromStr};
+
+/// A strand of a feature or alignment.
+///
+/// This uses the notation of the strand columns in GFF3 and BED.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Strand {
+    /// Forward strand (`+`).
+    Forward,
+    /// Reverse strand (`-`).
+    Reverse,
+    /// Strandedness is relevant but unknown (`?`).
+    Unknown,
+    /// Strandedness is not relevant, i.e., unstranded (`.`).
+    #[default]
+    Irrelevant,
+}
+
+impl Strand {
+    /// Returns the opposite strand.
+    ///
+    /// Only forward and reverse strands are flipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Strand;
+    /// assert_eq!(Strand::Forward.reverse(), Strand::Reverse);
+    /// assert_eq!(Strand::Irrelevant.reverse(), Strand::Irrelevant);
+    /// ```
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Forward => Self::Reverse,
+            Self::Reverse => Self::Forward,
+            _ => self,
+        }
+    }
+
+    /// Returns whether the strand is forward or reverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Strand;
+    /// assert!(Strand::Forward.is_known());
+    /// assert!(!Strand::Unknown.is_known());
+    /// assert!(!Strand::Irrelevant.is_known());
+    /// ```
+    pub fn is_known(self) -> bool {
+        matches!(self, Self::Forward | Self::Reverse)
+    }
+}
+
+impl AsRef<str> for Strand {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Forward => "+",
+            Self::Reverse => "-",
+            Self::Unknown => "?",
+            Self::Irrelevant => ".",
+        }
+    }
+}
+
+impl fmt::Display for Strand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// An error returned when a raw strand fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The strand is invalid.
+    Invalid(String),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::Invalid(s) => write!(f, "expected {{+, -, ?, .}}, got {}", s),
+        }
+    }
+}
+
+impl FromStr for Strand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(ParseError::Empty),
+            "+" => Ok(Self::Forward),
+            "-" => Ok(Self::Reverse),
+            "?" => Ok(Self::Unknown),
+            "." => Ok(Self::Irrelevant),
+            _ => Err(ParseError::Invalid(s.into())),
+        }
+    }
+}
+
+/// An error returned when a character fails to convert to a strand.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TryFromCharError(char);
+
+impl error::Error for TryFromCharError {}
+
+impl fmt::Display for TryFromCharError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {{+, -, ?, .}}, got {}", self.0)
+    }
+}
+
+impl TryFrom<char> for Strand {
+    type Error = TryFromCharError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '+' => Ok(Self::Forward),
+            '-' => Ok(Self::Reverse),
+            '?' => Ok(Self::Unknown),
+            '.' => Ok(Self::Irrelevant),
+            _ => Err(TryFromCharError(c)),
+        }
+    }
+}
+
+impl From<Strand> for char {
+    fn from(strand: Strand) -> Self {
+        match strand {
+            Strand::Forward => '+',
+            Strand::Reverse => '-',
+            Strand::Unknown => '?',
+            Strand::Irrelevant => '.',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(Strand::Forward.to_string(), "+");
+        assert_eq!(Strand::Reverse.to_string(), "-");
+        assert_eq!(Strand::Unknown.to_string(), "?");
+        assert_eq!(Strand::Irrelevant.to_string(), ".");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("+".parse(), Ok(Strand::Forward));
+        assert_eq!("-".parse(), Ok(Strand::Reverse));
+        assert_eq!("?".parse(), Ok(Strand::Unknown));
+        assert_eq!(".".parse(), Ok(Strand::Irrelevant));
+
+        assert_eq!("".parse::<Strand>(), Err(ParseError::Empty));
+        assert_eq!(
+            "*".parse::<Strand>(),
+            Err(ParseError::Invalid(String::from("*")))
+        );
+    }
+
+    #[test]
+    fn test_try_from_char_for_strand() {
+        assert_eq!(Strand::try_from('+'), Ok(Strand::Forward));
+        assert_eq!(Strand::try_from('*'), Err(TryFromCharError('*')));
+    }
+
+    #[test]
+    fn test_from_strand_for_char() {
+        assert_eq!(char::from(Strand::Forward), '+');
+        assert_eq!(char::from(Strand::Irrelevant), '.');
+    }
+}
@@ -0,0 +1,384 @@
+//! CRAM conformance validation.
+//!
+//! [`validate`] reads a CRAM file from the start and checks its structure against the CRAM
+//! specification. Records are not decoded, so no reference sequences are needed.
+//! [`validate_with_index`] additionally checks that an associated CRAM index (CRAI) is consistent
+//! with the file.
+//!
+//! Issues are reported with the following codes:
+//!
+//!   * `invalid_file_definition`: the file definition is invalid, e.g., the magic number is not
+//!     `CRAM`.
+//!   * `invalid_header`: the SAM header container or the SAM header cannot be read.
+//!   * `invalid_container`: a container header or block cannot be read.
+//!   * `invalid_container_length`: the container length differs from the size of its blocks.
+//!   * `invalid_record_counter`: the container record counter differs from the number of records
+//!     in the preceding containers.
+//!   * `invalid_reference_sequence_id`: the container reference sequence ID is not in the list of
+//!     reference sequences.
+//!   * `unsorted`: the file is sorted by coordinate (`SO:coordinate`), but a container is out of
+//!     order.
+//!   * `missing_eof`: the file does not end with an EOF container.
+//!   * `truncated`: the file ends in the middle of a container.
+//!   * `index_*`: the index is inconsistent with the file.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Seek},
+};
+
+use noodles_core::conformance::Report;
+use noodles_sam::{self as sam, header::header::SortOrder};
+
+use super::{container::ReferenceSequenceId, crai, Reader};
+
+/// Validates a CRAM file.
+///
+/// The reader is expected to be at the start of the stream.
+///
+/// Malformed or truncated input is recorded in the report. Any other I/O error is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_cram::{self as cram, conformance};
+///
+/// let mut reader = File::open("sample.cram").map(cram::Reader::new)?;
+/// let report = conformance::validate(&mut reader)?;
+///
+/// print!("{}", report);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate<R>(reader: &mut Reader<R>) -> io::Result<Report>
+where
+    R: Read + Seek,
+{
+    let mut report = Report::default();
+    validate_inner(reader, &mut report)?;
+    Ok(report)
+}
+
+/// Validates a CRAM file and its associated index.
+///
+/// In addition to the checks in [`validate`], this checks that each index record points to the
+/// start of a data container with a matching reference sequence ID.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles_cram::{self as cram, conformance};
+///
+/// let mut reader = File::open("sample.cram").map(cram::Reader::new)?;
+/// let index = cram::index("sample.cram")?;
+/// let report = conformance::validate_with_index(&mut reader, &index)?;
+///
+/// print!("{}", report);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate_with_index<R>(reader: &mut Reader<R>, index: &crai::Index) -> io::Result<Report>
+where
+    R: Read + Seek,
+{
+    let mut report = Report::default();
+
+    if let Some(containers) = validate_inner(reader, &mut report)? {
+        validate_index(index, &containers, &mut report);
+    }
+
+    Ok(report)
+}
+
+// Returns the reference sequence ID of each data container by its position, or `None` if reading
+// stopped early.
+fn validate_inner<R>(
+    reader: &mut Reader<R>,
+    report: &mut Report,
+) -> io::Result<Option<HashMap<u64, ReferenceSequenceId>>>
+where
+    R: Read + Seek,
+{
+    let file_definition = match reader.read_file_definition() {
+        Ok(file_definition) => file_definition,
+        Err(e) if is_format_error(&e) => {
+            report.add_error("invalid_file_definition", None, e.to_string());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    // CRAM 2.x blocks do not have a CRC32, so their lengths cannot be compared.
+    let has_block_crc32 = file_definition.version().major() >= 3;
+
+    let header = match reader.read_file_header() {
+        Ok(s) => match s.trim_end_matches('\0').parse::<sam::Header>() {
+            Ok(header) => header,
+            Err(e) => {
+                report.add_error("invalid_header", None, e.to_string());
+                return Ok(None);
+            }
+        },
+        Err(e) if is_format_error(&e) => {
+            report.add_error("invalid_header", None, e.to_string());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let reference_sequence_count = header.reference_sequences().len();
+
+    let is_coordinate_sorted = header
+        .header()
+        .and_then(|h| h.sort_order())
+        .map(|sort_order| sort_order == SortOrder::Coordinate)
+        .unwrap_or(false);
+
+    let mut containers = HashMap::new();
+    let mut record_count = 0;
+    let mut last_key = None;
+
+    loop {
+        let container_position = reader.position()?;
+
+        let container = match reader.read_container() {
+            Ok(container) => container,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                if reader.position()? == container_position {
+                    report.add_error("missing_eof", None, "missing EOF container");
+                } else {
+                    report.add_error("truncated", None, e.to_string());
+                }
+
+                *report.record_count_mut() = record_count;
+                return Ok(None);
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                report.add_error("invalid_container", None, e.to_string());
+                *report.record_count_mut() = record_count;
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let container_header = container.header();
+
+        if container.is_eof() {
+            break;
+        }
+
+        let blocks_len: usize = container.blocks().iter().map(|block| block.len()).sum();
+
+        if has_block_crc32 && blocks_len != container_header.len() as usize {
+            report.add_error(
+                "invalid_container_length",
+                Some(record_count),
+                format!(
+                    "container at {} has length {}, but its blocks are {} bytes",
+                    container_position,
+                    container_header.len(),
+                    blocks_len
+                ),
+            );
+        }
+
+        if container_header.record_counter() as u64 != record_count {
+            report.add_error(
+                "invalid_record_counter",
+                Some(record_count),
+                format!(
+                    "container at {} has record counter {}, expected {}",
+                    container_position,
+                    container_header.record_counter(),
+                    record_count
+                ),
+            );
+        }
+
+        let reference_sequence_id = container_header.reference_sequence_id();
+
+        if let ReferenceSequenceId::Some(id) = reference_sequence_id {
+            if id as usize >= reference_sequence_count {
+                report.add_error(
+                    "invalid_reference_sequence_id",
+                    Some(record_count),
+                    format!(
+                        "invalid reference sequence ID {} (reference sequence count = {})",
+                        id, reference_sequence_count
+                    ),
+                );
+            }
+        }
+
+        if is_coordinate_sorted {
+            // Unmapped containers sort last. Multi-reference containers are not ordered.
+            let key = match reference_sequence_id {
+                ReferenceSequenceId::Some(id) => Some((id, container_header.start_position())),
+                ReferenceSequenceId::None => Some((i32::MAX, 0)),
+                ReferenceSequenceId::Many => None,
+            };
+
+            if let Some(key) = key {
+                if let Some(last_key) = last_key {
+                    if key < last_key {
+                        report.add_error(
+                            "unsorted",
+                            Some(record_count),
+                            format!(
+                                "container at {} is out of coordinate order",
+                                container_position
+                            ),
+                        );
+                    }
+                }
+
+                last_key = Some(key);
+            }
+        }
+
+        containers.insert(container_position, reference_sequence_id);
+        record_count += container_header.record_count() as u64;
+    }
+
+    *report.record_count_mut() = record_count;
+
+    Ok(Some(containers))
+}
+
+fn validate_index(
+    index: &crai::Index,
+    containers: &HashMap<u64, ReferenceSequenceId>,
+    report: &mut Report,
+) {
+    for record in index {
+        let reference_sequence_id = match containers.get(&record.offset()) {
+            Some(id) => *id,
+            None => {
+                report.add_error(
+                    "index_invalid_offset",
+                    None,
+                    format!(
+                        "index record offset {} is not the start of a data container",
+                        record.offset()
+                    ),
+                );
+
+                continue;
+            }
+        };
+
+        let index_reference_sequence_id = record
+            .reference_sequence_id()
+            .map(i32::from)
+            .map(ReferenceSequenceId::Some)
+            .unwrap_or(ReferenceSequenceId::None);
+
+        if !reference_sequence_id.is_many() && index_reference_sequence_id != reference_sequence_id
+        {
+            report.add_error(
+                "index_reference_sequence_id_mismatch",
+                None,
+                format!(
+                    "index record at offset {} has reference sequence ID {}, but the container has {}",
+                    record.offset(),
+                    i32::from(index_reference_sequence_id),
+                    i32::from(reference_sequence_id)
+                ),
+            );
+        }
+    }
+}
+
+fn is_format_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{Record, Writer};
+
+    fn write(record_count: usize) -> io::Result<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new(), Vec::new());
+        writer.write_file_definition()?;
+        writer.write_file_header(&sam::Header::default())?;
+
+        for _ in 0..record_count {
+            writer.write_record(Record::default())?;
+        }
+
+        writer.try_finish()?;
+
+        Ok(writer.get_ref().clone())
+    }
+
+    fn eof_len() -> io::Result<usize> {
+        let mut writer = Writer::new(Vec::new(), Vec::new());
+        writer.try_finish()?;
+        Ok(writer.get_ref().len())
+    }
+
+    fn codes(report: &Report) -> Vec<&'static str> {
+        report.issues().iter().map(|issue| issue.code()).collect()
+    }
+
+    #[test]
+    fn test_validate() -> io::Result<()> {
+        let data = write(3)?;
+        let report = validate(&mut Reader::new(Cursor::new(data)))?;
+        assert!(report.issues().is_empty(), "{}", report);
+        assert_eq!(report.record_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_missing_eof_container() -> io::Result<()> {
+        let mut data = write(3)?;
+        data.truncate(data.len() - eof_len()?);
+
+        let report = validate(&mut Reader::new(Cursor::new(data.clone())))?;
+        assert_eq!(codes(&report), ["missing_eof"]);
+        assert_eq!(report.record_count(), 3);
+
+        data.truncate(data.len() - 1);
+
+        let report = validate(&mut Reader::new(Cursor::new(data)))?;
+        assert_eq!(codes(&report), ["truncated"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_file_definition() -> io::Result<()> {
+        let data = b"BAM\x01".to_vec();
+        let report = validate(&mut Reader::new(Cursor::new(data)))?;
+        assert_eq!(codes(&report), ["invalid_file_definition"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_index() -> io::Result<()> {
+        let data = write(1)?;
+
+        // Find the position of the first (and only) data container.
+        let mut reader = Reader::new(Cursor::new(data.clone()));
+        reader.read_file_definition()?;
+        reader.read_file_header()?;
+        let offset = reader.position()?;
+
+        let index = vec![crai::Record::new(None, 0, 0, offset, 0, 0)];
+        let report = validate_with_index(&mut Reader::new(Cursor::new(data.clone())), &index)?;
+        assert!(report.issues().is_empty(), "{}", report);
+
+        let index = vec![crai::Record::new(None, 0, 0, offset + 1, 0, 0)];
+        let report = validate_with_index(&mut Reader::new(Cursor::new(data)), &index)?;
+        assert_eq!(codes(&report), ["index_invalid_offset"]);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 mod bit_reader;
 mod bit_writer;
+pub mod conformance;
 pub mod container;
 pub mod crai;
 mod data_container;
@@ -9,6 +10,8 @@ mod num;
 mod rans;
 pub mod reader;
 pub mod record;
+#[cfg(feature = "refget")]
+pub mod refget;
 pub mod writer;
 
 pub use self::{
@@ -11,7 +11,7 @@ pub use self::{
     read_group_id::ReadGroupId, tag::Tag,
 };
 
-use std::{fmt, str};
+use std::{convert::TryFrom, fmt, io, str};
 
 use noodles_bam as bam;
 use noodles_sam as sam;
@@ -152,6 +152,18 @@ impl Record {
     }
 }
 
+impl sam::AlignmentRecord for Record {
+    fn alignment_start(&self) -> Option<sam::record::Position> {
+        sam::record::Position::try_from(Record::alignment_start(self)).ok()
+    }
+
+    fn alignment_span(&self) -> io::Result<u32> {
+        let span = Record::alignment_end(self) - Record::alignment_start(self) + 1;
+
+        u32::try_from(span.max(0)).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
 impl Default for Record {
     fn default() -> Self {
         Builder::default().build()
@@ -0,0 +1,375 @@
+//! refget client.
+//!
+//! [refget] is an API to retrieve reference sequences by a checksum of the sequence, e.g., its
+//! MD5 or TRUNC512 digest. CRAM files identify their reference sequences by MD5 checksum (the
+//! `M5` field in the SAM header), so the reference sequences of a CRAM file can be fetched without
+//! a local FASTA.
+//!
+//! [refget]: https://samtools.github.io/hts-specs/refget.html
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use std::io;
+//! use noodles_cram::refget;
+//!
+//! let client = refget::Client::new("https://refget.example.com/")?
+//!     .set_cache_dir("refget-cache");
+//!
+//! let sequence = client.sequence("d7eba311421bbc9d3ada44709dd61534", Some(0..4))?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use md5::{Digest, Md5};
+use noodles_fasta as fasta;
+use noodles_sam as sam;
+use reqwest::{blocking, header::ACCEPT, IntoUrl, StatusCode, Url};
+
+static SEQUENCE_MEDIA_TYPE: &str = "text/vnd.ga4gh.refget.v1.0.0+plain";
+
+/// A refget client.
+///
+/// Full sequences can optionally be cached on disk, keyed by their identifier. Subsequent
+/// requests for the sequence or any range of it are then read from the cache.
+pub struct Client {
+    http_client: blocking::Client,
+    base_url: Url,
+    cache_dir: Option<PathBuf>,
+}
+
+impl Client {
+    /// Creates a refget client with a default HTTP client.
+    ///
+    /// The base URL is the URL of the refget service, i.e., the URL to which `sequence/<id>` is
+    /// appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_cram::refget;
+    /// let client = refget::Client::new("https://refget.example.com/")?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new<U>(base_url: U) -> io::Result<Self>
+    where
+        U: IntoUrl,
+    {
+        let base_url = base_url
+            .into_url()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(Self::with_client(blocking::Client::new(), base_url))
+    }
+
+    /// Creates a refget client with the given HTTP client.
+    ///
+    /// This can be used to configure the client, e.g., to set authorization headers.
+    pub fn with_client(http_client: blocking::Client, base_url: Url) -> Self {
+        Self {
+            http_client,
+            base_url,
+            cache_dir: None,
+        }
+    }
+
+    /// Sets the directory of the on-disk sequence cache.
+    ///
+    /// The directory is created when the first sequence is cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_cram::refget;
+    ///
+    /// let client = refget::Client::new("https://refget.example.com/")?
+    ///     .set_cache_dir("refget-cache");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_cache_dir<P>(mut self, cache_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Returns the directory of the on-disk sequence cache, if set.
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Retrieves a sequence or a range of a sequence by its identifier.
+    ///
+    /// The identifier is any checksum supported by the service, e.g., a hex-encoded MD5 digest.
+    /// The range is 0-based and half-open.
+    ///
+    /// Full sequences are cached if a cache directory is set. When the identifier is an MD5
+    /// digest, full sequences are verified against it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io;
+    /// use noodles_cram::refget;
+    /// let client = refget::Client::new("https://refget.example.com/")?;
+    /// let sequence = client.sequence("d7eba311421bbc9d3ada44709dd61534", None)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn sequence(&self, id: &str, range: Option<Range<u64>>) -> io::Result<Vec<u8>> {
+        if let Some(sequence) = self.read_cached_sequence(id)? {
+            return Ok(match range {
+                Some(range) => slice(&sequence, range).to_vec(),
+                None => sequence,
+            });
+        }
+
+        if range.is_some() {
+            return self.fetch_sequence(id, range);
+        }
+
+        let sequence = self.fetch_sequence(id, None)?;
+        verify_md5_checksum(id, &sequence)?;
+        self.write_cached_sequence(id, &sequence)?;
+
+        Ok(sequence)
+    }
+
+    /// Retrieves the reference sequences listed in a SAM header.
+    ///
+    /// Reference sequences are looked up by their MD5 checksums (`M5`). The returned records are
+    /// in header order and can be given to a [`crate::Writer`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io;
+    /// use noodles_cram::{self as cram, refget};
+    /// use noodles_sam as sam;
+    ///
+    /// let client = refget::Client::new("https://refget.example.com/")?;
+    ///
+    /// let header: sam::Header = "@SQ\tSN:sq0\tLN:8\tM5:d7eba311421bbc9d3ada44709dd61534"
+    ///     .parse()
+    ///     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    ///
+    /// let reference_sequences = client.reference_sequences(&header)?;
+    /// let writer = cram::Writer::new(Vec::new(), reference_sequences);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn reference_sequences(&self, header: &sam::Header) -> io::Result<Vec<fasta::Record>> {
+        header
+            .reference_sequences()
+            .values()
+            .map(|reference_sequence| {
+                let md5_checksum = reference_sequence.md5_checksum().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "reference sequence {} is missing an MD5 checksum",
+                            reference_sequence.name()
+                        ),
+                    )
+                })?;
+
+                let sequence = self.sequence(&md5_checksum.to_string(), None)?;
+
+                let definition =
+                    fasta::record::Definition::new(reference_sequence.name().into(), None);
+
+                Ok(fasta::Record::new(definition, sequence))
+            })
+            .collect()
+    }
+
+    fn fetch_sequence(&self, id: &str, range: Option<Range<u64>>) -> io::Result<Vec<u8>> {
+        let url = build_sequence_url(&self.base_url, id, range)?;
+
+        let response = self
+            .http_client
+            .get(url)
+            .header(ACCEPT, SEQUENCE_MEDIA_TYPE)
+            .send()
+            .map_err(into_io_error)?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => response
+                .bytes()
+                .map(|buf| buf.to_vec())
+                .map_err(into_io_error),
+            StatusCode::NOT_FOUND => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("sequence not found: {}", id),
+            )),
+            status => Err(io::Error::other(format!(
+                "unexpected HTTP status: {}",
+                status
+            ))),
+        }
+    }
+
+    fn cache_path(&self, id: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|cache_dir| cache_dir.join(cache_file_name(id)))
+    }
+
+    fn read_cached_sequence(&self, id: &str) -> io::Result<Option<Vec<u8>>> {
+        let path = match self.cache_path(id) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        match File::open(path) {
+            Ok(mut file) => {
+                let mut buf = Vec::new();
+                file.read_to_end(&mut buf)?;
+                Ok(Some(buf))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write_cached_sequence(&self, id: &str, sequence: &[u8]) -> io::Result<()> {
+        let path = match self.cache_path(id) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(cache_dir) = &self.cache_dir {
+            fs::create_dir_all(cache_dir)?;
+        }
+
+        // Write to a temporary file first so that a partially written sequence is never read
+        // from the cache.
+        let tmp_path = path.with_extension("tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(sequence)?;
+        file.sync_all()?;
+
+        fs::rename(tmp_path, path)
+    }
+}
+
+fn build_sequence_url(base_url: &Url, id: &str, range: Option<Range<u64>>) -> io::Result<Url> {
+    let mut url = base_url.clone();
+
+    url.path_segments_mut()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid base URL"))?
+        .pop_if_empty()
+        .push("sequence")
+        .push(id);
+
+    if let Some(range) = range {
+        url.query_pairs_mut()
+            .append_pair("start", &range.start.to_string())
+            .append_pair("end", &range.end.to_string());
+    }
+
+    Ok(url)
+}
+
+fn cache_file_name(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn slice(sequence: &[u8], range: Range<u64>) -> &[u8] {
+    let start = (range.start as usize).min(sequence.len());
+    let end = (range.end as usize).min(sequence.len()).max(start);
+    &sequence[start..end]
+}
+
+fn verify_md5_checksum(id: &str, sequence: &[u8]) -> io::Result<()> {
+    let expected: sam::header::reference_sequence::Md5Checksum = match id.parse() {
+        Ok(checksum) => checksum,
+        // The identifier is not an MD5 digest.
+        Err(_) => return Ok(()),
+    };
+
+    let mut hasher = Md5::new();
+    hasher.update(sequence);
+    let actual = hasher.finalize();
+
+    if actual[..] == expected[..] {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("sequence checksum mismatch: expected {}", expected),
+        ))
+    }
+}
+
+fn into_io_error(e: reqwest::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sequence_url() -> Result<(), Box<dyn std::error::Error>> {
+        let base_url = Url::parse("https://refget.example.com/")?;
+
+        let url = build_sequence_url(&base_url, "d7eba311421bbc9d3ada44709dd61534", None)?;
+        assert_eq!(
+            url.as_str(),
+            "https://refget.example.com/sequence/d7eba311421bbc9d3ada44709dd61534"
+        );
+
+        let url = build_sequence_url(&base_url, "d7eba311421bbc9d3ada44709dd61534", Some(2..5))?;
+        assert_eq!(
+            url.as_str(),
+            "https://refget.example.com/sequence/d7eba311421bbc9d3ada44709dd61534?start=2&end=5"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_file_name() {
+        assert_eq!(
+            cache_file_name("d7eba311421bbc9d3ada44709dd61534"),
+            "d7eba311421bbc9d3ada44709dd61534"
+        );
+        assert_eq!(cache_file_name("TRUNC512:9a2b/c"), "TRUNC512_9a2b_c");
+    }
+
+    #[test]
+    fn test_sequence_with_cache() -> io::Result<()> {
+        let cache_dir = std::env::temp_dir().join("noodles-cram-refget-test_sequence_with_cache");
+
+        let client = Client::new("https://refget.example.com/")?.set_cache_dir(&cache_dir);
+
+        let id = "d7eba311421bbc9d3ada44709dd61534";
+        client.write_cached_sequence(id, b"ACGTNACG")?;
+
+        assert_eq!(client.sequence(id, None)?, b"ACGTNACG");
+        assert_eq!(client.sequence(id, Some(2..5))?, b"GTN");
+        assert_eq!(client.sequence(id, Some(6..16))?, b"CG");
+
+        fs::remove_dir_all(cache_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_md5_checksum() {
+        assert!(verify_md5_checksum("cb4ebac4ada8e6d179a241d37a2d28c9", b"ACGTNACG").is_ok());
+        assert!(verify_md5_checksum("cb4ebac4ada8e6d179a241d37a2d28c9", b"ACGT").is_err());
+        assert!(verify_md5_checksum("TRUNC512:9a2b", b"ACGT").is_ok());
+    }
+}
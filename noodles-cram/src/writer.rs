@@ -11,6 +11,7 @@ use std::{
     mem,
 };
 
+use noodles_core::QualityBinning;
 use noodles_fasta as fasta;
 use noodles_sam as sam;
 
@@ -51,6 +52,7 @@ where
     reference_sequences: Vec<fasta::Record>,
     data_container_builder: data_container::Builder,
     record_counter: i64,
+    quality_binning: Option<QualityBinning>,
 }
 
 impl<W> Writer<W>
@@ -71,9 +73,30 @@ where
             reference_sequences,
             data_container_builder: DataContainer::builder(RECORD_COUNTER_START),
             record_counter: RECORD_COUNTER_START,
+            quality_binning: None,
         }
     }
 
+    /// Sets a quality score binning scheme.
+    ///
+    /// When set, the quality scores of each record are binned as the record is written.
+    ///
+    /// By default, quality scores are not binned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::QualityBinning;
+    /// use noodles_cram as cram;
+    ///
+    /// let writer = cram::Writer::new(Vec::new(), Vec::new())
+    ///     .set_quality_binning(QualityBinning::illumina());
+    /// ```
+    pub fn set_quality_binning(mut self, quality_binning: QualityBinning) -> Self {
+        self.quality_binning = Some(quality_binning);
+        self
+    }
+
     /// Returns a reference to the underlying writer.
     ///
     /// # Examples
@@ -169,6 +192,10 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_record(&mut self, mut record: Record) -> io::Result<()> {
+        if let Some(quality_binning) = self.quality_binning {
+            quality_binning.apply(&mut record.quality_scores);
+        }
+
         loop {
             match add_record(
                 &mut self.data_container_builder,
@@ -7,5 +7,99 @@ mod writer;
 
 pub use self::{reader::Reader, record::Record, writer::Writer};
 
+use std::{
+    collections::HashSet,
+    io::{self, BufRead},
+};
+
+use self::indexer::Indexer;
+
 /// A FASTA index.
 pub type Index = Vec<Record>;
+
+/// Indexes a FASTA stream.
+///
+/// This scans all records in the stream and builds an index record (name, length, offset, line
+/// bases, and line width) for each, equivalent to `samtools faidx`.
+///
+/// # Errors
+///
+/// An error is returned if a record fails to be indexed, e.g., when its sequence is empty or its
+/// sequence lines do not have a uniform length, excluding the last line. Reference sequence
+/// names must also be unique.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_fasta::fai;
+///
+/// let data = b">sq0\nACGT\n>sq1\nNNNN\nNNNN\nNN\n";
+/// let index = fai::index(&data[..])?;
+///
+/// assert_eq!(index, [
+///     fai::Record::new(String::from("sq0"), 4, 5, 4, 5),
+///     fai::Record::new(String::from("sq1"), 10, 15, 4, 5),
+/// ]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn index<R>(reader: R) -> io::Result<Index>
+where
+    R: BufRead,
+{
+    let mut indexer = Indexer::new(reader);
+    let mut index = Vec::new();
+    let mut names = HashSet::new();
+
+    while let Some(record) = indexer.index_record()? {
+        if !names.insert(record.reference_sequence_name().to_string()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "duplicate reference sequence name: {}",
+                    record.reference_sequence_name()
+                ),
+            ));
+        }
+
+        index.push(record);
+    }
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index() -> io::Result<()> {
+        let data = b">sq0 LN:4\nACGT\n>sq1\nNNNNNNNN\nNN\n";
+        let index = index(&data[..])?;
+
+        assert_eq!(
+            index,
+            [
+                Record::new(String::from("sq0"), 4, 10, 4, 5),
+                Record::new(String::from("sq1"), 10, 20, 8, 9),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_with_invalid_records() {
+        let data = b">sq0\nACGT\nACG\nACGT\n";
+        assert!(index(&data[..]).is_err());
+
+        let data = b">sq0\n";
+        assert!(index(&data[..]).is_err());
+
+        let data = b">sq0\nACGT\n>sq0\nACGT\n";
+        assert!(matches!(
+            index(&data[..]),
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Indexed FASTA reader.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+#[cfg(feature = "bgzf")]
+use noodles_bgzf::{self as bgzf, gzi};
+
+use super::{
+    fai,
+    record::{Definition, Record},
+};
+
+enum Inner<R> {
+    Raw(R),
+    #[cfg(feature = "bgzf")]
+    Bgzf(Box<bgzf::Reader<R>>, gzi::Index),
+}
+
+/// An indexed FASTA reader.
+///
+/// An indexed reader uses a FASTA index (FAI) to read sequences and subsequences without reading
+/// the entire file. Both uncompressed and bgzipped FASTA files are supported. A bgzipped FASTA
+/// additionally requires its gzip index (GZI) to map the uncompressed offsets in the FASTA index
+/// to compressed positions, like `samtools faidx` with a `.fa.gz`. Reading bgzipped FASTA files
+/// requires the default `bgzf` feature.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "bgzf", doc = "```no_run")]
+#[cfg_attr(not(feature = "bgzf"), doc = "```ignore")]
+/// # use std::{fs::File, io};
+/// use noodles_bgzf::{self as bgzf, gzi};
+/// use noodles_fasta::{self as fasta, fai};
+///
+/// let index = File::open("reference.fa.gz")
+///     .map(bgzf::Reader::new)
+///     .and_then(fai::index)?;
+/// let gzi_index = gzi::read("reference.fa.gz.gzi")?;
+///
+/// let mut reader = File::open("reference.fa.gz")
+///     .map(|f| fasta::IndexedReader::bgzf(f, index, gzi_index))?;
+///
+/// let sequence = reader.query("sq0", 8, 13)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct IndexedReader<R> {
+    inner: Inner<R>,
+    index: fai::Index,
+}
+
+impl<R> IndexedReader<R>
+where
+    R: Read + Seek,
+{
+    /// Creates an indexed FASTA reader over an uncompressed FASTA.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_fasta::{self as fasta, fai};
+    ///
+    /// let data = b">sq0\nACGT\n>sq1\nNNNN\nNNNN\nNN\n";
+    /// let index = fai::index(&data[..])?;
+    ///
+    /// let reader = fasta::IndexedReader::new(io::Cursor::new(data), index);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(inner: R, index: fai::Index) -> Self {
+        Self {
+            inner: Inner::Raw(inner),
+            index,
+        }
+    }
+
+    /// Creates an indexed FASTA reader over a bgzipped FASTA.
+    ///
+    /// The FASTA index offsets are uncompressed positions, and the gzip index is used to find
+    /// the blocks that contain them.
+    ///
+    /// This is only available with the `bgzf` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// use noodles_bgzf::{self as bgzf, gzi};
+    /// use noodles_fasta::{self as fasta, fai};
+    ///
+    /// let mut writer = bgzf::Writer::new(Vec::new());
+    /// writer.write_all(b">sq0\nACGT\n>sq1\nNNNN\nNNNN\nNN\n")?;
+    /// let data = writer.finish()?;
+    ///
+    /// let index = fai::index(bgzf::Reader::new(&data[..]))?;
+    ///
+    /// let gzi_index = gzi::Index::new();
+    ///
+    /// let mut reader = fasta::IndexedReader::bgzf(io::Cursor::new(data), index, gzi_index);
+    /// assert_eq!(reader.query("sq0", 2, 3)?, b"CG");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    #[cfg(feature = "bgzf")]
+    pub fn bgzf(inner: R, index: fai::Index, gzi_index: gzi::Index) -> Self {
+        Self {
+            inner: Inner::Bgzf(Box::new(bgzf::Reader::new(inner)), gzi_index),
+            index,
+        }
+    }
+
+    /// Returns the FASTA index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_fasta::{self as fasta, fai};
+    ///
+    /// let data = b">sq0\nACGT\n";
+    /// let index = fai::index(&data[..])?;
+    ///
+    /// let reader = fasta::IndexedReader::new(io::Cursor::new(data), index);
+    /// assert_eq!(reader.index().len(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn index(&self) -> &fai::Index {
+        &self.index
+    }
+
+    /// Reads a subsequence of the sequence with the given name.
+    ///
+    /// `start` and `end` are 1-based and inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_fasta::{self as fasta, fai};
+    ///
+    /// let data = b">sq0\nACGT\n>sq1\nNNNN\nACGT\nNN\n";
+    /// let index = fai::index(&data[..])?;
+    ///
+    /// let mut reader = fasta::IndexedReader::new(io::Cursor::new(data), index);
+    /// assert_eq!(reader.query("sq1", 3, 6)?, b"NNAC");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn query(&mut self, name: &str, start: i64, end: i64) -> io::Result<Vec<u8>> {
+        let (offset, byte_len, len) = {
+            let record = self.get_index_record(name)?;
+
+            if start < 1 || start > end + 1 || end as u64 > record.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid interval: {}:{}-{}", name, start, end),
+                ));
+            }
+
+            let start = (start - 1) as u64;
+            let end = end as u64;
+
+            if start == end {
+                return Ok(Vec::new());
+            }
+
+            let offset = base_offset(record, start);
+            let byte_len = base_offset(record, end - 1) + 1 - offset;
+
+            (offset, byte_len, end - start)
+        };
+
+        self.seek(offset)?;
+
+        let mut buf = vec![0; byte_len as usize];
+        self.read_exact(&mut buf)?;
+
+        buf.retain(|&b| b != b'\n' && b != b'\r');
+
+        if buf.len() as u64 != len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "sequence does not match the index",
+            ));
+        }
+
+        Ok(buf)
+    }
+
+    /// Reads the entire sequence with the given name as a record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_fasta::{self as fasta, fai};
+    ///
+    /// let data = b">sq0\nACGT\n>sq1\nNNNN\nNNNN\nNN\n";
+    /// let index = fai::index(&data[..])?;
+    ///
+    /// let mut reader = fasta::IndexedReader::new(io::Cursor::new(data), index);
+    /// let record = reader.read_record("sq1")?;
+    /// assert_eq!(record.sequence(), b"NNNNNNNNNN");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_record(&mut self, name: &str) -> io::Result<Record> {
+        let len = self.get_index_record(name)?.len();
+        let sequence = self.query(name, 1, len as i64)?;
+        let definition = Definition::new(name.into(), None);
+        Ok(Record::new(definition, sequence))
+    }
+
+    fn get_index_record(&self, name: &str) -> io::Result<&fai::Record> {
+        self.index
+            .iter()
+            .find(|record| record.reference_sequence_name() == name)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid reference sequence name: {}", name),
+                )
+            })
+    }
+
+    fn seek(&mut self, pos: u64) -> io::Result<()> {
+        match &mut self.inner {
+            Inner::Raw(reader) => reader.seek(SeekFrom::Start(pos)).map(|_| ()),
+            #[cfg(feature = "bgzf")]
+            Inner::Bgzf(reader, index) => {
+                reader.seek_by_uncompressed_position(index, pos).map(|_| ())
+            }
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        match &mut self.inner {
+            Inner::Raw(reader) => reader.read_exact(buf),
+            #[cfg(feature = "bgzf")]
+            Inner::Bgzf(reader, _) => reader.read_exact(buf),
+        }
+    }
+}
+
+// Returns the byte offset of the 0-based base position.
+fn base_offset(record: &fai::Record, position: u64) -> u64 {
+    let line = position / record.line_bases();
+    let column = position % record.line_bases();
+    record.offset() + line * record.line_width() + column
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    static DATA: &[u8] = b">sq0\nACGT\n>sq1\r\nNNNN\r\nACGT\r\nNN\r\n";
+
+    #[test]
+    fn test_query() -> io::Result<()> {
+        let index = fai::index(DATA)?;
+        let mut reader = IndexedReader::new(Cursor::new(DATA), index);
+
+        assert_eq!(reader.query("sq0", 1, 4)?, b"ACGT");
+        assert_eq!(reader.query("sq0", 4, 3)?, b"");
+        assert_eq!(reader.query("sq1", 4, 9)?, b"NACGTN");
+        assert_eq!(reader.query("sq1", 10, 10)?, b"N");
+
+        assert!(reader.query("sq1", 0, 4).is_err());
+        assert!(reader.query("sq1", 8, 11).is_err());
+        assert!(reader.query("sq2", 1, 1).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "bgzf")]
+    #[test]
+    fn test_query_with_bgzf() -> io::Result<()> {
+        use std::io::Write;
+
+        // Split the FASTA into multiple blocks.
+        let mut writer = bgzf::Writer::new(Vec::new());
+        let mut gzi_index = gzi::Index::new();
+        let mut uncompressed_position = 0;
+
+        for chunk in DATA.chunks(7) {
+            writer.write_all(chunk)?;
+            writer.flush()?;
+
+            uncompressed_position += chunk.len() as u64;
+            gzi_index.push((writer.get_ref().len() as u64, uncompressed_position));
+        }
+
+        gzi_index.pop();
+        let data = writer.finish()?;
+
+        let index = fai::index(DATA)?;
+        let mut reader = IndexedReader::bgzf(Cursor::new(data), index, gzi_index);
+
+        assert_eq!(reader.query("sq0", 2, 4)?, b"CGT");
+        assert_eq!(reader.query("sq1", 4, 9)?, b"NACGTN");
+        assert_eq!(reader.read_record("sq1")?.sequence(), b"NNNNACGTNN");
+
+        Ok(())
+    }
+}
@@ -53,10 +53,11 @@
 //! ```
 
 pub mod fai;
+mod indexed_reader;
 pub mod reader;
 pub mod record;
 
-pub use self::{reader::Reader, record::Record};
+pub use self::{indexed_reader::IndexedReader, reader::Reader, record::Record};
 
 use std::{
     fs::File,
@@ -64,10 +65,10 @@ use std::{
     path::Path,
 };
 
-use self::fai::indexer::Indexer;
-
 /// Indexes a FASTA file.
 ///
+/// See [`fai::index`] for indexing a stream.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -80,12 +81,5 @@ pub fn index<P>(src: P) -> io::Result<fai::Index>
 where
     P: AsRef<Path>,
 {
-    let mut indexer = File::open(src).map(BufReader::new).map(Indexer::new)?;
-    let mut index = Vec::new();
-
-    while let Some(i) = indexer.index_record()? {
-        index.push(i);
-    }
-
-    Ok(index)
+    File::open(src).map(BufReader::new).and_then(fai::index)
 }
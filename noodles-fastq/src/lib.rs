@@ -11,6 +11,9 @@
 //! the read name, and is commonly discarded. The quality scores is list of Phred quality scores
 //! offset by 33 and is parallel to a base in the sequence.
 
+pub mod paired;
+pub mod trim;
+
 mod reader;
 mod record;
 mod writer;
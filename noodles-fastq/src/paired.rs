@@ -0,0 +1,287 @@
+//! Paired-end FASTQ interleaving and deinterleaving.
+//!
+//! Paired-end reads are commonly stored either as two files (R1 and R2), where the nth record of
+//! each file is a mate pair, or as a single interleaved file, where mates alternate.
+//!
+//! Mates are validated by read name. Read names are compared up to the first whitespace, and a
+//! trailing mate suffix (`/1` or `/2`) is ignored.
+
+use std::io;
+
+use super::Record;
+
+/// An iterator that interleaves two FASTQ record streams.
+///
+/// This is created by calling [`interleave`].
+pub struct Interleave<I, J> {
+    r1: I,
+    r2: J,
+    mate: Option<Record>,
+    is_done: bool,
+}
+
+/// Interleaves two FASTQ record streams, i.e., yields R1 and R2 records alternately.
+///
+/// The iterator returns an error if the read names of a pair do not match or if one stream has
+/// fewer records than the other. The iterator is fused after an error.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_fastq::{self as fastq, paired};
+///
+/// let r1 = b"@r0/1\nACGT\n+\nNDLS\n";
+/// let r2 = b"@r0/2\nTGCA\n+\nSLDN\n";
+///
+/// let mut reader1 = fastq::Reader::new(&r1[..]);
+/// let mut reader2 = fastq::Reader::new(&r2[..]);
+///
+/// let records: Vec<_> = paired::interleave(reader1.records(), reader2.records())
+///     .collect::<io::Result<_>>()?;
+///
+/// assert_eq!(records, [
+///     fastq::Record::new("r0/1", "ACGT", "NDLS"),
+///     fastq::Record::new("r0/2", "TGCA", "SLDN"),
+/// ]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn interleave<I, J>(r1: I, r2: J) -> Interleave<I, J>
+where
+    I: Iterator<Item = io::Result<Record>>,
+    J: Iterator<Item = io::Result<Record>>,
+{
+    Interleave {
+        r1,
+        r2,
+        mate: None,
+        is_done: false,
+    }
+}
+
+impl<I, J> Iterator for Interleave<I, J>
+where
+    I: Iterator<Item = io::Result<Record>>,
+    J: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        if let Some(mate) = self.mate.take() {
+            return Some(Ok(mate));
+        }
+
+        let result = match (self.r1.next(), self.r2.next()) {
+            (None, None) => {
+                self.is_done = true;
+                return None;
+            }
+            (Some(Err(e)), _) | (_, Some(Err(e))) => Err(e),
+            (Some(Ok(a)), Some(Ok(b))) => validate_mates(&a, &b).map(|_| {
+                self.mate = Some(b);
+                a
+            }),
+            (Some(Ok(_)), None) | (None, Some(Ok(_))) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "paired streams have a different number of records",
+            )),
+        };
+
+        if result.is_err() {
+            self.is_done = true;
+        }
+
+        Some(result)
+    }
+}
+
+/// An iterator that splits an interleaved FASTQ record stream into mate pairs.
+///
+/// This is created by calling [`deinterleave`].
+pub struct Deinterleave<I> {
+    records: I,
+    is_done: bool,
+}
+
+/// Splits an interleaved FASTQ record stream into (R1, R2) mate pairs.
+///
+/// Each item can be written to separate R1 and R2 writers. The iterator returns an error if the
+/// read names of a pair do not match or if the stream has an odd number of records. The iterator
+/// is fused after an error.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_fastq::{self as fastq, paired};
+///
+/// let data = b"@r0/1\nACGT\n+\nNDLS\n@r0/2\nTGCA\n+\nSLDN\n";
+/// let mut reader = fastq::Reader::new(&data[..]);
+///
+/// let mut writer1 = fastq::Writer::new(Vec::new());
+/// let mut writer2 = fastq::Writer::new(Vec::new());
+///
+/// for result in paired::deinterleave(reader.records()) {
+///     let (r1, r2) = result?;
+///     writer1.write_record(&r1)?;
+///     writer2.write_record(&r2)?;
+/// }
+///
+/// assert_eq!(writer1.get_ref(), b"@r0/1\nACGT\n+\nNDLS\n");
+/// assert_eq!(writer2.get_ref(), b"@r0/2\nTGCA\n+\nSLDN\n");
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn deinterleave<I>(records: I) -> Deinterleave<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    Deinterleave {
+        records,
+        is_done: false,
+    }
+}
+
+impl<I> Iterator for Deinterleave<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<(Record, Record)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        let result = match self.records.next() {
+            None => {
+                self.is_done = true;
+                return None;
+            }
+            Some(Err(e)) => Err(e),
+            Some(Ok(a)) => match self.records.next() {
+                None => Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "interleaved stream has an odd number of records",
+                )),
+                Some(Err(e)) => Err(e),
+                Some(Ok(b)) => validate_mates(&a, &b).map(|_| (a, b)),
+            },
+        };
+
+        if result.is_err() {
+            self.is_done = true;
+        }
+
+        Some(result)
+    }
+}
+
+fn validate_mates(a: &Record, b: &Record) -> io::Result<()> {
+    if base_read_name(a.read_name()) == base_read_name(b.read_name()) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "mate read names do not match: {} != {}",
+                String::from_utf8_lossy(a.read_name()),
+                String::from_utf8_lossy(b.read_name())
+            ),
+        ))
+    }
+}
+
+// Returns the read name without a description and mate suffix.
+fn base_read_name(read_name: &[u8]) -> &[u8] {
+    let end = read_name
+        .iter()
+        .position(|b| b.is_ascii_whitespace())
+        .unwrap_or(read_name.len());
+
+    let name = &read_name[..end];
+
+    if name.ends_with(b"/1") || name.ends_with(b"/2") {
+        &name[..name.len() - 2]
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_records(names: &[&str]) -> Vec<io::Result<Record>> {
+        names
+            .iter()
+            .map(|name| Ok(Record::new(*name, "ACGT", "NDLS")))
+            .collect()
+    }
+
+    #[test]
+    fn test_interleave() -> io::Result<()> {
+        let r1 = build_records(&["r0/1", "r1 1:N:0"]);
+        let r2 = build_records(&["r0/2", "r1 2:N:0"]);
+
+        let actual: Vec<_> = interleave(r1.into_iter(), r2.into_iter())
+            .map(|result| result.map(|record| record.read_name().to_vec()))
+            .collect::<io::Result<_>>()?;
+
+        let expected = [
+            b"r0/1".to_vec(),
+            b"r0/2".to_vec(),
+            b"r1 1:N:0".to_vec(),
+            b"r1 2:N:0".to_vec(),
+        ];
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interleave_with_invalid_pairs() {
+        let r1 = build_records(&["r0/1"]);
+        let r2 = build_records(&["r1/2"]);
+        let mut iter = interleave(r1.into_iter(), r2.into_iter());
+        assert!(matches!(iter.next(), Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData));
+        assert!(iter.next().is_none());
+
+        let r1 = build_records(&["r0/1", "r1/1"]);
+        let r2 = build_records(&["r0/2"]);
+        let mut iter = interleave(r1.into_iter(), r2.into_iter());
+        assert!(iter.next().unwrap().is_ok());
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_deinterleave() -> io::Result<()> {
+        let records = build_records(&["r0/1", "r0/2", "r1", "r1"]);
+        let pairs: Vec<_> = deinterleave(records.into_iter()).collect::<io::Result<_>>()?;
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].1.read_name(), b"r0/2");
+
+        let records = build_records(&["r0/1", "r0/2", "r1/1"]);
+        let mut iter = deinterleave(records.into_iter());
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_read_name() {
+        assert_eq!(base_read_name(b"r0"), b"r0");
+        assert_eq!(base_read_name(b"r0/1"), b"r0");
+        assert_eq!(base_read_name(b"r0/2 desc"), b"r0");
+        assert_eq!(base_read_name(b"r0 1:N:0:1"), b"r0");
+        assert_eq!(base_read_name(b"r0/3"), b"r0/3");
+    }
+}
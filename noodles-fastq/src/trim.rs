@@ -0,0 +1,381 @@
+//! FASTQ read trimming.
+//!
+//! Trimmers remove bases (and their quality scores) from the ends of a record. They can be applied
+//! to a record stream using [`trim`], and multiple trimmers are applied by chaining adapters.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::io;
+//! use noodles_fastq::{
+//!     self as fastq,
+//!     trim::{self, AdapterTrimmer, Crop, QualityTrimmer},
+//! };
+//!
+//! let data = b"@r0\nNACGTACGTAGATCGGAAGAGC\n+\n#IIIIIIIIIIIIIIIIIIIII\n";
+//! let mut reader = fastq::Reader::new(&data[..]);
+//!
+//! let records = trim::trim(reader.records(), Crop::new(1, 0));
+//! let records = trim::trim(records, AdapterTrimmer::new(b"AGATCGGAAGAGC".to_vec()));
+//! let records = trim::trim(records, QualityTrimmer::new(4, 20));
+//!
+//! let records: Vec<_> = records.collect::<io::Result<_>>()?;
+//! assert_eq!(records[0].sequence(), b"ACGTACGT");
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::io;
+
+use super::Record;
+
+const QUALITY_SCORE_OFFSET: u8 = b'!';
+
+/// A FASTQ read trimmer.
+pub trait Trimmer {
+    /// Trims the sequence and quality scores of a record in place.
+    fn trim(&self, record: &mut Record);
+}
+
+/// A trimmer that removes a fixed number of bases from the start and end of a read.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Crop {
+    head: usize,
+    tail: usize,
+}
+
+impl Crop {
+    /// Creates a trimmer that removes `head` bases from the start and `tail` bases from the end
+    /// of a read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fastq::{trim::{Crop, Trimmer}, Record};
+    ///
+    /// let mut record = Record::new("r0", "ACGTACGT", "NDLSNDLS");
+    /// Crop::new(1, 2).trim(&mut record);
+    ///
+    /// assert_eq!(record.sequence(), b"CGTAC");
+    /// assert_eq!(record.quality_scores(), b"DLSND");
+    /// ```
+    pub fn new(head: usize, tail: usize) -> Self {
+        Self { head, tail }
+    }
+}
+
+impl Trimmer for Crop {
+    fn trim(&self, record: &mut Record) {
+        let len = record.sequence().len();
+        let end = len.saturating_sub(self.tail);
+        let start = self.head.min(end);
+        truncate(record, start, end);
+    }
+}
+
+/// A trimmer that cuts a read once the average quality in a sliding window falls below a
+/// threshold.
+///
+/// The window is scanned from the start of the read. The read is cut at the start of the first
+/// window with a mean quality score below the threshold. If the read is shorter than the window,
+/// the entire read is treated as a single window.
+///
+/// Quality scores are expected to be Phred scores offset by 33.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QualityTrimmer {
+    window_size: usize,
+    min_quality: u8,
+}
+
+impl QualityTrimmer {
+    /// Creates a sliding window quality trimmer.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `window_size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fastq::{trim::{QualityTrimmer, Trimmer}, Record};
+    ///
+    /// let mut record = Record::new("r0", "ACGTACGT", "IIII##II");
+    /// QualityTrimmer::new(2, 20).trim(&mut record);
+    ///
+    /// assert_eq!(record.sequence(), b"ACGT");
+    /// ```
+    pub fn new(window_size: usize, min_quality: u8) -> Self {
+        assert!(window_size > 0, "window size must be > 0");
+
+        Self {
+            window_size,
+            min_quality,
+        }
+    }
+}
+
+impl Trimmer for QualityTrimmer {
+    fn trim(&self, record: &mut Record) {
+        let scores: Vec<u32> = record
+            .quality_scores()
+            .iter()
+            .map(|&b| u32::from(b.saturating_sub(QUALITY_SCORE_OFFSET)))
+            .collect();
+
+        let window_size = self.window_size.min(scores.len());
+
+        if window_size == 0 {
+            return;
+        }
+
+        let min_sum = u32::from(self.min_quality) * window_size as u32;
+
+        let end = scores
+            .windows(window_size)
+            .position(|window| window.iter().sum::<u32>() < min_sum)
+            .unwrap_or(scores.len());
+
+        truncate(record, 0, end);
+    }
+}
+
+/// A trimmer that clips an adapter sequence and everything after it from the end of a read.
+///
+/// The adapter is searched from the start of the read, allowing up to a maximum number of
+/// mismatches. A partial adapter at the end of the read is also clipped if it overlaps by at least
+/// the minimum overlap length. `N` in the read matches any adapter base.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdapterTrimmer {
+    adapter: Vec<u8>,
+    max_mismatches: usize,
+    min_overlap: usize,
+}
+
+impl AdapterTrimmer {
+    /// Creates an adapter trimmer.
+    ///
+    /// By default, no mismatches are allowed, and the minimum overlap is the length of the
+    /// adapter, i.e., only full adapter matches are clipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fastq::{trim::{AdapterTrimmer, Trimmer}, Record};
+    ///
+    /// let mut record = Record::new("r0", "ACGTAGATCG", "NDLSNDLSND");
+    /// AdapterTrimmer::new(b"AGATCG".to_vec()).trim(&mut record);
+    ///
+    /// assert_eq!(record.sequence(), b"ACGT");
+    /// ```
+    pub fn new(adapter: Vec<u8>) -> Self {
+        let min_overlap = adapter.len();
+
+        Self {
+            adapter,
+            max_mismatches: 0,
+            min_overlap,
+        }
+    }
+
+    /// Sets the maximum number of mismatches allowed in an adapter match.
+    ///
+    /// For partial matches at the end of the read, the allowed mismatches are scaled by the
+    /// overlap length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fastq::{trim::{AdapterTrimmer, Trimmer}, Record};
+    ///
+    /// let mut record = Record::new("r0", "ACGTAGTTCG", "NDLSNDLSND");
+    /// AdapterTrimmer::new(b"AGATCG".to_vec())
+    ///     .set_max_mismatches(1)
+    ///     .trim(&mut record);
+    ///
+    /// assert_eq!(record.sequence(), b"ACGT");
+    /// ```
+    pub fn set_max_mismatches(mut self, max_mismatches: usize) -> Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    /// Sets the minimum overlap of a partial adapter match at the end of the read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fastq::{trim::{AdapterTrimmer, Trimmer}, Record};
+    ///
+    /// let mut record = Record::new("r0", "ACGTACGTAGA", "NDLSNDLSNDL");
+    /// AdapterTrimmer::new(b"AGATCG".to_vec())
+    ///     .set_min_overlap(3)
+    ///     .trim(&mut record);
+    ///
+    /// assert_eq!(record.sequence(), b"ACGTACGT");
+    /// ```
+    pub fn set_min_overlap(mut self, min_overlap: usize) -> Self {
+        self.min_overlap = min_overlap.max(1);
+        self
+    }
+
+    fn find(&self, sequence: &[u8]) -> Option<usize> {
+        (0..sequence.len()).find(|&i| {
+            let read = &sequence[i..];
+            let len = read.len().min(self.adapter.len());
+
+            if len < self.adapter.len() && len < self.min_overlap {
+                return false;
+            }
+
+            let max_mismatches = self.max_mismatches * len / self.adapter.len();
+
+            let mismatches = read
+                .iter()
+                .zip(&self.adapter)
+                .filter(|&(&a, &b)| !bases_eq(a, b))
+                .count();
+
+            mismatches <= max_mismatches
+        })
+    }
+}
+
+impl Trimmer for AdapterTrimmer {
+    fn trim(&self, record: &mut Record) {
+        if self.adapter.is_empty() {
+            return;
+        }
+
+        if let Some(end) = self.find(record.sequence()) {
+            truncate(record, 0, end);
+        }
+    }
+}
+
+/// An iterator adapter that trims FASTQ records.
+///
+/// This is created by calling [`trim`].
+pub struct Trim<I, T> {
+    records: I,
+    trimmer: T,
+}
+
+/// Applies a trimmer to each record of a FASTQ record stream.
+///
+/// Reads may become empty after trimming, and it is up to the caller to filter them.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_fastq::{self as fastq, trim::{self, Crop}};
+///
+/// let data = b"@r0\nACGT\n+\nNDLS\n";
+/// let mut reader = fastq::Reader::new(&data[..]);
+///
+/// let records: Vec<_> = trim::trim(reader.records(), Crop::new(0, 1))
+///     .collect::<io::Result<_>>()?;
+///
+/// assert_eq!(records, [fastq::Record::new("r0", "ACG", "NDL")]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn trim<I, T>(records: I, trimmer: T) -> Trim<I, T>
+where
+    I: Iterator<Item = io::Result<Record>>,
+    T: Trimmer,
+{
+    Trim { records, trimmer }
+}
+
+impl<I, T> Iterator for Trim<I, T>
+where
+    I: Iterator<Item = io::Result<Record>>,
+    T: Trimmer,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.next().map(|result| {
+            result.map(|mut record| {
+                self.trimmer.trim(&mut record);
+                record
+            })
+        })
+    }
+}
+
+fn bases_eq(read_base: u8, adapter_base: u8) -> bool {
+    let read_base = read_base.to_ascii_uppercase();
+    read_base == b'N' || read_base == adapter_base.to_ascii_uppercase()
+}
+
+// Keeps the bases and quality scores in [start, end).
+fn truncate(record: &mut Record, start: usize, end: usize) {
+    let sequence = record.sequence_mut();
+    let end = end.min(sequence.len());
+    let start = start.min(end);
+    sequence.truncate(end);
+    sequence.drain(..start);
+
+    let quality_scores = record.quality_scores_mut();
+    quality_scores.truncate(end);
+    quality_scores.drain(..start.min(quality_scores.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop() {
+        let mut record = Record::new("r0", "ACGT", "NDLS");
+        Crop::new(3, 3).trim(&mut record);
+        assert!(record.sequence().is_empty());
+        assert!(record.quality_scores().is_empty());
+
+        let mut record = Record::new("r0", "ACGT", "NDLS");
+        Crop::default().trim(&mut record);
+        assert_eq!(record, Record::new("r0", "ACGT", "NDLS"));
+    }
+
+    #[test]
+    fn test_quality_trimmer() {
+        let mut record = Record::new("r0", "ACGTACGT", "IIIIIIII");
+        QualityTrimmer::new(4, 20).trim(&mut record);
+        assert_eq!(record.sequence(), b"ACGTACGT");
+
+        let mut record = Record::new("r0", "ACGTACGT", "#IIIIIII");
+        QualityTrimmer::new(1, 20).trim(&mut record);
+        assert!(record.sequence().is_empty());
+
+        let mut record = Record::new("r0", "AC", "##");
+        QualityTrimmer::new(4, 20).trim(&mut record);
+        assert!(record.sequence().is_empty());
+
+        let mut record = Record::default();
+        QualityTrimmer::new(4, 20).trim(&mut record);
+        assert!(record.sequence().is_empty());
+    }
+
+    #[test]
+    fn test_adapter_trimmer() {
+        let trimmer = AdapterTrimmer::new(b"AGATCG".to_vec());
+
+        let mut record = Record::new("r0", "ACGTACGT", "NDLSNDLS");
+        trimmer.trim(&mut record);
+        assert_eq!(record.sequence(), b"ACGTACGT");
+
+        let mut record = Record::new("r0", "AGATCGACGT", "NDLSNDLSND");
+        trimmer.trim(&mut record);
+        assert!(record.sequence().is_empty());
+
+        let mut record = Record::new("r0", "ACGTAGNTCGAC", "NDLSNDLSNDLS");
+        trimmer.trim(&mut record);
+        assert_eq!(record.sequence(), b"ACGT");
+        assert_eq!(record.quality_scores(), b"NDLS");
+
+        let trimmer = AdapterTrimmer::new(b"AGATCG".to_vec()).set_min_overlap(2);
+        let mut record = Record::new("r0", "ACGTAG", "NDLSND");
+        trimmer.trim(&mut record);
+        assert_eq!(record.sequence(), b"ACGT");
+    }
+}
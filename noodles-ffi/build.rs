@@ -0,0 +1,29 @@
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").expect("missing CARGO_MANIFEST_DIR");
+    let include_dir = env::var("OUT_DIR")
+        .map(|out_dir| PathBuf::from(out_dir).join("include"))
+        .expect("missing OUT_DIR");
+
+    fs::create_dir_all(&include_dir).expect("unable to create include directory");
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some(String::from("NOODLES_H")),
+        autogen_warning: Some(String::from(
+            "/* This file is generated by the noodles-ffi build script. Do not edit. */",
+        )),
+        usize_is_size_t: true,
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("unable to generate C bindings")
+        .write_to_file(include_dir.join("noodles.h"));
+
+    println!("cargo:rerun-if-changed=src");
+}
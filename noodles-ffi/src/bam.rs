@@ -0,0 +1,510 @@
+//! BAM reader and record functions.
+
+use std::{
+    ffi::CStr,
+    fs::File,
+    io,
+    os::raw::{c_char, c_int},
+    ptr,
+};
+
+use noodles_bam as bam;
+
+use super::{
+    copy_bytes_to_buf, copy_to_buf,
+    error::{catch_panic, set_last_error},
+};
+
+/// A BAM reader.
+pub struct NoodlesBamReader {
+    inner: bam::Reader<File>,
+    header: String,
+    reference_sequence_names: Vec<String>,
+    reference_sequence_lengths: Vec<i32>,
+}
+
+/// A BAM record.
+#[derive(Default)]
+pub struct NoodlesBamRecord {
+    inner: bam::Record,
+}
+
+/// Opens a BAM file and reads its header.
+///
+/// This returns `NULL` on error.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_open(path: *const c_char) -> *mut NoodlesBamReader {
+    catch_panic(ptr::null_mut(), || {
+        if path.is_null() {
+            set_last_error("path is null");
+            return ptr::null_mut();
+        }
+
+        let path = match CStr::from_ptr(path).to_str() {
+            Ok(path) => path,
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
+        };
+
+        match open(path) {
+            Ok(reader) => Box::into_raw(Box::new(reader)),
+            Err(e) => {
+                set_last_error(e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+fn open(path: &str) -> io::Result<NoodlesBamReader> {
+    let mut inner = File::open(path).map(bam::Reader::new)?;
+
+    let header = inner.read_header()?;
+
+    let reference_sequences = inner.read_reference_sequences()?;
+
+    let reference_sequence_names = reference_sequences
+        .iter()
+        .map(|rs| rs.name().into())
+        .collect();
+
+    let reference_sequence_lengths = reference_sequences.iter().map(|rs| rs.len()).collect();
+
+    Ok(NoodlesBamReader {
+        inner,
+        header,
+        reference_sequence_names,
+        reference_sequence_lengths,
+    })
+}
+
+/// Closes a BAM reader.
+///
+/// # Safety
+///
+/// `reader` must be null or a reader returned by [`noodles_bam_reader_open`] that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_free(reader: *mut NoodlesBamReader) {
+    catch_panic((), || {
+        if !reader.is_null() {
+            drop(Box::from_raw(reader));
+        }
+    })
+}
+
+/// Copies the raw SAM header of a BAM reader into a buffer.
+///
+/// This returns the length of the header.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_header(
+    reader: *const NoodlesBamReader,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || copy_to_buf((*reader).header.as_bytes(), buf, len))
+}
+
+/// Returns the number of reference sequences in a BAM reader.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_reference_sequence_count(
+    reader: *const NoodlesBamReader,
+) -> usize {
+    catch_panic(0, || (*reader).reference_sequence_names.len())
+}
+
+/// Copies the name of a reference sequence by its ID into a buffer.
+///
+/// This returns the length of the name or `0` if the ID is out of range.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_reference_sequence_name(
+    reader: *const NoodlesBamReader,
+    id: i32,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let reader = &*reader;
+
+        match usize_from_id(id).and_then(|i| reader.reference_sequence_names.get(i)) {
+            Some(name) => copy_to_buf(name.as_bytes(), buf, len),
+            None => {
+                set_last_error("invalid reference sequence ID");
+                0
+            }
+        }
+    })
+}
+
+/// Returns the length of a reference sequence by its ID.
+///
+/// This returns `-1` if the ID is out of range.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_reference_sequence_length(
+    reader: *const NoodlesBamReader,
+    id: i32,
+) -> i32 {
+    catch_panic(-1, || {
+        let reader = &*reader;
+
+        usize_from_id(id)
+            .and_then(|i| reader.reference_sequence_lengths.get(i))
+            .copied()
+            .unwrap_or(-1)
+    })
+}
+
+fn usize_from_id(id: i32) -> Option<usize> {
+    if id < 0 {
+        None
+    } else {
+        Some(id as usize)
+    }
+}
+
+/// Reads the next record.
+///
+/// This returns `1` if a record was read, `0` at EOF, and `-1` on error.
+///
+/// # Safety
+///
+/// `reader` and `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_reader_read_record(
+    reader: *mut NoodlesBamReader,
+    record: *mut NoodlesBamRecord,
+) -> c_int {
+    catch_panic(-1, || {
+        match (*reader).inner.read_record(&mut (*record).inner) {
+            Ok(0) => 0,
+            Ok(_) => 1,
+            Err(e) => {
+                set_last_error(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Creates an empty BAM record.
+///
+/// This returns `NULL` on error.
+#[no_mangle]
+pub extern "C" fn noodles_bam_record_new() -> *mut NoodlesBamRecord {
+    catch_panic(ptr::null_mut(), || {
+        Box::into_raw(Box::new(NoodlesBamRecord::default()))
+    })
+}
+
+/// Frees a BAM record.
+///
+/// # Safety
+///
+/// `record` must be null or a record returned by [`noodles_bam_record_new`] that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_free(record: *mut NoodlesBamRecord) {
+    catch_panic((), || {
+        if !record.is_null() {
+            drop(Box::from_raw(record));
+        }
+    })
+}
+
+/// Copies the read name of a BAM record into a buffer.
+///
+/// This returns the length of the read name or `0` if the read name is invalid.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_read_name(
+    record: *const NoodlesBamRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || match (*record).inner.read_name() {
+        Ok(read_name) => copy_to_buf(read_name.to_bytes(), buf, len),
+        Err(e) => {
+            set_last_error(e);
+            0
+        }
+    })
+}
+
+/// Returns the flags of a BAM record.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_flags(record: *const NoodlesBamRecord) -> u16 {
+    catch_panic(0, || u16::from((*record).inner.flags()))
+}
+
+/// Returns the reference sequence ID of a BAM record.
+///
+/// This returns `-1` if the record has no reference sequence.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_reference_sequence_id(
+    record: *const NoodlesBamRecord,
+) -> i32 {
+    catch_panic(-1, || {
+        (*record)
+            .inner
+            .reference_sequence_id()
+            .map(i32::from)
+            .unwrap_or(-1)
+    })
+}
+
+/// Returns the 1-based start position of a BAM record.
+///
+/// This returns `0` if the record has no position.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_position(record: *const NoodlesBamRecord) -> i64 {
+    catch_panic(0, || (*record).inner.position().map(i64::from).unwrap_or(0))
+}
+
+/// Returns the mapping quality of a BAM record.
+///
+/// A missing mapping quality is `255`.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_mapping_quality(record: *const NoodlesBamRecord) -> u8 {
+    catch_panic(255, || u8::from((*record).inner.mapping_quality()))
+}
+
+/// Returns the mate reference sequence ID of a BAM record.
+///
+/// This returns `-1` if the record has no mate reference sequence.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_mate_reference_sequence_id(
+    record: *const NoodlesBamRecord,
+) -> i32 {
+    catch_panic(-1, || {
+        (*record)
+            .inner
+            .mate_reference_sequence_id()
+            .map(i32::from)
+            .unwrap_or(-1)
+    })
+}
+
+/// Returns the 1-based mate start position of a BAM record.
+///
+/// This returns `0` if the record has no mate position.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_mate_position(record: *const NoodlesBamRecord) -> i64 {
+    catch_panic(0, || {
+        (*record).inner.mate_position().map(i64::from).unwrap_or(0)
+    })
+}
+
+/// Returns the template length of a BAM record.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_template_length(
+    record: *const NoodlesBamRecord,
+) -> i32 {
+    catch_panic(0, || (*record).inner.template_length())
+}
+
+/// Copies the CIGAR string of a BAM record into a buffer.
+///
+/// This returns the length of the CIGAR string.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_cigar(
+    record: *const NoodlesBamRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let cigar = (*record).inner.cigar().to_string();
+        copy_to_buf(cigar.as_bytes(), buf, len)
+    })
+}
+
+/// Copies the sequence of a BAM record into a buffer.
+///
+/// This returns the number of bases.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_sequence(
+    record: *const NoodlesBamRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let sequence = (*record).inner.sequence().to_string();
+        copy_to_buf(sequence.as_bytes(), buf, len)
+    })
+}
+
+/// Copies the raw quality scores of a BAM record into a buffer.
+///
+/// The scores are Phred quality scores without an ASCII offset, and the buffer is not
+/// NUL-terminated. This returns the number of scores.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_bam_record_quality_scores(
+    record: *const NoodlesBamRecord,
+    buf: *mut u8,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let quality_scores = (*record).inner.quality_scores();
+        copy_bytes_to_buf(&quality_scores, buf, len)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, ffi::CString, fs, io::Write};
+
+    use noodles_sam as sam;
+
+    use super::*;
+
+    #[test]
+    fn test_read_records() -> Result<(), Box<dyn std::error::Error>> {
+        let header: sam::Header = "@HD\tVN:1.6\n@SQ\tSN:sq0\tLN:8\n".parse()?;
+        let record: sam::Record = "r0\t0\tsq0\t2\t60\t4M\t*\t0\t0\tACGT\tNDLS".parse()?;
+
+        let mut writer = bam::Writer::new(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_reference_sequences(header.reference_sequences())?;
+        writer.write_sam_record(header.reference_sequences(), &record)?;
+        let data = writer.finish()?;
+
+        let path = env::temp_dir().join(format!("noodles-ffi-{}.bam", std::process::id()));
+        fs::File::create(&path)?.write_all(&data)?;
+        let raw_path = CString::new(path.to_str().unwrap())?;
+
+        unsafe {
+            let reader = noodles_bam_reader_open(raw_path.as_ptr());
+            assert!(!reader.is_null());
+
+            let mut buf = [0 as c_char; 8];
+
+            assert_eq!(noodles_bam_reader_header(reader, ptr::null_mut(), 0), 27);
+
+            assert_eq!(noodles_bam_reader_reference_sequence_count(reader), 1);
+            assert_eq!(
+                noodles_bam_reader_reference_sequence_name(reader, 0, buf.as_mut_ptr(), buf.len()),
+                3
+            );
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("sq0"));
+            assert_eq!(
+                noodles_bam_reader_reference_sequence_name(reader, 1, buf.as_mut_ptr(), buf.len()),
+                0
+            );
+            assert_eq!(noodles_bam_reader_reference_sequence_length(reader, 0), 8);
+
+            let record = noodles_bam_record_new();
+
+            assert_eq!(noodles_bam_reader_read_record(reader, record), 1);
+            assert_eq!(
+                noodles_bam_record_read_name(record, buf.as_mut_ptr(), buf.len()),
+                2
+            );
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("r0"));
+            assert_eq!(noodles_bam_record_flags(record), 0);
+            assert_eq!(noodles_bam_record_reference_sequence_id(record), 0);
+            assert_eq!(noodles_bam_record_position(record), 2);
+            assert_eq!(noodles_bam_record_mapping_quality(record), 60);
+            assert_eq!(noodles_bam_record_mate_reference_sequence_id(record), -1);
+            assert_eq!(
+                noodles_bam_record_cigar(record, buf.as_mut_ptr(), buf.len()),
+                2
+            );
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("4M"));
+
+            assert_eq!(noodles_bam_record_sequence(record, ptr::null_mut(), 0), 4);
+            assert_eq!(
+                noodles_bam_record_sequence(record, buf.as_mut_ptr(), buf.len()),
+                4
+            );
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("ACGT"));
+
+            let mut scores = [0; 4];
+            assert_eq!(
+                noodles_bam_record_quality_scores(record, scores.as_mut_ptr(), scores.len()),
+                4
+            );
+            assert_eq!(scores, [45, 35, 43, 50]);
+
+            assert_eq!(noodles_bam_reader_read_record(reader, record), 0);
+
+            noodles_bam_record_free(record);
+            noodles_bam_reader_free(reader);
+        }
+
+        fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_noodles_bam_reader_open_with_missing_file() {
+        let path = CString::new("/nonexistent/sample.bam").unwrap();
+        let reader = unsafe { noodles_bam_reader_open(path.as_ptr()) };
+        assert!(reader.is_null());
+        assert!(!crate::noodles_last_error_message().is_null());
+    }
+}
@@ -0,0 +1,98 @@
+//! Error reporting.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::CString,
+    fmt,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn set_last_error<E>(e: E)
+where
+    E: fmt::Display,
+{
+    // Interior NULs cannot be represented in a C string.
+    let message = e.to_string().replace('\0', " ");
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+// Runs `f`, returning `error_value` and setting the last error if it panics.
+//
+// Unwinding out of an `extern "C"` function is undefined behavior, so every entry point runs its
+// body using this.
+pub(crate) fn catch_panic<F, T>(error_value: T, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            set_last_error(format!("panic: {}", panic_message(payload.as_ref())));
+            error_value
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown error"
+    }
+}
+
+/// Returns the message of the last error on the current thread.
+///
+/// This returns `NULL` if no error occurred. The string is owned by the library and is valid
+/// until the next error on the same thread.
+#[no_mangle]
+pub extern "C" fn noodles_last_error_message() -> *const c_char {
+    catch_panic(ptr::null(), || {
+        LAST_ERROR.with(|last_error| {
+            last_error
+                .borrow()
+                .as_ref()
+                .map(|message| message.as_ptr())
+                .unwrap_or(ptr::null())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn test_set_last_error() {
+        set_last_error("invalid\0record");
+        let message = unsafe { CStr::from_ptr(noodles_last_error_message()) };
+        assert_eq!(message.to_str(), Ok("invalid record"));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(-1, || 1), 1);
+
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let value = catch_panic(-1, || panic!("noodles"));
+        panic::set_hook(previous_hook);
+
+        assert_eq!(value, -1);
+
+        let message = unsafe { CStr::from_ptr(noodles_last_error_message()) };
+        assert_eq!(message.to_str(), Ok("panic: noodles"));
+    }
+}
@@ -0,0 +1,108 @@
+#![deny(missing_docs)]
+
+//! **noodles-ffi** is a C ABI for reading BAM and VCF files with noodles.
+//!
+//! The C header is generated by the build script and written to `include/noodles.h` in the
+//! build output directory (`OUT_DIR`).
+//!
+//! With the `python` feature, this crate also provides a `noodles` Python module. See
+//! `pyproject.toml` for building it as an extension module with maturin.
+//!
+//! # Conventions
+//!
+//! * Readers and records are opaque handles that are created by `*_open` or `*_new` functions and
+//!   must be released by the matching `*_free` function.
+//! * Functions that read return `1` when a record is read, `0` at EOF, and `-1` on error.
+//!   Functions that create handles return `NULL` on error. The message of the last error on the
+//!   current thread is returned by [`noodles_last_error_message`].
+//! * Panics do not unwind into the caller. A panic is reported as an error, and the function
+//!   returns its error value, i.e., `-1`, `NULL`, `0` for lengths and counts, or the missing
+//!   value of a field.
+//! * All strings, including headers and names, are copied into a caller-provided buffer, like
+//!   `snprintf`: at most `len - 1` bytes are written, followed by a NUL terminator, and the full
+//!   length of the string (excluding the terminator) is returned. Call with a `NULL` buffer to
+//!   query the length. The only exception is the last error message, which is owned by the
+//!   library.
+//! * Positions are 1-based. A missing position is `0`.
+//!
+//! # Examples
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include "noodles.h"
+//!
+//! int main(void) {
+//!     NoodlesBamReader *reader = noodles_bam_reader_open("sample.bam");
+//!
+//!     if (reader == NULL) {
+//!         fprintf(stderr, "%s\n", noodles_last_error_message());
+//!         return 1;
+//!     }
+//!
+//!     NoodlesBamRecord *record = noodles_bam_record_new();
+//!     char read_name[256];
+//!
+//!     while (noodles_bam_reader_read_record(reader, record) == 1) {
+//!         noodles_bam_record_read_name(record, read_name, sizeof(read_name));
+//!         printf("%s\t%lld\n", read_name, (long long) noodles_bam_record_position(record));
+//!     }
+//!
+//!     noodles_bam_record_free(record);
+//!     noodles_bam_reader_free(reader);
+//!
+//!     return 0;
+//! }
+//! ```
+
+pub mod bam;
+pub mod error;
+#[cfg(feature = "python")]
+mod python;
+pub mod vcf;
+
+pub use self::error::noodles_last_error_message;
+
+use std::{os::raw::c_char, ptr, slice};
+
+// Copies `src` into a caller-provided buffer with `snprintf` semantics.
+//
+// Safety: `buf` must be null or valid for writes of `len` bytes.
+unsafe fn copy_to_buf(src: &[u8], buf: *mut c_char, len: usize) -> usize {
+    if !buf.is_null() && len > 0 {
+        let n = src.len().min(len - 1);
+        ptr::copy_nonoverlapping(src.as_ptr(), buf as *mut u8, n);
+        *buf.add(n) = 0;
+    }
+
+    src.len()
+}
+
+// Copies `src` into a caller-provided byte buffer, returning the full length of `src`.
+//
+// Safety: `buf` must be null or valid for writes of `len` bytes.
+unsafe fn copy_bytes_to_buf(src: &[u8], buf: *mut u8, len: usize) -> usize {
+    if !buf.is_null() {
+        let dst = slice::from_raw_parts_mut(buf, len);
+        let n = src.len().min(len);
+        dst[..n].copy_from_slice(&src[..n]);
+    }
+
+    src.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_to_buf() {
+        let mut buf = [0x7f as c_char; 4];
+
+        let n = unsafe { copy_to_buf(b"noodles", buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(n, 7);
+        assert_eq!(buf, [b'n' as c_char, b'o' as c_char, b'o' as c_char, 0]);
+
+        let n = unsafe { copy_to_buf(b"noodles", ptr::null_mut(), 0) };
+        assert_eq!(n, 7);
+    }
+}
@@ -0,0 +1,45 @@
+//! Python bindings.
+//!
+//! With the `python` feature, this crate builds the `noodles` Python module using pyo3. It has two
+//! submodules, `noodles.bam` and `noodles.vcf`, each with a `Reader` and a `Record` class. Build
+//! the extension module with the `extension-module` feature, e.g., using maturin.
+//!
+//! ```python
+//! import noodles
+//!
+//! reader = noodles.bam.Reader("sample.bam")
+//!
+//! for record in reader.query("sq0:8-13"):
+//!     print(record.read_name, record.position, record.cigar)
+//! ```
+//!
+//! Records wrap the native records, so fields are only decoded when accessed.
+
+mod bam;
+mod vcf;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// The `noodles` Python module.
+#[pymodule]
+#[pyo3(name = "noodles")]
+fn noodles_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+
+    let bam_module = PyModule::new(py, "bam")?;
+    bam::register(&bam_module)?;
+    m.add_submodule(&bam_module)?;
+
+    let vcf_module = PyModule::new(py, "vcf")?;
+    vcf::register(&vcf_module)?;
+    m.add_submodule(&vcf_module)?;
+
+    Ok(())
+}
+
+fn invalid_data<E>(e: E) -> PyErr
+where
+    E: std::fmt::Display,
+{
+    PyValueError::new_err(e.to_string())
+}
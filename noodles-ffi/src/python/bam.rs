@@ -0,0 +1,195 @@
+use std::{fs::File, path::PathBuf};
+
+use noodles::Region;
+use noodles_bam::{self as bam, bai};
+use noodles_sam as sam;
+use pyo3::{prelude::*, types::PyBytes};
+
+use super::invalid_data;
+
+pub(super) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Reader>()?;
+    m.add_class::<Record>()?;
+    Ok(())
+}
+
+/// A BAM reader.
+///
+/// The header is read when the reader is opened. Iterating the reader yields records.
+#[pyclass(module = "noodles.bam", unsendable)]
+pub struct Reader {
+    inner: bam::Reader<File>,
+    src: PathBuf,
+    raw_header: String,
+    header: sam::Header,
+    index: Option<bai::Index>,
+}
+
+#[pymethods]
+impl Reader {
+    #[new]
+    fn new(src: PathBuf) -> PyResult<Self> {
+        let mut inner = File::open(&src).map(bam::Reader::new)?;
+
+        let raw_header = inner.read_header()?;
+        let header = raw_header.parse().map_err(invalid_data)?;
+        inner.read_reference_sequences()?;
+
+        Ok(Self {
+            inner,
+            src,
+            raw_header,
+            header,
+            index: None,
+        })
+    }
+
+    /// The raw SAM header.
+    #[getter]
+    fn header(&self) -> &str {
+        &self.raw_header
+    }
+
+    /// The reference sequence names and lengths.
+    #[getter]
+    fn reference_sequences(&self) -> Vec<(String, i32)> {
+        self.header
+            .reference_sequences()
+            .values()
+            .map(|rs| (rs.name().into(), rs.len()))
+            .collect()
+    }
+
+    /// Returns the records that intersect a region, e.g., `sq0:8-13`.
+    ///
+    /// This reads the associated index (`<src>.bai`) on first use.
+    fn query(&mut self, region: &str) -> PyResult<Vec<Record>> {
+        let reference_sequences = self.header.reference_sequences();
+        let region = Region::from_str_reference_sequences(region, reference_sequences)
+            .map_err(invalid_data)?;
+
+        if self.index.is_none() {
+            let mut src = self.src.clone().into_os_string();
+            src.push(".bai");
+            self.index = Some(bai::read(src)?);
+        }
+
+        let index = self.index.as_ref().expect("missing index");
+
+        self.inner
+            .query(reference_sequences, index, &region)?
+            .map(|result| result.map(Record::from).map_err(PyErr::from))
+            .collect()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Record>> {
+        let mut record = bam::Record::default();
+
+        match self.inner.read_record(&mut record)? {
+            0 => Ok(None),
+            _ => Ok(Some(Record::from(record))),
+        }
+    }
+}
+
+/// A BAM record.
+#[pyclass(module = "noodles.bam")]
+pub struct Record {
+    inner: bam::Record,
+}
+
+impl From<bam::Record> for Record {
+    fn from(inner: bam::Record) -> Self {
+        Self { inner }
+    }
+}
+
+#[pymethods]
+impl Record {
+    /// The read name.
+    #[getter]
+    fn read_name(&self) -> PyResult<String> {
+        let read_name = self.inner.read_name().map_err(invalid_data)?;
+        Ok(read_name.to_string_lossy().into_owned())
+    }
+
+    /// The flags.
+    #[getter]
+    fn flags(&self) -> u16 {
+        u16::from(self.inner.flags())
+    }
+
+    /// The reference sequence ID, or `None` if unmapped.
+    #[getter]
+    fn reference_sequence_id(&self) -> Option<i32> {
+        self.inner.reference_sequence_id().map(i32::from)
+    }
+
+    /// The 1-based start position, or `None` if unmapped.
+    #[getter]
+    fn position(&self) -> Option<i64> {
+        self.inner.position().map(i64::from)
+    }
+
+    /// The mapping quality, or `None` if missing.
+    #[getter]
+    fn mapping_quality(&self) -> Option<u8> {
+        *self.inner.mapping_quality()
+    }
+
+    /// The mate reference sequence ID, or `None` if the mate is unmapped.
+    #[getter]
+    fn mate_reference_sequence_id(&self) -> Option<i32> {
+        self.inner.mate_reference_sequence_id().map(i32::from)
+    }
+
+    /// The 1-based mate start position, or `None` if the mate is unmapped.
+    #[getter]
+    fn mate_position(&self) -> Option<i64> {
+        self.inner.mate_position().map(i64::from)
+    }
+
+    /// The template length.
+    #[getter]
+    fn template_length(&self) -> i32 {
+        self.inner.template_length()
+    }
+
+    /// The CIGAR string.
+    #[getter]
+    fn cigar(&self) -> String {
+        self.inner.cigar().to_string()
+    }
+
+    /// The sequence.
+    #[getter]
+    fn sequence(&self) -> String {
+        self.inner.sequence().to_string()
+    }
+
+    /// The quality scores as raw Phred scores, i.e., without an ASCII offset.
+    #[getter]
+    fn quality_scores<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner.quality_scores())
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Record(read_name={:?}, flags={}, position={:?})",
+            self.inner
+                .read_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            self.flags(),
+            self.position()
+        )
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use noodles::Region;
+use noodles_bgzf as bgzf;
+use noodles_sam as sam;
+use noodles_tabix as tabix;
+use noodles_vcf as vcf;
+use pyo3::{exceptions::PyIOError, prelude::*};
+
+use super::invalid_data;
+
+const GZIP_MAGIC_NUMBER: [u8; 2] = [0x1f, 0x8b];
+
+pub(super) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Reader>()?;
+    m.add_class::<Record>()?;
+    Ok(())
+}
+
+/// A VCF reader.
+///
+/// The file can be uncompressed or bgzip-compressed. The header is read when the reader is
+/// opened. Iterating the reader yields records.
+#[pyclass(module = "noodles.vcf", unsendable)]
+pub struct Reader {
+    inner: vcf::Reader<Box<dyn BufRead>>,
+    src: PathBuf,
+    raw_header: String,
+    header: vcf::Header,
+    index: Option<tabix::Index>,
+    buf: String,
+}
+
+impl Reader {
+    // Builds a reference sequence dictionary from the contig header records. Contigs without a
+    // length are treated as unbounded.
+    fn reference_sequences(&self) -> sam::header::ReferenceSequences {
+        self.header
+            .contigs()
+            .iter()
+            .map(|contig| {
+                let name = contig.id().to_string();
+                let len = contig.len().unwrap_or(i32::MAX);
+                let reference_sequence = sam::header::ReferenceSequence::new(name.clone(), len);
+                (name, reference_sequence)
+            })
+            .collect()
+    }
+}
+
+#[pymethods]
+impl Reader {
+    #[new]
+    fn new(src: PathBuf) -> PyResult<Self> {
+        let mut file = File::open(&src)?;
+
+        let mut magic_number = [0; 2];
+        let is_bgzip_compressed =
+            file.read_exact(&mut magic_number).is_ok() && magic_number == GZIP_MAGIC_NUMBER;
+        file.seek(SeekFrom::Start(0))?;
+
+        let inner: Box<dyn BufRead> = if is_bgzip_compressed {
+            Box::new(BufReader::new(bgzf::Reader::new(file)))
+        } else {
+            Box::new(BufReader::new(file))
+        };
+
+        let mut inner = vcf::Reader::new(inner);
+
+        let raw_header = inner.read_header()?;
+        let header = raw_header.parse().map_err(invalid_data)?;
+
+        Ok(Self {
+            inner,
+            src,
+            raw_header,
+            header,
+            index: None,
+            buf: String::new(),
+        })
+    }
+
+    /// The raw header.
+    #[getter]
+    fn header(&self) -> &str {
+        &self.raw_header
+    }
+
+    /// The sample names.
+    #[getter]
+    fn sample_names(&self) -> Vec<String> {
+        self.header.sample_names().to_vec()
+    }
+
+    /// Returns the records that intersect a region, e.g., `sq0:8-13`.
+    ///
+    /// The file must be bgzip-compressed. This reads the associated tabix index (`<src>.tbi`)
+    /// on first use.
+    fn query(&mut self, region: &str) -> PyResult<Vec<Record>> {
+        let reference_sequences = self.reference_sequences();
+        let region = Region::from_str_reference_sequences(region, &reference_sequences)
+            .map_err(invalid_data)?;
+
+        if self.index.is_none() {
+            let mut src = self.src.clone().into_os_string();
+            src.push(".tbi");
+            self.index = Some(tabix::read(src)?);
+        }
+
+        let index = self.index.as_ref().expect("missing index");
+
+        // The query uses its own handle, so iteration of this reader is unaffected.
+        let mut reader = File::open(&self.src).map(bgzf::Reader::new)?;
+
+        tabix::query(&mut reader, index, &region)?
+            .map(|result| {
+                let line = result.map_err(|e| PyIOError::new_err(e.to_string()))?;
+                line.parse::<vcf::Record>()
+                    .map(Record::from)
+                    .map_err(invalid_data)
+            })
+            .collect()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Record>> {
+        self.buf.clear();
+
+        match self.inner.read_record(&mut self.buf)? {
+            0 => Ok(None),
+            _ => {
+                let record: vcf::Record = self.buf.parse().map_err(invalid_data)?;
+                Ok(Some(Record::from(record)))
+            }
+        }
+    }
+}
+
+/// A VCF record.
+#[pyclass(module = "noodles.vcf")]
+pub struct Record {
+    inner: vcf::Record,
+}
+
+impl From<vcf::Record> for Record {
+    fn from(inner: vcf::Record) -> Self {
+        Self { inner }
+    }
+}
+
+#[pymethods]
+impl Record {
+    /// The chromosome (`CHROM`).
+    #[getter]
+    fn chromosome(&self) -> String {
+        self.inner.chromosome().to_string()
+    }
+
+    /// The 1-based position (`POS`).
+    #[getter]
+    fn position(&self) -> i64 {
+        self.inner.position()
+    }
+
+    /// The IDs (`ID`).
+    #[getter]
+    fn ids(&self) -> Vec<String> {
+        self.inner.ids().to_vec()
+    }
+
+    /// The reference bases (`REF`).
+    #[getter]
+    fn reference_bases(&self) -> String {
+        self.inner.reference_bases().to_string()
+    }
+
+    /// The alternate bases (`ALT`).
+    #[getter]
+    fn alternate_bases(&self) -> Vec<String> {
+        self.inner
+            .alternate_bases()
+            .iter()
+            .map(|allele| allele.to_string())
+            .collect()
+    }
+
+    /// The quality score (`QUAL`), or `None` if missing.
+    #[getter]
+    fn quality_score(&self) -> Option<f32> {
+        *self.inner.quality_score()
+    }
+
+    /// The raw filter status (`FILTER`).
+    #[getter]
+    fn filter_status(&self) -> String {
+        self.inner.filter_status().to_string()
+    }
+
+    /// The raw information field (`INFO`).
+    #[getter]
+    fn info(&self) -> String {
+        self.inner.info().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Record(chromosome={:?}, position={})",
+            self.chromosome(),
+            self.position()
+        )
+    }
+}
@@ -0,0 +1,444 @@
+//! VCF reader and record functions.
+
+use std::{
+    ffi::CStr,
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    os::raw::{c_char, c_int},
+    ptr,
+};
+
+use noodles_bgzf as bgzf;
+use noodles_vcf as vcf;
+
+use super::{
+    copy_to_buf,
+    error::{catch_panic, set_last_error},
+};
+
+const GZIP_MAGIC_NUMBER: [u8; 2] = [0x1f, 0x8b];
+
+/// A VCF reader.
+pub struct NoodlesVcfReader {
+    inner: vcf::Reader<Box<dyn BufRead>>,
+    header: String,
+    sample_count: usize,
+    buf: String,
+}
+
+/// A VCF record.
+///
+/// A new record is empty until a record is read into it. Fields of an empty record are empty
+/// strings or missing values.
+#[derive(Default)]
+pub struct NoodlesVcfRecord {
+    inner: Option<vcf::Record>,
+}
+
+/// Opens a VCF file and reads its header.
+///
+/// The file can be uncompressed or bgzip-compressed. This returns `NULL` on error.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_reader_open(path: *const c_char) -> *mut NoodlesVcfReader {
+    catch_panic(ptr::null_mut(), || {
+        if path.is_null() {
+            set_last_error("path is null");
+            return ptr::null_mut();
+        }
+
+        let path = match CStr::from_ptr(path).to_str() {
+            Ok(path) => path,
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
+        };
+
+        match open(path) {
+            Ok(reader) => Box::into_raw(Box::new(reader)),
+            Err(e) => {
+                set_last_error(e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+fn open(path: &str) -> io::Result<NoodlesVcfReader> {
+    let mut file = File::open(path)?;
+
+    let mut magic_number = [0; 2];
+    let is_bgzip_compressed =
+        file.read_exact(&mut magic_number).is_ok() && magic_number == GZIP_MAGIC_NUMBER;
+    file.seek(SeekFrom::Start(0))?;
+
+    let inner: Box<dyn BufRead> = if is_bgzip_compressed {
+        Box::new(BufReader::new(bgzf::Reader::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let mut inner = vcf::Reader::new(inner);
+
+    let raw_header = inner.read_header()?;
+    let header: vcf::Header = raw_header
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(NoodlesVcfReader {
+        inner,
+        header: raw_header,
+        sample_count: header.sample_names().len(),
+        buf: String::new(),
+    })
+}
+
+/// Closes a VCF reader.
+///
+/// # Safety
+///
+/// `reader` must be null or a reader returned by [`noodles_vcf_reader_open`] that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_reader_free(reader: *mut NoodlesVcfReader) {
+    catch_panic((), || {
+        if !reader.is_null() {
+            drop(Box::from_raw(reader));
+        }
+    })
+}
+
+/// Copies the raw header of a VCF reader into a buffer.
+///
+/// This returns the length of the header.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_reader_header(
+    reader: *const NoodlesVcfReader,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || copy_to_buf((*reader).header.as_bytes(), buf, len))
+}
+
+/// Returns the number of samples in a VCF reader.
+///
+/// # Safety
+///
+/// `reader` must be a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_reader_sample_count(reader: *const NoodlesVcfReader) -> usize {
+    catch_panic(0, || (*reader).sample_count)
+}
+
+/// Reads the next record.
+///
+/// This returns `1` if a record was read, `0` at EOF, and `-1` on error.
+///
+/// # Safety
+///
+/// `reader` and `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_reader_read_record(
+    reader: *mut NoodlesVcfReader,
+    record: *mut NoodlesVcfRecord,
+) -> c_int {
+    catch_panic(-1, || {
+        let reader = &mut *reader;
+
+        reader.buf.clear();
+
+        match reader.inner.read_record(&mut reader.buf) {
+            Ok(0) => 0,
+            Ok(_) => match reader.buf.parse() {
+                Ok(r) => {
+                    (*record).inner = Some(r);
+                    1
+                }
+                Err(e) => {
+                    set_last_error(e);
+                    -1
+                }
+            },
+            Err(e) => {
+                set_last_error(e);
+                -1
+            }
+        }
+    })
+}
+
+/// Creates an empty VCF record.
+///
+/// This returns `NULL` on error.
+#[no_mangle]
+pub extern "C" fn noodles_vcf_record_new() -> *mut NoodlesVcfRecord {
+    catch_panic(ptr::null_mut(), || {
+        Box::into_raw(Box::new(NoodlesVcfRecord::default()))
+    })
+}
+
+/// Frees a VCF record.
+///
+/// # Safety
+///
+/// `record` must be null or a record returned by [`noodles_vcf_record_new`] that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_free(record: *mut NoodlesVcfRecord) {
+    catch_panic((), || {
+        if !record.is_null() {
+            drop(Box::from_raw(record));
+        }
+    })
+}
+
+/// Copies the chromosome (`CHROM`) of a VCF record into a buffer.
+///
+/// This returns the length of the chromosome name.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_chromosome(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let chromosome = format_field(record, |r| r.chromosome().to_string());
+        copy_to_buf(chromosome.as_bytes(), buf, len)
+    })
+}
+
+/// Returns the 1-based position (`POS`) of a VCF record.
+///
+/// This returns `0` if the record is empty.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_position(record: *const NoodlesVcfRecord) -> i64 {
+    catch_panic(0, || {
+        (*record).inner.as_ref().map(|r| r.position()).unwrap_or(0)
+    })
+}
+
+/// Copies the IDs (`ID`) of a VCF record into a buffer.
+///
+/// This returns the length of the raw IDs field.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_ids(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let ids = format_field(record, |r| r.ids().to_string());
+        copy_to_buf(ids.as_bytes(), buf, len)
+    })
+}
+
+/// Copies the reference bases (`REF`) of a VCF record into a buffer.
+///
+/// This returns the number of reference bases.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_reference_bases(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let reference_bases = format_field(record, |r| r.reference_bases().to_string());
+        copy_to_buf(reference_bases.as_bytes(), buf, len)
+    })
+}
+
+/// Copies the alternate bases (`ALT`) of a VCF record into a buffer.
+///
+/// Alleles are comma-separated. This returns the length of the raw alternate bases field.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_alternate_bases(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let alternate_bases = format_field(record, |r| r.alternate_bases().to_string());
+        copy_to_buf(alternate_bases.as_bytes(), buf, len)
+    })
+}
+
+/// Returns the quality score (`QUAL`) of a VCF record.
+///
+/// A missing quality score, or that of an empty record, is NaN.
+///
+/// # Safety
+///
+/// `record` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_quality_score(record: *const NoodlesVcfRecord) -> f32 {
+    catch_panic(f32::NAN, || {
+        (*record)
+            .inner
+            .as_ref()
+            .and_then(|r| *r.quality_score())
+            .unwrap_or(f32::NAN)
+    })
+}
+
+/// Copies the filter status (`FILTER`) of a VCF record into a buffer.
+///
+/// This returns the length of the raw filter field.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_filter_status(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let filter_status = format_field(record, |r| r.filter_status().to_string());
+        copy_to_buf(filter_status.as_bytes(), buf, len)
+    })
+}
+
+/// Copies the information (`INFO`) of a VCF record into a buffer.
+///
+/// This returns the length of the raw information field.
+///
+/// # Safety
+///
+/// `record` must be valid, and `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn noodles_vcf_record_info(
+    record: *const NoodlesVcfRecord,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    catch_panic(0, || {
+        let info = format_field(record, |r| r.info().to_string());
+        copy_to_buf(info.as_bytes(), buf, len)
+    })
+}
+
+unsafe fn format_field<F>(record: *const NoodlesVcfRecord, f: F) -> String
+where
+    F: Fn(&vcf::Record) -> String,
+{
+    (*record).inner.as_ref().map(f).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, ffi::CString, fs, io::Write};
+
+    use super::*;
+
+    fn read_field<T, F>(handle: *const T, f: F) -> String
+    where
+        F: Fn(*const T, *mut c_char, usize) -> usize,
+    {
+        let len = f(handle, ptr::null_mut(), 0);
+        let mut buf = vec![0 as c_char; len + 1];
+        f(handle, buf.as_mut_ptr(), buf.len());
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_read_records() -> Result<(), Box<dyn std::error::Error>> {
+        let data = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+sq0\t8\trs13\tA\tC,G\t5.5\tPASS\tNS=1\tGT\t0|1
+";
+
+        let path = env::temp_dir().join(format!("noodles-ffi-{}.vcf", std::process::id()));
+        fs::File::create(&path)?.write_all(data)?;
+        let raw_path = CString::new(path.to_str().unwrap())?;
+
+        unsafe {
+            let reader = noodles_vcf_reader_open(raw_path.as_ptr());
+            assert!(!reader.is_null());
+            assert_eq!(noodles_vcf_reader_sample_count(reader), 1);
+            assert_eq!(
+                read_field(reader, |r, b, l| noodles_vcf_reader_header(r, b, l)),
+                "##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\n"
+            );
+
+            let record = noodles_vcf_record_new();
+            assert_eq!(noodles_vcf_record_position(record), 0);
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_chromosome(r, b, l)),
+                ""
+            );
+
+            assert_eq!(noodles_vcf_reader_read_record(reader, record), 1);
+
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_chromosome(r, b, l)),
+                "sq0"
+            );
+            assert_eq!(noodles_vcf_record_position(record), 8);
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_ids(r, b, l)),
+                "rs13"
+            );
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_reference_bases(
+                    r, b, l
+                )),
+                "A"
+            );
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_alternate_bases(
+                    r, b, l
+                )),
+                "C,G"
+            );
+            assert_eq!(noodles_vcf_record_quality_score(record), 5.5);
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_filter_status(r, b, l)),
+                "PASS"
+            );
+            assert_eq!(
+                read_field(record, |r, b, l| noodles_vcf_record_info(r, b, l)),
+                "NS=1"
+            );
+
+            assert_eq!(noodles_vcf_reader_read_record(reader, record), 0);
+
+            noodles_vcf_record_free(record);
+            noodles_vcf_reader_free(reader);
+        }
+
+        fs::remove_file(path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+//! GFF feature hierarchy.
+//!
+//! GFF3 records form a hierarchy (e.g., gene → mRNA → exon) using the `ID` and `Parent`
+//! attributes. A [`Tree`] resolves these relationships across a list of records.
+//!
+//! Records that share the same `ID` are parts of a single discontinuous feature (e.g., a CDS
+//! split across multiple lines) and are grouped into one [`Feature`].
+
+use std::{
+    collections::{hash_map, HashMap},
+    error, fmt,
+    ops::RangeInclusive,
+};
+
+use noodles_core::Strand;
+
+use super::Record;
+
+const ID: &str = "ID";
+const PARENT: &str = "Parent";
+const PARENT_DELIMITER: char = ',';
+
+const EXON: &str = "exon";
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node {
+    records: Vec<Record>,
+    parents: Vec<usize>,
+    children: Vec<usize>,
+}
+
+/// A tree of GFF features.
+///
+/// Technically, this is a directed acyclic graph, as a feature may have multiple parents.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_gff::{self as gff, feature::Tree};
+///
+/// let data = b"##gff-version 3
+/// sq0\tNOODLES\tgene\t8\t55\t.\t+\t.\tID=gene0
+/// sq0\tNOODLES\tmRNA\t8\t55\t.\t+\t.\tID=tx0;Parent=gene0
+/// sq0\tNOODLES\texon\t34\t55\t.\t+\t.\tParent=tx0
+/// sq0\tNOODLES\texon\t8\t13\t.\t+\t.\tParent=tx0
+/// ";
+///
+/// let mut reader = gff::Reader::new(&data[..]);
+/// let records: Vec<_> = reader.records().collect::<Result<_, _>>()?;
+///
+/// let tree = Tree::from_records(records)
+///     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+///
+/// let gene = tree.get("gene0").expect("missing gene0");
+/// assert_eq!(gene.ty(), "gene");
+///
+/// let transcript = gene.children().next().expect("missing transcript");
+/// assert_eq!(transcript.id(), Some("tx0"));
+/// assert_eq!(transcript.exons(), [8..=13, 34..=55]);
+/// # Ok::<_, io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tree {
+    nodes: Vec<Node>,
+    ids: HashMap<String, usize>,
+    roots: Vec<usize>,
+}
+
+impl Tree {
+    /// Builds a feature tree from a list of records.
+    ///
+    /// Parents can be defined before or after their children.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if a `Parent` attribute references an ID that does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_gff::feature::Tree;
+    /// let tree = Tree::from_records(Vec::new())?;
+    /// assert!(tree.is_empty());
+    /// # Ok::<_, noodles_gff::feature::BuildError>(())
+    /// ```
+    pub fn from_records<I>(records: I) -> Result<Self, BuildError>
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+
+        for record in records {
+            let id = find_attribute(&record, ID).map(|s| s.to_string());
+
+            match id {
+                Some(id) => match ids.entry(id) {
+                    hash_map::Entry::Occupied(entry) => nodes[*entry.get()].records.push(record),
+                    hash_map::Entry::Vacant(entry) => {
+                        entry.insert(nodes.len());
+                        nodes.push(Node::new(record));
+                    }
+                },
+                None => nodes.push(Node::new(record)),
+            }
+        }
+
+        let mut roots = Vec::new();
+
+        for i in 0..nodes.len() {
+            let mut parents = Vec::new();
+
+            for record in &nodes[i].records {
+                for parent_id in parent_ids(record) {
+                    let j = ids
+                        .get(parent_id)
+                        .copied()
+                        .ok_or_else(|| BuildError::MissingParent(parent_id.into()))?;
+
+                    if !parents.contains(&j) {
+                        parents.push(j);
+                    }
+                }
+            }
+
+            if parents.is_empty() {
+                roots.push(i);
+            }
+
+            for &j in &parents {
+                nodes[j].children.push(i);
+            }
+
+            nodes[i].parents = parents;
+        }
+
+        Ok(Self { nodes, ids, roots })
+    }
+
+    /// Returns the number of features in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether there are any features in the tree.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the feature with the given ID.
+    pub fn get(&self, id: &str) -> Option<Feature<'_>> {
+        self.ids.get(id).map(|&i| self.feature(i))
+    }
+
+    /// Returns an iterator over features with no parents, in input order.
+    pub fn roots(&self) -> impl Iterator<Item = Feature<'_>> {
+        self.roots.iter().map(move |&i| self.feature(i))
+    }
+
+    /// Returns an iterator over all features, in input order.
+    pub fn iter(&self) -> impl Iterator<Item = Feature<'_>> {
+        (0..self.nodes.len()).map(move |i| self.feature(i))
+    }
+
+    fn feature(&self, index: usize) -> Feature<'_> {
+        Feature { tree: self, index }
+    }
+}
+
+impl Node {
+    fn new(record: Record) -> Self {
+        Self {
+            records: vec![record],
+            parents: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// A GFF feature in a [`Tree`].
+#[derive(Clone, Copy, Debug)]
+pub struct Feature<'a> {
+    tree: &'a Tree,
+    index: usize,
+}
+
+impl<'a> Feature<'a> {
+    /// Returns the records of the feature.
+    ///
+    /// This is never empty. Discontinuous features have more than one record.
+    pub fn records(&self) -> &'a [Record] {
+        &self.node().records
+    }
+
+    /// Returns the first record of the feature.
+    pub fn record(&self) -> &'a Record {
+        &self.records()[0]
+    }
+
+    /// Returns the feature ID, if set.
+    pub fn id(&self) -> Option<&'a str> {
+        find_attribute(self.record(), ID)
+    }
+
+    /// Returns the feature type.
+    pub fn ty(&self) -> &'a str {
+        self.record().ty()
+    }
+
+    /// Returns the strand of the feature.
+    pub fn strand(&self) -> Strand {
+        self.record().strand().into()
+    }
+
+    /// Returns the start position of the feature.
+    ///
+    /// This is the minimum start position of all the records of the feature.
+    pub fn start(&self) -> i32 {
+        self.records()
+            .iter()
+            .map(|record| record.start())
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Returns the end position of the feature.
+    ///
+    /// This is the maximum end position of all the records of the feature.
+    pub fn end(&self) -> i32 {
+        self.records()
+            .iter()
+            .map(|record| record.end())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over the parents of the feature.
+    pub fn parents(&self) -> impl Iterator<Item = Feature<'a>> {
+        let tree = self.tree;
+        self.node().parents.iter().map(move |&i| tree.feature(i))
+    }
+
+    /// Returns an iterator over the children of the feature, in input order.
+    pub fn children(&self) -> impl Iterator<Item = Feature<'a>> {
+        let tree = self.tree;
+        self.node().children.iter().map(move |&i| tree.feature(i))
+    }
+
+    /// Returns an iterator over the children of the feature with the given type.
+    pub fn children_of_type(&self, ty: &'a str) -> impl Iterator<Item = Feature<'a>> {
+        self.children().filter(move |child| child.ty() == ty)
+    }
+
+    /// Returns the intervals of the exon children of the feature, i.e., the spliced transcript.
+    ///
+    /// Intervals are 1-based, inclusive, sorted by start position, and overlapping or adjacent
+    /// exons are merged.
+    pub fn exons(&self) -> Vec<RangeInclusive<i32>> {
+        let mut intervals: Vec<_> = self
+            .children_of_type(EXON)
+            .flat_map(|exon| exon.records().iter())
+            .map(|record| (record.start(), record.end()))
+            .collect();
+
+        intervals.sort_unstable();
+
+        let mut merged: Vec<(i32, i32)> = Vec::with_capacity(intervals.len());
+
+        for (start, end) in intervals {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        merged.into_iter().map(|(start, end)| start..=end).collect()
+    }
+
+    /// Returns the intervals between the exon children of the feature.
+    ///
+    /// Intervals are 1-based, inclusive, and sorted by start position.
+    pub fn introns(&self) -> Vec<RangeInclusive<i32>> {
+        self.exons()
+            .windows(2)
+            .map(|pair| (pair[0].end() + 1)..=(pair[1].start() - 1))
+            .collect()
+    }
+
+    /// Returns the total length of the exon children of the feature.
+    pub fn spliced_len(&self) -> i32 {
+        self.exons()
+            .iter()
+            .map(|interval| interval.end() - interval.start() + 1)
+            .sum()
+    }
+
+    fn node(&self) -> &'a Node {
+        &self.tree.nodes[self.index]
+    }
+}
+
+/// An error returned when a feature tree fails to build.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuildError {
+    /// A parent ID does not reference a feature.
+    MissingParent(String),
+}
+
+impl error::Error for BuildError {}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingParent(id) => write!(f, "missing parent: {}", id),
+        }
+    }
+}
+
+fn find_attribute<'r>(record: &'r Record, key: &str) -> Option<&'r str> {
+    record
+        .attributes()
+        .iter()
+        .find(|entry| entry.key() == key)
+        .map(|entry| entry.value())
+}
+
+fn parent_ids(record: &Record) -> impl Iterator<Item = &str> {
+    find_attribute(record, PARENT)
+        .into_iter()
+        .flat_map(|s| s.split(PARENT_DELIMITER))
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_records(s: &str) -> Vec<Record> {
+        s.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_from_records() -> Result<(), BuildError> {
+        let records = parse_records(
+            "\
+sq0\tNOODLES\texon\t8\t13\t.\t+\t.\tParent=tx0,tx1
+sq0\tNOODLES\tmRNA\t8\t55\t.\t+\t.\tID=tx0;Parent=gene0
+sq0\tNOODLES\tmRNA\t8\t34\t.\t+\t.\tID=tx1;Parent=gene0
+sq0\tNOODLES\tgene\t8\t55\t.\t+\t.\tID=gene0
+sq0\tNOODLES\tCDS\t10\t13\t.\t+\t0\tID=cds0;Parent=tx0
+sq0\tNOODLES\tCDS\t34\t40\t.\t+\t2\tID=cds0;Parent=tx0
+",
+        );
+
+        let tree = Tree::from_records(records)?;
+        assert_eq!(tree.len(), 5);
+
+        let roots: Vec<_> = tree.roots().map(|feature| feature.id()).collect();
+        assert_eq!(roots, [Some("gene0")]);
+
+        let gene = tree.get("gene0").unwrap();
+        let children: Vec<_> = gene.children().map(|feature| feature.id()).collect();
+        assert_eq!(children, [Some("tx0"), Some("tx1")]);
+
+        let exon = tree.iter().next().unwrap();
+        assert_eq!(exon.ty(), "exon");
+        let parents: Vec<_> = exon.parents().map(|feature| feature.id()).collect();
+        assert_eq!(parents, [Some("tx0"), Some("tx1")]);
+
+        let cds = tree.get("cds0").unwrap();
+        assert_eq!(cds.records().len(), 2);
+        assert_eq!((cds.start(), cds.end()), (10, 40));
+        assert_eq!(cds.strand(), Strand::Forward);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_records_with_missing_parent() {
+        let records = parse_records("sq0\tNOODLES\texon\t8\t13\t.\t+\t.\tParent=tx0\n");
+
+        assert_eq!(
+            Tree::from_records(records),
+            Err(BuildError::MissingParent(String::from("tx0")))
+        );
+    }
+
+    #[test]
+    fn test_exons_and_introns() -> Result<(), BuildError> {
+        let records = parse_records(
+            "\
+sq0\tNOODLES\tmRNA\t8\t89\t.\t+\t.\tID=tx0
+sq0\tNOODLES\texon\t55\t89\t.\t+\t.\tParent=tx0
+sq0\tNOODLES\texon\t8\t13\t.\t+\t.\tParent=tx0
+sq0\tNOODLES\texon\t14\t21\t.\t+\t.\tParent=tx0
+sq0\tNOODLES\tCDS\t34\t40\t.\t+\t0\tParent=tx0
+",
+        );
+
+        let tree = Tree::from_records(records)?;
+        let transcript = tree.get("tx0").unwrap();
+
+        assert_eq!(transcript.exons(), [8..=21, 55..=89]);
+        assert_eq!(transcript.introns(), [22..=54]);
+        assert_eq!(transcript.spliced_len(), 49);
+
+        Ok(())
+    }
+}
@@ -32,7 +32,9 @@
 //! ```
 
 pub mod directive;
+pub mod feature;
 pub mod line;
+mod percent_encoding;
 pub mod reader;
 pub mod record;
 mod writer;
@@ -0,0 +1,138 @@
+//! GFF3 percent-encoding.
+//!
+//! Characters with reserved meanings in a column are escaped as `%` followed by two uppercase
+//! hexadecimal digits of each UTF-8 byte, e.g., `;` is `%3B`.
+
+use std::borrow::Cow;
+
+/// Returns whether the character is reserved in all columns, i.e., `%` and control characters
+/// (which include tab, newline, and carriage return).
+pub(crate) fn is_reserved(c: char) -> bool {
+    c == '%' || c.is_control()
+}
+
+/// Returns whether the character is reserved in the reference sequence name (seqid) column.
+///
+/// This is any character not in the set `[a-zA-Z0-9.:^*$@!+_?-|]`.
+pub(crate) fn is_reserved_in_reference_sequence_name(c: char) -> bool {
+    !(c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '.' | ':' | '^' | '*' | '$' | '@' | '!' | '+' | '_' | '?' | '-' | '|'
+        ))
+}
+
+/// Returns whether the character is reserved in an attribute key.
+pub(crate) fn is_reserved_in_attribute_key(c: char) -> bool {
+    is_reserved_in_attribute_value(c) || c == ','
+}
+
+/// Returns whether the character is reserved in an attribute value.
+///
+/// Commas are not escaped because they separate multiple values.
+pub(crate) fn is_reserved_in_attribute_value(c: char) -> bool {
+    is_reserved(c) || matches!(c, ';' | '=' | '&')
+}
+
+/// Escapes characters that match the given predicate.
+pub(crate) fn percent_encode<F>(s: &str, is_reserved: F) -> Cow<'_, str>
+where
+    F: Fn(char) -> bool,
+{
+    if !s.chars().any(&is_reserved) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut buf = String::with_capacity(s.len());
+    let mut bytes = [0; 4];
+
+    for c in s.chars() {
+        if is_reserved(c) {
+            for b in c.encode_utf8(&mut bytes).bytes() {
+                buf.push_str(&format!("%{:02X}", b));
+            }
+        } else {
+            buf.push(c);
+        }
+    }
+
+    Cow::Owned(buf)
+}
+
+/// Unescapes percent-encoded characters.
+///
+/// Invalid escape sequences are kept as is. If the decoded bytes are not valid UTF-8, the input is
+/// returned unchanged.
+pub(crate) fn percent_decode(s: &str) -> Cow<'_, str> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s);
+    }
+
+    let src = s.as_bytes();
+    let mut buf = Vec::with_capacity(src.len());
+    let mut i = 0;
+
+    while i < src.len() {
+        if src[i] == b'%' && i + 2 < src.len() {
+            if let (Some(hi), Some(lo)) = (hex_value(src[i + 1]), hex_value(src[i + 2])) {
+                buf.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+
+        buf.push(src[i]);
+        i += 1;
+    }
+
+    match String::from_utf8(buf) {
+        Ok(t) => Cow::Owned(t),
+        Err(_) => Cow::Borrowed(s),
+    }
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(
+            percent_encode("gene0", is_reserved_in_attribute_value),
+            "gene0"
+        );
+        assert_eq!(
+            percent_encode("a;b=c&d,e%f\tg", is_reserved_in_attribute_value),
+            "a%3Bb%3Dc%26d,e%25f%09g"
+        );
+        assert_eq!(percent_encode("a,b", is_reserved_in_attribute_key), "a%2Cb");
+        assert_eq!(
+            percent_encode("sq 0", is_reserved_in_reference_sequence_name),
+            "sq%200"
+        );
+        assert_eq!(
+            percent_encode("sq\u{e9}", is_reserved_in_reference_sequence_name),
+            "sq%C3%A9"
+        );
+        assert_eq!(percent_encode("Gene 0", is_reserved), "Gene 0");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("gene0"), "gene0");
+        assert_eq!(percent_decode("a%3Bb%3db"), "a;b=b");
+        assert_eq!(percent_decode("sq%C3%A9"), "sq\u{e9}");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%FF"), "%FF");
+    }
+}
@@ -10,7 +10,11 @@ pub use self::{
     attributes::Attributes, builder::Builder, field::Field, phase::Phase, strand::Strand,
 };
 
-use std::{error, fmt, num, str::FromStr};
+use std::{error, fmt, io, num, str::FromStr};
+
+use noodles_core::scan;
+
+use crate::percent_encoding::percent_decode;
 
 pub(crate) const NULL_FIELD: &str = ".";
 const FIELD_DELIMITER: char = '\t';
@@ -174,6 +178,12 @@ impl Default for Record {
     }
 }
 
+impl scan::Locus for Record {
+    fn interval(&self) -> io::Result<Option<(i64, i64)>> {
+        Ok(Some((i64::from(self.start()), i64::from(self.end()))))
+    }
+}
+
 /// An error returned when a raw GFF record fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -211,10 +221,11 @@ impl FromStr for Record {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut fields = s.splitn(MAX_FIELDS, FIELD_DELIMITER);
 
-        let reference_sequence_name =
-            parse_string(&mut fields, Field::ReferenceSequenceName).map(|s| s.into())?;
-        let source = parse_string(&mut fields, Field::Source).map(|s| s.into())?;
-        let ty = parse_string(&mut fields, Field::Type).map(|s| s.into())?;
+        let reference_sequence_name = parse_string(&mut fields, Field::ReferenceSequenceName)
+            .map(|s| percent_decode(s).into_owned())?;
+        let source =
+            parse_string(&mut fields, Field::Source).map(|s| percent_decode(s).into_owned())?;
+        let ty = parse_string(&mut fields, Field::Type).map(|s| percent_decode(s).into_owned())?;
 
         let start = parse_string(&mut fields, Field::Start)
             .and_then(|s| s.parse().map_err(ParseError::InvalidStart))?;
@@ -293,6 +304,17 @@ mod tests {
             ])
         );
 
+        let s = "sq%200	NOODLES	gene	8	13	.	+	.	Note=8%3B13";
+        let record = s.parse::<Record>()?;
+        assert_eq!(record.reference_sequence_name(), "sq 0");
+        assert_eq!(
+            record.attributes(),
+            &Attributes::from(vec![attributes::Entry::new(
+                String::from("Note"),
+                String::from("8;13")
+            )])
+        );
+
         Ok(())
     }
 }
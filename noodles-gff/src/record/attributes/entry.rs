@@ -2,9 +2,16 @@
 
 use std::{error, fmt, str::FromStr};
 
+use crate::percent_encoding::{
+    is_reserved_in_attribute_key, is_reserved_in_attribute_value, percent_decode, percent_encode,
+};
+
 const SEPARATOR: char = '=';
 
 /// A GFF record attribute entry.
+///
+/// The key and value are unescaped. Reserved characters are percent-decoded when parsed and
+/// percent-encoded when formatted.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Entry {
     key: String,
@@ -53,7 +60,13 @@ impl Entry {
 
 impl fmt::Display for Entry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}{}", self.key, SEPARATOR, self.value)
+        write!(
+            f,
+            "{}{}{}",
+            percent_encode(&self.key, is_reserved_in_attribute_key),
+            SEPARATOR,
+            percent_encode(&self.value, is_reserved_in_attribute_value)
+        )
     }
 }
 
@@ -92,12 +105,18 @@ impl FromStr for Entry {
 
         let key = components
             .next()
-            .and_then(|s| if s.is_empty() { None } else { Some(s.into()) })
+            .and_then(|s| {
+                if s.is_empty() {
+                    None
+                } else {
+                    Some(percent_decode(s).into_owned())
+                }
+            })
             .ok_or(ParseError::MissingKey)?;
 
         let value = components
             .next()
-            .map(|s| s.into())
+            .map(|s| percent_decode(s).into_owned())
             .ok_or(ParseError::MissingValue)?;
 
         Ok(Self::new(key, value))
@@ -112,6 +131,9 @@ mod tests {
     fn test_fmt() {
         let entry = Entry::new(String::from("gene_name"), String::from("gene0"));
         assert_eq!(entry.to_string(), "gene_name=gene0");
+
+        let entry = Entry::new(String::from("note"), String::from("8;13=21"));
+        assert_eq!(entry.to_string(), "note=8%3B13%3D21");
     }
 
     #[test]
@@ -121,6 +143,11 @@ mod tests {
             Entry::new(String::from("gene_name"), String::from("gene0"))
         );
 
+        assert_eq!(
+            "note=8%3B13%3D21".parse::<Entry>()?,
+            Entry::new(String::from("note"), String::from("8;13=21"))
+        );
+
         assert_eq!("".parse::<Entry>(), Err(ParseError::Empty));
         assert_eq!("=gene0".parse::<Entry>(), Err(ParseError::MissingKey));
         assert_eq!("gene_name".parse::<Entry>(), Err(ParseError::MissingValue));
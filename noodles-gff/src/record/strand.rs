@@ -82,6 +82,28 @@ impl From<Strand> for char {
     }
 }
 
+impl From<Strand> for noodles_core::Strand {
+    fn from(strand: Strand) -> Self {
+        match strand {
+            Strand::None => Self::Irrelevant,
+            Strand::Forward => Self::Forward,
+            Strand::Reverse => Self::Reverse,
+            Strand::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl From<noodles_core::Strand> for Strand {
+    fn from(strand: noodles_core::Strand) -> Self {
+        match strand {
+            noodles_core::Strand::Forward => Self::Forward,
+            noodles_core::Strand::Reverse => Self::Reverse,
+            noodles_core::Strand::Unknown => Self::Unknown,
+            noodles_core::Strand::Irrelevant => Self::None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +144,30 @@ mod tests {
         assert_eq!(char::from(Strand::Reverse), '-');
         assert_eq!(char::from(Strand::Unknown), '?');
     }
+
+    #[test]
+    fn test_from_strand_for_core_strand() {
+        assert_eq!(
+            noodles_core::Strand::from(Strand::None),
+            noodles_core::Strand::Irrelevant
+        );
+        assert_eq!(
+            noodles_core::Strand::from(Strand::Forward),
+            noodles_core::Strand::Forward
+        );
+        assert_eq!(
+            noodles_core::Strand::from(Strand::Reverse),
+            noodles_core::Strand::Reverse
+        );
+        assert_eq!(
+            noodles_core::Strand::from(Strand::Unknown),
+            noodles_core::Strand::Unknown
+        );
+    }
+
+    #[test]
+    fn test_from_core_strand_for_strand() {
+        assert_eq!(Strand::from(noodles_core::Strand::Irrelevant), Strand::None);
+        assert_eq!(Strand::from(noodles_core::Strand::Forward), Strand::Forward);
+    }
 }
@@ -1,10 +1,21 @@
 use std::io::{self, Write};
 
-use super::{record, Directive, Record};
+use noodles_fasta as fasta;
+
+use super::{
+    percent_encoding::{is_reserved, is_reserved_in_reference_sequence_name, percent_encode},
+    record, Directive, Line, Record,
+};
+
+const FASTA_LINE_WIDTH: usize = 80;
 
 /// A GFF writer.
+///
+/// Reserved characters in the reference sequence name, source, type, and attributes fields are
+/// percent-encoded.
 pub struct Writer<W> {
     inner: W,
+    is_in_fasta_section: bool,
 }
 
 impl<W> Writer<W>
@@ -20,7 +31,10 @@ where
     /// let writer = gff::Writer::new(Vec::new());
     /// ```
     pub fn new(inner: W) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            is_in_fasta_section: false,
+        }
     }
 
     /// Returns a reference to the underlying writer.
@@ -36,8 +50,39 @@ where
         &self.inner
     }
 
+    /// Writes a GFF line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_gff as gff;
+    ///
+    /// let mut writer = gff::Writer::new(Vec::new());
+    ///
+    /// let version = gff::Directive::GffVersion(Default::default());
+    /// writer.write_line(&gff::Line::Directive(version))?;
+    /// writer.write_line(&gff::Line::Comment(String::from("format: gff3")))?;
+    ///
+    /// assert_eq!(writer.get_ref(), b"##gff-version 3\n#format: gff3\n");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_line(&mut self, line: &Line) -> io::Result<()> {
+        match line {
+            Line::Directive(directive) => self.write_directive(directive),
+            Line::Comment(comment) => {
+                self.ensure_not_in_fasta_section()?;
+                writeln!(self.inner, "#{}", comment)
+            }
+            Line::Record(record) => self.write_record(record),
+        }
+    }
+
     /// Writes a GFF directive.
     ///
+    /// Writing the `FASTA` directive ends the records list. Subsequent records must be written
+    /// using [`Self::write_fasta_record`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -53,6 +98,12 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_directive(&mut self, directive: &Directive) -> io::Result<()> {
+        self.ensure_not_in_fasta_section()?;
+
+        if let Directive::StartOfFasta = directive {
+            self.is_in_fasta_section = true;
+        }
+
         writeln!(self.inner, "{}", directive)
     }
 
@@ -80,12 +131,17 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        self.ensure_not_in_fasta_section()?;
+
         write!(
             self.inner,
             "{seqid}\t{source}\t{ty}\t{start}\t{end}",
-            seqid = record.reference_sequence_name(),
-            source = record.source(),
-            ty = record.ty(),
+            seqid = percent_encode(
+                record.reference_sequence_name(),
+                is_reserved_in_reference_sequence_name
+            ),
+            source = percent_encode(record.source(), is_reserved),
+            ty = percent_encode(record.ty(), is_reserved),
             start = record.start(),
             end = record.end(),
         )?;
@@ -110,4 +166,123 @@ where
             writeln!(self.inner, "\t{}", record.attributes())
         }
     }
+
+    /// Writes a FASTA record to the bundled reference sequences section.
+    ///
+    /// The `FASTA` directive is written before the first FASTA record, if it was not already
+    /// written. Sequences are wrapped at 80 bases per line. After this is called, no other GFF
+    /// lines can be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_fasta as fasta;
+    /// use noodles_gff as gff;
+    ///
+    /// let mut writer = gff::Writer::new(Vec::new());
+    ///
+    /// let record = fasta::Record::new(
+    ///     fasta::record::Definition::new(String::from("sq0"), None),
+    ///     b"ACGT".to_vec(),
+    /// );
+    /// writer.write_fasta_record(&record)?;
+    ///
+    /// assert_eq!(writer.get_ref(), b"##FASTA\n>sq0\nACGT\n");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_fasta_record(&mut self, record: &fasta::Record) -> io::Result<()> {
+        if !self.is_in_fasta_section {
+            self.write_directive(&Directive::StartOfFasta)?;
+        }
+
+        write!(self.inner, ">{}", record.reference_sequence_name())?;
+
+        if let Some(description) = record.description() {
+            write!(self.inner, " {}", description)?;
+        }
+
+        writeln!(self.inner)?;
+
+        for line in record.sequence().chunks(FASTA_LINE_WIDTH) {
+            self.inner.write_all(line)?;
+            writeln!(self.inner)?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_not_in_fasta_section(&self) -> io::Result<()> {
+        if self.is_in_fasta_section {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot write GFF lines after the FASTA directive",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{attributes::Entry, Attributes};
+
+    use super::*;
+
+    #[test]
+    fn test_write_record_with_reserved_characters() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+
+        let record = Record::builder()
+            .set_reference_sequence_name(String::from("sq 0"))
+            .set_source(String::from("NOODLES"))
+            .set_type(String::from("gene"))
+            .set_start(8)
+            .set_end(13)
+            .set_attributes(Attributes::from(vec![
+                Entry::new(String::from("ID"), String::from("gene0")),
+                Entry::new(String::from("Note"), String::from("8;13=21&34")),
+            ]))
+            .build();
+
+        writer.write_record(&record)?;
+
+        let expected = b"sq%200\tNOODLES\tgene\t8\t13\t.\t.\t.\tID=gene0;Note=8%3B13%3D21%2634\n";
+        assert_eq!(&writer.get_ref()[..], &expected[..]);
+
+        let line = std::str::from_utf8(&writer.get_ref()[..expected.len() - 1])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let actual: Record = line
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        assert_eq!(actual, record);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_fasta_record() -> io::Result<()> {
+        let mut writer = Writer::new(Vec::new());
+
+        writer.write_directive(&Directive::GffVersion(Default::default()))?;
+
+        let record = fasta::Record::new(
+            fasta::record::Definition::new(String::from("sq0"), Some(String::from("LN:85"))),
+            vec![b'A'; 85],
+        );
+        writer.write_fasta_record(&record)?;
+
+        let mut expected = b"##gff-version 3\n##FASTA\n>sq0 LN:85\n".to_vec();
+        expected.extend(vec![b'A'; 80]);
+        expected.extend(b"\nAAAAA\n");
+        assert_eq!(writer.get_ref(), &expected);
+
+        assert!(writer.write_record(&Record::default()).is_err());
+        assert!(writer
+            .write_directive(&Directive::GffVersion(Default::default()))
+            .is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,197 @@
+//! PLINK BIM (extended variant information).
+//!
+//! A BIM file has one variant per line with six whitespace-separated columns: chromosome,
+//! variant ID, genetic distance (in centimorgans), position, allele 1, and allele 2. The order of
+//! variants is the order of the rows in the BED file.
+
+use std::{
+    error, fmt,
+    io::{self, BufRead},
+    num,
+    str::FromStr,
+};
+
+/// A PLINK BIM record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    chromosome: String,
+    id: String,
+    genetic_distance: f64,
+    position: i64,
+    allele_1: String,
+    allele_2: String,
+}
+
+impl Record {
+    /// Returns the chromosome.
+    pub fn chromosome(&self) -> &str {
+        &self.chromosome
+    }
+
+    /// Returns the variant ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the genetic distance in centimorgans.
+    pub fn genetic_distance(&self) -> f64 {
+        self.genetic_distance
+    }
+
+    /// Returns the 1-based position.
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns allele 1, which is usually the minor allele.
+    ///
+    /// This is `0` if the allele is missing, e.g., for a monomorphic variant.
+    pub fn allele_1(&self) -> &str {
+        &self.allele_1
+    }
+
+    /// Returns allele 2, which is usually the major allele.
+    pub fn allele_2(&self) -> &str {
+        &self.allele_2
+    }
+}
+
+/// An error returned when a raw BIM record fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The chromosome is missing.
+    MissingChromosome,
+    /// The variant ID is missing.
+    MissingId,
+    /// The genetic distance is missing.
+    MissingGeneticDistance,
+    /// The genetic distance is invalid.
+    InvalidGeneticDistance(num::ParseFloatError),
+    /// The position is missing.
+    MissingPosition,
+    /// The position is invalid.
+    InvalidPosition(num::ParseIntError),
+    /// Allele 1 is missing.
+    MissingAllele1,
+    /// Allele 2 is missing.
+    MissingAllele2,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingChromosome => f.write_str("missing chromosome"),
+            Self::MissingId => f.write_str("missing ID"),
+            Self::MissingGeneticDistance => f.write_str("missing genetic distance"),
+            Self::InvalidGeneticDistance(e) => write!(f, "invalid genetic distance: {}", e),
+            Self::MissingPosition => f.write_str("missing position"),
+            Self::InvalidPosition(e) => write!(f, "invalid position: {}", e),
+            Self::MissingAllele1 => f.write_str("missing allele 1"),
+            Self::MissingAllele2 => f.write_str("missing allele 2"),
+        }
+    }
+}
+
+impl FromStr for Record {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let chromosome = fields.next().ok_or(ParseError::MissingChromosome)?;
+        let id = fields.next().ok_or(ParseError::MissingId)?;
+
+        let genetic_distance = fields
+            .next()
+            .ok_or(ParseError::MissingGeneticDistance)
+            .and_then(|s| s.parse().map_err(ParseError::InvalidGeneticDistance))?;
+
+        let position = fields
+            .next()
+            .ok_or(ParseError::MissingPosition)
+            .and_then(|s| s.parse().map_err(ParseError::InvalidPosition))?;
+
+        let allele_1 = fields.next().ok_or(ParseError::MissingAllele1)?;
+        let allele_2 = fields.next().ok_or(ParseError::MissingAllele2)?;
+
+        Ok(Self {
+            chromosome: chromosome.into(),
+            id: id.into(),
+            genetic_distance,
+            position,
+            allele_1: allele_1.into(),
+            allele_2: allele_2.into(),
+        })
+    }
+}
+
+/// Reads all records from a BIM file.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_plink::bim;
+///
+/// let data = b"1\trs0\t0\t8\tG\tA\n1\trs1\t0.5\t13\tT\tC\n";
+/// let records = bim::read(&data[..])?;
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].id(), "rs1");
+/// assert_eq!(records[1].position(), 13);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read<R>(reader: R) -> io::Result<Vec<Record>>
+where
+    R: BufRead,
+{
+    let mut records = Vec::new();
+
+    for result in reader.lines() {
+        let line = result?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: Record = line
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "1\trs0\t0.5\t8\tG\tA".parse(),
+            Ok(Record {
+                chromosome: String::from("1"),
+                id: String::from("rs0"),
+                genetic_distance: 0.5,
+                position: 8,
+                allele_1: String::from("G"),
+                allele_2: String::from("A"),
+            })
+        );
+
+        assert_eq!(
+            "1 rs0 0 8 G".parse::<Record>(),
+            Err(ParseError::MissingAllele2)
+        );
+
+        assert!(matches!(
+            "1 rs0 0 n G A".parse::<Record>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+//! PLINK to VCF conversion.
+//!
+//! Allele 2 is the reference allele, and allele 1 is the alternate allele. This is the
+//! convention used by `plink --recode vcf`, but allele 2 is not guaranteed to be the reference
+//! allele unless it was set, e.g., with `--a2-allele`.
+//!
+//! Chromosome names are not translated, e.g., PLINK chromosome code `23` stays `23`.
+
+use std::{convert::TryFrom, io};
+
+use noodles_vcf::{
+    self as vcf,
+    header::{self, format::Type, Contig, Number},
+    pedigree::Pedigree,
+    record::{genotype::field::Key, AlternateBases, Chromosome, Format, Ids, ReferenceBases},
+};
+
+use super::{bim, Genotype};
+
+const MISSING_ALLELE: &str = "0";
+const MISSING_ID: &str = ".";
+
+/// Builds a VCF header from PLINK variants and individuals.
+///
+/// The header has a contig for each chromosome, in order of first appearance, a genotype
+/// (`GT`) format, and a sample for each individual, named by individual ID.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_plink::{bim, convert};
+/// use noodles_vcf::pedigree::Pedigree;
+///
+/// let variants = bim::read(&b"1\trs0\t0\t8\tG\tA\n2\trs1\t0\t13\tT\tC\n"[..])?;
+/// let pedigree = Pedigree::read(&b"fam0\tsample0\t0\t0\t1\t-9\n"[..])?;
+///
+/// let header = convert::build_header(&variants, &pedigree);
+///
+/// assert_eq!(header.contigs().len(), 2);
+/// assert_eq!(header.sample_names(), [String::from("sample0")]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn build_header(variants: &[bim::Record], pedigree: &Pedigree) -> vcf::Header {
+    let mut chromosomes: Vec<&str> = Vec::new();
+
+    for variant in variants {
+        if !chromosomes.contains(&variant.chromosome()) {
+            chromosomes.push(variant.chromosome());
+        }
+    }
+
+    let builder = chromosomes
+        .into_iter()
+        .fold(vcf::Header::builder(), |builder, chromosome| {
+            builder.add_contig(Contig::new(chromosome.into()))
+        })
+        .add_format(header::Format::new(
+            Key::Genotype,
+            Number::Count(1),
+            Type::String,
+            String::from("Genotype"),
+        ));
+
+    pedigree
+        .iter()
+        .fold(builder, |builder, individual| {
+            builder.add_sample_name(individual.id())
+        })
+        .build()
+}
+
+/// Builds a VCF record from a PLINK variant and its genotypes.
+///
+/// A missing allele 1 (`0`) becomes a missing alternate allele (`.`), and a missing allele 2
+/// becomes an `N` reference base.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_plink::{bim, convert, Genotype};
+///
+/// let variants = bim::read(&b"1\trs0\t0\t8\tG\tA\n"[..])?;
+/// let genotypes = [Genotype::Heterozygous, Genotype::HomozygousAllele2];
+///
+/// let record = convert::build_record(&variants[0], &genotypes)?;
+///
+/// assert_eq!(record.position(), 8);
+/// assert_eq!(record.reference_bases().to_string(), "A");
+/// assert_eq!(record.alternate_bases().to_string(), "G");
+/// assert_eq!(record.genotypes().len(), 2);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn build_record(variant: &bim::Record, genotypes: &[Genotype]) -> io::Result<vcf::Record> {
+    let chromosome: Chromosome = variant.chromosome().parse().map_err(invalid_data)?;
+
+    let ids = match variant.id() {
+        MISSING_ID => Ids::default(),
+        id => Ids::from(vec![id.into()]),
+    };
+
+    let reference_bases: ReferenceBases = match variant.allele_2() {
+        MISSING_ALLELE => "N".parse(),
+        allele => allele.parse(),
+    }
+    .map_err(invalid_data)?;
+
+    let alternate_bases = match variant.allele_1() {
+        MISSING_ALLELE => AlternateBases::default(),
+        allele => allele.parse().map_err(invalid_data)?,
+    };
+
+    let format = Format::try_from(vec![Key::Genotype]).map_err(invalid_data)?;
+
+    let genotypes = genotypes
+        .iter()
+        .map(|&genotype| vcf::record::Genotype::from_str_format(raw_genotype(genotype), &format))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid_data)?;
+
+    vcf::Record::builder()
+        .set_chromosome(chromosome)
+        .set_position(variant.position())
+        .set_ids(ids)
+        .set_reference_bases(reference_bases)
+        .set_alternate_bases(alternate_bases)
+        .set_format(format)
+        .set_genotypes(genotypes)
+        .build()
+        .map_err(invalid_data)
+}
+
+fn raw_genotype(genotype: Genotype) -> &'static str {
+    match genotype {
+        Genotype::HomozygousAllele1 => "1/1",
+        Genotype::Missing => "./.",
+        Genotype::Heterozygous => "0/1",
+        Genotype::HomozygousAllele2 => "0/0",
+    }
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_record() -> io::Result<()> {
+        let variant: bim::Record = "1\t.\t0\t8\t0\tA".parse().unwrap();
+        let genotypes = [
+            Genotype::HomozygousAllele2,
+            Genotype::Missing,
+            Genotype::HomozygousAllele2,
+        ];
+
+        let record = build_record(&variant, &genotypes)?;
+
+        assert!(record.ids().is_empty());
+        assert!(record.alternate_bases().is_empty());
+
+        let raw_genotypes: Vec<_> = record.genotypes().iter().map(|g| g.to_string()).collect();
+        assert_eq!(raw_genotypes, ["0/0", "./.", "0/0"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+/// A PLINK genotype.
+///
+/// A genotype is relative to the two alleles of a variant: allele 1 (usually the minor allele)
+/// and allele 2 (usually the major allele).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Genotype {
+    /// Homozygous for allele 1 (`0b00`).
+    HomozygousAllele1,
+    /// Missing (`0b01`).
+    Missing,
+    /// Heterozygous (`0b10`).
+    Heterozygous,
+    /// Homozygous for allele 2 (`0b11`).
+    HomozygousAllele2,
+}
+
+impl Genotype {
+    /// Returns the number of copies of allele 1.
+    ///
+    /// This is `None` if the genotype is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_plink::Genotype;
+    /// assert_eq!(Genotype::HomozygousAllele1.allele_1_count(), Some(2));
+    /// assert_eq!(Genotype::Missing.allele_1_count(), None);
+    /// ```
+    pub fn allele_1_count(self) -> Option<u8> {
+        match self {
+            Self::HomozygousAllele1 => Some(2),
+            Self::Missing => None,
+            Self::Heterozygous => Some(1),
+            Self::HomozygousAllele2 => Some(0),
+        }
+    }
+}
+
+impl From<u8> for Genotype {
+    /// Decodes a genotype from the lowest 2 bits of a value.
+    fn from(n: u8) -> Self {
+        match n & 0b11 {
+            0b00 => Self::HomozygousAllele1,
+            0b01 => Self::Missing,
+            0b10 => Self::Heterozygous,
+            _ => Self::HomozygousAllele2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_u8_for_genotype() {
+        assert_eq!(Genotype::from(0b00), Genotype::HomozygousAllele1);
+        assert_eq!(Genotype::from(0b01), Genotype::Missing);
+        assert_eq!(Genotype::from(0b10), Genotype::Heterozygous);
+        assert_eq!(Genotype::from(0b11), Genotype::HomozygousAllele2);
+        assert_eq!(Genotype::from(0b0110), Genotype::Heterozygous);
+    }
+}
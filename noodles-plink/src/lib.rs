@@ -0,0 +1,47 @@
+#![deny(missing_docs)]
+
+//! **noodles-plink** handles the reading of PLINK 1.9 binary genotype files.
+//!
+//! A PLINK fileset is made of three files: the variant information (BIM), the individuals
+//! (FAM), and the binary genotype table (BED). Genotypes are packed into 2 bits each and stored
+//! in variant-major order, so each variant is a row with a genotype for every individual.
+//!
+//! FAM files are pedigree files and are read using [`noodles_vcf::pedigree::Pedigree::read`].
+//!
+//! # Examples
+//!
+//! ## Convert a PLINK fileset to VCF
+//!
+//! ```no_run
+//! # use std::{fs::File, io::{self, BufReader}};
+//! use noodles_plink::{self as plink, bim};
+//! use noodles_vcf::{self as vcf, pedigree::Pedigree};
+//!
+//! let variants = File::open("sample.bim").map(BufReader::new).and_then(bim::read)?;
+//! let pedigree = File::open("sample.fam").map(BufReader::new).and_then(Pedigree::read)?;
+//!
+//! let header = plink::convert::build_header(&variants, &pedigree);
+//!
+//! let mut writer = vcf::Writer::new(io::stdout());
+//! writer.write_header(&header)?;
+//!
+//! let mut reader = File::open("sample.bed").map(BufReader::new).map(plink::Reader::new)?;
+//! reader.read_header()?;
+//!
+//! for (variant, result) in variants.iter().zip(reader.records(pedigree.len())) {
+//!     let genotypes = result?;
+//!     let record = plink::convert::build_record(variant, &genotypes)?;
+//!     writer.write_record(&record)?;
+//! }
+//! # Ok::<(), io::Error>(())
+//! ```
+
+pub mod bim;
+pub mod convert;
+mod genotype;
+mod reader;
+
+pub use self::{genotype::Genotype, reader::Reader};
+
+// The magic number and the variant-major mode flag.
+static MAGIC_NUMBER: &[u8] = &[0x6c, 0x1b, 0x01];
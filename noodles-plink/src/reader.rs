@@ -0,0 +1,225 @@
+mod records;
+
+pub use self::records::Records;
+
+use std::io::{self, Read};
+
+use super::{Genotype, MAGIC_NUMBER};
+
+const GENOTYPES_PER_BYTE: usize = 4;
+
+/// A PLINK BED reader.
+///
+/// The genotype table is read one variant at a time. A row is padded to a whole number of bytes,
+/// so the number of individuals, i.e., the number of records in the FAM file, must be known to
+/// read it.
+pub struct Reader<R> {
+    inner: R,
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Creates a PLINK BED reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_plink as plink;
+    /// let data = [];
+    /// let reader = plink::Reader::new(&data[..]);
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_plink as plink;
+    /// let data = [];
+    /// let reader = plink::Reader::new(&data[..]);
+    /// assert!(reader.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Reads the magic number and mode.
+    ///
+    /// Only variant-major files are supported. Individual-major files, which were written by
+    /// PLINK versions before 1.0, are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_plink as plink;
+    /// let data = [0x6c, 0x1b, 0x01];
+    /// let mut reader = plink::Reader::new(&data[..]);
+    /// reader.read_header()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_header(&mut self) -> io::Result<()> {
+        let mut magic = [0; 3];
+        self.inner.read_exact(&mut magic)?;
+
+        if magic == MAGIC_NUMBER {
+            Ok(())
+        } else if magic[..2] == MAGIC_NUMBER[..2] {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unsupported PLINK BED mode: individual-major",
+            ))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid PLINK BED header",
+            ))
+        }
+    }
+
+    /// Reads the genotypes of a single variant.
+    ///
+    /// The stream is expected to be directly after the header or at the start of another
+    /// variant. `genotypes` is replaced with one genotype for each of the `sample_count`
+    /// individuals.
+    ///
+    /// If successful, the number of bytes read is returned. If the number of bytes read is 0, the
+    /// stream reached EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_plink::{self as plink, Genotype};
+    ///
+    /// let data = [0x6c, 0x1b, 0x01, 0b00_11_01_10, 0b00_00_00_00];
+    /// let mut reader = plink::Reader::new(&data[..]);
+    /// reader.read_header()?;
+    ///
+    /// let mut genotypes = Vec::new();
+    /// reader.read_genotypes(3, &mut genotypes)?;
+    ///
+    /// assert_eq!(genotypes, [
+    ///     Genotype::Heterozygous,
+    ///     Genotype::Missing,
+    ///     Genotype::HomozygousAllele2,
+    /// ]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_genotypes(
+        &mut self,
+        sample_count: usize,
+        genotypes: &mut Vec<Genotype>,
+    ) -> io::Result<usize> {
+        let len = sample_count.div_ceil(GENOTYPES_PER_BYTE);
+        let mut buf = vec![0; len];
+
+        let n = read_full(&mut self.inner, &mut buf)?;
+
+        if n == 0 {
+            return Ok(0);
+        } else if n < len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected EOF in PLINK BED variant",
+            ));
+        }
+
+        genotypes.clear();
+
+        genotypes.extend(
+            buf.iter()
+                .flat_map(|&b| (0..GENOTYPES_PER_BYTE).map(move |i| Genotype::from(b >> (2 * i))))
+                .take(sample_count),
+        );
+
+        Ok(n)
+    }
+
+    /// Returns an iterator over the genotypes of each variant starting from the current stream
+    /// position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_plink as plink;
+    ///
+    /// let data = [0x6c, 0x1b, 0x01, 0b11_00_10_01, 0b11_11_11_11];
+    /// let mut reader = plink::Reader::new(&data[..]);
+    /// reader.read_header()?;
+    ///
+    /// let variants = reader.records(4).collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(variants.len(), 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn records(&mut self, sample_count: usize) -> Records<'_, R> {
+        Records::new(self, sample_count)
+    }
+}
+
+// Reads until `buf` is full or the stream reaches EOF.
+fn read_full<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: Read,
+{
+    let mut n = 0;
+
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(m) => n += m,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_header() {
+        let mut reader = Reader::new(&[0x6c, 0x1b, 0x01][..]);
+        assert!(reader.read_header().is_ok());
+
+        let mut reader = Reader::new(&[0x6c, 0x1b, 0x00][..]);
+        assert!(reader.read_header().is_err());
+
+        let mut reader = Reader::new(&b"BED"[..]);
+        assert!(reader.read_header().is_err());
+    }
+
+    #[test]
+    fn test_read_genotypes() -> io::Result<()> {
+        let data = [0b11_10_01_00, 0b01_01_01_11, 0b00];
+        let mut reader = Reader::new(&data[..]);
+        let mut genotypes = Vec::new();
+
+        assert_eq!(reader.read_genotypes(5, &mut genotypes)?, 2);
+        assert_eq!(
+            genotypes,
+            [
+                Genotype::HomozygousAllele1,
+                Genotype::Missing,
+                Genotype::Heterozygous,
+                Genotype::HomozygousAllele2,
+                Genotype::HomozygousAllele2,
+            ]
+        );
+
+        // The last variant is truncated.
+        assert!(reader.read_genotypes(5, &mut genotypes).is_err());
+        assert_eq!(reader.read_genotypes(5, &mut genotypes)?, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Read};
+
+use crate::Genotype;
+
+use super::Reader;
+
+/// An iterator over the genotypes of each variant of a PLINK BED reader.
+///
+/// This is created by calling [`Reader::records`].
+pub struct Records<'a, R> {
+    inner: &'a mut Reader<R>,
+    sample_count: usize,
+}
+
+impl<'a, R> Records<'a, R>
+where
+    R: Read,
+{
+    pub(crate) fn new(inner: &'a mut Reader<R>, sample_count: usize) -> Self {
+        Self {
+            inner,
+            sample_count,
+        }
+    }
+}
+
+impl<'a, R> Iterator for Records<'a, R>
+where
+    R: Read,
+{
+    type Item = io::Result<Vec<Genotype>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut genotypes = Vec::with_capacity(self.sample_count);
+
+        match self.inner.read_genotypes(self.sample_count, &mut genotypes) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(genotypes)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use noodles_sam as sam;
+
+fn build_raw_record() -> String {
+    format!(
+        "r0\t99\tsq0\t8\t34\t100M\t=\t144\t236\t{}\t{}\tNH:i:1\tRG:Z:rg0",
+        "ACGT".repeat(25),
+        "NDLS".repeat(25),
+    )
+}
+
+fn parse_record(c: &mut Criterion) {
+    let raw_record = build_raw_record();
+
+    let mut group = c.benchmark_group("parse_record");
+    group.throughput(Throughput::Bytes(raw_record.len() as u64));
+    group.bench_function("from_str", |b| {
+        b.iter(|| black_box(&raw_record).parse::<sam::Record>())
+    });
+    group.finish();
+}
+
+fn read_records(c: &mut Criterion) {
+    let raw_record = build_raw_record();
+    let data = (0..1000).fold(String::new(), |mut data, _| {
+        data.push_str(&raw_record);
+        data.push('\n');
+        data
+    });
+
+    let mut group = c.benchmark_group("read_records");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("records", |b| {
+        b.iter(|| {
+            let mut reader = sam::Reader::new(black_box(data.as_bytes()));
+            reader.records().count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_record, read_records);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! Alignment record.
+
+use std::{convert::TryFrom, io};
+
+use super::{record::Position, Record};
+
+/// An alignment record.
+///
+/// This is implemented by the records of alignment formats (SAM, BAM, and CRAM) to provide a
+/// common way to get the interval of an alignment on its reference sequence.
+pub trait AlignmentRecord {
+    /// Returns the start position of the alignment.
+    ///
+    /// This is 1-based. It is `None` if the record has no position.
+    fn alignment_start(&self) -> Option<Position>;
+
+    /// Returns the number of reference bases covered by the alignment.
+    ///
+    /// This is the reference length of the CIGAR, i.e., the sum of the lengths of the operations
+    /// that consume the reference.
+    fn alignment_span(&self) -> io::Result<u32>;
+
+    /// Returns the end position of the alignment.
+    ///
+    /// This is 1-based and inclusive. It is `None` if the record has no position. If the
+    /// alignment span is 0, e.g., an unmapped record placed at the position of its mate, the end
+    /// is the same as the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use std::convert::TryFrom;
+    /// use noodles_sam::{self as sam, record::Position, AlignmentRecord};
+    ///
+    /// let record = sam::Record::builder()
+    ///     .set_position(Position::try_from(8).map_err(|e| {
+    ///         io::Error::new(io::ErrorKind::InvalidInput, e)
+    ///     })?)
+    ///     .set_cigar("2M3D4M1I".parse().map_err(|e| {
+    ///         io::Error::new(io::ErrorKind::InvalidInput, e)
+    ///     })?)
+    ///     .build();
+    ///
+    /// assert_eq!(record.alignment_end()?.map(i64::from), Some(16));
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    fn alignment_end(&self) -> io::Result<Option<Position>> {
+        let start = match self.alignment_start() {
+            Some(position) => i64::from(position),
+            None => return Ok(None),
+        };
+
+        let span = self.alignment_span()?;
+        let end = start + i64::from(span.max(1)) - 1;
+
+        Position::try_from(end)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl AlignmentRecord for Record {
+    fn alignment_start(&self) -> Option<Position> {
+        self.position()
+    }
+
+    fn alignment_span(&self) -> io::Result<u32> {
+        Ok(self.cigar().reference_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alignment_end() -> Result<(), Box<dyn std::error::Error>> {
+        let record = Record::builder()
+            .set_position(Position::try_from(8)?)
+            .set_cigar("2S4M2N3M".parse()?)
+            .build();
+
+        assert_eq!(record.alignment_start().map(i64::from), Some(8));
+        assert_eq!(record.alignment_span()?, 9);
+        assert_eq!(record.alignment_end()?.map(i64::from), Some(16));
+
+        let record = Record::builder()
+            .set_position(Position::try_from(8)?)
+            .build();
+
+        assert_eq!(record.alignment_span()?, 0);
+        assert_eq!(record.alignment_end()?.map(i64::from), Some(8));
+
+        let record = Record::default();
+        assert!(record.alignment_end()?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,542 @@
+//! ALT-aware alignment helpers.
+//!
+//! Some assemblies, e.g., GRCh38, include alternate locus (ALT) contigs, which are alternative
+//! representations of regions of the primary assembly. Records mapped to an ALT contig can be
+//! classified as such and projected onto the primary assembly.
+//!
+//! ALT contigs are read either from the `AH` field of `@SQ` header records ([`AltContigs::from_header`])
+//! or from a BWA `.alt` file ([`read_alt_contigs`]). An `.alt` file is a list of SAM records,
+//! where each record is an alignment of an ALT contig (`QNAME`) to the primary assembly. Since
+//! these include the CIGAR, positions in indels are handled exactly, whereas an `AH` region is
+//! assumed to be colinear with the ALT contig.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+
+use super::{
+    header::reference_sequence::AlternativeLocus,
+    record::{cigar::op::Kind, Cigar},
+    Header, Record,
+};
+
+const FIELD_DELIMITER: char = '\t';
+const HEADER_PREFIX: char = '@';
+
+/// The locus in the primary assembly of an ALT contig.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrimaryLocus {
+    reference_sequence_name: String,
+    start: i64,
+    end: i64,
+    is_reverse_complemented: bool,
+    cigar: Option<Cigar>,
+}
+
+impl PrimaryLocus {
+    /// Creates a primary locus from a region.
+    ///
+    /// `start` and `end` are 1-based and inclusive. The ALT contig is assumed to be colinear with
+    /// the region, i.e., position 1 of the ALT contig is at `start`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    /// ```
+    pub fn new<S>(reference_sequence_name: S, start: i64, end: i64) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            reference_sequence_name: reference_sequence_name.into(),
+            start,
+            end,
+            is_reverse_complemented: false,
+            cigar: None,
+        }
+    }
+
+    /// Returns the primary reference sequence name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    /// assert_eq!(locus.reference_sequence_name(), "sq0");
+    /// ```
+    pub fn reference_sequence_name(&self) -> &str {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the 1-based start position of the locus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    /// assert_eq!(locus.start(), 8);
+    /// ```
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the 1-based end position (inclusive) of the locus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    /// assert_eq!(locus.end(), 13);
+    /// ```
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns whether the ALT contig aligns to the reverse strand of the primary assembly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    /// assert!(!locus.is_reverse_complemented());
+    /// ```
+    pub fn is_reverse_complemented(&self) -> bool {
+        self.is_reverse_complemented
+    }
+
+    /// Projects a 1-based position on the ALT contig onto the primary assembly.
+    ///
+    /// This returns `None` if the position is not aligned to the primary assembly, e.g., it is
+    /// in an insertion or clipped region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::PrimaryLocus;
+    ///
+    /// let locus = PrimaryLocus::new("sq0", 8, 13);
+    ///
+    /// assert_eq!(locus.project(1), Some(8));
+    /// assert_eq!(locus.project(6), Some(13));
+    /// assert!(locus.project(7).is_none());
+    /// ```
+    pub fn project(&self, position: i64) -> Option<i64> {
+        if position < 1 {
+            return None;
+        }
+
+        let cigar = match &self.cigar {
+            Some(cigar) => cigar,
+            None => {
+                let projected_position = self.start + position - 1;
+                return Some(projected_position).filter(|&p| p <= self.end);
+            }
+        };
+
+        let position = if self.is_reverse_complemented {
+            let query_len: i64 = cigar
+                .iter()
+                .filter(|op| consumes_query(op.kind()))
+                .map(|op| i64::from(op.len()))
+                .sum();
+
+            query_len - position + 1
+        } else {
+            position
+        };
+
+        let mut query_position = 1;
+        let mut reference_position = self.start;
+
+        for op in cigar.iter() {
+            let len = i64::from(op.len());
+
+            match op.kind() {
+                Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                    if position < query_position + len {
+                        return Some(reference_position + position - query_position);
+                    }
+
+                    query_position += len;
+                    reference_position += len;
+                }
+                Kind::Insertion | Kind::SoftClip | Kind::HardClip => {
+                    if position < query_position + len {
+                        return None;
+                    }
+
+                    query_position += len;
+                }
+                Kind::Deletion | Kind::Skip => reference_position += len,
+                Kind::Pad => {}
+            }
+        }
+
+        None
+    }
+}
+
+// Hard clips are included, as the query is the entire ALT contig.
+fn consumes_query(kind: Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Match
+            | Kind::Insertion
+            | Kind::SoftClip
+            | Kind::HardClip
+            | Kind::SeqMatch
+            | Kind::SeqMismatch
+    )
+}
+
+/// A set of ALT contigs and their primary loci.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AltContigs {
+    contigs: HashMap<String, Option<PrimaryLocus>>,
+}
+
+impl AltContigs {
+    /// Creates a set of ALT contigs from the `@SQ` alternative loci (`AH`) in a SAM header.
+    ///
+    /// Reference sequences with an `AH` of `*` or a name without a region are ALT contigs with
+    /// unknown primary loci. An `AH` that refers to the reference sequence itself (`=`) is
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     alt::AltContigs,
+    ///     header::{reference_sequence::AlternativeLocus, ReferenceSequence},
+    /// };
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence(ReferenceSequence::new(String::from("sq0"), 1000))
+    ///     .add_reference_sequence(
+    ///         ReferenceSequence::builder()
+    ///             .set_name("sq0_alt")
+    ///             .set_length(100)
+    ///             .set_alternative_locus(AlternativeLocus::Region(String::from("sq0"), 501, 600))
+    ///             .build(),
+    ///     )
+    ///     .build();
+    ///
+    /// let alt_contigs = AltContigs::from_header(&header);
+    ///
+    /// assert!(!alt_contigs.is_alt("sq0"));
+    /// assert!(alt_contigs.is_alt("sq0_alt"));
+    /// assert_eq!(alt_contigs.project("sq0_alt", 8), Some(("sq0", 508)));
+    /// ```
+    pub fn from_header(header: &Header) -> Self {
+        let mut contigs = HashMap::new();
+
+        for (name, reference_sequence) in header.reference_sequences() {
+            let locus = match reference_sequence.alternative_locus() {
+                Some(AlternativeLocus::Unknown) => None,
+                Some(AlternativeLocus::Name(n)) if n == "=" => continue,
+                Some(AlternativeLocus::Name(_)) => None,
+                Some(AlternativeLocus::Region(n, start, end)) => {
+                    let n = if n == "=" { name } else { n };
+                    Some(PrimaryLocus::new(n.clone(), *start, *end))
+                }
+                None => continue,
+            };
+
+            contigs.insert(name.clone(), locus);
+        }
+
+        Self { contigs }
+    }
+
+    /// Adds an ALT contig.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::{AltContigs, PrimaryLocus};
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", Some(PrimaryLocus::new("sq0", 501, 600)));
+    ///
+    /// assert!(alt_contigs.is_alt("sq0_alt"));
+    /// ```
+    pub fn insert<S>(&mut self, name: S, locus: Option<PrimaryLocus>)
+    where
+        S: Into<String>,
+    {
+        self.contigs.insert(name.into(), locus);
+    }
+
+    /// Returns the number of ALT contigs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::AltContigs;
+    /// let alt_contigs = AltContigs::default();
+    /// assert_eq!(alt_contigs.len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.contigs.len()
+    }
+
+    /// Returns whether there are any ALT contigs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::AltContigs;
+    /// let alt_contigs = AltContigs::default();
+    /// assert!(alt_contigs.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.contigs.is_empty()
+    }
+
+    /// Returns whether the given reference sequence is an ALT contig.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::AltContigs;
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", None);
+    ///
+    /// assert!(alt_contigs.is_alt("sq0_alt"));
+    /// assert!(!alt_contigs.is_alt("sq0"));
+    /// ```
+    pub fn is_alt(&self, reference_sequence_name: &str) -> bool {
+        self.contigs.contains_key(reference_sequence_name)
+    }
+
+    /// Returns the primary locus of an ALT contig, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::{AltContigs, PrimaryLocus};
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", Some(PrimaryLocus::new("sq0", 501, 600)));
+    ///
+    /// let locus = alt_contigs.get("sq0_alt");
+    /// assert_eq!(locus.map(|l| l.start()), Some(501));
+    /// ```
+    pub fn get(&self, reference_sequence_name: &str) -> Option<&PrimaryLocus> {
+        self.contigs
+            .get(reference_sequence_name)
+            .and_then(|locus| locus.as_ref())
+    }
+
+    /// Projects a 1-based position on an ALT contig onto the primary assembly.
+    ///
+    /// This returns the primary reference sequence name and position, or `None` if the reference
+    /// sequence is not an ALT contig, its primary locus is unknown, or the position is not aligned
+    /// to the primary assembly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alt::{AltContigs, PrimaryLocus};
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", Some(PrimaryLocus::new("sq0", 501, 600)));
+    ///
+    /// assert_eq!(alt_contigs.project("sq0_alt", 8), Some(("sq0", 508)));
+    /// assert!(alt_contigs.project("sq0", 8).is_none());
+    /// ```
+    pub fn project(&self, reference_sequence_name: &str, position: i64) -> Option<(&str, i64)> {
+        let locus = self.get(reference_sequence_name)?;
+        locus
+            .project(position)
+            .map(|p| (locus.reference_sequence_name(), p))
+    }
+
+    /// Returns whether the record is mapped to an ALT contig.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, alt::AltContigs};
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", None);
+    ///
+    /// let record = sam::Record::builder()
+    ///     .set_reference_sequence_name("sq0_alt".parse()?)
+    ///     .build();
+    ///
+    /// assert!(alt_contigs.is_alt_record(&record));
+    /// # Ok::<(), sam::record::reference_sequence_name::ParseError>(())
+    /// ```
+    pub fn is_alt_record(&self, record: &Record) -> bool {
+        record
+            .reference_sequence_name()
+            .map(|name| self.is_alt(name))
+            .unwrap_or(false)
+    }
+
+    /// Projects the start position of a record mapped to an ALT contig onto the primary assembly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_sam::{self as sam, alt::{AltContigs, PrimaryLocus}, record::Position};
+    ///
+    /// let mut alt_contigs = AltContigs::default();
+    /// alt_contigs.insert("sq0_alt", Some(PrimaryLocus::new("sq0", 501, 600)));
+    ///
+    /// let record = sam::Record::builder()
+    ///     .set_reference_sequence_name("sq0_alt".parse()?)
+    ///     .set_position(Position::try_from(8)?)
+    ///     .build();
+    ///
+    /// assert_eq!(alt_contigs.project_record(&record), Some(("sq0", 508)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn project_record(&self, record: &Record) -> Option<(&str, i64)> {
+        let name = record.reference_sequence_name()?;
+        let position = record.position()?;
+        self.project(name, i64::from(position))
+    }
+}
+
+/// Reads ALT contigs from a BWA `.alt` file.
+///
+/// Each line is either a SAM record, which is an alignment of the ALT contig (`QNAME`) to the
+/// primary assembly, or only the name of an ALT contig. Empty lines and header lines (`@`) are
+/// skipped. Unmapped ALT contigs have unknown primary loci.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_sam::alt;
+///
+/// let data = b"@SQ\tSN:sq0\tLN:1000
+/// sq0_alt\t0\tsq0\t501\t255\t4M2I4M\t*\t0\t0\t*\t*
+/// sq1_alt
+/// ";
+///
+/// let alt_contigs = alt::read_alt_contigs(&data[..])?;
+///
+/// assert_eq!(alt_contigs.len(), 2);
+/// assert_eq!(alt_contigs.project("sq0_alt", 8), Some(("sq0", 506)));
+/// assert!(alt_contigs.project("sq0_alt", 6).is_none());
+/// assert!(alt_contigs.is_alt("sq1_alt"));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read_alt_contigs<R>(reader: R) -> io::Result<AltContigs>
+where
+    R: BufRead,
+{
+    let mut alt_contigs = AltContigs::default();
+
+    for result in reader.lines() {
+        let line = result?;
+        let line = line.trim_end_matches('\r');
+
+        if line.is_empty() || line.starts_with(HEADER_PREFIX) {
+            continue;
+        }
+
+        if !line.contains(FIELD_DELIMITER) {
+            alt_contigs.insert(line, None);
+            continue;
+        }
+
+        let record: Record = line
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let name = record
+            .read_name()
+            .map(|name| name.to_string())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing ALT contig name"))?;
+
+        let locus = build_primary_locus(&record);
+        alt_contigs.insert(name, locus);
+    }
+
+    Ok(alt_contigs)
+}
+
+fn build_primary_locus(record: &Record) -> Option<PrimaryLocus> {
+    let flags = record.flags();
+
+    if flags.is_unmapped() {
+        return None;
+    }
+
+    let reference_sequence_name = record.reference_sequence_name()?;
+    let start = record.position().map(i64::from)?;
+    let reference_len = i64::from(record.cigar().reference_len());
+
+    Some(PrimaryLocus {
+        reference_sequence_name: reference_sequence_name.to_string(),
+        start,
+        end: start + reference_len - 1,
+        is_reverse_complemented: flags.is_reverse_complemented(),
+        cigar: Some(record.cigar().clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_locus(raw_record: &str) -> Option<PrimaryLocus> {
+        let record: Record = raw_record.parse().unwrap();
+        build_primary_locus(&record)
+    }
+
+    #[test]
+    fn test_project() {
+        let locus = build_locus("alt\t0\tsq0\t100\t255\t2S3M1I2M2D2M1H\t*\t0\t0\t*\t*").unwrap();
+        assert_eq!(locus.end(), 108);
+
+        let actual: Vec<_> = (1..=12).map(|position| locus.project(position)).collect();
+        let expected = [
+            None,
+            None,
+            Some(100),
+            Some(101),
+            Some(102),
+            None,
+            Some(103),
+            Some(104),
+            Some(107),
+            Some(108),
+            None,
+            None,
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_project_with_reverse_complemented_alignment() {
+        let locus = build_locus("alt\t16\tsq0\t100\t255\t1S4M\t*\t0\t0\t*\t*").unwrap();
+        assert!(locus.is_reverse_complemented());
+
+        let actual: Vec<_> = (1..=6).map(|position| locus.project(position)).collect();
+        let expected = [Some(103), Some(102), Some(101), Some(100), None, None];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_build_primary_locus_with_unmapped_record() {
+        assert!(build_locus("alt\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*").is_none());
+    }
+}
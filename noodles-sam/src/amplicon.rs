@@ -0,0 +1,637 @@
+//! Amplicon primer clipping.
+//!
+//! This clips primer sequences from the ends of records that start or end in a primer, similar
+//! to `samtools ampliconclip`. Primer locations are read from a BED file.
+
+use std::{
+    collections::HashMap,
+    error, fmt,
+    io::{self, BufRead},
+    num,
+    str::FromStr,
+};
+
+pub use noodles_core::Strand;
+
+use super::{
+    clip::{self, Mode},
+    Record,
+};
+
+const FIELD_DELIMITER: char = '\t';
+const COMMENT_PREFIX: char = '#';
+
+static TRACK_PREFIX: &str = "track";
+static BROWSER_PREFIX: &str = "browser";
+
+const DEFAULT_TOLERANCE: i64 = 5;
+
+/// A primer location.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Primer {
+    reference_sequence_name: String,
+    start: i64,
+    end: i64,
+    strand: Option<Strand>,
+}
+
+impl Primer {
+    /// Creates a primer location.
+    ///
+    /// `start` and `end` are 1-based and inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::{Primer, Strand};
+    /// let primer = Primer::new("sq0", 8, 13, Some(Strand::Forward));
+    /// ```
+    pub fn new<S>(reference_sequence_name: S, start: i64, end: i64, strand: Option<Strand>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            reference_sequence_name: reference_sequence_name.into(),
+            start,
+            end,
+            strand,
+        }
+    }
+
+    /// Returns the reference sequence name of the primer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Primer;
+    /// let primer = Primer::new("sq0", 8, 13, None);
+    /// assert_eq!(primer.reference_sequence_name(), "sq0");
+    /// ```
+    pub fn reference_sequence_name(&self) -> &str {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the 1-based start position of the primer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Primer;
+    /// let primer = Primer::new("sq0", 8, 13, None);
+    /// assert_eq!(primer.start(), 8);
+    /// ```
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the 1-based, inclusive end position of the primer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Primer;
+    /// let primer = Primer::new("sq0", 8, 13, None);
+    /// assert_eq!(primer.end(), 13);
+    /// ```
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns the strand of the primer, if set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::{Primer, Strand};
+    /// let primer = Primer::new("sq0", 8, 13, Some(Strand::Reverse));
+    /// assert_eq!(primer.strand(), Some(Strand::Reverse));
+    /// ```
+    pub fn strand(&self) -> Option<Strand> {
+        self.strand
+    }
+
+    fn matches_strand(&self, strand: Strand) -> bool {
+        self.strand.map(|s| s == strand).unwrap_or(true)
+    }
+}
+
+/// An error returned when a BED primer line fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The reference sequence name is missing.
+    MissingReferenceSequenceName,
+    /// The start is missing.
+    MissingStart,
+    /// The start is invalid.
+    InvalidStart(num::ParseIntError),
+    /// The end is missing.
+    MissingEnd,
+    /// The end is invalid.
+    InvalidEnd(num::ParseIntError),
+    /// The interval is invalid, i.e., the end is not after the start.
+    InvalidInterval(i64, i64),
+    /// The strand is invalid.
+    InvalidStrand(String),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReferenceSequenceName => f.write_str("missing reference sequence name"),
+            Self::MissingStart => f.write_str("missing start"),
+            Self::InvalidStart(e) => write!(f, "invalid start: {}", e),
+            Self::MissingEnd => f.write_str("missing end"),
+            Self::InvalidEnd(e) => write!(f, "invalid end: {}", e),
+            Self::InvalidInterval(start, end) => write!(f, "invalid interval: {}-{}", start, end),
+            Self::InvalidStrand(s) => write!(f, "invalid strand: {}", s),
+        }
+    }
+}
+
+impl FromStr for Primer {
+    type Err = ParseError;
+
+    /// Parses a BED line as a primer location.
+    ///
+    /// Only the first three columns (chrom, chromStart, chromEnd) are required. The strand is read
+    /// from the sixth column, if present. BED positions are 0-based and half-open and are
+    /// converted to 1-based, inclusive positions.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split(FIELD_DELIMITER);
+
+        let reference_sequence_name = fields
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or(ParseError::MissingReferenceSequenceName)?;
+
+        let start: i64 = fields
+            .next()
+            .ok_or(ParseError::MissingStart)
+            .and_then(|s| s.parse().map_err(ParseError::InvalidStart))?;
+
+        let end: i64 = fields
+            .next()
+            .ok_or(ParseError::MissingEnd)
+            .and_then(|s| s.parse().map_err(ParseError::InvalidEnd))?;
+
+        if start < 0 || end <= start {
+            return Err(ParseError::InvalidInterval(start, end));
+        }
+
+        // name, score
+        // BED only allows forward, reverse, and unstranded (`.`) strands.
+        let strand = match fields.nth(2).map(|s| (s, s.parse())) {
+            Some((_, Ok(Strand::Irrelevant))) | None => None,
+            Some((_, Ok(strand))) if strand.is_known() => Some(strand),
+            Some((s, _)) => return Err(ParseError::InvalidStrand(s.into())),
+        };
+
+        Ok(Self::new(reference_sequence_name, start + 1, end, strand))
+    }
+}
+
+/// Reads primer locations from a BED file.
+///
+/// Empty lines, comments (`#`), and `track` and `browser` lines are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_sam::amplicon::{self, Primer};
+///
+/// let data = b"# primers\nsq0\t7\t13\tp0_LEFT\t1\t+\n";
+/// let primers = amplicon::read_primers(&data[..])?;
+///
+/// assert_eq!(primers.len(), 1);
+/// assert_eq!(primers[0].start(), 8);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read_primers<R>(reader: R) -> io::Result<Vec<Primer>>
+where
+    R: BufRead,
+{
+    let mut primers = Vec::new();
+
+    for result in reader.lines() {
+        let line = result?;
+        let line = line.trim_end_matches('\r');
+
+        if line.is_empty()
+            || line.starts_with(COMMENT_PREFIX)
+            || line.starts_with(TRACK_PREFIX)
+            || line.starts_with(BROWSER_PREFIX)
+        {
+            continue;
+        }
+
+        let primer: Primer = line
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        primers.push(primer);
+    }
+
+    Ok(primers)
+}
+
+/// The result of clipping a record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clip {
+    /// The record does not start or end in a primer.
+    Unchanged,
+    /// The record with primer bases clipped.
+    Clipped(Record),
+    /// All aligned bases of the record are in primers.
+    Excluded,
+}
+
+/// An amplicon primer clipper.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam,
+///     amplicon::{Clip, Clipper, Primer},
+///     record::{Flags, Position},
+/// };
+///
+/// let clipper = Clipper::new(vec![Primer::new("sq0", 1, 4, None)]);
+///
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_reference_sequence_name("sq0".parse()?)
+///     .set_position(Position::try_from(1)?)
+///     .set_cigar("8M".parse()?)
+///     .build();
+///
+/// match clipper.clip(&record) {
+///     Clip::Clipped(r) => assert_eq!(r.cigar().to_string(), "4S4M"),
+///     _ => panic!("expected clipped record"),
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Clipper {
+    primers: HashMap<String, Vec<Primer>>,
+    mode: Mode,
+    both_ends: bool,
+    use_strand: bool,
+    tolerance: i64,
+}
+
+impl Clipper {
+    /// Creates a clipper from a list of primer locations.
+    ///
+    /// By default, primers are soft clipped from the 5' end of records, primer strands are
+    /// ignored, and the alignment start (or end) of a record may be up to 5 bases before (or after)
+    /// a primer for the primer to match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::{Clipper, Primer};
+    /// let clipper = Clipper::new(vec![Primer::new("sq0", 8, 13, None)]);
+    /// ```
+    pub fn new(primers: Vec<Primer>) -> Self {
+        let mut primers_by_name: HashMap<String, Vec<Primer>> = HashMap::new();
+
+        for primer in primers {
+            primers_by_name
+                .entry(primer.reference_sequence_name.clone())
+                .or_default()
+                .push(primer);
+        }
+
+        Self {
+            primers: primers_by_name,
+            mode: Mode::default(),
+            both_ends: false,
+            use_strand: false,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// Sets the clip mode.
+    ///
+    /// When hard clipping, primer bases are removed from the sequence and quality scores. The
+    /// default is soft clipping.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{amplicon::Clipper, clip::Mode};
+    /// let clipper = Clipper::new(Vec::new()).set_mode(Mode::Hard);
+    /// ```
+    pub fn set_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether primers are clipped from both ends of a record.
+    ///
+    /// Otherwise, only the 5' end is clipped, i.e., the alignment start of forward records and the
+    /// alignment end of reverse records. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Clipper;
+    /// let clipper = Clipper::new(Vec::new()).set_both_ends(true);
+    /// ```
+    pub fn set_both_ends(mut self, both_ends: bool) -> Self {
+        self.both_ends = both_ends;
+        self
+    }
+
+    /// Sets whether primer strands are used.
+    ///
+    /// If set, only forward primers are clipped from the alignment start and only reverse primers
+    /// are clipped from the alignment end. Primers without a strand match both. The default is
+    /// `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Clipper;
+    /// let clipper = Clipper::new(Vec::new()).set_use_strand(true);
+    /// ```
+    pub fn set_use_strand(mut self, use_strand: bool) -> Self {
+        self.use_strand = use_strand;
+        self
+    }
+
+    /// Sets the number of bases an alignment may extend past the outer edge of a primer.
+    ///
+    /// The default is 5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::amplicon::Clipper;
+    /// let clipper = Clipper::new(Vec::new()).set_tolerance(0);
+    /// ```
+    pub fn set_tolerance(mut self, tolerance: i64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Clips primer bases from a record.
+    ///
+    /// A primer is clipped from the alignment start if the start is within the primer (or up to
+    /// the tolerance before it), and likewise for the alignment end. If multiple primers match an
+    /// end, the one that clips the most bases is used. Unmapped records are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, amplicon::{Clip, Clipper, Primer}};
+    ///
+    /// let clipper = Clipper::new(vec![Primer::new("sq0", 8, 13, None)]);
+    /// let record = sam::Record::default();
+    ///
+    /// assert_eq!(clipper.clip(&record), Clip::Unchanged);
+    /// ```
+    pub fn clip(&self, record: &Record) -> Clip {
+        let (alignment_start, alignment_end) = match clip::reference_interval(record) {
+            Some(interval) => interval,
+            None => return Clip::Unchanged,
+        };
+
+        let primers = match record
+            .reference_sequence_name()
+            .and_then(|name| self.primers.get(&**name))
+        {
+            Some(primers) => primers,
+            None => return Clip::Unchanged,
+        };
+
+        let is_reverse = record.flags().is_reverse_complemented();
+        let clip_start = self.both_ends || !is_reverse;
+        let clip_end = self.both_ends || is_reverse;
+
+        let mut start = alignment_start;
+        let mut end = alignment_end;
+
+        for primer in primers {
+            if clip_start
+                && (!self.use_strand || primer.matches_strand(Strand::Forward))
+                && alignment_start >= primer.start - self.tolerance
+                && alignment_start <= primer.end
+            {
+                start = start.max(primer.end + 1);
+            }
+
+            if clip_end
+                && (!self.use_strand || primer.matches_strand(Strand::Reverse))
+                && alignment_end >= primer.start
+                && alignment_end <= primer.end + self.tolerance
+            {
+                end = end.min(primer.start - 1);
+            }
+        }
+
+        if start == alignment_start && end == alignment_end {
+            return Clip::Unchanged;
+        } else if start > end {
+            return Clip::Excluded;
+        }
+
+        match clip::clip(record, start, end, self.mode) {
+            Some(r) => Clip::Clipped(r),
+            None => Clip::Excluded,
+        }
+    }
+}
+
+/// An iterator adapter that clips primers from records.
+///
+/// This is created by calling [`clip_records`].
+pub struct ClipRecords<'a, I> {
+    inner: I,
+    clipper: &'a Clipper,
+}
+
+impl<'a, I> Iterator for ClipRecords<'a, I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next()? {
+                Ok(record) => match self.clipper.clip(&record) {
+                    Clip::Unchanged => return Some(Ok(record)),
+                    Clip::Clipped(r) => return Some(Ok(r)),
+                    Clip::Excluded => {}
+                },
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Clips primers from a stream of records.
+///
+/// Records that are entirely within primers are discarded.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, amplicon::{self, Clipper, Primer}};
+///
+/// let data = b"r0\t0\tsq0\t1\t60\t8M\t*\t0\t0\tACGTACGT\t*
+/// r1\t0\tsq0\t1\t60\t4M\t*\t0\t0\tACGT\t*
+/// ";
+/// let mut reader = sam::Reader::new(&data[..]);
+///
+/// let clipper = Clipper::new(vec![Primer::new("sq0", 1, 4, None)]);
+/// let records: Vec<_> = amplicon::clip_records(reader.records(), &clipper)
+///     .collect::<io::Result<_>>()?;
+///
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].cigar().to_string(), "4S4M");
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn clip_records<I>(records: I, clipper: &Clipper) -> ClipRecords<'_, I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    ClipRecords {
+        inner: records.into_iter(),
+        clipper,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_record(flags: Flags, position: i64, cigar: &str) -> Record {
+        Record::builder()
+            .set_flags(flags)
+            .set_reference_sequence_name("sq0".parse().unwrap())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .build()
+    }
+
+    fn clipped_cigar(clip: Clip) -> Option<(i64, String)> {
+        match clip {
+            Clip::Clipped(r) => Some((r.position().map(i64::from).unwrap(), r.cigar().to_string())),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_from_str_for_primer() {
+        assert_eq!("sq0\t7\t13".parse(), Ok(Primer::new("sq0", 8, 13, None)));
+        assert_eq!(
+            "sq0\t7\t13\tp0_RIGHT\t1\t-".parse(),
+            Ok(Primer::new("sq0", 8, 13, Some(Strand::Reverse)))
+        );
+
+        assert_eq!(
+            "".parse::<Primer>(),
+            Err(ParseError::MissingReferenceSequenceName)
+        );
+        assert_eq!("sq0".parse::<Primer>(), Err(ParseError::MissingStart));
+        assert_eq!("sq0\t7".parse::<Primer>(), Err(ParseError::MissingEnd));
+        assert!(matches!(
+            "sq0\tx\t13".parse::<Primer>(),
+            Err(ParseError::InvalidStart(_))
+        ));
+        assert_eq!(
+            "sq0\t13\t7".parse::<Primer>(),
+            Err(ParseError::InvalidInterval(13, 7))
+        );
+        assert_eq!(
+            "sq0\t7\t13\tp0\t1\t?".parse::<Primer>(),
+            Err(ParseError::InvalidStrand(String::from("?")))
+        );
+    }
+
+    #[test]
+    fn test_read_primers() -> io::Result<()> {
+        let data = b"track name=primers\n\nsq0\t0\t4\np0_LEFT\nsq0\t16\t20\n";
+        assert!(read_primers(&data[..]).is_err());
+
+        let data = b"track name=primers\n\nsq0\t0\t4\r\n# p1\nsq0\t16\t20\n";
+        let primers = read_primers(&data[..])?;
+        assert_eq!(
+            primers,
+            [
+                Primer::new("sq0", 1, 4, None),
+                Primer::new("sq0", 17, 20, None)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clip() {
+        let primers = vec![
+            Primer::new("sq0", 3, 6, Some(Strand::Forward)),
+            Primer::new("sq0", 17, 20, Some(Strand::Reverse)),
+        ];
+        let clipper = Clipper::new(primers);
+
+        // The alignment start is within the tolerance of the forward primer.
+        let record = build_record(Flags::empty(), 1, "18M");
+        assert_eq!(
+            clipped_cigar(clipper.clip(&record)),
+            Some((7, String::from("6S12M")))
+        );
+
+        // Only the 5' end of a reverse record is clipped.
+        let record = build_record(Flags::REVERSE_COMPLEMENTED, 3, "16M");
+        assert_eq!(
+            clipped_cigar(clipper.clip(&record)),
+            Some((3, String::from("14M2S")))
+        );
+
+        let clipper = clipper.set_both_ends(true);
+        assert_eq!(
+            clipped_cigar(clipper.clip(&record)),
+            Some((7, String::from("4S10M2S")))
+        );
+
+        let record = build_record(Flags::empty(), 8, "8M");
+        assert_eq!(clipper.clip(&record), Clip::Unchanged);
+
+        let record = build_record(Flags::empty(), 3, "4M");
+        assert_eq!(clipper.clip(&record), Clip::Excluded);
+
+        let record = Record::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_name("sq1".parse().unwrap())
+            .set_position(Position::try_from(3).unwrap())
+            .set_cigar("8M".parse().unwrap())
+            .build();
+        assert_eq!(clipper.clip(&record), Clip::Unchanged);
+    }
+
+    #[test]
+    fn test_clip_with_use_strand() {
+        let primers = vec![Primer::new("sq0", 1, 4, Some(Strand::Reverse))];
+        let record = build_record(Flags::empty(), 1, "8M");
+
+        let clipper = Clipper::new(primers.clone());
+        assert!(matches!(clipper.clip(&record), Clip::Clipped(_)));
+
+        let clipper = Clipper::new(primers).set_use_strand(true);
+        assert_eq!(clipper.clip(&record), Clip::Unchanged);
+    }
+}
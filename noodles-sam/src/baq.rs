@@ -0,0 +1,585 @@
+//! Base alignment quality (BAQ).
+//!
+//! BAQ is the Phred-scaled probability of a read base being misaligned. It is computed by a
+//! profile hidden Markov model (HMM) that realigns the read to its local reference sequence, as
+//! in samtools/bcftools (`sam_prob_realn`). Capping base qualities by BAQ reduces false SNVs
+//! caused by misaligned bases near indels.
+
+use std::convert::TryFrom;
+
+use super::{
+    record::{cigar::op::Kind, quality_scores::Score, QualityScores},
+    Record,
+};
+
+// Gap open probability.
+const GAP_OPEN: f64 = 0.001;
+// Gap extension probability.
+const GAP_EXTEND: f64 = 0.1;
+// Insertion emission probability.
+const EI: f64 = 0.25;
+// Mismatch emission probability factor.
+const EM: f64 = 1.0 / 3.0;
+
+const MIN_BANDWIDTH: usize = 7;
+const MAX_BAQ: u8 = 99;
+
+/// A base alignment quality calculator.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam, baq,
+///     record::{Flags, Position},
+/// };
+///
+/// let mut record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_position(Position::try_from(1)?)
+///     .set_cigar("4M".parse()?)
+///     .set_sequence("ACGT".parse()?)
+///     .set_quality_scores("IIII".parse()?)
+///     .build();
+///
+/// let calculator = baq::Calculator::new();
+/// calculator.apply(&mut record, b"ACGTACGT");
+///
+/// assert_eq!(record.quality_scores().len(), 4);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Calculator {
+    is_extended: bool,
+}
+
+impl Calculator {
+    /// Creates a base alignment quality calculator.
+    ///
+    /// By default, BAQ is not extended.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to compute extended BAQ.
+    ///
+    /// Extended BAQ (`samtools mpileup -E`) is less strict: the BAQ of a base is the minimum of the
+    /// maximum BAQs to its left and right within the same alignment block. This increases
+    /// sensitivity at the cost of specificity.
+    pub fn set_extended(mut self, is_extended: bool) -> Self {
+        self.is_extended = is_extended;
+        self
+    }
+
+    /// Computes the base qualities of a record capped by their BAQ.
+    ///
+    /// `reference_sequence` is the complete sequence of the reference sequence the record is
+    /// aligned to.
+    ///
+    /// This returns `None` if the record is unmapped, has no quality scores, has a reference skip
+    /// (`N`), or has no aligned bases.
+    pub fn compute(&self, record: &Record, reference_sequence: &[u8]) -> Option<QualityScores> {
+        let start = usize::try_from(i64::from(record.position()?) - 1).ok()?;
+        let sequence = record.sequence();
+        let qualities: Vec<u8> = record
+            .quality_scores()
+            .iter()
+            .map(|&score| u8::from(score))
+            .collect();
+
+        if record.flags().is_unmapped() || qualities.is_empty() || qualities.len() != sequence.len()
+        {
+            return None;
+        }
+
+        let blocks = match_blocks(record, start)?;
+
+        let (ref_start, ref_end) = (blocks.first()?, blocks.last()?);
+        let mut xb = ref_start.reference_start as i64;
+        let mut xe = (ref_end.reference_start + ref_end.len) as i64;
+        let yb = ref_start.query_start as i64;
+        let ye = (ref_end.query_start + ref_end.len) as i64;
+        let l_qseq = sequence.len() as i64;
+
+        if ye > l_qseq {
+            return None;
+        }
+
+        let mut bandwidth = MIN_BANDWIDTH as i64;
+        let diff = ((xe - xb) - (ye - yb)).abs();
+
+        if diff > bandwidth {
+            bandwidth = diff + 3;
+        }
+
+        xb = (xb - (yb + bandwidth / 2)).max(0);
+        xe += l_qseq - ye + bandwidth / 2;
+
+        if xe - xb - l_qseq > bandwidth {
+            let d = (xe - xb - l_qseq - bandwidth) / 2;
+            xb += d;
+            xe -= d;
+        }
+
+        xe = xe.min(reference_sequence.len() as i64);
+
+        if xe <= xb {
+            return None;
+        }
+
+        let xb = xb as usize;
+        let xe = xe as usize;
+
+        let reference: Vec<u8> = reference_sequence[xb..xe]
+            .iter()
+            .map(|&b| encode_base(b))
+            .collect();
+
+        let query: Vec<u8> = sequence
+            .iter()
+            .map(|&base| encode_base(char::from(base) as u8))
+            .collect();
+
+        let (states, mut baqs) = glocal(&reference, &query, &qualities, bandwidth as usize);
+
+        if self.is_extended {
+            for block in &blocks {
+                let y = block.query_start;
+                let x = block.reference_start as i64 - xb as i64;
+
+                for i in y..y + block.len {
+                    if !is_aligned(states[i], x + (i - y) as i64) {
+                        baqs[i] = 0;
+                    }
+                }
+
+                let mut left = baqs[y..y + block.len].to_vec();
+
+                for i in 1..left.len() {
+                    left[i] = left[i].max(left[i - 1]);
+                }
+
+                let mut right = baqs[y..y + block.len].to_vec();
+
+                for i in (0..right.len().saturating_sub(1)).rev() {
+                    right[i] = right[i].max(right[i + 1]);
+                }
+
+                for (i, (&l, &r)) in left.iter().zip(&right).enumerate() {
+                    baqs[y + i] = l.min(r);
+                }
+            }
+        } else {
+            let mut is_in_block = vec![false; baqs.len()];
+
+            for block in &blocks {
+                let y = block.query_start;
+                let x = block.reference_start as i64 - xb as i64;
+
+                for i in y..y + block.len {
+                    is_in_block[i] = true;
+
+                    if !is_aligned(states[i], x + (i - y) as i64) {
+                        baqs[i] = 0;
+                    }
+                }
+            }
+
+            // Bases outside alignment blocks (soft clips and insertions) keep their quality.
+            for (baq, (&q, &is_in_block)) in baqs.iter_mut().zip(qualities.iter().zip(&is_in_block))
+            {
+                if !is_in_block {
+                    *baq = q;
+                }
+            }
+        }
+
+        let scores = qualities
+            .iter()
+            .zip(&baqs)
+            .map(|(&q, &baq)| Score::try_from(q.min(baq)).ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(QualityScores::from(scores))
+    }
+
+    /// Caps the base qualities of a record by their BAQ.
+    ///
+    /// The record is unchanged if BAQ cannot be computed. See [`Self::compute`].
+    pub fn apply(&self, record: &mut Record, reference_sequence: &[u8]) {
+        if let Some(quality_scores) = self.compute(record, reference_sequence) {
+            *record.quality_scores_mut() = quality_scores;
+        }
+    }
+}
+
+// An alignment block of `M`, `=`, or `X` operations.
+struct Block {
+    reference_start: usize,
+    query_start: usize,
+    len: usize,
+}
+
+fn match_blocks(record: &Record, start: usize) -> Option<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut x = start;
+    let mut y = 0;
+
+    for op in record.cigar().iter() {
+        let len = op.len() as usize;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                blocks.push(Block {
+                    reference_start: x,
+                    query_start: y,
+                    len,
+                });
+
+                x += len;
+                y += len;
+            }
+            Kind::SoftClip | Kind::Insertion => y += len,
+            Kind::Deletion => x += len,
+            Kind::Skip => return None,
+            Kind::HardClip | Kind::Pad => {}
+        }
+    }
+
+    if blocks.is_empty() {
+        None
+    } else {
+        Some(blocks)
+    }
+}
+
+// Returns whether the MAP state is a match at the given reference position.
+fn is_aligned(state: Option<(usize, bool)>, reference_position: i64) -> bool {
+    match state {
+        Some((position, is_insertion)) => !is_insertion && position as i64 == reference_position,
+        None => false,
+    }
+}
+
+fn encode_base(b: u8) -> u8 {
+    match b.to_ascii_uppercase() {
+        b'A' => 0,
+        b'C' => 1,
+        b'G' => 2,
+        b'T' => 3,
+        _ => 4,
+    }
+}
+
+fn emission(r: u8, q: u8, error_probability: f64) -> f64 {
+    if r > 3 || q > 3 {
+        1.0
+    } else if r == q {
+        1.0 - error_probability
+    } else {
+        error_probability * EM
+    }
+}
+
+// Computes the MAP states and BAQs of a query aligned to a reference using a glocal (global in the
+// query, local in the reference) banded profile HMM.
+//
+// This is a port of htslib `probaln_glocal`. A MAP state is the 0-based reference position and
+// whether the base is in an insertion.
+#[allow(clippy::many_single_char_names)]
+fn glocal(
+    reference: &[u8],
+    query: &[u8],
+    qualities: &[u8],
+    bandwidth: usize,
+) -> (Vec<Option<(usize, bool)>>, Vec<u8>) {
+    let l_ref = reference.len() as i64;
+    let l_query = query.len() as i64;
+
+    let mut bw = l_ref.max(l_query);
+    bw = bw.min(bandwidth as i64);
+    bw = bw.max((l_ref - l_query).abs());
+
+    let bw2 = (bw * 2 + 1) as usize;
+    let row_len = bw2 * 3 + 6;
+    let rows = l_query as usize + 1;
+
+    let mut f = vec![0.0; rows * row_len];
+    let mut b = vec![0.0; rows * row_len];
+    let mut s = vec![0.0; l_query as usize + 2];
+
+    let qual: Vec<f64> = qualities
+        .iter()
+        .map(|&q| 10f64.powf(-f64::from(q) / 10.0))
+        .collect();
+
+    let u = |i: i64, k: i64| -> usize {
+        let x = (i - bw).max(0);
+        ((k - x + 1) * 3) as usize
+    };
+
+    let in_row = |u: usize| u >= 3 && u < bw2 * 3 + 3;
+
+    // Transition probabilities.
+    let s_m = 1.0 / (2.0 * l_query as f64 + 2.0);
+    let s_i = s_m;
+
+    let d = GAP_OPEN;
+    let e = GAP_EXTEND;
+
+    let m = [
+        (1.0 - d - d) * (1.0 - s_m),
+        d * (1.0 - s_m),
+        d * (1.0 - s_m),
+        (1.0 - e) * (1.0 - s_i),
+        e * (1.0 - s_i),
+        0.0,
+        1.0 - e,
+        0.0,
+        e,
+    ];
+
+    let b_m = (1.0 - d) / l_ref as f64;
+    let b_i = d / l_ref as f64;
+
+    // Forward.
+    f[u(0, 0)] = 1.0;
+    s[0] = 1.0;
+
+    {
+        let row = row_len;
+        let beg = 1;
+        let end = l_ref.min(bw + 1);
+        let mut sum = 0.0;
+
+        for k in beg..=end {
+            let e = emission(reference[k as usize - 1], query[0], qual[0]);
+            let v = row + u(1, k);
+            f[v] = e * b_m;
+            f[v + 1] = EI * b_i;
+            sum += f[v] + f[v + 1];
+        }
+
+        s[1] = sum;
+
+        for k in u(1, beg)..=u(1, end) + 2 {
+            f[row + k] /= sum;
+        }
+    }
+
+    for i in 2..=l_query {
+        let row = i as usize * row_len;
+        let prev = row - row_len;
+        let qli = qual[i as usize - 1];
+        let qyi = query[i as usize - 1];
+        let beg = (i - bw).max(1);
+        let end = (i + bw).min(l_ref);
+        let mut sum = 0.0;
+
+        for k in beg..=end {
+            let e = emission(reference[k as usize - 1], qyi, qli);
+            let v = u(i, k);
+            let v11 = u(i - 1, k - 1);
+            let v10 = u(i - 1, k);
+            let v01 = u(i, k - 1);
+
+            f[row + v] =
+                e * (m[0] * f[prev + v11] + m[3] * f[prev + v11 + 1] + m[6] * f[prev + v11 + 2]);
+            f[row + v + 1] = EI * (m[1] * f[prev + v10] + m[4] * f[prev + v10 + 1]);
+            f[row + v + 2] = m[2] * f[row + v01] + m[8] * f[row + v01 + 2];
+
+            sum += f[row + v] + f[row + v + 1] + f[row + v + 2];
+        }
+
+        s[i as usize] = sum;
+
+        let y = 1.0 / sum;
+
+        for k in u(i, beg)..=u(i, end) + 2 {
+            f[row + k] *= y;
+        }
+    }
+
+    {
+        let row = l_query as usize * row_len;
+        let mut sum = 0.0;
+
+        for k in 1..=l_ref {
+            let v = u(l_query, k);
+
+            if !in_row(v) {
+                continue;
+            }
+
+            sum += f[row + v] * s_m + f[row + v + 1] * s_i;
+        }
+
+        s[l_query as usize + 1] = sum;
+    }
+
+    // Backward.
+    {
+        let row = l_query as usize * row_len;
+        let z = s[l_query as usize] * s[l_query as usize + 1];
+
+        for k in 1..=l_ref {
+            let v = u(l_query, k);
+
+            if !in_row(v) {
+                continue;
+            }
+
+            b[row + v] = s_m / z;
+            b[row + v + 1] = s_i / z;
+        }
+    }
+
+    for i in (1..l_query).rev() {
+        let row = i as usize * row_len;
+        let next = row + row_len;
+        let y = if i > 1 { 1.0 } else { 0.0 };
+        let qli1 = qual[i as usize];
+        let qyi1 = query[i as usize];
+        let beg = (i - bw).max(1);
+        let end = (i + bw).min(l_ref);
+
+        for k in (beg..=end).rev() {
+            let v = u(i, k);
+            let v11 = u(i + 1, k + 1);
+            let v10 = u(i + 1, k);
+            let v01 = u(i, k + 1);
+
+            let e = if k >= l_ref {
+                0.0
+            } else {
+                emission(reference[k as usize], qyi1, qli1) * b[next + v11]
+            };
+
+            b[row + v] = e * m[0] + EI * m[1] * b[next + v10 + 1] + m[2] * b[row + v01 + 2];
+            b[row + v + 1] = e * m[3] + EI * m[4] * b[next + v10 + 1];
+            b[row + v + 2] = (e * m[6] + m[8] * b[row + v01 + 2]) * y;
+        }
+
+        let z = 1.0 / s[i as usize];
+
+        for k in u(i, beg)..=u(i, end) + 2 {
+            b[row + k] *= z;
+        }
+    }
+
+    // MAP.
+    let mut states = vec![None; l_query as usize];
+    let mut baqs = vec![0; l_query as usize];
+
+    for i in 1..=l_query {
+        let row = i as usize * row_len;
+        let beg = (i - bw).max(1);
+        let end = (i + bw).min(l_ref);
+        let mut sum = 0.0;
+        let mut max = 0.0;
+        let mut max_state = None;
+
+        for k in beg..=end {
+            let v = u(i, k);
+
+            let z = f[row + v] * b[row + v];
+
+            if z > max {
+                max = z;
+                max_state = Some((k as usize - 1, false));
+            }
+
+            sum += z;
+
+            let z = f[row + v + 1] * b[row + v + 1];
+
+            if z > max {
+                max = z;
+                max_state = Some((k as usize - 1, true));
+            }
+
+            sum += z;
+        }
+
+        if sum > 0.0 {
+            max /= sum;
+        }
+
+        states[i as usize - 1] = max_state;
+
+        let q = (-4.343 * (1.0 - max).ln() + 0.499) as i64;
+        baqs[i as usize - 1] = if q > 100 { MAX_BAQ } else { q as u8 };
+    }
+
+    (states, baqs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_record(cigar: &str, sequence: &str, quality_scores: &str) -> Record {
+        Record::builder()
+            .set_flags(Flags::empty())
+            .set_position(Position::try_from(11).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_sequence(sequence.parse().unwrap())
+            .set_quality_scores(quality_scores.parse().unwrap())
+            .build()
+    }
+
+    const REFERENCE_SEQUENCE: &[u8] = b"TTGACCTAGCAACGTGACTGATCGGATCCAGTCAAGTAGCTACG";
+
+    #[test]
+    fn test_compute_with_perfect_match() {
+        let record = build_record("10M", "AACGTGACTG", "IIIIIIIIII");
+        let quality_scores = Calculator::new()
+            .compute(&record, REFERENCE_SEQUENCE)
+            .unwrap();
+
+        // Bases far from the read ends keep their quality.
+        let qualities: Vec<_> = quality_scores.iter().map(|&s| u8::from(s)).collect();
+        assert!(qualities.iter().all(|&q| q <= 40));
+        assert_eq!(qualities[4], 40);
+    }
+
+    #[test]
+    fn test_compute_with_misaligned_base() {
+        // The last base mismatches but would match with a deletion.
+        let record = build_record("10M", "AACGTGACTA", "IIIIIIIIII");
+        let quality_scores = Calculator::new()
+            .compute(&record, REFERENCE_SEQUENCE)
+            .unwrap();
+
+        let qualities: Vec<_> = quality_scores.iter().map(|&s| u8::from(s)).collect();
+        assert!(qualities[9] < 40);
+    }
+
+    #[test]
+    fn test_compute_with_unsupported_records() {
+        let calculator = Calculator::new();
+
+        let record = build_record("2M1N2M", "ACGT", "IIII");
+        assert!(calculator.compute(&record, REFERENCE_SEQUENCE).is_none());
+
+        let record = build_record("4S", "ACGT", "IIII");
+        assert!(calculator.compute(&record, REFERENCE_SEQUENCE).is_none());
+
+        let record = Record::default();
+        assert!(calculator.compute(&record, REFERENCE_SEQUENCE).is_none());
+    }
+
+    #[test]
+    fn test_glocal() {
+        let reference = [0, 1, 2, 3, 0, 1, 2, 3];
+        let query = [0, 1, 2, 3];
+        let (states, baqs) = glocal(&reference, &query, &[40; 4], 7);
+
+        assert_eq!(states.len(), 4);
+        assert!(states.iter().all(|state| matches!(state, Some((_, false)))));
+        assert_eq!(baqs.len(), 4);
+    }
+}
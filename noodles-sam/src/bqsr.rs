@@ -0,0 +1,577 @@
+//! Base quality score recalibration (BQSR).
+//!
+//! This applies a recalibration report generated by GATK `BaseRecalibrator` to records, which is
+//! equivalent to `gatk ApplyBQSR` with the default read group, quality score, context, and cycle
+//! covariates. Only base substitution qualities are recalibrated.
+
+mod report;
+
+pub use self::report::{read_report, Report};
+
+use std::{collections::HashMap, convert::TryFrom, io};
+
+use self::report::EventType;
+use super::{
+    header::Header,
+    record::{
+        data::{
+            field::{Tag, Value},
+            Field,
+        },
+        quality_scores::Score,
+        sequence::Base,
+        QualityScores,
+    },
+    Record,
+};
+
+static CONTEXT_COVARIATE_NAME: &str = "Context";
+static CYCLE_COVARIATE_NAME: &str = "Cycle";
+
+// `--mismatches-context-size`
+const CONTEXT_SIZE: usize = 2;
+// `--low-quality-tail`
+const LOW_QUALITY_TAIL: u8 = 2;
+// `--preserve-qualities-smaller-than`
+const DEFAULT_PRESERVE_QUALITIES_LESS_THAN: u8 = 6;
+
+const MIN_SCORE: u8 = 1;
+const MAX_SCORE: u8 = 93;
+
+/// A base quality score recalibrator.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```no_run")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::{fs::File, io::{self, BufReader}};
+/// use noodles_sam::{self as sam, bqsr};
+///
+/// let report = File::open("recal.table").map(BufReader::new).and_then(bqsr::read_report)?;
+/// let recalibrator = bqsr::Recalibrator::new(report).set_emit_original_qualities(true);
+///
+/// let mut reader = File::open("sample.sam").map(BufReader::new).map(sam::Reader::new)?;
+/// let header = reader.read_header()?.parse().map_err(|_| io::ErrorKind::InvalidData)?;
+/// let recalibrator = recalibrator.set_header(&header);
+///
+/// let mut writer = sam::Writer::new(io::stdout());
+///
+/// for result in reader.records() {
+///     let mut record = result?;
+///     recalibrator.recalibrate(&mut record);
+///     writer.write_record(&record)?;
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Recalibrator {
+    report: Report,
+    read_group_keys: HashMap<String, String>,
+    preserve_qualities_less_than: u8,
+    emit_original_qualities: bool,
+    quantize: bool,
+}
+
+impl Recalibrator {
+    /// Creates a recalibrator from a recalibration report.
+    ///
+    /// By default, qualities less than 6 are preserved, original qualities are not written, and
+    /// recalibrated qualities are not quantized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::bqsr::{self, Recalibrator};
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new(report: Report) -> Self {
+        Self {
+            report,
+            read_group_keys: HashMap::new(),
+            preserve_qualities_less_than: DEFAULT_PRESERVE_QUALITIES_LESS_THAN,
+            emit_original_qualities: false,
+            quantize: false,
+        }
+    }
+
+    /// Sets the header of the records to recalibrate.
+    ///
+    /// GATK keys read groups in the report by their platform unit (`PU`), if set, or otherwise
+    /// their ID. Without a header, the read group ID of a record (`RG`) is used as the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::{self as sam, bqsr::{self, Recalibrator}};
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report).set_header(&sam::Header::default());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_header(mut self, header: &Header) -> Self {
+        self.read_group_keys = header
+            .read_groups()
+            .iter()
+            .filter_map(|(id, read_group)| {
+                read_group
+                    .platform_unit()
+                    .map(|platform_unit| (id.clone(), platform_unit.into()))
+            })
+            .collect();
+
+        self
+    }
+
+    /// Sets the quality score below which qualities are not recalibrated.
+    ///
+    /// The default is 6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::bqsr::{self, Recalibrator};
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report).set_preserve_qualities_less_than(2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_preserve_qualities_less_than(mut self, score: u8) -> Self {
+        self.preserve_qualities_less_than = score;
+        self
+    }
+
+    /// Sets whether the original quality scores are written to the `OQ` data field.
+    ///
+    /// An existing `OQ` data field is never overwritten. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::bqsr::{self, Recalibrator};
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report).set_emit_original_qualities(true);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_emit_original_qualities(mut self, emit_original_qualities: bool) -> Self {
+        self.emit_original_qualities = emit_original_qualities;
+        self
+    }
+
+    /// Sets whether recalibrated qualities are quantized using the quantization table of the
+    /// report.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::bqsr::{self, Recalibrator};
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report).set_quantize(true);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_quantize(mut self, quantize: bool) -> Self {
+        self.quantize = quantize;
+        self
+    }
+
+    /// Recalibrates the quality scores of a record.
+    ///
+    /// The record is unchanged if it has no quality scores, no read group, or a read group that
+    /// is not in the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::{self as sam, bqsr::{self, Recalibrator}};
+    ///
+    /// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+    /// let recalibrator = Recalibrator::new(report);
+    ///
+    /// let mut record = sam::Record::default();
+    /// recalibrator.recalibrate(&mut record);
+    /// assert_eq!(record, sam::Record::default());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn recalibrate(&self, record: &mut Record) {
+        if let Some(quality_scores) = self.recalibrated_quality_scores(record) {
+            if self.emit_original_qualities
+                && record.data().get(&Tag::OriginalQualityScores).is_none()
+            {
+                let original_quality_scores = record.quality_scores().to_string();
+
+                record.data_mut().insert(Field::new(
+                    Tag::OriginalQualityScores,
+                    Value::String(original_quality_scores),
+                ));
+            }
+
+            *record.quality_scores_mut() = quality_scores;
+        }
+    }
+
+    fn recalibrated_quality_scores(&self, record: &Record) -> Option<QualityScores> {
+        if record.quality_scores().is_empty() {
+            return None;
+        }
+
+        let read_group_id = record
+            .data()
+            .get_typed::<&str>(&Tag::ReadGroup)
+            .ok()
+            .flatten()?;
+
+        let read_group_key = self
+            .read_group_keys
+            .get(read_group_id)
+            .map(|key| key.as_str())
+            .unwrap_or(read_group_id);
+
+        let read_group_tables = self.report.read_groups.get(read_group_key)?;
+        let read_group_datum = read_group_tables.read_group.get(&EventType::Substitution)?;
+
+        let epsilon = read_group_datum.estimated_q_reported;
+        let read_group_q = read_group_datum.datum.empirical_quality(epsilon);
+
+        let contexts = contexts(record);
+        let cycles = cycles(record);
+
+        let scores: Vec<_> = record
+            .quality_scores()
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| {
+                let q = u8::from(score);
+
+                if q < self.preserve_qualities_less_than {
+                    return score;
+                }
+
+                let quality_score_table = read_group_tables
+                    .quality_scores
+                    .get(&(q, EventType::Substitution));
+
+                let quality_score_q = quality_score_table
+                    .and_then(|table| table.datum)
+                    .map(|datum| datum.empirical_quality(read_group_q))
+                    .unwrap_or(read_group_q);
+
+                let mut delta = 0.0;
+
+                if let Some(table) = quality_score_table {
+                    let covariates = [
+                        contexts[i]
+                            .as_deref()
+                            .and_then(|value| table.covariate(CONTEXT_COVARIATE_NAME, value)),
+                        table.covariate(CYCLE_COVARIATE_NAME, &cycles[i]),
+                    ];
+
+                    for datum in covariates.iter().flatten() {
+                        delta += datum.empirical_quality(quality_score_q) - quality_score_q;
+                    }
+                }
+
+                let mut recalibrated_q = bound_quality_score(quality_score_q + delta);
+
+                if self.quantize {
+                    recalibrated_q = self.report.quantize(recalibrated_q);
+                }
+
+                // `recalibrated_q` is guaranteed to be <= 93.
+                Score::try_from(recalibrated_q).unwrap_or(score)
+            })
+            .collect();
+
+        Some(QualityScores::from(scores))
+    }
+}
+
+/// An iterator adapter that recalibrates the quality scores of records.
+///
+/// This is created by calling [`recalibrate_records`].
+pub struct Recalibrate<'a, I> {
+    inner: I,
+    recalibrator: &'a Recalibrator,
+}
+
+impl<'a, I> Iterator for Recalibrate<'a, I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|result| {
+            result.map(|mut record| {
+                self.recalibrator.recalibrate(&mut record);
+                record
+            })
+        })
+    }
+}
+
+/// Recalibrates the quality scores of a stream of records.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, bqsr::{self, Recalibrator}};
+///
+/// let data = b"r0\t4\t*\t0\t255\t*\t*\t0\t0\tACGT\tNDLS\tRG:Z:rg0\n";
+/// let mut reader = sam::Reader::new(&data[..]);
+///
+/// let report = bqsr::read_report(&b"#:GATKReport.v1.1:0\n"[..])?;
+/// let recalibrator = Recalibrator::new(report);
+///
+/// let records = bqsr::recalibrate_records(reader.records(), &recalibrator);
+/// assert_eq!(records.count(), 1);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn recalibrate_records<I>(
+    records: I,
+    recalibrator: &Recalibrator,
+) -> Recalibrate<'_, I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    Recalibrate {
+        inner: records.into_iter(),
+        recalibrator,
+    }
+}
+
+// `QualityUtils.boundQual(MathUtils.fastRound(q), MAX_RECALIBRATED_Q_SCORE)`
+fn bound_quality_score(q: f64) -> u8 {
+    let rounded = (if q > 0.0 { q + 0.5 } else { q - 0.5 }) as i64;
+    rounded.max(i64::from(MIN_SCORE)).min(i64::from(MAX_SCORE)) as u8
+}
+
+// `ContextCovariate`
+//
+// Contexts are built in the original orientation of the read, i.e., reverse complemented for
+// records on the reverse strand. Low-quality bases at the ends of the read are treated as `N`, and
+// contexts with an `N` have no value.
+fn contexts(record: &Record) -> Vec<Option<String>> {
+    let quality_scores = record.quality_scores();
+    let len = record.sequence().len();
+
+    let mut bases: Vec<char> = record
+        .sequence()
+        .iter()
+        .map(|&base| match base {
+            Base::A | Base::C | Base::G | Base::T => char::from(base),
+            _ => 'N',
+        })
+        .collect();
+
+    if quality_scores.len() == len {
+        let is_low_quality = |score: &Score| u8::from(*score) <= LOW_QUALITY_TAIL;
+
+        let leading_len = quality_scores
+            .iter()
+            .take_while(|s| is_low_quality(s))
+            .count();
+        let trailing_len = quality_scores
+            .iter()
+            .rev()
+            .take_while(|s| is_low_quality(s))
+            .count();
+
+        for base in bases.iter_mut().take(leading_len) {
+            *base = 'N';
+        }
+
+        for base in bases.iter_mut().rev().take(trailing_len) {
+            *base = 'N';
+        }
+    }
+
+    let is_reverse = record.flags().is_reverse_complemented();
+
+    if is_reverse {
+        bases = bases.into_iter().rev().map(complement).collect();
+    }
+
+    let mut contexts: Vec<_> = (0..len)
+        .map(|i| {
+            if i + 1 < CONTEXT_SIZE {
+                return None;
+            }
+
+            let context = &bases[i + 1 - CONTEXT_SIZE..=i];
+
+            if context.contains(&'N') {
+                None
+            } else {
+                Some(context.iter().collect())
+            }
+        })
+        .collect();
+
+    if is_reverse {
+        contexts.reverse();
+    }
+
+    contexts
+}
+
+fn complement(base: char) -> char {
+    match base {
+        'A' => 'T',
+        'C' => 'G',
+        'G' => 'C',
+        'T' => 'A',
+        _ => 'N',
+    }
+}
+
+// `CycleCovariate`
+//
+// Cycles are 1-based in the original orientation of the read and negative for the second read of
+// a pair.
+fn cycles(record: &Record) -> Vec<String> {
+    let flags = record.flags();
+    let len = record.quality_scores().len() as i64;
+
+    let read_order_factor = if flags.is_paired() && flags.is_read_2() {
+        -1
+    } else {
+        1
+    };
+
+    let (mut cycle, increment) = if flags.is_reverse_complemented() {
+        (len * read_order_factor, -read_order_factor)
+    } else {
+        (read_order_factor, read_order_factor)
+    };
+
+    let mut cycles = Vec::with_capacity(len as usize);
+
+    for _ in 0..len {
+        cycles.push(cycle.to_string());
+        cycle += increment;
+    }
+
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Data, Flags};
+
+    use super::*;
+
+    static REPORT: &[u8] = b"#:GATKReport.v1.1:3
+#:GATKTable:6:1:%s:%s:%.4f:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable0:
+ReadGroup  EventType  EmpiricalQuality  EstimatedQReported  Observations  Errors
+rg0        M                   20.0000             30.0000        100000 1000.00
+
+#:GATKTable:6:1:%s:%d:%s:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable1:
+ReadGroup  QualityScore  EventType  EmpiricalQuality  Observations  Errors
+rg0                  30  M                   20.0000        100000 1000.00
+
+#:GATKTable:8:1:%s:%d:%s:%s:%s:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable2:
+ReadGroup  QualityScore  CovariateValue  CovariateName  EventType  EmpiricalQuality  Observations  Errors
+rg0                  30  1               Cycle          M                   20.0000        100000 1000.00
+";
+
+    fn build_record(flags: Flags, quality_scores: &str) -> Record {
+        let data = Data::from(vec![Field::new(
+            Tag::ReadGroup,
+            Value::String(String::from("rg0")),
+        )]);
+
+        Record::builder()
+            .set_flags(flags)
+            .set_sequence("ACGT".parse().unwrap())
+            .set_quality_scores(quality_scores.parse().unwrap())
+            .set_data(data)
+            .build()
+    }
+
+    #[test]
+    fn test_recalibrate() -> io::Result<()> {
+        let recalibrator =
+            Recalibrator::new(read_report(REPORT)?).set_emit_original_qualities(true);
+
+        // Q30 is recalibrated to Q20. Q2 is preserved.
+        let mut record = build_record(Flags::empty(), "??#?");
+        recalibrator.recalibrate(&mut record);
+        assert_eq!(record.quality_scores().to_string(), "55#5");
+        assert_eq!(
+            record.data().get_typed::<&str>(&Tag::OriginalQualityScores),
+            Ok(Some("??#?"))
+        );
+
+        // A read group that is not in the report is unchanged.
+        let mut record = Record::builder()
+            .set_quality_scores("????".parse().unwrap())
+            .set_data(Data::from(vec![Field::new(
+                Tag::ReadGroup,
+                Value::String(String::from("rg1")),
+            )]))
+            .build();
+        recalibrator.recalibrate(&mut record);
+        assert_eq!(record.quality_scores().to_string(), "????");
+        assert!(record.data().get(&Tag::OriginalQualityScores).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bound_quality_score() {
+        assert_eq!(bound_quality_score(-3.0), 1);
+        assert_eq!(bound_quality_score(0.4), 1);
+        assert_eq!(bound_quality_score(19.5), 20);
+        assert_eq!(bound_quality_score(120.0), 93);
+    }
+
+    #[test]
+    fn test_contexts() {
+        let record = build_record(Flags::empty(), "#???");
+        assert_eq!(
+            contexts(&record),
+            [
+                None,
+                None,
+                Some(String::from("CG")),
+                Some(String::from("GT"))
+            ]
+        );
+
+        // reverse complement: ACGT
+        let record = build_record(Flags::REVERSE_COMPLEMENTED, "????");
+        assert_eq!(
+            contexts(&record),
+            [
+                Some(String::from("GT")),
+                Some(String::from("CG")),
+                Some(String::from("AC")),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let record = build_record(Flags::empty(), "????");
+        assert_eq!(cycles(&record), ["1", "2", "3", "4"]);
+
+        let record = build_record(Flags::REVERSE_COMPLEMENTED, "????");
+        assert_eq!(cycles(&record), ["4", "3", "2", "1"]);
+
+        let record = build_record(Flags::PAIRED | Flags::READ_2, "????");
+        assert_eq!(cycles(&record), ["-1", "-2", "-3", "-4"]);
+    }
+}
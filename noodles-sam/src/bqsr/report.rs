@@ -0,0 +1,564 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+
+const REPORT_PREFIX: &str = "#:GATKReport.";
+const TABLE_PREFIX: &str = "#:GATKTable:";
+
+static QUANTIZED_TABLE_NAME: &str = "Quantized";
+static READ_GROUP_TABLE_NAME: &str = "RecalTable0";
+static QUALITY_SCORE_TABLE_NAME: &str = "RecalTable1";
+static COVARIATE_TABLE_NAME: &str = "RecalTable2";
+
+const MAX_SCORE: u8 = 93;
+
+// `QualityUtils.MAX_REASONABLE_Q_SCORE`
+const MAX_REASONABLE_Q_SCORE: u32 = 60;
+// `RecalDatum.MAX_GATK_USABLE_Q_SCORE`
+const MAX_USABLE_Q_SCORE: u32 = 40;
+// `RecalDatum.SMOOTHING_CONSTANT`
+const SMOOTHING_CONSTANT: u64 = 1;
+const MAX_NUMBER_OF_OBSERVATIONS: u64 = i32::MAX as u64;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) enum EventType {
+    // `M`
+    Substitution,
+    // `I`
+    Insertion,
+    // `D`
+    Deletion,
+}
+
+impl EventType {
+    fn from_symbol(s: &str) -> Option<Self> {
+        match s {
+            "M" => Some(Self::Substitution),
+            "I" => Some(Self::Insertion),
+            "D" => Some(Self::Deletion),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Datum {
+    observations: u64,
+    errors: f64,
+}
+
+impl Datum {
+    // `RecalDatum.getEmpiricalQuality(conditionalPrior)`
+    pub(super) fn empirical_quality(&self, prior: f64) -> f64 {
+        let errors = (self.errors + 0.5) as u64 + SMOOTHING_CONSTANT;
+        let observations = self.observations + SMOOTHING_CONSTANT + SMOOTHING_CONSTANT;
+        let q = bayesian_estimate_of_empirical_quality(observations, errors, prior);
+        f64::from(q.min(u32::from(MAX_SCORE)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct ReadGroupDatum {
+    pub(super) datum: Datum,
+    pub(super) estimated_q_reported: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct QualityScoreTable {
+    pub(super) datum: Option<Datum>,
+    // covariate name => covariate value => datum
+    covariates: HashMap<String, HashMap<String, Datum>>,
+}
+
+impl QualityScoreTable {
+    pub(super) fn covariate(&self, name: &str, value: &str) -> Option<&Datum> {
+        self.covariates
+            .get(name)
+            .and_then(|values| values.get(value))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct ReadGroupTables {
+    pub(super) read_group: HashMap<EventType, ReadGroupDatum>,
+    pub(super) quality_scores: HashMap<(u8, EventType), QualityScoreTable>,
+}
+
+/// A GATK base quality score recalibration report.
+///
+/// This is the table written by `gatk BaseRecalibrator`. Only the quantization and recalibration
+/// tables are read; arguments used to generate the report are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub(super) quantized_scores: Vec<u8>,
+    pub(super) read_groups: HashMap<String, ReadGroupTables>,
+}
+
+impl Report {
+    /// Returns whether the report has recalibration data for the given read group.
+    ///
+    /// The read group is the key used in the report, i.e., the platform unit (`PU`) of the read
+    /// group, if set, or otherwise its ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::bqsr;
+    ///
+    /// let data = b"#:GATKReport.v1.1:1
+    /// #:GATKTable:6:1:%s:%s:%.4f:%.4f:%d:%.2f:;
+    /// #:GATKTable:RecalTable0:
+    /// ReadGroup  EventType  EmpiricalQuality  EstimatedQReported  Observations  Errors
+    /// rg0        M                   30.0000             31.0000          1000    1.00
+    ///
+    /// ";
+    ///
+    /// let report = bqsr::read_report(&data[..])?;
+    /// assert!(report.contains_read_group("rg0"));
+    /// assert!(!report.contains_read_group("rg1"));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn contains_read_group(&self, read_group: &str) -> bool {
+        self.read_groups.contains_key(read_group)
+    }
+
+    pub(super) fn quantize(&self, score: u8) -> u8 {
+        self.quantized_scores
+            .get(usize::from(score))
+            .copied()
+            .unwrap_or(score)
+    }
+}
+
+struct Table {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn column_index(&self, name: &str) -> io::Result<usize> {
+        self.columns
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| invalid_data(format!("table {}: missing column: {}", self.name, name)))
+    }
+}
+
+/// Reads a GATK base quality score recalibration report.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io::{self, BufReader}};
+/// use noodles_sam::bqsr;
+/// let report = File::open("recal.table").map(BufReader::new).and_then(bqsr::read_report)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn read_report<R>(reader: R) -> io::Result<Report>
+where
+    R: BufRead,
+{
+    let tables = read_tables(reader)?;
+
+    let mut report = Report {
+        quantized_scores: (0..=MAX_SCORE).collect(),
+        read_groups: HashMap::new(),
+    };
+
+    for table in &tables {
+        if table.name == QUANTIZED_TABLE_NAME {
+            read_quantized_table(table, &mut report.quantized_scores)?;
+        } else if table.name == READ_GROUP_TABLE_NAME {
+            read_read_group_table(table, &mut report.read_groups)?;
+        } else if table.name == QUALITY_SCORE_TABLE_NAME {
+            read_quality_score_table(table, &mut report.read_groups)?;
+        } else if table.name == COVARIATE_TABLE_NAME {
+            read_covariate_table(table, &mut report.read_groups)?;
+        }
+    }
+
+    Ok(report)
+}
+
+fn read_tables<R>(reader: R) -> io::Result<Vec<Table>>
+where
+    R: BufRead,
+{
+    let mut lines = reader.lines();
+
+    match lines.next().transpose()? {
+        Some(line) if line.starts_with(REPORT_PREFIX) => {}
+        _ => return Err(invalid_data("invalid GATK report header")),
+    }
+
+    let mut tables = Vec::new();
+    let mut current_table: Option<Table> = None;
+
+    for result in lines {
+        let line = result?;
+        let line = line.trim_end();
+
+        if let Some(s) = line.strip_prefix(TABLE_PREFIX) {
+            // A table has two definition lines: its format, which starts with the number of
+            // columns, and its name and description.
+            let name = s.split(':').next().unwrap_or_default();
+
+            if name.parse::<usize>().is_err() {
+                if let Some(table) = current_table.take() {
+                    tables.push(table);
+                }
+
+                current_table = Some(Table {
+                    name: name.into(),
+                    columns: Vec::new(),
+                    rows: Vec::new(),
+                });
+            }
+
+            continue;
+        }
+
+        let table = match current_table.as_mut() {
+            Some(table) => table,
+            None => continue,
+        };
+
+        if line.is_empty() {
+            if let Some(table) = current_table.take() {
+                tables.push(table);
+            }
+        } else if table.columns.is_empty() {
+            table.columns = line.split_whitespace().map(String::from).collect();
+        } else {
+            table
+                .rows
+                .push(line.split_whitespace().map(String::from).collect());
+        }
+    }
+
+    if let Some(table) = current_table.take() {
+        tables.push(table);
+    }
+
+    Ok(tables)
+}
+
+fn read_quantized_table(table: &Table, quantized_scores: &mut [u8]) -> io::Result<()> {
+    let score_index = table.column_index("QualityScore")?;
+    let quantized_score_index = table.column_index("QuantizedScore")?;
+
+    for row in &table.rows {
+        let score: u8 = parse_field(table, row, score_index)?;
+        let quantized_score: u8 = parse_field(table, row, quantized_score_index)?;
+
+        if let Some(s) = quantized_scores.get_mut(usize::from(score)) {
+            *s = quantized_score.min(MAX_SCORE);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_read_group_table(
+    table: &Table,
+    read_groups: &mut HashMap<String, ReadGroupTables>,
+) -> io::Result<()> {
+    let read_group_index = table.column_index("ReadGroup")?;
+    let event_type_index = table.column_index("EventType")?;
+    let estimated_q_reported_index = table.column_index("EstimatedQReported")?;
+
+    for row in &table.rows {
+        let read_group = get_field(table, row, read_group_index)?;
+        let event_type = parse_event_type(table, row, event_type_index)?;
+        let estimated_q_reported = parse_field(table, row, estimated_q_reported_index)?;
+        let datum = parse_datum(table, row)?;
+
+        read_groups
+            .entry(read_group.into())
+            .or_default()
+            .read_group
+            .insert(
+                event_type,
+                ReadGroupDatum {
+                    datum,
+                    estimated_q_reported,
+                },
+            );
+    }
+
+    Ok(())
+}
+
+fn read_quality_score_table(
+    table: &Table,
+    read_groups: &mut HashMap<String, ReadGroupTables>,
+) -> io::Result<()> {
+    let read_group_index = table.column_index("ReadGroup")?;
+    let score_index = table.column_index("QualityScore")?;
+    let event_type_index = table.column_index("EventType")?;
+
+    for row in &table.rows {
+        let read_group = get_field(table, row, read_group_index)?;
+        let score = parse_field(table, row, score_index)?;
+        let event_type = parse_event_type(table, row, event_type_index)?;
+        let datum = parse_datum(table, row)?;
+
+        read_groups
+            .entry(read_group.into())
+            .or_default()
+            .quality_scores
+            .entry((score, event_type))
+            .or_default()
+            .datum = Some(datum);
+    }
+
+    Ok(())
+}
+
+fn read_covariate_table(
+    table: &Table,
+    read_groups: &mut HashMap<String, ReadGroupTables>,
+) -> io::Result<()> {
+    let read_group_index = table.column_index("ReadGroup")?;
+    let score_index = table.column_index("QualityScore")?;
+    let value_index = table.column_index("CovariateValue")?;
+    let name_index = table.column_index("CovariateName")?;
+    let event_type_index = table.column_index("EventType")?;
+
+    for row in &table.rows {
+        let read_group = get_field(table, row, read_group_index)?;
+        let score = parse_field(table, row, score_index)?;
+        let value = get_field(table, row, value_index)?;
+        let name = get_field(table, row, name_index)?;
+        let event_type = parse_event_type(table, row, event_type_index)?;
+        let datum = parse_datum(table, row)?;
+
+        read_groups
+            .entry(read_group.into())
+            .or_default()
+            .quality_scores
+            .entry((score, event_type))
+            .or_default()
+            .covariates
+            .entry(name.into())
+            .or_default()
+            .insert(value.into(), datum);
+    }
+
+    Ok(())
+}
+
+fn get_field<'a>(table: &Table, row: &'a [String], i: usize) -> io::Result<&'a str> {
+    row.get(i)
+        .map(|s| s.as_str())
+        .ok_or_else(|| invalid_data(format!("table {}: missing field", table.name)))
+}
+
+fn parse_field<T>(table: &Table, row: &[String], i: usize) -> io::Result<T>
+where
+    T: std::str::FromStr,
+{
+    let s = get_field(table, row, i)?;
+
+    s.parse().map_err(|_| {
+        invalid_data(format!(
+            "table {}: invalid {}: {}",
+            table.name, table.columns[i], s
+        ))
+    })
+}
+
+fn parse_event_type(table: &Table, row: &[String], i: usize) -> io::Result<EventType> {
+    let s = get_field(table, row, i)?;
+
+    EventType::from_symbol(s)
+        .ok_or_else(|| invalid_data(format!("table {}: invalid event type: {}", table.name, s)))
+}
+
+fn parse_datum(table: &Table, row: &[String]) -> io::Result<Datum> {
+    let observations_index = table.column_index("Observations")?;
+    let errors_index = table.column_index("Errors")?;
+
+    Ok(Datum {
+        observations: parse_field(table, row, observations_index)?,
+        errors: parse_field(table, row, errors_index)?,
+    })
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+// `RecalDatum.bayesianEstimateOfEmpiricalQuality`
+fn bayesian_estimate_of_empirical_quality(observations: u64, errors: u64, q_reported: f64) -> u32 {
+    let mut max_q = 0;
+    let mut max_log10_posterior = f64::NEG_INFINITY;
+
+    for q in 0..=MAX_REASONABLE_Q_SCORE {
+        let q_empirical = f64::from(q);
+
+        let log10_posterior = log10_q_empirical_prior(q_empirical, q_reported)
+            + log10_q_empirical_likelihood(q_empirical, observations, errors);
+
+        if log10_posterior > max_log10_posterior {
+            max_q = q;
+            max_log10_posterior = log10_posterior;
+        }
+    }
+
+    max_q
+}
+
+// `RecalDatum.log10QempPrior`
+//
+// This is a Gaussian with a height of 0.9, a mean of 0, and a standard deviation of 0.5.
+fn log10_q_empirical_prior(q_empirical: f64, q_reported: f64) -> f64 {
+    let difference = ((q_empirical - q_reported).abs() as u32).min(MAX_USABLE_Q_SCORE);
+    let d = f64::from(difference);
+    finite_or_min((0.9 * (-(d * d) / (2.0 * 0.5 * 0.5)).exp()).log10())
+}
+
+// `RecalDatum.log10QempLikelihood`
+//
+// This is the log10 binomial probability of the number of errors given the observations, without
+// the binomial coefficient, which is the same for every empirical quality.
+fn log10_q_empirical_likelihood(q_empirical: f64, mut observations: u64, mut errors: u64) -> f64 {
+    if observations == 0 {
+        return 0.0;
+    }
+
+    if observations > MAX_NUMBER_OF_OBSERVATIONS {
+        let fraction = MAX_NUMBER_OF_OBSERVATIONS as f64 / observations as f64;
+        errors = (errors as f64 * fraction).round() as u64;
+        observations = MAX_NUMBER_OF_OBSERVATIONS;
+    }
+
+    let log10_p = q_empirical * -0.1;
+
+    let log10_probability = if log10_p == 0.0 {
+        if errors == observations {
+            0.0
+        } else {
+            f64::NEG_INFINITY
+        }
+    } else {
+        let log10_one_minus_p = (1.0 - 10f64.powf(log10_p)).log10();
+        log10_p * errors as f64 + log10_one_minus_p * (observations as f64 - errors as f64)
+    };
+
+    finite_or_min(log10_probability)
+}
+
+fn finite_or_min(n: f64) -> f64 {
+    if n.is_finite() {
+        n
+    } else {
+        f64::MIN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static DATA: &[u8] = b"#:GATKReport.v1.1:5
+#:GATKTable:2:2:%s:%s:;
+#:GATKTable:Arguments:Recalibration argument collection values used in this run
+Argument  Value
+covariate ReadGroupCovariate,QualityScoreCovariate,ContextCovariate,CycleCovariate
+mismatches_context_size 2
+
+#:GATKTable:3:2:%d:%d:%d:;
+#:GATKTable:Quantized:Quality quantization map
+QualityScore  Count  QuantizedScore
+30            100    31
+40            100    40
+
+#:GATKTable:6:1:%s:%s:%.4f:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable0:
+ReadGroup  EventType  EmpiricalQuality  EstimatedQReported  Observations  Errors
+rg0        M                   30.0000             31.0000          1000    1.00
+
+#:GATKTable:6:1:%s:%d:%s:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable1:
+ReadGroup  QualityScore  EventType  EmpiricalQuality  Observations  Errors
+rg0                  30  M                   30.0000          1000    1.00
+
+#:GATKTable:8:2:%s:%d:%s:%s:%s:%.4f:%d:%.2f:;
+#:GATKTable:RecalTable2:
+ReadGroup  QualityScore  CovariateValue  CovariateName  EventType  EmpiricalQuality  Observations  Errors
+rg0                  30  AC              Context        M                   30.0000           500    0.50
+rg0                  30  1               Cycle          M                   30.0000           500    0.50
+";
+
+    #[test]
+    fn test_read_report() -> io::Result<()> {
+        let report = read_report(DATA)?;
+
+        assert_eq!(report.quantize(30), 31);
+        assert_eq!(report.quantize(20), 20);
+
+        let read_group_tables = &report.read_groups["rg0"];
+
+        let read_group_datum = read_group_tables.read_group[&EventType::Substitution];
+        assert_eq!(read_group_datum.estimated_q_reported, 31.0);
+        assert_eq!(
+            read_group_datum.datum,
+            Datum {
+                observations: 1000,
+                errors: 1.0
+            }
+        );
+
+        let quality_score_table = &read_group_tables.quality_scores[&(30, EventType::Substitution)];
+        assert_eq!(
+            quality_score_table.datum,
+            Some(Datum {
+                observations: 1000,
+                errors: 1.0
+            })
+        );
+        assert_eq!(
+            quality_score_table.covariate("Context", "AC"),
+            Some(&Datum {
+                observations: 500,
+                errors: 0.5
+            })
+        );
+        assert!(quality_score_table.covariate("Context", "GT").is_none());
+        assert!(quality_score_table.covariate("Cycle", "1").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_report_with_invalid_header() {
+        assert!(read_report(&b"ReadGroup\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_empirical_quality() {
+        // 2 errors in 1002 observations (with smoothing) is about Q27.
+        let datum = Datum {
+            observations: 1000,
+            errors: 1.0,
+        };
+        assert_eq!(datum.empirical_quality(27.0), 27.0);
+
+        // The prior pulls the estimate towards the reported quality.
+        assert!(datum.empirical_quality(40.0) > 27.0);
+
+        // With few observations, the estimate is the prior.
+        let datum = Datum {
+            observations: 0,
+            errors: 0.0,
+        };
+        assert_eq!(datum.empirical_quality(25.0), 25.0);
+    }
+}
@@ -0,0 +1,378 @@
+//! Edit distance (`NM`) and mismatched positions (`MD`) recalculation.
+//!
+//! This recomputes the `NM` and `MD` data fields of records against a reference, like `samtools
+//! calmd`. `NM` is the number of mismatched aligned bases plus the number of inserted and deleted
+//! bases. Reference and read bases are compared case-insensitively, and an `N` in either is a
+//! mismatch.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature = "io", doc = "```")]
+#![cfg_attr(not(feature = "io"), doc = "```ignore")]
+//! # use std::io;
+//! use std::collections::HashMap;
+//! use noodles_sam::{self as sam, calmd::Calmd};
+//!
+//! let data = b"r0\t0\tsq0\t1\t60\t4M\t*\t0\t0\tACGA\tNDLS\n";
+//! let mut reader = sam::Reader::new(&data[..]);
+//!
+//! let mut reference = HashMap::new();
+//! reference.insert(String::from("sq0"), b"ACGT".to_vec());
+//!
+//! let calmd = Calmd::new(reference);
+//!
+//! for result in reader.records() {
+//!     let mut record = result?;
+//!     calmd.apply(&mut record)?;
+//!     assert_eq!(record.data().to_string(), "NM:i:1\tMD:Z:3T0");
+//! }
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::io;
+
+use super::{
+    pileup::mpileup::Reference,
+    record::{
+        cigar::op::Kind,
+        data::{
+            field::{Tag, Value},
+            Field,
+        },
+        sequence::Base,
+        Sequence,
+    },
+    transform::{Action, Context, Transform},
+    Record,
+};
+
+/// The handling of existing `NM` and `MD` data fields.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// Replace existing values with the recalculated values.
+    #[default]
+    Overwrite,
+    /// Keep existing values but return an error if they differ from the recalculated values.
+    /// Missing values are added.
+    Validate,
+}
+
+/// An `NM` and `MD` calculator.
+pub struct Calmd<R> {
+    reference: R,
+    mode: Mode,
+    replace_matches: bool,
+}
+
+impl<R> Calmd<R>
+where
+    R: Reference,
+{
+    /// Creates an `NM` and `MD` calculator.
+    ///
+    /// The reference is a provider of reference sequence bases, e.g., a map of reference sequence
+    /// names to sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use noodles_sam::calmd::Calmd;
+    /// let calmd = Calmd::new(HashMap::new());
+    /// ```
+    pub fn new(reference: R) -> Self {
+        Self {
+            reference,
+            mode: Mode::default(),
+            replace_matches: false,
+        }
+    }
+
+    /// Sets how existing `NM` and `MD` data fields are handled.
+    ///
+    /// By default, existing values are overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use noodles_sam::calmd::{Calmd, Mode};
+    /// let calmd = Calmd::new(HashMap::new()).set_mode(Mode::Validate);
+    /// ```
+    pub fn set_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether read bases that match the reference are replaced with `=`.
+    ///
+    /// This is equivalent to `samtools calmd -e`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use noodles_sam::calmd::Calmd;
+    /// let calmd = Calmd::new(HashMap::new()).set_replace_matches(true);
+    /// ```
+    pub fn set_replace_matches(mut self, replace_matches: bool) -> Self {
+        self.replace_matches = replace_matches;
+        self
+    }
+
+    /// Calculates the edit distance (`NM`) and mismatched positions (`MD`) of a record.
+    ///
+    /// This returns `None` if the record is unmapped, has no sequence, or the reference does not
+    /// cover the alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// use std::collections::HashMap;
+    /// use noodles_sam::{self as sam, calmd::Calmd, record::Position};
+    ///
+    /// let mut reference = HashMap::new();
+    /// reference.insert(String::from("sq0"), b"ACGTACGT".to_vec());
+    ///
+    /// let record = sam::Record::builder()
+    ///     .set_flags(sam::record::Flags::empty())
+    ///     .set_reference_sequence_name("sq0".parse()?)
+    ///     .set_position(Position::try_from(1)?)
+    ///     .set_cigar("2M1I2M2D2M".parse()?)
+    ///     .set_sequence("ACTGTGT".parse()?)
+    ///     .build();
+    ///
+    /// let calmd = Calmd::new(reference);
+    /// assert_eq!(calmd.calculate(&record), Some((3, String::from("4^AC2"))));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn calculate(&self, record: &Record) -> Option<(i32, String)> {
+        self.align(record)
+            .map(|alignment| (alignment.nm, alignment.md))
+    }
+
+    /// Recalculates and sets the `NM` and `MD` data fields of a record.
+    ///
+    /// If read bases are replaced with `=`, the sequence is updated. Records that cannot be
+    /// calculated, e.g., unmapped records, are left unchanged.
+    ///
+    /// In validation mode, this returns an error if an existing value differs from the
+    /// recalculated value.
+    pub fn apply(&self, record: &mut Record) -> io::Result<()> {
+        let alignment = match self.align(record) {
+            Some(alignment) => alignment,
+            None => return Ok(()),
+        };
+
+        let nm = Field::new(Tag::EditDistance, Value::Int32(alignment.nm));
+        let md = Field::new(Tag::MismatchedPositions, Value::String(alignment.md));
+
+        for field in [nm, md] {
+            if self.mode == Mode::Validate {
+                if let Some(existing_field) = record.data().get(field.tag()) {
+                    if !values_eq(existing_field.value(), field.value()) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "{} mismatch: expected {}, got {}",
+                                field.tag(),
+                                field.value(),
+                                existing_field.value()
+                            ),
+                        ));
+                    }
+
+                    continue;
+                }
+            }
+
+            record.data_mut().insert(field);
+        }
+
+        if self.replace_matches {
+            *record.sequence_mut() = alignment.sequence;
+        }
+
+        Ok(())
+    }
+
+    fn align(&self, record: &Record) -> Option<Alignment> {
+        if record.flags().is_unmapped() || record.sequence().is_empty() {
+            return None;
+        }
+
+        let reference_sequence_name = record.reference_sequence_name()?;
+        let mut reference_position = record.position().map(i64::from)?;
+
+        let bases = record.sequence();
+        let mut read_position = 0;
+
+        let mut sequence: Vec<Base> = bases.to_vec();
+        let mut nm = 0;
+        let mut md = String::new();
+        let mut match_len = 0;
+
+        for op in record.cigar().iter() {
+            let len = op.len() as usize;
+
+            match op.kind() {
+                Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                    for _ in 0..len {
+                        let reference_base = self
+                            .reference
+                            .base(reference_sequence_name, reference_position)?
+                            .to_ascii_uppercase();
+                        let read_base = *bases.get(read_position)?;
+
+                        let is_match = read_base == Base::Eq
+                            || (reference_base != b'N'
+                                && char::from(read_base) == char::from(reference_base));
+
+                        if is_match {
+                            match_len += 1;
+
+                            if self.replace_matches {
+                                sequence[read_position] = Base::Eq;
+                            }
+                        } else {
+                            nm += 1;
+                            md.push_str(&match_len.to_string());
+                            md.push(char::from(reference_base));
+                            match_len = 0;
+                        }
+
+                        read_position += 1;
+                        reference_position += 1;
+                    }
+                }
+                Kind::Insertion => {
+                    nm += len as i32;
+                    read_position += len;
+                }
+                Kind::Deletion => {
+                    nm += len as i32;
+                    md.push_str(&match_len.to_string());
+                    md.push('^');
+
+                    for _ in 0..len {
+                        let reference_base = self
+                            .reference
+                            .base(reference_sequence_name, reference_position)?;
+                        md.push(char::from(reference_base.to_ascii_uppercase()));
+                        reference_position += 1;
+                    }
+
+                    match_len = 0;
+                }
+                Kind::Skip => reference_position += len as i64,
+                Kind::SoftClip => read_position += len,
+                Kind::HardClip | Kind::Pad => {}
+            }
+        }
+
+        md.push_str(&match_len.to_string());
+
+        Some(Alignment {
+            nm,
+            md,
+            sequence: Sequence::from(sequence),
+        })
+    }
+}
+
+impl<R> Transform for Calmd<R>
+where
+    R: Reference,
+{
+    fn apply(&mut self, record: &mut Record, _: &Context<'_>) -> io::Result<Action> {
+        Calmd::apply(self, record).map(|_| Action::Keep)
+    }
+}
+
+struct Alignment {
+    nm: i32,
+    md: String,
+    sequence: Sequence,
+}
+
+// Compares values by content, since `NM` may be stored as any integer type.
+fn values_eq(a: &Value, b: &Value) -> bool {
+    match (a.as_int32(), b.as_int32()) {
+        (Some(m), Some(n)) => m == n,
+        _ => a.as_str() == b.as_str(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, convert::TryFrom};
+
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_reference() -> HashMap<String, Vec<u8>> {
+        let mut reference = HashMap::new();
+        reference.insert(String::from("sq0"), b"ACGTacgtNN".to_vec());
+        reference
+    }
+
+    fn build_record(position: i64, cigar: &str, sequence: &str) -> Record {
+        Record::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_name("sq0".parse().unwrap())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_sequence(sequence.parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_calculate() {
+        let calmd = Calmd::new(build_reference());
+
+        let record = build_record(1, "8M", "ACGTACGT");
+        assert_eq!(calmd.calculate(&record), Some((0, String::from("8"))));
+
+        let record = build_record(1, "8M", "TCGTACGA");
+        assert_eq!(calmd.calculate(&record), Some((2, String::from("0A6T0"))));
+
+        let record = build_record(1, "2S2M1D2M", "NNTCTA");
+        assert_eq!(calmd.calculate(&record), Some((2, String::from("0A1^G2"))));
+
+        let record = build_record(1, "2M4N2M", "ACGT");
+        assert_eq!(calmd.calculate(&record), Some((0, String::from("4"))));
+
+        let record = build_record(7, "4M", "GTNN");
+        assert_eq!(calmd.calculate(&record), Some((2, String::from("2N0N0"))));
+
+        let record = build_record(8, "4M", "TNNA");
+        assert!(calmd.calculate(&record).is_none());
+
+        assert!(calmd.calculate(&Record::default()).is_none());
+    }
+
+    #[test]
+    fn test_apply() -> Result<(), Box<dyn std::error::Error>> {
+        let mut record = build_record(1, "4M", "ACGA");
+        Calmd::new(build_reference())
+            .set_replace_matches(true)
+            .apply(&mut record)?;
+        assert_eq!(record.data().to_string(), "NM:i:1\tMD:Z:3T0");
+        assert_eq!(record.sequence().to_string(), "===A");
+
+        let calmd = Calmd::new(build_reference()).set_mode(Mode::Validate);
+
+        let mut record = build_record(1, "4M", "ACGA");
+        record.data_mut().insert("NM:i:1".parse()?);
+        calmd.apply(&mut record)?;
+        assert_eq!(record.data().to_string(), "NM:i:1\tMD:Z:3T0");
+
+        let mut record = build_record(1, "4M", "ACGA");
+        record.data_mut().insert("NM:i:0".parse()?);
+        assert!(calmd.apply(&mut record).is_err());
+
+        Ok(())
+    }
+}
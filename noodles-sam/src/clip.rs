@@ -0,0 +1,420 @@
+//! Reference intervals and clipping of records to a region.
+//!
+//! Clipping a record to a region produces the sub-alignment that overlaps the region. Read bases
+//! that align outside the region are either soft clipped or hard clipped, and the alignment start
+//! is moved to the first aligned base in the region. This is useful, e.g., for amplicon primer
+//! trimming or for displaying only the part of an alignment that is in view.
+
+use std::convert::TryFrom;
+
+use super::{
+    record::{
+        cigar::{op::Kind, Op},
+        Cigar, Position, QualityScores, Sequence,
+    },
+    Record,
+};
+
+/// The way read bases outside a region are clipped.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// Keep the bases in the sequence and mark them as soft clipped (`S`).
+    #[default]
+    Soft,
+    /// Remove the bases from the sequence and quality scores and mark them as hard clipped (`H`).
+    ///
+    /// Existing soft clips are converted to hard clips.
+    Hard,
+}
+
+/// Returns the reference interval of a record.
+///
+/// The interval is 1-based and inclusive. It is calculated from the alignment start and the
+/// reference span of the CIGAR. A record with a CIGAR that does not consume the reference sequence
+/// is treated as spanning a single base.
+///
+/// This returns `None` if the record is unmapped or has no position.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{self as sam, clip, record::{Flags, Position}};
+///
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_position(Position::try_from(8)?)
+///     .set_cigar("2S4M1D3M".parse()?)
+///     .build();
+///
+/// assert_eq!(clip::reference_interval(&record), Some((8, 15)));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn reference_interval(record: &Record) -> Option<(i64, i64)> {
+    if record.flags().is_unmapped() {
+        return None;
+    }
+
+    let start = record.position().map(i64::from)?;
+    let len = i64::from(record.cigar().reference_len()).max(1);
+
+    Some((start, start + len - 1))
+}
+
+/// Clips a record to the given region.
+///
+/// `start` and `end` are the 1-based, inclusive bounds of the region on the reference sequence of
+/// the record. The returned record has its position, CIGAR, and, when hard clipping, sequence and
+/// quality scores updated. Insertions, deletions, and skips at the edges of the sub-alignment are
+/// clipped or removed so that it starts and ends with an aligned base. All other fields are kept
+/// as is.
+///
+/// This returns `None` if the record is unmapped, has no position, or does not have any aligned
+/// bases in the region.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam,
+///     clip::{self, Mode},
+///     record::{Flags, Position},
+/// };
+///
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_position(Position::try_from(8)?)
+///     .set_cigar("4M1D4M".parse()?)
+///     .set_sequence("ACGTACGT".parse()?)
+///     .build();
+///
+/// let clipped = clip::clip(&record, 10, 14, Mode::Soft).expect("overlaps region");
+/// assert_eq!(clipped.position().map(i64::from), Some(10));
+/// assert_eq!(clipped.cigar().to_string(), "2S2M1D2M2S");
+/// assert_eq!(clipped.sequence().to_string(), "ACGTACGT");
+///
+/// let clipped = clip::clip(&record, 10, 14, Mode::Hard).expect("overlaps region");
+/// assert_eq!(clipped.cigar().to_string(), "2H2M1D2M2H");
+/// assert_eq!(clipped.sequence().to_string(), "GTAC");
+///
+/// assert!(clip::clip(&record, 20, 30, Mode::Soft).is_none());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn clip(record: &Record, start: i64, end: i64, mode: Mode) -> Option<Record> {
+    if record.flags().is_unmapped() {
+        return None;
+    }
+
+    let alignment_start = record.position().map(i64::from)?;
+    let sub_alignment = clip_cigar(record.cigar(), alignment_start, start, end)?;
+
+    let position = Position::try_from(sub_alignment.start).ok()?;
+
+    let mut ops = Vec::with_capacity(sub_alignment.ops.len() + 4);
+
+    let (sequence, quality_scores) = match mode {
+        Mode::Soft => {
+            push_op(
+                &mut ops,
+                Kind::HardClip,
+                sub_alignment.leading_hard_clip_len,
+            );
+            push_op(&mut ops, Kind::SoftClip, sub_alignment.leading_clip_len);
+            ops.extend(sub_alignment.ops);
+            push_op(&mut ops, Kind::SoftClip, sub_alignment.trailing_clip_len);
+            push_op(
+                &mut ops,
+                Kind::HardClip,
+                sub_alignment.trailing_hard_clip_len,
+            );
+
+            (record.sequence().clone(), record.quality_scores().clone())
+        }
+        Mode::Hard => {
+            let leading_len = sub_alignment.leading_clip_len;
+            let trailing_len = sub_alignment.trailing_clip_len;
+
+            push_op(
+                &mut ops,
+                Kind::HardClip,
+                sub_alignment.leading_hard_clip_len + leading_len,
+            );
+            ops.extend(sub_alignment.ops);
+            push_op(
+                &mut ops,
+                Kind::HardClip,
+                trailing_len + sub_alignment.trailing_hard_clip_len,
+            );
+
+            let sequence = Sequence::from(trim(record.sequence(), leading_len, trailing_len));
+            let quality_scores =
+                QualityScores::from(trim(record.quality_scores(), leading_len, trailing_len));
+
+            (sequence, quality_scores)
+        }
+    };
+
+    Some(build_record(
+        record,
+        position,
+        Cigar::from(ops),
+        sequence,
+        quality_scores,
+    ))
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct SubAlignment {
+    start: i64,
+    leading_hard_clip_len: u32,
+    leading_clip_len: u32,
+    ops: Vec<Op>,
+    trailing_clip_len: u32,
+    trailing_hard_clip_len: u32,
+}
+
+// Splits the CIGAR operations of an alignment starting at `alignment_start` into the operations
+// that overlap [`start`, `end`] and the number of read bases before and after them.
+//
+// Leading and trailing soft clips are included in the clip lengths, and leading and trailing
+// hard clips are returned separately.
+fn clip_cigar(cigar: &Cigar, alignment_start: i64, start: i64, end: i64) -> Option<SubAlignment> {
+    let mut sub_alignment = SubAlignment::default();
+    let mut sub_alignment_start = None;
+
+    // Operations that are kept only if they are followed by an aligned base in the region.
+    let mut pending_ops = Vec::new();
+
+    let mut reference_position = alignment_start;
+    let mut seen_body = false;
+
+    for op in cigar.iter().filter(|op| !op.is_empty()) {
+        let len = op.len();
+
+        match op.kind() {
+            Kind::HardClip => {
+                if seen_body {
+                    sub_alignment.trailing_hard_clip_len += len;
+                } else {
+                    sub_alignment.leading_hard_clip_len += len;
+                }
+            }
+            Kind::SoftClip => {
+                if seen_body {
+                    sub_alignment.trailing_clip_len += len;
+                } else {
+                    sub_alignment.leading_clip_len += len;
+                }
+            }
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                seen_body = true;
+
+                let op_start = reference_position;
+                let op_end = reference_position + i64::from(len) - 1;
+                reference_position += i64::from(len);
+
+                let overlap_start = op_start.max(start);
+                let overlap_end = op_end.min(end);
+
+                if overlap_start > overlap_end {
+                    let clip_len = len + query_len(&pending_ops);
+                    pending_ops.clear();
+
+                    if sub_alignment_start.is_none() {
+                        sub_alignment.leading_clip_len += clip_len;
+                    } else {
+                        sub_alignment.trailing_clip_len += clip_len;
+                    }
+
+                    continue;
+                }
+
+                // `overlap_start` and `overlap_end` are within the operation.
+                let leading_len = (overlap_start - op_start) as u32;
+                let overlap_len = (overlap_end - overlap_start + 1) as u32;
+                let trailing_len = len - leading_len - overlap_len;
+
+                if sub_alignment_start.is_none() {
+                    sub_alignment_start = Some(overlap_start);
+                    sub_alignment.leading_clip_len += query_len(&pending_ops) + leading_len;
+                } else {
+                    sub_alignment.ops.extend(pending_ops.iter().copied());
+                }
+
+                pending_ops.clear();
+
+                sub_alignment.ops.push(Op::new(op.kind(), overlap_len));
+                sub_alignment.trailing_clip_len += trailing_len;
+            }
+            Kind::Insertion | Kind::Deletion | Kind::Skip | Kind::Pad => {
+                seen_body = true;
+
+                if matches!(op.kind(), Kind::Deletion | Kind::Skip) {
+                    reference_position += i64::from(len);
+                }
+
+                pending_ops.push(*op);
+            }
+        }
+    }
+
+    sub_alignment.trailing_clip_len += query_len(&pending_ops);
+
+    sub_alignment.start = sub_alignment_start?;
+
+    Some(sub_alignment)
+}
+
+fn query_len(ops: &[Op]) -> u32 {
+    ops.iter()
+        .filter(|op| op.kind() == Kind::Insertion)
+        .map(|op| op.len())
+        .sum()
+}
+
+fn push_op(ops: &mut Vec<Op>, kind: Kind, len: u32) {
+    if len > 0 {
+        ops.push(Op::new(kind, len));
+    }
+}
+
+// Removes `leading_len` and `trailing_len` elements from the ends of a list. A missing list (e.g.,
+// `*`) or a list that is too short is returned as is.
+fn trim<T>(values: &[T], leading_len: u32, trailing_len: u32) -> Vec<T>
+where
+    T: Copy,
+{
+    let leading_len = leading_len as usize;
+    let trailing_len = trailing_len as usize;
+
+    if leading_len + trailing_len > values.len() {
+        return values.to_vec();
+    }
+
+    values[leading_len..values.len() - trailing_len].to_vec()
+}
+
+fn build_record(
+    template: &Record,
+    position: Position,
+    cigar: Cigar,
+    sequence: Sequence,
+    quality_scores: QualityScores,
+) -> Record {
+    let mut builder = Record::builder()
+        .set_flags(template.flags())
+        .set_position(position)
+        .set_mapping_quality(template.mapping_quality())
+        .set_cigar(cigar)
+        .set_template_length(template.template_length())
+        .set_sequence(sequence)
+        .set_quality_scores(quality_scores)
+        .set_data(template.data().clone());
+
+    if let Some(read_name) = template.read_name() {
+        builder = builder.set_read_name(read_name.clone());
+    }
+
+    if let Some(reference_sequence_name) = template.reference_sequence_name() {
+        builder = builder.set_reference_sequence_name(reference_sequence_name.clone());
+    }
+
+    if let Some(mate_reference_sequence_name) = template.mate_reference_sequence_name() {
+        builder = builder.set_mate_reference_sequence_name(mate_reference_sequence_name.clone());
+    }
+
+    if let Some(mate_position) = template.mate_position() {
+        builder = builder.set_mate_position(mate_position);
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::Flags;
+
+    use super::*;
+
+    fn build_record(position: i64, cigar: &str, sequence: &str, quality_scores: &str) -> Record {
+        Record::builder()
+            .set_flags(Flags::empty())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_sequence(sequence.parse().unwrap())
+            .set_quality_scores(quality_scores.parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_reference_interval() {
+        let record = build_record(8, "4M2I3D1M", "*", "*");
+        assert_eq!(reference_interval(&record), Some((8, 15)));
+
+        let record = build_record(8, "*", "*", "*");
+        assert_eq!(reference_interval(&record), Some((8, 8)));
+
+        let record = Record::default();
+        assert!(reference_interval(&record).is_none());
+
+        let record = Record::builder()
+            .set_flags(Flags::UNMAPPED)
+            .set_position(Position::try_from(8).unwrap())
+            .build();
+        assert!(reference_interval(&record).is_none());
+    }
+
+    #[test]
+    fn test_clip() {
+        let record = build_record(8, "1H2S4M", "ACGTAC", "NOPQRS");
+
+        // contained
+        let clipped = clip(&record, 1, 100, Mode::Soft).unwrap();
+        assert_eq!(clipped.position().map(i64::from), Some(8));
+        assert_eq!(clipped.cigar().to_string(), "1H2S4M");
+
+        let clipped = clip(&record, 1, 100, Mode::Hard).unwrap();
+        assert_eq!(clipped.cigar().to_string(), "3H4M");
+        assert_eq!(clipped.sequence().to_string(), "GTAC");
+        assert_eq!(clipped.quality_scores().to_string(), "PQRS");
+
+        // overlapping the end
+        let clipped = clip(&record, 10, 100, Mode::Soft).unwrap();
+        assert_eq!(clipped.position().map(i64::from), Some(10));
+        assert_eq!(clipped.cigar().to_string(), "1H4S2M");
+
+        let clipped = clip(&record, 1, 9, Mode::Hard).unwrap();
+        assert_eq!(clipped.position().map(i64::from), Some(8));
+        assert_eq!(clipped.cigar().to_string(), "3H2M2H");
+        assert_eq!(clipped.sequence().to_string(), "GT");
+        assert_eq!(clipped.quality_scores().to_string(), "PQ");
+
+        // no overlap
+        assert!(clip(&record, 1, 7, Mode::Soft).is_none());
+        assert!(clip(&record, 12, 20, Mode::Soft).is_none());
+    }
+
+    #[test]
+    fn test_clip_with_indels() {
+        let record = build_record(1, "2M2I2M3D2M", "ACGTACGT", "*");
+
+        // The insertion is at the start of the sub-alignment.
+        let clipped = clip(&record, 3, 100, Mode::Soft).unwrap();
+        assert_eq!(clipped.position().map(i64::from), Some(3));
+        assert_eq!(clipped.cigar().to_string(), "4S2M3D2M");
+
+        // The deletion is at the end of the sub-alignment.
+        let clipped = clip(&record, 1, 6, Mode::Hard).unwrap();
+        assert_eq!(clipped.cigar().to_string(), "2M2I2M2H");
+        assert_eq!(clipped.sequence().to_string(), "ACGTAC");
+
+        // The region only overlaps the deletion.
+        assert!(clip(&record, 5, 7, Mode::Soft).is_none());
+
+        // The region starts in the deletion.
+        let clipped = clip(&record, 6, 100, Mode::Soft).unwrap();
+        assert_eq!(clipped.position().map(i64::from), Some(8));
+        assert_eq!(clipped.cigar().to_string(), "6S2M");
+    }
+}
@@ -0,0 +1,440 @@
+//! Consensus read calling from groups of duplicate records.
+//!
+//! A group is typically a set of records that share the same alignment position and UMI (see
+//! [`crate::umi`]). The bases of each record are stacked by their index in the read, and each
+//! consensus base is the most likely base given the base qualities of the observations.
+
+use std::{convert::TryFrom, error, fmt};
+
+use super::{
+    record::{
+        data::{
+            field::{Tag, Value},
+            Field,
+        },
+        quality_scores::Score,
+        sequence::Base,
+        Data, Flags, QualityScores, Sequence,
+    },
+    Record,
+};
+
+// fgbio conventions
+static MAX_DEPTH_TAG: &str = "cD";
+static MIN_DEPTH_TAG: &str = "cM";
+static ERROR_RATE_TAG: &str = "cE";
+
+const BASES: [Base; 4] = [Base::A, Base::C, Base::G, Base::T];
+
+const NO_CALL_QUALITY: u8 = 2;
+const MAX_QUALITY: u8 = 93;
+
+/// A consensus read caller.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{self as sam, consensus::Caller};
+///
+/// let records = [
+///     sam::Record::builder()
+///         .set_sequence("ACGT".parse()?)
+///         .set_quality_scores("IIII".parse()?)
+///         .build(),
+///     sam::Record::builder()
+///         .set_sequence("ACTT".parse()?)
+///         .set_quality_scores("II#I".parse()?)
+///         .build(),
+/// ];
+///
+/// let caller = Caller::default();
+/// let record = caller.call(&records)?;
+///
+/// assert_eq!(record.sequence().to_string(), "ACGT");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Caller {
+    min_reads: usize,
+    min_input_base_quality: u8,
+    max_quality: u8,
+}
+
+impl Caller {
+    /// Sets the minimum number of observations required to call a base.
+    ///
+    /// Positions with fewer observations are called as `N`. The default is 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::consensus::Caller;
+    /// let caller = Caller::default().set_min_reads(2);
+    /// ```
+    pub fn set_min_reads(mut self, min_reads: usize) -> Self {
+        self.min_reads = min_reads;
+        self
+    }
+
+    /// Sets the minimum base quality of an observation.
+    ///
+    /// Bases with lower qualities are ignored. The default is 10.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::consensus::Caller;
+    /// let caller = Caller::default().set_min_input_base_quality(20);
+    /// ```
+    pub fn set_min_input_base_quality(mut self, min_input_base_quality: u8) -> Self {
+        self.min_input_base_quality = min_input_base_quality;
+        self
+    }
+
+    /// Sets the maximum quality of a consensus base.
+    ///
+    /// The default is 90.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::consensus::Caller;
+    /// let caller = Caller::default().set_max_quality(60);
+    /// ```
+    pub fn set_max_quality(mut self, max_quality: u8) -> Self {
+        self.max_quality = max_quality.min(MAX_QUALITY);
+        self
+    }
+
+    /// Calls a consensus record from a group of duplicate records.
+    ///
+    /// The alignment fields, read name, and data of the consensus record are copied from the
+    /// first record with the longest sequence. The duplicate flag is cleared. The following data
+    /// fields are added:
+    ///
+    ///   * `cD`: the maximum depth of the consensus bases,
+    ///   * `cM`: the minimum depth of the consensus bases, and
+    ///   * `cE`: the fraction of observations that disagree with the consensus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, consensus::Caller};
+    ///
+    /// let records = [
+    ///     sam::Record::builder().set_sequence("ACGT".parse()?).build(),
+    ///     sam::Record::builder().set_sequence("ACGT".parse()?).build(),
+    /// ];
+    ///
+    /// let record = Caller::default().call(&records)?;
+    /// assert_eq!(record.data().to_string(), "cD:i:2\tcM:i:2\tcE:f:0");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn call(&self, records: &[Record]) -> Result<Record, CallError> {
+        let template = records
+            .iter()
+            .fold(None, |longest: Option<&Record>, record| match longest {
+                Some(r) if r.sequence().len() >= record.sequence().len() => Some(r),
+                _ => Some(record),
+            })
+            .ok_or(CallError::Empty)?;
+
+        for record in records {
+            if !record.quality_scores().is_empty()
+                && record.quality_scores().len() != record.sequence().len()
+            {
+                return Err(CallError::QualityScoresLengthMismatch);
+            }
+        }
+
+        let len = template.sequence().len();
+
+        let mut bases = Vec::with_capacity(len);
+        let mut scores = Vec::with_capacity(len);
+        let mut min_depth = usize::MAX;
+        let mut max_depth = 0;
+        let mut error_count = 0;
+        let mut observation_count = 0;
+
+        for i in 0..len {
+            let observations = self.observations(records, i);
+            let depth = observations.len();
+
+            min_depth = min_depth.min(depth);
+            max_depth = max_depth.max(depth);
+
+            let (base, quality) = if depth < self.min_reads.max(1) {
+                (Base::N, NO_CALL_QUALITY)
+            } else {
+                call_base(&observations, self.max_quality)
+            };
+
+            observation_count += depth;
+            error_count += observations.iter().filter(|(b, _)| *b != base).count();
+
+            bases.push(base);
+            scores.push(Score::try_from(quality).expect("invalid consensus quality"));
+        }
+
+        if len == 0 {
+            min_depth = 0;
+        }
+
+        let error_rate = if observation_count == 0 {
+            0.0
+        } else {
+            error_count as f32 / observation_count as f32
+        };
+
+        let mut data = template.data().clone();
+        data.insert(Field::new(
+            Tag::Other(MAX_DEPTH_TAG.into()),
+            Value::Int32(depth_to_i32(max_depth)),
+        ));
+        data.insert(Field::new(
+            Tag::Other(MIN_DEPTH_TAG.into()),
+            Value::Int32(depth_to_i32(min_depth)),
+        ));
+        data.insert(Field::new(
+            Tag::Other(ERROR_RATE_TAG.into()),
+            Value::Float(error_rate),
+        ));
+
+        let mut flags = template.flags();
+        flags.remove(Flags::DUPLICATE);
+
+        Ok(build_record(
+            template,
+            flags,
+            Sequence::from(bases),
+            QualityScores::from(scores),
+            data,
+        ))
+    }
+
+    // Returns the bases and qualities at read position `i` that pass the input filters.
+    fn observations(&self, records: &[Record], i: usize) -> Vec<(Base, u8)> {
+        records
+            .iter()
+            .filter_map(|record| {
+                let base = record.sequence().get(i).copied()?;
+
+                if !BASES.contains(&base) {
+                    return None;
+                }
+
+                // A missing quality score is treated as the maximum quality.
+                let quality = match record.quality_scores().get(i) {
+                    Some(&score) => u8::from(score),
+                    None => MAX_QUALITY,
+                };
+
+                if quality < self.min_input_base_quality {
+                    None
+                } else {
+                    Some((base, quality))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Caller {
+    fn default() -> Self {
+        Self {
+            min_reads: 1,
+            min_input_base_quality: 10,
+            max_quality: 90,
+        }
+    }
+}
+
+/// An error returned when a consensus record fails to be called.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CallError {
+    /// The group is empty.
+    Empty,
+    /// The number of quality scores of a record does not match its sequence length.
+    QualityScoresLengthMismatch,
+}
+
+impl error::Error for CallError {}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty group"),
+            Self::QualityScoresLengthMismatch => {
+                f.write_str("quality scores length does not match sequence length")
+            }
+        }
+    }
+}
+
+// Returns the most likely base and its Phred-scaled error probability.
+//
+// The likelihood of each candidate base is the product of the probabilities of the observations,
+// i.e., `1 - e` for a matching base and `e / 3` for a mismatched base, where `e` is the error
+// probability of the base quality. A uniform prior is used.
+fn call_base(observations: &[(Base, u8)], max_quality: u8) -> (Base, u8) {
+    let mut log_likelihoods = [0.0f64; BASES.len()];
+
+    for &(base, quality) in observations {
+        let e = phred_to_probability(quality);
+
+        for (candidate, log_likelihood) in BASES.iter().zip(log_likelihoods.iter_mut()) {
+            *log_likelihood += if *candidate == base {
+                (1.0 - e).ln()
+            } else {
+                (e / 3.0).ln()
+            };
+        }
+    }
+
+    let (i, &max_log_likelihood) = log_likelihoods
+        .iter()
+        .enumerate()
+        .fold(None, |best: Option<(usize, &f64)>, (i, ll)| match best {
+            Some((_, best_ll)) if best_ll >= ll => best,
+            _ => Some((i, ll)),
+        })
+        .expect("log likelihoods cannot be empty");
+
+    // log-sum-exp normalized by the maximum for numerical stability
+    let total: f64 = log_likelihoods
+        .iter()
+        .map(|ll| (ll - max_log_likelihood).exp())
+        .sum();
+
+    let error_probability = 1.0 - 1.0 / total;
+    let quality = probability_to_phred(error_probability).min(f64::from(max_quality));
+
+    (BASES[i], quality.round() as u8)
+}
+
+fn phred_to_probability(quality: u8) -> f64 {
+    10f64.powf(-f64::from(quality) / 10.0)
+}
+
+fn probability_to_phred(p: f64) -> f64 {
+    if p <= 0.0 {
+        f64::from(MAX_QUALITY)
+    } else {
+        -10.0 * p.log10()
+    }
+}
+
+fn depth_to_i32(depth: usize) -> i32 {
+    i32::try_from(depth).unwrap_or(i32::MAX)
+}
+
+fn build_record(
+    template: &Record,
+    flags: Flags,
+    sequence: Sequence,
+    quality_scores: QualityScores,
+    data: Data,
+) -> Record {
+    let mut builder = Record::builder()
+        .set_flags(flags)
+        .set_mapping_quality(template.mapping_quality())
+        .set_cigar(template.cigar().clone())
+        .set_template_length(template.template_length())
+        .set_sequence(sequence)
+        .set_quality_scores(quality_scores)
+        .set_data(data);
+
+    if let Some(read_name) = template.read_name() {
+        builder = builder.set_read_name(read_name.clone());
+    }
+
+    if let Some(reference_sequence_name) = template.reference_sequence_name() {
+        builder = builder.set_reference_sequence_name(reference_sequence_name.clone());
+    }
+
+    if let Some(position) = template.position() {
+        builder = builder.set_position(position);
+    }
+
+    if let Some(mate_reference_sequence_name) = template.mate_reference_sequence_name() {
+        builder = builder.set_mate_reference_sequence_name(mate_reference_sequence_name.clone());
+    }
+
+    if let Some(mate_position) = template.mate_position() {
+        builder = builder.set_mate_position(mate_position);
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_record(sequence: &str, quality_scores: &str) -> Record {
+        Record::builder()
+            .set_flags(Flags::DUPLICATE)
+            .set_sequence(sequence.parse().unwrap())
+            .set_quality_scores(quality_scores.parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_call() -> Result<(), CallError> {
+        let records = [
+            build_record("ACGT", "IIII"),
+            build_record("ACGA", "III+"),
+            build_record("ACGTA", "IIIII"),
+        ];
+
+        let record = Caller::default().call(&records)?;
+
+        assert_eq!(record.sequence().to_string(), "ACGTA");
+        assert!(!record.flags().is_duplicate());
+
+        let data = record.data();
+        assert_eq!(
+            data.get_typed::<i32>(&Tag::Other(MAX_DEPTH_TAG.into())),
+            Ok(Some(3))
+        );
+        assert_eq!(
+            data.get_typed::<i32>(&Tag::Other(MIN_DEPTH_TAG.into())),
+            Ok(Some(1))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_with_min_reads() -> Result<(), CallError> {
+        let records = [build_record("ACGT", "IIII"), build_record("AC", "II")];
+
+        let record = Caller::default().set_min_reads(2).call(&records)?;
+
+        assert_eq!(record.sequence().to_string(), "ACNN");
+        assert_eq!(record.quality_scores().to_string(), "vv##");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_with_no_records() {
+        assert_eq!(Caller::default().call(&[]), Err(CallError::Empty));
+    }
+
+    #[test]
+    fn test_call_base() {
+        // Two Q30 observations agree.
+        let (base, quality) = call_base(&[(Base::A, 30), (Base::A, 30)], 90);
+        assert_eq!(base, Base::A);
+        assert_eq!(quality, 65);
+
+        // A Q30 observation disagrees with a Q20 observation.
+        let (base, quality) = call_base(&[(Base::A, 30), (Base::C, 20)], 90);
+        assert_eq!(base, Base::A);
+        assert_eq!(quality, 10);
+
+        let (_, quality) = call_base(&[(Base::A, 40), (Base::A, 40), (Base::A, 40)], 60);
+        assert_eq!(quality, 60);
+    }
+}
@@ -0,0 +1,253 @@
+//! Template downsampling.
+//!
+//! Records are kept or discarded by hashing their read names, so all records of the same template
+//! (e.g., both mates of a pair and any secondary or supplementary alignments) are either all kept
+//! or all discarded. This uses the same hashing as `samtools view --subsample`.
+
+use std::io;
+
+use super::Record;
+
+const MISSING_READ_NAME: &[u8] = b"*";
+
+// 24-bit hash space
+const HASH_MASK: u32 = 0xffffff;
+const HASH_SPACE: f64 = 16777216.0;
+
+/// A template downsampler.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{self as sam, downsample::Downsampler};
+///
+/// let downsampler = Downsampler::new(0.5).set_seed(13);
+///
+/// let record = sam::Record::builder().set_read_name("r0".parse()?).build();
+/// let mate = record.clone();
+///
+/// assert_eq!(downsampler.keep(&record), downsampler.keep(&mate));
+/// # Ok::<(), sam::record::read_name::ParseError>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Downsampler {
+    fraction: f64,
+    seed: u32,
+}
+
+impl Downsampler {
+    /// Creates a downsampler that keeps the given fraction of templates.
+    ///
+    /// The seed defaults to 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::downsample::Downsampler;
+    /// let downsampler = Downsampler::new(0.25);
+    /// ```
+    pub fn new(fraction: f64) -> Self {
+        Self { fraction, seed: 0 }
+    }
+
+    /// Sets the seed.
+    ///
+    /// Different seeds select different subsets of templates. With a seed of 0, the selection
+    /// matches `samtools view -s 0.<fraction>`. Note that `samtools` randomizes a nonzero integer
+    /// part of `-s` before using it as a seed, so other seeds do not correspond.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::downsample::Downsampler;
+    /// let downsampler = Downsampler::new(0.25).set_seed(8);
+    /// ```
+    pub fn set_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns whether the given record is kept.
+    ///
+    /// A record without a read name is treated as having the read name `*`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, downsample::Downsampler};
+    ///
+    /// let record = sam::Record::builder().set_read_name("r0".parse()?).build();
+    ///
+    /// assert!(Downsampler::new(1.0).keep(&record));
+    /// assert!(!Downsampler::new(0.0).keep(&record));
+    /// # Ok::<(), sam::record::read_name::ParseError>(())
+    /// ```
+    pub fn keep(&self, record: &Record) -> bool {
+        let read_name = record
+            .read_name()
+            .map(|name| name.as_bytes())
+            .unwrap_or(MISSING_READ_NAME);
+
+        self.keep_read_name(read_name)
+    }
+
+    /// Returns whether a record with the given read name is kept.
+    ///
+    /// This can be used with records of other formats, e.g., BAM.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::downsample::Downsampler;
+    /// assert!(Downsampler::new(1.0).keep_read_name(b"r0"));
+    /// ```
+    pub fn keep_read_name(&self, read_name: &[u8]) -> bool {
+        let k = wang_hash(x31_hash(read_name) ^ self.seed);
+        f64::from(k & HASH_MASK) / HASH_SPACE < self.fraction
+    }
+}
+
+/// An iterator adapter that downsamples records.
+///
+/// This is created by calling [`downsample`].
+pub struct Downsample<I> {
+    inner: I,
+    downsampler: Downsampler,
+}
+
+impl<I> Iterator for Downsample<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next()? {
+                Ok(record) => {
+                    if self.downsampler.keep(&record) {
+                        return Some(Ok(record));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Downsamples a stream of records.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, downsample::{self, Downsampler}};
+///
+/// let data = b"r0\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*
+/// r1\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*
+/// ";
+/// let mut reader = sam::Reader::new(&data[..]);
+///
+/// let records = downsample::downsample(reader.records(), Downsampler::new(1.0));
+/// assert_eq!(records.count(), 2);
+/// ```
+pub fn downsample<I>(records: I, downsampler: Downsampler) -> Downsample<I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    Downsample {
+        inner: records.into_iter(),
+        downsampler,
+    }
+}
+
+// khash `__ac_X31_hash_string`
+fn x31_hash(s: &[u8]) -> u32 {
+    let mut iter = s.iter();
+
+    let mut h = match iter.next() {
+        Some(&b) => u32::from(b),
+        None => return 0,
+    };
+
+    for &b in iter {
+        h = (h << 5).wrapping_sub(h).wrapping_add(u32::from(b));
+    }
+
+    h
+}
+
+// khash `__ac_Wang_hash`
+fn wang_hash(mut key: u32) -> u32 {
+    key = key.wrapping_add(!(key << 15));
+    key ^= key >> 10;
+    key = key.wrapping_add(key << 3);
+    key ^= key >> 6;
+    key = key.wrapping_add(!(key << 11));
+    key ^= key >> 16;
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x31_hash() {
+        assert_eq!(x31_hash(b""), 0);
+        assert_eq!(x31_hash(b"r"), 114);
+        // 114 * 31 + 48
+        assert_eq!(x31_hash(b"r0"), 3582);
+    }
+
+    #[test]
+    fn test_keep_read_name() {
+        let downsampler = Downsampler::new(0.5);
+
+        let kept = (0..1000)
+            .map(|i| format!("r{}", i))
+            .filter(|name| downsampler.keep_read_name(name.as_bytes()))
+            .count();
+
+        assert!(kept > 400 && kept < 600, "kept = {}", kept);
+    }
+
+    #[test]
+    fn test_keep_read_name_with_seed() {
+        let a = Downsampler::new(0.5);
+        let b = Downsampler::new(0.5).set_seed(1);
+
+        let names: Vec<_> = (0..100).map(|i| format!("r{}", i)).collect();
+
+        let kept_a: Vec<_> = names
+            .iter()
+            .map(|name| a.keep_read_name(name.as_bytes()))
+            .collect();
+        let kept_b: Vec<_> = names
+            .iter()
+            .map(|name| b.keep_read_name(name.as_bytes()))
+            .collect();
+
+        assert_ne!(kept_a, kept_b);
+
+        let kept_a_again: Vec<_> = names
+            .iter()
+            .map(|name| a.keep_read_name(name.as_bytes()))
+            .collect();
+
+        assert_eq!(kept_a, kept_a_again);
+    }
+
+    #[test]
+    fn test_downsample() {
+        let records = vec![
+            Ok(Record::default()),
+            Err(io::Error::from(io::ErrorKind::InvalidData)),
+        ];
+
+        let mut iter = downsample(records, Downsampler::new(0.0));
+        assert!(matches!(iter.next(), Some(Err(_))));
+        assert!(iter.next().is_none());
+    }
+}
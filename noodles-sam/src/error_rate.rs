@@ -0,0 +1,565 @@
+//! Reference-free error rate estimation.
+//!
+//! Mismatches, insertions, and deletions are counted from the alignments of records and their
+//! `MD` and `NM` data fields, so no reference sequence is needed. Counts are collected per read
+//! group and per cycle.
+
+use std::{collections::BTreeMap, convert::TryFrom, error, fmt};
+
+use super::{
+    record::{
+        cigar::op::Kind,
+        data::{field::Tag, GetTypedError},
+    },
+    Record,
+};
+
+/// Error counts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Counts {
+    aligned_bases: u64,
+    mismatches: u64,
+    insertions: u64,
+    inserted_bases: u64,
+    deletions: u64,
+    deleted_bases: u64,
+}
+
+impl Counts {
+    /// Returns the number of read bases aligned to the reference, i.e., in `M`, `=`, or `X`
+    /// operations.
+    pub fn aligned_bases(&self) -> u64 {
+        self.aligned_bases
+    }
+
+    /// Returns the number of aligned bases that differ from the reference.
+    pub fn mismatches(&self) -> u64 {
+        self.mismatches
+    }
+
+    /// Returns the number of insertions.
+    pub fn insertions(&self) -> u64 {
+        self.insertions
+    }
+
+    /// Returns the number of inserted bases.
+    pub fn inserted_bases(&self) -> u64 {
+        self.inserted_bases
+    }
+
+    /// Returns the number of deletions.
+    pub fn deletions(&self) -> u64 {
+        self.deletions
+    }
+
+    /// Returns the number of deleted bases.
+    pub fn deleted_bases(&self) -> u64 {
+        self.deleted_bases
+    }
+
+    /// Returns the number of mismatches per aligned base.
+    ///
+    /// This is 0 if there are no aligned bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::error_rate::Counts;
+    /// assert_eq!(Counts::default().mismatch_rate(), 0.0);
+    /// ```
+    pub fn mismatch_rate(&self) -> f64 {
+        rate(self.mismatches, self.aligned_bases)
+    }
+
+    /// Returns the number of insertions per aligned base.
+    ///
+    /// This is 0 if there are no aligned bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::error_rate::Counts;
+    /// assert_eq!(Counts::default().insertion_rate(), 0.0);
+    /// ```
+    pub fn insertion_rate(&self) -> f64 {
+        rate(self.insertions, self.aligned_bases)
+    }
+
+    /// Returns the number of deletions per aligned base.
+    ///
+    /// This is 0 if there are no aligned bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::error_rate::Counts;
+    /// assert_eq!(Counts::default().deletion_rate(), 0.0);
+    /// ```
+    pub fn deletion_rate(&self) -> f64 {
+        rate(self.deletions, self.aligned_bases)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.aligned_bases += other.aligned_bases;
+        self.mismatches += other.mismatches;
+        self.insertions += other.insertions;
+        self.inserted_bases += other.inserted_bases;
+        self.deletions += other.deletions;
+        self.deleted_bases += other.deleted_bases;
+    }
+}
+
+fn rate(n: u64, aligned_bases: u64) -> f64 {
+    if aligned_bases == 0 {
+        0.0
+    } else {
+        n as f64 / aligned_bases as f64
+    }
+}
+
+/// Error counts of a read group.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReadGroupCounts {
+    total: Counts,
+    cycles: Vec<Counts>,
+}
+
+impl ReadGroupCounts {
+    /// Returns the error counts of all records in the read group.
+    pub fn total(&self) -> &Counts {
+        &self.total
+    }
+
+    /// Returns the error counts per cycle.
+    ///
+    /// Index 0 is cycle 1. Cycles are counted from the first base of the read as sequenced, i.e.,
+    /// from the end of the sequence for records on the reverse strand, and include soft clipped
+    /// bases. Only records with an `MD` data field are counted per cycle.
+    pub fn cycles(&self) -> &[Counts] {
+        &self.cycles
+    }
+}
+
+/// An error returned when a record fails to be added to an error rate report.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AddError {
+    /// A data field value is invalid.
+    InvalidField(GetTypedError),
+    /// The mismatched positions (`MD`) are invalid or do not match the CIGAR.
+    InvalidMismatchedPositions,
+    /// The edit distance (`NM`) is less than the number of inserted and deleted bases.
+    InvalidEditDistance,
+}
+
+impl error::Error for AddError {}
+
+impl fmt::Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidField(e) => write!(f, "invalid field: {}", e),
+            Self::InvalidMismatchedPositions => f.write_str("invalid mismatched positions"),
+            Self::InvalidEditDistance => f.write_str("invalid edit distance"),
+        }
+    }
+}
+
+/// An error rate report.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam,
+///     error_rate::Report,
+///     record::{Flags, Position},
+/// };
+///
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_position(Position::try_from(1)?)
+///     .set_cigar("4M".parse()?)
+///     .set_data("RG:Z:rg0\tMD:Z:1A2".parse()?)
+///     .build();
+///
+/// let mut report = Report::default();
+/// report.add(&record)?;
+///
+/// assert_eq!(report.total().mismatch_rate(), 0.25);
+///
+/// let read_group_counts = &report.read_groups()[&Some(String::from("rg0"))];
+/// assert_eq!(read_group_counts.cycles()[1].mismatches(), 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    read_groups: BTreeMap<Option<String>, ReadGroupCounts>,
+    skipped_records: u64,
+}
+
+impl Report {
+    /// Adds a record to the report.
+    ///
+    /// Unmapped, secondary, and supplementary records are skipped. Mismatches are counted from the
+    /// mismatched positions (`MD`) or, if missing, derived from the edit distance (`NM`). Records
+    /// without either data field are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, error_rate::Report};
+    ///
+    /// let mut report = Report::default();
+    /// report.add(&sam::Record::default())?;
+    ///
+    /// assert_eq!(report.skipped_records(), 1);
+    /// # Ok::<(), sam::error_rate::AddError>(())
+    /// ```
+    pub fn add(&mut self, record: &Record) -> Result<(), AddError> {
+        let flags = record.flags();
+
+        if flags.is_unmapped() || flags.is_secondary() || flags.is_supplementary() {
+            self.skipped_records += 1;
+            return Ok(());
+        }
+
+        let data = record.data();
+
+        let mismatched_positions = data
+            .get_typed::<&str>(&Tag::MismatchedPositions)
+            .map_err(AddError::InvalidField)?;
+
+        let edit_distance = data
+            .get_typed::<i32>(&Tag::EditDistance)
+            .map_err(AddError::InvalidField)?;
+
+        let read_group = data
+            .get_typed::<&str>(&Tag::ReadGroup)
+            .map_err(AddError::InvalidField)?;
+
+        let (counts, cycles) = match (mismatched_positions, edit_distance) {
+            (Some(md), _) => {
+                let ops =
+                    parse_mismatched_positions(md).ok_or(AddError::InvalidMismatchedPositions)?;
+                count_with_mismatched_positions(record, &ops)?
+            }
+            (None, Some(nm)) => (count_with_edit_distance(record, nm)?, Vec::new()),
+            (None, None) => {
+                self.skipped_records += 1;
+                return Ok(());
+            }
+        };
+
+        let read_group_counts = self
+            .read_groups
+            .entry(read_group.map(String::from))
+            .or_default();
+
+        read_group_counts.total.merge(&counts);
+
+        if read_group_counts.cycles.len() < cycles.len() {
+            read_group_counts
+                .cycles
+                .resize_with(cycles.len(), Counts::default);
+        }
+
+        for (total, cycle) in read_group_counts.cycles.iter_mut().zip(&cycles) {
+            total.merge(cycle);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the error counts per read group.
+    ///
+    /// Records without a read group (`RG`) are grouped under `None`.
+    pub fn read_groups(&self) -> &BTreeMap<Option<String>, ReadGroupCounts> {
+        &self.read_groups
+    }
+
+    /// Returns the error counts of all read groups.
+    pub fn total(&self) -> Counts {
+        let mut total = Counts::default();
+
+        for read_group_counts in self.read_groups.values() {
+            total.merge(&read_group_counts.total);
+        }
+
+        total
+    }
+
+    /// Returns the number of records that were skipped.
+    pub fn skipped_records(&self) -> u64 {
+        self.skipped_records
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ReferenceBase {
+    Match,
+    Mismatch,
+    Deleted,
+}
+
+// Expands mismatched positions (`MD`) to one entry per reference base.
+fn parse_mismatched_positions(s: &str) -> Option<Vec<ReferenceBase>> {
+    let mut bases = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    loop {
+        let mut len = 0usize;
+
+        while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+            len = len.checked_mul(10)?.checked_add(d as usize)?;
+            chars.next();
+        }
+
+        bases.resize(bases.len() + len, ReferenceBase::Match);
+
+        match chars.next() {
+            Some('^') => {
+                let mut is_empty = true;
+
+                while chars
+                    .peek()
+                    .map(|c| c.is_ascii_alphabetic())
+                    .unwrap_or(false)
+                {
+                    bases.push(ReferenceBase::Deleted);
+                    chars.next();
+                    is_empty = false;
+                }
+
+                if is_empty {
+                    return None;
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() => bases.push(ReferenceBase::Mismatch),
+            Some(_) => return None,
+            None => break,
+        }
+    }
+
+    Some(bases)
+}
+
+fn count_with_mismatched_positions(
+    record: &Record,
+    reference_bases: &[ReferenceBase],
+) -> Result<(Counts, Vec<Counts>), AddError> {
+    let read_len = read_len(record);
+    let is_reverse = record.flags().is_reverse_complemented();
+
+    let cycle = |i: usize| {
+        if is_reverse {
+            read_len - 1 - i
+        } else {
+            i
+        }
+    };
+
+    let mut cycles = vec![Counts::default(); read_len];
+    let mut reference_bases = reference_bases.iter();
+    let mut i = 0;
+
+    for op in record.cigar().iter() {
+        let len = op.len() as usize;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                for _ in 0..len {
+                    let counts = &mut cycles[cycle(i)];
+
+                    match reference_bases.next() {
+                        Some(ReferenceBase::Match) => {}
+                        Some(ReferenceBase::Mismatch) => counts.mismatches += 1,
+                        _ => return Err(AddError::InvalidMismatchedPositions),
+                    }
+
+                    counts.aligned_bases += 1;
+                    i += 1;
+                }
+            }
+            Kind::Insertion => {
+                let counts = &mut cycles[cycle(i)];
+                counts.insertions += 1;
+                counts.inserted_bases += len as u64;
+                i += len;
+            }
+            Kind::Deletion => {
+                for _ in 0..len {
+                    if reference_bases.next() != Some(&ReferenceBase::Deleted) {
+                        return Err(AddError::InvalidMismatchedPositions);
+                    }
+                }
+
+                // Deletions are attributed to the cycle of the preceding base.
+                if read_len > 0 {
+                    let counts = &mut cycles[cycle(i.saturating_sub(1))];
+                    counts.deletions += 1;
+                    counts.deleted_bases += len as u64;
+                }
+            }
+            Kind::SoftClip => i += len,
+            Kind::Skip | Kind::HardClip | Kind::Pad => {}
+        }
+    }
+
+    if reference_bases.next().is_some() {
+        return Err(AddError::InvalidMismatchedPositions);
+    }
+
+    let mut total = Counts::default();
+
+    for counts in &cycles {
+        total.merge(counts);
+    }
+
+    Ok((total, cycles))
+}
+
+fn count_with_edit_distance(record: &Record, edit_distance: i32) -> Result<Counts, AddError> {
+    let mut counts = Counts::default();
+
+    for op in record.cigar().iter() {
+        let len = u64::from(op.len());
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => counts.aligned_bases += len,
+            Kind::Insertion => {
+                counts.insertions += 1;
+                counts.inserted_bases += len;
+            }
+            Kind::Deletion => {
+                counts.deletions += 1;
+                counts.deleted_bases += len;
+            }
+            _ => {}
+        }
+    }
+
+    let indel_bases = counts.inserted_bases + counts.deleted_bases;
+
+    counts.mismatches = u64::try_from(edit_distance)
+        .ok()
+        .and_then(|nm| nm.checked_sub(indel_bases))
+        .ok_or(AddError::InvalidEditDistance)?;
+
+    Ok(counts)
+}
+
+// Returns the number of bases in the read, as described by the CIGAR.
+fn read_len(record: &Record) -> usize {
+    record
+        .cigar()
+        .iter()
+        .filter(|op| {
+            matches!(
+                op.kind(),
+                Kind::Match | Kind::Insertion | Kind::SoftClip | Kind::SeqMatch | Kind::SeqMismatch
+            )
+        })
+        .map(|op| op.len() as usize)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_record(flags: Flags, cigar: &str, data: &str) -> Record {
+        Record::builder()
+            .set_flags(flags)
+            .set_position(Position::try_from(1).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_data(data.parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_parse_mismatched_positions() {
+        use ReferenceBase::{Deleted, Match, Mismatch};
+
+        assert_eq!(parse_mismatched_positions("3"), Some(vec![Match; 3]));
+        assert_eq!(
+            parse_mismatched_positions("1A0^CG1"),
+            Some(vec![Match, Mismatch, Deleted, Deleted, Match])
+        );
+        assert_eq!(parse_mismatched_positions(""), Some(Vec::new()));
+
+        assert!(parse_mismatched_positions("1^2").is_none());
+        assert!(parse_mismatched_positions("1*2").is_none());
+    }
+
+    #[test]
+    fn test_add() -> Result<(), AddError> {
+        let mut report = Report::default();
+
+        // cycles: 1=S, 2=M, 3=X, 4=M, 5=I, 6=M (deletion), 7=M
+        report.add(&build_record(
+            Flags::empty(),
+            "1S3M1I1M2D1M",
+            "RG:Z:rg0\tMD:Z:1A2^TT1",
+        ))?;
+
+        // reverse strand: cycles 1..=4 map to stored indices 3..=0
+        report.add(&build_record(
+            Flags::REVERSE_COMPLEMENTED,
+            "4M",
+            "RG:Z:rg0\tMD:Z:0C3",
+        ))?;
+
+        report.add(&build_record(Flags::empty(), "4M1D", "NM:i:2"))?;
+
+        report.add(&build_record(Flags::SECONDARY, "4M", "MD:Z:4"))?;
+        report.add(&build_record(Flags::empty(), "4M", ""))?;
+
+        assert_eq!(report.skipped_records(), 2);
+
+        let rg0 = &report.read_groups()[&Some(String::from("rg0"))];
+
+        let total = rg0.total();
+        assert_eq!(total.aligned_bases(), 9);
+        assert_eq!(total.mismatches(), 2);
+        assert_eq!(total.insertions(), 1);
+        assert_eq!(total.inserted_bases(), 1);
+        assert_eq!(total.deletions(), 1);
+        assert_eq!(total.deleted_bases(), 2);
+
+        let cycles = rg0.cycles();
+        assert_eq!(cycles.len(), 7);
+        assert_eq!(cycles[2].mismatches(), 1);
+        assert_eq!(cycles[3].mismatches(), 1);
+        assert_eq!(cycles[4].insertions(), 1);
+        assert_eq!(cycles[5].deletions(), 1);
+
+        let none = &report.read_groups()[&None];
+        assert_eq!(none.total().mismatches(), 1);
+        assert_eq!(none.total().deleted_bases(), 1);
+        assert!(none.cycles().is_empty());
+
+        assert_eq!(report.total().aligned_bases(), 13);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_invalid_fields() {
+        let mut report = Report::default();
+
+        assert_eq!(
+            report.add(&build_record(Flags::empty(), "4M", "MD:Z:3")),
+            Err(AddError::InvalidMismatchedPositions)
+        );
+        assert_eq!(
+            report.add(&build_record(Flags::empty(), "4M1D", "MD:Z:5")),
+            Err(AddError::InvalidMismatchedPositions)
+        );
+        assert_eq!(
+            report.add(&build_record(Flags::empty(), "4M2I", "NM:i:1")),
+            Err(AddError::InvalidEditDistance)
+        );
+    }
+}
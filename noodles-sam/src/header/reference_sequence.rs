@@ -1,5 +1,6 @@
 //! SAM header reference sequence and fields.
 
+mod alternative_locus;
 mod builder;
 mod md5_checksum;
 mod molecule_topology;
@@ -8,7 +9,8 @@ mod tag;
 use std::{collections::HashMap, convert::TryFrom, error, fmt, num};
 
 pub use self::{
-    builder::Builder, md5_checksum::Md5Checksum, molecule_topology::MoleculeTopology, tag::Tag,
+    alternative_locus::AlternativeLocus, builder::Builder, md5_checksum::Md5Checksum,
+    molecule_topology::MoleculeTopology, tag::Tag,
 };
 
 use super::{record, Record};
@@ -23,7 +25,7 @@ use super::{record, Record};
 pub struct ReferenceSequence {
     name: String,
     len: i32,
-    alternative_locus: Option<String>,
+    alternative_locus: Option<AlternativeLocus>,
     alternative_names: Option<String>,
     assemby_id: Option<String>,
     description: Option<String>,
@@ -145,8 +147,8 @@ impl ReferenceSequence {
     /// let mut reference_sequence = ReferenceSequence::new(String::from("sq0"), 13);
     /// assert!(reference_sequence.alternative_locus().is_none());
     /// ```
-    pub fn alternative_locus(&self) -> Option<&str> {
-        self.alternative_locus.as_deref()
+    pub fn alternative_locus(&self) -> Option<&AlternativeLocus> {
+        self.alternative_locus.as_ref()
     }
 
     /// Returns the alternative names (aliases) of the reference sequence.
@@ -332,6 +334,8 @@ pub enum TryFromRecordError {
     InvalidTag(tag::ParseError),
     /// The length tag (`LN`) has a invalid value.
     InvalidLength(num::ParseIntError),
+    /// The alternative locus is invalid.
+    InvalidAlternativeLocus(alternative_locus::ParseError),
     /// The MD5 checksum is invalid.
     InvalidMd5Checksum(md5_checksum::ParseError),
     /// The molecule topology is invalid.
@@ -347,6 +351,7 @@ impl fmt::Display for TryFromRecordError {
             Self::MissingRequiredTag(tag) => write!(f, "missing required tag: {:?}", tag),
             Self::InvalidTag(e) => write!(f, "{}", e),
             Self::InvalidLength(e) => write!(f, "invalid reference sequence length: {}", e),
+            Self::InvalidAlternativeLocus(e) => write!(f, "invalid alternative locus: {}", e),
             Self::InvalidMd5Checksum(e) => write!(f, "invalid MD5 checksum: {}", e),
             Self::InvalidMoleculeTopology(e) => write!(f, "invalid molecule topology: {}", e),
         }
@@ -385,7 +390,12 @@ fn parse_map(raw_fields: Vec<(String, String)>) -> Result<ReferenceSequence, Try
                     .map_err(TryFromRecordError::InvalidLength)?;
                 builder
             }
-            Tag::AlternativeLocus => builder.set_alternative_locus(value),
+            Tag::AlternativeLocus => {
+                let alternative_locus = value
+                    .parse()
+                    .map_err(TryFromRecordError::InvalidAlternativeLocus)?;
+                builder.set_alternative_locus(alternative_locus)
+            }
             Tag::AlternativeNames => builder.set_alternative_names(value),
             Tag::AssemblyId => builder.set_assembly_id(value),
             Tag::Description => builder.set_description(value),
@@ -522,4 +532,39 @@ mod tests {
             Err(TryFromRecordError::InvalidLength(_))
         ));
     }
+
+    #[test]
+    fn test_try_from_record_for_reference_sequence_with_alternative_locus() {
+        let record = Record::new(
+            record::Kind::ReferenceSequence,
+            record::Value::Map(vec![
+                (String::from("SN"), String::from("sq0_alt")),
+                (String::from("LN"), String::from("13")),
+                (String::from("AH"), String::from("sq0:8-20")),
+            ]),
+        );
+
+        let reference_sequence = ReferenceSequence::try_from(record).unwrap();
+
+        assert_eq!(
+            reference_sequence.alternative_locus(),
+            Some(&AlternativeLocus::Region(String::from("sq0"), 8, 20))
+        );
+
+        let record = Record::new(
+            record::Kind::ReferenceSequence,
+            record::Value::Map(vec![
+                (String::from("SN"), String::from("sq0_alt")),
+                (String::from("LN"), String::from("13")),
+                (String::from("AH"), String::from("sq0:20-8")),
+            ]),
+        );
+
+        assert_eq!(
+            ReferenceSequence::try_from(record),
+            Err(TryFromRecordError::InvalidAlternativeLocus(
+                alternative_locus::ParseError::InvalidInterval(20, 8)
+            ))
+        );
+    }
 }
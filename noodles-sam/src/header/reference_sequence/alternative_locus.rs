@@ -0,0 +1,177 @@
+use std::{error, fmt, num, str::FromStr};
+
+const UNKNOWN: &str = "*";
+
+/// A SAM header reference sequence alternative locus (`AH`).
+///
+/// This marks the reference sequence as an alternate locus of a region in the primary assembly,
+/// e.g., an ALT contig in GRCh38.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AlternativeLocus {
+    /// The primary locus is unknown (`*`).
+    Unknown,
+    /// A reference sequence name.
+    ///
+    /// A name of `=` refers to the reference sequence itself.
+    Name(String),
+    /// A region of a reference sequence: the name and 1-based start and end positions
+    /// (inclusive).
+    Region(String, i64, i64),
+}
+
+impl AlternativeLocus {
+    /// Returns the name of the primary reference sequence, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::header::reference_sequence::AlternativeLocus;
+    ///
+    /// let alternative_locus = AlternativeLocus::Region(String::from("sq0"), 8, 13);
+    /// assert_eq!(alternative_locus.reference_sequence_name(), Some("sq0"));
+    ///
+    /// assert!(AlternativeLocus::Unknown.reference_sequence_name().is_none());
+    /// ```
+    pub fn reference_sequence_name(&self) -> Option<&str> {
+        match self {
+            Self::Unknown => None,
+            Self::Name(name) | Self::Region(name, _, _) => Some(name),
+        }
+    }
+}
+
+impl fmt::Display for AlternativeLocus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => f.write_str(UNKNOWN),
+            Self::Name(name) => f.write_str(name),
+            Self::Region(name, start, end) => write!(f, "{}:{}-{}", name, start, end),
+        }
+    }
+}
+
+/// An error returned when a raw SAM header reference sequence alternative locus fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The region start or end position is invalid.
+    InvalidPosition(num::ParseIntError),
+    /// The region interval is invalid, i.e., the start is less than 1 or greater than the end.
+    InvalidInterval(i64, i64),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidPosition(e) => write!(f, "invalid position: {}", e),
+            Self::InvalidInterval(start, end) => write!(f, "invalid interval: {}-{}", start, end),
+        }
+    }
+}
+
+impl FromStr for AlternativeLocus {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        } else if s == UNKNOWN {
+            return Ok(Self::Unknown);
+        }
+
+        // Names may contain colons (e.g., `HLA-A*01:01:01:01`), so only a suffix that looks like
+        // an interval is parsed as one.
+        let (name, raw_interval) = match s.rfind(':') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => return Ok(Self::Name(s.into())),
+        };
+
+        let is_interval = !name.is_empty()
+            && raw_interval
+                .split('-')
+                .map(|t| !t.is_empty() && t.bytes().all(|b| b.is_ascii_digit()))
+                .eq([true, true].iter().copied());
+
+        if !is_interval {
+            return Ok(Self::Name(s.into()));
+        }
+
+        let mut positions = raw_interval.split('-');
+
+        let start: i64 = positions
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .map_err(ParseError::InvalidPosition)?;
+
+        let end: i64 = positions
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .map_err(ParseError::InvalidPosition)?;
+
+        if start < 1 || start > end {
+            return Err(ParseError::InvalidInterval(start, end));
+        }
+
+        Ok(Self::Region(name.into(), start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(AlternativeLocus::Unknown.to_string(), "*");
+        assert_eq!(
+            AlternativeLocus::Name(String::from("sq0")).to_string(),
+            "sq0"
+        );
+        assert_eq!(
+            AlternativeLocus::Region(String::from("sq0"), 8, 13).to_string(),
+            "sq0:8-13"
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("*".parse(), Ok(AlternativeLocus::Unknown));
+        assert_eq!("=".parse(), Ok(AlternativeLocus::Name(String::from("="))));
+        assert_eq!(
+            "sq0".parse(),
+            Ok(AlternativeLocus::Name(String::from("sq0")))
+        );
+        assert_eq!(
+            "sq0:8-13".parse(),
+            Ok(AlternativeLocus::Region(String::from("sq0"), 8, 13))
+        );
+        assert_eq!(
+            "=:8-13".parse(),
+            Ok(AlternativeLocus::Region(String::from("="), 8, 13))
+        );
+        assert_eq!(
+            "HLA-A*01:01:01:01".parse(),
+            Ok(AlternativeLocus::Name(String::from("HLA-A*01:01:01:01")))
+        );
+
+        assert_eq!("".parse::<AlternativeLocus>(), Err(ParseError::Empty));
+        assert_eq!(
+            "sq0:13-8".parse::<AlternativeLocus>(),
+            Err(ParseError::InvalidInterval(13, 8))
+        );
+        assert_eq!(
+            "sq0:0-8".parse::<AlternativeLocus>(),
+            Err(ParseError::InvalidInterval(0, 8))
+        );
+        assert!(matches!(
+            "sq0:1-99999999999999999999".parse::<AlternativeLocus>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+    }
+}
@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use super::{Md5Checksum, MoleculeTopology, ReferenceSequence, Tag};
+use super::{AlternativeLocus, Md5Checksum, MoleculeTopology, ReferenceSequence, Tag};
 
 /// A SAM header reference sequence builder.
 #[derive(Debug, Default)]
 pub struct Builder {
     name: Option<String>,
     len: Option<i32>,
-    alternative_locus: Option<String>,
+    alternative_locus: Option<AlternativeLocus>,
     alternative_names: Option<String>,
     assemby_id: Option<String>,
     description: Option<String>,
@@ -65,21 +65,21 @@ impl Builder {
     /// # Examples
     ///
     /// ```
-    /// use noodles_sam::header::ReferenceSequence;
+    /// use noodles_sam::header::{reference_sequence::AlternativeLocus, ReferenceSequence};
     ///
     /// let reference_sequence = ReferenceSequence::builder()
     ///     .set_name("sq0")
     ///     .set_length(13)
-    ///     .set_alternative_locus("sq0_alt")
+    ///     .set_alternative_locus(AlternativeLocus::Unknown)
     ///     .build();
     ///
-    /// assert_eq!(reference_sequence.alternative_locus(), Some("sq0_alt"));
+    /// assert_eq!(
+    ///     reference_sequence.alternative_locus(),
+    ///     Some(&AlternativeLocus::Unknown)
+    /// );
     /// ```
-    pub fn set_alternative_locus<I>(mut self, alternative_locus: I) -> Self
-    where
-        I: Into<String>,
-    {
-        self.alternative_locus = Some(alternative_locus.into());
+    pub fn set_alternative_locus(mut self, alternative_locus: AlternativeLocus) -> Self {
+        self.alternative_locus = Some(alternative_locus);
         self
     }
 
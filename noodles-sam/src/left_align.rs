@@ -0,0 +1,275 @@
+//! Left alignment of indels in records.
+//!
+//! An indel in a repeat can be placed at multiple equivalent positions. Variant normalization
+//! places it at the leftmost one, so left aligning the indels of records makes alignments
+//! consistent with normalized variants.
+
+use super::{
+    record::{
+        cigar::{op::Kind, Op},
+        Cigar,
+    },
+    Record,
+};
+
+/// Left aligns the insertions and deletions in the CIGAR of a record.
+///
+/// `reference_sequence` is the complete sequence of the reference sequence the record is aligned
+/// to. Each indel is shifted left while the sequence it moves over is the same, i.e., through
+/// repeats, and while at least one aligned base remains before it. Only indels between alignment
+/// matches (`M`) are shifted. Adjacent operations of the same kind are merged.
+///
+/// The alignment start, sequence, and quality scores are unchanged.
+///
+/// This returns `None` if the record is unmapped or has no position.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam,
+///     left_align::left_align,
+///     record::{Flags, Position},
+/// };
+///
+/// // A deletion of one `CA` unit in `CACACA`.
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_position(Position::try_from(1)?)
+///     .set_cigar("6M2D2M".parse()?)
+///     .set_sequence("GTCACAGT".parse()?)
+///     .build();
+///
+/// let aligned_record = left_align(&record, b"GTCACACAGT").unwrap();
+/// assert_eq!(aligned_record.cigar().to_string(), "2M2D6M");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn left_align(record: &Record, reference_sequence: &[u8]) -> Option<Record> {
+    if record.flags().is_unmapped() {
+        return None;
+    }
+
+    let start = i64::from(record.position()?) - 1;
+
+    let sequence: Vec<u8> = record
+        .sequence()
+        .iter()
+        .map(|&base| char::from(base) as u8)
+        .collect();
+
+    let cigar = left_align_cigar(record.cigar(), start, &sequence, reference_sequence);
+
+    let mut aligned_record = record.clone();
+    *aligned_record.cigar_mut() = cigar;
+
+    Some(aligned_record)
+}
+
+// Left aligns the indels of a CIGAR of an alignment starting at the 0-based reference position
+// `start`.
+fn left_align_cigar(
+    cigar: &Cigar,
+    start: i64,
+    sequence: &[u8],
+    reference_sequence: &[u8],
+) -> Cigar {
+    let mut ops: Vec<(Kind, u32)> = cigar.iter().map(|op| (op.kind(), op.len())).collect();
+
+    let mut reference_position = start;
+    let mut query_position = 0;
+    let mut j = 0;
+
+    while j < ops.len() {
+        let (kind, len) = ops[j];
+
+        if matches!(kind, Kind::Insertion | Kind::Deletion)
+            && j > 0
+            && ops[j - 1].0 == Kind::Match
+            && ops.get(j + 1).map(|op| op.0) == Some(Kind::Match)
+        {
+            let shift = shift_len(
+                kind,
+                len,
+                ops[j - 1].1,
+                reference_position,
+                query_position,
+                sequence,
+                reference_sequence,
+            );
+
+            ops[j - 1].1 -= shift;
+            ops[j + 1].1 += shift;
+            reference_position -= i64::from(shift);
+            query_position -= shift as usize;
+        }
+
+        match kind {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                reference_position += i64::from(len);
+                query_position += len as usize;
+            }
+            Kind::Insertion | Kind::SoftClip => query_position += len as usize,
+            Kind::Deletion | Kind::Skip => reference_position += i64::from(len),
+            Kind::HardClip | Kind::Pad => {}
+        }
+
+        j += 1;
+    }
+
+    let mut merged_ops: Vec<Op> = Vec::with_capacity(ops.len());
+
+    for (kind, len) in ops {
+        if len == 0 {
+            continue;
+        }
+
+        match merged_ops.last_mut() {
+            Some(last) if last.kind() == kind => *last = Op::new(kind, last.len() + len),
+            _ => merged_ops.push(Op::new(kind, len)),
+        }
+    }
+
+    Cigar::from(merged_ops)
+}
+
+// Returns the number of positions an indel can be shifted left.
+//
+// `reference_position` and `query_position` are the 0-based positions at the start of the indel,
+// and `preceding_len` is the length of the preceding match, of which at least one base is kept.
+fn shift_len(
+    kind: Kind,
+    len: u32,
+    preceding_len: u32,
+    reference_position: i64,
+    query_position: usize,
+    sequence: &[u8],
+    reference_sequence: &[u8],
+) -> u32 {
+    let len = len as usize;
+    let mut shift = 0;
+
+    while shift + 1 < preceding_len {
+        let is_shiftable = match kind {
+            Kind::Deletion => {
+                let i = reference_position - i64::from(shift);
+
+                if i < 1 {
+                    false
+                } else {
+                    let i = i as usize;
+                    is_same_base(
+                        reference_sequence.get(i - 1),
+                        reference_sequence.get(i + len - 1),
+                    )
+                }
+            }
+            Kind::Insertion => {
+                let i = query_position - shift as usize;
+
+                if i < 1 {
+                    false
+                } else {
+                    is_same_base(sequence.get(i - 1), sequence.get(i + len - 1))
+                }
+            }
+            _ => false,
+        };
+
+        if !is_shiftable {
+            break;
+        }
+
+        shift += 1;
+    }
+
+    shift
+}
+
+fn is_same_base(a: Option<&u8>, b: Option<&u8>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_record(position: i64, cigar: &str, sequence: &str) -> Record {
+        Record::builder()
+            .set_flags(Flags::empty())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_sequence(sequence.parse().unwrap())
+            .build()
+    }
+
+    fn left_align_to_string(record: &Record, reference_sequence: &[u8]) -> Option<String> {
+        left_align(record, reference_sequence).map(|r| r.cigar().to_string())
+    }
+
+    #[test]
+    fn test_left_align_with_deletion() {
+        // ref: ACGTTTTAC, read: ACGTTTAC
+        let record = build_record(1, "6M1D2M", "ACGTTTAC");
+        assert_eq!(
+            left_align_to_string(&record, b"ACGTTTTAC"),
+            Some(String::from("3M1D5M"))
+        );
+
+        // The deletion is already left aligned.
+        let record = build_record(1, "3M1D5M", "ACGTTTAC");
+        assert_eq!(
+            left_align_to_string(&record, b"ACGTTTTAC"),
+            Some(String::from("3M1D5M"))
+        );
+    }
+
+    #[test]
+    fn test_left_align_with_insertion() {
+        // ref: ACGAGAGT, read: ACGAGAGAGT
+        let record = build_record(1, "7M2I1M", "ACGAGAGAGT");
+        assert_eq!(
+            left_align_to_string(&record, b"ACGAGAGT"),
+            Some(String::from("2M2I6M"))
+        );
+    }
+
+    #[test]
+    fn test_left_align_keeps_one_aligned_base() {
+        // ref: AAAAC, read: AAAC
+        let record = build_record(1, "3M1D1M", "AAAC");
+        assert_eq!(
+            left_align_to_string(&record, b"AAAAC"),
+            Some(String::from("1M1D3M"))
+        );
+    }
+
+    #[test]
+    fn test_left_align_with_adjacent_indels() {
+        // The deletion keeps one aligned base after the soft clip, and the insertion shifts
+        // through the `AA` run.
+        let record = build_record(3, "2S2M1D2M1I2M", "GGTTTAAAC");
+        assert_eq!(
+            left_align_to_string(&record, b"CCTTTTAAC"),
+            Some(String::from("2S1M1D2M1I3M"))
+        );
+    }
+
+    #[test]
+    fn test_left_align_with_unmapped_record() {
+        let record = Record::builder()
+            .set_flags(Flags::UNMAPPED)
+            .set_position(Position::try_from(1).unwrap())
+            .build();
+        assert!(left_align(&record, b"ACGT").is_none());
+
+        assert!(left_align(&Record::default(), b"ACGT").is_none());
+    }
+}
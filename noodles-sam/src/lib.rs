@@ -17,7 +17,8 @@
 //!
 //! ## Read all records from a file
 //!
-//! ```no_run
+#![cfg_attr(feature = "io", doc = "```no_run")]
+#![cfg_attr(not(feature = "io"), doc = "```ignore")]
 //! # use std::{fs::File, io::{self, BufReader}};
 //! use noodles_sam as sam;
 //!
@@ -30,10 +31,51 @@
 //! }
 //! # Ok::<(), io::Error>(())
 //! ```
+//!
+//! ## Parse data from a byte slice
+//!
+//! Data that is already in memory can be parsed using the functions in [`slice`]. These are
+//! available when the default `io` feature is disabled, which removes the reader and writer, e.g.,
+//! for `wasm32-unknown-unknown` builds.
+//!
+//! ```
+//! use noodles_sam::slice;
+//!
+//! let data = b"@HD\tVN:1.6\nr0\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\n";
+//! let (header, records) = slice::parse(data)?;
+//!
+//! for result in records {
+//!     let record = result?;
+//!     println!("{:?}", record);
+//! }
+//! # Ok::<(), slice::ParseError>(())
+//! ```
 
+mod alignment_record;
+pub mod alt;
+pub mod amplicon;
+pub mod baq;
+pub mod bqsr;
+pub mod calmd;
+pub mod clip;
+pub mod consensus;
+pub mod downsample;
+pub mod error_rate;
 pub mod header;
+pub mod left_align;
+pub mod pileup;
+#[cfg(feature = "io")]
 pub mod reader;
 pub mod record;
+pub mod slice;
+pub mod stats;
+pub mod transform;
+pub mod umi;
+pub mod view;
+#[cfg(feature = "io")]
 mod writer;
 
-pub use self::{header::Header, reader::Reader, record::Record, writer::Writer};
+pub use self::{alignment_record::AlignmentRecord, header::Header, record::Record};
+
+#[cfg(feature = "io")]
+pub use self::{reader::Reader, writer::Writer};
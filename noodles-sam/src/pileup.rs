@@ -0,0 +1,656 @@
+//! Pileup of aligned bases.
+//!
+//! A pileup stacks the aligned bases of a coordinate-sorted stream of records by reference
+//! sequence position. Each [`Column`] holds the [`Alignment`]s of every record that covers its
+//! position, including deletions and reference skips.
+
+pub mod mpileup;
+
+use std::{
+    collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque},
+    io,
+};
+
+use crate::{
+    record::{cigar::op::Kind, sequence::Base, Flags, MappingQuality},
+    Record,
+};
+
+// samtools `tweak_overlap_quality`
+const MAX_MERGED_QUALITY: u8 = 200;
+
+/// A method of handling overlapping mate bases.
+///
+/// When the two segments of a template overlap, the same template base is observed twice at a
+/// position. Counting both overestimates the evidence for that base.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverlapMode {
+    /// Count both mate bases.
+    #[default]
+    None,
+    /// Count only the mate base with the higher base quality. On a tie, the base from the record
+    /// that comes first is kept.
+    Mask,
+    /// Count one mate base with an adjusted base quality, as in samtools mpileup.
+    ///
+    /// If the bases agree, the kept base quality is the sum of both, capped at 200. Otherwise,
+    /// the base with the higher quality is kept, and its quality is reduced to 80%.
+    Merge,
+}
+
+/// A record aligned at a pileup column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alignment {
+    read_name: Option<String>,
+    flags: Flags,
+    mapping_quality: MappingQuality,
+    query_position: Option<usize>,
+    base: Option<Base>,
+    quality: Option<u8>,
+    is_head: bool,
+    is_tail: bool,
+    is_reference_skip: bool,
+    insertion: Vec<Base>,
+    deletion_len: u32,
+}
+
+impl Alignment {
+    /// Returns the read name of the record.
+    pub fn read_name(&self) -> Option<&str> {
+        self.read_name.as_deref()
+    }
+
+    /// Returns the flags of the record.
+    pub fn flags(&self) -> Flags {
+        self.flags
+    }
+
+    /// Returns the mapping quality of the record.
+    pub fn mapping_quality(&self) -> MappingQuality {
+        self.mapping_quality
+    }
+
+    /// Returns the 0-based position of the base in the read.
+    ///
+    /// This is `None` if the column is a deletion or reference skip in the record.
+    pub fn query_position(&self) -> Option<usize> {
+        self.query_position
+    }
+
+    /// Returns the aligned base.
+    ///
+    /// This is `None` if the column is a deletion or reference skip in the record.
+    pub fn base(&self) -> Option<Base> {
+        self.base
+    }
+
+    /// Returns the quality of the aligned base.
+    ///
+    /// This is `None` if there is no aligned base or the record has no quality scores.
+    pub fn quality(&self) -> Option<u8> {
+        self.quality
+    }
+
+    /// Returns whether this is the first aligned position of the record.
+    pub fn is_head(&self) -> bool {
+        self.is_head
+    }
+
+    /// Returns whether this is the last aligned position of the record.
+    pub fn is_tail(&self) -> bool {
+        self.is_tail
+    }
+
+    /// Returns whether the column is a deletion in the record.
+    pub fn is_deletion(&self) -> bool {
+        self.base.is_none() && !self.is_reference_skip
+    }
+
+    /// Returns whether the column is a reference skip (`N`) in the record.
+    pub fn is_reference_skip(&self) -> bool {
+        self.is_reference_skip
+    }
+
+    /// Returns the bases inserted after this position.
+    pub fn insertion(&self) -> &[Base] {
+        &self.insertion
+    }
+
+    /// Returns the length of the deletion that starts after this position.
+    pub fn deletion_len(&self) -> u32 {
+        self.deletion_len
+    }
+}
+
+/// A pileup column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Column {
+    reference_sequence_name: String,
+    position: i64,
+    alignments: Vec<Alignment>,
+}
+
+impl Column {
+    /// Returns the reference sequence name of the column.
+    pub fn reference_sequence_name(&self) -> &str {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the position of the column.
+    ///
+    /// This is 1-based.
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns the records aligned at this column.
+    ///
+    /// Alignments are in record order.
+    pub fn alignments(&self) -> &[Alignment] {
+        &self.alignments
+    }
+
+    /// Returns the depth of the column.
+    ///
+    /// This is the number of alignments, including deletions but excluding reference skips.
+    pub fn depth(&self) -> usize {
+        self.alignments
+            .iter()
+            .filter(|alignment| !alignment.is_reference_skip())
+            .count()
+    }
+}
+
+/// A pileup engine.
+///
+/// By default, unmapped, secondary, QC-failed, and duplicate records are skipped, and all
+/// aligned bases are included.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, pileup::{Engine, OverlapMode}};
+///
+/// let data = b"@HD\tVN:1.6\tSO:coordinate
+/// r0\t99\tsq0\t1\t60\t4M\t=\t3\t6\tACGT\tIIII
+/// r0\t147\tsq0\t3\t60\t4M\t=\t1\t-6\tGTAC\tIIII
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// reader.read_header()?;
+///
+/// let engine = Engine::new().set_overlap_mode(OverlapMode::Mask);
+/// let columns = engine
+///     .pileup(reader.records())
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// let depths: Vec<_> = columns.iter().map(|column| column.depth()).collect();
+/// assert_eq!(depths, [1, 1, 1, 1, 1, 1]);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Engine {
+    min_mapping_quality: u8,
+    min_base_quality: u8,
+    overlap_mode: OverlapMode,
+}
+
+impl Engine {
+    /// Creates a pileup engine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum mapping quality of records to include.
+    ///
+    /// Records with a missing mapping quality are only included if this is 0.
+    pub fn set_min_mapping_quality(mut self, min_mapping_quality: u8) -> Self {
+        self.min_mapping_quality = min_mapping_quality;
+        self
+    }
+
+    /// Sets the minimum base quality of aligned bases to include.
+    ///
+    /// Deletions and reference skips are always included.
+    pub fn set_min_base_quality(mut self, min_base_quality: u8) -> Self {
+        self.min_base_quality = min_base_quality;
+        self
+    }
+
+    /// Sets the method of handling overlapping mate bases.
+    ///
+    /// Mates are detected by read name. Only aligned bases of paired records are considered.
+    pub fn set_overlap_mode(mut self, overlap_mode: OverlapMode) -> Self {
+        self.overlap_mode = overlap_mode;
+        self
+    }
+
+    /// Returns an iterator over pileup columns of a coordinate-sorted stream of records.
+    ///
+    /// Only columns with at least one alignment are returned. The iterator returns an error if
+    /// the records are not coordinate-sorted.
+    pub fn pileup<I>(&self, records: I) -> Columns<I::IntoIter>
+    where
+        I: IntoIterator<Item = io::Result<Record>>,
+    {
+        Columns {
+            records: records.into_iter(),
+            engine: self.clone(),
+            reference_sequence_name: None,
+            visited_reference_sequence_names: HashSet::new(),
+            last_start: 0,
+            pending: BTreeMap::new(),
+            columns: VecDeque::new(),
+            is_eof: false,
+        }
+    }
+}
+
+/// An iterator over pileup columns.
+///
+/// This is created by calling [`Engine::pileup`].
+pub struct Columns<I> {
+    records: I,
+    engine: Engine,
+    reference_sequence_name: Option<String>,
+    visited_reference_sequence_names: HashSet<String>,
+    last_start: i64,
+    pending: BTreeMap<i64, Vec<Alignment>>,
+    columns: VecDeque<Column>,
+    is_eof: bool,
+}
+
+impl<I> Columns<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    fn add_record(&mut self, record: &Record) -> io::Result<()> {
+        if self.is_filtered(record) {
+            return Ok(());
+        }
+
+        let (name, start) = match (record.reference_sequence_name(), record.position()) {
+            (Some(name), Some(position)) => (name, i64::from(position)),
+            _ => return Ok(()),
+        };
+
+        if self.reference_sequence_name.as_deref() != Some(name.as_str()) {
+            self.complete_columns_before(i64::MAX);
+
+            if !self
+                .visited_reference_sequence_names
+                .insert(name.to_string())
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("records are not coordinate-sorted: {}", name),
+                ));
+            }
+
+            self.reference_sequence_name = Some(name.to_string());
+        } else if start < self.last_start {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("records are not coordinate-sorted: {}:{}", name, start),
+            ));
+        }
+
+        self.last_start = start;
+        self.complete_columns_before(start);
+
+        for (position, alignment) in align(record, start) {
+            if let Some(quality) = alignment.quality {
+                if quality < self.engine.min_base_quality {
+                    continue;
+                }
+            }
+
+            self.pending.entry(position).or_default().push(alignment);
+        }
+
+        Ok(())
+    }
+
+    fn is_filtered(&self, record: &Record) -> bool {
+        let flags = record.flags();
+
+        if flags.is_unmapped() || flags.is_secondary() || flags.is_qc_fail() || flags.is_duplicate()
+        {
+            return true;
+        }
+
+        match *record.mapping_quality() {
+            Some(mapping_quality) => mapping_quality < self.engine.min_mapping_quality,
+            None => self.engine.min_mapping_quality > 0,
+        }
+    }
+
+    // Completes all columns before the given position.
+    fn complete_columns_before(&mut self, position: i64) {
+        let name = match self.reference_sequence_name.as_ref() {
+            Some(name) => name,
+            None => return,
+        };
+
+        while let Some(&next_position) = self.pending.keys().next() {
+            if next_position >= position {
+                break;
+            }
+
+            let mut alignments = self.pending.remove(&next_position).unwrap_or_default();
+            resolve_overlaps(&mut alignments, self.engine.overlap_mode);
+
+            self.columns.push_back(Column {
+                reference_sequence_name: name.clone(),
+                position: next_position,
+                alignments,
+            });
+        }
+    }
+}
+
+impl<I> Iterator for Columns<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Column>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(column) = self.columns.pop_front() {
+                return Some(Ok(column));
+            }
+
+            if self.is_eof {
+                return None;
+            }
+
+            match self.records.next() {
+                Some(Ok(record)) => {
+                    if let Err(e) = self.add_record(&record) {
+                        self.is_eof = true;
+                        self.pending.clear();
+                        return Some(Err(e));
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.complete_columns_before(i64::MAX);
+                    self.is_eof = true;
+                }
+            }
+        }
+    }
+}
+
+// Expands a record into its alignments at each reference sequence position it covers.
+fn align(record: &Record, start: i64) -> Vec<(i64, Alignment)> {
+    let flags = record.flags();
+    let sequence = record.sequence();
+    let quality_scores = record.quality_scores();
+
+    let new_alignment = || Alignment {
+        read_name: record.read_name().map(|name| name.to_string()),
+        flags,
+        mapping_quality: record.mapping_quality(),
+        query_position: None,
+        base: None,
+        quality: None,
+        is_head: false,
+        is_tail: false,
+        is_reference_skip: false,
+        insertion: Vec::new(),
+        deletion_len: 0,
+    };
+
+    let mut alignments: Vec<(i64, Alignment)> = Vec::new();
+    let mut reference_position = start;
+    let mut query_position = 0;
+
+    for op in record.cigar().iter() {
+        let len = op.len() as usize;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                for i in query_position..query_position + len {
+                    let mut alignment = new_alignment();
+                    alignment.query_position = Some(i);
+                    alignment.base = Some(sequence.get(i).copied().unwrap_or(Base::N));
+                    alignment.quality = quality_scores.get(i).map(|&score| u8::from(score));
+                    alignments.push((reference_position, alignment));
+                    reference_position += 1;
+                }
+
+                query_position += len;
+            }
+            Kind::Insertion => {
+                if let Some((_, last)) = alignments.last_mut() {
+                    let end = (query_position + len).min(sequence.len());
+                    let start = query_position.min(end);
+                    last.insertion.extend(sequence[start..end].iter().copied());
+                }
+
+                query_position += len;
+            }
+            Kind::Deletion | Kind::Skip => {
+                let is_reference_skip = op.kind() == Kind::Skip;
+
+                if !is_reference_skip {
+                    if let Some((_, last)) = alignments.last_mut() {
+                        last.deletion_len += op.len();
+                    }
+                }
+
+                for _ in 0..len {
+                    let mut alignment = new_alignment();
+                    alignment.is_reference_skip = is_reference_skip;
+                    alignments.push((reference_position, alignment));
+                    reference_position += 1;
+                }
+            }
+            Kind::SoftClip => query_position += len,
+            Kind::HardClip | Kind::Pad => {}
+        }
+    }
+
+    if let Some((_, first)) = alignments.first_mut() {
+        first.is_head = true;
+    }
+
+    if let Some((_, last)) = alignments.last_mut() {
+        last.is_tail = true;
+    }
+
+    alignments
+}
+
+fn resolve_overlaps(alignments: &mut Vec<Alignment>, mode: OverlapMode) {
+    if mode == OverlapMode::None {
+        return;
+    }
+
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    let mut is_removed = vec![false; alignments.len()];
+
+    for j in 0..alignments.len() {
+        let alignment = &alignments[j];
+
+        let read_name = match (alignment.read_name.as_ref(), alignment.base) {
+            (Some(read_name), Some(_)) if alignment.flags.is_paired() => read_name.clone(),
+            _ => continue,
+        };
+
+        let i = match indices.entry(read_name) {
+            hash_map::Entry::Occupied(entry) => *entry.get(),
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(j);
+                continue;
+            }
+        };
+
+        let q_i = alignments[i].quality.unwrap_or(0);
+        let q_j = alignments[j].quality.unwrap_or(0);
+        let (kept, removed) = if q_j > q_i { (j, i) } else { (i, j) };
+
+        if mode == OverlapMode::Merge {
+            let quality = if alignments[i].base == alignments[j].base {
+                q_i.saturating_add(q_j).min(MAX_MERGED_QUALITY)
+            } else {
+                (u16::from(q_i.max(q_j)) * 4 / 5) as u8
+            };
+
+            alignments[kept].quality = Some(quality);
+        }
+
+        is_removed[removed] = true;
+    }
+
+    let mut is_removed = is_removed.into_iter();
+    alignments.retain(|_| !is_removed.next().unwrap_or(false));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::Position;
+
+    use super::*;
+
+    fn build_record(
+        read_name: &str,
+        flags: Flags,
+        position: i64,
+        cigar: &str,
+        sequence: &str,
+        quality_scores: &str,
+    ) -> io::Result<Record> {
+        Ok(Record::builder()
+            .set_read_name(read_name.parse().unwrap())
+            .set_flags(flags)
+            .set_reference_sequence_name("sq0".parse().unwrap())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .set_sequence(sequence.parse().unwrap())
+            .set_quality_scores(quality_scores.parse().unwrap())
+            .build())
+    }
+
+    #[test]
+    fn test_align() -> io::Result<()> {
+        let record = build_record("r0", Flags::empty(), 8, "1S2M1I1M2D1M", "ACGTAC", "ABCDEF")?;
+        let alignments = align(&record, 8);
+
+        let positions: Vec<_> = alignments.iter().map(|(position, _)| *position).collect();
+        assert_eq!(positions, [8, 9, 10, 11, 12, 13]);
+
+        let (_, first) = &alignments[0];
+        assert!(first.is_head());
+        assert_eq!(first.base(), Some(Base::C));
+        assert_eq!(first.query_position(), Some(1));
+        assert_eq!(first.quality(), Some(33));
+
+        let (_, second) = &alignments[1];
+        assert_eq!(second.insertion(), [Base::T]);
+
+        let (_, third) = &alignments[2];
+        assert_eq!(third.base(), Some(Base::A));
+        assert_eq!(third.deletion_len(), 2);
+
+        let (_, fourth) = &alignments[3];
+        assert!(fourth.is_deletion());
+        assert!(fourth.base().is_none());
+
+        let (_, last) = &alignments[5];
+        assert!(last.is_tail());
+        assert_eq!(last.base(), Some(Base::C));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pileup() -> io::Result<()> {
+        let records = vec![
+            build_record("r0", Flags::empty(), 1, "2M", "AC", "II"),
+            build_record("r1", Flags::empty(), 2, "1M1N1M", "CT", "II"),
+            build_record("r2", Flags::DUPLICATE, 2, "2M", "CG", "II"),
+        ];
+
+        let columns: Vec<_> = Engine::new().pileup(records).collect::<io::Result<_>>()?;
+
+        let actual: Vec<_> = columns
+            .iter()
+            .map(|column| (column.position(), column.alignments().len(), column.depth()))
+            .collect();
+
+        assert_eq!(actual, [(1, 1, 1), (2, 2, 2), (3, 1, 0), (4, 1, 1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pileup_with_min_base_quality() -> io::Result<()> {
+        let records = vec![build_record("r0", Flags::empty(), 1, "2M", "AC", "I#")?];
+
+        let columns: Vec<_> = Engine::new()
+            .set_min_base_quality(10)
+            .pileup(records.into_iter().map(Ok))
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(columns.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pileup_with_unsorted_records() {
+        let records = vec![
+            build_record("r0", Flags::empty(), 5, "1M", "A", "I"),
+            build_record("r1", Flags::empty(), 1, "1M", "A", "I"),
+        ];
+
+        let result: io::Result<Vec<_>> = Engine::new().pileup(records).collect();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_pileup_with_overlap_mode() -> io::Result<()> {
+        let flags = Flags::PAIRED;
+
+        let build_records = || {
+            vec![
+                build_record("r0", flags | Flags::READ_1, 1, "2M", "AC", "5I"),
+                build_record("r0", flags | Flags::READ_2, 2, "2M", "GT", "+I"),
+                build_record("r1", flags | Flags::READ_1, 2, "1M", "C", "I"),
+            ]
+        };
+
+        let columns: Vec<_> = Engine::new()
+            .pileup(build_records())
+            .collect::<io::Result<_>>()?;
+        assert_eq!(columns[1].depth(), 3);
+
+        let columns: Vec<_> = Engine::new()
+            .set_overlap_mode(OverlapMode::Mask)
+            .pileup(build_records())
+            .collect::<io::Result<_>>()?;
+        let alignments = columns[1].alignments();
+        assert_eq!(alignments.len(), 2);
+        assert_eq!(alignments[0].base(), Some(Base::C));
+        assert_eq!(alignments[0].quality(), Some(40));
+        assert_eq!(alignments[1].read_name(), Some("r1"));
+
+        let columns: Vec<_> = Engine::new()
+            .set_overlap_mode(OverlapMode::Merge)
+            .pileup(build_records())
+            .collect::<io::Result<_>>()?;
+        let alignments = columns[1].alignments();
+        assert_eq!(alignments.len(), 2);
+        assert_eq!(alignments[0].base(), Some(Base::C));
+        assert_eq!(alignments[0].quality(), Some(32));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+//! samtools mpileup text format.
+//!
+//! Each pileup column is written as a line with six tab-separated fields: 1) reference sequence
+//! name, 2) position (1-based), 3) reference base, 4) depth, 5) read bases, and 6) base
+//! qualities.
+//!
+//! In the read bases, a match to the reference base is `.` on the forward strand and `,` on the
+//! reverse strand. Mismatches are uppercase on the forward strand and lowercase on the reverse
+//! strand. The start of a read is marked with `^` followed by its mapping quality (+33), and the
+//! end with `$`. Deletions are `*`, and reference skips are `>` or `<`. An insertion or deletion
+//! that follows a base is written as `+`/`-`, its length, and its bases.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use super::{Alignment, Column};
+
+const MISSING_REFERENCE_BASE: u8 = b'N';
+const MAX_QUALITY_CHAR: u8 = b'~';
+
+/// A provider of reference sequence bases.
+///
+/// This is implemented for closures `Fn(&str, i64) -> Option<u8>` and for maps of reference
+/// sequence names to sequences.
+pub trait Reference {
+    /// Returns the base at the given 1-based position of a reference sequence.
+    fn base(&self, reference_sequence_name: &str, position: i64) -> Option<u8>;
+}
+
+impl<F> Reference for F
+where
+    F: Fn(&str, i64) -> Option<u8>,
+{
+    fn base(&self, reference_sequence_name: &str, position: i64) -> Option<u8> {
+        self(reference_sequence_name, position)
+    }
+}
+
+impl Reference for HashMap<String, Vec<u8>> {
+    fn base(&self, reference_sequence_name: &str, position: i64) -> Option<u8> {
+        if position < 1 {
+            return None;
+        }
+
+        self.get(reference_sequence_name)
+            .and_then(|sequence| sequence.get((position - 1) as usize))
+            .copied()
+    }
+}
+
+/// An mpileup writer.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use std::collections::HashMap;
+/// use noodles_sam::{self as sam, pileup::{mpileup, Engine}};
+///
+/// let data = b"@HD\tVN:1.6\tSO:coordinate
+/// r0\t0\tsq0\t1\t60\t2M\t*\t0\t0\tAT\tIH
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// reader.read_header()?;
+///
+/// let mut reference = HashMap::new();
+/// reference.insert(String::from("sq0"), b"AC".to_vec());
+///
+/// let mut writer = mpileup::Writer::new(Vec::new(), reference);
+///
+/// for result in Engine::new().pileup(reader.records()) {
+///     let column = result?;
+///     writer.write_column(&column)?;
+/// }
+///
+/// let expected = b"sq0\t1\tA\t1\t^].\tI
+/// sq0\t2\tC\t1\tT$\tH
+/// ";
+///
+/// assert_eq!(&writer.get_ref()[..], &expected[..]);
+/// # Ok::<_, io::Error>(())
+/// ```
+pub struct Writer<W, R> {
+    inner: W,
+    reference: R,
+}
+
+impl<W, R> Writer<W, R>
+where
+    W: Write,
+    R: Reference,
+{
+    /// Creates an mpileup writer.
+    ///
+    /// Positions without a reference base are written as `N`.
+    pub fn new(inner: W, reference: R) -> Self {
+        Self { inner, reference }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes a pileup column.
+    pub fn write_column(&mut self, column: &Column) -> io::Result<()> {
+        let name = column.reference_sequence_name();
+        let position = column.position();
+
+        let reference_base = self
+            .reference
+            .base(name, position)
+            .unwrap_or(MISSING_REFERENCE_BASE);
+
+        let mut bases = Vec::new();
+        let mut qualities = Vec::new();
+
+        for alignment in column.alignments() {
+            self.push_bases(&mut bases, name, position, reference_base, alignment);
+            qualities.push(quality_char(alignment.quality()));
+        }
+
+        if bases.is_empty() {
+            bases.push(b'*');
+            qualities.push(b'*');
+        }
+
+        write!(
+            self.inner,
+            "{}\t{}\t{}\t{}\t",
+            name,
+            position,
+            char::from(reference_base),
+            column.alignments().len()
+        )?;
+
+        self.inner.write_all(&bases)?;
+        self.inner.write_all(b"\t")?;
+        self.inner.write_all(&qualities)?;
+        self.inner.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    fn push_bases(
+        &self,
+        buf: &mut Vec<u8>,
+        name: &str,
+        position: i64,
+        reference_base: u8,
+        alignment: &Alignment,
+    ) {
+        let is_reverse = alignment.flags().is_reverse_complemented();
+
+        if alignment.is_head() {
+            buf.push(b'^');
+            let mapping_quality = u8::from(alignment.mapping_quality());
+            buf.push(quality_char(Some(mapping_quality)));
+        }
+
+        match alignment.base() {
+            Some(base) => {
+                let base = char::from(base) as u8;
+
+                if reference_base != MISSING_REFERENCE_BASE
+                    && base.eq_ignore_ascii_case(&reference_base)
+                {
+                    buf.push(if is_reverse { b',' } else { b'.' });
+                } else {
+                    buf.push(strand_case(base, is_reverse));
+                }
+            }
+            None if alignment.is_reference_skip() => buf.push(if is_reverse { b'<' } else { b'>' }),
+            None => buf.push(b'*'),
+        }
+
+        let insertion = alignment.insertion();
+
+        if !insertion.is_empty() {
+            buf.push(b'+');
+            buf.extend(insertion.len().to_string().bytes());
+            buf.extend(
+                insertion
+                    .iter()
+                    .map(|&base| strand_case(char::from(base) as u8, is_reverse)),
+            );
+        }
+
+        let deletion_len = alignment.deletion_len();
+
+        if deletion_len > 0 {
+            buf.push(b'-');
+            buf.extend(deletion_len.to_string().bytes());
+            buf.extend((1..=i64::from(deletion_len)).map(|i| {
+                let base = self
+                    .reference
+                    .base(name, position + i)
+                    .unwrap_or(MISSING_REFERENCE_BASE);
+
+                strand_case(base, is_reverse)
+            }));
+        }
+
+        if alignment.is_tail() {
+            buf.push(b'$');
+        }
+    }
+}
+
+fn strand_case(base: u8, is_reverse: bool) -> u8 {
+    if is_reverse {
+        base.to_ascii_lowercase()
+    } else {
+        base.to_ascii_uppercase()
+    }
+}
+
+fn quality_char(quality: Option<u8>) -> u8 {
+    match quality {
+        Some(quality) => quality.saturating_add(33).min(MAX_QUALITY_CHAR),
+        None => MAX_QUALITY_CHAR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{
+        pileup::Engine,
+        record::{Flags, MappingQuality, Position},
+        Record,
+    };
+
+    use super::*;
+
+    fn build_record(
+        flags: Flags,
+        position: i64,
+        cigar: &str,
+        sequence: &str,
+    ) -> Result<Record, Box<dyn std::error::Error>> {
+        Ok(Record::builder()
+            .set_read_name("r0".parse()?)
+            .set_flags(flags)
+            .set_reference_sequence_name("sq0".parse()?)
+            .set_position(Position::try_from(position)?)
+            .set_mapping_quality(MappingQuality::from(40))
+            .set_cigar(cigar.parse()?)
+            .set_sequence(sequence.parse()?)
+            .build())
+    }
+
+    #[test]
+    fn test_write_column() -> Result<(), Box<dyn std::error::Error>> {
+        let records = vec![
+            build_record(Flags::empty(), 1, "2M2I1M", "ACTTT")?,
+            build_record(Flags::REVERSE_COMPLEMENTED, 1, "1M2D1M", "AC")?,
+        ];
+
+        let reference = |_: &str, position: i64| b"ACGTA".get((position - 1) as usize).copied();
+        let mut writer = Writer::new(Vec::new(), reference);
+
+        for result in Engine::new().pileup(records.into_iter().map(Ok)) {
+            writer.write_column(&result?)?;
+        }
+
+        let expected = b"sq0\t1\tA\t2\t^I.^I,-2cg\t~~
+sq0\t2\tC\t2\t.+2TT*\t~~
+sq0\t3\tG\t2\tT$*\t~~
+sq0\t4\tT\t1\tc$\t~
+";
+
+        assert_eq!(&writer.get_ref()[..], &expected[..]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quality_char() {
+        assert_eq!(quality_char(Some(0)), b'!');
+        assert_eq!(quality_char(Some(40)), b'I');
+        assert_eq!(quality_char(Some(200)), b'~');
+        assert_eq!(quality_char(None), b'~');
+    }
+}
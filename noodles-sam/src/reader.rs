@@ -7,10 +7,20 @@ pub use self::records::Records;
 use std::io::{self, BufRead, BufReader, Read, Seek};
 
 use noodles_bgzf as bgzf;
+use noodles_core::{
+    limits::{self, Limit, Limits},
+    progress::{self, ProgressObserver},
+};
 
 const HEADER_PREFIX: u8 = b'@';
 const NEWLINE: u8 = b'\n';
 
+const FIELD_DELIMITER: char = '\t';
+const DATA_FIELD_INDEX: usize = 11;
+
+const LINE_FEED: char = '\n';
+const CARRIAGE_RETURN: char = '\r';
+
 /// A SAM reader.
 ///
 /// The SAM format is comprised to two parts: 1) a header and 2) a list of records.
@@ -41,6 +51,8 @@ const NEWLINE: u8 = b'\n';
 #[derive(Debug)]
 pub struct Reader<R> {
     inner: R,
+    progress: progress::Tracker,
+    limits: Limits,
 }
 
 impl<R> Reader<R>
@@ -61,7 +73,73 @@ where
     /// let reader = sam::Reader::new(&data[..]);
     /// ```
     pub fn new(inner: R) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            progress: progress::Tracker::default(),
+            limits: Limits::default(),
+        }
+    }
+
+    /// Sets the limits on the header size, record line length, and data fields size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_core::limits::{Limit, LimitExceeded, Limits};
+    /// use noodles_sam as sam;
+    ///
+    /// let data = b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\n";
+    ///
+    /// let mut reader = sam::Reader::new(&data[..]);
+    /// reader.set_limits(Limits::default().set_max_line_length(16));
+    ///
+    /// let mut buf = String::new();
+    /// let e = reader.read_record(&mut buf).unwrap_err();
+    ///
+    /// assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    /// assert_eq!(
+    ///     e.get_ref().and_then(|e| e.downcast_ref::<LimitExceeded>()),
+    ///     Some(&LimitExceeded::new(Limit::LineLength, 16))
+    /// );
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Sets an observer that is notified after each record is read.
+    ///
+    /// The reader does not know whether its input is compressed, so the reported compressed
+    /// position is always `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_core::progress::Progress;
+    /// use noodles_sam as sam;
+    ///
+    /// let data = b"@HD\tVN:1.6
+    /// *\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*
+    /// ";
+    ///
+    /// let mut reader = sam::Reader::new(&data[..]);
+    /// reader.set_progress_observer(|progress: Progress| {
+    ///     eprintln!("{} records read", progress.records_read());
+    /// });
+    ///
+    /// reader.read_header()?;
+    ///
+    /// for result in reader.records() {
+    ///     let _record = result?;
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_progress_observer<O>(&mut self, observer: O)
+    where
+        O: ProgressObserver + Send + 'static,
+    {
+        self.progress.set_observer(Box::new(observer));
     }
 
     /// Reads the raw SAM header.
@@ -73,6 +151,9 @@ where
     ///
     /// The SAM header is optional, and if it is missing, an empty string is returned.
     ///
+    /// Line endings are normalized to line feeds (`\n`), i.e., CRLF (`\r\n`) line endings are
+    /// converted.
+    ///
     /// # Examples
     ///
     /// ```
@@ -102,7 +183,14 @@ where
 
             let (read_eol, len) = match buf.iter().position(|&b| b == NEWLINE) {
                 Some(i) => {
-                    header_buf.extend(&buf[..=i]);
+                    header_buf.extend(&buf[..i]);
+
+                    if header_buf.ends_with(&[CARRIAGE_RETURN as u8]) {
+                        header_buf.pop();
+                    }
+
+                    header_buf.push(NEWLINE);
+
                     (true, i + 1)
                 }
                 None => {
@@ -113,6 +201,12 @@ where
 
             eol = read_eol;
             self.inner.consume(len);
+
+            limits::check(
+                Limit::HeaderSize,
+                self.limits.max_header_size(),
+                header_buf.len(),
+            )?;
         }
 
         String::from_utf8(header_buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
@@ -121,7 +215,8 @@ where
     /// Reads a single raw SAM record.
     ///
     /// This reads from the underlying stream until a newline is reached and appends it to the
-    /// given buffer, sans the final newline character.
+    /// given buffer, sans the final newline (`\n` or `\r\n`). The last record of a stream is not
+    /// required to end with a newline.
     ///
     /// The stream is expected to be directly after the header or at the start of another record.
     ///
@@ -152,9 +247,31 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn read_record(&mut self, buf: &mut String) -> io::Result<usize> {
-        let result = self.inner.read_line(buf);
-        buf.pop();
-        result
+        let n = match self.limits.max_line_length() {
+            Some(max_line_length) => {
+                let mut reader = self.inner.by_ref().take(max_line_length as u64 + 1);
+                let n = read_line(&mut reader, buf)?;
+                limits::check(Limit::LineLength, Some(max_line_length), n)?;
+                n
+            }
+            None => read_line(&mut self.inner, buf)?,
+        };
+
+        if let Some(max_aux_data_size) = self.limits.max_aux_data_size() {
+            let data_len = buf
+                .match_indices(FIELD_DELIMITER)
+                .nth(DATA_FIELD_INDEX - 1)
+                .map(|(i, _)| buf.len() - i - 1)
+                .unwrap_or(0);
+
+            limits::check(Limit::AuxDataSize, Some(max_aux_data_size), data_len)?;
+        }
+
+        if n > 0 {
+            self.progress.record_read(n, None);
+        }
+
+        Ok(n)
     }
 
     /// Returns an iterator over records starting from the current stream position.
@@ -217,6 +334,30 @@ where
     }
 }
 
+// Reads all bytes until a line feed ('\n') or EOF is reached.
+//
+// The buffer will not include the trailing newline ('\n' or '\r\n').
+fn read_line<R>(reader: &mut R, buf: &mut String) -> io::Result<usize>
+where
+    R: BufRead,
+{
+    match reader.read_line(buf) {
+        Ok(0) => Ok(0),
+        Ok(n) => {
+            if buf.ends_with(LINE_FEED) {
+                buf.pop();
+
+                if buf.ends_with(CARRIAGE_RETURN) {
+                    buf.pop();
+                }
+            }
+
+            Ok(n)
+        }
+        Err(e) => Err(e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +378,123 @@ mod tests {
         assert_eq!(header, "@HD\tVN1.6\n");
         Ok(())
     }
+
+    #[test]
+    fn test_read_header_with_crlf_line_endings() -> io::Result<()> {
+        let data = b"@HD\tVN:1.6\r\n@CO\tnoodles\r\n*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\r\n";
+        let mut reader = Reader::new(&data[..]);
+        let header = reader.read_header()?;
+        assert_eq!(header, "@HD\tVN:1.6\n@CO\tnoodles\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record() -> io::Result<()> {
+        let data = b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\n";
+        let mut reader = Reader::new(&data[..]);
+
+        let mut buf = String::new();
+        assert_eq!(reader.read_record(&mut buf)?, 24);
+        assert_eq!(buf, "*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*");
+
+        buf.clear();
+        assert_eq!(reader.read_record(&mut buf)?, 0);
+        assert!(buf.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record_with_crlf_line_ending() -> io::Result<()> {
+        let data = b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\r\n";
+        let mut reader = Reader::new(&data[..]);
+
+        let mut buf = String::new();
+        assert_eq!(reader.read_record(&mut buf)?, 25);
+        assert_eq!(buf, "*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record_without_final_newline() -> io::Result<()> {
+        let data = b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\n*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*";
+        let mut reader = Reader::new(&data[..]);
+
+        let mut buf = String::new();
+        reader.read_record(&mut buf)?;
+        assert_eq!(buf, "*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*");
+
+        buf.clear();
+        assert_eq!(reader.read_record(&mut buf)?, 23);
+        assert_eq!(buf, "*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_progress_observer() -> io::Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        use noodles_core::progress::Progress;
+
+        let data =
+            b"@HD\tVN:1.6\n*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\n*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*";
+        let mut reader = Reader::new(&data[..]);
+
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let progresses = Arc::clone(&observed);
+        reader.set_progress_observer(move |progress: Progress| {
+            progresses.lock().unwrap().push(progress)
+        });
+
+        reader.read_header()?;
+
+        for result in reader.records() {
+            result?;
+        }
+
+        assert_eq!(
+            *observed.lock().unwrap(),
+            [Progress::new(None, 24, 1), Progress::new(None, 47, 2)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_with_limits() -> io::Result<()> {
+        use noodles_core::limits::LimitExceeded;
+
+        fn limit_exceeded(e: &io::Error) -> Option<&LimitExceeded> {
+            e.get_ref().and_then(|e| e.downcast_ref())
+        }
+
+        let data = b"@HD\tVN:1.6\n@CO\tnoodles\n";
+        let mut reader = Reader::new(&data[..]);
+        reader.set_limits(Limits::default().set_max_header_size(16));
+        let e = reader.read_header().unwrap_err();
+        assert_eq!(
+            limit_exceeded(&e),
+            Some(&LimitExceeded::new(Limit::HeaderSize, 16))
+        );
+
+        let data = b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\tNH:i:1\n";
+        let mut buf = String::new();
+
+        let mut reader = Reader::new(&data[..]);
+        reader.set_limits(Limits::default().set_max_line_length(31));
+        assert_eq!(reader.read_record(&mut buf)?, 31);
+
+        buf.clear();
+        let mut reader = Reader::new(&data[..]);
+        reader.set_limits(Limits::default().set_max_aux_data_size(4));
+        let e = reader.read_record(&mut buf).unwrap_err();
+        assert_eq!(
+            limit_exceeded(&e),
+            Some(&LimitExceeded::new(Limit::AuxDataSize, 4))
+        );
+
+        Ok(())
+    }
 }
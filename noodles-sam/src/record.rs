@@ -18,7 +18,19 @@ pub use self::{
     read_name::ReadName, reference_sequence_name::ReferenceSequenceName, sequence::Sequence,
 };
 
-use std::{error, fmt, num, str::FromStr};
+use std::{
+    convert::TryFrom,
+    error, fmt,
+    io::{self, Write},
+    num,
+    str::{self, FromStr},
+};
+
+use memchr::memchr;
+use noodles_core::{
+    checksum,
+    codec::{Decode, Encode},
+};
 
 pub(crate) const NULL_FIELD: &str = "*";
 const ZERO_FIELD: &str = "0";
@@ -122,6 +134,22 @@ impl Record {
         self.flags
     }
 
+    /// Returns a mutable reference to the SAM flags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, record::Flags};
+    ///
+    /// let mut record = sam::Record::default();
+    /// record.flags_mut().remove(Flags::UNMAPPED);
+    ///
+    /// assert!(record.flags().is_empty());
+    /// ```
+    pub fn flags_mut(&mut self) -> &mut Flags {
+        &mut self.flags
+    }
+
     /// Returns the reference sequence name of this record.
     ///
     /// # Examples
@@ -156,7 +184,7 @@ impl Record {
     /// let record = sam::Record::builder()
     ///     .set_position(Position::try_from(13)?)
     ///     .build();
-    /// assert_eq!(record.position().map(i32::from), Some(13));
+    /// assert_eq!(record.position().map(i64::from), Some(13));
     /// # Ok::<(), sam::record::position::TryFromIntError>(())
     /// ```
     pub fn position(&self) -> Option<Position> {
@@ -208,6 +236,23 @@ impl Record {
         &self.cigar
     }
 
+    /// Returns a mutable reference to the CIGAR operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = sam::Record::builder().set_cigar("4M".parse()?).build();
+    /// *record.cigar_mut() = "2M2I".parse()?;
+    ///
+    /// assert_eq!(record.cigar().to_string(), "2M2I");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cigar_mut(&mut self) -> &mut Cigar {
+        &mut self.cigar
+    }
+
     /// Returns the mate reference sequence name of this record.
     ///
     /// # Examples
@@ -248,7 +293,7 @@ impl Record {
     /// let record = sam::Record::builder()
     ///     .set_mate_position(Position::try_from(21)?)
     ///     .build();
-    /// assert_eq!(record.mate_position().map(i32::from), Some(21));
+    /// assert_eq!(record.mate_position().map(i64::from), Some(21));
     /// # Ok::<(), sam::record::position::TryFromIntError>(())
     /// ```
     pub fn mate_position(&self) -> Option<Position> {
@@ -296,6 +341,23 @@ impl Record {
         &self.sequence
     }
 
+    /// Returns a mutable reference to the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = sam::Record::builder().set_sequence("AT".parse()?).build();
+    /// *record.sequence_mut() = "=T".parse()?;
+    ///
+    /// assert_eq!(record.sequence().to_string(), "=T");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sequence_mut(&mut self) -> &mut Sequence {
+        &mut self.sequence
+    }
+
     /// Returns the quality score for each base in the sequence.
     ///
     /// # Examples
@@ -319,6 +381,23 @@ impl Record {
         &self.quality_scores
     }
 
+    /// Returns a mutable reference to the quality scores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    ///
+    /// let mut record = sam::Record::builder().set_quality_scores("ND".parse()?).build();
+    /// *record.quality_scores_mut() = "NN".parse()?;
+    ///
+    /// assert_eq!(record.quality_scores().to_string(), "NN");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn quality_scores_mut(&mut self) -> &mut QualityScores {
+        &mut self.quality_scores
+    }
+
     /// Returns the optional data fields for this record.
     ///
     /// # Examples
@@ -339,6 +418,26 @@ impl Record {
     pub fn data(&self) -> &Data {
         &self.data
     }
+
+    /// Returns a mutable reference to the optional data fields for this record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, record::data};
+    ///
+    /// let mut record = sam::Record::default();
+    ///
+    /// record.data_mut().insert(data::Field::new(
+    ///     data::field::Tag::ReadGroup,
+    ///     data::field::Value::String(String::from("rg0")),
+    /// ));
+    ///
+    /// assert_eq!(record.data().to_string(), "RG:Z:rg0");
+    /// ```
+    pub fn data_mut(&mut self) -> &mut Data {
+        &mut self.data
+    }
 }
 
 impl Default for Record {
@@ -347,6 +446,110 @@ impl Default for Record {
     }
 }
 
+impl checksum::Record for Record {
+    fn checksum_fields(&self) -> io::Result<checksum::Fields<'_>> {
+        let read_name = self
+            .read_name()
+            .map(|name| name.as_bytes())
+            .unwrap_or_else(|| NULL_FIELD.as_bytes());
+
+        let sequence: Vec<_> = self
+            .sequence()
+            .iter()
+            .map(|&base| char::from(base).to_ascii_uppercase() as u8)
+            .collect();
+
+        let quality_scores: Vec<_> = self
+            .quality_scores()
+            .iter()
+            .map(|&score| u8::from(score))
+            .collect();
+
+        Ok(checksum::Fields::new(
+            read_name.into(),
+            u16::from(self.flags()),
+            sequence.into(),
+            quality_scores.into(),
+        ))
+    }
+}
+
+impl Encode for Record {
+    fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+        write_record(dst, self)
+    }
+}
+
+impl Decode for Record {
+    fn decode(src: &[u8]) -> io::Result<Self> {
+        str::from_utf8(src)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|s| {
+                s.parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+    }
+}
+
+// Writes a record as a SAM line without the line terminator.
+pub(crate) fn write_record<W>(writer: &mut W, record: &Record) -> io::Result<()>
+where
+    W: Write,
+{
+    let qname = record
+        .read_name()
+        .map(|name| name.as_str())
+        .unwrap_or(NULL_FIELD);
+
+    let rnext = record
+        .mate_reference_sequence_name()
+        .map(|mate_reference_sequence_name| {
+            if let Some(reference_sequence_name) = record.reference_sequence_name() {
+                if mate_reference_sequence_name == reference_sequence_name {
+                    return EQ_FIELD;
+                }
+            }
+
+            mate_reference_sequence_name.as_str()
+        })
+        .unwrap_or(NULL_FIELD);
+
+    let pos = record
+        .position()
+        .map(i64::from)
+        .unwrap_or(position::UNMAPPED);
+
+    let pnext = record
+        .mate_position()
+        .map(i64::from)
+        .unwrap_or(position::UNMAPPED);
+
+    write!(
+        writer,
+        "{qname}\t{flag}\t{rname}\t{pos}\t{mapq}\t{cigar}\t{rnext}\t{pnext}\t{tlen}\t{seq}\t{qual}",
+        qname = qname,
+        flag = u16::from(record.flags()),
+        rname = record
+            .reference_sequence_name()
+            .map(|name| name.as_str())
+            .unwrap_or(NULL_FIELD),
+        pos = pos,
+        mapq = u8::from(record.mapping_quality()),
+        cigar = record.cigar(),
+        rnext = rnext,
+        pnext = pnext,
+        tlen = record.template_length(),
+        seq = record.sequence(),
+        qual = record.quality_scores(),
+    )?;
+
+    if !record.data().is_empty() {
+        write!(writer, "\t{}", record.data())?;
+    }
+
+    Ok(())
+}
+
 /// An error returned when a raw SAM record fails to parse.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParseError {
@@ -408,7 +611,7 @@ impl FromStr for Record {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut fields = s.splitn(MAX_FIELDS, FIELD_DELIMITER);
+        let mut fields = Fields::new(s);
 
         let qname = parse_string(&mut fields, Field::Name).and_then(|s| {
             if s == NULL_FIELD {
@@ -419,7 +622,11 @@ impl FromStr for Record {
         })?;
 
         let flag = parse_string(&mut fields, Field::Flags)
-            .and_then(|s| s.parse::<u16>().map_err(ParseError::InvalidFlags))
+            .and_then(|s| {
+                parse_uint(s)
+                    .map_or_else(|| s.parse::<u16>(), Ok)
+                    .map_err(ParseError::InvalidFlags)
+            })
             .map(Flags::from)?;
 
         let rname = parse_string(&mut fields, Field::ReferenceSequenceName).and_then(|s| {
@@ -434,11 +641,17 @@ impl FromStr for Record {
 
         let pos = parse_string(&mut fields, Field::Position).and_then(|s| match s {
             ZERO_FIELD => Ok(None),
-            _ => s.parse().map(Some).map_err(ParseError::InvalidMatePosition),
+            _ => parse_position(s)
+                .map(Some)
+                .map_err(ParseError::InvalidPosition),
         })?;
 
         let mapq = parse_string(&mut fields, Field::MappingQuality)
-            .and_then(|s| s.parse::<u8>().map_err(ParseError::InvalidMappingQuality))
+            .and_then(|s| {
+                parse_uint(s)
+                    .map_or_else(|| s.parse::<u8>(), Ok)
+                    .map_err(ParseError::InvalidMappingQuality)
+            })
             .map(MappingQuality::from)?;
 
         let cigar = parse_string(&mut fields, Field::Cigar)
@@ -456,11 +669,16 @@ impl FromStr for Record {
 
         let pnext = parse_string(&mut fields, Field::MatePosition).and_then(|s| match s {
             ZERO_FIELD => Ok(None),
-            _ => s.parse().map(Some).map_err(ParseError::InvalidMatePosition),
+            _ => parse_position(s)
+                .map(Some)
+                .map_err(ParseError::InvalidMatePosition),
         })?;
 
-        let tlen = parse_string(&mut fields, Field::TemplateLength)
-            .and_then(|s| s.parse::<i32>().map_err(ParseError::InvalidTemplateLength))?;
+        let tlen = parse_string(&mut fields, Field::TemplateLength).and_then(|s| {
+            parse_int(s)
+                .map_or_else(|| s.parse::<i32>(), Ok)
+                .map_err(ParseError::InvalidTemplateLength)
+        })?;
 
         let seq = parse_string(&mut fields, Field::Sequence)
             .and_then(|s| s.parse().map_err(ParseError::InvalidSequence))?;
@@ -490,9 +708,171 @@ impl FromStr for Record {
     }
 }
 
+// An iterator over the fields of a raw record.
+//
+// This is equivalent to `s.splitn(MAX_FIELDS, FIELD_DELIMITER)` but uses `memchr` to find
+// delimiters.
+struct Fields<'a> {
+    s: Option<&'a str>,
+    n: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s: Some(s), n: 0 }
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.s?;
+
+        self.n += 1;
+
+        if self.n == MAX_FIELDS {
+            self.s = None;
+            return Some(s);
+        }
+
+        // The delimiter is ASCII, so splitting around it is always on a char boundary.
+        match memchr(FIELD_DELIMITER as u8, s.as_bytes()) {
+            Some(i) => {
+                self.s = Some(&s[i + 1..]);
+                Some(&s[..i])
+            }
+            None => {
+                self.s = None;
+                Some(s)
+            }
+        }
+    }
+}
+
+// Numeric fields are parsed directly from their ASCII digits. If the fast path fails, the field is
+// parsed again using `str::parse` to build the error.
+fn parse_digits(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    digits.iter().try_fold(0u64, |n, &b| {
+        if b.is_ascii_digit() {
+            n.checked_mul(10)?.checked_add(u64::from(b - b'0'))
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_uint<N>(s: &str) -> Option<N>
+where
+    N: TryFrom<u64>,
+{
+    let digits = s.as_bytes();
+    let digits = digits.strip_prefix(b"+").unwrap_or(digits);
+    parse_digits(digits).and_then(|n| N::try_from(n).ok())
+}
+
+fn parse_int<N>(s: &str) -> Option<N>
+where
+    N: TryFrom<i64>,
+{
+    let n = match s.as_bytes() {
+        [b'-', digits @ ..] => parse_digits(digits)
+            .and_then(|n| i64::try_from(n).ok())
+            .map(|n| -n)?,
+        [b'+', digits @ ..] | digits => parse_digits(digits).and_then(|n| i64::try_from(n).ok())?,
+    };
+
+    N::try_from(n).ok()
+}
+
+fn parse_position(s: &str) -> Result<Position, position::ParseError> {
+    parse_int::<i64>(s)
+        .and_then(|n| Position::try_from(n).ok())
+        .map_or_else(|| s.parse(), Ok)
+}
+
 fn parse_string<'a, I>(fields: &mut I, field: Field) -> Result<&'a str, ParseError>
 where
     I: Iterator<Item = &'a str>,
 {
     fields.next().ok_or(ParseError::MissingField(field))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() -> io::Result<()> {
+        let record = Record::default();
+
+        let mut buf = Vec::new();
+        record.encode(&mut buf)?;
+        assert_eq!(buf, b"*\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*");
+
+        assert_eq!(Record::decode(&buf)?, record);
+
+        assert_eq!(
+            Record::decode(b"*\t4").map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fields() {
+        let s = "r0\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*\tNH:i:1\tCO:Z:noodles";
+        let actual: Vec<_> = Fields::new(s).collect();
+        let expected: Vec<_> = s.splitn(MAX_FIELDS, FIELD_DELIMITER).collect();
+        assert_eq!(actual, expected);
+
+        assert_eq!(Fields::new("").collect::<Vec<_>>(), [""]);
+        assert_eq!(Fields::new("r0\t").collect::<Vec<_>>(), ["r0", ""]);
+    }
+
+    #[test]
+    fn test_parse_uint() {
+        assert_eq!(parse_uint::<u16>("0"), Some(0));
+        assert_eq!(parse_uint::<u16>("+13"), Some(13));
+        assert_eq!(parse_uint::<u16>("65535"), Some(65535));
+        assert_eq!(parse_uint::<u16>("65536"), None);
+        assert_eq!(parse_uint::<u8>("-0"), None);
+        assert_eq!(parse_uint::<u8>(""), None);
+        assert_eq!(parse_uint::<u8>("+"), None);
+        assert_eq!(parse_uint::<u8>("1n"), None);
+        assert_eq!(parse_uint::<u64>("18446744073709551616"), None);
+    }
+
+    #[test]
+    fn test_parse_int() {
+        assert_eq!(parse_int::<i32>("0"), Some(0));
+        assert_eq!(parse_int::<i32>("-8"), Some(-8));
+        assert_eq!(parse_int::<i32>("+8"), Some(8));
+        assert_eq!(parse_int::<i32>("2147483648"), None);
+        assert_eq!(parse_int::<i32>("-"), None);
+        assert_eq!(parse_int::<i32>("--8"), None);
+        assert_eq!(parse_int::<i32>("8 "), None);
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(parse_position("13"), Ok(Position::try_from(13).unwrap()));
+        assert!(parse_position("0").is_err());
+        assert!(parse_position("-13").is_err());
+        assert!(parse_position("n").is_err());
+    }
+
+    #[test]
+    fn test_from_str_with_invalid_position() {
+        let s = "*\t4\t*\tn\t255\t*\t*\t0\t0\t*\t*";
+        assert!(matches!(
+            s.parse::<Record>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+    }
+}
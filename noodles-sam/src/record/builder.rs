@@ -106,7 +106,7 @@ impl Builder {
     ///     .set_position(Position::try_from(13)?)
     ///     .build();
     ///
-    /// assert_eq!(record.position().map(i32::from), Some(13));
+    /// assert_eq!(record.position().map(i64::from), Some(13));
     /// # Ok::<(), sam::record::position::TryFromIntError>(())
     /// ```
     pub fn set_position(mut self, position: Position) -> Self {
@@ -185,7 +185,7 @@ impl Builder {
     ///     .set_mate_position(Position::try_from(17)?)
     ///     .build();
     ///
-    /// assert_eq!(record.mate_position().map(i32::from), Some(17));
+    /// assert_eq!(record.mate_position().map(i64::from), Some(17));
     /// # Ok::<(), sam::record::position::TryFromIntError>(())
     /// ```
     pub fn set_mate_position(mut self, mate_position: Position) -> Self {
@@ -379,7 +379,7 @@ mod tests {
             record.reference_sequence_name(),
             Some(&reference_sequence_name)
         );
-        assert_eq!(record.position().map(i32::from), Some(13));
+        assert_eq!(record.position().map(i64::from), Some(13));
         assert_eq!(u8::from(record.mapping_quality()), 37);
         assert_eq!(record.cigar().len(), 1);
 
@@ -388,7 +388,7 @@ mod tests {
             Some(&mate_reference_sequence_name)
         );
 
-        assert_eq!(record.mate_position().map(i32::from), Some(17));
+        assert_eq!(record.mate_position().map(i64::from), Some(17));
         assert_eq!(record.template_length(), 4);
         assert_eq!(record.sequence(), &sequence);
         assert_eq!(record.quality_scores(), &quality_scores);
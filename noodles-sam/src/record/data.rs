@@ -6,14 +6,227 @@ pub use self::field::Field;
 
 use std::{error, fmt, ops::Deref, str::FromStr};
 
+use self::field::{
+    value::{FromValue, Type},
+    Tag,
+};
+
 const DELIMITER: char = '\t';
 
 /// SAM record data.
 ///
-/// This is also called optional fields.
+/// This is also called optional fields. Fields are kept in insertion order, and each tag appears
+/// at most once.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Data(Vec<Field>);
 
+impl Data {
+    /// Returns a reference to the field with the given tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let nh = Field::new(Tag::AlignmentHitCount, Value::Int32(1));
+    /// let data = Data::from(vec![nh.clone()]);
+    ///
+    /// assert_eq!(data.get(&Tag::AlignmentHitCount), Some(&nh));
+    /// assert!(data.get(&Tag::ReadGroup).is_none());
+    /// ```
+    pub fn get(&self, tag: &Tag) -> Option<&Field> {
+        self.0.iter().find(|field| field.tag() == tag)
+    }
+
+    /// Returns the value of the field with the given tag as the given type.
+    ///
+    /// If the tag is a standard tag, the stored value type is first validated against the type
+    /// defined for the tag (see [`Tag::ty`]). This returns `Ok(None)` if the field does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::ReadGroup, Value::Int32(0)),
+    /// ]);
+    ///
+    /// assert_eq!(data.get_typed::<i32>(&Tag::AlignmentHitCount), Ok(Some(1)));
+    /// assert!(data.get_typed::<&str>(&Tag::AlignmentHitCount).is_err());
+    /// assert_eq!(data.get_typed::<i32>(&Tag::EditDistance), Ok(None));
+    ///
+    /// // `RG` is defined as a string.
+    /// assert!(data.get_typed::<i32>(&Tag::ReadGroup).is_err());
+    /// ```
+    pub fn get_typed<'a, T>(&'a self, tag: &Tag) -> Result<Option<T>, GetTypedError>
+    where
+        T: FromValue<'a>,
+    {
+        let value = match self.get(tag) {
+            Some(field) => field.value(),
+            None => return Ok(None),
+        };
+
+        let actual = value.ty();
+
+        if let Some(expected) = tag.ty() {
+            if actual != expected {
+                return Err(GetTypedError::InvalidType {
+                    tag: tag.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        T::from_value(value)
+            .map(Some)
+            .ok_or_else(|| GetTypedError::IncompatibleType {
+                tag: tag.clone(),
+                requested: T::TYPE,
+                actual,
+            })
+    }
+
+    /// Returns a mutable reference to the field with the given tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let mut data = Data::from(vec![Field::new(Tag::AlignmentHitCount, Value::Int32(1))]);
+    ///
+    /// if let Some(field) = data.get_mut(&Tag::AlignmentHitCount) {
+    ///     *field.value_mut() = Value::Int32(2);
+    /// }
+    ///
+    /// assert_eq!(data.to_string(), "NH:i:2");
+    /// ```
+    pub fn get_mut(&mut self, tag: &Tag) -> Option<&mut Field> {
+        self.0.iter_mut().find(|field| field.tag() == tag)
+    }
+
+    /// Returns the index of the field with the given tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::ReadGroup, Value::String(String::from("rg0"))),
+    /// ]);
+    ///
+    /// assert_eq!(data.get_index_of(&Tag::ReadGroup), Some(1));
+    /// assert!(data.get_index_of(&Tag::Comment).is_none());
+    /// ```
+    pub fn get_index_of(&self, tag: &Tag) -> Option<usize> {
+        self.0.iter().position(|field| field.tag() == tag)
+    }
+
+    /// Inserts a field into the data.
+    ///
+    /// If a field with the same tag already exists, it is replaced in place, and the old field is
+    /// returned. Otherwise, the field is appended, and `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let mut data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::EditDistance, Value::Int32(0)),
+    /// ]);
+    ///
+    /// let rg = Field::new(Tag::ReadGroup, Value::String(String::from("rg0")));
+    /// assert!(data.insert(rg).is_none());
+    ///
+    /// let nh = Field::new(Tag::AlignmentHitCount, Value::Int32(2));
+    /// let old_nh = data.insert(nh);
+    /// assert_eq!(old_nh, Some(Field::new(Tag::AlignmentHitCount, Value::Int32(1))));
+    ///
+    /// assert_eq!(data.to_string(), "NH:i:2\tNM:i:0\tRG:Z:rg0");
+    /// ```
+    pub fn insert(&mut self, field: Field) -> Option<Field> {
+        match self.get_index_of(field.tag()) {
+            Some(i) => Some(std::mem::replace(&mut self.0[i], field)),
+            None => {
+                self.0.push(field);
+                None
+            }
+        }
+    }
+
+    /// Removes the field with the given tag.
+    ///
+    /// The order of the remaining fields is preserved. This returns the removed field, if it
+    /// existed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let mut data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::OriginalQualityScores, Value::String(String::from("NDLS"))),
+    ///     Field::new(Tag::ReadGroup, Value::String(String::from("rg0"))),
+    /// ]);
+    ///
+    /// assert!(data.remove(&Tag::OriginalQualityScores).is_some());
+    /// assert!(data.remove(&Tag::OriginalQualityScores).is_none());
+    ///
+    /// assert_eq!(data.to_string(), "NH:i:1\tRG:Z:rg0");
+    /// ```
+    pub fn remove(&mut self, tag: &Tag) -> Option<Field> {
+        self.get_index_of(tag).map(|i| self.0.remove(i))
+    }
+
+    /// Retains only the fields specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let mut data = Data::from(vec![
+    ///     Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+    ///     Field::new(Tag::Other(String::from("XA")), Value::Int32(0)),
+    /// ]);
+    ///
+    /// data.retain(|field| !matches!(field.tag(), Tag::Other(_)));
+    ///
+    /// assert_eq!(data.to_string(), "NH:i:1");
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Field) -> bool,
+    {
+        self.0.retain(f);
+    }
+
+    /// Removes all fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::{field::{Tag, Value}, Data, Field};
+    ///
+    /// let mut data = Data::from(vec![Field::new(Tag::AlignmentHitCount, Value::Int32(1))]);
+    /// data.clear();
+    ///
+    /// assert!(data.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
 impl Deref for Data {
     type Target = [Field];
 
@@ -37,8 +250,68 @@ impl fmt::Display for Data {
 }
 
 impl From<Vec<Field>> for Data {
+    /// Converts a list of fields to data.
+    ///
+    /// Fields with duplicate tags are merged using [`Data::insert`], i.e., the last value is kept
+    /// at the position of the first occurrence.
     fn from(fields: Vec<Field>) -> Self {
-        Self(fields)
+        let mut data = Self(Vec::with_capacity(fields.len()));
+
+        for field in fields {
+            data.insert(field);
+        }
+
+        data
+    }
+}
+
+/// An error returned when a SAM record data field value cannot be read as a given type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GetTypedError {
+    /// The stored value type does not match the type defined for the standard tag.
+    InvalidType {
+        /// The field tag.
+        tag: Tag,
+        /// The type defined for the tag.
+        expected: Type,
+        /// The stored value type.
+        actual: Type,
+    },
+    /// The stored value cannot be read as the requested type.
+    IncompatibleType {
+        /// The field tag.
+        tag: Tag,
+        /// The requested type.
+        requested: Type,
+        /// The stored value type.
+        actual: Type,
+    },
+}
+
+impl error::Error for GetTypedError {}
+
+impl fmt::Display for GetTypedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidType {
+                tag,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "invalid type for {}: expected {}, got {}",
+                tag, expected, actual
+            ),
+            Self::IncompatibleType {
+                tag,
+                requested,
+                actual,
+            } => write!(
+                f,
+                "incompatible type for {}: requested {}, got {}",
+                tag, requested, actual
+            ),
+        }
     }
 }
 
@@ -47,6 +320,8 @@ impl From<Vec<Field>> for Data {
 pub enum ParseError {
     /// The input data contains an invalid field.
     InvalidField(field::ParseError),
+    /// A tag is duplicated.
+    DuplicateTag(Tag),
 }
 
 impl error::Error for ParseError {}
@@ -55,6 +330,7 @@ impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidField(e) => write!(f, "invalid field: {}", e),
+            Self::DuplicateTag(tag) => write!(f, "duplicate tag: {}", tag),
         }
     }
 }
@@ -67,19 +343,114 @@ impl FromStr for Data {
             return Ok(Self::default());
         }
 
-        s.split(DELIMITER)
-            .map(|t| t.parse().map_err(ParseError::InvalidField))
-            .collect::<Result<Vec<_>, _>>()
-            .map(Self::from)
+        let mut data = Self::default();
+
+        for raw_field in s.split(DELIMITER) {
+            let field: Field = raw_field.parse().map_err(ParseError::InvalidField)?;
+
+            if data.get(field.tag()).is_some() {
+                return Err(ParseError::DuplicateTag(field.tag().clone()));
+            }
+
+            data.0.push(field);
+        }
+
+        Ok(data)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::field::{Tag, Value};
+    use super::field::Value;
 
     use super::*;
 
+    #[test]
+    fn test_insert() {
+        let mut data = Data::default();
+
+        let rg = Field::new(Tag::ReadGroup, Value::String(String::from("rg0")));
+        assert!(data.insert(rg).is_none());
+
+        let nh = Field::new(Tag::AlignmentHitCount, Value::Int32(1));
+        assert!(data.insert(nh).is_none());
+
+        let rg = Field::new(Tag::ReadGroup, Value::String(String::from("rg1")));
+        assert_eq!(
+            data.insert(rg),
+            Some(Field::new(
+                Tag::ReadGroup,
+                Value::String(String::from("rg0"))
+            ))
+        );
+
+        assert_eq!(data.to_string(), "RG:Z:rg1\tNH:i:1");
+    }
+
+    #[test]
+    fn test_get_typed() {
+        let data = Data::from(vec![
+            Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+            Field::new(Tag::ReadGroup, Value::Int32(0)),
+            Field::new(Tag::Cigar, Value::UInt32Array(vec![0x40])),
+            Field::new(Tag::Other(String::from("ZN")), Value::Float(0.5)),
+        ]);
+
+        assert_eq!(data.get_typed::<i32>(&Tag::AlignmentHitCount), Ok(Some(1)));
+        assert_eq!(data.get_typed::<&[u32]>(&Tag::Cigar), Ok(Some(&[0x40][..])));
+        assert_eq!(
+            data.get_typed::<f32>(&Tag::Other(String::from("ZN"))),
+            Ok(Some(0.5))
+        );
+        assert_eq!(data.get_typed::<i32>(&Tag::EditDistance), Ok(None));
+
+        assert_eq!(
+            data.get_typed::<i32>(&Tag::ReadGroup),
+            Err(GetTypedError::InvalidType {
+                tag: Tag::ReadGroup,
+                expected: Type::String,
+                actual: Type::Int32,
+            })
+        );
+
+        assert_eq!(
+            data.get_typed::<&[i8]>(&Tag::Cigar),
+            Err(GetTypedError::IncompatibleType {
+                tag: Tag::Cigar,
+                requested: Type::Array,
+                actual: Type::Array,
+            })
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut data = Data::from(vec![
+            Field::new(Tag::ReadGroup, Value::String(String::from("rg0"))),
+            Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+            Field::new(Tag::EditDistance, Value::Int32(0)),
+        ]);
+
+        assert_eq!(
+            data.remove(&Tag::AlignmentHitCount),
+            Some(Field::new(Tag::AlignmentHitCount, Value::Int32(1)))
+        );
+        assert!(data.remove(&Tag::AlignmentHitCount).is_none());
+
+        assert_eq!(data.to_string(), "RG:Z:rg0\tNM:i:0");
+    }
+
+    #[test]
+    fn test_from_vec_field_for_data_with_duplicate_tags() {
+        let data = Data::from(vec![
+            Field::new(Tag::AlignmentHitCount, Value::Int32(1)),
+            Field::new(Tag::ReadGroup, Value::String(String::from("rg0"))),
+            Field::new(Tag::AlignmentHitCount, Value::Int32(2)),
+        ]);
+
+        assert_eq!(data.to_string(), "NH:i:2\tRG:Z:rg0");
+    }
+
     #[test]
     fn test_fmt() {
         let data = Data::from(vec![
@@ -103,5 +474,10 @@ mod tests {
         );
 
         assert_eq!("".parse::<Data>(), Ok(Data::default()));
+
+        assert_eq!(
+            "NH:i:1\tNH:i:2".parse::<Data>(),
+            Err(ParseError::DuplicateTag(Tag::AlignmentHitCount))
+        );
     }
 }
@@ -2,6 +2,8 @@
 
 use std::{error, fmt, str::FromStr};
 
+use super::value::Type;
+
 const LEN: usize = 2;
 
 /// A SAM record data field tag.
@@ -10,130 +12,208 @@ const LEN: usize = 2;
 /// (2020-05-29).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Tag {
-    /// (`AM`).
+    /// Smallest template-independent mapping quality of any segment in the same template (`AM`,
+    /// type `i`).
     MinMappingQuality,
-    /// (`AS`).
+    /// Alignment score generated by the aligner (`AS`, type `i`).
     AlignmentScore,
-    /// (`BC`).
+    /// Barcode sequence identifying the sample (`BC`, type `Z`).
     SampleBarcodeSequence,
-    /// (`BQ`).
+    /// Offset to base alignment quality (BAQ) (`BQ`, type `Z`).
     BaseAlignmentQualityOffsets,
-    /// (`BZ`).
+    /// Phred quality of the unique molecular barcode bases in the `OX` tag (`BZ`, type `Z`).
     OriginalUmiQualityScores,
-    /// (`CB`).
+    /// Cell identifier (`CB`, type `Z`).
     CellBarcodeId,
-    /// (`CC`).
+    /// Reference name of the next hit (`CC`, type `Z`).
     NextHitReferenceSequenceName,
-    /// (`CG`).
+    /// BAM only: CIGAR in BAM's binary encoding if (and only if) it consists of > 65535 operators
+    /// (`CG`, type `B:I`).
     Cigar,
-    /// (`CM`).
+    /// Edit distance between the color sequence and the color reference (`CM`, type `i`).
     ColorEditDistance,
-    /// (`CO`).
+    /// Free-text comments (`CO`, type `Z`).
     Comment,
-    /// (`CP`).
+    /// Leftmost coordinate of the next hit (`CP`, type `i`).
     NextHitPosition,
-    /// (`CQ`).
+    /// Color read base qualities (`CQ`, type `Z`).
     ColarQualityScores,
-    /// (`CR`).
+    /// Cellular barcode sequence bases (uncorrected) (`CR`, type `Z`).
     CellBarcodeSequence,
-    /// (`CS`).
+    /// Color read sequence (`CS`, type `Z`).
     ColorSequence,
-    /// (`CT`).
+    /// Complete read annotation tag, used for consensus annotation dummy features (`CT`, type `Z`).
     CompleteReadAnnotations,
-    /// (`CY`).
+    /// Phred quality of the cellular barcode sequence in the `CR` tag (`CY`, type `Z`).
     CellBarcodeQualityScores,
-    /// (`E2`).
+    /// The 2nd most likely base calls (`E2`, type `Z`).
     NextHitSequence,
-    /// (`FI`).
+    /// The index of the segment in the template (`FI`, type `i`).
     SegmentIndex,
-    /// (`FS`).
+    /// Segment suffix (`FS`, type `Z`).
     SegmentSuffix,
-    /// (`FZ`).
+    /// Flow signal intensities (`FZ`, type `B:S`).
     AlterantiveSequence,
-    /// (`GC`).
+    /// Reserved for backwards compatibility reasons (`GC`).
     ReservedGC,
-    /// (`GQ`).
+    /// Reserved for backwards compatibility reasons (`GQ`).
     ReservedGQ,
-    /// (`GS`).
+    /// Reserved for backwards compatibility reasons (`GS`).
     ReservedGS,
-    /// (`H0`).
+    /// Number of perfect hits (`H0`, type `i`).
     PerfectHitCount,
-    /// (`H1`).
+    /// Number of 1-difference hits (`H1`, type `i`).
     OneDifferenceHitCount,
-    /// (`H2`).
+    /// Number of 2-difference hits (`H2`, type `i`).
     TwoDifferenceHitCount,
-    /// (`HI`).
+    /// Query hit index (`HI`, type `i`).
     HitIndex,
-    /// (`IH`).
+    /// Number of stored alignments in SAM that contains the query in the current record (`IH`, type
+    /// `i`).
     TotalHitCount,
-    /// (`LB`).
+    /// Library (`LB`, type `Z`).
     Library,
-    /// (`MC`).
+    /// CIGAR string for mate/next segment (`MC`, type `Z`).
     MateCigar,
-    /// (`MD`).
+    /// String encoding mismatched and deleted reference bases (`MD`, type `Z`).
     MismatchedPositions,
-    /// (`MF`).
+    /// Reserved for backwards compatibility reasons (`MF`).
     ReservedMF,
-    /// (`MI`).
+    /// Molecular identifier (`MI`, type `Z`).
     UmiId,
-    /// (`MQ`).
+    /// Mapping quality of the mate/next segment (`MQ`, type `i`).
     MateMappingQuality,
-    /// (`NH`).
+    /// Number of reported alignments that contain the query in the current record (`NH`, type `i`).
     AlignmentHitCount,
-    /// (`NM`).
+    /// Edit distance to the reference (`NM`, type `i`).
     EditDistance,
-    /// (`OA`).
+    /// Original alignment (`OA`, type `Z`).
     OriginalAlignment,
-    /// (`OC`).
+    /// Original CIGAR, usually before realignment (`OC`, type `Z`).
     OriginalCigar,
-    /// (`OP`).
+    /// Original 1-based position, usually before realignment (`OP`, type `i`).
     OriginalPosition,
-    /// (`OQ`).
+    /// Original base quality, usually before recalibration (`OQ`, type `Z`).
     OriginalQualityScores,
-    /// (`OX`).
+    /// Original unique molecular barcode bases (`OX`, type `Z`).
     OriginalUmiBarcodeSequence,
-    /// (`PG`).
+    /// Program (`PG`, type `Z`).
     Program,
-    /// (`PQ`).
+    /// Phred likelihood of the template (`PQ`, type `i`).
     TemplateLikelihood,
-    /// (`PT`).
+    /// Read annotations for parts of the padded read sequence (`PT`, type `Z`).
     PaddedReadAnnotations,
-    /// (`PU`).
+    /// Platform unit (`PU`, type `Z`).
     PlatformUnit,
-    /// (`Q2`).
+    /// Phred quality of the mate/next segment sequence in the `R2` tag (`Q2`, type `Z`).
     MateQualityScores,
-    /// (`QT`).
+    /// Phred quality of the sample barcode sequence in the `BC` tag (`QT`, type `Z`).
     SampleBarcodeQualityScores,
-    /// (`QX`).
+    /// Quality score of the unique molecular identifier in the `RX` tag (`QX`, type `Z`).
     UmiQualityScores,
-    /// (`R2`).
+    /// Sequence of the mate/next segment in the template (`R2`, type `Z`).
     MateSequence,
-    /// (`RG`).
+    /// Read group (`RG`, type `Z`).
     ReadGroup,
-    /// (`RT`).
+    /// Reserved for backwards compatibility reasons (`RT`).
     ReservedRT,
-    /// (`RX`).
+    /// Sequence bases of the (possibly corrected) unique molecular identifier (`RX`, type `Z`).
     UmiSequence,
-    /// (`S2`).
+    /// Reserved for backwards compatibility reasons (`S2`).
     ReservedS2,
-    /// (`SA`).
+    /// Other canonical alignments in a chimeric alignment (`SA`, type `Z`).
     OtherAlignments,
-    /// (`SM`).
+    /// Template-independent mapping quality (`SM`, type `i`).
     TemplateMappingQuality,
-    /// (`SQ`).
+    /// Reserved for backwards compatibility reasons (`SQ`).
     ReservedSQ,
-    /// (`TC`).
+    /// The number of segments in the template (`TC`, type `i`).
     SegmentCount,
-    /// (`TS`).
+    /// Strand (`+` or `-`) of the transcript to which the read has been mapped (`TS`, type `A`).
     TranscriptStrand,
-    /// (`U2`).
+    /// Phred probability of the 2nd call being wrong conditional on the best being wrong (`U2`,
+    /// type `Z`).
     NextHitQualityScores,
-    /// (`UQ`).
+    /// Phred likelihood of the segment, conditional on the mapping being correct (`UQ`, type `i`).
     SegmentLikelihood,
     /// Any other non-standard tag.
     Other(String),
 }
 
+impl Tag {
+    /// Returns the value type defined for this tag.
+    ///
+    /// This is `None` for reserved and other tags, which have no defined type. Array tags
+    /// additionally define an element subtype, i.e., `CG` (`I`) and `FZ` (`S`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::field::{value::Type, Tag};
+    ///
+    /// assert_eq!(Tag::AlignmentHitCount.ty(), Some(Type::Int32));
+    /// assert_eq!(Tag::ReadGroup.ty(), Some(Type::String));
+    /// assert!(Tag::Other(String::from("ZN")).ty().is_none());
+    /// ```
+    pub fn ty(&self) -> Option<Type> {
+        match self {
+            Self::TranscriptStrand => Some(Type::Char),
+            Self::MinMappingQuality
+            | Self::AlignmentScore
+            | Self::ColorEditDistance
+            | Self::NextHitPosition
+            | Self::SegmentIndex
+            | Self::PerfectHitCount
+            | Self::OneDifferenceHitCount
+            | Self::TwoDifferenceHitCount
+            | Self::HitIndex
+            | Self::TotalHitCount
+            | Self::MateMappingQuality
+            | Self::AlignmentHitCount
+            | Self::EditDistance
+            | Self::OriginalPosition
+            | Self::TemplateLikelihood
+            | Self::TemplateMappingQuality
+            | Self::SegmentCount
+            | Self::SegmentLikelihood => Some(Type::Int32),
+            Self::SampleBarcodeSequence
+            | Self::BaseAlignmentQualityOffsets
+            | Self::OriginalUmiQualityScores
+            | Self::CellBarcodeId
+            | Self::NextHitReferenceSequenceName
+            | Self::Comment
+            | Self::ColarQualityScores
+            | Self::CellBarcodeSequence
+            | Self::ColorSequence
+            | Self::CompleteReadAnnotations
+            | Self::CellBarcodeQualityScores
+            | Self::NextHitSequence
+            | Self::SegmentSuffix
+            | Self::Library
+            | Self::MateCigar
+            | Self::MismatchedPositions
+            | Self::UmiId
+            | Self::OriginalAlignment
+            | Self::OriginalCigar
+            | Self::OriginalQualityScores
+            | Self::OriginalUmiBarcodeSequence
+            | Self::Program
+            | Self::PaddedReadAnnotations
+            | Self::PlatformUnit
+            | Self::MateQualityScores
+            | Self::SampleBarcodeQualityScores
+            | Self::UmiQualityScores
+            | Self::MateSequence
+            | Self::ReadGroup
+            | Self::UmiSequence
+            | Self::OtherAlignments
+            | Self::NextHitQualityScores => Some(Type::String),
+            Self::Cigar | Self::AlterantiveSequence => Some(Type::Array),
+            _ => None,
+        }
+    }
+}
+
 impl AsRef<str> for Tag {
     fn as_ref(&self) -> &str {
         match self {
@@ -160,7 +240,7 @@ impl AsRef<str> for Tag {
             Self::ReservedGC => "GC",
             Self::ReservedGQ => "GQ",
             Self::ReservedGS => "GS",
-            Self::PerfectHitCount => "HO",
+            Self::PerfectHitCount => "H0",
             Self::OneDifferenceHitCount => "H1",
             Self::TwoDifferenceHitCount => "H2",
             Self::HitIndex => "HI",
@@ -248,7 +328,7 @@ impl FromStr for Tag {
             "GC" => Ok(Self::ReservedGC),
             "GQ" => Ok(Self::ReservedGQ),
             "GS" => Ok(Self::ReservedGS),
-            "HO" => Ok(Self::PerfectHitCount),
+            "H0" => Ok(Self::PerfectHitCount),
             "H1" => Ok(Self::OneDifferenceHitCount),
             "H2" => Ok(Self::TwoDifferenceHitCount),
             "HI" => Ok(Self::HitIndex),
@@ -300,6 +380,17 @@ impl FromStr for Tag {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ty() {
+        assert_eq!(Tag::TranscriptStrand.ty(), Some(Type::Char));
+        assert_eq!(Tag::EditDistance.ty(), Some(Type::Int32));
+        assert_eq!(Tag::MismatchedPositions.ty(), Some(Type::String));
+        assert_eq!(Tag::Cigar.ty(), Some(Type::Array));
+        assert_eq!(Tag::AlterantiveSequence.ty(), Some(Type::Array));
+        assert!(Tag::ReservedGC.ty().is_none());
+        assert!(Tag::Other(String::from("ZN")).ty().is_none());
+    }
+
     #[test]
     fn test_fmt() {
         assert_eq!(Tag::MinMappingQuality.to_string(), "AM");
@@ -325,7 +416,7 @@ mod tests {
         assert_eq!(Tag::ReservedGC.to_string(), "GC");
         assert_eq!(Tag::ReservedGQ.to_string(), "GQ");
         assert_eq!(Tag::ReservedGS.to_string(), "GS");
-        assert_eq!(Tag::PerfectHitCount.to_string(), "HO");
+        assert_eq!(Tag::PerfectHitCount.to_string(), "H0");
         assert_eq!(Tag::OneDifferenceHitCount.to_string(), "H1");
         assert_eq!(Tag::TwoDifferenceHitCount.to_string(), "H2");
         assert_eq!(Tag::HitIndex.to_string(), "HI");
@@ -390,7 +481,7 @@ mod tests {
         assert_eq!("GC".parse(), Ok(Tag::ReservedGC));
         assert_eq!("GQ".parse(), Ok(Tag::ReservedGQ));
         assert_eq!("GS".parse(), Ok(Tag::ReservedGS));
-        assert_eq!("HO".parse(), Ok(Tag::PerfectHitCount));
+        assert_eq!("H0".parse(), Ok(Tag::PerfectHitCount));
         assert_eq!("H1".parse(), Ok(Tag::OneDifferenceHitCount));
         assert_eq!("H2".parse(), Ok(Tag::TwoDifferenceHitCount));
         assert_eq!("HI".parse(), Ok(Tag::HitIndex));
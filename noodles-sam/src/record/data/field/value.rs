@@ -5,19 +5,27 @@ pub mod ty;
 
 pub use self::{subtype::Subtype, ty::Type};
 
-use std::{error, fmt, num, str::FromStr};
+use std::{convert::TryFrom, error, fmt, num, str::FromStr};
 
 use super::DELIMITER;
 
 const ARRAY_VALUE_DELIMITER: char = ',';
 
 /// A SAM record data field value.
+///
+/// An integer (`i`) is in the range [-2^31, 2^32). Its canonical representation is
+/// [`Self::Int32`] when it fits in an `i32` and [`Self::UInt32`] otherwise. Use
+/// [`Value::try_from`] with an `i64` to create a canonical integer value.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     /// A character (`A`).
     Char(char),
     /// A 32-bit integer (`i`).
     Int32(i32),
+    /// A 32-bit unsigned integer (`i`) greater than `i32::MAX`.
+    ///
+    /// This represents a value that is stored as a BAM `I` type.
+    UInt32(u32),
     /// A single-precision floating-point (`f`).
     Float(f32),
     /// A string (`Z`).
@@ -52,7 +60,7 @@ impl Value {
     pub fn ty(&self) -> Type {
         match *self {
             Self::Char(_) => Type::Char,
-            Self::Int32(_) => Type::Int32,
+            Self::Int32(_) | Self::UInt32(_) => Type::Int32,
             Self::Float(_) => Type::Float,
             Self::String(_) => Type::String,
             Self::Hex(_) => Type::Hex,
@@ -148,6 +156,55 @@ impl Value {
         self.as_int32().is_some()
     }
 
+    /// Returns the value as a 32-bit unsigned integer if it is a 32-bit unsigned integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::field::Value;
+    /// assert_eq!(Value::UInt32(3000000000).as_uint32(), Some(3000000000));
+    /// assert_eq!(Value::Int32(0).as_uint32(), None);
+    /// ```
+    pub fn as_uint32(&self) -> Option<u32> {
+        match *self {
+            Self::UInt32(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the value is a 32-bit unsigned integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::field::Value;
+    /// assert!(Value::UInt32(3000000000).is_uint32());
+    /// assert!(!Value::Int32(0).is_uint32());
+    /// ```
+    pub fn is_uint32(&self) -> bool {
+        self.as_uint32().is_some()
+    }
+
+    /// Returns the value as a 64-bit integer if it is an integer.
+    ///
+    /// This is lossless for both [`Self::Int32`] and [`Self::UInt32`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::data::field::Value;
+    /// assert_eq!(Value::Int32(-8).as_int(), Some(-8));
+    /// assert_eq!(Value::UInt32(3000000000).as_int(), Some(3000000000));
+    /// assert_eq!(Value::Char('a').as_int(), None);
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Self::Int32(n) => Some(i64::from(n)),
+            Self::UInt32(n) => Some(i64::from(n)),
+            _ => None,
+        }
+    }
+
     /// Returns the value as a single-precision floating-point if it is a single-precision
     /// float-point.
     ///
@@ -449,6 +506,7 @@ impl fmt::Display for Value {
         match self {
             Self::Char(c) => write!(f, "{}", c),
             Self::Int32(n) => write!(f, "{}", n),
+            Self::UInt32(n) => write!(f, "{}", n),
             Self::Float(n) => write!(f, "{}", n),
             Self::String(s) => f.write_str(s),
             Self::Hex(s) => f.write_str(s),
@@ -519,6 +577,153 @@ impl fmt::Display for Value {
     }
 }
 
+/// A type that can be borrowed from a SAM record data field value.
+///
+/// This is used by [`crate::record::Data::get_typed`].
+pub trait FromValue<'a>: Sized {
+    /// The data field value type of `Self`.
+    const TYPE: Type;
+
+    /// Returns the value as `Self` if it is of the matching variant.
+    fn from_value(value: &'a Value) -> Option<Self>;
+}
+
+impl<'a> FromValue<'a> for char {
+    const TYPE: Type = Type::Char;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_char()
+    }
+}
+
+impl<'a> FromValue<'a> for i32 {
+    const TYPE: Type = Type::Int32;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_int32()
+    }
+}
+
+impl<'a> FromValue<'a> for i64 {
+    const TYPE: Type = Type::Int32;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_int()
+    }
+}
+
+impl<'a> FromValue<'a> for f32 {
+    const TYPE: Type = Type::Float;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_float()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a str {
+    const TYPE: Type = Type::String;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_str()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [i8] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_int8_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [u8] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_uint8_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [i16] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_int16_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [u16] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_uint16_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [i32] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_int32_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [u32] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_uint32_array()
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [f32] {
+    const TYPE: Type = Type::Array;
+
+    fn from_value(value: &'a Value) -> Option<Self> {
+        value.as_float_array()
+    }
+}
+
+/// An error returned when an integer fails to convert to a SAM record data field value.
+///
+/// SAM integers are in the range [-2^31, 2^32).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TryFromIntError(i64);
+
+impl error::Error for TryFromIntError {}
+
+impl fmt::Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "integer value is out of range: {}", self.0)
+    }
+}
+
+impl TryFrom<i64> for Value {
+    type Error = TryFromIntError;
+
+    /// Converts an integer to its canonical integer value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use noodles_sam::record::data::field::Value;
+    ///
+    /// assert_eq!(Value::try_from(-8), Ok(Value::Int32(-8)));
+    /// assert_eq!(Value::try_from(3000000000), Ok(Value::UInt32(3000000000)));
+    /// assert!(Value::try_from(5000000000).is_err());
+    /// ```
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
+        if let Ok(m) = i32::try_from(n) {
+            Ok(Self::Int32(m))
+        } else if let Ok(m) = u32::try_from(n) {
+            Ok(Self::UInt32(m))
+        } else {
+            Err(TryFromIntError(n))
+        }
+    }
+}
+
 /// An error returned when a raw SAM record data field value fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -532,6 +737,8 @@ pub enum ParseError {
     InvalidCharValue,
     /// The data field integer value is invalid.
     InvalidIntValue(num::ParseIntError),
+    /// The data field integer value is out of range.
+    IntValueOutOfRange(TryFromIntError),
     /// The data field floating-point value is invalid.
     InvalidFloatValue(num::ParseFloatError),
     /// The data field subtype is missing.
@@ -550,6 +757,7 @@ impl fmt::Display for ParseError {
             Self::MissingValue => f.write_str("missing value"),
             Self::InvalidCharValue => f.write_str("invalid char value"),
             Self::InvalidIntValue(e) => write!(f, "{}", e),
+            Self::IntValueOutOfRange(e) => write!(f, "{}", e),
             Self::InvalidFloatValue(e) => write!(f, "{}", e),
             Self::MissingSubtype => f.write_str("missing subtype"),
             Self::InvalidSubtype(e) => write!(f, "{}", e),
@@ -572,7 +780,7 @@ impl FromStr for Value {
 
         match ty {
             Type::Char => parse_char(value).map(Self::Char),
-            Type::Int32 => parse_i32(value).map(Self::Int32),
+            Type::Int32 => parse_int(value),
             Type::Float => parse_f32(value).map(Self::Float),
             Type::String => Ok(Self::String(value.into())),
             Type::Hex => Ok(Self::Hex(value.into())),
@@ -609,6 +817,12 @@ fn parse_u32(s: &str) -> Result<u32, ParseError> {
     s.parse().map_err(ParseError::InvalidIntValue)
 }
 
+fn parse_int(s: &str) -> Result<Value, ParseError> {
+    s.parse::<i64>()
+        .map_err(ParseError::InvalidIntValue)
+        .and_then(|n| Value::try_from(n).map_err(ParseError::IntValueOutOfRange))
+}
+
 fn parse_f32(s: &str) -> Result<f32, ParseError> {
     s.parse().map_err(ParseError::InvalidFloatValue)
 }
@@ -667,6 +881,7 @@ mod tests {
     fn test_ty() {
         assert_eq!(Value::Char('n').ty(), Type::Char);
         assert_eq!(Value::Int32(0).ty(), Type::Int32);
+        assert_eq!(Value::UInt32(3000000000).ty(), Type::Int32);
         assert_eq!(Value::Float(0.0).ty(), Type::Float);
         assert_eq!(Value::String(String::from("noodles")).ty(), Type::String);
         assert_eq!(Value::Hex(String::from("cafe")).ty(), Type::Hex);
@@ -699,6 +914,7 @@ mod tests {
     fn test_fmt() {
         assert_eq!(Value::Char('n').to_string(), "n");
         assert_eq!(Value::Int32(13).to_string(), "13");
+        assert_eq!(Value::UInt32(3000000000).to_string(), "3000000000");
         assert_eq!(Value::Float(3.14).to_string(), "3.14");
 
         assert_eq!(
@@ -719,4 +935,74 @@ mod tests {
             "f,2.71,3.14"
         );
     }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("A:n".parse(), Ok(Value::Char('n')));
+        assert_eq!("i:13".parse(), Ok(Value::Int32(13)));
+        assert_eq!("i:-2147483648".parse(), Ok(Value::Int32(i32::MIN)));
+        assert_eq!("i:2147483648".parse(), Ok(Value::UInt32(2147483648)));
+        assert_eq!("i:4294967295".parse(), Ok(Value::UInt32(u32::MAX)));
+        assert_eq!("f:0.5".parse(), Ok(Value::Float(0.5)));
+        assert_eq!(
+            "Z:noodles".parse(),
+            Ok(Value::String(String::from("noodles")))
+        );
+        assert_eq!("H:cafe".parse(), Ok(Value::Hex(String::from("cafe"))));
+
+        assert_eq!("B:c,1,-2".parse(), Ok(Value::Int8Array(vec![1, -2])));
+        assert_eq!("B:C,3,5".parse(), Ok(Value::UInt8Array(vec![3, 5])));
+        assert_eq!("B:s,8,-13".parse(), Ok(Value::Int16Array(vec![8, -13])));
+        assert_eq!("B:S,21,34".parse(), Ok(Value::UInt16Array(vec![21, 34])));
+        assert_eq!("B:i,55,-89".parse(), Ok(Value::Int32Array(vec![55, -89])));
+        assert_eq!(
+            "B:I,144,233".parse(),
+            Ok(Value::UInt32Array(vec![144, 233]))
+        );
+        assert_eq!(
+            "B:f,2.71,8.13".parse(),
+            Ok(Value::FloatArray(vec![2.71, 8.13]))
+        );
+        assert_eq!("B:c".parse(), Ok(Value::Int8Array(Vec::new())));
+
+        assert!(matches!(
+            "i:4294967296".parse::<Value>(),
+            Err(ParseError::IntValueOutOfRange(_))
+        ));
+        assert!(matches!(
+            "i:-2147483649".parse::<Value>(),
+            Err(ParseError::IntValueOutOfRange(_))
+        ));
+
+        assert!(matches!(
+            "B:n,0".parse::<Value>(),
+            Err(ParseError::InvalidSubtype(_))
+        ));
+        assert!(matches!(
+            "B:C,256".parse::<Value>(),
+            Err(ParseError::InvalidIntValue(_))
+        ));
+        assert!(matches!(
+            "B:c,1,".parse::<Value>(),
+            Err(ParseError::InvalidIntValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_fmt_from_str_round_trip() {
+        let values = [
+            Value::Int8Array(vec![i8::MIN, i8::MAX]),
+            Value::UInt8Array(vec![u8::MIN, u8::MAX]),
+            Value::Int16Array(vec![i16::MIN, i16::MAX]),
+            Value::UInt16Array(vec![u16::MIN, u16::MAX]),
+            Value::Int32Array(vec![i32::MIN, i32::MAX]),
+            Value::UInt32Array(vec![u32::MIN, u32::MAX]),
+            Value::FloatArray(vec![-0.25, 1e-7]),
+        ];
+
+        for value in &values {
+            let s = format!("{}:{}", value.ty(), value);
+            assert_eq!(s.parse::<Value>().as_ref(), Ok(value));
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use noodles_core::Strand;
+
 bitflags::bitflags! {
     /// SAM record flags.
     #[derive(Default)]
@@ -107,6 +109,56 @@ impl Flags {
         self.contains(Self::MATE_REVERSE_COMPLEMENTED)
     }
 
+    /// Returns the strand of the alignment.
+    ///
+    /// This is unknown if the read is unmapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Strand;
+    /// use noodles_sam::record::Flags;
+    /// assert_eq!(Flags::empty().strand(), Strand::Forward);
+    /// assert_eq!(Flags::REVERSE_COMPLEMENTED.strand(), Strand::Reverse);
+    /// assert_eq!(Flags::UNMAPPED.strand(), Strand::Unknown);
+    /// ```
+    pub fn strand(self) -> Strand {
+        if self.is_unmapped() {
+            Strand::Unknown
+        } else if self.is_reverse_complemented() {
+            Strand::Reverse
+        } else {
+            Strand::Forward
+        }
+    }
+
+    /// Returns the strand of the mate alignment.
+    ///
+    /// This is irrelevant if the read is not paired and unknown if the mate is unmapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Strand;
+    /// use noodles_sam::record::Flags;
+    ///
+    /// let flags = Flags::PAIRED | Flags::MATE_REVERSE_COMPLEMENTED;
+    /// assert_eq!(flags.mate_strand(), Strand::Reverse);
+    ///
+    /// assert_eq!(Flags::empty().mate_strand(), Strand::Irrelevant);
+    /// ```
+    pub fn mate_strand(self) -> Strand {
+        if !self.is_paired() {
+            Strand::Irrelevant
+        } else if self.is_mate_unmapped() {
+            Strand::Unknown
+        } else if self.is_mate_reverse_complemented() {
+            Strand::Reverse
+        } else {
+            Strand::Forward
+        }
+    }
+
     /// Returns whether the `READ_1` flag is set.
     ///
     /// # Examples
@@ -3,25 +3,40 @@
 use std::{
     convert::TryFrom,
     error, fmt,
-    num::{self, NonZeroI32},
+    num::{self, NonZeroI64},
     str::FromStr,
 };
 
-pub(crate) const UNMAPPED: i32 = 0;
+pub(crate) const UNMAPPED: i64 = 0;
 
 /// A SAM record position.
 ///
 /// This represents a 1-based start position on the reference sequence. The value is guaranteed to
 /// be a positive, non-zero integer.
+///
+/// Positions are stored as 64-bit integers, which allows representing positions on reference
+/// sequences longer than 2^31 - 1 bases. Formats that cannot represent such positions (e.g., BAM
+/// and BAI, which are limited to positions up to 2^29) return an error when writing them.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Position(NonZeroI32);
+pub struct Position(NonZeroI64);
 
-impl From<Position> for i32 {
+impl From<Position> for i64 {
     fn from(position: Position) -> Self {
         Self::from(position.0)
     }
 }
 
+/// Converts a position to a 32-bit integer.
+///
+/// This fails if the position is > 2^31 - 1. Use `i64::from` for positions that may be larger.
+impl TryFrom<Position> for i32 {
+    type Error = num::TryFromIntError;
+
+    fn try_from(position: Position) -> Result<Self, Self::Error> {
+        Self::try_from(position.0.get())
+    }
+}
+
 /// An error returned when a raw SAM record position fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -46,14 +61,14 @@ impl FromStr for Position {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let n: i32 = s.parse().map_err(ParseError::Parse)?;
+        let n: i64 = s.parse().map_err(ParseError::Parse)?;
         Self::try_from(n).map_err(ParseError::Invalid)
     }
 }
 
 /// An error returned when a raw SAM record position fails to convert.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct TryFromIntError(i32);
+pub struct TryFromIntError(i64);
 
 impl error::Error for TryFromIntError {}
 
@@ -67,10 +82,18 @@ impl TryFrom<i32> for Position {
     type Error = TryFromIntError;
 
     fn try_from(n: i32) -> Result<Self, Self::Error> {
+        Self::try_from(i64::from(n))
+    }
+}
+
+impl TryFrom<i64> for Position {
+    type Error = TryFromIntError;
+
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
         if n < 0 {
             Err(TryFromIntError(n))
         } else {
-            NonZeroI32::new(n).map(Self).ok_or(TryFromIntError(n))
+            NonZeroI64::new(n).map(Self).ok_or(TryFromIntError(n))
         }
     }
 }
@@ -80,15 +103,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_from_position_for_i32() -> Result<(), TryFromIntError> {
-        assert_eq!(i32::from(Position::try_from(8)?), 8);
-        assert_eq!(i32::from(Position::try_from(13)?), 13);
+    fn test_from_position_for_i64() -> Result<(), TryFromIntError> {
+        assert_eq!(i64::from(Position::try_from(8)?), 8);
+        assert_eq!(i64::from(Position::try_from(13)?), 13);
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_position_for_i32() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(i32::try_from(Position::try_from(8)?)?, 8);
+        assert_eq!(i32::try_from(Position::try_from(13)?)?, 13);
+        assert!(i32::try_from(Position::try_from(2147483648i64)?).is_err());
         Ok(())
     }
 
     #[test]
     fn test_from_str() -> Result<(), TryFromIntError> {
         assert_eq!("13".parse(), Ok(Position::try_from(13)?));
+        assert_eq!("4294967296".parse(), Ok(Position::try_from(4294967296i64)?));
 
         assert!(matches!("".parse::<Position>(), Err(ParseError::Parse(_))));
         assert!(matches!(
@@ -112,7 +144,7 @@ mod tests {
     fn test_try_from_i32_for_position() -> Result<(), num::TryFromIntError> {
         assert_eq!(
             Position::try_from(13),
-            Ok(Position(NonZeroI32::try_from(13)?))
+            Ok(Position(NonZeroI64::try_from(13)?))
         );
 
         assert_eq!(Position::try_from(0), Err(TryFromIntError(0)));
@@ -120,4 +152,16 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_try_from_i64_for_position() -> Result<(), num::TryFromIntError> {
+        assert_eq!(
+            Position::try_from(4294967296i64),
+            Ok(Position(NonZeroI64::try_from(4294967296)?))
+        );
+
+        assert_eq!(Position::try_from(0i64), Err(TryFromIntError(0)));
+
+        Ok(())
+    }
 }
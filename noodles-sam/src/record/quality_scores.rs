@@ -67,12 +67,16 @@ impl FromStr for QualityScores {
         match s {
             "" => Err(ParseError::Empty),
             NULL_FIELD => Ok(Self::default()),
-            _ => s
-                .chars()
-                .map(Score::try_from)
-                .collect::<Result<Vec<_>, _>>()
-                .map(Self::from)
-                .map_err(ParseError::InvalidScore),
+            _ => {
+                let mut scores = Vec::with_capacity(s.len());
+
+                for b in s.bytes() {
+                    let score = Score::try_from(char::from(b)).map_err(ParseError::InvalidScore)?;
+                    scores.push(score);
+                }
+
+                Ok(Self::from(scores))
+            }
         }
     }
 }
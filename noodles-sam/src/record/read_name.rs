@@ -1,5 +1,9 @@
 //! SAM record read name.
 
+pub mod illumina;
+
+pub use self::illumina::IlluminaReadName;
+
 use std::{error, fmt, ops::Deref, str::FromStr};
 
 // § 1.4 The alignment section: mandatory fields (2020-07-19)
@@ -11,6 +15,24 @@ const MAX_LENGTH: usize = 254;
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ReadName(String);
 
+impl ReadName {
+    /// Parses the read name using Illumina read name conventions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::record::ReadName;
+    ///
+    /// let read_name: ReadName = "NDLS01:8:HNDLSBCXX:1:1101:10000:2000".parse()?;
+    /// let illumina_read_name = read_name.parse_illumina()?;
+    /// assert_eq!(illumina_read_name.tile(), 1101);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_illumina(&self) -> Result<IlluminaReadName, illumina::ParseError> {
+        self.0.parse()
+    }
+}
+
 impl Deref for ReadName {
     type Target = String;
 
@@ -0,0 +1,290 @@
+//! Illumina read name.
+
+use std::{error, fmt, num, str::FromStr};
+
+const FIELD_DELIMITER: char = ':';
+const LEGACY_INDEX_DELIMITER: char = '#';
+const LEGACY_READ_NUMBER_DELIMITER: char = '/';
+
+/// A parsed Illumina read name.
+///
+/// The following conventions are supported:
+///
+///   * CASAVA 1.8+: `<instrument>:<run>:<flowcell>:<lane>:<tile>:<x>:<y>[:<umi>]`, optionally
+///     followed by a comment `<read>:<is filtered>:<control number>:<index>`;
+///   * CASAVA <1.8: `<instrument>:<lane>:<tile>:<x>:<y>[#<index>][/<read>]`; and
+///   * SRA: `<accession>.<spot> <illumina read name> [...]`, where the original read name is the
+///     second whitespace-delimited field.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::record::read_name::IlluminaReadName;
+///
+/// let read_name: IlluminaReadName =
+///     "NDLS01:8:HNDLSBCXX:1:1101:10000:2000:ACGT 1:N:0:TTAGGC".parse()?;
+///
+/// assert_eq!(read_name.instrument(), "NDLS01");
+/// assert_eq!(read_name.run(), Some(8));
+/// assert_eq!(read_name.flowcell(), Some("HNDLSBCXX"));
+/// assert_eq!(read_name.lane(), 1);
+/// assert_eq!(read_name.tile(), 1101);
+/// assert_eq!(read_name.x(), 10000);
+/// assert_eq!(read_name.y(), 2000);
+/// assert_eq!(read_name.umi(), Some("ACGT"));
+/// assert_eq!(read_name.index(), Some("TTAGGC"));
+/// # Ok::<(), noodles_sam::record::read_name::illumina::ParseError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IlluminaReadName {
+    instrument: String,
+    run: Option<u32>,
+    flowcell: Option<String>,
+    lane: u32,
+    tile: u32,
+    x: u32,
+    y: u32,
+    umi: Option<String>,
+    index: Option<String>,
+}
+
+impl IlluminaReadName {
+    /// Returns the instrument ID.
+    pub fn instrument(&self) -> &str {
+        &self.instrument
+    }
+
+    /// Returns the run number.
+    ///
+    /// This is only set for CASAVA 1.8+ read names.
+    pub fn run(&self) -> Option<u32> {
+        self.run
+    }
+
+    /// Returns the flowcell ID.
+    ///
+    /// This is only set for CASAVA 1.8+ read names.
+    pub fn flowcell(&self) -> Option<&str> {
+        self.flowcell.as_deref()
+    }
+
+    /// Returns the flowcell lane.
+    pub fn lane(&self) -> u32 {
+        self.lane
+    }
+
+    /// Returns the tile number within the flowcell lane.
+    pub fn tile(&self) -> u32 {
+        self.tile
+    }
+
+    /// Returns the x-coordinate of the cluster within the tile.
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Returns the y-coordinate of the cluster within the tile.
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// Returns the unique molecular identifier (UMI).
+    pub fn umi(&self) -> Option<&str> {
+        self.umi.as_deref()
+    }
+
+    /// Returns the index (sample barcode) sequence or number.
+    pub fn index(&self) -> Option<&str> {
+        self.index.as_deref()
+    }
+}
+
+/// An error returned when a raw Illumina read name fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The number of fields is invalid.
+    InvalidFieldCount(usize),
+    /// A field is missing.
+    MissingField(&'static str),
+    /// A numeric field is invalid.
+    InvalidField(&'static str, num::ParseIntError),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidFieldCount(n) => {
+                write!(f, "invalid field count: expected 5, 7, or 8, got {}", n)
+            }
+            Self::MissingField(name) => write!(f, "missing field: {}", name),
+            Self::InvalidField(name, e) => write!(f, "invalid {}: {}", name, e),
+        }
+    }
+}
+
+impl FromStr for IlluminaReadName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let name = tokens.next().ok_or(ParseError::Empty)?;
+        let comment = tokens.next();
+
+        match parse_name(name, comment) {
+            Ok(read_name) => Ok(read_name),
+            Err(e) => match comment {
+                // SRA read names may keep the original read name as the second field.
+                Some(original_name) => parse_name(original_name, tokens.next()).map_err(|_| e),
+                None => Err(e),
+            },
+        }
+    }
+}
+
+fn parse_name(s: &str, comment: Option<&str>) -> Result<IlluminaReadName, ParseError> {
+    let fields: Vec<_> = s.split(FIELD_DELIMITER).collect();
+
+    match fields.len() {
+        7 | 8 => {
+            let index = comment
+                .and_then(|c| c.split(FIELD_DELIMITER).nth(3))
+                .filter(|t| !t.is_empty())
+                .map(|t| t.into());
+
+            Ok(IlluminaReadName {
+                instrument: parse_string(fields[0], "instrument")?,
+                run: parse_u32(fields[1], "run").map(Some)?,
+                flowcell: parse_string(fields[2], "flowcell").map(Some)?,
+                lane: parse_u32(fields[3], "lane")?,
+                tile: parse_u32(fields[4], "tile")?,
+                x: parse_u32(fields[5], "x")?,
+                y: parse_u32(fields[6], "y")?,
+                umi: fields.get(7).map(|t| String::from(*t)),
+                index,
+            })
+        }
+        5 => {
+            let (y, index) = parse_legacy_suffix(fields[4]);
+
+            Ok(IlluminaReadName {
+                instrument: parse_string(fields[0], "instrument")?,
+                run: None,
+                flowcell: None,
+                lane: parse_u32(fields[1], "lane")?,
+                tile: parse_u32(fields[2], "tile")?,
+                x: parse_u32(fields[3], "x")?,
+                y: parse_u32(y, "y")?,
+                umi: None,
+                index: index.map(|t| t.into()),
+            })
+        }
+        n => Err(ParseError::InvalidFieldCount(n)),
+    }
+}
+
+// Splits `<y>[#<index>][/<read>]`.
+fn parse_legacy_suffix(s: &str) -> (&str, Option<&str>) {
+    let s = match s.rfind(LEGACY_READ_NUMBER_DELIMITER) {
+        Some(i) => &s[..i],
+        None => s,
+    };
+
+    match s.find(LEGACY_INDEX_DELIMITER) {
+        Some(i) => (&s[..i], Some(&s[i + 1..]).filter(|t| !t.is_empty())),
+        None => (s, None),
+    }
+}
+
+fn parse_string(s: &str, name: &'static str) -> Result<String, ParseError> {
+    if s.is_empty() {
+        Err(ParseError::MissingField(name))
+    } else {
+        Ok(s.into())
+    }
+}
+
+fn parse_u32(s: &str, name: &'static str) -> Result<u32, ParseError> {
+    if s.is_empty() {
+        Err(ParseError::MissingField(name))
+    } else {
+        s.parse().map_err(|e| ParseError::InvalidField(name, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> Result<(), ParseError> {
+        let read_name: IlluminaReadName = "NDLS01:8:HNDLSBCXX:1:1101:10000:2000".parse()?;
+
+        assert_eq!(
+            read_name,
+            IlluminaReadName {
+                instrument: String::from("NDLS01"),
+                run: Some(8),
+                flowcell: Some(String::from("HNDLSBCXX")),
+                lane: 1,
+                tile: 1101,
+                x: 10000,
+                y: 2000,
+                umi: None,
+                index: None,
+            }
+        );
+
+        let read_name: IlluminaReadName = "HWUSI-EAS100R:6:73:941:1973#ACGT/1".parse()?;
+
+        assert_eq!(
+            read_name,
+            IlluminaReadName {
+                instrument: String::from("HWUSI-EAS100R"),
+                run: None,
+                flowcell: None,
+                lane: 6,
+                tile: 73,
+                x: 941,
+                y: 1973,
+                umi: None,
+                index: Some(String::from("ACGT")),
+            }
+        );
+
+        let read_name: IlluminaReadName =
+            "SRR001666.1 071112_SLXA-EAS1_s_7:5:1:817:345 length=36".parse()?;
+        assert_eq!(read_name.instrument(), "071112_SLXA-EAS1_s_7");
+        assert_eq!(read_name.lane(), 5);
+        assert_eq!(read_name.y(), 345);
+        assert!(read_name.index().is_none());
+
+        assert_eq!("".parse::<IlluminaReadName>(), Err(ParseError::Empty));
+        assert_eq!(
+            "r0".parse::<IlluminaReadName>(),
+            Err(ParseError::InvalidFieldCount(1))
+        );
+        assert_eq!(
+            "NDLS01:6::941:1973".parse::<IlluminaReadName>(),
+            Err(ParseError::MissingField("tile"))
+        );
+        assert!(matches!(
+            "NDLS01:6:73:941:y".parse::<IlluminaReadName>(),
+            Err(ParseError::InvalidField("y", _))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_legacy_suffix() {
+        assert_eq!(parse_legacy_suffix("1973"), ("1973", None));
+        assert_eq!(parse_legacy_suffix("1973#0/1"), ("1973", Some("0")));
+        assert_eq!(parse_legacy_suffix("1973#/1"), ("1973", None));
+        assert_eq!(parse_legacy_suffix("1973/2"), ("1973", None));
+    }
+}
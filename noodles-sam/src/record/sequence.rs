@@ -67,13 +67,18 @@ impl FromStr for Sequence {
         match s {
             "" => Err(ParseError::Empty),
             NULL_FIELD => Ok(Self::default()),
-            _ => s
-                .chars()
-                .map(|c| c.to_ascii_uppercase())
-                .map(Base::try_from)
-                .collect::<Result<Vec<_>, _>>()
-                .map(Self::from)
-                .map_err(ParseError::InvalidBase),
+            _ => {
+                let mut bases = Vec::with_capacity(s.len());
+
+                for b in s.bytes() {
+                    let base = Base::try_from(char::from(b.to_ascii_uppercase()))
+                        .map_err(ParseError::InvalidBase)?;
+
+                    bases.push(base);
+                }
+
+                Ok(Self::from(bases))
+            }
         }
     }
 }
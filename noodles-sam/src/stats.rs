@@ -0,0 +1,6 @@
+//! Alignment statistics.
+
+pub mod coverage;
+pub mod library_complexity;
+
+pub use self::library_complexity::{library_complexity, LibraryComplexity};
@@ -0,0 +1,561 @@
+//! Window-based coverage summaries.
+//!
+//! The per-base read depth of a coordinate-sorted stream of records is summarized over fixed-size
+//! windows that tile each reference sequence. Windows are emitted in reference sequence order,
+//! including windows with no coverage, so the output can be written directly as a bedGraph, e.g.,
+//! for conversion to bigWig.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, io,
+};
+
+use noodles_core::Strand;
+
+use crate::{
+    header::ReferenceSequences,
+    record::{cigar::op::Kind, Flags},
+    Record,
+};
+
+/// A statistic used to summarize the depths in a window.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Statistic {
+    /// The mean depth.
+    #[default]
+    Mean,
+    /// The median depth.
+    Median,
+}
+
+/// A normalization of window values.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Normalization {
+    /// No normalization.
+    #[default]
+    None,
+    /// Counts per million: values are scaled by `10^6 / total_reads`.
+    Cpm(u64),
+    /// Reads per kilobase per million: values are scaled by
+    /// `10^9 / (total_reads * window_size)`.
+    Rpkm(u64),
+}
+
+impl Normalization {
+    fn scale_factor(self, window_size: u64) -> f64 {
+        match self {
+            Self::None => 1.0,
+            Self::Cpm(total_reads) => 1e6 / total_reads.max(1) as f64,
+            Self::Rpkm(total_reads) => 1e9 / (total_reads.max(1) * window_size) as f64,
+        }
+    }
+}
+
+/// A summarized window.
+///
+/// Positions are 1-based and inclusive. Its [`fmt::Display`] implementation writes a bedGraph
+/// line, which uses 0-based, half-open intervals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Window {
+    reference_sequence_name: String,
+    start: i64,
+    end: i64,
+    value: f64,
+}
+
+impl Window {
+    /// Returns the reference sequence name of the window.
+    pub fn reference_sequence_name(&self) -> &str {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the start position of the window.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the end position of the window.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns the summarized value of the window.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}",
+            self.reference_sequence_name,
+            self.start - 1,
+            self.end,
+            self.value
+        )
+    }
+}
+
+/// A window-based coverage summarizer.
+///
+/// Unmapped, secondary, supplementary, QC-failed, and duplicate records are skipped. Only bases
+/// in alignment matches (`M`, `=`, and `X`) add to the depth.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, stats::coverage::{Summarizer, Statistic}};
+///
+/// let data = b"@HD\tVN:1.6\tSO:coordinate
+/// @SQ\tSN:sq0\tLN:8
+/// r0\t0\tsq0\t1\t60\t4M\t*\t0\t0\tACGT\tNDLS
+/// r1\t0\tsq0\t3\t60\t4M\t*\t0\t0\tACGT\tNDLS
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// let header: sam::Header = reader.read_header()?.parse().map_err(|e| {
+///     io::Error::new(io::ErrorKind::InvalidData, e)
+/// })?;
+///
+/// let summarizer = Summarizer::new(4).set_statistic(Statistic::Mean);
+/// let windows = summarizer
+///     .summarize(reader.records(), header.reference_sequences())
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(windows.len(), 2);
+/// assert_eq!(windows[0].to_string(), "sq0\t0\t4\t1.5");
+/// assert_eq!(windows[1].to_string(), "sq0\t4\t8\t0.5");
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Summarizer {
+    window_size: u64,
+    statistic: Statistic,
+    normalization: Normalization,
+    strand: Strand,
+}
+
+impl Summarizer {
+    /// Creates a coverage summarizer with the given window size.
+    ///
+    /// The window size is at least 1. By default, the mean depth of each window is reported
+    /// without normalization.
+    pub fn new(window_size: u64) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            statistic: Statistic::default(),
+            normalization: Normalization::default(),
+            strand: Strand::default(),
+        }
+    }
+
+    /// Sets the statistic used to summarize each window.
+    pub fn set_statistic(mut self, statistic: Statistic) -> Self {
+        self.statistic = statistic;
+        self
+    }
+
+    /// Sets the normalization of window values.
+    pub fn set_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Sets the strand of records to count.
+    ///
+    /// If the strand is forward or reverse, only records aligned to that strand are counted.
+    /// Otherwise, all records are counted, which is the default.
+    pub fn set_strand(mut self, strand: Strand) -> Self {
+        self.strand = strand;
+        self
+    }
+
+    /// Returns an iterator over summarized windows of a coordinate-sorted stream of records.
+    ///
+    /// Windows tile each reference sequence in the given dictionary. The last window of a
+    /// reference sequence may be shorter than the window size.
+    ///
+    /// The iterator returns an error if a record is on a reference sequence not in the dictionary
+    /// or if the records are not sorted by reference sequence.
+    pub fn summarize<I>(
+        &self,
+        records: I,
+        reference_sequences: &ReferenceSequences,
+    ) -> Windows<I::IntoIter>
+    where
+        I: IntoIterator<Item = io::Result<Record>>,
+    {
+        let reference_sequences: Vec<_> = reference_sequences
+            .values()
+            .map(|rs| (rs.name().to_string(), i64::from(rs.len())))
+            .collect();
+
+        let reference_sequence_indices = reference_sequences
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.clone(), i))
+            .collect();
+
+        Windows {
+            records: records.into_iter(),
+            summarizer: self.clone(),
+            reference_sequences,
+            reference_sequence_indices,
+            reference_sequence_index: 0,
+            window_start: 1,
+            depths: VecDeque::new(),
+            windows: VecDeque::new(),
+            is_eof: false,
+        }
+    }
+}
+
+/// An iterator over summarized coverage windows.
+///
+/// This is created by calling [`Summarizer::summarize`].
+pub struct Windows<I> {
+    records: I,
+    summarizer: Summarizer,
+    reference_sequences: Vec<(String, i64)>,
+    reference_sequence_indices: HashMap<String, usize>,
+    reference_sequence_index: usize,
+    window_start: i64,
+    depths: VecDeque<u32>,
+    windows: VecDeque<Window>,
+    is_eof: bool,
+}
+
+impl<I> Windows<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    fn window_size(&self) -> i64 {
+        self.summarizer.window_size as i64
+    }
+
+    fn reference_sequence_len(&self) -> i64 {
+        self.reference_sequences
+            .get(self.reference_sequence_index)
+            .map(|(_, len)| *len)
+            .unwrap_or(0)
+    }
+
+    // Completes all windows that end before the given position.
+    fn complete_windows_before(&mut self, position: i64) {
+        let len = self.reference_sequence_len();
+
+        while self.window_start <= len && self.window_start + self.window_size() - 1 < position {
+            self.complete_window();
+        }
+    }
+
+    fn complete_reference_sequence(&mut self) {
+        self.complete_windows_before(i64::MAX);
+        self.reference_sequence_index += 1;
+        self.window_start = 1;
+        self.depths.clear();
+    }
+
+    fn complete_window(&mut self) {
+        let name = self.reference_sequences[self.reference_sequence_index]
+            .0
+            .clone();
+
+        let start = self.window_start;
+        let end = (start + self.window_size() - 1).min(self.reference_sequence_len());
+        let len = (end - start + 1) as usize;
+
+        let mut depths: Vec<u32> = self.depths.iter().take(len).copied().collect();
+        depths.resize(len, 0);
+
+        let value = match self.summarizer.statistic {
+            Statistic::Mean => mean(&depths),
+            Statistic::Median => median(&mut depths),
+        };
+
+        let scale_factor = self
+            .summarizer
+            .normalization
+            .scale_factor(self.summarizer.window_size);
+
+        self.windows.push_back(Window {
+            reference_sequence_name: name,
+            start,
+            end,
+            value: value * scale_factor,
+        });
+
+        let n = len.min(self.depths.len());
+        self.depths.drain(..n);
+        self.window_start = end + 1;
+    }
+
+    fn add_record(&mut self, record: &Record) -> io::Result<()> {
+        let flags = record.flags();
+
+        if is_filtered(flags) {
+            return Ok(());
+        }
+
+        let strand = self.summarizer.strand;
+
+        if strand.is_known() && flags.strand() != strand {
+            return Ok(());
+        }
+
+        let (name, start) = match (record.reference_sequence_name(), record.position()) {
+            (Some(name), Some(position)) => (name, i64::from(position)),
+            _ => return Ok(()),
+        };
+
+        let index = self
+            .reference_sequence_indices
+            .get(name.as_str())
+            .copied()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("missing reference sequence: {}", name),
+                )
+            })?;
+
+        if index < self.reference_sequence_index {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "records are not sorted by reference sequence",
+            ));
+        }
+
+        while self.reference_sequence_index < index {
+            self.complete_reference_sequence();
+        }
+
+        self.complete_windows_before(start);
+
+        let len = self.reference_sequence_len();
+        let mut position = start;
+
+        for op in record.cigar().iter() {
+            let op_len = i64::from(op.len());
+
+            match op.kind() {
+                Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                    let end = (position + op_len - 1).min(len);
+
+                    for p in position.max(self.window_start)..=end {
+                        let i = (p - self.window_start) as usize;
+
+                        if i >= self.depths.len() {
+                            self.depths.resize(i + 1, 0);
+                        }
+
+                        self.depths[i] += 1;
+                    }
+
+                    position += op_len;
+                }
+                Kind::Deletion | Kind::Skip => position += op_len,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<I> Iterator for Windows<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Window>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(window) = self.windows.pop_front() {
+                return Some(Ok(window));
+            }
+
+            if self.is_eof {
+                return None;
+            }
+
+            match self.records.next() {
+                Some(Ok(record)) => {
+                    if let Err(e) = self.add_record(&record) {
+                        self.is_eof = true;
+                        return Some(Err(e));
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    while self.reference_sequence_index < self.reference_sequences.len() {
+                        self.complete_reference_sequence();
+                    }
+
+                    self.is_eof = true;
+                }
+            }
+        }
+    }
+}
+
+fn is_filtered(flags: Flags) -> bool {
+    flags.is_unmapped()
+        || flags.is_secondary()
+        || flags.is_supplementary()
+        || flags.is_qc_fail()
+        || flags.is_duplicate()
+}
+
+fn mean(depths: &[u32]) -> f64 {
+    if depths.is_empty() {
+        0.0
+    } else {
+        depths.iter().map(|&d| f64::from(d)).sum::<f64>() / depths.len() as f64
+    }
+}
+
+fn median(depths: &mut [u32]) -> f64 {
+    if depths.is_empty() {
+        return 0.0;
+    }
+
+    depths.sort_unstable();
+
+    let mid = depths.len() / 2;
+
+    if depths.len().is_multiple_of(2) {
+        (f64::from(depths[mid - 1]) + f64::from(depths[mid])) / 2.0
+    } else {
+        f64::from(depths[mid])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{header::ReferenceSequence, record::Position};
+
+    use super::*;
+
+    fn build_record(name: &str, position: i64, cigar: &str) -> io::Result<Record> {
+        build_record_with_flags(Flags::empty(), name, position, cigar)
+    }
+
+    fn build_record_with_flags(
+        flags: Flags,
+        name: &str,
+        position: i64,
+        cigar: &str,
+    ) -> io::Result<Record> {
+        Ok(Record::builder()
+            .set_flags(flags)
+            .set_reference_sequence_name(name.parse().unwrap())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .build())
+    }
+
+    fn build_reference_sequences() -> ReferenceSequences {
+        vec![
+            ReferenceSequence::new(String::from("sq0"), 10),
+            ReferenceSequence::new(String::from("sq1"), 4),
+            ReferenceSequence::new(String::from("sq2"), 3),
+        ]
+        .into_iter()
+        .map(|rs| (rs.name().into(), rs))
+        .collect()
+    }
+
+    #[test]
+    fn test_summarize() -> io::Result<()> {
+        let reference_sequences = build_reference_sequences();
+
+        let records = vec![
+            build_record("sq0", 1, "2M2D2M"),
+            build_record("sq0", 3, "6M"),
+            build_record("sq1", 2, "2S3M"),
+        ];
+
+        let windows: Vec<_> = Summarizer::new(4)
+            .summarize(records, &reference_sequences)
+            .collect::<io::Result<_>>()?;
+
+        let actual: Vec<_> = windows.iter().map(|w| w.to_string()).collect();
+
+        assert_eq!(
+            actual,
+            [
+                "sq0\t0\t4\t1",
+                "sq0\t4\t8\t1.5",
+                "sq0\t8\t10\t0",
+                "sq1\t0\t4\t0.75",
+                "sq2\t0\t3\t0",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_with_median_and_normalization() -> io::Result<()> {
+        let reference_sequences = build_reference_sequences();
+
+        let records = vec![build_record("sq0", 1, "3M"), build_record("sq0", 1, "1M")];
+
+        let windows: Vec<_> = Summarizer::new(4)
+            .set_statistic(Statistic::Median)
+            .set_normalization(Normalization::Cpm(2000000))
+            .summarize(records, &reference_sequences)
+            .collect::<io::Result<_>>()?;
+
+        // depths: [2, 1, 1, 0]
+        assert_eq!(windows[0].value(), 0.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_with_strand() -> io::Result<()> {
+        let reference_sequences = build_reference_sequences();
+
+        let records = vec![
+            build_record("sq0", 1, "4M"),
+            build_record_with_flags(Flags::REVERSE_COMPLEMENTED, "sq0", 1, "2M"),
+        ];
+
+        let windows: Vec<_> = Summarizer::new(4)
+            .set_strand(Strand::Reverse)
+            .summarize(records, &reference_sequences)
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(windows[0].value(), 0.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_with_unsorted_records() {
+        let reference_sequences = build_reference_sequences();
+
+        let records = vec![build_record("sq1", 1, "1M"), build_record("sq0", 1, "1M")];
+
+        let result: io::Result<Vec<_>> = Summarizer::new(4)
+            .summarize(records, &reference_sequences)
+            .collect();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), 0.0);
+        assert_eq!(median(&mut [3, 1, 2]), 2.0);
+        assert_eq!(median(&mut [4, 1, 3, 2]), 2.5);
+    }
+}
@@ -0,0 +1,500 @@
+//! Library complexity estimation.
+//!
+//! Reads (or read pairs) are grouped by their unclipped 5' start sites and strands. Reads that
+//! share a start site with an earlier read are considered duplicates. The library size, i.e., the
+//! number of unique molecules, is then estimated using the Lander-Waterman model, as in Picard
+//! `EstimateLibraryComplexity`.
+//!
+//! Duplicates are further classified as optical duplicates if their read names can be parsed as
+//! Illumina read names and they are near another read with the same start site on the same tile,
+//! as in Picard `MarkDuplicates`. The remaining duplicates are PCR duplicates.
+//!
+//! Records are expected to be coordinate-sorted. Start sites are only tracked for the current
+//! reference sequence, so memory usage is bounded by the number of unique start sites on a single
+//! reference sequence.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io,
+};
+
+use crate::{
+    record::{cigar::op::Kind, read_name::IlluminaReadName},
+    Record,
+};
+
+const MAX_BISECTION_ITERATIONS: usize = 40;
+
+// Picard `MarkDuplicates` `OPTICAL_DUPLICATE_PIXEL_DISTANCE`
+const DEFAULT_OPTICAL_DUPLICATE_PIXEL_DISTANCE: u32 = 100;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct StartSite {
+    position: i64,
+    is_reverse_complemented: bool,
+    mate: Option<(String, i64, bool)>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Location {
+    flowcell: Option<String>,
+    lane: u32,
+    tile: u32,
+    x: u32,
+    y: u32,
+}
+
+impl Location {
+    fn is_near(&self, other: &Self, distance: u32) -> bool {
+        self.flowcell == other.flowcell
+            && self.lane == other.lane
+            && self.tile == other.tile
+            && self.x.abs_diff(other.x) <= distance
+            && self.y.abs_diff(other.y) <= distance
+    }
+}
+
+impl From<IlluminaReadName> for Location {
+    fn from(read_name: IlluminaReadName) -> Self {
+        Self {
+            flowcell: read_name.flowcell().map(|s| s.into()),
+            lane: read_name.lane(),
+            tile: read_name.tile(),
+            x: read_name.x(),
+            y: read_name.y(),
+        }
+    }
+}
+
+/// A library complexity estimator.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use noodles_sam::{
+///     self as sam,
+///     record::{Flags, Position},
+///     stats::LibraryComplexity,
+/// };
+///
+/// let record = sam::Record::builder()
+///     .set_flags(Flags::empty())
+///     .set_reference_sequence_name("sq0".parse()?)
+///     .set_position(Position::try_from(8)?)
+///     .set_cigar("4M".parse()?)
+///     .build();
+///
+/// let mut library_complexity = LibraryComplexity::new();
+/// library_complexity.add(&record);
+/// library_complexity.add(&record);
+///
+/// assert_eq!(library_complexity.reads(), 2);
+/// assert_eq!(library_complexity.unique_reads(), 1);
+/// assert_eq!(library_complexity.duplication_rate(), 0.5);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LibraryComplexity {
+    reads: u64,
+    unique_reads: u64,
+    optical_duplicate_reads: u64,
+    optical_duplicate_pixel_distance: u32,
+    reference_sequence_name: Option<String>,
+    start_sites: HashMap<StartSite, Vec<Location>>,
+}
+
+impl LibraryComplexity {
+    /// Creates a library complexity estimator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::stats::LibraryComplexity;
+    /// let library_complexity = LibraryComplexity::new();
+    /// assert_eq!(library_complexity.reads(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum distance between two reads on the same tile, in pixels, for a duplicate
+    /// to be classified as an optical duplicate.
+    ///
+    /// The default is 100, which is suitable for unpatterned flowcells. Patterned flowcells
+    /// typically use a larger distance, e.g., 2500.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::stats::LibraryComplexity;
+    /// let library_complexity =
+    ///     LibraryComplexity::new().set_optical_duplicate_pixel_distance(2500);
+    /// ```
+    pub fn set_optical_duplicate_pixel_distance(mut self, distance: u32) -> Self {
+        self.optical_duplicate_pixel_distance = distance;
+        self
+    }
+
+    /// Adds a record.
+    ///
+    /// Unmapped, secondary, supplementary, and QC-failed records are skipped. For paired reads,
+    /// only the first segment is counted, and its start site includes the position and strand of
+    /// its mate, i.e., read pairs are counted once.
+    ///
+    /// A duplicate is an optical duplicate if its Illumina read name places it within the optical
+    /// duplicate pixel distance of an earlier read with the same start site.
+    pub fn add(&mut self, record: &Record) {
+        let flags = record.flags();
+
+        if flags.is_unmapped()
+            || flags.is_secondary()
+            || flags.is_supplementary()
+            || flags.is_qc_fail()
+            || (flags.is_paired() && !flags.is_read_1())
+        {
+            return;
+        }
+
+        let (reference_sequence_name, position) = match (
+            record.reference_sequence_name(),
+            five_prime_position(record),
+        ) {
+            (Some(name), Some(position)) => (name.to_string(), position),
+            _ => return,
+        };
+
+        if self.reference_sequence_name.as_ref() != Some(&reference_sequence_name) {
+            self.start_sites.clear();
+            self.reference_sequence_name = Some(reference_sequence_name);
+        }
+
+        let mate = if flags.is_paired() && !flags.is_mate_unmapped() {
+            match (
+                record.mate_reference_sequence_name(),
+                record.mate_position(),
+            ) {
+                (Some(name), Some(position)) => Some((
+                    name.to_string(),
+                    i64::from(position),
+                    flags.is_mate_reverse_complemented(),
+                )),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let start_site = StartSite {
+            position,
+            is_reverse_complemented: flags.is_reverse_complemented(),
+            mate,
+        };
+
+        let location = record
+            .read_name()
+            .and_then(|read_name| read_name.parse_illumina().ok())
+            .map(Location::from);
+
+        self.reads += 1;
+
+        match self.start_sites.entry(start_site) {
+            Entry::Vacant(entry) => {
+                self.unique_reads += 1;
+                entry.insert(location.into_iter().collect());
+            }
+            Entry::Occupied(mut entry) => {
+                if let Some(location) = location {
+                    let distance = self.optical_duplicate_pixel_distance;
+
+                    if entry.get().iter().any(|l| l.is_near(&location, distance)) {
+                        self.optical_duplicate_reads += 1;
+                    }
+
+                    entry.get_mut().push(location);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of counted reads (or read pairs).
+    pub fn reads(&self) -> u64 {
+        self.reads
+    }
+
+    /// Returns the number of counted reads (or read pairs) with unique start sites.
+    pub fn unique_reads(&self) -> u64 {
+        self.unique_reads
+    }
+
+    /// Returns the number of counted reads (or read pairs) that are duplicates.
+    pub fn duplicate_reads(&self) -> u64 {
+        self.reads - self.unique_reads
+    }
+
+    /// Returns the number of counted reads (or read pairs) that are optical duplicates.
+    pub fn optical_duplicate_reads(&self) -> u64 {
+        self.optical_duplicate_reads
+    }
+
+    /// Returns the number of counted reads (or read pairs) that are PCR duplicates, i.e.,
+    /// duplicates that are not optical duplicates.
+    pub fn pcr_duplicate_reads(&self) -> u64 {
+        self.duplicate_reads() - self.optical_duplicate_reads
+    }
+
+    /// Returns the fraction of counted reads (or read pairs) that are duplicates.
+    ///
+    /// This is 0 if no reads were counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::stats::LibraryComplexity;
+    /// assert_eq!(LibraryComplexity::new().duplication_rate(), 0.0);
+    /// ```
+    pub fn duplication_rate(&self) -> f64 {
+        if self.reads == 0 {
+            0.0
+        } else {
+            self.duplicate_reads() as f64 / self.reads as f64
+        }
+    }
+
+    /// Returns the estimated number of unique molecules in the library.
+    ///
+    /// This solves the Lander-Waterman equation `C / X = 1 - exp(-N / X)`, where `N` is the
+    /// number of reads excluding optical duplicates, `C` is the number of unique reads, and `X`
+    /// is the library size.
+    ///
+    /// This returns `None` if there are no duplicates, i.e., the library size cannot be
+    /// estimated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::stats::LibraryComplexity;
+    /// assert!(LibraryComplexity::new().estimated_library_size().is_none());
+    /// ```
+    pub fn estimated_library_size(&self) -> Option<u64> {
+        estimate_library_size(self.reads - self.optical_duplicate_reads, self.unique_reads)
+    }
+
+    /// Returns the expected number of unique reads (or read pairs) if `reads` were sequenced.
+    ///
+    /// This extrapolates using the estimated library size and returns `None` if it cannot be
+    /// estimated.
+    pub fn expected_unique_reads(&self, reads: u64) -> Option<f64> {
+        self.estimated_library_size().map(|library_size| {
+            let x = library_size as f64;
+            x * (1.0 - (-(reads as f64) / x).exp())
+        })
+    }
+}
+
+impl Default for LibraryComplexity {
+    fn default() -> Self {
+        Self {
+            reads: 0,
+            unique_reads: 0,
+            optical_duplicate_reads: 0,
+            optical_duplicate_pixel_distance: DEFAULT_OPTICAL_DUPLICATE_PIXEL_DISTANCE,
+            reference_sequence_name: None,
+            start_sites: HashMap::new(),
+        }
+    }
+}
+
+/// Estimates the library complexity of a coordinate-sorted stream of records.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, stats};
+///
+/// let data = b"@HD\tVN:1.6\tSO:coordinate
+/// r0\t0\tsq0\t8\t60\t4M\t*\t0\t0\tACGT\tNDLS
+/// r1\t0\tsq0\t8\t60\t4M\t*\t0\t0\tACGT\tNDLS
+/// r2\t16\tsq0\t8\t60\t4M\t*\t0\t0\tACGT\tNDLS
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// reader.read_header()?;
+///
+/// let library_complexity = stats::library_complexity(reader.records())?;
+/// assert_eq!(library_complexity.reads(), 3);
+/// assert_eq!(library_complexity.unique_reads(), 2);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn library_complexity<I>(records: I) -> io::Result<LibraryComplexity>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    let mut library_complexity = LibraryComplexity::new();
+
+    for result in records {
+        let record = result?;
+        library_complexity.add(&record);
+    }
+
+    Ok(library_complexity)
+}
+
+// Returns the unclipped 5' position, i.e., the unclipped start of forward reads or the unclipped
+// end of reverse reads.
+fn five_prime_position(record: &Record) -> Option<i64> {
+    let start = record.position().map(i64::from)?;
+    let cigar = record.cigar();
+
+    let is_clip = |kind| matches!(kind, Kind::SoftClip | Kind::HardClip);
+
+    if record.flags().is_reverse_complemented() {
+        let end = start + i64::from(cigar.reference_len()).max(1) - 1;
+
+        let trailing_clip_len: i64 = cigar
+            .iter()
+            .rev()
+            .take_while(|op| is_clip(op.kind()))
+            .map(|op| i64::from(op.len()))
+            .sum();
+
+        Some(end + trailing_clip_len)
+    } else {
+        let leading_clip_len: i64 = cigar
+            .iter()
+            .take_while(|op| is_clip(op.kind()))
+            .map(|op| i64::from(op.len()))
+            .sum();
+
+        Some(start - leading_clip_len)
+    }
+}
+
+// Picard `DuplicationMetrics.estimateLibrarySize`
+fn estimate_library_size(reads: u64, unique_reads: u64) -> Option<u64> {
+    let n = reads as f64;
+    let c = unique_reads as f64;
+
+    // f(x) = c / x - 1 + exp(-n / x)
+    let f = |x: f64| c / x - 1.0 + (-n / x).exp();
+
+    if unique_reads == 0 || unique_reads >= reads || f(c) < 0.0 {
+        return None;
+    }
+
+    let mut lo = 1.0;
+    let mut hi = 100.0;
+
+    while f(hi * c) > 0.0 {
+        hi *= 10.0;
+    }
+
+    for _ in 0..MAX_BISECTION_ITERATIONS {
+        let r = (lo + hi) / 2.0;
+        let u = f(r * c);
+
+        if u == 0.0 {
+            break;
+        } else if u > 0.0 {
+            lo = r;
+        } else {
+            hi = r;
+        }
+    }
+
+    Some((c * (lo + hi) / 2.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::record::{Flags, Position};
+
+    use super::*;
+
+    fn build_record(flags: Flags, position: i64, cigar: &str) -> Record {
+        Record::builder()
+            .set_flags(flags)
+            .set_reference_sequence_name("sq0".parse().unwrap())
+            .set_position(Position::try_from(position).unwrap())
+            .set_cigar(cigar.parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_five_prime_position() {
+        let record = build_record(Flags::empty(), 8, "2H3S4M");
+        assert_eq!(five_prime_position(&record), Some(3));
+
+        let record = build_record(Flags::REVERSE_COMPLEMENTED, 8, "3S4M1D2M2S1H");
+        assert_eq!(five_prime_position(&record), Some(17));
+    }
+
+    #[test]
+    fn test_add() {
+        let mut library_complexity = LibraryComplexity::new();
+
+        // Soft clipping does not change the unclipped start site.
+        library_complexity.add(&build_record(Flags::empty(), 8, "4M"));
+        library_complexity.add(&build_record(Flags::empty(), 10, "2S4M"));
+        library_complexity.add(&build_record(Flags::REVERSE_COMPLEMENTED, 8, "4M"));
+        library_complexity.add(&build_record(Flags::SECONDARY, 8, "4M"));
+        library_complexity.add(&build_record(Flags::UNMAPPED, 8, "4M"));
+        library_complexity.add(&build_record(Flags::PAIRED | Flags::READ_2, 8, "4M"));
+
+        assert_eq!(library_complexity.reads(), 3);
+        assert_eq!(library_complexity.unique_reads(), 2);
+        assert_eq!(library_complexity.duplicate_reads(), 1);
+    }
+
+    #[test]
+    fn test_add_with_optical_duplicates() {
+        let build_named_record = |read_name: &str| {
+            Record::builder()
+                .set_flags(Flags::empty())
+                .set_read_name(read_name.parse().unwrap())
+                .set_reference_sequence_name("sq0".parse().unwrap())
+                .set_position(Position::try_from(8).unwrap())
+                .set_cigar("4M".parse().unwrap())
+                .build()
+        };
+
+        let mut library_complexity = LibraryComplexity::new();
+
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1101:1000:1000"));
+        // near the first read
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1101:1050:1100"));
+        // different tile
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1102:1000:1000"));
+        // far from all other reads
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1101:5000:5000"));
+        // not an Illumina read name
+        library_complexity.add(&build_named_record("r0"));
+
+        assert_eq!(library_complexity.reads(), 5);
+        assert_eq!(library_complexity.unique_reads(), 1);
+        assert_eq!(library_complexity.duplicate_reads(), 4);
+        assert_eq!(library_complexity.optical_duplicate_reads(), 1);
+        assert_eq!(library_complexity.pcr_duplicate_reads(), 3);
+
+        let mut library_complexity =
+            LibraryComplexity::new().set_optical_duplicate_pixel_distance(10);
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1101:1000:1000"));
+        library_complexity.add(&build_named_record("NDLS01:8:FC0:1:1101:1050:1100"));
+        assert_eq!(library_complexity.optical_duplicate_reads(), 0);
+    }
+
+    #[test]
+    fn test_estimate_library_size() {
+        assert!(estimate_library_size(0, 0).is_none());
+        assert!(estimate_library_size(100, 100).is_none());
+
+        // C / X = 1 - exp(-N / X)
+        let library_size = estimate_library_size(1000, 800).unwrap() as f64;
+        let expected = library_size * (1.0 - (-1000.0 / library_size).exp());
+        assert!((expected - 800.0).abs() < 1.0);
+    }
+}
@@ -0,0 +1,547 @@
+//! Record transform pipeline.
+//!
+//! A transform edits a record in place and decides whether it is kept. Transforms are composed
+//! into a [`Pipeline`], which is typically applied to records between a reader and a writer.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature = "io", doc = "```")]
+#![cfg_attr(not(feature = "io"), doc = "```ignore")]
+//! # use std::io;
+//! use noodles_sam::{
+//!     self as sam,
+//!     record::data::field::Tag,
+//!     transform::{ClearDuplicateFlags, Context, Pipeline, SetReadGroup, StripTags},
+//! };
+//!
+//! let data = b"@HD\tVN:1.6
+//! @RG\tID:rg0
+//! r0\t1024\t*\t0\t255\t*\t*\t0\t0\t*\t*\tNM:i:0\tRG:Z:rg1
+//! ";
+//!
+//! let mut reader = sam::Reader::new(&data[..]);
+//! let header: sam::Header = reader.read_header()?.parse().map_err(|e| {
+//!     io::Error::new(io::ErrorKind::InvalidData, e)
+//! })?;
+//!
+//! let mut pipeline = Pipeline::new()
+//!     .add_transform(StripTags::new(vec![Tag::EditDistance]))
+//!     .add_transform(SetReadGroup::new(String::from("rg0")))
+//!     .add_transform(ClearDuplicateFlags);
+//!
+//! let context = Context::new(&header);
+//!
+//! let mut writer = sam::Writer::new(Vec::new());
+//!
+//! for result in pipeline.transform_records(reader.records(), &context) {
+//!     let record = result?;
+//!     writer.write_record(&record)?;
+//! }
+//!
+//! assert_eq!(writer.get_ref(), b"r0\t0\t*\t0\t255\t*\t*\t0\t0\t*\t*\tRG:Z:rg0\n");
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::io;
+
+use super::{
+    record::{
+        data::{
+            field::{Tag, Value},
+            Field,
+        },
+        Flags,
+    },
+    Header, Record,
+};
+
+/// The outcome of a transform.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Keep the record and continue to the next transform.
+    Keep,
+    /// Discard the record. No further transforms are applied.
+    Discard,
+}
+
+/// Shared state available to transforms.
+#[derive(Clone, Copy, Debug)]
+pub struct Context<'a> {
+    header: &'a Header,
+}
+
+impl<'a> Context<'a> {
+    /// Creates a transform context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, transform::Context};
+    /// let header = sam::Header::default();
+    /// let context = Context::new(&header);
+    /// ```
+    pub fn new(header: &'a Header) -> Self {
+        Self { header }
+    }
+
+    /// Returns the SAM header of the records being transformed.
+    pub fn header(&self) -> &'a Header {
+        self.header
+    }
+}
+
+/// A record transform.
+///
+/// This is implemented for closures `FnMut(&mut Record, &Context) -> io::Result<Action>`.
+pub trait Transform {
+    /// Applies the transform to a record.
+    fn apply(&mut self, record: &mut Record, context: &Context<'_>) -> io::Result<Action>;
+}
+
+impl<F> Transform for F
+where
+    F: FnMut(&mut Record, &Context<'_>) -> io::Result<Action>,
+{
+    fn apply(&mut self, record: &mut Record, context: &Context<'_>) -> io::Result<Action> {
+        self(record, context)
+    }
+}
+
+/// A list of transforms applied in order.
+#[derive(Default)]
+pub struct Pipeline {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::transform::Pipeline;
+    /// let pipeline = Pipeline::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transform to the pipeline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     transform::{Action, ClearDuplicateFlags, Context, Pipeline},
+    /// };
+    ///
+    /// let pipeline = Pipeline::new()
+    ///     .add_transform(ClearDuplicateFlags)
+    ///     .add_transform(|record: &mut sam::Record, _: &Context<'_>| {
+    ///         if record.flags().is_unmapped() {
+    ///             Ok(Action::Discard)
+    ///         } else {
+    ///             Ok(Action::Keep)
+    ///         }
+    ///     });
+    /// ```
+    pub fn add_transform<T>(mut self, transform: T) -> Self
+    where
+        T: Transform + 'static,
+    {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Returns an iterator adapter that applies the pipeline to each record and skips discarded
+    /// records.
+    pub fn transform_records<'a, I>(
+        &'a mut self,
+        records: I,
+        context: &'a Context<'a>,
+    ) -> TransformRecords<'a, I::IntoIter>
+    where
+        I: IntoIterator<Item = io::Result<Record>>,
+    {
+        TransformRecords {
+            inner: records.into_iter(),
+            pipeline: self,
+            context,
+        }
+    }
+}
+
+impl Transform for Pipeline {
+    fn apply(&mut self, record: &mut Record, context: &Context<'_>) -> io::Result<Action> {
+        for transform in &mut self.transforms {
+            if transform.apply(record, context)? == Action::Discard {
+                return Ok(Action::Discard);
+            }
+        }
+
+        Ok(Action::Keep)
+    }
+}
+
+/// An iterator adapter that transforms records.
+///
+/// This is created by calling [`Pipeline::transform_records`].
+pub struct TransformRecords<'a, I> {
+    inner: I,
+    pipeline: &'a mut Pipeline,
+    context: &'a Context<'a>,
+}
+
+impl<'a, I> Iterator for TransformRecords<'a, I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut record = match self.inner.next()? {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.pipeline.apply(&mut record, self.context) {
+                Ok(Action::Keep) => return Some(Ok(record)),
+                Ok(Action::Discard) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// A transform that removes data fields with the given tags.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StripTags {
+    tags: Vec<Tag>,
+}
+
+impl StripTags {
+    /// Creates a transform that removes data fields with the given tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{record::data::field::Tag, transform::StripTags};
+    /// let transform = StripTags::new(vec![Tag::EditDistance, Tag::MismatchedPositions]);
+    /// ```
+    pub fn new(tags: Vec<Tag>) -> Self {
+        Self { tags }
+    }
+}
+
+impl Transform for StripTags {
+    fn apply(&mut self, record: &mut Record, _: &Context<'_>) -> io::Result<Action> {
+        let tags = &self.tags;
+        record
+            .data_mut()
+            .retain(|field| !tags.contains(field.tag()));
+        Ok(Action::Keep)
+    }
+}
+
+/// A transform that filters data fields by tag and caps their sizes.
+///
+/// Fields are removed if they are not in the keep list (when set), are in the remove list, or
+/// are larger than the maximum length of their tag. The length of a string or hex value is its
+/// number of bytes, and the length of an array is its number of elements. Other values have a
+/// length of 1.
+///
+/// This is typically used to drop large auxiliary data, e.g., original quality scores (`OQ`) or
+/// base insertion and deletion qualities (`BI`, `BD`), when reprocessing alignments.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_sam::{
+///     self as sam,
+///     record::data::field::Tag,
+///     transform::{Context, TagPolicy, Transform},
+/// };
+///
+/// let mut policy = TagPolicy::new()
+///     .set_remove(vec![Tag::EditDistance])
+///     .set_max_len(Tag::OriginalQualityScores, 2);
+///
+/// let mut record = sam::Record::builder()
+///     .set_data("NM:i:0\tOQ:Z:NDLS\tRG:Z:rg0".parse().unwrap())
+///     .build();
+///
+/// let header = sam::Header::default();
+/// policy.apply(&mut record, &Context::new(&header))?;
+///
+/// assert_eq!(record.data().to_string(), "RG:Z:rg0");
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TagPolicy {
+    keep: Option<Vec<Tag>>,
+    remove: Vec<Tag>,
+    max_lens: Vec<(Tag, usize)>,
+}
+
+impl TagPolicy {
+    /// Creates a tag policy that keeps all data fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::transform::TagPolicy;
+    /// let policy = TagPolicy::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tags of the data fields to keep.
+    ///
+    /// All other data fields are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{record::data::field::Tag, transform::TagPolicy};
+    /// let policy = TagPolicy::new().set_keep(vec![Tag::ReadGroup, Tag::AlignmentHitCount]);
+    /// ```
+    pub fn set_keep(mut self, tags: Vec<Tag>) -> Self {
+        self.keep = Some(tags);
+        self
+    }
+
+    /// Sets the tags of the data fields to remove.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{record::data::field::Tag, transform::TagPolicy};
+    /// let policy = TagPolicy::new().set_remove(vec![Tag::OriginalQualityScores]);
+    /// ```
+    pub fn set_remove(mut self, tags: Vec<Tag>) -> Self {
+        self.remove = tags;
+        self
+    }
+
+    /// Sets the maximum length of the value of a data field.
+    ///
+    /// A data field with a longer value is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{record::data::field::Tag, transform::TagPolicy};
+    ///
+    /// let policy = TagPolicy::new()
+    ///     .set_max_len(Tag::OriginalQualityScores, 0)
+    ///     .set_max_len(Tag::Other(String::from("BI")), 0)
+    ///     .set_max_len(Tag::Other(String::from("BD")), 0);
+    /// ```
+    pub fn set_max_len(mut self, tag: Tag, max_len: usize) -> Self {
+        match self.max_lens.iter_mut().find(|(t, _)| *t == tag) {
+            Some((_, len)) => *len = max_len,
+            None => self.max_lens.push((tag, max_len)),
+        }
+
+        self
+    }
+
+    fn is_allowed(&self, field: &Field) -> bool {
+        let tag = field.tag();
+
+        if let Some(keep) = &self.keep {
+            if !keep.contains(tag) {
+                return false;
+            }
+        }
+
+        if self.remove.contains(tag) {
+            return false;
+        }
+
+        self.max_lens
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, max_len)| value_len(field.value()) <= *max_len)
+            .unwrap_or(true)
+    }
+}
+
+impl Transform for TagPolicy {
+    fn apply(&mut self, record: &mut Record, _: &Context<'_>) -> io::Result<Action> {
+        let policy = &*self;
+        record.data_mut().retain(|field| policy.is_allowed(field));
+        Ok(Action::Keep)
+    }
+}
+
+fn value_len(value: &Value) -> usize {
+    match value {
+        Value::String(s) | Value::Hex(s) => s.len(),
+        Value::Int8Array(values) => values.len(),
+        Value::UInt8Array(values) => values.len(),
+        Value::Int16Array(values) => values.len(),
+        Value::UInt16Array(values) => values.len(),
+        Value::Int32Array(values) => values.len(),
+        Value::UInt32Array(values) => values.len(),
+        Value::FloatArray(values) => values.len(),
+        _ => 1,
+    }
+}
+
+/// A transform that sets the read group (`RG`) of each record.
+///
+/// The read group must be defined in the header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetReadGroup {
+    id: String,
+}
+
+impl SetReadGroup {
+    /// Creates a transform that sets the read group of each record to the given ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::transform::SetReadGroup;
+    /// let transform = SetReadGroup::new(String::from("rg0"));
+    /// ```
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl Transform for SetReadGroup {
+    fn apply(&mut self, record: &mut Record, context: &Context<'_>) -> io::Result<Action> {
+        if !context.header().read_groups().contains_key(&self.id) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("read group not in header: {}", self.id),
+            ));
+        }
+
+        record
+            .data_mut()
+            .insert(Field::new(Tag::ReadGroup, Value::String(self.id.clone())));
+
+        Ok(Action::Keep)
+    }
+}
+
+/// A transform that clears the duplicate flag (`0x0400`) of each record.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ClearDuplicateFlags;
+
+impl Transform for ClearDuplicateFlags {
+    fn apply(&mut self, record: &mut Record, _: &Context<'_>) -> io::Result<Action> {
+        record.flags_mut().remove(Flags::DUPLICATE);
+        Ok(Action::Keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::ReadGroup;
+
+    use super::*;
+
+    #[test]
+    fn test_pipeline() -> io::Result<()> {
+        let header = Header::builder()
+            .add_read_group(ReadGroup::new(String::from("rg0")))
+            .build();
+        let context = Context::new(&header);
+
+        let mut pipeline = Pipeline::new()
+            .add_transform(|record: &mut Record, _: &Context<'_>| {
+                if record.flags().is_secondary() {
+                    Ok(Action::Discard)
+                } else {
+                    Ok(Action::Keep)
+                }
+            })
+            .add_transform(SetReadGroup::new(String::from("rg0")))
+            .add_transform(ClearDuplicateFlags);
+
+        let mut record = Record::builder().set_flags(Flags::DUPLICATE).build();
+        assert_eq!(pipeline.apply(&mut record, &context)?, Action::Keep);
+        assert!(record.flags().is_empty());
+        assert_eq!(record.data().to_string(), "RG:Z:rg0");
+
+        let mut record = Record::builder()
+            .set_flags(Flags::SECONDARY | Flags::DUPLICATE)
+            .build();
+        assert_eq!(pipeline.apply(&mut record, &context)?, Action::Discard);
+        assert!(record.flags().is_duplicate());
+
+        let mut pipeline = Pipeline::new().add_transform(SetReadGroup::new(String::from("rg1")));
+        let mut record = Record::default();
+        assert!(pipeline.apply(&mut record, &context).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_tags() -> io::Result<()> {
+        let header = Header::default();
+        let context = Context::new(&header);
+
+        let mut record = Record::builder()
+            .set_data("NM:i:0\tMD:Z:4\tRG:Z:rg0".parse().unwrap())
+            .build();
+
+        StripTags::new(vec![Tag::EditDistance, Tag::MismatchedPositions])
+            .apply(&mut record, &context)?;
+
+        assert_eq!(record.data().to_string(), "RG:Z:rg0");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_policy() -> io::Result<()> {
+        let header = Header::default();
+        let context = Context::new(&header);
+
+        let build_record = || {
+            Record::builder()
+                .set_data(
+                    "NM:i:0\tOQ:Z:NDLS\tBI:Z:NDL\tRG:Z:rg0\tZA:B:c,1,2"
+                        .parse()
+                        .unwrap(),
+                )
+                .build()
+        };
+
+        let mut record = build_record();
+        TagPolicy::new()
+            .set_keep(vec![Tag::ReadGroup, Tag::OriginalQualityScores])
+            .set_remove(vec![Tag::OriginalQualityScores])
+            .apply(&mut record, &context)?;
+        assert_eq!(record.data().to_string(), "RG:Z:rg0");
+
+        let mut record = build_record();
+        TagPolicy::new()
+            .set_max_len(Tag::OriginalQualityScores, 4)
+            .set_max_len(Tag::Other(String::from("BI")), 2)
+            .set_max_len(Tag::Other(String::from("ZA")), 1)
+            .apply(&mut record, &context)?;
+        assert_eq!(record.data().to_string(), "NM:i:0\tOQ:Z:NDLS\tRG:Z:rg0");
+
+        let mut record = build_record();
+        TagPolicy::new()
+            .set_max_len(Tag::OriginalQualityScores, 8)
+            .set_max_len(Tag::OriginalQualityScores, 0)
+            .apply(&mut record, &context)?;
+        assert_eq!(
+            record.data().to_string(),
+            "NM:i:0\tBI:Z:NDL\tRG:Z:rg0\tZA:B:c,1,2"
+        );
+
+        Ok(())
+    }
+}
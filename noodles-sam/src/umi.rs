@@ -0,0 +1,413 @@
+//! Unique molecular identifier (UMI) and barcode extraction.
+//!
+//! UMIs and barcodes are either encoded in read names (e.g., `r0:ACGT`) or stored in data fields.
+//! The helpers in this module read from both locations and normalize UMIs into the standard `RX`
+//! tag.
+
+use std::{error, fmt, str::FromStr};
+
+use super::{
+    record::{
+        data::{
+            field::{Tag, Value},
+            Field,
+        },
+        Data,
+    },
+    Record,
+};
+
+// § 1.7 Barcodes and unique molecular identifiers (2020-05-29)
+const UMI_SEPARATOR: char = '-';
+const DUPLEX_UMI_SEPARATORS: [char; 2] = ['+', '_'];
+
+// 10x Genomics conventions
+static CORRECTED_UMI_TAG: &str = "UB";
+static RAW_UMI_TAG: &str = "UR";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Literal(char),
+    AnyChar,
+    Wildcard,
+    Capture,
+}
+
+/// A read name pattern used to extract a UMI or barcode.
+///
+/// A pattern is a sequence of literal characters and the following special tokens:
+///
+///   * `?` matches any single character,
+///   * `*` matches zero or more characters, and
+///   * `{}` matches and captures one or more characters.
+///
+/// A pattern must contain exactly one capture. `*` and `{}` match as many characters as possible
+/// while still matching the rest of the pattern.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::umi::Pattern;
+///
+/// // The last colon-delimited field, e.g., as written by `bcl2fastq`.
+/// let pattern: Pattern = "*:{}".parse()?;
+/// assert_eq!(pattern.extract("r0:1:ACGT"), Some("ACGT"));
+///
+/// // The suffix after an underscore, e.g., as written by `umi_tools extract`.
+/// let pattern: Pattern = "*_{}".parse()?;
+/// assert_eq!(pattern.extract("r0_ACGT"), Some("ACGT"));
+/// assert_eq!(pattern.extract("r0"), None);
+/// # Ok::<(), noodles_sam::umi::ParseError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern(Vec<Token>);
+
+impl Pattern {
+    /// Returns the captured part of the given input if the input matches the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::umi::Pattern;
+    /// let pattern: Pattern = "r?:{}".parse()?;
+    /// assert_eq!(pattern.extract("r0:ACGT"), Some("ACGT"));
+    /// assert_eq!(pattern.extract("r01:ACGT"), None);
+    /// # Ok::<(), noodles_sam::umi::ParseError>(())
+    /// ```
+    pub fn extract<'a>(&self, s: &'a str) -> Option<&'a str> {
+        let mut capture = None;
+
+        if match_tokens(&self.0, s, 0, &mut capture) {
+            capture.map(|(start, end)| &s[start..end])
+        } else {
+            None
+        }
+    }
+}
+
+fn match_tokens(
+    tokens: &[Token],
+    s: &str,
+    pos: usize,
+    capture: &mut Option<(usize, usize)>,
+) -> bool {
+    let (token, rest) = match tokens.split_first() {
+        Some(t) => t,
+        None => return pos == s.len(),
+    };
+
+    match token {
+        Token::Literal(c) => {
+            s[pos..].starts_with(*c) && match_tokens(rest, s, pos + c.len_utf8(), capture)
+        }
+        Token::AnyChar => match s[pos..].chars().next() {
+            Some(c) => match_tokens(rest, s, pos + c.len_utf8(), capture),
+            None => false,
+        },
+        Token::Wildcard => (pos..=s.len())
+            .rev()
+            .filter(|&end| s.is_char_boundary(end))
+            .any(|end| match_tokens(rest, s, end, capture)),
+        Token::Capture => (pos + 1..=s.len())
+            .rev()
+            .filter(|&end| s.is_char_boundary(end))
+            .any(|end| {
+                *capture = Some((pos, end));
+                match_tokens(rest, s, end, capture)
+            }),
+    }
+}
+
+/// An error returned when a raw read name pattern fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The pattern has no capture (`{}`).
+    MissingCapture,
+    /// The pattern has more than one capture (`{}`).
+    DuplicateCapture,
+    /// A capture is not closed, i.e., `{` is not followed by `}`.
+    UnclosedCapture,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCapture => f.write_str("missing capture"),
+            Self::DuplicateCapture => f.write_str("duplicate capture"),
+            Self::UnclosedCapture => f.write_str("unclosed capture"),
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Vec::new();
+        let mut has_capture = false;
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '?' => Token::AnyChar,
+                '*' => Token::Wildcard,
+                '{' => {
+                    if chars.next() != Some('}') {
+                        return Err(ParseError::UnclosedCapture);
+                    } else if has_capture {
+                        return Err(ParseError::DuplicateCapture);
+                    }
+
+                    has_capture = true;
+
+                    Token::Capture
+                }
+                _ => Token::Literal(c),
+            };
+
+            tokens.push(token);
+        }
+
+        if has_capture {
+            Ok(Self(tokens))
+        } else {
+            Err(ParseError::MissingCapture)
+        }
+    }
+}
+
+/// Returns the UMI encoded in the read name of the given record.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{self as sam, umi};
+///
+/// let record = sam::Record::builder().set_read_name("r0:ACGT".parse()?).build();
+/// let pattern = "*:{}".parse()?;
+///
+/// assert_eq!(umi::read_name_umi(&record, &pattern), Some("ACGT"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn read_name_umi<'a>(record: &'a Record, pattern: &Pattern) -> Option<&'a str> {
+    record
+        .read_name()
+        .and_then(|read_name| pattern.extract(read_name))
+}
+
+/// Returns the UMI stored in the given data.
+///
+/// The tags are checked in the following order: `RX` (the standard UMI tag), `UB` (corrected
+/// UMI), and `UR` (raw UMI).
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{record::{data::{field::{Tag, Value}, Field}, Data}, umi};
+///
+/// let data = Data::from(vec![Field::new(
+///     Tag::Other(String::from("UR")),
+///     Value::String(String::from("ACGT")),
+/// )]);
+///
+/// assert_eq!(umi::umi(&data), Some("ACGT"));
+/// ```
+pub fn umi(data: &Data) -> Option<&str> {
+    let tags = [
+        Tag::UmiSequence,
+        Tag::Other(CORRECTED_UMI_TAG.into()),
+        Tag::Other(RAW_UMI_TAG.into()),
+    ];
+
+    find_str(data, &tags)
+}
+
+/// Returns the cell barcode stored in the given data.
+///
+/// The tags are checked in the following order: `CB` (cell identifier, typically corrected) and
+/// `CR` (uncorrected cellular barcode).
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{record::{data::{field::{Tag, Value}, Field}, Data}, umi};
+///
+/// let data = Data::from(vec![
+///     Field::new(Tag::CellBarcodeSequence, Value::String(String::from("ACGT"))),
+///     Field::new(Tag::CellBarcodeId, Value::String(String::from("ACGA-1"))),
+/// ]);
+///
+/// assert_eq!(umi::cell_barcode(&data), Some("ACGA-1"));
+/// ```
+pub fn cell_barcode(data: &Data) -> Option<&str> {
+    find_str(data, &[Tag::CellBarcodeId, Tag::CellBarcodeSequence])
+}
+
+/// Returns the sample barcode (`BC`) stored in the given data.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{record::{data::{field::{Tag, Value}, Field}, Data}, umi};
+///
+/// let data = Data::from(vec![Field::new(
+///     Tag::SampleBarcodeSequence,
+///     Value::String(String::from("ACGT")),
+/// )]);
+///
+/// assert_eq!(umi::sample_barcode(&data), Some("ACGT"));
+/// ```
+pub fn sample_barcode(data: &Data) -> Option<&str> {
+    find_str(data, &[Tag::SampleBarcodeSequence])
+}
+
+fn find_str<'a>(data: &'a Data, tags: &[Tag]) -> Option<&'a str> {
+    tags.iter()
+        .filter_map(|tag| data.get(tag))
+        .find_map(|field| field.value().as_str())
+}
+
+/// Normalizes a raw UMI.
+///
+/// Bases are uppercased, and the separators of duplex UMIs (`+` or `_`) are replaced with a
+/// hyphen (`-`), as recommended for the `RX` tag.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::umi;
+/// assert_eq!(umi::normalize_umi("acgt+TTGA"), "ACGT-TTGA");
+/// ```
+pub fn normalize_umi(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if DUPLEX_UMI_SEPARATORS.contains(&c) {
+                UMI_SEPARATOR
+            } else {
+                c.to_ascii_uppercase()
+            }
+        })
+        .collect()
+}
+
+/// Extracts the UMI from the read name of the given record and stores it in the `RX` tag.
+///
+/// The UMI is normalized using [`normalize_umi`]. An existing `RX` field is replaced. This
+/// returns whether a UMI was found.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_sam::{self as sam, record::data::field::Tag, umi};
+///
+/// let mut record = sam::Record::builder().set_read_name("r0:acgt+ttga".parse()?).build();
+/// let pattern = "*:{}".parse()?;
+///
+/// assert!(umi::tag_read_name_umi(&mut record, &pattern));
+/// assert_eq!(record.data().to_string(), "RX:Z:ACGT-TTGA");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn tag_read_name_umi(record: &mut Record, pattern: &Pattern) -> bool {
+    let umi = match read_name_umi(record, pattern) {
+        Some(umi) => normalize_umi(umi),
+        None => return false,
+    };
+
+    record
+        .data_mut()
+        .insert(Field::new(Tag::UmiSequence, Value::String(umi)));
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_for_pattern() {
+        assert_eq!(
+            "*:{}".parse(),
+            Ok(Pattern(vec![
+                Token::Wildcard,
+                Token::Literal(':'),
+                Token::Capture
+            ]))
+        );
+
+        assert_eq!(
+            "r?{}".parse(),
+            Ok(Pattern(vec![
+                Token::Literal('r'),
+                Token::AnyChar,
+                Token::Capture
+            ]))
+        );
+
+        assert_eq!("*:".parse::<Pattern>(), Err(ParseError::MissingCapture));
+        assert_eq!(
+            "{}:{}".parse::<Pattern>(),
+            Err(ParseError::DuplicateCapture)
+        );
+        assert_eq!("*:{".parse::<Pattern>(), Err(ParseError::UnclosedCapture));
+    }
+
+    #[test]
+    fn test_extract() -> Result<(), ParseError> {
+        let pattern: Pattern = "*:{}".parse()?;
+        assert_eq!(pattern.extract("r0:1:ACGT"), Some("ACGT"));
+        assert_eq!(pattern.extract("r0:"), None);
+        assert_eq!(pattern.extract("r0"), None);
+
+        let pattern: Pattern = "{}_*".parse()?;
+        assert_eq!(pattern.extract("ACGT_r0"), Some("ACGT"));
+
+        let pattern: Pattern = "*:UMI_{}:*".parse()?;
+        assert_eq!(pattern.extract("r0:UMI_ACGT:1"), Some("ACGT"));
+
+        let pattern: Pattern = "{}".parse()?;
+        assert_eq!(pattern.extract("ACGT"), Some("ACGT"));
+        assert_eq!(pattern.extract(""), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_umi() {
+        let data = Data::from(vec![
+            Field::new(
+                Tag::Other(String::from("UR")),
+                Value::String(String::from("ACGA")),
+            ),
+            Field::new(Tag::UmiSequence, Value::String(String::from("ACGT"))),
+        ]);
+
+        assert_eq!(umi(&data), Some("ACGT"));
+        assert!(umi(&Data::default()).is_none());
+    }
+
+    #[test]
+    fn test_normalize_umi() {
+        assert_eq!(normalize_umi("ACGT"), "ACGT");
+        assert_eq!(normalize_umi("acgt"), "ACGT");
+        assert_eq!(normalize_umi("ACGT_TTGA"), "ACGT-TTGA");
+        assert_eq!(normalize_umi("ACGT-TTGA"), "ACGT-TTGA");
+    }
+
+    #[test]
+    fn test_tag_read_name_umi() -> Result<(), Box<dyn std::error::Error>> {
+        let pattern = "*:{}".parse()?;
+
+        let mut record = Record::builder().set_read_name("r0:ACGT".parse()?).build();
+        assert!(tag_read_name_umi(&mut record, &pattern));
+        assert_eq!(record.data().to_string(), "RX:Z:ACGT");
+
+        let mut record = Record::builder().set_read_name("r0".parse()?).build();
+        assert!(!tag_read_name_umi(&mut record, &pattern));
+        assert!(record.data().is_empty());
+
+        Ok(())
+    }
+}
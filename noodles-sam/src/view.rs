@@ -0,0 +1,383 @@
+//! Alignment viewer grid.
+//!
+//! This lays out the alignments of a region as rows of cells against reference coordinates,
+//! like `samtools tview` but without a user interface. Each column is a reference position, and
+//! each row holds one or more non-overlapping records, packed in order of their alignment start.
+//!
+//! Cells describe what a record has at a position, e.g., whether its base matches the reference,
+//! so frontends can choose how to display them. [`Grid`] also implements [`std::fmt::Display`]
+//! using the tview notation: `.` and `,` for matches on the forward and reverse strands, the read
+//! base for mismatches (uppercase on the forward strand, lowercase on the reverse strand), `*` for
+//! deletions, and `>` and `<` for reference skips. Soft clips and insertions do not occupy columns,
+//! but insertions are noted on the cell of the position before them.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature = "io", doc = "```")]
+#![cfg_attr(not(feature = "io"), doc = "```ignore")]
+//! # use std::io;
+//! use noodles_sam::{self as sam, view};
+//!
+//! let data = b"r0\t0\tsq0\t2\t60\t3M\t*\t0\t0\tCTT\tNDL
+//! r1\t16\tsq0\t3\t60\t1M1D1M\t*\t0\t0\tGT\tND
+//! ";
+//!
+//! let mut reader = sam::Reader::new(&data[..]);
+//! let grid = view::layout(reader.records(), 1, b"ACGTA")?;
+//!
+//! assert_eq!(grid.to_string(), "ACGTA\n .T. \n  ,*t\n");
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::{fmt, io};
+
+use crate::{
+    record::{cigar::op::Kind, sequence::Base},
+    Record,
+};
+
+// The number of empty columns required between two records in the same row.
+const GAP: i64 = 1;
+
+/// The contents of a record at a reference position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CellKind {
+    /// The read base equals the reference base.
+    Match,
+    /// The read base differs from the reference base.
+    Mismatch,
+    /// The reference base is deleted from the read.
+    Deletion,
+    /// The reference base is skipped, e.g., an intron.
+    ReferenceSkip,
+}
+
+/// A record at a reference position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cell {
+    kind: CellKind,
+    base: Option<Base>,
+    quality: Option<u8>,
+    is_reverse_complemented: bool,
+    insertion_len: u32,
+}
+
+impl Cell {
+    /// Returns the kind of cell.
+    pub fn kind(&self) -> CellKind {
+        self.kind
+    }
+
+    /// Returns the read base.
+    ///
+    /// This is `None` for deletions and reference skips.
+    pub fn base(&self) -> Option<Base> {
+        self.base
+    }
+
+    /// Returns the quality of the read base.
+    ///
+    /// This is `None` if there is no read base or the record has no quality scores.
+    pub fn quality(&self) -> Option<u8> {
+        self.quality
+    }
+
+    /// Returns whether the record is on the reverse strand.
+    pub fn is_reverse_complemented(&self) -> bool {
+        self.is_reverse_complemented
+    }
+
+    /// Returns the number of bases inserted after this position.
+    pub fn insertion_len(&self) -> u32 {
+        self.insertion_len
+    }
+
+    /// Returns the tview character of this cell.
+    pub fn to_char(&self) -> char {
+        match (self.kind, self.is_reverse_complemented) {
+            (CellKind::Match, false) => '.',
+            (CellKind::Match, true) => ',',
+            (CellKind::Mismatch, is_reverse_complemented) => {
+                let c = self.base.map(char::from).unwrap_or('N');
+
+                if is_reverse_complemented {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                }
+            }
+            (CellKind::Deletion, _) => '*',
+            (CellKind::ReferenceSkip, false) => '>',
+            (CellKind::ReferenceSkip, true) => '<',
+        }
+    }
+}
+
+/// A row of cells.
+///
+/// A row has a cell for each reference position in the grid. A cell is `None` if no record in
+/// the row covers the position.
+pub type Row = Vec<Option<Cell>>;
+
+/// A grid of alignments against reference coordinates.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid {
+    start: i64,
+    reference_sequence: Vec<u8>,
+    rows: Vec<Row>,
+}
+
+impl Grid {
+    /// Returns the 1-based reference position of the first column.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the reference bases of the columns.
+    pub fn reference_sequence(&self) -> &[u8] {
+        &self.reference_sequence
+    }
+
+    /// Returns the rows.
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &b in &self.reference_sequence {
+            write!(f, "{}", char::from(b))?;
+        }
+
+        writeln!(f)?;
+
+        for row in &self.rows {
+            for cell in row {
+                let c = cell.map(|cell| cell.to_char()).unwrap_or(' ');
+                write!(f, "{}", c)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lays out records against a reference region.
+///
+/// The region starts at the 1-based position `start` and spans `reference_sequence`. Unmapped
+/// records and records that do not overlap the region are skipped. Reference and read bases are
+/// compared case-insensitively.
+///
+/// Records are placed in the first row where they do not overlap, with at least one empty column
+/// between records, so coordinate-sorted input gives the most compact layout.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, view};
+///
+/// let data = b"r0\t0\tsq0\t1\t60\t2M\t*\t0\t0\tAC\tND
+/// r1\t0\tsq0\t4\t60\t2M\t*\t0\t0\tTA\tND
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// let grid = view::layout(reader.records(), 1, b"ACGTA")?;
+///
+/// assert_eq!(grid.rows().len(), 1);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn layout<I>(records: I, start: i64, reference_sequence: &[u8]) -> io::Result<Grid>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    let end = start + reference_sequence.len() as i64 - 1;
+
+    let mut rows: Vec<Row> = Vec::new();
+    let mut row_ends: Vec<i64> = Vec::new();
+
+    for result in records {
+        let record = result?;
+
+        if record.flags().is_unmapped() {
+            continue;
+        }
+
+        let record_start = match record.position() {
+            Some(position) => i64::from(position),
+            None => continue,
+        };
+
+        let record_end = record_start + i64::from(record.cigar().reference_len()) - 1;
+
+        if record_end < start || record_start > end {
+            continue;
+        }
+
+        let i = match row_ends
+            .iter()
+            .position(|&row_end| row_end + GAP < record_start)
+        {
+            Some(i) => i,
+            None => {
+                rows.push(vec![None; reference_sequence.len()]);
+                row_ends.push(i64::MIN);
+                rows.len() - 1
+            }
+        };
+
+        row_ends[i] = record_end;
+        place(
+            &record,
+            record_start,
+            start,
+            reference_sequence,
+            &mut rows[i],
+        );
+    }
+
+    Ok(Grid {
+        start,
+        reference_sequence: reference_sequence.to_vec(),
+        rows,
+    })
+}
+
+fn place(record: &Record, record_start: i64, start: i64, reference_sequence: &[u8], row: &mut Row) {
+    let is_reverse_complemented = record.flags().is_reverse_complemented();
+    let sequence = record.sequence();
+    let quality_scores = record.quality_scores();
+
+    let mut reference_position = record_start;
+    let mut query_position = 0;
+    let mut last_column = None;
+
+    let column = |reference_position: i64| -> Option<usize> {
+        let i = reference_position - start;
+
+        if i >= 0 && (i as usize) < reference_sequence.len() {
+            Some(i as usize)
+        } else {
+            None
+        }
+    };
+
+    for op in record.cigar().iter() {
+        let len = op.len() as usize;
+
+        match op.kind() {
+            Kind::Match | Kind::SeqMatch | Kind::SeqMismatch => {
+                for _ in 0..len {
+                    if let Some(i) = column(reference_position) {
+                        let base = sequence.get(query_position).copied();
+
+                        let kind = match base {
+                            Some(base)
+                                if char::from(base)
+                                    .eq_ignore_ascii_case(&char::from(reference_sequence[i])) =>
+                            {
+                                CellKind::Match
+                            }
+                            _ => CellKind::Mismatch,
+                        };
+
+                        row[i] = Some(Cell {
+                            kind,
+                            base,
+                            quality: quality_scores.get(query_position).map(|&s| u8::from(s)),
+                            is_reverse_complemented,
+                            insertion_len: 0,
+                        });
+
+                        last_column = Some(i);
+                    } else {
+                        last_column = None;
+                    }
+
+                    reference_position += 1;
+                    query_position += 1;
+                }
+            }
+            Kind::Deletion | Kind::Skip => {
+                let kind = if op.kind() == Kind::Deletion {
+                    CellKind::Deletion
+                } else {
+                    CellKind::ReferenceSkip
+                };
+
+                for _ in 0..len {
+                    if let Some(i) = column(reference_position) {
+                        row[i] = Some(Cell {
+                            kind,
+                            base: None,
+                            quality: None,
+                            is_reverse_complemented,
+                            insertion_len: 0,
+                        });
+
+                        last_column = Some(i);
+                    } else {
+                        last_column = None;
+                    }
+
+                    reference_position += 1;
+                }
+            }
+            Kind::Insertion => {
+                if let Some(cell) = last_column.and_then(|i| row[i].as_mut()) {
+                    cell.insertion_len += op.len();
+                }
+
+                query_position += len;
+            }
+            Kind::SoftClip => query_position += len,
+            Kind::HardClip | Kind::Pad => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::codec::Decode;
+
+    use super::*;
+
+    fn parse_records(data: &[u8]) -> Vec<io::Result<Record>> {
+        data.split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(Record::decode)
+            .collect()
+    }
+
+    #[test]
+    fn test_layout() -> io::Result<()> {
+        let data = b"\
+r0\t0\tsq0\t1\t60\t2S2M1I1M\t*\t0\t0\tGGACTG\tNDLSNN
+r1\t0\tsq0\t2\t60\t2M\t*\t0\t0\tCA\tND
+r2\t4\t*\t0\t0\t*\t*\t0\t0\tACGT\tNDLS
+r3\t0\tsq0\t6\t60\t1M2N1M\t*\t0\t0\tAA\tND
+r4\t0\tsq0\t9\t60\t1M\t*\t0\t0\tA\tN
+";
+
+        let grid = layout(parse_records(data), 1, b"ACGTAC")?;
+
+        assert_eq!(grid.start(), 1);
+        assert_eq!(grid.rows().len(), 2);
+        assert_eq!(grid.to_string(), "ACGTAC\n...  A\n .A   \n");
+
+        let cell = grid.rows()[0][1].unwrap();
+        assert_eq!(cell.kind(), CellKind::Match);
+        assert_eq!(cell.insertion_len(), 1);
+        assert_eq!(cell.quality(), Some(b'S' - b'!'));
+
+        let cell = grid.rows()[1][2].unwrap();
+        assert_eq!(cell.kind(), CellKind::Mismatch);
+        assert_eq!(cell.base(), Some(Base::A));
+
+        Ok(())
+    }
+}
@@ -6,9 +6,14 @@ mod indexer;
 pub mod reference_sequence;
 
 pub use self::{
-    builder::Builder, header::Header, indexer::Indexer, reference_sequence::ReferenceSequence,
+    builder::Builder,
+    header::Header,
+    indexer::{index_lines, Indexer},
+    reference_sequence::ReferenceSequence,
 };
 
+pub(crate) use self::indexer::parse_line;
+
 /// A tabix index.
 #[derive(Debug)]
 pub struct Index {
@@ -1,8 +1,22 @@
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    io::{self, Read},
+};
+
+use noodles_bgzf as bgzf;
+
 use super::{
+    header::{format::CoordinateSystem, Format},
     reference_sequence::{self, bin::Chunk},
     Header, Index, ReferenceSequence,
 };
 
+const FIELD_DELIMITER: char = '\t';
+
+// Tabix bins cannot represent positions > 2^29.
+const MAX_POSITION: u32 = 1 << 29;
+
 /// A tabix indexer.
 #[derive(Debug, Default)]
 pub struct Indexer {
@@ -88,3 +102,325 @@ impl Indexer {
             .build()
     }
 }
+
+/// Indexes a bgzipped, tab-delimited text file.
+///
+/// The columns and coordinate system of the input are described by the given header, e.g.,
+/// [`crate::index::header::Builder::vcf`]. Lines before the line skip count, lines starting with the
+/// line comment prefix, and empty lines are not indexed. The input must be grouped by reference
+/// sequence name and sorted by start position.
+///
+/// This is equivalent to `tabix --preset`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io::{self, Write};
+/// use noodles_bgzf as bgzf;
+/// use noodles_tabix as tabix;
+///
+/// let mut writer = bgzf::Writer::new(Vec::new());
+/// writer.write_all(b"#CHROM\tPOS\tID\tREF\tALT\nsq0\t1\t.\tA\t.\n")?;
+/// let data = writer.finish()?;
+///
+/// let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+/// let header = tabix::index::header::Builder::vcf().build();
+/// let index = tabix::index::index_lines(&mut reader, header)?;
+///
+/// assert_eq!(index.reference_sequence_names(), [String::from("sq0")]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn index_lines<R>(reader: &mut bgzf::LineReader<R>, header: Header) -> io::Result<Index>
+where
+    R: Read,
+{
+    let mut indexer = Index::indexer();
+    indexer.set_header(header.clone());
+
+    let mut seen_reference_sequence_names = HashSet::new();
+    let mut last_position: Option<(String, u32)> = None;
+
+    let mut buf = String::new();
+    let mut line_count = 0;
+
+    while let Some(start_position) = reader.read_line(&mut buf)? {
+        line_count += 1;
+
+        if line_count <= header.line_skip_count()
+            || buf.is_empty()
+            || buf.as_bytes()[0] == header.line_comment_prefix()
+        {
+            continue;
+        }
+
+        let end_position = reader.virtual_position();
+
+        let (reference_sequence_name, start, end) = parse_line(&buf, &header)?;
+
+        match &last_position {
+            Some((name, last_start)) if name == reference_sequence_name => {
+                if start < *last_start {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input is not coordinate-sorted",
+                    ));
+                }
+            }
+            _ => {
+                if !seen_reference_sequence_names.insert(reference_sequence_name.to_string()) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input is not grouped by reference sequence name",
+                    ));
+                }
+            }
+        }
+
+        last_position = Some((reference_sequence_name.into(), start));
+
+        let chunk = Chunk::new(start_position, end_position);
+        indexer.add_record(reference_sequence_name, start, end, chunk);
+    }
+
+    Ok(indexer.build())
+}
+
+// Returns the reference sequence name and the 1-based, inclusive start and end positions.
+pub(crate) fn parse_line<'a>(line: &'a str, header: &Header) -> io::Result<(&'a str, u32, u32)> {
+    let fields: Vec<_> = line.split(FIELD_DELIMITER).collect();
+
+    let reference_sequence_name = get_field(&fields, header.reference_sequence_name_index())?;
+    let mut start = get_field(&fields, header.start_position_index()).and_then(parse_position)?;
+
+    let mut end = match header.format() {
+        Format::Vcf => vcf_end_position(&fields, start)?,
+        Format::Sam => sam_end_position(&fields, start)?,
+        Format::Generic(_) => match header.end_position_index() {
+            Some(i) => get_field(&fields, i).and_then(parse_position)?,
+            None => start,
+        },
+    };
+
+    if header.format().coordinate_system() == CoordinateSystem::Bed {
+        start = start
+            .checked_add(1)
+            .ok_or_else(position_out_of_range_error)?;
+
+        if header.end_position_index().is_none() {
+            end = start;
+        }
+    }
+
+    if start == 0 || end < start {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid interval: [{}, {}]", start, end),
+        ));
+    }
+
+    if end > MAX_POSITION {
+        return Err(position_out_of_range_error());
+    }
+
+    Ok((reference_sequence_name, start, end))
+}
+
+fn position_out_of_range_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "position is out of range for a tabix index",
+    )
+}
+
+// Returns the 1-based, inclusive end position of an interval that starts at `start` and spans
+// `len` bases. An empty interval spans the start position.
+fn end_position(start: u32, len: u32) -> io::Result<u32> {
+    start
+        .checked_add(len.max(1) - 1)
+        .ok_or_else(position_out_of_range_error)
+}
+
+// `i` is 1-based.
+fn get_field<'a>(fields: &[&'a str], i: usize) -> io::Result<&'a str> {
+    i.checked_sub(1)
+        .and_then(|j| fields.get(j))
+        .copied()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("missing field {}", i)))
+}
+
+fn parse_position(s: &str) -> io::Result<u32> {
+    s.parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// The end position of a VCF record is the last base of the reference bases (`REF`) or, if larger,
+// the `END` information field.
+fn vcf_end_position(fields: &[&str], start: u32) -> io::Result<u32> {
+    const REFERENCE_BASES_INDEX: usize = 4;
+    const INFO_INDEX: usize = 8;
+
+    let reference_bases = get_field(fields, REFERENCE_BASES_INDEX)?;
+    let len = u32::try_from(reference_bases.len()).map_err(|_| position_out_of_range_error())?;
+    let mut end = end_position(start, len)?;
+
+    if let Some(info) = fields.get(INFO_INDEX - 1) {
+        for field in info.split(';') {
+            if let Some(value) = field.strip_prefix("END=") {
+                end = end.max(parse_position(value)?);
+            }
+        }
+    }
+
+    Ok(end)
+}
+
+// The end position of a SAM record is determined by the reference length of its CIGAR.
+fn sam_end_position(fields: &[&str], start: u32) -> io::Result<u32> {
+    const CIGAR_INDEX: usize = 6;
+
+    let cigar = get_field(fields, CIGAR_INDEX)?;
+
+    if cigar == "*" {
+        return Ok(start);
+    }
+
+    let mut reference_len = 0u32;
+    let mut len = 0u32;
+
+    for c in cigar.chars() {
+        if let Some(d) = c.to_digit(10) {
+            len = len
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid CIGAR"))?;
+        } else {
+            if matches!(c, 'M' | 'D' | 'N' | '=' | 'X') {
+                reference_len = reference_len
+                    .checked_add(len)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid CIGAR"))?;
+            }
+
+            len = 0;
+        }
+    }
+
+    end_position(start, reference_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::index::header;
+
+    use super::*;
+
+    fn build_data(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut writer = bgzf::Writer::new(Vec::new());
+        writer.write_all(data)?;
+        writer.finish()
+    }
+
+    #[test]
+    fn test_index_lines() -> io::Result<()> {
+        let data = build_data(b"#chrom\tstart\tend\nsq0\t7\t13\nsq0\t8\t21\nsq1\t0\t5\n")?;
+        let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+
+        let index = index_lines(&mut reader, header::Builder::bed().build())?;
+
+        assert_eq!(
+            index.reference_sequence_names(),
+            [String::from("sq0"), String::from("sq1")]
+        );
+
+        let reference_sequences = index.reference_sequences();
+        assert_eq!(reference_sequences.len(), 2);
+
+        let bins = reference_sequences[0].bins();
+        assert_eq!(bins.len(), 1);
+        assert_eq!(bins[0].id(), 4681);
+        assert_eq!(
+            bins[0].chunks(),
+            [Chunk::new(
+                bgzf::VirtualPosition::from(17),
+                bgzf::VirtualPosition::from(35)
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_lines_with_unsorted_input() -> io::Result<()> {
+        let data = build_data(b"sq0\t8\t13\nsq1\t0\t5\nsq0\t21\t34\n")?;
+        let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+        assert!(index_lines(&mut reader, header::Builder::bed().build()).is_err());
+
+        let data = build_data(b"sq0\t8\t13\nsq0\t5\t13\n")?;
+        let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+        assert!(index_lines(&mut reader, header::Builder::bed().build()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line() -> io::Result<()> {
+        let header = header::Builder::vcf().build();
+        assert_eq!(parse_line("sq0\t8\t.\tACGT\t.", &header)?, ("sq0", 8, 11));
+        assert_eq!(
+            parse_line("sq0\t8\t.\tA\t<DEL>\t.\t.\tSVTYPE=DEL;END=21", &header)?,
+            ("sq0", 8, 21)
+        );
+
+        let header = header::Builder::sam().build();
+        assert_eq!(
+            parse_line("r0\t0\tsq0\t8\t60\t4M2D3S\t*", &header)?,
+            ("sq0", 8, 13)
+        );
+
+        let header = header::Builder::gff().build();
+        assert_eq!(
+            parse_line("sq0\tsrc\tgene\t8\t13\t.", &header)?,
+            ("sq0", 8, 13)
+        );
+
+        let header = header::Builder::bed().build();
+        assert_eq!(parse_line("sq0\t7\t13", &header)?, ("sq0", 8, 13));
+        assert!(parse_line("sq0\t13\t7", &header).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_with_out_of_range_positions() {
+        fn kind(result: io::Result<(&str, u32, u32)>) -> Option<io::ErrorKind> {
+            result.err().map(|e| e.kind())
+        }
+
+        let header = header::Builder::vcf().build();
+        assert_eq!(
+            kind(parse_line("sq0\t4294967295\t.\tACGT\t.", &header)),
+            Some(io::ErrorKind::InvalidData)
+        );
+        assert_eq!(
+            kind(parse_line("sq0\t536870913\t.\tA\t.", &header)),
+            Some(io::ErrorKind::InvalidData)
+        );
+        assert!(parse_line("sq0\t536870912\t.\tA\t.", &header).is_ok());
+
+        let header = header::Builder::sam().build();
+        assert_eq!(
+            kind(parse_line(
+                "r0\t0\tsq0\t8\t60\t4294967295M4294967295M\t*",
+                &header
+            )),
+            Some(io::ErrorKind::InvalidData)
+        );
+
+        let header = header::Builder::bed().build();
+        assert_eq!(
+            kind(parse_line("sq0\t4294967295\t4294967295", &header)),
+            Some(io::ErrorKind::InvalidData)
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub(crate) use self::builder::Builder;
 
 use noodles_bgzf as bgzf;
 
+const WINDOW_SIZE: u32 = 16384;
+
 /// A tabix index reference sequence.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ReferenceSequence {
@@ -58,4 +60,116 @@ impl ReferenceSequence {
     pub fn intervals(&self) -> &[bgzf::VirtualPosition] {
         &self.intervals
     }
+
+    /// Returns a list of bins in this reference sequence that intersect the given range.
+    ///
+    /// `start` and `end` are 1-based, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_tabix::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), Vec::new());
+    /// let query_bins = reference_sequence.query(8, 13);
+    /// assert!(query_bins.is_empty());
+    /// ```
+    pub fn query(&self, start: u32, end: u32) -> Vec<&Bin> {
+        let region_bins = region_to_bins(start - 1, end);
+
+        self.bins()
+            .iter()
+            .filter(|b| region_bins.get(b.id() as usize).copied().unwrap_or(false))
+            .collect()
+    }
+
+    /// Finds the minimum start offset in the linear index for a given start position.
+    ///
+    /// `start` is 1-based.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_tabix::index::ReferenceSequence;
+    /// let reference_sequence = ReferenceSequence::new(Vec::new(), Vec::new());
+    /// assert_eq!(reference_sequence.min_offset(13), bgzf::VirtualPosition::from(0));
+    /// ```
+    pub fn min_offset(&self, start: u32) -> bgzf::VirtualPosition {
+        let i = ((start - 1) / WINDOW_SIZE) as usize;
+        self.intervals.get(i).copied().unwrap_or_default()
+    }
+}
+
+// 0-based, [start, end)
+fn region_to_bins(start: u32, mut end: u32) -> Vec<bool> {
+    end -= 1;
+
+    let mut bins = vec![false; bin::MAX_ID];
+    bins[0] = true;
+
+    for &(offset, shift) in &[(1, 26), (9, 23), (73, 20), (585, 17), (4681, 14)] {
+        let first = (offset + (start >> shift)) as usize;
+        let last = ((offset + (end >> shift)) as usize).min(bin::MAX_ID - 1);
+
+        for bin in bins.iter_mut().take(last + 1).skip(first) {
+            *bin = true;
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let reference_sequence = ReferenceSequence::new(
+            vec![
+                Bin::new(0, Vec::new()),
+                Bin::new(4681, Vec::new()),
+                Bin::new(4682, Vec::new()),
+            ],
+            Vec::new(),
+        );
+
+        let query_bins = reference_sequence.query(8, 13);
+        let ids: Vec<_> = query_bins.iter().map(|b| b.id()).collect();
+        assert_eq!(ids, [0, 4681]);
+    }
+
+    #[test]
+    fn test_min_offset() {
+        let reference_sequence = ReferenceSequence::new(
+            Vec::new(),
+            vec![
+                bgzf::VirtualPosition::from(8),
+                bgzf::VirtualPosition::from(13),
+            ],
+        );
+
+        assert_eq!(
+            reference_sequence.min_offset(1),
+            bgzf::VirtualPosition::from(8)
+        );
+        assert_eq!(
+            reference_sequence.min_offset(WINDOW_SIZE + 1),
+            bgzf::VirtualPosition::from(13)
+        );
+        assert_eq!(
+            reference_sequence.min_offset(2 * WINDOW_SIZE + 1),
+            bgzf::VirtualPosition::default()
+        );
+    }
+
+    #[test]
+    fn test_region_to_bins() {
+        // [8, 13]
+        let actual = region_to_bins(7, 13);
+        let expected: Vec<_> = (0..bin::MAX_ID)
+            .map(|k| [0, 1, 9, 73, 585, 4681].contains(&k))
+            .collect();
+        assert_eq!(actual, expected);
+    }
 }
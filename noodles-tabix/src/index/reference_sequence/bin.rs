@@ -7,6 +7,8 @@ pub use self::chunk::Chunk;
 
 pub(crate) use self::builder::Builder;
 
+pub(crate) const MAX_ID: usize = ((1 << 18) - 1) / 7 + 1;
+
 /// A tabix index reference sequence bin.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Bin {
@@ -4,15 +4,13 @@ use noodles_bgzf as bgzf;
 
 use super::{
     bin::{self, Chunk},
-    Bin, ReferenceSequence,
+    Bin, ReferenceSequence, WINDOW_SIZE,
 };
 
-const WINDOW_SIZE: u32 = 16384;
-
 #[derive(Debug, Default)]
 pub struct Builder {
     bin_builders: HashMap<u32, bin::Builder>,
-    intervals: Vec<bgzf::VirtualPosition>,
+    intervals: Vec<Option<bgzf::VirtualPosition>>,
 }
 
 impl Builder {
@@ -29,11 +27,26 @@ impl Builder {
             .map(|(_, b)| b.build())
             .collect();
 
-        ReferenceSequence::new(bins, self.intervals)
+        // Empty windows take the offset of the next nonempty window.
+        let mut intervals = Vec::with_capacity(self.intervals.len());
+        let mut next_interval = bgzf::VirtualPosition::default();
+
+        for interval in self.intervals.into_iter().rev() {
+            if let Some(virtual_position) = interval {
+                next_interval = virtual_position;
+            }
+
+            intervals.push(next_interval);
+        }
+
+        intervals.reverse();
+
+        ReferenceSequence::new(bins, intervals)
     }
 
+    // `start` and `end` are 1-based, inclusive.
     fn update_bins(&mut self, start: u32, end: u32, chunk: Chunk) {
-        let bin_id = region_to_bin(start, end);
+        let bin_id = region_to_bin(start - 1, end);
 
         let builder = self.bin_builders.entry(bin_id).or_insert_with(|| {
             let mut builder = Bin::builder();
@@ -49,14 +62,14 @@ impl Builder {
         let linear_index_end_offset = ((end - 1) / WINDOW_SIZE) as usize;
 
         if linear_index_end_offset >= self.intervals.len() {
-            self.intervals.resize(
-                linear_index_end_offset + 1,
-                bgzf::VirtualPosition::default(),
-            );
+            self.intervals.resize(linear_index_end_offset + 1, None);
         }
 
-        for i in linear_index_start_offset..=linear_index_end_offset {
-            self.intervals[i] = chunk.start();
+        // Each window keeps the start of the first chunk that overlaps it.
+        for interval in &mut self.intervals[linear_index_start_offset..=linear_index_end_offset] {
+            if interval.is_none() {
+                *interval = Some(chunk.start());
+            }
         }
     }
 }
@@ -100,4 +113,47 @@ mod tests {
         // [63245986, 63245986]
         assert_eq!(region_to_bin(63245985, 63255986), 8541);
     }
+
+    #[test]
+    fn test_build_with_linear_index() {
+        let mut builder = Builder::default();
+
+        builder.add_record(
+            1,
+            8,
+            Chunk::new(
+                bgzf::VirtualPosition::from(0),
+                bgzf::VirtualPosition::from(8),
+            ),
+        );
+
+        builder.add_record(
+            13,
+            21,
+            Chunk::new(
+                bgzf::VirtualPosition::from(8),
+                bgzf::VirtualPosition::from(13),
+            ),
+        );
+
+        builder.add_record(
+            2 * WINDOW_SIZE + 1,
+            2 * WINDOW_SIZE + 8,
+            Chunk::new(
+                bgzf::VirtualPosition::from(13),
+                bgzf::VirtualPosition::from(21),
+            ),
+        );
+
+        let reference_sequence = builder.build();
+
+        assert_eq!(
+            reference_sequence.intervals(),
+            [
+                bgzf::VirtualPosition::from(0),
+                bgzf::VirtualPosition::from(13),
+                bgzf::VirtualPosition::from(13),
+            ]
+        );
+    }
 }
@@ -26,10 +26,16 @@
 //! ```
 
 pub mod index;
+pub mod query;
 mod reader;
 mod writer;
 
-pub use self::{index::Index, reader::Reader, writer::Writer};
+pub use self::{
+    index::Index,
+    query::{query, Query},
+    reader::Reader,
+    writer::Writer,
+};
 
 use std::{fs::File, io, path::Path};
 
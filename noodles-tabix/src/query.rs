@@ -0,0 +1,397 @@
+//! Tabix-backed region query.
+
+use std::{
+    convert::TryFrom,
+    error,
+    io::{self, BufRead, Read, Seek},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use noodles::Region;
+use noodles_bgzf as bgzf;
+
+use super::{
+    index::{parse_line, reference_sequence::bin::Chunk, Header},
+    Index,
+};
+
+enum State {
+    Seek,
+    Read(bgzf::VirtualPosition),
+    End,
+}
+
+/// An iterator over lines of a bgzipped, tabix-indexed text file that intersect a given region.
+///
+/// Each line is returned without its line ending. This is created by calling [`query`].
+pub struct Query<'a, R>
+where
+    R: Read + Seek,
+{
+    reader: &'a mut bgzf::Reader<R>,
+    header: Header,
+    chunks: Vec<Chunk>,
+    reference_sequence_name: String,
+    start: u32,
+    end: u32,
+    i: usize,
+    state: State,
+    line_buf: String,
+}
+
+/// Returns an iterator over lines that intersect the given region.
+///
+/// The lines are read from the given BGZF reader, and the locations of the region are looked up
+/// in the associated tabix index. The columns used to determine the interval of each line are
+/// described by the index header.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io};
+/// use noodles::Region;
+/// use noodles_bgzf as bgzf;
+/// use noodles_tabix as tabix;
+///
+/// let mut reader = File::open("sample.vcf.gz").map(bgzf::Reader::new)?;
+/// let index = tabix::read("sample.vcf.gz.tbi")?;
+/// let region = Region::mapped("sq0", 8, 13);
+///
+/// for result in tabix::query(&mut reader, &index, &region)? {
+///     let line = result?;
+///     println!("{}", line);
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn query<'a, R>(
+    reader: &'a mut bgzf::Reader<R>,
+    index: &Index,
+    region: &Region,
+) -> io::Result<Query<'a, R>>
+where
+    R: Read + Seek,
+{
+    let (name, start, end) = resolve_region(region)?;
+
+    let chunks = match index
+        .reference_sequence_names()
+        .iter()
+        .position(|n| n == name)
+    {
+        Some(i) => {
+            let reference_sequence = &index.reference_sequences()[i];
+
+            let chunks: Vec<_> = reference_sequence
+                .query(start, end)
+                .iter()
+                .flat_map(|bin| bin.chunks())
+                .copied()
+                .collect();
+
+            let min_offset = reference_sequence.min_offset(start);
+            optimize_chunks(&chunks, min_offset)
+        }
+        // A reference sequence that is not in the index has no records.
+        None => Vec::new(),
+    };
+
+    Ok(Query {
+        reader,
+        header: index.header().clone(),
+        chunks,
+        reference_sequence_name: name.into(),
+        start,
+        end,
+        i: 0,
+        state: State::Seek,
+        line_buf: String::new(),
+    })
+}
+
+impl<'a, R> Query<'a, R>
+where
+    R: Read + Seek,
+{
+    /// Converts this query into an iterator over parsed records.
+    ///
+    /// Each line is parsed using the [`FromStr`] implementation of `T`, e.g., a VCF or GFF record.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles::Region;
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_tabix as tabix;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut reader = File::open("sample.vcf.gz").map(bgzf::Reader::new)?;
+    /// let index = tabix::read("sample.vcf.gz.tbi")?;
+    /// let region = Region::mapped("sq0", 8, 13);
+    ///
+    /// for result in tabix::query(&mut reader, &index, &region)?.records::<vcf::Record>() {
+    ///     let record = result?;
+    ///     println!("{:?}", record);
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn records<T>(self) -> Records<'a, R, T>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        Records {
+            inner: self,
+            _marker: PhantomData,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<Option<bgzf::VirtualPosition>> {
+        if self.i >= self.chunks.len() {
+            return Ok(None);
+        }
+
+        let chunk = self.chunks[self.i];
+        self.reader.seek(chunk.start())?;
+
+        self.i += 1;
+
+        Ok(Some(chunk.end()))
+    }
+
+    fn read_line(&mut self) -> io::Result<usize> {
+        self.line_buf.clear();
+
+        match self.reader.read_line(&mut self.line_buf) {
+            Ok(0) => Ok(0),
+            Ok(n) => {
+                if self.line_buf.ends_with('\n') {
+                    self.line_buf.pop();
+
+                    if self.line_buf.ends_with('\r') {
+                        self.line_buf.pop();
+                    }
+                }
+
+                Ok(n)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn intersects(&self) -> io::Result<bool> {
+        if self.line_buf.is_empty()
+            || self.line_buf.as_bytes()[0] == self.header.line_comment_prefix()
+        {
+            return Ok(false);
+        }
+
+        let (name, start, end) = parse_line(&self.line_buf, &self.header)?;
+
+        Ok(name == self.reference_sequence_name && in_interval(start, end, self.start, self.end))
+    }
+}
+
+impl<'a, R> Iterator for Query<'a, R>
+where
+    R: Read + Seek,
+{
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state {
+                State::Seek => {
+                    self.state = match self.next_chunk() {
+                        Ok(Some(chunk_end)) => State::Read(chunk_end),
+                        Ok(None) => State::End,
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                State::Read(chunk_end) => match self.read_line() {
+                    Ok(0) => {
+                        self.state = State::Seek;
+                    }
+                    Ok(_) => {
+                        if self.reader.virtual_position() >= chunk_end {
+                            self.state = State::Seek;
+                        }
+
+                        match self.intersects() {
+                            Ok(true) => return Some(Ok(self.line_buf.clone())),
+                            Ok(false) => {}
+                            Err(e) => return Some(Err(e)),
+                        }
+                    }
+                    Err(e) => return Some(Err(e)),
+                },
+                State::End => return None,
+            }
+        }
+    }
+}
+
+/// An iterator over parsed records of a bgzipped, tabix-indexed text file that intersect a given
+/// region.
+///
+/// This is created by calling [`Query::records`].
+pub struct Records<'a, R, T>
+where
+    R: Read + Seek,
+{
+    inner: Query<'a, R>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, R, T> Iterator for Records<'a, R, T>
+where
+    R: Read + Seek,
+    T: FromStr,
+    T::Err: Into<Box<dyn error::Error + Send + Sync>>,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|result| {
+            result.and_then(|line| {
+                line.parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+        })
+    }
+}
+
+fn resolve_region(region: &Region) -> io::Result<(&str, u32, u32)> {
+    match region {
+        Region::Mapped { name, start, end } => match (u32::try_from(*start), u32::try_from(*end)) {
+            (Ok(s), Ok(e)) if s > 0 && s <= e => Ok((name.as_str(), s, e)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid region interval: {}-{}", start, end),
+            )),
+        },
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "region is not mapped",
+        )),
+    }
+}
+
+// Merges overlapping chunks and removes chunks that end before `min_offset`.
+fn optimize_chunks(chunks: &[Chunk], min_offset: bgzf::VirtualPosition) -> Vec<Chunk> {
+    let mut chunks: Vec<_> = chunks
+        .iter()
+        .filter(|c| c.end() > min_offset)
+        .copied()
+        .collect();
+
+    if chunks.is_empty() {
+        return chunks;
+    }
+
+    chunks.sort_unstable_by_key(|c| c.start());
+
+    let mut merged_chunks = Vec::with_capacity(chunks.len());
+
+    // `chunks` is guaranteed to be non-empty.
+    let mut current_chunk = chunks[0];
+
+    for next_chunk in chunks.iter().skip(1) {
+        if next_chunk.start() > current_chunk.end() {
+            merged_chunks.push(current_chunk);
+            current_chunk = *next_chunk;
+        } else if current_chunk.end() < next_chunk.end() {
+            current_chunk = Chunk::new(current_chunk.start(), next_chunk.end());
+        }
+    }
+
+    merged_chunks.push(current_chunk);
+
+    merged_chunks
+}
+
+fn in_interval(a_start: u32, a_end: u32, b_start: u32, b_end: u32) -> bool {
+    a_start <= b_end && b_start <= a_end
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use crate::index::{self, header};
+
+    use super::*;
+
+    fn build_data(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut writer = bgzf::Writer::new(Vec::new());
+        writer.write_all(data)?;
+        writer.finish()
+    }
+
+    #[test]
+    fn test_query() -> io::Result<()> {
+        let data = build_data(
+            b"#chrom\tstart\tend\nsq0\t7\t13\nsq0\t20\t34\nsq0\t40000\t40010\nsq1\t7\t13\n",
+        )?;
+
+        let index = {
+            let mut reader = bgzf::LineReader::new(bgzf::Reader::new(&data[..]));
+            index::index_lines(&mut reader, header::Builder::bed().build())?
+        };
+
+        let mut reader = bgzf::Reader::new(Cursor::new(&data));
+
+        let region = Region::mapped("sq0", 10, 21);
+        let lines: Vec<_> = query(&mut reader, &index, &region)?.collect::<io::Result<_>>()?;
+        assert_eq!(
+            lines,
+            [String::from("sq0\t7\t13"), String::from("sq0\t20\t34")]
+        );
+
+        let region = Region::mapped("sq1", 1, 5);
+        assert!(query(&mut reader, &index, &region)?.next().is_none());
+
+        let region = Region::mapped("sq2", 1, 5);
+        assert!(query(&mut reader, &index, &region)?.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_region() {
+        assert_eq!(
+            resolve_region(&Region::mapped("sq0", 8, 13)).ok(),
+            Some(("sq0", 8, 13))
+        );
+        assert!(resolve_region(&Region::mapped("sq0", 0, 13)).is_err());
+        assert!(resolve_region(&Region::mapped("sq0", 13, 8)).is_err());
+        assert!(resolve_region(&Region::Unmapped).is_err());
+    }
+
+    #[test]
+    fn test_optimize_chunks() {
+        let chunks = [
+            Chunk::new(
+                bgzf::VirtualPosition::from(2),
+                bgzf::VirtualPosition::from(3),
+            ),
+            Chunk::new(
+                bgzf::VirtualPosition::from(5),
+                bgzf::VirtualPosition::from(8),
+            ),
+            Chunk::new(
+                bgzf::VirtualPosition::from(7),
+                bgzf::VirtualPosition::from(13),
+            ),
+        ];
+
+        let actual = optimize_chunks(&chunks, bgzf::VirtualPosition::from(5));
+        let expected = [Chunk::new(
+            bgzf::VirtualPosition::from(5),
+            bgzf::VirtualPosition::from(13),
+        )];
+
+        assert_eq!(actual, expected);
+    }
+}
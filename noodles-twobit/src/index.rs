@@ -0,0 +1,83 @@
+//! 2bit index and record.
+
+/// A 2bit index record.
+///
+/// An index record is the name of a sequence and the offset of its sequence record in the file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    name: String,
+    offset: u64,
+}
+
+impl Record {
+    /// Creates a 2bit index record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_twobit::index;
+    /// let record = index::Record::new(String::from("sq0"), 32);
+    /// ```
+    pub fn new(name: String, offset: u64) -> Self {
+        Self { name, offset }
+    }
+
+    /// Returns the sequence name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_twobit::index;
+    /// let record = index::Record::new(String::from("sq0"), 32);
+    /// assert_eq!(record.name(), "sq0");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the offset of the sequence record in the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_twobit::index;
+    /// let record = index::Record::new(String::from("sq0"), 32);
+    /// assert_eq!(record.offset(), 32);
+    /// ```
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+/// A 2bit index.
+///
+/// The index is the file header and the list of sequences in the file. It is read using
+/// [`crate::Reader::read_index`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Index {
+    is_big_endian: bool,
+    records: Vec<Record>,
+}
+
+impl Index {
+    pub(crate) fn new(is_big_endian: bool, records: Vec<Record>) -> Self {
+        Self {
+            is_big_endian,
+            records,
+        }
+    }
+
+    pub(crate) fn is_big_endian(&self) -> bool {
+        self.is_big_endian
+    }
+
+    /// Returns the index records.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Returns the index record with the given sequence name.
+    pub fn get(&self, name: &str) -> Option<&Record> {
+        self.records.iter().find(|record| record.name() == name)
+    }
+}
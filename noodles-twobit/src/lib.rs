@@ -0,0 +1,50 @@
+#![deny(missing_docs)]
+
+//! **noodles-twobit** handles the reading of the UCSC 2bit format.
+//!
+//! 2bit is a compact binary format for reference sequences. Bases are packed into 2 bits each,
+//! and runs of `N` and soft-masked regions are stored separately as blocks. Sequences and
+//! subsequences are read directly using the sequence index in the file header, which makes it a
+//! smaller and faster alternative to FASTA and its index (FAI) for whole genomes.
+//!
+//! # Examples
+//!
+//! ## Read a subsequence
+//!
+//! ```no_run
+//! # use std::{fs::File, io};
+//! use noodles_twobit as twobit;
+//!
+//! let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+//! let index = reader.read_index()?;
+//!
+//! let sequence = reader.read_subsequence(&index, "sq0", 8, 13)?;
+//! println!("{}", String::from_utf8_lossy(&sequence));
+//! # Ok::<(), io::Error>(())
+//! ```
+//!
+//! ## Load reference sequences for pileup
+//!
+//! ```no_run
+//! # use std::{collections::HashMap, fs::File, io};
+//! use noodles_twobit as twobit;
+//!
+//! let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+//! let index = reader.read_index()?;
+//!
+//! let mut reference = HashMap::new();
+//!
+//! for record in index.records() {
+//!     let sequence = reader.read_sequence(&index, record.name())?;
+//!     reference.insert(record.name().to_string(), sequence);
+//! }
+//! # Ok::<(), io::Error>(())
+//! ```
+
+pub mod index;
+mod reader;
+
+pub use self::{index::Index, reader::Reader};
+
+// 0x1a412743 as written by a little-endian machine.
+const MAGIC_NUMBER: u32 = 0x1a41_2743;
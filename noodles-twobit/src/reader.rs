@@ -0,0 +1,530 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use noodles_fasta as fasta;
+
+use super::{index, Index, MAGIC_NUMBER};
+
+const BASES: [u8; 4] = [b'T', b'C', b'A', b'G'];
+
+// The sequence record header of a single sequence.
+struct SequenceRecord {
+    dna_size: u64,
+    n_blocks: Vec<(u64, u64)>,
+    mask_blocks: Vec<(u64, u64)>,
+    dna_offset: u64,
+}
+
+/// A 2bit reader.
+///
+/// 2bit is a compact binary format for reference sequences. Each base is packed into 2 bits, and
+/// runs of `N` and soft-masked (lowercase) regions are stored as blocks. Every sequence record
+/// can be read directly, and subsequences are read without decoding the entire sequence.
+///
+/// Bases in `N` blocks are returned as `N`, and bases in mask blocks are returned in lowercase.
+pub struct Reader<R> {
+    inner: R,
+}
+
+impl<R> Reader<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a 2bit reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_twobit as twobit;
+    /// let reader = twobit::Reader::new(io::Cursor::new(Vec::new()));
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_twobit as twobit;
+    /// let reader = twobit::Reader::new(io::Cursor::new(Vec::new()));
+    /// assert!(reader.get_ref().get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Reads the file header and sequence index.
+    ///
+    /// The byte order of the file is detected from the magic number. Both version 0 (32-bit
+    /// offsets) and version 1 (64-bit offsets) files are supported.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_twobit as twobit;
+    ///
+    /// let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+    /// let index = reader.read_index()?;
+    ///
+    /// for record in index.records() {
+    ///     println!("{}", record.name());
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_index(&mut self) -> io::Result<Index> {
+        self.inner.seek(SeekFrom::Start(0))?;
+
+        let magic = self.inner.read_u32::<LittleEndian>()?;
+
+        let is_big_endian = if magic == MAGIC_NUMBER {
+            false
+        } else if magic == MAGIC_NUMBER.swap_bytes() {
+            true
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid 2bit header",
+            ));
+        };
+
+        let version = read_u32(&mut self.inner, is_big_endian)?;
+
+        if version > 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported 2bit version: {}", version),
+            ));
+        }
+
+        let sequence_count = read_u32(&mut self.inner, is_big_endian)?;
+        // reserved
+        read_u32(&mut self.inner, is_big_endian)?;
+
+        let mut records = Vec::with_capacity(sequence_count as usize);
+
+        for _ in 0..sequence_count {
+            let name_size = self.inner.read_u8()?;
+            let mut buf = vec![0; usize::from(name_size)];
+            self.inner.read_exact(&mut buf)?;
+
+            let name = String::from_utf8(buf)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let offset = if version == 1 {
+                read_u64(&mut self.inner, is_big_endian)?
+            } else {
+                u64::from(read_u32(&mut self.inner, is_big_endian)?)
+            };
+
+            records.push(index::Record::new(name, offset));
+        }
+
+        Ok(Index::new(is_big_endian, records))
+    }
+
+    /// Returns the length of a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_twobit as twobit;
+    ///
+    /// let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+    /// let index = reader.read_index()?;
+    /// let len = reader.sequence_len(&index, "sq0")?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn sequence_len(&mut self, index: &Index, name: &str) -> io::Result<u64> {
+        self.read_sequence_record(index, name)
+            .map(|record| record.dna_size)
+    }
+
+    /// Reads the entire sequence with the given name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_twobit as twobit;
+    ///
+    /// let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+    /// let index = reader.read_index()?;
+    /// let sequence = reader.read_sequence(&index, "sq0")?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_sequence(&mut self, index: &Index, name: &str) -> io::Result<Vec<u8>> {
+        let record = self.read_sequence_record(index, name)?;
+        self.read_bases(&record, 0, record.dna_size)
+    }
+
+    /// Reads a subsequence of the sequence with the given name.
+    ///
+    /// `start` and `end` are 1-based and inclusive. Only the bytes that pack the subsequence are
+    /// read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_twobit as twobit;
+    ///
+    /// let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+    /// let index = reader.read_index()?;
+    /// let sequence = reader.read_subsequence(&index, "sq0", 8, 13)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_subsequence(
+        &mut self,
+        index: &Index,
+        name: &str,
+        start: i64,
+        end: i64,
+    ) -> io::Result<Vec<u8>> {
+        let record = self.read_sequence_record(index, name)?;
+
+        let (start, end) = u64::try_from(start - 1)
+            .and_then(|s| u64::try_from(end).map(|e| (s, e)))
+            .ok()
+            .filter(|&(s, e)| s <= e && e <= record.dna_size)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid interval: {}-{}", start, end),
+                )
+            })?;
+
+        self.read_bases(&record, start, end)
+    }
+
+    /// Reads the sequence with the given name as a FASTA record.
+    ///
+    /// This can be used to provide reference sequences, e.g., to a CRAM writer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{fs::File, io};
+    /// use noodles_twobit as twobit;
+    ///
+    /// let mut reader = File::open("reference.2bit").map(twobit::Reader::new)?;
+    /// let index = reader.read_index()?;
+    ///
+    /// let reference_sequences = index
+    ///     .records()
+    ///     .iter()
+    ///     .map(|record| reader.read_record(&index, record.name()))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn read_record(&mut self, index: &Index, name: &str) -> io::Result<fasta::Record> {
+        let sequence = self.read_sequence(index, name)?;
+        let definition = fasta::record::Definition::new(name.into(), None);
+        Ok(fasta::Record::new(definition, sequence))
+    }
+
+    fn read_sequence_record(&mut self, index: &Index, name: &str) -> io::Result<SequenceRecord> {
+        let index_record = index.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("sequence not in index: {}", name),
+            )
+        })?;
+
+        let is_big_endian = index.is_big_endian();
+
+        self.inner.seek(SeekFrom::Start(index_record.offset()))?;
+
+        let dna_size = read_u32(&mut self.inner, is_big_endian).map(u64::from)?;
+        let n_blocks = read_blocks(&mut self.inner, is_big_endian)?;
+        let mask_blocks = read_blocks(&mut self.inner, is_big_endian)?;
+        // reserved
+        read_u32(&mut self.inner, is_big_endian)?;
+
+        let dna_offset = self.inner.stream_position()?;
+
+        Ok(SequenceRecord {
+            dna_size,
+            n_blocks,
+            mask_blocks,
+            dna_offset,
+        })
+    }
+
+    // Reads and decodes the bases in the 0-based, half-open interval [start, end).
+    fn read_bases(&mut self, record: &SequenceRecord, start: u64, end: u64) -> io::Result<Vec<u8>> {
+        let first_byte = start / 4;
+        let last_byte = end.div_ceil(4);
+
+        self.inner
+            .seek(SeekFrom::Start(record.dna_offset + first_byte))?;
+
+        let mut buf = vec![0; (last_byte - first_byte) as usize];
+        self.inner.read_exact(&mut buf)?;
+
+        let mut sequence: Vec<u8> = (start..end)
+            .map(|i| {
+                let byte = buf[(i / 4 - first_byte) as usize];
+                let shift = 6 - 2 * (i % 4);
+                BASES[usize::from((byte >> shift) & 0x03)]
+            })
+            .collect();
+
+        for (block_start, block_end) in overlaps(&record.n_blocks, start, end) {
+            for base in &mut sequence[block_start..block_end] {
+                *base = b'N';
+            }
+        }
+
+        for (block_start, block_end) in overlaps(&record.mask_blocks, start, end) {
+            sequence[block_start..block_end].make_ascii_lowercase();
+        }
+
+        Ok(sequence)
+    }
+}
+
+fn read_u32<R>(reader: &mut R, is_big_endian: bool) -> io::Result<u32>
+where
+    R: Read,
+{
+    if is_big_endian {
+        reader.read_u32::<BigEndian>()
+    } else {
+        reader.read_u32::<LittleEndian>()
+    }
+}
+
+fn read_u64<R>(reader: &mut R, is_big_endian: bool) -> io::Result<u64>
+where
+    R: Read,
+{
+    if is_big_endian {
+        reader.read_u64::<BigEndian>()
+    } else {
+        reader.read_u64::<LittleEndian>()
+    }
+}
+
+// Reads a block count, block starts, and block sizes as a list of 0-based, half-open intervals.
+fn read_blocks<R>(reader: &mut R, is_big_endian: bool) -> io::Result<Vec<(u64, u64)>>
+where
+    R: Read,
+{
+    let count = read_u32(reader, is_big_endian)? as usize;
+
+    let starts = (0..count)
+        .map(|_| read_u32(reader, is_big_endian).map(u64::from))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let sizes = (0..count)
+        .map(|_| read_u32(reader, is_big_endian).map(u64::from))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    Ok(starts
+        .into_iter()
+        .zip(sizes)
+        .map(|(start, size)| (start, start + size))
+        .collect())
+}
+
+// Returns the parts of blocks that overlap [start, end) relative to `start`.
+fn overlaps(
+    blocks: &[(u64, u64)],
+    start: u64,
+    end: u64,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    blocks.iter().filter_map(move |&(block_start, block_end)| {
+        let overlap_start = block_start.max(start);
+        let overlap_end = block_end.min(end);
+
+        if overlap_start < overlap_end {
+            Some((
+                (overlap_start - start) as usize,
+                (overlap_end - start) as usize,
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use byteorder::{ByteOrder, WriteBytesExt};
+
+    use super::*;
+
+    // (name, sequence, N blocks, mask blocks)
+    type Sequence = (
+        &'static str,
+        &'static [u8],
+        &'static [(u32, u32)],
+        &'static [(u32, u32)],
+    );
+
+    fn encode_base(base: u8) -> u8 {
+        match base.to_ascii_uppercase() {
+            b'C' => 1,
+            b'A' => 2,
+            b'G' => 3,
+            _ => 0,
+        }
+    }
+
+    fn write_blocks<B>(buf: &mut Vec<u8>, blocks: &[(u32, u32)]) -> io::Result<()>
+    where
+        B: ByteOrder,
+    {
+        buf.write_u32::<B>(blocks.len() as u32)?;
+
+        for &(start, _) in blocks {
+            buf.write_u32::<B>(start)?;
+        }
+
+        for &(_, size) in blocks {
+            buf.write_u32::<B>(size)?;
+        }
+
+        Ok(())
+    }
+
+    // Builds a version 0 2bit file.
+    fn build_file<B>(sequences: &[Sequence]) -> io::Result<Vec<u8>>
+    where
+        B: ByteOrder,
+    {
+        let mut records = Vec::new();
+
+        for (_, sequence, n_blocks, mask_blocks) in sequences {
+            let mut buf = Vec::new();
+            buf.write_u32::<B>(sequence.len() as u32)?;
+            write_blocks::<B>(&mut buf, n_blocks)?;
+            write_blocks::<B>(&mut buf, mask_blocks)?;
+            buf.write_u32::<B>(0)?;
+
+            for chunk in sequence.chunks(4) {
+                let mut byte = 0;
+
+                for (i, &base) in chunk.iter().enumerate() {
+                    byte |= encode_base(base) << (6 - 2 * i);
+                }
+
+                buf.push(byte);
+            }
+
+            records.push(buf);
+        }
+
+        let index_len: usize = sequences.iter().map(|(name, ..)| 1 + name.len() + 4).sum();
+
+        let mut buf = Vec::new();
+        buf.write_u32::<B>(MAGIC_NUMBER)?;
+        buf.write_u32::<B>(0)?;
+        buf.write_u32::<B>(sequences.len() as u32)?;
+        buf.write_u32::<B>(0)?;
+
+        let mut offset = 16 + index_len;
+
+        for ((name, ..), record) in sequences.iter().zip(&records) {
+            buf.push(name.len() as u8);
+            buf.extend(name.as_bytes());
+            buf.write_u32::<B>(offset as u32)?;
+            offset += record.len();
+        }
+
+        for record in records {
+            buf.extend(record);
+        }
+
+        Ok(buf)
+    }
+
+    fn build_sequences() -> Vec<Sequence> {
+        vec![
+            ("sq0", &b"ACGTNNNNacgtA"[..], &[(4, 4)][..], &[(8, 4)][..]),
+            ("sq1", &b"TTGCA"[..], &[][..], &[][..]),
+        ]
+    }
+
+    #[test]
+    fn test_read_index() -> io::Result<()> {
+        let data = build_file::<LittleEndian>(&build_sequences())?;
+        let mut reader = Reader::new(Cursor::new(data));
+        let index = reader.read_index()?;
+
+        assert_eq!(index.records().len(), 2);
+        assert_eq!(index.records()[0].name(), "sq0");
+        assert_eq!(index.records()[1].name(), "sq1");
+        assert!(!index.is_big_endian());
+
+        let data = build_file::<BigEndian>(&build_sequences())?;
+        let mut reader = Reader::new(Cursor::new(data));
+        let index = reader.read_index()?;
+        assert!(index.is_big_endian());
+
+        let mut reader = Reader::new(Cursor::new(vec![0; 16]));
+        assert!(reader.read_index().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_sequence() -> io::Result<()> {
+        for data in [
+            build_file::<LittleEndian>(&build_sequences())?,
+            build_file::<BigEndian>(&build_sequences())?,
+        ] {
+            let mut reader = Reader::new(Cursor::new(data));
+            let index = reader.read_index()?;
+
+            assert_eq!(reader.read_sequence(&index, "sq0")?, b"ACGTNNNNacgtA");
+            assert_eq!(reader.read_sequence(&index, "sq1")?, b"TTGCA");
+            assert_eq!(reader.sequence_len(&index, "sq0")?, 13);
+
+            assert!(reader.read_sequence(&index, "sq2").is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_subsequence() -> io::Result<()> {
+        let data = build_file::<LittleEndian>(&build_sequences())?;
+        let mut reader = Reader::new(Cursor::new(data));
+        let index = reader.read_index()?;
+
+        assert_eq!(reader.read_subsequence(&index, "sq0", 3, 10)?, b"GTNNNNac");
+        assert_eq!(reader.read_subsequence(&index, "sq0", 13, 13)?, b"A");
+        assert_eq!(reader.read_subsequence(&index, "sq1", 2, 4)?, b"TGC");
+        assert!(reader.read_subsequence(&index, "sq1", 2, 1)?.is_empty());
+
+        assert!(reader.read_subsequence(&index, "sq1", 0, 4).is_err());
+        assert!(reader.read_subsequence(&index, "sq1", 2, 6).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_record() -> io::Result<()> {
+        let data = build_file::<LittleEndian>(&build_sequences())?;
+        let mut reader = Reader::new(Cursor::new(data));
+        let index = reader.read_index()?;
+
+        let record = reader.read_record(&index, "sq1")?;
+        assert_eq!(record.reference_sequence_name(), "sq1");
+        assert_eq!(record.sequence(), b"TTGCA");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,506 @@
+//! VCF record annotation.
+//!
+//! An annotator copies fields from an annotation source into records, like `bcftools annotate`.
+//! The source is queried by the region of each record, typically using a tabix index of a
+//! bgzipped VCF or BED file (see `noodles_tabix::query`).
+//!
+//! Annotations from a VCF source are matched by position and alleles. The record IDs (`ID`)
+//! and/or selected information fields (`INFO`) are copied. Annotations from a BED source are
+//! matched by overlap, and selected columns are copied into information fields.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::io;
+//! use noodles_vcf::{
+//!     self as vcf,
+//!     annotate::{Annotate, VcfAnnotator},
+//! };
+//!
+//! let source_header: vcf::Header = "##fileformat=VCFv4.3
+//! ###INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+//! #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+//! ".parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+//!
+//! let source_lines = vec![String::from("sq0\t8\tnd0\tA\tC\t.\tPASS\tDP=13")];
+//!
+//! let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> {
+//!     Ok(source_lines.clone())
+//! };
+//!
+//! let mut annotator = VcfAnnotator::new(source, source_header)
+//!     .copy_ids()
+//!     .copy_info("DP");
+//!
+//! let mut header = vcf::Header::default();
+//! annotator.update_header(&mut header);
+//! assert_eq!(header.infos().len(), 1);
+//!
+//! let mut record: vcf::Record = "sq0\t8\t.\tA\tC\t.\tPASS\t.".parse().map_err(|e| {
+//!     io::Error::new(io::ErrorKind::InvalidData, e)
+//! })?;
+//!
+//! annotator.annotate(&mut record)?;
+//! assert_eq!(record.ids().to_string(), "nd0");
+//! assert_eq!(record.info().to_string(), "DP=13");
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::io;
+
+use super::{
+    header,
+    record::{info, Ids, Info},
+    Header, Record,
+};
+
+const MISSING_FIELD: &str = ".";
+
+/// An annotation source.
+///
+/// This is implemented for closures `FnMut(&str, i64, i64) -> io::Result<Vec<String>>`.
+pub trait Source {
+    /// Returns the raw lines that intersect the given region.
+    ///
+    /// The region is given by a reference sequence name and a 1-based, inclusive interval.
+    fn query(
+        &mut self,
+        reference_sequence_name: &str,
+        start: i64,
+        end: i64,
+    ) -> io::Result<Vec<String>>;
+}
+
+impl<F> Source for F
+where
+    F: FnMut(&str, i64, i64) -> io::Result<Vec<String>>,
+{
+    fn query(
+        &mut self,
+        reference_sequence_name: &str,
+        start: i64,
+        end: i64,
+    ) -> io::Result<Vec<String>> {
+        self(reference_sequence_name, start, end)
+    }
+}
+
+/// A record annotator.
+pub trait Annotate {
+    /// Adds the definitions of annotated fields to the header.
+    ///
+    /// Information fields that are already defined are not changed.
+    fn update_header(&self, header: &mut Header);
+
+    /// Copies matching annotations into the record.
+    ///
+    /// Existing fields are replaced. Fields that are missing in the annotation are left unchanged.
+    fn annotate(&mut self, record: &mut Record) -> io::Result<()>;
+}
+
+/// An annotator with a VCF source.
+pub struct VcfAnnotator<S> {
+    source: S,
+    header: Header,
+    copy_ids: bool,
+    info_keys: Vec<String>,
+    match_alleles: bool,
+}
+
+impl<S> VcfAnnotator<S>
+where
+    S: Source,
+{
+    /// Creates an annotator with a VCF source.
+    ///
+    /// The header of the source is used for the definitions of copied information fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_vcf::{self as vcf, annotate::VcfAnnotator};
+    ///
+    /// let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> { Ok(Vec::new()) };
+    /// let annotator = VcfAnnotator::new(source, vcf::Header::default());
+    /// ```
+    pub fn new(source: S, header: Header) -> Self {
+        Self {
+            source,
+            header,
+            copy_ids: false,
+            info_keys: Vec::new(),
+            match_alleles: true,
+        }
+    }
+
+    /// Copies the record IDs (`ID`).
+    pub fn copy_ids(mut self) -> Self {
+        self.copy_ids = true;
+        self
+    }
+
+    /// Copies the information field (`INFO`) with the given key.
+    pub fn copy_info<K>(mut self, key: K) -> Self
+    where
+        K: Into<String>,
+    {
+        self.info_keys.push(key.into());
+        self
+    }
+
+    /// Sets whether annotations must have the same reference and alternate bases.
+    ///
+    /// By default, annotations are matched by position and alleles. If disabled, annotations are
+    /// matched only by position.
+    pub fn set_match_alleles(mut self, match_alleles: bool) -> Self {
+        self.match_alleles = match_alleles;
+        self
+    }
+
+    fn find_annotation(&mut self, record: &Record) -> io::Result<Option<Record>> {
+        let (start, end) = record_interval(record);
+        let lines = self
+            .source
+            .query(&record.chromosome().to_string(), start, end)?;
+
+        for line in lines {
+            let annotation: Record = line
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let is_match = annotation.position() == record.position()
+                && (!self.match_alleles
+                    || (annotation.reference_bases() == record.reference_bases()
+                        && annotation.alternate_bases() == record.alternate_bases()));
+
+            if is_match {
+                return Ok(Some(annotation));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl<S> Annotate for VcfAnnotator<S>
+where
+    S: Source,
+{
+    fn update_header(&self, header: &mut Header) {
+        for key in &self.info_keys {
+            if let Some(info) = find_info(&self.header, key) {
+                add_info(header, info.clone());
+            }
+        }
+    }
+
+    fn annotate(&mut self, record: &mut Record) -> io::Result<()> {
+        let annotation = match self.find_annotation(record)? {
+            Some(annotation) => annotation,
+            None => return Ok(()),
+        };
+
+        if self.copy_ids && !annotation.ids().is_empty() {
+            *record.ids_mut() = Ids::from(annotation.ids().to_vec());
+        }
+
+        let mut fields = record.info().to_vec();
+
+        for key in &self.info_keys {
+            if let Some(field) = annotation
+                .info()
+                .iter()
+                .find(|field| field.key().as_ref() == key.as_str())
+            {
+                set_field(&mut fields, field.clone());
+            }
+        }
+
+        *record.info_mut() = Info::from(fields);
+
+        Ok(())
+    }
+}
+
+/// An annotator with a BED source.
+pub struct BedAnnotator<S> {
+    source: S,
+    columns: Vec<(usize, header::Info)>,
+}
+
+impl<S> BedAnnotator<S>
+where
+    S: Source,
+{
+    /// Creates an annotator with a BED source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_vcf::annotate::BedAnnotator;
+    ///
+    /// let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> { Ok(Vec::new()) };
+    /// let annotator = BedAnnotator::new(source);
+    /// ```
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Copies the BED column with the given 1-based index into an information field.
+    ///
+    /// The column value is parsed using the number and type of the given information field
+    /// definition. Missing (`.`) or empty values are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_vcf::{
+    ///     annotate::BedAnnotator,
+    ///     header::{info::Type, Info, Number},
+    ///     record::info::field::Key,
+    /// };
+    ///
+    /// let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> { Ok(Vec::new()) };
+    ///
+    /// let annotator = BedAnnotator::new(source).copy_column(
+    ///     4,
+    ///     Info::new(
+    ///         Key::Other(String::from("GENE"), Number::Count(1), Type::String),
+    ///         Number::Count(1),
+    ///         Type::String,
+    ///         String::from("Gene name"),
+    ///     ),
+    /// );
+    /// ```
+    pub fn copy_column(mut self, column: usize, info: header::Info) -> Self {
+        self.columns.push((column, info));
+        self
+    }
+}
+
+impl<S> Annotate for BedAnnotator<S>
+where
+    S: Source,
+{
+    fn update_header(&self, header: &mut Header) {
+        for (_, info) in &self.columns {
+            add_info(header, info.clone());
+        }
+    }
+
+    fn annotate(&mut self, record: &mut Record) -> io::Result<()> {
+        let (start, end) = record_interval(record);
+        let lines = self
+            .source
+            .query(&record.chromosome().to_string(), start, end)?;
+
+        let mut fields = record.info().to_vec();
+
+        for line in lines {
+            let columns: Vec<_> = line.split('\t').collect();
+
+            if !bed_line_overlaps(&columns, start, end)? {
+                continue;
+            }
+
+            for (i, info) in &self.columns {
+                let raw_value = match i.checked_sub(1).and_then(|j| columns.get(j)) {
+                    Some(&s) if !s.is_empty() && s != MISSING_FIELD => s,
+                    _ => continue,
+                };
+
+                let key = info.id();
+                let value = info::field::Value::from_str_key(raw_value, key)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                set_field(&mut fields, info::Field::new(key.clone(), value));
+            }
+
+            break;
+        }
+
+        *record.info_mut() = Info::from(fields);
+
+        Ok(())
+    }
+}
+
+/// Returns an iterator adapter that annotates each record.
+///
+/// The header of the output should first be updated using [`Annotate::update_header`].
+pub fn annotate_records<I, A>(records: I, annotator: A) -> AnnotateRecords<I::IntoIter, A>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+    A: Annotate,
+{
+    AnnotateRecords {
+        inner: records.into_iter(),
+        annotator,
+    }
+}
+
+/// An iterator adapter that annotates records.
+///
+/// This is created by calling [`annotate_records`].
+pub struct AnnotateRecords<I, A> {
+    inner: I,
+    annotator: A,
+}
+
+impl<I, A> Iterator for AnnotateRecords<I, A>
+where
+    I: Iterator<Item = io::Result<Record>>,
+    A: Annotate,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|result| {
+            result.and_then(|mut record| {
+                self.annotator.annotate(&mut record)?;
+                Ok(record)
+            })
+        })
+    }
+}
+
+// Returns the 1-based, inclusive interval spanned by the record.
+fn record_interval(record: &Record) -> (i64, i64) {
+    (record.position(), record.end())
+}
+
+// BED intervals are 0-based and half-open.
+fn bed_line_overlaps(columns: &[&str], start: i64, end: i64) -> io::Result<bool> {
+    let parse_position = |i: usize| -> io::Result<i64> {
+        columns
+            .get(i)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing BED position"))
+            .and_then(|s| {
+                s.parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+    };
+
+    let bed_start = parse_position(1)? + 1;
+    let bed_end = parse_position(2)?;
+
+    Ok(bed_start <= end && start <= bed_end)
+}
+
+fn find_info<'h>(header: &'h Header, key: &str) -> Option<&'h header::Info> {
+    header.infos().iter().find(|info| info.id().as_ref() == key)
+}
+
+fn add_info(header: &mut Header, info: header::Info) {
+    let is_defined = header.infos().iter().any(|i| i.id() == info.id());
+
+    if !is_defined {
+        header.infos_mut().push(info);
+    }
+}
+
+fn set_field(fields: &mut Vec<info::Field>, field: info::Field) {
+    match fields.iter().position(|f| f.key() == field.key()) {
+        Some(i) => fields[i] = field,
+        None => fields.push(field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::{info::Type, Number};
+
+    use super::*;
+
+    fn parse_record(s: &str) -> Record {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_vcf_annotator() -> io::Result<()> {
+        let source_lines = vec![
+            String::from("sq0\t8\tnd0\tA\tG\t.\tPASS\tDP=8;AF=0.5"),
+            String::from("sq0\t8\tnd1\tA\tC\t.\tPASS\tDP=13;AF=0.25"),
+        ];
+
+        let source = |name: &str, start: i64, end: i64| -> io::Result<Vec<String>> {
+            assert_eq!((name, start, end), ("sq0", 8, 9));
+            Ok(source_lines.clone())
+        };
+
+        let mut annotator = VcfAnnotator::new(source, Header::default())
+            .copy_ids()
+            .copy_info("DP");
+
+        let mut record = parse_record("sq0\t8\t.\tAT\tC\t.\tPASS\tDP=1;NS=2");
+        annotator.annotate(&mut record)?;
+        assert_eq!(record.ids().to_string(), ".");
+        assert_eq!(record.info().to_string(), "DP=1;NS=2");
+
+        let mut annotator = annotator.set_match_alleles(false);
+        annotator.annotate(&mut record)?;
+        assert_eq!(record.ids().to_string(), "nd0");
+        assert_eq!(record.info().to_string(), "DP=8;NS=2");
+
+        let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> {
+            Ok(vec![
+                String::from("sq0\t8\tnd0\tA\tG\t.\tPASS\tDP=8;AF=0.5"),
+                String::from("sq0\t8\tnd1\tA\tC\t.\tPASS\tDP=13;AF=0.25"),
+            ])
+        };
+
+        let mut annotator = VcfAnnotator::new(source, Header::default()).copy_info("AF");
+        let mut record = parse_record("sq0\t8\t.\tA\tC\t.\tPASS\t.");
+        annotator.annotate(&mut record)?;
+        assert_eq!(record.info().to_string(), "AF=0.25");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bed_annotator() -> io::Result<()> {
+        let info = header::Info::new(
+            info::field::Key::Other(String::from("GENE"), Number::Count(1), Type::String),
+            Number::Count(1),
+            Type::String,
+            String::from("Gene name"),
+        );
+
+        let source = |_: &str, _: i64, _: i64| -> io::Result<Vec<String>> {
+            Ok(vec![
+                String::from("sq0\t0\t7\tndls0"),
+                String::from("sq0\t7\t13\tndls1"),
+                String::from("sq0\t7\t13\t."),
+            ])
+        };
+
+        let mut annotator = BedAnnotator::new(source).copy_column(4, info);
+
+        let mut header = Header::default();
+        annotator.update_header(&mut header);
+        annotator.update_header(&mut header);
+        assert_eq!(header.infos().len(), 1);
+
+        let mut record = parse_record("sq0\t8\t.\tA\tC\t.\tPASS\t.");
+        annotator.annotate(&mut record)?;
+        assert_eq!(record.info().to_string(), "GENE=ndls1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bed_line_overlaps() -> io::Result<()> {
+        assert!(!bed_line_overlaps(&["sq0", "0", "7"], 8, 8)?);
+        assert!(bed_line_overlaps(&["sq0", "7", "8"], 8, 8)?);
+        assert!(!bed_line_overlaps(&["sq0", "8", "13"], 8, 8)?);
+        assert!(bed_line_overlaps(&["sq0", "8", "13"], 8, 9)?);
+        assert!(bed_line_overlaps(&["sq0"], 8, 8).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,523 @@
+//! Variant candidate generation.
+//!
+//! A [`CandidateGenerator`] scans pileup columns and emits candidate variant sites as VCF record
+//! skeletons, i.e., with only the chromosome, position, reference bases, alternate bases, and
+//! allele count information fields set. It does not genotype or filter sites; it provides the
+//! input for callers to do so.
+//!
+//! At each column, single-nucleotide variant (SNV) and indel candidates are emitted as separate
+//! records. SNV alleles are the aligned bases at the column. Indel alleles are the insertions and
+//! deletions that follow the aligned bases at the column, anchored on the reference base.
+//!
+//! Each record has the following information fields:
+//!
+//!   * `DP`: the number of reads informative for the record's alleles,
+//!   * `AD`: the number of reads supporting each allele (reference first),
+//!   * `ADF` and `ADR`: the number of forward and reverse strand reads supporting each allele,
+//!     and
+//!   * `SB`: strand bias counts, as reference forward, reference reverse, alternate forward, and
+//!     alternate reverse.
+
+use std::{cmp::Reverse, convert::TryFrom, error, fmt};
+
+use noodles_sam::pileup::{mpileup::Reference, Alignment, Column};
+
+use crate::{
+    record::{
+        alternate_bases::Allele,
+        chromosome,
+        info::{
+            field::{Key, Value},
+            Field,
+        },
+        reference_bases::Base,
+        AlternateBases, Chromosome, Info, ReferenceBases,
+    },
+    Record,
+};
+
+/// An error returned when candidates fail to generate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GenerateError {
+    /// The reference sequence name is not a valid chromosome.
+    InvalidChromosome(chromosome::ParseError),
+    /// A reference base is missing.
+    MissingReferenceBase(String, i64),
+}
+
+impl error::Error for GenerateError {}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChromosome(e) => write!(f, "invalid chromosome: {}", e),
+            Self::MissingReferenceBase(name, position) => {
+                write!(f, "missing reference base: {}:{}", name, position)
+            }
+        }
+    }
+}
+
+// Read counts of an allele: forward and reverse strand.
+type StrandCounts = [i32; 2];
+
+// An indel allele following the anchor base: the inserted bases and the deletion length.
+type IndelKey = (Vec<Base>, u32);
+
+/// A variant candidate generator.
+///
+/// By default, an alternate allele must be supported by at least 2 reads.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_sam::{self as sam, pileup::Engine};
+/// use noodles_vcf::call::CandidateGenerator;
+///
+/// let data = b"@HD\tVN:1.6\tSO:coordinate
+/// r0\t0\tsq0\t1\t60\t2M\t*\t0\t0\tAT\tII
+/// r1\t16\tsq0\t1\t60\t2M\t*\t0\t0\tAT\tII
+/// r2\t0\tsq0\t2\t60\t1M\t*\t0\t0\tC\tI
+/// ";
+///
+/// let mut reader = sam::Reader::new(&data[..]);
+/// reader.read_header()?;
+///
+/// let reference = |_: &str, position: i64| b"AC".get((position - 1) as usize).copied();
+/// let generator = CandidateGenerator::new(reference);
+///
+/// let mut records = Vec::new();
+///
+/// for result in Engine::new().pileup(reader.records()) {
+///     let column = result?;
+///     let candidates = generator
+///         .generate(&column)
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+///     records.extend(candidates);
+/// }
+///
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].position(), 2);
+/// assert_eq!(records[0].alternate_bases().to_string(), "T");
+/// assert_eq!(records[0].info().to_string(), "DP=3;AD=1,2;ADF=1,1;ADR=0,1;SB=1,0,1,1");
+/// # Ok::<_, io::Error>(())
+/// ```
+pub struct CandidateGenerator<R> {
+    reference: R,
+    min_alternate_count: i32,
+    min_alternate_fraction: f32,
+}
+
+impl<R> CandidateGenerator<R>
+where
+    R: Reference,
+{
+    /// Creates a variant candidate generator.
+    pub fn new(reference: R) -> Self {
+        Self {
+            reference,
+            min_alternate_count: 2,
+            min_alternate_fraction: 0.0,
+        }
+    }
+
+    /// Sets the minimum number of reads supporting an alternate allele.
+    pub fn set_min_alternate_count(mut self, min_alternate_count: i32) -> Self {
+        self.min_alternate_count = min_alternate_count;
+        self
+    }
+
+    /// Sets the minimum fraction of informative reads supporting an alternate allele.
+    pub fn set_min_alternate_fraction(mut self, min_alternate_fraction: f32) -> Self {
+        self.min_alternate_fraction = min_alternate_fraction;
+        self
+    }
+
+    /// Generates the candidate variant records of a pileup column.
+    ///
+    /// This returns at most two records: an SNV candidate, followed by an indel candidate.
+    /// Alternate alleles are ordered by descending read count.
+    pub fn generate(&self, column: &Column) -> Result<Vec<Record>, GenerateError> {
+        let name = column.reference_sequence_name();
+        let position = column.position();
+
+        let chromosome: Chromosome = name.parse().map_err(GenerateError::InvalidChromosome)?;
+        let reference_base = self.reference_base(name, position)?;
+
+        let mut records = Vec::new();
+
+        if let Some(record) = self.generate_snv(&chromosome, position, reference_base, column) {
+            records.push(record);
+        }
+
+        if let Some(record) =
+            self.generate_indel(&chromosome, name, position, reference_base, column)?
+        {
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    fn reference_base(&self, name: &str, position: i64) -> Result<Base, GenerateError> {
+        self.reference
+            .base(name, position)
+            .map(to_base)
+            .ok_or_else(|| GenerateError::MissingReferenceBase(name.into(), position))
+    }
+
+    fn generate_snv(
+        &self,
+        chromosome: &Chromosome,
+        position: i64,
+        reference_base: Base,
+        column: &Column,
+    ) -> Option<Record> {
+        let mut reference_counts = StrandCounts::default();
+        let mut alternate_counts: Vec<(Base, StrandCounts)> = Vec::new();
+
+        for alignment in column.alignments() {
+            let base = match alignment.base() {
+                Some(base) => to_base(char::from(base) as u8),
+                None => continue,
+            };
+
+            let i = strand_index(alignment);
+
+            if base == reference_base {
+                reference_counts[i] += 1;
+            } else if base != Base::N {
+                add_count(&mut alternate_counts, base, i);
+            }
+        }
+
+        let alternate_counts = self.filter_alternate_counts(reference_counts, alternate_counts);
+
+        if alternate_counts.is_empty() {
+            return None;
+        }
+
+        let reference_bases = ReferenceBases::try_from(vec![reference_base]).ok()?;
+
+        let alternate_bases: Vec<_> = alternate_counts
+            .iter()
+            .map(|(base, _)| Allele::Bases(vec![*base]))
+            .collect();
+
+        let counts: Vec<_> = alternate_counts.into_iter().map(|(_, c)| c).collect();
+
+        build_record(
+            chromosome,
+            position,
+            reference_bases,
+            alternate_bases,
+            reference_counts,
+            &counts,
+        )
+    }
+
+    fn generate_indel(
+        &self,
+        chromosome: &Chromosome,
+        name: &str,
+        position: i64,
+        reference_base: Base,
+        column: &Column,
+    ) -> Result<Option<Record>, GenerateError> {
+        let mut reference_counts = StrandCounts::default();
+        let mut alternate_counts: Vec<(IndelKey, StrandCounts)> = Vec::new();
+
+        for alignment in column.alignments() {
+            if alignment.base().is_none() {
+                continue;
+            }
+
+            let i = strand_index(alignment);
+
+            let insertion: Vec<_> = alignment
+                .insertion()
+                .iter()
+                .map(|&base| to_base(char::from(base) as u8))
+                .collect();
+
+            let deletion_len = alignment.deletion_len();
+
+            if insertion.is_empty() && deletion_len == 0 {
+                reference_counts[i] += 1;
+            } else {
+                add_count(&mut alternate_counts, (insertion, deletion_len), i);
+            }
+        }
+
+        let alternate_counts = self.filter_alternate_counts(reference_counts, alternate_counts);
+
+        if alternate_counts.is_empty() {
+            return Ok(None);
+        }
+
+        let max_deletion_len = alternate_counts
+            .iter()
+            .map(|((_, deletion_len), _)| *deletion_len)
+            .max()
+            .unwrap_or(0);
+
+        let mut reference_bases = vec![reference_base];
+
+        for i in 1..=i64::from(max_deletion_len) {
+            reference_bases.push(self.reference_base(name, position + i)?);
+        }
+
+        let alternate_bases: Vec<_> = alternate_counts
+            .iter()
+            .map(|((insertion, deletion_len), _)| {
+                let mut bases = vec![reference_base];
+                bases.extend(insertion.iter().copied());
+                bases.extend(
+                    reference_bases[1 + *deletion_len as usize..]
+                        .iter()
+                        .copied(),
+                );
+                Allele::Bases(bases)
+            })
+            .collect();
+
+        let reference_bases = match ReferenceBases::try_from(reference_bases) {
+            Ok(reference_bases) => reference_bases,
+            Err(_) => return Ok(None),
+        };
+
+        let counts: Vec<_> = alternate_counts.into_iter().map(|(_, c)| c).collect();
+
+        Ok(build_record(
+            chromosome,
+            position,
+            reference_bases,
+            alternate_bases,
+            reference_counts,
+            &counts,
+        ))
+    }
+
+    // Removes alternate alleles below the thresholds and sorts the rest by descending count.
+    fn filter_alternate_counts<K>(
+        &self,
+        reference_counts: StrandCounts,
+        alternate_counts: Vec<(K, StrandCounts)>,
+    ) -> Vec<(K, StrandCounts)> {
+        let depth = total(reference_counts)
+            + alternate_counts
+                .iter()
+                .map(|(_, counts)| total(*counts))
+                .sum::<i32>();
+
+        let mut alternate_counts: Vec<_> = alternate_counts
+            .into_iter()
+            .filter(|(_, counts)| {
+                let n = total(*counts);
+                n >= self.min_alternate_count
+                    && n as f32 >= self.min_alternate_fraction * depth as f32
+            })
+            .collect();
+
+        alternate_counts.sort_by_key(|(_, counts)| Reverse(total(*counts)));
+
+        alternate_counts
+    }
+}
+
+fn to_base(b: u8) -> Base {
+    Base::try_from(char::from(b.to_ascii_uppercase())).unwrap_or(Base::N)
+}
+
+fn strand_index(alignment: &Alignment) -> usize {
+    usize::from(alignment.flags().is_reverse_complemented())
+}
+
+fn total(counts: StrandCounts) -> i32 {
+    counts[0] + counts[1]
+}
+
+fn add_count<K>(alternate_counts: &mut Vec<(K, StrandCounts)>, key: K, i: usize)
+where
+    K: PartialEq,
+{
+    match alternate_counts.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, counts)) => counts[i] += 1,
+        None => {
+            let mut counts = StrandCounts::default();
+            counts[i] += 1;
+            alternate_counts.push((key, counts));
+        }
+    }
+}
+
+fn build_record(
+    chromosome: &Chromosome,
+    position: i64,
+    reference_bases: ReferenceBases,
+    alternate_bases: Vec<Allele>,
+    reference_counts: StrandCounts,
+    alternate_counts: &[StrandCounts],
+) -> Option<Record> {
+    let all_counts: Vec<_> = std::iter::once(reference_counts)
+        .chain(alternate_counts.iter().copied())
+        .collect();
+
+    let depth = all_counts.iter().map(|counts| total(*counts)).sum();
+
+    let alternate_forward_count = alternate_counts.iter().map(|counts| counts[0]).sum();
+    let alternate_reverse_count = alternate_counts.iter().map(|counts| counts[1]).sum();
+
+    let info = Info::from(vec![
+        Field::new(Key::TotalDepth, Value::Integer(depth)),
+        Field::new(
+            Key::TotalReadDepths,
+            Value::IntegerArray(all_counts.iter().map(|counts| total(*counts)).collect()),
+        ),
+        Field::new(
+            Key::ForwardStrandReadDepths,
+            Value::IntegerArray(all_counts.iter().map(|counts| counts[0]).collect()),
+        ),
+        Field::new(
+            Key::ReverseStrandReadDepths,
+            Value::IntegerArray(all_counts.iter().map(|counts| counts[1]).collect()),
+        ),
+        Field::new(
+            Key::StrandBias,
+            Value::IntegerArray(vec![
+                reference_counts[0],
+                reference_counts[1],
+                alternate_forward_count,
+                alternate_reverse_count,
+            ]),
+        ),
+    ]);
+
+    Record::builder()
+        .set_chromosome(chromosome.clone())
+        .set_position(position)
+        .set_reference_bases(reference_bases)
+        .set_alternate_bases(AlternateBases::from(alternate_bases))
+        .set_info(info)
+        .build()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io};
+
+    use noodles_sam::{
+        self as sam,
+        pileup::Engine,
+        record::{Flags, Position},
+    };
+
+    use super::*;
+
+    fn build_sam_record(
+        flags: Flags,
+        cigar: &str,
+        sequence: &str,
+    ) -> Result<sam::Record, Box<dyn std::error::Error>> {
+        Ok(sam::Record::builder()
+            .set_flags(flags)
+            .set_reference_sequence_name("sq0".parse()?)
+            .set_position(Position::try_from(1)?)
+            .set_cigar(cigar.parse()?)
+            .set_sequence(sequence.parse()?)
+            .build())
+    }
+
+    fn generate_all(
+        sam_records: Vec<sam::Record>,
+        reference_sequence: &[u8],
+    ) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+        let mut reference = HashMap::new();
+        reference.insert(String::from("sq0"), reference_sequence.to_vec());
+
+        let generator = CandidateGenerator::new(reference);
+        let mut records = Vec::new();
+
+        for result in Engine::new().pileup(sam_records.into_iter().map(Ok)) {
+            let column: Column = result?;
+            records.extend(generator.generate(&column)?);
+        }
+
+        Ok(records)
+    }
+
+    #[test]
+    fn test_generate_with_indels() -> Result<(), Box<dyn std::error::Error>> {
+        let sam_records = vec![
+            build_sam_record(Flags::empty(), "1M2D1M", "AT")?,
+            build_sam_record(Flags::REVERSE_COMPLEMENTED, "1M2D1M", "AT")?,
+            build_sam_record(Flags::empty(), "1M1D2M", "AGT")?,
+            build_sam_record(Flags::empty(), "1M1D2M", "AGT")?,
+            build_sam_record(Flags::empty(), "1M1I3M", "AGCGT")?,
+            build_sam_record(Flags::empty(), "4M", "ACGT")?,
+        ];
+
+        let records = generate_all(sam_records, b"ACGT")?;
+        assert_eq!(records.len(), 1);
+
+        let record = &records[0];
+        assert_eq!(record.position(), 1);
+        assert_eq!(record.reference_bases().to_string(), "ACG");
+        assert_eq!(record.alternate_bases().to_string(), "A,AG");
+        assert_eq!(
+            record.info().to_string(),
+            "DP=5;AD=1,2,2;ADF=1,1,2;ADR=0,1,0;SB=1,0,3,1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_with_min_alternate_fraction() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reference = HashMap::new();
+        reference.insert(String::from("sq0"), b"A".to_vec());
+
+        let sam_records = vec![
+            build_sam_record(Flags::empty(), "1M", "C")?,
+            build_sam_record(Flags::empty(), "1M", "C")?,
+            build_sam_record(Flags::empty(), "1M", "A")?,
+            build_sam_record(Flags::empty(), "1M", "A")?,
+            build_sam_record(Flags::empty(), "1M", "A")?,
+        ];
+
+        let generator = CandidateGenerator::new(reference).set_min_alternate_fraction(0.5);
+
+        for result in Engine::new().pileup(sam_records.into_iter().map(Ok)) {
+            let column = result?;
+            assert!(generator.generate(&column)?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_with_missing_reference_base() -> io::Result<()> {
+        let sam_records = vec![sam::Record::builder()
+            .set_reference_sequence_name("sq1".parse().unwrap())
+            .set_position(Position::try_from(1).unwrap())
+            .set_cigar("1M".parse().unwrap())
+            .set_sequence("A".parse().unwrap())
+            .build()];
+
+        let generator = CandidateGenerator::new(HashMap::<String, Vec<u8>>::new());
+
+        for result in Engine::new().pileup(sam_records.into_iter().map(Ok)) {
+            let column = result?;
+
+            assert_eq!(
+                generator.generate(&column),
+                Err(GenerateError::MissingReferenceBase(String::from("sq1"), 1))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+//! VCF concatenation.
+
+use std::{error, fmt, io, vec};
+
+use super::{header::Contig, Header, Record, Writer};
+
+/// An error returned when VCF headers are incompatible for concatenation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConcatHeadersError {
+    /// The sample names of a header differ from the sample names of the first header.
+    SampleNamesMismatch,
+    /// An information record (`INFO`) has a different number or type in another header.
+    InfoMismatch(String),
+    /// A genotype format record (`FORMAT`) has a different number or type in another header.
+    FormatMismatch(String),
+    /// The contigs of a header are in a different order than in a previous header.
+    ContigOrderMismatch(String),
+}
+
+impl error::Error for ConcatHeadersError {}
+
+impl fmt::Display for ConcatHeadersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SampleNamesMismatch => f.write_str("sample names mismatch"),
+            Self::InfoMismatch(id) => write!(f, "INFO mismatch: {}", id),
+            Self::FormatMismatch(id) => write!(f, "FORMAT mismatch: {}", id),
+            Self::ContigOrderMismatch(id) => write!(f, "contig order mismatch: {}", id),
+        }
+    }
+}
+
+/// Concatenates a list of VCF headers.
+///
+/// All headers must have the same sample names, in the same order, and compatible information
+/// (`INFO`) and genotype format (`FORMAT`) records. Contigs that are in more than one header must
+/// be in the same relative order.
+///
+/// The concatenated header is the first header with the information, filter (`FILTER`),
+/// genotype format, and contig (`contig`) records of the other headers that it does not already
+/// define appended.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, concat, header::Contig};
+///
+/// let headers = [
+///     vcf::Header::builder()
+///         .add_contig(Contig::new(String::from("sq0")))
+///         .add_sample_name("sample0")
+///         .build(),
+///     vcf::Header::builder()
+///         .add_contig(Contig::new(String::from("sq1")))
+///         .add_sample_name("sample0")
+///         .build(),
+/// ];
+///
+/// let header = concat::concat_headers(&headers)?;
+///
+/// assert_eq!(header.contigs(), [
+///     Contig::new(String::from("sq0")),
+///     Contig::new(String::from("sq1")),
+/// ]);
+///
+/// assert_eq!(header.sample_names(), [String::from("sample0")]);
+/// # Ok::<(), concat::ConcatHeadersError>(())
+/// ```
+pub fn concat_headers(headers: &[Header]) -> Result<Header, ConcatHeadersError> {
+    let mut iter = headers.iter();
+    let mut concatenated_header = iter.next().cloned().unwrap_or_default();
+
+    for header in iter {
+        if header.sample_names() != concatenated_header.sample_names() {
+            return Err(ConcatHeadersError::SampleNamesMismatch);
+        }
+
+        for info in header.infos() {
+            match concatenated_header
+                .infos()
+                .iter()
+                .find(|i| i.id() == info.id())
+            {
+                Some(i) if i.number() != info.number() || i.ty() != info.ty() => {
+                    return Err(ConcatHeadersError::InfoMismatch(info.id().to_string()));
+                }
+                Some(_) => {}
+                None => concatenated_header.infos_mut().push(info.clone()),
+            }
+        }
+
+        for filter in header.filters() {
+            if !concatenated_header
+                .filters()
+                .iter()
+                .any(|f| f.id() == filter.id())
+            {
+                concatenated_header.filters_mut().push(filter.clone());
+            }
+        }
+
+        for format in header.formats() {
+            match concatenated_header
+                .formats()
+                .iter()
+                .find(|f| f.id() == format.id())
+            {
+                Some(f) if f.number() != format.number() || f.ty() != format.ty() => {
+                    return Err(ConcatHeadersError::FormatMismatch(format.id().to_string()));
+                }
+                Some(_) => {}
+                None => concatenated_header.formats_mut().push(format.clone()),
+            }
+        }
+
+        let mut last_index = None;
+
+        for contig in header.contigs() {
+            let contigs = concatenated_header.contigs_mut();
+
+            let i = match contigs.iter().position(|c| c.id() == contig.id()) {
+                Some(i) => i,
+                None => {
+                    contigs.push(contig.clone());
+                    contigs.len() - 1
+                }
+            };
+
+            if last_index.map(|j| i < j).unwrap_or(false) {
+                return Err(ConcatHeadersError::ContigOrderMismatch(contig.id().into()));
+            }
+
+            last_index = Some(i);
+        }
+    }
+
+    Ok(concatenated_header)
+}
+
+/// A VCF concatenator.
+///
+/// A concatenator reads the records of each input in turn, like `bcftools concat`. The inputs
+/// must have the same samples, and together, they must be sorted by reference sequence and
+/// position, e.g., inputs that are sharded by reference sequence, in order.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, concat::Concat, header::Contig};
+///
+/// let headers = [
+///     vcf::Header::builder().add_contig(Contig::new(String::from("sq0"))).build(),
+///     vcf::Header::builder().add_contig(Contig::new(String::from("sq1"))).build(),
+/// ];
+///
+/// let record0 = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .build()?;
+///
+/// let record1 = vcf::Record::builder()
+///     .set_chromosome("sq1".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .build()?;
+///
+/// let inputs = vec![vec![Ok(record0)].into_iter(), vec![Ok(record1)].into_iter()];
+///
+/// let concat = Concat::new(&headers, inputs)?;
+/// let records = concat.collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].chromosome().to_string(), "sq1");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Concat<I> {
+    header: Header,
+    inputs: vec::IntoIter<I>,
+    current_input: Option<I>,
+    last_key: Option<(usize, i64)>,
+}
+
+impl<I> Concat<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    /// Creates a concatenator from a list of headers and their record inputs.
+    pub fn new(headers: &[Header], inputs: Vec<I>) -> Result<Self, ConcatHeadersError> {
+        let header = concat_headers(headers)?;
+        let mut inputs = inputs.into_iter();
+        let current_input = inputs.next();
+
+        Ok(Self {
+            header,
+            inputs,
+            current_input,
+            last_key: None,
+        })
+    }
+
+    /// Returns the concatenated header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    fn check_order(&mut self, record: &Record) -> io::Result<()> {
+        let key = site_key(self.header.contigs(), record)?;
+
+        if self.last_key.map(|k| key < k).unwrap_or(false) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "input is not coordinate-sorted",
+            ));
+        }
+
+        self.last_key = Some(key);
+
+        Ok(())
+    }
+}
+
+impl<I> Iterator for Concat<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let input = self.current_input.as_mut()?;
+
+            match input.next() {
+                Some(Ok(record)) => {
+                    return Some(self.check_order(&record).map(|_| record));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => self.current_input = self.inputs.next(),
+            }
+        }
+    }
+}
+
+/// Concatenates VCF inputs and writes the result.
+///
+/// This writes the concatenated header and then all records. See [`Concat`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, concat};
+///
+/// let headers = [vcf::Header::default(), vcf::Header::default()];
+/// let inputs: Vec<std::vec::IntoIter<io::Result<vcf::Record>>> =
+///     vec![Vec::new().into_iter(), Vec::new().into_iter()];
+///
+/// let mut writer = vcf::Writer::new(Vec::new());
+/// concat::concat(&mut writer, &headers, inputs)?;
+///
+/// assert!(writer.get_ref().starts_with(b"##fileformat=VCFv4.3\n"));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn concat<W, I>(writer: &mut Writer<W>, headers: &[Header], inputs: Vec<I>) -> io::Result<()>
+where
+    W: io::Write,
+    I: Iterator<Item = io::Result<Record>>,
+{
+    let concat =
+        Concat::new(headers, inputs).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    writer.write_header(concat.header())?;
+
+    for result in concat {
+        let record = result?;
+        writer.write_record(&record)?;
+    }
+
+    Ok(())
+}
+
+fn site_key(contigs: &[Contig], record: &Record) -> io::Result<(usize, i64)> {
+    let name = record.chromosome().to_string();
+
+    let i = contigs
+        .iter()
+        .position(|contig| contig.id() == name)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("missing contig: {}", name),
+            )
+        })?;
+
+    Ok((i, record.position()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_header(contig_ids: &[&str], sample_name: &str) -> Header {
+        contig_ids
+            .iter()
+            .fold(Header::builder(), |builder, id| {
+                builder.add_contig(Contig::new(String::from(*id)))
+            })
+            .add_sample_name(sample_name)
+            .build()
+    }
+
+    fn build_record(chromosome: &str, position: i64) -> io::Result<Record> {
+        Record::builder()
+            .set_chromosome(chromosome.parse().unwrap())
+            .set_position(position)
+            .set_reference_bases("A".parse().unwrap())
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    #[test]
+    fn test_concat_headers() {
+        let headers = [
+            build_header(&["sq0", "sq1"], "sample0"),
+            build_header(&["sq1", "sq2"], "sample0"),
+        ];
+        let header = concat_headers(&headers).unwrap();
+        let contig_ids: Vec<_> = header.contigs().iter().map(|c| c.id()).collect();
+        assert_eq!(contig_ids, ["sq0", "sq1", "sq2"]);
+
+        let headers = [
+            build_header(&["sq0"], "sample0"),
+            build_header(&["sq1"], "sample1"),
+        ];
+        assert_eq!(
+            concat_headers(&headers),
+            Err(ConcatHeadersError::SampleNamesMismatch)
+        );
+
+        let headers = [
+            build_header(&["sq0", "sq1"], "sample0"),
+            build_header(&["sq1", "sq0"], "sample0"),
+        ];
+        assert_eq!(
+            concat_headers(&headers),
+            Err(ConcatHeadersError::ContigOrderMismatch(String::from("sq0")))
+        );
+    }
+
+    #[test]
+    fn test_next() -> io::Result<()> {
+        let headers = [
+            build_header(&["sq0"], "sample0"),
+            build_header(&["sq1"], "sample0"),
+        ];
+
+        let inputs = vec![
+            vec![build_record("sq0", 1), build_record("sq0", 5)].into_iter(),
+            Vec::new().into_iter(),
+            vec![build_record("sq1", 2)].into_iter(),
+        ];
+
+        let records: Vec<_> = Concat::new(&headers, inputs)
+            .unwrap()
+            .collect::<io::Result<_>>()?;
+
+        let sites: Vec<_> = records
+            .iter()
+            .map(|r| (r.chromosome().to_string(), r.position()))
+            .collect();
+
+        assert_eq!(
+            sites,
+            [
+                (String::from("sq0"), 1),
+                (String::from("sq0"), 5),
+                (String::from("sq1"), 2),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unsorted_inputs() {
+        let headers = [
+            build_header(&["sq0", "sq1"], "sample0"),
+            build_header(&["sq0", "sq1"], "sample0"),
+        ];
+
+        let inputs = vec![
+            vec![build_record("sq1", 1)].into_iter(),
+            vec![build_record("sq0", 1)].into_iter(),
+        ];
+
+        let concat = Concat::new(&headers, inputs).unwrap();
+        assert!(concat.collect::<io::Result<Vec<_>>>().is_err());
+    }
+}
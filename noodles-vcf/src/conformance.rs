@@ -0,0 +1,347 @@
+//! VCF conformance validation.
+//!
+//! [`validate`] reads a VCF file from the start and checks its header and records against the VCF
+//! specification. [`validate_with_index`] additionally checks that an associated tabix index is
+//! consistent with the file.
+//!
+//! Issues are reported with the following codes:
+//!
+//!   * `invalid_header`: the header cannot be parsed.
+//!   * `invalid_record`: a record cannot be parsed.
+//!   * `undefined_contig` (warning): the record chromosome is not defined in a `##contig` header
+//!     record.
+//!   * `unsorted`: records of a chromosome are not contiguous, or positions within a chromosome
+//!     decrease.
+//!   * `contig_order` (warning): chromosomes are not in the order of the `##contig` header
+//!     records.
+//!   * `index_*`: the index is inconsistent with the file.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, BufRead},
+};
+
+use noodles_core::conformance::Report;
+use noodles_tabix as tabix;
+
+use super::{record::Chromosome, Header, Reader, Record};
+
+/// Validates a VCF file.
+///
+/// The reader is expected to be at the start of the stream.
+///
+/// Malformed input is recorded in the report. Any other I/O error is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, conformance};
+///
+/// let data = b"##fileformat=VCFv4.3
+/// #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+/// sq0\t8\t.\tA\t.\t.\tPASS\t.
+/// sq0\t5\t.\tA\t.\t.\tPASS\t.
+/// ";
+///
+/// let mut reader = vcf::Reader::new(&data[..]);
+/// let report = conformance::validate(&mut reader)?;
+///
+/// assert!(!report.is_valid());
+/// assert_eq!(report.issues()[0].code(), "unsorted");
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate<R>(reader: &mut Reader<R>) -> io::Result<Report>
+where
+    R: BufRead,
+{
+    let mut report = Report::default();
+    validate_inner(reader, &mut report)?;
+    Ok(report)
+}
+
+/// Validates a VCF file and its associated tabix index.
+///
+/// In addition to the checks in [`validate`], this checks that the reference sequence names in
+/// the index match the chromosomes in the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{fs::File, io::{self, BufReader}};
+/// use noodles_bgzf as bgzf;
+/// use noodles_tabix as tabix;
+/// use noodles_vcf::{self as vcf, conformance};
+///
+/// let mut reader = File::open("sample.vcf.gz")
+///     .map(bgzf::Reader::new)
+///     .map(BufReader::new)
+///     .map(vcf::Reader::new)?;
+///
+/// let index = tabix::read("sample.vcf.gz.tbi")?;
+/// let report = conformance::validate_with_index(&mut reader, &index)?;
+///
+/// print!("{}", report);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn validate_with_index<R>(reader: &mut Reader<R>, index: &tabix::Index) -> io::Result<Report>
+where
+    R: BufRead,
+{
+    let mut report = Report::default();
+
+    if let Some(names) = validate_inner(reader, &mut report)? {
+        validate_index(index, &names, &mut report);
+    }
+
+    Ok(report)
+}
+
+// Returns the chromosome names in the order they appear, or `None` if the header cannot be read.
+fn validate_inner<R>(reader: &mut Reader<R>, report: &mut Report) -> io::Result<Option<Vec<String>>>
+where
+    R: BufRead,
+{
+    let raw_header = match reader.read_header() {
+        Ok(s) => s,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            report.add_error("invalid_header", None, e.to_string());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let header = match raw_header.parse::<Header>() {
+        Ok(header) => Some(header),
+        Err(e) => {
+            report.add_error("invalid_header", None, e.to_string());
+            None
+        }
+    };
+
+    let contig_indices: HashMap<String, usize> = header
+        .iter()
+        .flat_map(|h| h.contigs())
+        .enumerate()
+        .map(|(i, contig)| (contig.id().into(), i))
+        .collect();
+
+    let mut names = Vec::new();
+    let mut seen_names = HashSet::new();
+    let mut undefined_names = HashSet::new();
+    let mut last_position = 0;
+    let mut last_contig_index = None;
+    let mut is_contig_order_reported = false;
+
+    let mut buf = String::new();
+    let mut i = 0;
+
+    loop {
+        buf.clear();
+
+        match reader.read_record(&mut buf) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                report.add_error("invalid_record", Some(i), e.to_string());
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+
+        let record: Record = match buf.parse() {
+            Ok(record) => record,
+            Err(e) => {
+                report.add_error("invalid_record", Some(i), e.to_string());
+                i += 1;
+                continue;
+            }
+        };
+
+        let name = match record.chromosome() {
+            Chromosome::Name(name) => name.clone(),
+            Chromosome::Symbol(symbol) => format!("<{}>", symbol),
+        };
+        let position = record.position();
+
+        if names.last() != Some(&name) {
+            if seen_names.contains(&name) {
+                report.add_error(
+                    "unsorted",
+                    Some(i),
+                    format!("records for chromosome {} are not contiguous", name),
+                );
+            } else {
+                seen_names.insert(name.clone());
+                names.push(name.clone());
+
+                match contig_indices.get(&name) {
+                    Some(&contig_index) => {
+                        let is_out_of_order = last_contig_index
+                            .map(|last| contig_index < last)
+                            .unwrap_or(false);
+
+                        if is_out_of_order && !is_contig_order_reported {
+                            report.add_warning(
+                                "contig_order",
+                                Some(i),
+                                format!(
+                                    "chromosome {} is out of the order of the contig header records",
+                                    name
+                                ),
+                            );
+
+                            is_contig_order_reported = true;
+                        }
+
+                        last_contig_index = Some(contig_index);
+                    }
+                    None => {
+                        if !contig_indices.is_empty() && undefined_names.insert(name.clone()) {
+                            report.add_warning(
+                                "undefined_contig",
+                                Some(i),
+                                format!("chromosome {} is not defined in the header", name),
+                            );
+                        }
+                    }
+                }
+            }
+        } else if position < last_position {
+            report.add_error(
+                "unsorted",
+                Some(i),
+                format!(
+                    "position {} is less than the previous position {}",
+                    position, last_position
+                ),
+            );
+        }
+
+        last_position = position;
+        i += 1;
+    }
+
+    *report.record_count_mut() = i;
+
+    Ok(Some(names))
+}
+
+fn validate_index(index: &tabix::Index, names: &[String], report: &mut Report) {
+    let index_names = index.reference_sequence_names();
+
+    if index_names != names {
+        report.add_error(
+            "index_reference_sequence_names_mismatch",
+            None,
+            format!(
+                "index reference sequence names ({}) do not match the chromosomes in the file ({})",
+                index_names.join(", "),
+                names.join(", ")
+            ),
+        );
+    }
+
+    if index.reference_sequences().len() != index_names.len() {
+        report.add_error(
+            "index_reference_sequence_count_mismatch",
+            None,
+            format!(
+                "index has {} reference sequence names, but {} reference sequences",
+                index_names.len(),
+                index.reference_sequences().len()
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(report: &Report) -> Vec<&'static str> {
+        report.issues().iter().map(|issue| issue.code()).collect()
+    }
+
+    #[test]
+    fn test_validate() -> io::Result<()> {
+        let data = b"##fileformat=VCFv4.3
+##contig=<ID=sq0>
+##contig=<ID=sq1>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t1\t.\tA\t.\t.\tPASS\t.
+sq0\t8\t.\tA\t.\t.\tPASS\t.
+sq1\t5\t.\tA\t.\t.\tPASS\t.
+";
+
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert!(report.issues().is_empty(), "{}", report);
+        assert_eq!(report.record_count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_unsorted_records() -> io::Result<()> {
+        let data = b"##fileformat=VCFv4.3
+##contig=<ID=sq0>
+##contig=<ID=sq1>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq1\t5\t.\tA\t.\t.\tPASS\t.
+sq0\t8\t.\tA\t.\t.\tPASS\t.
+sq0\t1\t.\tA\t.\t.\tPASS\t.
+sq1\t6\t.\tA\t.\t.\tPASS\t.
+sq2\t1\t.\tA\t.\t.\tPASS\t.
+sq2\tndls\t.\tA\t.\t.\tPASS\t.
+";
+
+        let report = validate(&mut Reader::new(&data[..]))?;
+
+        assert_eq!(
+            codes(&report),
+            [
+                "contig_order",
+                "unsorted",
+                "unsorted",
+                "undefined_contig",
+                "invalid_record"
+            ]
+        );
+
+        let records: Vec<_> = report.issues().iter().map(|issue| issue.record()).collect();
+        assert_eq!(records, [Some(1), Some(2), Some(3), Some(4), Some(5)]);
+
+        assert_eq!(report.record_count(), 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_header() -> io::Result<()> {
+        let data = b"#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+        let report = validate(&mut Reader::new(&data[..]))?;
+        assert_eq!(codes(&report), ["invalid_header"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_index() {
+        let names = vec![String::from("sq0"), String::from("sq1")];
+
+        let index = tabix::Index::builder()
+            .set_reference_sequence_names(names.clone())
+            .set_reference_sequences(vec![
+                tabix::index::ReferenceSequence::new(Vec::new(), Vec::new()),
+                tabix::index::ReferenceSequence::new(Vec::new(), Vec::new()),
+            ])
+            .build();
+
+        let mut report = Report::default();
+        validate_index(&index, &names, &mut report);
+        assert!(report.issues().is_empty());
+
+        let mut report = Report::default();
+        validate_index(&index, &names[..1], &mut report);
+        assert_eq!(codes(&report), ["index_reference_sequence_names_mismatch"]);
+    }
+}
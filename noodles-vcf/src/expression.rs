@@ -0,0 +1,809 @@
+//! VCF record expressions.
+//!
+//! An expression is evaluated on a record, e.g., `INFO/DP > 10 && QUAL > 30`. The following
+//! values are supported:
+//!
+//!   * numbers, e.g., `10` or `1.5e-3`;
+//!   * `QUAL`: the quality score;
+//!   * `INFO/<key>`: an info field value; and
+//!   * `FMT/<key>`: a genotype field value.
+//!
+//! Values can be combined with arithmetic (`+`, `-`, `*`, `/`), compared (`<`, `<=`, `>`, `>=`,
+//! `==`, `!=`), and the results combined with boolean logic (`&&`, `||`, `!`). Parentheses group
+//! subexpressions.
+//!
+//! Values are lists of numbers. An array field is a list of its elements, and a missing field or
+//! element is an empty list. Arithmetic is applied elementwise, where a single number is applied
+//! to each element of the other operand. A comparison is true if it is true for any element, and
+//! a comparison with an empty list is false. As a boolean, `QUAL`, `INFO/<key>`, and
+//! `FMT/<key>` test whether the value is present, e.g., `INFO/DB` is true if the `DB` flag is
+//! set.
+//!
+//! Outside of a quantifier, `FMT/<key>` is the list of values of all samples, i.e., a comparison
+//! is true if it is true for any sample. The quantifiers `any(...)` and `all(...)` evaluate an
+//! expression for each sample separately and are true if the expression is true for any or all
+//! samples, respectively. Both are false if the record has no samples.
+//!
+//! # Examples
+//!
+//! ```
+//! use noodles_vcf::{self as vcf, expression::Expression, record::{Format, Genotype}};
+//!
+//! let format: Format = "GT:GQ:DP".parse()?;
+//!
+//! let record = vcf::Record::builder()
+//!     .set_chromosome("sq0".parse()?)
+//!     .set_position(1)
+//!     .set_reference_bases("A".parse()?)
+//!     .set_alternate_bases("C".parse()?)
+//!     .set_quality_score("40".parse()?)
+//!     .set_info("DP=21".parse()?)
+//!     .set_format(format.clone())
+//!     .add_genotype(Genotype::from_str_format("0/1:30:13", &format)?)
+//!     .add_genotype(Genotype::from_str_format("0/0:12:8", &format)?)
+//!     .build()?;
+//!
+//! let expression: Expression = "INFO/DP > 10 && QUAL > 30".parse()?;
+//! assert!(expression.evaluate(&record));
+//!
+//! let expression: Expression = "any(FMT/GQ >= 30 && FMT/DP > 10)".parse()?;
+//! assert!(expression.evaluate(&record));
+//!
+//! let expression: Expression = "all(FMT/GQ / QUAL > 0.5)".parse()?;
+//! assert!(!expression.evaluate(&record));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{error, fmt, io, str::FromStr};
+
+use super::{
+    record::{genotype, info},
+    Record,
+};
+
+const QUALITY_SCORE: &str = "QUAL";
+const INFO_PREFIX: &str = "INFO/";
+const FORMAT_PREFIX: &str = "FMT/";
+const ANY: &str = "any";
+const ALL: &str = "all";
+
+/// An arithmetic operator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArithmeticOperator {
+    /// Addition (`+`).
+    Add,
+    /// Subtraction (`-`).
+    Subtract,
+    /// Multiplication (`*`).
+    Multiply,
+    /// Division (`/`).
+    Divide,
+}
+
+impl ArithmeticOperator {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Self::Add => a + b,
+            Self::Subtract => a - b,
+            Self::Multiply => a * b,
+            Self::Divide => a / b,
+        }
+    }
+}
+
+/// A comparison operator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComparisonOperator {
+    /// Less than (`<`).
+    Lt,
+    /// Less than or equal to (`<=`).
+    Le,
+    /// Greater than (`>`).
+    Gt,
+    /// Greater than or equal to (`>=`).
+    Ge,
+    /// Equal to (`==` or `=`).
+    Eq,
+    /// Not equal to (`!=`).
+    Ne,
+}
+
+impl ComparisonOperator {
+    fn apply(self, a: f64, b: f64) -> bool {
+        match self {
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+            Self::Eq => (a - b).abs() < f64::EPSILON,
+            Self::Ne => (a - b).abs() >= f64::EPSILON,
+        }
+    }
+}
+
+/// A VCF record expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// A number.
+    Number(f64),
+    /// The quality score (`QUAL`).
+    QualityScore,
+    /// An info field value (`INFO/<key>`).
+    Info(String),
+    /// A genotype field value (`FMT/<key>`).
+    Genotype(String),
+    /// The arithmetic negation of an expression (`-`).
+    Negate(Box<Expression>),
+    /// An arithmetic operation.
+    Arithmetic(ArithmeticOperator, Box<Expression>, Box<Expression>),
+    /// A comparison.
+    Comparison(ComparisonOperator, Box<Expression>, Box<Expression>),
+    /// The logical negation of an expression (`!`).
+    Not(Box<Expression>),
+    /// Whether all expressions are true (`&&`).
+    And(Vec<Expression>),
+    /// Whether any expression is true (`||`).
+    Or(Vec<Expression>),
+    /// Whether the expression is true for any sample (`any(...)`).
+    Any(Box<Expression>),
+    /// Whether the expression is true for all samples (`all(...)`).
+    All(Box<Expression>),
+}
+
+impl Expression {
+    /// Evaluates the expression as a boolean for the given record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, expression::Expression};
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_quality_score("8".parse()?)
+    ///     .set_info("DP=13".parse()?)
+    ///     .build()?;
+    ///
+    /// let expression: Expression = "QUAL < 10 && !(INFO/DP >= 20)".parse()?;
+    /// assert!(expression.evaluate(&record));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn evaluate(&self, record: &Record) -> bool {
+        self.test(record, None)
+    }
+
+    /// Evaluates the expression as a list of numbers for the given record.
+    ///
+    /// Boolean results are `1` (true) or `0` (false).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, expression::Expression};
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_quality_score("8".parse()?)
+    ///     .build()?;
+    ///
+    /// let expression: Expression = "QUAL * 2 + 1".parse()?;
+    /// assert_eq!(expression.values(&record), [17.0]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn values(&self, record: &Record) -> Vec<f64> {
+        self.eval(record, None)
+    }
+
+    fn test(&self, record: &Record, sample: Option<usize>) -> bool {
+        match self {
+            Self::Number(n) => *n != 0.0,
+            Self::QualityScore => record.quality_score().is_some(),
+            Self::Info(key) => find_info_field(record, key).is_some(),
+            Self::Genotype(key) => !genotype_values(record, key, sample).is_empty(),
+            Self::Negate(_) | Self::Arithmetic(..) => {
+                self.eval(record, sample).iter().any(|&n| n != 0.0)
+            }
+            Self::Comparison(operator, a, b) => {
+                broadcast(&a.eval(record, sample), &b.eval(record, sample), |m, n| {
+                    operator.apply(m, n)
+                })
+                .into_iter()
+                .any(|is_true| is_true)
+            }
+            Self::Not(expression) => !expression.test(record, sample),
+            Self::And(expressions) => expressions.iter().all(|e| e.test(record, sample)),
+            Self::Or(expressions) => expressions.iter().any(|e| e.test(record, sample)),
+            Self::Any(expression) => {
+                let sample_count = record.genotypes().len();
+                (0..sample_count).any(|i| expression.test(record, Some(i)))
+            }
+            Self::All(expression) => {
+                let sample_count = record.genotypes().len();
+                sample_count > 0 && (0..sample_count).all(|i| expression.test(record, Some(i)))
+            }
+        }
+    }
+
+    fn eval(&self, record: &Record, sample: Option<usize>) -> Vec<f64> {
+        match self {
+            Self::Number(n) => vec![*n],
+            Self::QualityScore => record
+                .quality_score()
+                .map(|score| vec![f64::from(score)])
+                .unwrap_or_default(),
+            Self::Info(key) => find_info_field(record, key)
+                .map(|field| info_values(field.value()))
+                .unwrap_or_default(),
+            Self::Genotype(key) => genotype_values(record, key, sample),
+            Self::Negate(expression) => expression
+                .eval(record, sample)
+                .into_iter()
+                .map(|n| -n)
+                .collect(),
+            Self::Arithmetic(operator, a, b) => {
+                broadcast(&a.eval(record, sample), &b.eval(record, sample), |m, n| {
+                    operator.apply(m, n)
+                })
+            }
+            _ => {
+                if self.test(record, sample) {
+                    vec![1.0]
+                } else {
+                    vec![0.0]
+                }
+            }
+        }
+    }
+}
+
+fn find_info_field<'r>(record: &'r Record, key: &str) -> Option<&'r info::Field> {
+    record
+        .info()
+        .iter()
+        .find(|field| field.key().as_ref() == key)
+}
+
+fn info_values(value: &info::field::Value) -> Vec<f64> {
+    use info::field::Value;
+
+    match value {
+        Value::Integer(n) => vec![f64::from(*n)],
+        Value::Float(n) => vec![f64::from(*n)],
+        Value::Flag => vec![1.0],
+        Value::IntegerArray(values) => values.iter().map(|&n| f64::from(n)).collect(),
+        Value::FloatArray(values) => values.iter().map(|&n| f64::from(n)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Returns the values of the given sample or, if no sample is given, of all samples.
+fn genotype_values(record: &Record, key: &str, sample: Option<usize>) -> Vec<f64> {
+    use genotype::field::Value;
+
+    let genotypes = record.genotypes();
+
+    let genotypes = match sample {
+        Some(i) => genotypes.get(i..=i).unwrap_or(&[]),
+        None => genotypes,
+    };
+
+    genotypes
+        .iter()
+        .filter_map(|genotype| {
+            genotype
+                .iter()
+                .find(|field| field.key().as_ref() == key)
+                .and_then(|field| field.value())
+        })
+        .flat_map(|value| match value {
+            Value::Integer(n) => vec![f64::from(*n)],
+            Value::Float(n) => vec![f64::from(*n)],
+            Value::IntegerArray(values) => values.iter().flatten().map(|&n| f64::from(n)).collect(),
+            Value::FloatArray(values) => values.iter().flatten().map(|&n| f64::from(n)).collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+// Applies `f` elementwise. A single value is applied to each value of the other list.
+fn broadcast<F, T>(a: &[f64], b: &[f64], f: F) -> Vec<T>
+where
+    F: Fn(f64, f64) -> T,
+{
+    match (a, b) {
+        ([], _) | (_, []) => Vec::new(),
+        ([m], _) => b.iter().map(|&n| f(*m, n)).collect(),
+        (_, [n]) => a.iter().map(|&m| f(m, *n)).collect(),
+        _ => a.iter().zip(b).map(|(&m, &n)| f(m, n)).collect(),
+    }
+}
+
+/// An error returned when a raw expression fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// A value is invalid. Values are numbers, `QUAL`, `INFO/<key>`, or `FMT/<key>`.
+    InvalidValue(String),
+    /// A number is invalid.
+    InvalidNumber(String),
+    /// A closing parenthesis is missing.
+    MissingClosingParenthesis,
+    /// The input has unexpected trailing characters.
+    UnexpectedInput(String),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidValue(s) => write!(f, "invalid value: {}", s),
+            Self::InvalidNumber(s) => write!(f, "invalid number: {}", s),
+            Self::MissingClosingParenthesis => f.write_str("missing closing parenthesis"),
+            Self::UnexpectedInput(s) => write!(f, "unexpected input: {}", s),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let mut src = s;
+        let expression = parse_or(&mut src)?;
+
+        let rest = src.trim_start();
+
+        if rest.is_empty() {
+            Ok(expression)
+        } else {
+            Err(ParseError::UnexpectedInput(rest.into()))
+        }
+    }
+}
+
+// or = and ("||" and)*
+fn parse_or(src: &mut &str) -> Result<Expression, ParseError> {
+    let mut expressions = vec![parse_and(src)?];
+
+    while consume(src, "||") {
+        expressions.push(parse_and(src)?);
+    }
+
+    if expressions.len() == 1 {
+        Ok(expressions.remove(0))
+    } else {
+        Ok(Expression::Or(expressions))
+    }
+}
+
+// and = not ("&&" not)*
+fn parse_and(src: &mut &str) -> Result<Expression, ParseError> {
+    let mut expressions = vec![parse_not(src)?];
+
+    while consume(src, "&&") {
+        expressions.push(parse_not(src)?);
+    }
+
+    if expressions.len() == 1 {
+        Ok(expressions.remove(0))
+    } else {
+        Ok(Expression::And(expressions))
+    }
+}
+
+// not = "!" not | comparison
+fn parse_not(src: &mut &str) -> Result<Expression, ParseError> {
+    if consume(src, "!") {
+        parse_not(src).map(|e| Expression::Not(Box::new(e)))
+    } else {
+        parse_comparison(src)
+    }
+}
+
+// comparison = sum [operator sum]
+fn parse_comparison(src: &mut &str) -> Result<Expression, ParseError> {
+    // Two-character operators are checked first.
+    const OPERATORS: [(&str, ComparisonOperator); 7] = [
+        ("<=", ComparisonOperator::Le),
+        (">=", ComparisonOperator::Ge),
+        ("==", ComparisonOperator::Eq),
+        ("!=", ComparisonOperator::Ne),
+        ("<", ComparisonOperator::Lt),
+        (">", ComparisonOperator::Gt),
+        ("=", ComparisonOperator::Eq),
+    ];
+
+    let a = parse_sum(src)?;
+
+    match OPERATORS.iter().find(|(token, _)| consume(src, token)) {
+        Some((_, operator)) => {
+            let b = parse_sum(src)?;
+            Ok(Expression::Comparison(*operator, Box::new(a), Box::new(b)))
+        }
+        None => Ok(a),
+    }
+}
+
+// sum = product (("+" | "-") product)*
+fn parse_sum(src: &mut &str) -> Result<Expression, ParseError> {
+    const OPERATORS: [(&str, ArithmeticOperator); 2] = [
+        ("+", ArithmeticOperator::Add),
+        ("-", ArithmeticOperator::Subtract),
+    ];
+
+    let mut expression = parse_product(src)?;
+
+    while let Some((_, operator)) = OPERATORS.iter().find(|(token, _)| consume(src, token)) {
+        let b = parse_product(src)?;
+        expression = Expression::Arithmetic(*operator, Box::new(expression), Box::new(b));
+    }
+
+    Ok(expression)
+}
+
+// product = unary (("*" | "/") unary)*
+fn parse_product(src: &mut &str) -> Result<Expression, ParseError> {
+    const OPERATORS: [(&str, ArithmeticOperator); 2] = [
+        ("*", ArithmeticOperator::Multiply),
+        ("/", ArithmeticOperator::Divide),
+    ];
+
+    let mut expression = parse_unary(src)?;
+
+    while let Some((_, operator)) = OPERATORS.iter().find(|(token, _)| consume(src, token)) {
+        let b = parse_unary(src)?;
+        expression = Expression::Arithmetic(*operator, Box::new(expression), Box::new(b));
+    }
+
+    Ok(expression)
+}
+
+// unary = "-" unary | primary
+fn parse_unary(src: &mut &str) -> Result<Expression, ParseError> {
+    if consume(src, "-") {
+        parse_unary(src).map(|e| Expression::Negate(Box::new(e)))
+    } else {
+        parse_primary(src)
+    }
+}
+
+// primary = number | "(" or ")" | ("any" | "all") "(" or ")" | "QUAL" | "INFO/" key | "FMT/" key
+fn parse_primary(src: &mut &str) -> Result<Expression, ParseError> {
+    if consume(src, "(") {
+        return parse_group(src);
+    }
+
+    *src = src.trim_start();
+
+    if src.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return parse_number(src).map(Expression::Number);
+    }
+
+    let name = take_while(src, |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '.')
+    });
+
+    if name == ANY && consume(src, "(") {
+        parse_group(src).map(|e| Expression::Any(Box::new(e)))
+    } else if name == ALL && consume(src, "(") {
+        parse_group(src).map(|e| Expression::All(Box::new(e)))
+    } else if name == QUALITY_SCORE {
+        Ok(Expression::QualityScore)
+    } else if let Some(key) = name.strip_prefix(INFO_PREFIX).filter(|k| !k.is_empty()) {
+        Ok(Expression::Info(key.into()))
+    } else if let Some(key) = name.strip_prefix(FORMAT_PREFIX).filter(|k| !k.is_empty()) {
+        Ok(Expression::Genotype(key.into()))
+    } else if name.is_empty() {
+        Err(ParseError::InvalidValue(src.trim().into()))
+    } else {
+        Err(ParseError::InvalidValue(name.into()))
+    }
+}
+
+// group = or ")"
+fn parse_group(src: &mut &str) -> Result<Expression, ParseError> {
+    let expression = parse_or(src)?;
+
+    if consume(src, ")") {
+        Ok(expression)
+    } else {
+        Err(ParseError::MissingClosingParenthesis)
+    }
+}
+
+// number = digits ["." digits] [("e" | "E") ["+" | "-"] digits]
+fn parse_number(src: &mut &str) -> Result<f64, ParseError> {
+    let s = *src;
+    let bytes = s.as_bytes();
+
+    let mut end = bytes
+        .iter()
+        .position(|&b| !(b.is_ascii_digit() || b == b'.'))
+        .unwrap_or(bytes.len());
+
+    if let Some(b'e') | Some(b'E') = bytes.get(end) {
+        let mut i = end + 1;
+
+        if let Some(b'+') | Some(b'-') = bytes.get(i) {
+            i += 1;
+        }
+
+        if bytes.get(i).map(|b| b.is_ascii_digit()).unwrap_or(false) {
+            end = i + bytes[i..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or_else(|| bytes.len() - i);
+        }
+    }
+
+    let (token, rest) = s.split_at(end);
+    *src = rest;
+
+    token
+        .parse()
+        .map_err(|_| ParseError::InvalidNumber(token.into()))
+}
+
+fn take_while<'a, F>(src: &mut &'a str, f: F) -> &'a str
+where
+    F: Fn(char) -> bool,
+{
+    let s = src.trim_start();
+    let end = s.find(|c| !f(c)).unwrap_or(s.len());
+    let (token, rest) = s.split_at(end);
+    *src = rest;
+    token
+}
+
+fn consume(src: &mut &str, token: &str) -> bool {
+    let s = src.trim_start();
+
+    match s.strip_prefix(token) {
+        Some(rest) => {
+            *src = rest;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns an iterator adapter that selects records for which the expression is true.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "io", doc = "```")]
+#[cfg_attr(not(feature = "io"), doc = "```ignore")]
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, expression};
+///
+/// let data = b"##fileformat=VCFv4.3
+/// #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+/// sq0\t1\t.\tA\tC\t8\tPASS\tDP=13
+/// sq0\t2\t.\tC\tG\t34\tPASS\tDP=21
+/// ";
+///
+/// let mut reader = vcf::Reader::new(&data[..]);
+/// reader.read_header()?;
+///
+/// let expression = "QUAL > 30".parse().map_err(|e| {
+///     io::Error::new(io::ErrorKind::InvalidInput, e)
+/// })?;
+///
+/// let positions = expression::select(reader.records(), expression)
+///     .map(|result| result.map(|record| record.position()))
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(positions, [2]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn select<I>(records: I, expression: Expression) -> Select<I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    Select {
+        inner: records.into_iter(),
+        expression,
+    }
+}
+
+/// An iterator adapter that selects records for which an expression is true.
+///
+/// This is created by calling [`select`].
+pub struct Select<I> {
+    inner: I,
+    expression: Expression,
+}
+
+impl<I> Iterator for Select<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next()? {
+                Ok(record) => {
+                    if self.expression.evaluate(&record) {
+                        return Some(Ok(record));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Format, Genotype};
+
+    use super::*;
+
+    fn build_record() -> Record {
+        let format: Format = "GT:GQ:AD".parse().unwrap();
+
+        Record::builder()
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(1)
+            .set_reference_bases("A".parse().unwrap())
+            .set_alternate_bases("C".parse().unwrap())
+            .set_quality_score("8".parse().unwrap())
+            .set_info("DP=13;AF=0.25;DB".parse().unwrap())
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format("0/1:30:7,6", &format).unwrap())
+            .add_genotype(Genotype::from_str_format("1/1:.:0,5", &format).unwrap())
+            .add_genotype(Genotype::from_str_format("0/0:12:9,0", &format).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        fn boxed(expression: Expression) -> Box<Expression> {
+            Box::new(expression)
+        }
+
+        assert_eq!(
+            "QUAL<10".parse(),
+            Ok(Expression::Comparison(
+                ComparisonOperator::Lt,
+                boxed(Expression::QualityScore),
+                boxed(Expression::Number(10.0)),
+            ))
+        );
+
+        assert_eq!(
+            "INFO/DP >= 5 || !INFO/DB && any(FMT/GQ != 1.5e1)".parse(),
+            Ok(Expression::Or(vec![
+                Expression::Comparison(
+                    ComparisonOperator::Ge,
+                    boxed(Expression::Info(String::from("DP"))),
+                    boxed(Expression::Number(5.0)),
+                ),
+                Expression::And(vec![
+                    Expression::Not(boxed(Expression::Info(String::from("DB")))),
+                    Expression::Any(boxed(Expression::Comparison(
+                        ComparisonOperator::Ne,
+                        boxed(Expression::Genotype(String::from("GQ"))),
+                        boxed(Expression::Number(15.0)),
+                    ))),
+                ]),
+            ]))
+        );
+
+        assert_eq!(
+            "-(1 + 2) * 3 - 4".parse(),
+            Ok(Expression::Arithmetic(
+                ArithmeticOperator::Subtract,
+                boxed(Expression::Arithmetic(
+                    ArithmeticOperator::Multiply,
+                    boxed(Expression::Negate(boxed(Expression::Arithmetic(
+                        ArithmeticOperator::Add,
+                        boxed(Expression::Number(1.0)),
+                        boxed(Expression::Number(2.0)),
+                    )))),
+                    boxed(Expression::Number(3.0)),
+                )),
+                boxed(Expression::Number(4.0)),
+            ))
+        );
+
+        assert_eq!("".parse::<Expression>(), Err(ParseError::Empty));
+        assert_eq!(
+            "DP < 1".parse::<Expression>(),
+            Err(ParseError::InvalidValue(String::from("DP")))
+        );
+        assert_eq!(
+            "INFO/ < 1".parse::<Expression>(),
+            Err(ParseError::InvalidValue(String::from("INFO/")))
+        );
+        assert_eq!(
+            "QUAL < 1..0".parse::<Expression>(),
+            Err(ParseError::InvalidNumber(String::from("1..0")))
+        );
+        assert_eq!(
+            "(QUAL < 1".parse::<Expression>(),
+            Err(ParseError::MissingClosingParenthesis)
+        );
+        assert_eq!(
+            "QUAL < 1 QUAL".parse::<Expression>(),
+            Err(ParseError::UnexpectedInput(String::from("QUAL")))
+        );
+    }
+
+    #[test]
+    fn test_evaluate() -> Result<(), ParseError> {
+        let record = build_record();
+
+        let evaluate = |s: &str| s.parse::<Expression>().map(|e| e.evaluate(&record));
+
+        assert!(evaluate("QUAL < 10")?);
+        assert!(!evaluate("QUAL >= 10")?);
+        assert!(evaluate("QUAL")?);
+        assert!(evaluate("INFO/DP == 13")?);
+        assert!(evaluate("INFO/AF < 0.5")?);
+        assert!(evaluate("INFO/DB")?);
+        assert!(!evaluate("INFO/H2")?);
+        assert!(!evaluate("INFO/NS > 0")?);
+        assert!(!evaluate("INFO/NS < 0")?);
+        assert!(evaluate("INFO/DP > QUAL")?);
+        assert!(evaluate(
+            "INFO/DP / QUAL > 1.5 && INFO/DP - 2 * QUAL == -3"
+        )?);
+        assert!(evaluate("FMT/GQ < 20")?);
+        assert!(!evaluate("FMT/GQ > 30")?);
+        assert!(evaluate("FMT/AD == 0")?);
+        assert!(evaluate("QUAL > -1")?);
+        assert!(evaluate("QUAL > 10 || (INFO/DB && FMT/GQ >= 30)")?);
+
+        assert!(evaluate("any(FMT/GQ < 20)")?);
+        assert!(!evaluate("all(FMT/GQ < 20)")?);
+        assert!(evaluate("all(FMT/AD > 4)")?);
+        assert!(!evaluate("any(FMT/GQ > 20 && FMT/AD == 0)")?);
+        assert!(evaluate("any(!FMT/GQ)")?);
+        assert!(!evaluate("all(FMT/GQ)")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_values() -> Result<(), ParseError> {
+        let record = build_record();
+
+        let values = |s: &str| s.parse::<Expression>().map(|e| e.values(&record));
+
+        assert_eq!(values("-QUAL")?, [-8.0]);
+        assert!(values("INFO/NS + 1")?.is_empty());
+        assert_eq!(values("FMT/GQ")?, [30.0, 12.0]);
+        assert_eq!(values("FMT/AD * 2")?, [14.0, 12.0, 0.0, 10.0, 18.0, 0.0]);
+        assert_eq!(values("FMT/GQ - FMT/GQ")?, [0.0, 0.0]);
+        assert_eq!(values("INFO/DB")?, [1.0]);
+        assert_eq!(values("QUAL > 1")?, [1.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_select() -> io::Result<()> {
+        let mut record = build_record();
+        *record.info_mut() = "DP=5".parse().unwrap();
+
+        let records = vec![Ok(build_record()), Ok(record)];
+        let expression = "INFO/DP > 10".parse().unwrap();
+        let selected: Vec<_> = select(records, expression).collect::<io::Result<_>>()?;
+        assert_eq!(selected, [build_record()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+//! VCF record filtering.
+//!
+//! A filter engine evaluates a list of named filter expressions on each record and writes the
+//! outcome into the filter status (`FILTER`), like `bcftools filter --soft-filter`. A record
+//! fails a filter if its expression is true.
+//!
+//! Filter expressions are [`Expression`]s, e.g., `QUAL < 10 || INFO/DP < 5`. See the
+//! [`expression`](crate::expression) module for the syntax.
+//!
+//! # Examples
+//!
+//! ```
+//! use noodles_vcf::{self as vcf, filter::Engine, record::FilterStatus};
+//!
+//! let engine = Engine::new()
+//!     .add_filter("q10", "Quality below 10", "QUAL < 10".parse()?)
+//!     .add_filter("lowdp", "Total depth below 5", "INFO/DP < 5".parse()?);
+//!
+//! let mut header = vcf::Header::default();
+//! engine.update_header(&mut header);
+//! assert_eq!(header.filters().len(), 2);
+//!
+//! let mut record = vcf::Record::builder()
+//!     .set_chromosome("sq0".parse()?)
+//!     .set_position(1)
+//!     .set_reference_bases("A".parse()?)
+//!     .set_quality_score("8".parse()?)
+//!     .set_info("DP=13".parse()?)
+//!     .build()?;
+//!
+//! engine.apply(&mut record);
+//! assert_eq!(record.filter_status(), &FilterStatus::Fail(vec![String::from("q10")]));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{expression::Expression, header, record::FilterStatus, Header, Record};
+
+/// A VCF record filter engine.
+#[derive(Clone, Debug, Default)]
+pub struct Engine {
+    filters: Vec<(header::Filter, Expression)>,
+    append: bool,
+}
+
+impl Engine {
+    /// Creates a filter engine with no filters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::filter::Engine;
+    /// let engine = Engine::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a filter.
+    ///
+    /// A record fails the filter with the given ID if the expression is true.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::filter::Engine;
+    /// let engine = Engine::new().add_filter("q10", "Quality below 10", "QUAL < 10".parse()?);
+    /// # Ok::<(), noodles_vcf::expression::ParseError>(())
+    /// ```
+    pub fn add_filter<I, D>(mut self, id: I, description: D, expression: Expression) -> Self
+    where
+        I: Into<String>,
+        D: Into<String>,
+    {
+        let filter = header::Filter::new(id.into(), description.into());
+        self.filters.push((filter, expression));
+        self
+    }
+
+    /// Sets whether failed filters are appended to the existing filter status.
+    ///
+    /// By default, the filter status is replaced. This is equivalent to `bcftools filter
+    /// --mode +`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::filter::Engine;
+    /// let engine = Engine::new().set_append(true);
+    /// ```
+    pub fn set_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Adds the filter definitions to the header.
+    ///
+    /// Filters that are already defined are not changed.
+    pub fn update_header(&self, header: &mut Header) {
+        for (filter, _) in &self.filters {
+            let is_defined = header.filters().iter().any(|f| f.id() == filter.id());
+
+            if !is_defined {
+                header.filters_mut().push(filter.clone());
+            }
+        }
+    }
+
+    /// Evaluates the filters for the given record and sets its filter status.
+    ///
+    /// If the record fails any filter, the filter status is set to the list of failed filter IDs.
+    /// Otherwise, it is set to pass, unless failed filters are appended and the record already
+    /// failed a filter.
+    pub fn apply(&self, record: &mut Record) {
+        let failed_ids: Vec<String> = self
+            .filters
+            .iter()
+            .filter(|(_, expression)| expression.evaluate(record))
+            .map(|(filter, _)| filter.id().into())
+            .collect();
+
+        let filter_status = record.filter_status_mut();
+
+        if failed_ids.is_empty() {
+            if !(self.append && matches!(filter_status, FilterStatus::Fail(_))) {
+                *filter_status = FilterStatus::Pass;
+            }
+        } else if self.append {
+            match filter_status {
+                FilterStatus::Fail(ids) => {
+                    for id in failed_ids {
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                }
+                _ => *filter_status = FilterStatus::Fail(failed_ids),
+            }
+        } else {
+            *filter_status = FilterStatus::Fail(failed_ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        expression::ParseError,
+        record::{Format, Genotype},
+    };
+
+    use super::*;
+
+    fn build_record() -> Record {
+        let format: Format = "GT:GQ".parse().unwrap();
+
+        Record::builder()
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(1)
+            .set_reference_bases("A".parse().unwrap())
+            .set_alternate_bases("C".parse().unwrap())
+            .set_quality_score("8".parse().unwrap())
+            .set_info("DP=13;AF=0.25;DB".parse().unwrap())
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format("0/1:30", &format).unwrap())
+            .add_genotype(Genotype::from_str_format("1/1:.", &format).unwrap())
+            .add_genotype(Genotype::from_str_format("0/0:12", &format).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply() -> Result<(), ParseError> {
+        let engine = Engine::new()
+            .add_filter("q10", "Quality below 10", "QUAL < 10".parse()?)
+            .add_filter("dp20", "Depth below 20", "INFO/DP < 20".parse()?)
+            .add_filter("db", "In dbSNP", "!INFO/DB".parse()?);
+
+        let mut record = build_record();
+        engine.apply(&mut record);
+        assert_eq!(
+            record.filter_status(),
+            &FilterStatus::Fail(vec![String::from("q10"), String::from("dp20")])
+        );
+
+        let engine = Engine::new().add_filter("q5", "Quality below 5", "QUAL < 5".parse()?);
+
+        let mut record = build_record();
+        *record.filter_status_mut() = FilterStatus::Fail(vec![String::from("q10")]);
+        engine.apply(&mut record);
+        assert_eq!(record.filter_status(), &FilterStatus::Pass);
+
+        let engine = engine.set_append(true);
+
+        let mut record = build_record();
+        *record.filter_status_mut() = FilterStatus::Fail(vec![String::from("q10")]);
+        engine.apply(&mut record);
+        assert_eq!(
+            record.filter_status(),
+            &FilterStatus::Fail(vec![String::from("q10")])
+        );
+
+        let engine = engine.add_filter("dp20", "Depth below 20", "INFO/DP < 20".parse()?);
+        engine.apply(&mut record);
+        assert_eq!(
+            record.filter_status(),
+            &FilterStatus::Fail(vec![String::from("q10"), String::from("dp20")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_header() {
+        let engine = Engine::new()
+            .add_filter("q10", "Quality below 10", Expression::QualityScore)
+            .add_filter("dp20", "Depth below 20", Expression::QualityScore);
+
+        let mut header = Header::builder()
+            .add_filter(header::Filter::new(
+                String::from("q10"),
+                String::from("Quality below 10"),
+            ))
+            .build();
+
+        engine.update_header(&mut header);
+
+        let ids: Vec<_> = header.filters().iter().map(|f| f.id()).collect();
+        assert_eq!(ids, ["q10", "dp20"]);
+    }
+}
@@ -0,0 +1,124 @@
+//! VCF record framing for message streams.
+//!
+//! Use [`noodles_core::framing`] to write and read frames of VCF records, and [`fingerprint`] to
+//! check that producers and consumers agree on the header.
+
+use noodles_core::framing::Fingerprint;
+
+use super::Header;
+
+/// Computes the fingerprint of a header.
+///
+/// The fingerprint includes the records that define how record fields are interpreted, in
+/// order: contig IDs; filter IDs; the IDs, numbers, and types of info fields and genotype
+/// fields; and the sample names. Other header records, e.g., descriptions, are not included.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, header::Contig};
+///
+/// let header = vcf::Header::builder()
+///     .add_contig(Contig::new(String::from("sq0")))
+///     .add_sample_name("sample0")
+///     .build();
+///
+/// let fingerprint = vcf::framing::fingerprint(&header);
+/// assert_ne!(fingerprint, vcf::framing::fingerprint(&vcf::Header::default()));
+/// ```
+pub fn fingerprint(header: &Header) -> Fingerprint {
+    let mut parts = Vec::new();
+
+    for contig in header.contigs() {
+        parts.push(format!("contig\t{}", contig.id()));
+    }
+
+    for filter in header.filters() {
+        parts.push(format!("filter\t{}", filter.id()));
+    }
+
+    for info in header.infos() {
+        parts.push(format!(
+            "info\t{}\t{}\t{}",
+            info.id(),
+            info.number(),
+            info.ty()
+        ));
+    }
+
+    for format in header.formats() {
+        parts.push(format!(
+            "format\t{}\t{}\t{}",
+            format.id(),
+            format.number(),
+            format.ty()
+        ));
+    }
+
+    for sample_name in header.sample_names() {
+        parts.push(format!("sample\t{}", sample_name));
+    }
+
+    let parts: Vec<_> = parts.iter().map(|part| part.as_bytes()).collect();
+    Fingerprint::from_parts(&parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use noodles_core::framing;
+
+    use super::*;
+    use crate::Record;
+
+    #[test]
+    fn test_fingerprint() -> Result<(), Box<dyn std::error::Error>> {
+        let header: Header = "##fileformat=VCFv4.3
+##INFO=<ID=NS,Number=1,Type=Integer,Description=\"Number of samples with data\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+"
+        .parse()?;
+
+        let fingerprint = fingerprint(&header);
+
+        // Descriptions are not part of the fingerprint.
+        let redescribed: Header = "##fileformat=VCFv4.3
+##INFO=<ID=NS,Number=1,Type=Integer,Description=\"Samples\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+"
+        .parse()?;
+        assert_eq!(super::fingerprint(&redescribed), fingerprint);
+
+        let retyped: Header = "##fileformat=VCFv4.3
+##INFO=<ID=NS,Number=1,Type=Float,Description=\"Number of samples with data\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+"
+        .parse()?;
+        assert_ne!(super::fingerprint(&retyped), fingerprint);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frame_round_trip() -> io::Result<()> {
+        let record = Record::builder()
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(8)
+            .set_reference_bases("A".parse().unwrap())
+            .build()
+            .unwrap();
+
+        let mut buf = Vec::new();
+        framing::write_frame(&mut buf, &record)?;
+
+        let (actual, n) = framing::read_frame::<Record>(&buf)?.expect("missing frame");
+        assert_eq!(actual, record);
+        assert_eq!(n, buf.len());
+
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
 //! VCF header and fields.
+//!
+//! Contig, filter, info, and format records can have a dictionary index (`IDX`). BCF uses it to
+//! give each ID a fixed position in the contig or string dictionary. Indices can be
+//! non-contiguous, e.g., after subsetting, and are kept when a header is read and written.
 
 pub mod alternative_allele;
 mod builder;
@@ -94,6 +98,34 @@ impl Header {
         &self.infos
     }
 
+    /// Returns a mutable reference to the list of information records (`INFO`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::{info::Type, Info, Number},
+    ///     record::info::field::Key,
+    /// };
+    ///
+    /// let mut header = vcf::Header::default();
+    ///
+    /// header.infos_mut().push(Info::new(
+    ///     Key::SamplesWithDataCount,
+    ///     Number::Count(1),
+    ///     Type::Integer,
+    ///     String::from("Number of samples with data"),
+    /// ));
+    ///
+    /// let infos = header.infos();
+    /// assert_eq!(infos.len(), 1);
+    /// assert_eq!(infos[0].id(), &Key::SamplesWithDataCount);
+    /// ```
+    pub fn infos_mut(&mut self) -> &mut Vec<Info> {
+        &mut self.infos
+    }
+
     /// Returns a list of filter records (`FILTER`).
     ///
     /// # Examples
@@ -116,6 +148,28 @@ impl Header {
         &self.filters
     }
 
+    /// Returns a mutable reference to the list of filter records (`FILTER`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, header::Filter};
+    ///
+    /// let mut header = vcf::Header::default();
+    ///
+    /// header.filters_mut().push(Filter::new(
+    ///     String::from("q10"),
+    ///     String::from("Quality below 10"),
+    /// ));
+    ///
+    /// let filters = header.filters();
+    /// assert_eq!(filters.len(), 1);
+    /// assert_eq!(filters[0].id(), "q10");
+    /// ```
+    pub fn filters_mut(&mut self) -> &mut Vec<Filter> {
+        &mut self.filters
+    }
+
     /// Returns a list of genotype format records (`FORMAT`).
     ///
     /// # Examples
@@ -144,6 +198,34 @@ impl Header {
         &self.formats
     }
 
+    /// Returns a mutable reference to the list of genotype format records (`FORMAT`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::{format::Type, Format, Number},
+    ///     record::genotype::field::Key,
+    /// };
+    ///
+    /// let mut header = vcf::Header::default();
+    ///
+    /// header.formats_mut().push(Format::new(
+    ///     Key::Genotype,
+    ///     Number::Count(1),
+    ///     Type::String,
+    ///     String::from("Genotype"),
+    /// ));
+    ///
+    /// let formats = header.formats();
+    /// assert_eq!(formats.len(), 1);
+    /// assert_eq!(formats[0].id(), &Key::Genotype);
+    /// ```
+    pub fn formats_mut(&mut self) -> &mut Vec<Format> {
+        &mut self.formats
+    }
+
     /// Returns a list of symbolic alternate alleles (`ALT`).
     ///
     /// # Examples
@@ -210,6 +292,22 @@ impl Header {
         &self.contigs
     }
 
+    /// Returns a mutable reference to the list of contig records (`contig`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, header::Contig};
+    ///
+    /// let mut header = vcf::Header::default();
+    /// header.contigs_mut().push(Contig::new(String::from("sq0")));
+    ///
+    /// assert_eq!(header.contigs(), [Contig::new(String::from("sq0"))]);
+    /// ```
+    pub fn contigs_mut(&mut self) -> &mut Vec<Contig> {
+        &mut self.contigs
+    }
+
     /// Returns a URI to the relationships between genomes (`pedigreeDB`).
     ///
     /// # Examples
@@ -248,6 +346,25 @@ impl Header {
         &self.samples_names
     }
 
+    /// Returns a mutable reference to the list of sample names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut header = vcf::Header::builder().add_sample_name("sample0").build();
+    /// header.sample_names_mut().push(String::from("sample1"));
+    ///
+    /// assert_eq!(header.sample_names(), [
+    ///     String::from("sample0"),
+    ///     String::from("sample1"),
+    /// ]);
+    /// ```
+    pub fn sample_names_mut(&mut self) -> &mut Vec<String> {
+        &mut self.samples_names
+    }
+
     /// Returns a header record with the given key.
     ///
     /// This includes all records other than `fileformat`, `INFO`, `FILTER`, `FORMAT`, `ALT`,
@@ -609,6 +726,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_str_with_idx() -> Result<(), ParseError> {
+        let s = r#"##fileformat=VCFv4.3
+##INFO=<ID=NS,Number=1,Type=Integer,Description="Number of samples with data",IDX=3>
+##FORMAT=<ID=GT,Number=1,Type=String,Description="Genotype",IDX=1>
+##contig=<ID=sq1,length=13,IDX=1>
+#CHROM	POS	ID	REF	ALT	QUAL	FILTER	INFO
+"#;
+
+        let header: Header = s.parse()?;
+
+        assert_eq!(header.infos()[0].idx(), Some(3));
+        assert_eq!(header.formats()[0].idx(), Some(1));
+        assert_eq!(header.contigs()[0].idx(), Some(1));
+        assert_eq!(header.to_string(), s);
+
+        Ok(())
+    }
+
     #[test]
     fn test_from_str_without_file_format() {
         let s = r#"##ALT=<ID=DEL,Description="Deletion">
@@ -13,6 +13,7 @@ use self::key::Key;
 pub struct Contig {
     id: String,
     len: Option<i32>,
+    idx: Option<usize>,
     fields: HashMap<String, String>,
 }
 
@@ -30,6 +31,7 @@ impl Contig {
         Self {
             id,
             len: None,
+            idx: None,
             fields: HashMap::new(),
         }
     }
@@ -60,6 +62,35 @@ impl Contig {
         self.len
     }
 
+    /// Returns the index of the contig in the header dictionary (`IDX`), if it is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::header::Contig;
+    /// let contig = Contig::new(String::from("sq0"));
+    ///
+    /// assert!(contig.idx().is_none());
+    /// ```
+    pub fn idx(&self) -> Option<usize> {
+        self.idx
+    }
+
+    /// Returns a mutable reference to the header dictionary index (`IDX`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::header::Contig;
+    /// let mut contig = Contig::new(String::from("sq0"));
+    ///
+    /// *contig.idx_mut() = Some(1);
+    /// assert_eq!(contig.idx(), Some(1));
+    /// ```
+    pub fn idx_mut(&mut self) -> &mut Option<usize> {
+        &mut self.idx
+    }
+
     /// Returns the value of the field with the given key.
     ///
     /// # Examples
@@ -103,6 +134,10 @@ impl fmt::Display for Contig {
             write!(f, r#",{}="{}""#, key, value)?;
         }
 
+        if let Some(idx) = self.idx {
+            write!(f, ",{}={}", Key::Idx, idx)?;
+        }
+
         f.write_str(">")?;
 
         Ok(())
@@ -118,6 +153,8 @@ pub enum TryFromRecordError {
     InvalidKey(key::ParseError),
     /// The length is invalid.
     InvalidLength(num::ParseIntError),
+    /// The index (`IDX`) is invalid.
+    InvalidIdx(num::ParseIntError),
     /// A required field is missing.
     MissingField(Key),
 }
@@ -131,6 +168,7 @@ impl fmt::Display for TryFromRecordError {
             Self::MissingField(key) => write!(f, "missing {} field", key),
             Self::InvalidKey(e) => write!(f, "invalid key: {}", e),
             Self::InvalidLength(e) => write!(f, "invalid length: {}", e),
+            Self::InvalidIdx(e) => write!(f, "invalid IDX: {}", e),
         }
     }
 }
@@ -149,6 +187,7 @@ impl TryFrom<Record> for Contig {
 fn parse_struct(fields: Vec<(String, String)>) -> Result<Contig, TryFromRecordError> {
     let mut id = None;
     let mut len = None;
+    let mut idx = None;
     let mut other_fields = HashMap::new();
 
     for (raw_key, value) in fields {
@@ -164,6 +203,12 @@ fn parse_struct(fields: Vec<(String, String)>) -> Result<Contig, TryFromRecordEr
                     .map(Some)
                     .map_err(TryFromRecordError::InvalidLength)?;
             }
+            Key::Idx => {
+                idx = value
+                    .parse()
+                    .map(Some)
+                    .map_err(TryFromRecordError::InvalidIdx)?;
+            }
             Key::Other(k) => {
                 other_fields.insert(k, value);
             }
@@ -173,6 +218,7 @@ fn parse_struct(fields: Vec<(String, String)>) -> Result<Contig, TryFromRecordEr
     Ok(Contig {
         id: id.ok_or(TryFromRecordError::MissingField(Key::Id))?,
         len,
+        idx,
         fields: other_fields,
     })
 }
@@ -215,6 +261,7 @@ mod tests {
             Ok(Contig {
                 id: String::from("sq0"),
                 len: Some(13),
+                idx: None,
                 fields: vec![(
                     String::from("md5"),
                     String::from("d7eba311421bbc9d3ada44709dd61534")
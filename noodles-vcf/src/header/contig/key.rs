@@ -9,6 +9,8 @@ pub enum Key {
     Id,
     /// (`length`).
     Length,
+    /// (`IDX`).
+    Idx,
     /// Any other key.
     Other(String),
 }
@@ -18,6 +20,7 @@ impl AsRef<str> for Key {
         match self {
             Self::Id => "ID",
             Self::Length => "length",
+            Self::Idx => "IDX",
             Self::Other(s) => s,
         }
     }
@@ -49,6 +52,7 @@ impl FromStr for Key {
             "" => Err(ParseError(s.into())),
             "ID" => Ok(Self::Id),
             "length" => Ok(Self::Length),
+            "IDX" => Ok(Self::Idx),
             _ => Ok(Self::Other(s.into())),
         }
     }
@@ -62,6 +66,7 @@ mod tests {
     fn test_fmt() {
         assert_eq!(Key::Id.to_string(), "ID");
         assert_eq!(Key::Length.to_string(), "length");
+        assert_eq!(Key::Idx.to_string(), "IDX");
         assert_eq!(Key::Other(String::from("md5")).to_string(), "md5");
     }
 
@@ -69,6 +74,7 @@ mod tests {
     fn test_from_str() -> Result<(), ParseError> {
         assert_eq!("ID".parse::<Key>()?, Key::Id);
         assert_eq!("length".parse::<Key>()?, Key::Length);
+        assert_eq!("IDX".parse::<Key>()?, Key::Idx);
         assert_eq!(
             "assembly".parse::<Key>()?,
             Key::Other(String::from("assembly"))
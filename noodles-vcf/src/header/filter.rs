@@ -2,7 +2,7 @@
 
 mod key;
 
-use std::{convert::TryFrom, error, fmt};
+use std::{convert::TryFrom, error, fmt, num};
 
 use super::{record, Record};
 
@@ -13,6 +13,7 @@ use self::key::Key;
 pub struct Filter {
     id: String,
     description: String,
+    idx: Option<usize>,
 }
 
 impl Filter {
@@ -25,7 +26,11 @@ impl Filter {
     /// let filter = Filter::new(String::from("q10"), String::from("Quality below 10"));
     /// ```
     pub fn new(id: String, description: String) -> Self {
-        Self { id, description }
+        Self {
+            id,
+            description,
+            idx: None,
+        }
     }
 
     /// Returns the ID of the filter.
@@ -53,6 +58,35 @@ impl Filter {
     pub fn description(&self) -> &str {
         &self.description
     }
+
+    /// Returns the index of the filter in the header dictionary (`IDX`), if it is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::header::Filter;
+    /// let filter = Filter::new(String::from("q10"), String::from("Quality below 10"));
+    ///
+    /// assert!(filter.idx().is_none());
+    /// ```
+    pub fn idx(&self) -> Option<usize> {
+        self.idx
+    }
+
+    /// Returns a mutable reference to the header dictionary index (`IDX`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::header::Filter;
+    /// let mut filter = Filter::new(String::from("q10"), String::from("Quality below 10"));
+    ///
+    /// *filter.idx_mut() = Some(1);
+    /// assert_eq!(filter.idx(), Some(1));
+    /// ```
+    pub fn idx_mut(&mut self) -> &mut Option<usize> {
+        &mut self.idx
+    }
 }
 
 impl fmt::Display for Filter {
@@ -64,6 +98,10 @@ impl fmt::Display for Filter {
         write!(f, "{}={}", Key::Id, self.id)?;
         write!(f, r#",{}="{}""#, Key::Description, self.description)?;
 
+        if let Some(idx) = self.idx {
+            write!(f, ",{}={}", Key::Idx, idx)?;
+        }
+
         f.write_str(">")?;
 
         Ok(())
@@ -77,6 +115,8 @@ pub enum TryFromRecordError {
     InvalidRecord,
     /// A field is missing.
     MissingField(Key),
+    /// The index (`IDX`) is invalid.
+    InvalidIdx(num::ParseIntError),
 }
 
 impl error::Error for TryFromRecordError {}
@@ -86,6 +126,7 @@ impl fmt::Display for TryFromRecordError {
         match self {
             Self::InvalidRecord => f.write_str("invalid record"),
             Self::MissingField(key) => write!(f, "missing {} field", key),
+            Self::InvalidIdx(e) => write!(f, "invalid IDX: {}", e),
         }
     }
 }
@@ -120,7 +161,22 @@ fn parse_struct(fields: Vec<(String, String)>) -> Result<Filter, TryFromRecordEr
             _ => Err(TryFromRecordError::MissingField(Key::Description)),
         })?;
 
-    Ok(Filter { id, description })
+    let mut idx = None;
+
+    for (k, v) in it {
+        if let Ok(Key::Idx) = k.parse() {
+            idx = v
+                .parse()
+                .map(Some)
+                .map_err(TryFromRecordError::InvalidIdx)?;
+        }
+    }
+
+    Ok(Filter {
+        id,
+        description,
+        idx,
+    })
 }
 
 #[cfg(test)]
@@ -160,6 +216,7 @@ mod tests {
             Ok(Filter {
                 id: String::from("q10"),
                 description: String::from("Quality below 10"),
+                idx: None,
             })
         );
     }
@@ -5,7 +5,7 @@ mod ty;
 
 pub use self::ty::Type;
 
-use std::{convert::TryFrom, error, fmt};
+use std::{convert::TryFrom, error, fmt, num};
 
 use crate::record::genotype;
 
@@ -20,6 +20,7 @@ pub struct Format {
     number: Number,
     ty: Type,
     description: String,
+    idx: Option<usize>,
 }
 
 impl Format {
@@ -46,6 +47,7 @@ impl Format {
             number,
             ty,
             description,
+            idx: None,
         }
     }
 
@@ -140,6 +142,53 @@ impl Format {
     pub fn description(&self) -> &str {
         &self.description
     }
+
+    /// Returns the index of the genotype format in the header dictionary (`IDX`), if it is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     header::{format::Type, Format, Number},
+    ///     record::genotype::field::Key,
+    /// };
+    ///
+    /// let format = Format::new(
+    ///     Key::Genotype,
+    ///     Number::Count(1),
+    ///     Type::String,
+    ///     String::from("Genotype"),
+    /// );
+    ///
+    /// assert!(format.idx().is_none());
+    /// ```
+    pub fn idx(&self) -> Option<usize> {
+        self.idx
+    }
+
+    /// Returns a mutable reference to the header dictionary index (`IDX`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     header::{format::Type, Format, Number},
+    ///     record::genotype::field::Key,
+    /// };
+    ///
+    /// let mut format = Format::new(
+    ///     Key::Genotype,
+    ///     Number::Count(1),
+    ///     Type::String,
+    ///     String::from("Genotype"),
+    /// );
+    ///
+    /// *format.idx_mut() = Some(1);
+    /// assert_eq!(format.idx(), Some(1));
+    /// ```
+    pub fn idx_mut(&mut self) -> &mut Option<usize> {
+        &mut self.idx
+    }
 }
 
 impl fmt::Display for Format {
@@ -153,6 +202,10 @@ impl fmt::Display for Format {
         write!(f, ",{}={}", Key::Type, self.ty)?;
         write!(f, r#",{}="{}""#, Key::Description, self.description)?;
 
+        if let Some(idx) = self.idx {
+            write!(f, ",{}={}", Key::Idx, idx)?;
+        }
+
         f.write_str(">")?;
 
         Ok(())
@@ -173,6 +226,8 @@ pub enum TryFromRecordError {
     InvalidNumber(number::ParseError),
     /// The type is invalid.
     InvalidType(ty::ParseError),
+    /// The index (`IDX`) is invalid.
+    InvalidIdx(num::ParseIntError),
 }
 
 impl error::Error for TryFromRecordError {}
@@ -185,6 +240,7 @@ impl fmt::Display for TryFromRecordError {
             Self::InvalidId(e) => write!(f, "invalid ID: {}", e),
             Self::InvalidNumber(e) => write!(f, "invalid number: {}", e),
             Self::InvalidType(e) => write!(f, "invalid type: {}", e),
+            Self::InvalidIdx(e) => write!(f, "invalid IDX: {}", e),
         }
     }
 }
@@ -235,11 +291,23 @@ fn parse_struct(fields: Vec<(String, String)>) -> Result<Format, TryFromRecordEr
             _ => Err(TryFromRecordError::MissingField(Key::Description)),
         })?;
 
+    let mut idx = None;
+
+    for (k, v) in it {
+        if let Ok(Key::Idx) = k.parse() {
+            idx = v
+                .parse()
+                .map(Some)
+                .map_err(TryFromRecordError::InvalidIdx)?;
+        }
+    }
+
     Ok(Format {
         id,
         number,
         ty,
         description,
+        idx,
     })
 }
 
@@ -281,6 +349,7 @@ mod tests {
                 number: Number::Count(1),
                 ty: Type::Integer,
                 description: String::from("Genotype"),
+                idx: None,
             })
         );
     }
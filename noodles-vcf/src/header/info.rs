@@ -5,7 +5,7 @@ pub mod ty;
 
 pub use self::ty::Type;
 
-use std::{collections::HashMap, convert::TryFrom, error, fmt};
+use std::{collections::HashMap, convert::TryFrom, error, fmt, num};
 
 use crate::record::info;
 
@@ -20,6 +20,7 @@ pub struct Info {
     number: Number,
     ty: Type,
     description: String,
+    idx: Option<usize>,
     fields: HashMap<String, String>,
 }
 
@@ -47,6 +48,7 @@ impl Info {
             number,
             ty,
             description,
+            idx: None,
             fields: HashMap::new(),
         }
     }
@@ -143,9 +145,56 @@ impl Info {
         &self.description
     }
 
+    /// Returns the index of the information field in the header dictionary (`IDX`), if it is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     header::{info::Type, Info, Number},
+    ///     record::info::field::Key,
+    /// };
+    ///
+    /// let info = Info::new(
+    ///     Key::SamplesWithDataCount,
+    ///     Number::Count(1),
+    ///     Type::Integer,
+    ///     String::from("Number of samples with data"),
+    /// );
+    ///
+    /// assert!(info.idx().is_none());
+    /// ```
+    pub fn idx(&self) -> Option<usize> {
+        self.idx
+    }
+
+    /// Returns a mutable reference to the header dictionary index (`IDX`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     header::{info::Type, Info, Number},
+    ///     record::info::field::Key,
+    /// };
+    ///
+    /// let mut info = Info::new(
+    ///     Key::SamplesWithDataCount,
+    ///     Number::Count(1),
+    ///     Type::Integer,
+    ///     String::from("Number of samples with data"),
+    /// );
+    ///
+    /// *info.idx_mut() = Some(1);
+    /// assert_eq!(info.idx(), Some(1));
+    /// ```
+    pub fn idx_mut(&mut self) -> &mut Option<usize> {
+        &mut self.idx
+    }
+
     /// Returns the extra fields in the record.
     ///
-    /// This includes fields other than `ID`, `Number`, `Type`, and `Description`.
+    /// This includes fields other than `ID`, `Number`, `Type`, `Description`, and `IDX`.
     pub fn fields(&self) -> &HashMap<String, String> {
         &self.fields
     }
@@ -166,6 +215,10 @@ impl fmt::Display for Info {
             write!(f, r#",{}="{}""#, key, value)?;
         }
 
+        if let Some(idx) = self.idx {
+            write!(f, ",{}={}", Key::Idx, idx)?;
+        }
+
         f.write_str(">")?;
 
         Ok(())
@@ -185,6 +238,8 @@ pub enum TryFromRecordError {
     InvalidNumber(number::ParseError),
     /// The type is invalid.
     InvalidType(ty::ParseError),
+    /// The index (`IDX`) is invalid.
+    InvalidIdx(num::ParseIntError),
 }
 
 impl error::Error for TryFromRecordError {}
@@ -197,6 +252,7 @@ impl fmt::Display for TryFromRecordError {
             Self::InvalidId(e) => write!(f, "invalid ID: {}", e),
             Self::InvalidNumber(e) => write!(f, "invalid number: {}", e),
             Self::InvalidType(e) => write!(f, "invalid type: {}", e),
+            Self::InvalidIdx(e) => write!(f, "invalid IDX: {}", e),
         }
     }
 }
@@ -247,12 +303,30 @@ fn parse_struct(fields: Vec<(String, String)>) -> Result<Info, TryFromRecordErro
             _ => Err(TryFromRecordError::MissingField(Key::Description)),
         })?;
 
+    let mut idx = None;
+    let mut other_fields = HashMap::new();
+
+    for (k, v) in it {
+        match k.parse() {
+            Ok(Key::Idx) => {
+                idx = v
+                    .parse()
+                    .map(Some)
+                    .map_err(TryFromRecordError::InvalidIdx)?;
+            }
+            _ => {
+                other_fields.insert(k, v);
+            }
+        }
+    }
+
     Ok(Info {
         id,
         number,
         ty,
         description,
-        fields: it.collect(),
+        idx,
+        fields: other_fields,
     })
 }
 
@@ -298,6 +372,7 @@ mod tests {
                 number: Number::Count(1),
                 ty: Type::Integer,
                 description: String::from("Number of samples with data"),
+                idx: None,
                 fields: HashMap::new(),
             })
         );
@@ -327,6 +402,7 @@ mod tests {
                 number: Number::Count(1),
                 ty: Type::Integer,
                 description: String::from("Number of samples with data"),
+                idx: None,
                 fields: vec![
                     (String::from("Source"), String::from("dbsnp")),
                     (String::from("Version"), String::from("138")),
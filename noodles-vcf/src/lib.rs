@@ -6,7 +6,8 @@
 //!
 //! ## Read all records from a file
 //!
-//! ```no_run
+#![cfg_attr(feature = "io", doc = "```no_run")]
+#![cfg_attr(not(feature = "io"), doc = "```ignore")]
 //! # use std::{fs::File, io::{self, BufReader}};
 //! use noodles_vcf as vcf;
 //!
@@ -19,10 +20,49 @@
 //! }
 //! # Ok::<(), io::Error>(())
 //! ```
+//!
+//! ## Parse data from a byte slice
+//!
+//! Data that is already in memory can be parsed using the functions in [`slice`]. These are
+//! available when the default `io` feature is disabled, which removes the reader and writer, e.g.,
+//! for `wasm32-unknown-unknown` builds.
+//!
+//! ```
+//! use noodles_vcf::slice;
+//!
+//! let data = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+//! let (header, records) = slice::parse(data)?;
+//!
+//! for result in records {
+//!     let record = result?;
+//!     println!("{:?}", record);
+//! }
+//! # Ok::<(), slice::ParseError>(())
+//! ```
 
+pub mod annotate;
+pub mod call;
+#[cfg(feature = "io")]
+pub mod concat;
+#[cfg(feature = "io")]
+pub mod conformance;
+pub mod expression;
+pub mod filter;
+pub mod framing;
 pub mod header;
+pub mod matrix;
+pub mod merge;
+pub mod pedigree;
+#[cfg(feature = "io")]
 mod reader;
 pub mod record;
+pub mod slice;
+pub mod sort;
+pub mod subset;
+#[cfg(feature = "io")]
 mod writer;
 
-pub use self::{header::Header, reader::Reader, record::Record, writer::Writer};
+pub use self::{header::Header, record::Record};
+
+#[cfg(feature = "io")]
+pub use self::{reader::Reader, writer::Writer};
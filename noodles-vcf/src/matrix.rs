@@ -0,0 +1,343 @@
+//! VCF genotype matrices.
+//!
+//! A genotype matrix is a dense, sample-major table of per-sample values for a list of records,
+//! e.g., the records of a region query. Row `i` holds the values of sample `i` for each variant,
+//! in record order.
+
+use std::io;
+
+use super::{record::genotype::phasing::Alleles, Header, Record};
+
+/// A dense matrix of samples × variants.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Matrix<T> {
+    sample_count: usize,
+    variant_count: usize,
+    values: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Returns the number of rows.
+    pub fn sample_count(&self) -> usize {
+        self.sample_count
+    }
+
+    /// Returns the number of columns.
+    pub fn variant_count(&self) -> usize {
+        self.variant_count
+    }
+
+    /// Returns the value of a sample at a variant.
+    pub fn get(&self, sample_index: usize, variant_index: usize) -> Option<&T> {
+        if sample_index < self.sample_count && variant_index < self.variant_count {
+            self.values
+                .get(sample_index * self.variant_count + variant_index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the values of a sample, i.e., a row.
+    pub fn sample(&self, sample_index: usize) -> Option<&[T]> {
+        let start = sample_index.checked_mul(self.variant_count)?;
+        self.values.get(start..start + self.variant_count)
+    }
+
+    /// Returns all values in sample-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Converts each value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_vcf::{self as vcf, matrix, record::{Format, Genotype}};
+    ///
+    /// let header = vcf::Header::builder().add_sample_name("sample0").build();
+    /// let format: Format = "GT".parse().unwrap();
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse().unwrap())
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse().unwrap())
+    ///     .set_format(format.clone())
+    ///     .add_genotype(Genotype::from_str_format("./.", &format).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let dosages = matrix::dosages(&header, vec![Ok(record)])?;
+    /// let values = dosages.map(|d| d.map(f64::from).unwrap_or(f64::NAN));
+    /// assert!(values.as_slice()[0].is_nan());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Matrix<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Matrix {
+            sample_count: self.sample_count,
+            variant_count: self.variant_count,
+            values: self.values.into_iter().map(f).collect(),
+        }
+    }
+
+    /// Returns all values in sample-major order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+/// Builds an alternate allele dosage matrix.
+///
+/// The dosage of a sample at a variant is the number of non-reference alleles in its genotype
+/// (`GT`). It is `None` if the genotype is absent or has a missing allele.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, matrix, record::{Format, Genotype}};
+///
+/// let header = vcf::Header::builder()
+///     .add_sample_name("sample0")
+///     .add_sample_name("sample1")
+///     .build();
+///
+/// let format: Format = "GT".parse().unwrap();
+///
+/// let build_record = |position, genotypes: &[&str]| {
+///     genotypes
+///         .iter()
+///         .fold(vcf::Record::builder(), |builder, genotype| {
+///             builder.add_genotype(Genotype::from_str_format(genotype, &format).unwrap())
+///         })
+///         .set_chromosome("sq0".parse().unwrap())
+///         .set_position(position)
+///         .set_reference_bases("A".parse().unwrap())
+///         .set_alternate_bases("C,G".parse().unwrap())
+///         .set_format(format.clone())
+///         .build()
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+/// };
+///
+/// let records = vec![
+///     build_record(1, &["0/1", "1|2"]),
+///     build_record(5, &["./.", "0|0"]),
+/// ];
+///
+/// let dosages = matrix::dosages(&header, records)?;
+///
+/// assert_eq!(dosages.sample(0), Some(&[Some(1), None][..]));
+/// assert_eq!(dosages.sample(1), Some(&[Some(2), Some(0)][..]));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn dosages<I>(header: &Header, records: I) -> io::Result<Matrix<Option<u8>>>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    let sample_count = header.sample_names().len();
+
+    build(sample_count, 1, records, |alleles, values| {
+        let dosage = alleles.and_then(|alleles| {
+            alleles
+                .iter()
+                .map(|allele| allele.position().map(|i| (i > 0) as u8))
+                .sum::<Option<u8>>()
+        });
+
+        values.push(dosage);
+
+        Ok(())
+    })
+}
+
+/// Builds an allele matrix.
+///
+/// Each sample has `ploidy` rows, one for each allele of its genotype (`GT`), so row
+/// `i * ploidy + j` is allele `j` of sample `i`. An allele value is its position, i.e., `0` for
+/// the reference allele and `n` for the `n`th alternate allele. It is `None` if the genotype is
+/// absent, the allele is missing, or the genotype has fewer alleles than `ploidy`.
+///
+/// # Errors
+///
+/// An error is returned if a genotype has more alleles than `ploidy`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, matrix, record::{Format, Genotype}};
+///
+/// let header = vcf::Header::builder().add_sample_name("sample0").build();
+/// let format: Format = "GT".parse().unwrap();
+///
+/// let record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse().unwrap())
+///     .set_position(1)
+///     .set_reference_bases("A".parse().unwrap())
+///     .set_alternate_bases("C".parse().unwrap())
+///     .set_format(format.clone())
+///     .add_genotype(Genotype::from_str_format("1|.", &format).unwrap())
+///     .build()
+///     .unwrap();
+///
+/// let alleles = matrix::alleles(&header, vec![Ok(record)], 2)?;
+///
+/// assert_eq!(alleles.sample_count(), 2);
+/// assert_eq!(alleles.as_slice(), [Some(1), None]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn alleles<I>(header: &Header, records: I, ploidy: usize) -> io::Result<Matrix<Option<usize>>>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    let sample_count = header.sample_names().len();
+
+    build(sample_count, ploidy, records, |alleles, values| {
+        let positions: Vec<_> = alleles
+            .map(|alleles| alleles.iter().map(|allele| allele.position()).collect())
+            .unwrap_or_default();
+
+        if positions.len() > ploidy {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "expected at most {} alleles, got {}",
+                    ploidy,
+                    positions.len()
+                ),
+            ));
+        }
+
+        values.extend(positions.iter().copied());
+        values.extend((positions.len()..ploidy).map(|_| None));
+
+        Ok(())
+    })
+}
+
+// Builds a sample-major matrix with `rows_per_sample` rows for each sample. `f` pushes the
+// `rows_per_sample` values of a sample at a variant.
+fn build<I, T, F>(
+    sample_count: usize,
+    rows_per_sample: usize,
+    records: I,
+    mut f: F,
+) -> io::Result<Matrix<T>>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+    T: Clone,
+    F: FnMut(Option<&Alleles>, &mut Vec<T>) -> io::Result<()>,
+{
+    let row_count = sample_count * rows_per_sample;
+
+    // Values are first collected in variant-major order, i.e., one column at a time.
+    let mut columns = Vec::new();
+    let mut variant_count = 0;
+
+    for result in records {
+        let record = result?;
+
+        for i in 0..sample_count {
+            let alleles = record
+                .genotypes()
+                .get(i)
+                .and_then(|genotype| genotype.alleles())
+                .transpose()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            f(alleles.as_ref(), &mut columns)?;
+        }
+
+        variant_count += 1;
+    }
+
+    let values = (0..row_count)
+        .flat_map(|row| {
+            let columns = &columns;
+            (0..variant_count).map(move |variant| columns[variant * row_count + row].clone())
+        })
+        .collect();
+
+    Ok(Matrix {
+        sample_count: row_count,
+        variant_count,
+        values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Format, Genotype};
+
+    use super::*;
+
+    fn build_header(sample_count: usize) -> Header {
+        (0..sample_count)
+            .fold(Header::builder(), |builder, i| {
+                builder.add_sample_name(format!("sample{}", i))
+            })
+            .build()
+    }
+
+    fn build_record(genotypes: &[&str]) -> io::Result<Record> {
+        let format: Format = "GT".parse().unwrap();
+
+        genotypes
+            .iter()
+            .fold(Record::builder(), |builder, genotype| {
+                builder.add_genotype(Genotype::from_str_format(genotype, &format).unwrap())
+            })
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(1)
+            .set_reference_bases("A".parse().unwrap())
+            .set_alternate_bases("C,G".parse().unwrap())
+            .set_format(format)
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    #[test]
+    fn test_dosages() -> io::Result<()> {
+        let header = build_header(3);
+        let records = vec![
+            build_record(&["0/0", "0/1", "2/1"]),
+            build_record(&["1", "./1", "."]),
+        ];
+
+        let matrix = dosages(&header, records)?;
+
+        assert_eq!(matrix.sample_count(), 3);
+        assert_eq!(matrix.variant_count(), 2);
+        assert_eq!(
+            matrix.as_slice(),
+            [Some(0), Some(1), Some(1), None, Some(2), None]
+        );
+        assert_eq!(matrix.get(2, 0), Some(&Some(2)));
+        assert_eq!(matrix.get(3, 0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_alleles() -> io::Result<()> {
+        let header = build_header(2);
+        let records = vec![build_record(&["0|1", "2"]), build_record(&["./1", "1/1"])];
+
+        let matrix = alleles(&header, records, 2)?;
+
+        assert_eq!(matrix.sample_count(), 4);
+        assert_eq!(matrix.sample(0), Some(&[Some(0), None][..]));
+        assert_eq!(matrix.sample(1), Some(&[Some(1), Some(1)][..]));
+        assert_eq!(matrix.sample(2), Some(&[Some(2), Some(1)][..]));
+        assert_eq!(matrix.sample(3), Some(&[None, Some(1)][..]));
+
+        let records = vec![build_record(&["0/1/1", "0/0"])];
+        assert!(alleles(&header, records, 2).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,544 @@
+//! VCF multi-input merging.
+
+use std::{collections::VecDeque, convert::TryFrom, error, fmt, io, iter::Peekable, slice};
+
+use super::{
+    header::Contig,
+    record::{
+        allele_counts::{self, AlleleCounts},
+        genotype::{
+            self,
+            field::{Key, Value},
+        },
+        normalize, AlternateBases, Format, Genotype,
+    },
+    Header, Record,
+};
+
+/// An error returned when VCF headers fail to merge.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MergeHeadersError {
+    /// An information record (`INFO`) has a different number or type in another header.
+    InfoMismatch(String),
+    /// A genotype format record (`FORMAT`) has a different number or type in another header.
+    FormatMismatch(String),
+    /// A sample name is in more than one header.
+    DuplicateSample(String),
+}
+
+impl error::Error for MergeHeadersError {}
+
+impl fmt::Display for MergeHeadersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InfoMismatch(id) => write!(f, "INFO mismatch: {}", id),
+            Self::FormatMismatch(id) => write!(f, "FORMAT mismatch: {}", id),
+            Self::DuplicateSample(name) => write!(f, "duplicate sample: {}", name),
+        }
+    }
+}
+
+/// Merges a list of VCF headers.
+///
+/// The merged header is the first header with the information (`INFO`), filter (`FILTER`),
+/// genotype format (`FORMAT`), and contig (`contig`) records of the other headers that it does not
+/// already define appended. The sample names of all headers are concatenated, in order.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, header::Contig, merge};
+///
+/// let headers = [
+///     vcf::Header::builder()
+///         .add_contig(Contig::new(String::from("sq0")))
+///         .add_sample_name("sample0")
+///         .build(),
+///     vcf::Header::builder()
+///         .add_contig(Contig::new(String::from("sq1")))
+///         .add_sample_name("sample1")
+///         .build(),
+/// ];
+///
+/// let header = merge::merge_headers(&headers)?;
+///
+/// assert_eq!(header.contigs(), [
+///     Contig::new(String::from("sq0")),
+///     Contig::new(String::from("sq1")),
+/// ]);
+///
+/// assert_eq!(header.sample_names(), [
+///     String::from("sample0"),
+///     String::from("sample1"),
+/// ]);
+/// # Ok::<(), merge::MergeHeadersError>(())
+/// ```
+pub fn merge_headers(headers: &[Header]) -> Result<Header, MergeHeadersError> {
+    let mut iter = headers.iter();
+    let mut merged_header = iter.next().cloned().unwrap_or_default();
+
+    for header in iter {
+        for info in header.infos() {
+            match merged_header.infos().iter().find(|i| i.id() == info.id()) {
+                Some(i) if i.number() != info.number() || i.ty() != info.ty() => {
+                    return Err(MergeHeadersError::InfoMismatch(info.id().to_string()));
+                }
+                Some(_) => {}
+                None => merged_header.infos_mut().push(info.clone()),
+            }
+        }
+
+        for filter in header.filters() {
+            if !merged_header
+                .filters()
+                .iter()
+                .any(|f| f.id() == filter.id())
+            {
+                merged_header.filters_mut().push(filter.clone());
+            }
+        }
+
+        for format in header.formats() {
+            match merged_header
+                .formats()
+                .iter()
+                .find(|f| f.id() == format.id())
+            {
+                Some(f) if f.number() != format.number() || f.ty() != format.ty() => {
+                    return Err(MergeHeadersError::FormatMismatch(format.id().to_string()));
+                }
+                Some(_) => {}
+                None => merged_header.formats_mut().push(format.clone()),
+            }
+        }
+
+        for contig in header.contigs() {
+            if !merged_header
+                .contigs()
+                .iter()
+                .any(|c| c.id() == contig.id())
+            {
+                merged_header.contigs_mut().push(contig.clone());
+            }
+        }
+
+        for sample_name in header.sample_names() {
+            if merged_header.sample_names().contains(sample_name) {
+                return Err(MergeHeadersError::DuplicateSample(sample_name.clone()));
+            }
+
+            merged_header.sample_names_mut().push(sample_name.clone());
+        }
+    }
+
+    Ok(merged_header)
+}
+
+/// A VCF merger.
+///
+/// A merger combines records from multiple coordinate-sorted inputs. Records from different inputs
+/// at the same position with the same reference bases are merged into a single record: alternate
+/// alleles are merged, the genotypes of each input are placed in their sample columns, and samples
+/// of inputs without a record at the site are padded with missing genotypes. All other fields are
+/// taken from the first record at the site, with the existing allele count information fields
+/// (`AC`, `AN`, and `AF`) recomputed.
+///
+/// The order of reference sequences is defined by the contig records of the merged header.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use noodles_vcf::{self as vcf, header::Contig, merge::Merger};
+///
+/// let headers = [
+///     vcf::Header::builder().add_contig(Contig::new(String::from("sq0"))).build(),
+///     vcf::Header::builder().add_contig(Contig::new(String::from("sq0"))).build(),
+/// ];
+///
+/// let inputs = vec![
+///     vec![vcf::Record::builder()
+///         .set_chromosome("sq0".parse()?)
+///         .set_position(1)
+///         .set_reference_bases("A".parse()?)
+///         .set_alternate_bases("C".parse()?)
+///         .build()
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))]
+///     .into_iter(),
+///     vec![vcf::Record::builder()
+///         .set_chromosome("sq0".parse()?)
+///         .set_position(1)
+///         .set_reference_bases("A".parse()?)
+///         .set_alternate_bases("G".parse()?)
+///         .build()
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))]
+///     .into_iter(),
+/// ];
+///
+/// let merger = Merger::new(&headers, inputs)?;
+/// let records = merger.collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].alternate_bases().to_string(), "C,G");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Merger<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    header: Header,
+    inputs: Vec<Peekable<I>>,
+    sample_counts: Vec<usize>,
+    last_key: Option<(usize, i64)>,
+    buf: VecDeque<Record>,
+}
+
+impl<I> Merger<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    /// Creates a merger from a list of headers and their record inputs.
+    ///
+    /// Each input must be sorted by reference sequence and position.
+    pub fn new(headers: &[Header], inputs: Vec<I>) -> Result<Self, MergeHeadersError> {
+        let header = merge_headers(headers)?;
+        let sample_counts = headers.iter().map(|h| h.sample_names().len()).collect();
+
+        Ok(Self {
+            header,
+            inputs: inputs.into_iter().map(|input| input.peekable()).collect(),
+            sample_counts,
+            last_key: None,
+            buf: VecDeque::new(),
+        })
+    }
+
+    /// Returns the merged header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    // Reads all records at the next site and merges them into the buffer. This returns `false`
+    // when all inputs are exhausted.
+    fn fill_buf(&mut self) -> io::Result<bool> {
+        let contigs = self.header.contigs();
+
+        let mut min_key = None;
+
+        for input in &mut self.inputs {
+            if let Some(Err(_)) = input.peek() {
+                if let Some(Err(e)) = input.next() {
+                    return Err(e);
+                }
+            }
+
+            if let Some(Ok(record)) = input.peek() {
+                let key = site_key(contigs, record)?;
+
+                if min_key.map(|k| key < k).unwrap_or(true) {
+                    min_key = Some(key);
+                }
+            }
+        }
+
+        let key = match min_key {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+
+        if self.last_key.map(|k| key < k).unwrap_or(false) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "input is not coordinate-sorted",
+            ));
+        }
+
+        self.last_key = Some(key);
+
+        let mut site_records: Vec<VecDeque<Record>> = vec![VecDeque::new(); self.inputs.len()];
+
+        for (input, records) in self.inputs.iter_mut().zip(&mut site_records) {
+            loop {
+                match input.peek() {
+                    Some(Ok(record)) if site_key(contigs, record)? == key => {
+                        if let Some(Ok(record)) = input.next() {
+                            records.push_back(record);
+                        }
+                    }
+                    Some(Err(_)) => {
+                        if let Some(Err(e)) = input.next() {
+                            return Err(e);
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        // Records are grouped by reference bases, in the order they first appear.
+        while let Some(reference_bases) = site_records
+            .iter()
+            .find_map(|records| records.front())
+            .map(|record| record.reference_bases().clone())
+        {
+            let group: Vec<_> = site_records
+                .iter_mut()
+                .map(|records| {
+                    records
+                        .iter()
+                        .position(|r| r.reference_bases() == &reference_bases)
+                        .and_then(|i| records.remove(i))
+                })
+                .collect();
+
+            let record = merge_records(&group, &self.sample_counts)?;
+            self.buf.push_back(record);
+        }
+
+        Ok(true)
+    }
+}
+
+impl<I> Iterator for Merger<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.buf.pop_front() {
+                return Some(Ok(record));
+            }
+
+            match self.fill_buf() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+fn site_key(contigs: &[Contig], record: &Record) -> io::Result<(usize, i64)> {
+    let name = record.chromosome().to_string();
+
+    let i = contigs
+        .iter()
+        .position(|contig| contig.id() == name)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("missing contig: {}", name),
+            )
+        })?;
+
+    Ok((i, record.position()))
+}
+
+fn merge_records(group: &[Option<Record>], sample_counts: &[usize]) -> io::Result<Record> {
+    let records: Vec<_> = group.iter().flatten().collect();
+
+    let first_record = records
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty site"))?;
+
+    let alternate_bases: Vec<_> = records.iter().map(|r| r.alternate_bases()).collect();
+    let (alternate_bases, allele_maps) = normalize::merge_alternate_bases(&alternate_bases);
+    let allele_count = alternate_bases.len() + 1;
+
+    let infos: Vec<_> = records.iter().map(|r| r.info()).collect();
+    let mut info = normalize::join_info(&infos, &allele_maps, allele_count);
+
+    let mut keys: Vec<Key> = Vec::new();
+
+    for format in records.iter().filter_map(|r| r.format()) {
+        for key in format.iter() {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+
+    let mut genotypes = Vec::new();
+
+    if !keys.is_empty() {
+        let mut allele_maps = allele_maps.iter();
+
+        for (record, &sample_count) in group.iter().zip(sample_counts) {
+            let allele_map = record.as_ref().and_then(|_| allele_maps.next());
+
+            for i in 0..sample_count {
+                let genotype = match (record, allele_map) {
+                    (Some(r), Some(allele_map)) => r.genotypes().get(i).map(|genotype| {
+                        normalize::join_genotypes(
+                            &[genotype],
+                            slice::from_ref(allele_map),
+                            allele_count,
+                        )
+                    }),
+                    _ => None,
+                }
+                .transpose()
+                .map_err(invalid_genotype)?;
+
+                genotypes.push(project_genotype(genotype.as_ref(), &keys)?);
+            }
+        }
+
+        let counts = AlleleCounts::from_genotypes(&genotypes, alternate_bases.len());
+        info = allele_counts::recompute_info(&info, &counts);
+    }
+
+    let mut record = Record::clone(first_record);
+
+    *record.alternate_bases_mut() = AlternateBases::from(alternate_bases);
+    *record.info_mut() = info;
+
+    if keys.is_empty() {
+        *record.format_mut() = None;
+    } else {
+        let format =
+            Format::try_from(keys).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        *record.format_mut() = Some(format);
+    }
+
+    *record.genotypes_mut() = genotypes;
+
+    Ok(record)
+}
+
+// Reorders the fields of a genotype to the given keys, filling missing fields.
+fn project_genotype(genotype: Option<&Genotype>, keys: &[Key]) -> io::Result<Genotype> {
+    let fields: Vec<_> = keys
+        .iter()
+        .map(|key| {
+            genotype
+                .and_then(|g| g.iter().find(|field| field.key() == key))
+                .cloned()
+                .unwrap_or_else(|| {
+                    let value = match key {
+                        Key::Genotype => Some(Value::String(String::from("./."))),
+                        _ => None,
+                    };
+
+                    genotype::Field::new(key.clone(), value)
+                })
+        })
+        .collect();
+
+    Genotype::try_from(fields).map_err(invalid_genotype)
+}
+
+fn invalid_genotype(e: genotype::TryFromFieldsError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::Contig;
+
+    use super::*;
+
+    fn build_header(sample_name: &str) -> Header {
+        Header::builder()
+            .add_contig(Contig::new(String::from("sq0")))
+            .add_contig(Contig::new(String::from("sq1")))
+            .add_sample_name(sample_name)
+            .build()
+    }
+
+    fn build_record(
+        chromosome: &str,
+        position: i64,
+        reference_bases: &str,
+        alternate_bases: &str,
+        genotype: &str,
+    ) -> io::Result<Record> {
+        let format: Format = "GT".parse().unwrap();
+
+        Record::builder()
+            .set_chromosome(chromosome.parse().unwrap())
+            .set_position(position)
+            .set_reference_bases(reference_bases.parse().unwrap())
+            .set_alternate_bases(alternate_bases.parse().unwrap())
+            .set_info("AC=1;AN=2".parse().unwrap())
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format(genotype, &format).unwrap())
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    #[test]
+    fn test_merge_headers() {
+        let headers = [build_header("sample0"), build_header("sample1")];
+        let header = merge_headers(&headers).unwrap();
+
+        assert_eq!(header.contigs().len(), 2);
+        assert_eq!(
+            header.sample_names(),
+            [String::from("sample0"), String::from("sample1")]
+        );
+
+        let headers = [build_header("sample0"), build_header("sample0")];
+
+        assert_eq!(
+            merge_headers(&headers),
+            Err(MergeHeadersError::DuplicateSample(String::from("sample0")))
+        );
+    }
+
+    #[test]
+    fn test_next() -> io::Result<()> {
+        let headers = [build_header("sample0"), build_header("sample1")];
+
+        let inputs = vec![
+            vec![
+                build_record("sq0", 1, "A", "C", "0/1"),
+                build_record("sq0", 5, "G", "T", "1/1"),
+            ]
+            .into_iter(),
+            vec![
+                build_record("sq0", 1, "A", "G", "1/1"),
+                build_record("sq1", 2, "C", "A", "0/1"),
+            ]
+            .into_iter(),
+        ];
+
+        let merger = Merger::new(&headers, inputs).unwrap();
+        let records = merger.collect::<io::Result<Vec<_>>>()?;
+
+        assert_eq!(records.len(), 3);
+
+        assert_eq!(records[0].position(), 1);
+        assert_eq!(records[0].alternate_bases().to_string(), "C,G");
+        assert_eq!(records[0].info().to_string(), "AC=1,2;AN=4");
+        assert_eq!(records[0].genotypes()[0].to_string(), "0/1");
+        assert_eq!(records[0].genotypes()[1].to_string(), "2/2");
+
+        assert_eq!(records[1].position(), 5);
+        assert_eq!(records[1].info().to_string(), "AC=2;AN=2");
+        assert_eq!(records[1].genotypes()[0].to_string(), "1/1");
+        assert_eq!(records[1].genotypes()[1].to_string(), "./.");
+
+        assert_eq!(records[2].chromosome().to_string(), "sq1");
+        assert_eq!(records[2].genotypes()[0].to_string(), "./.");
+        assert_eq!(records[2].genotypes()[1].to_string(), "0/1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unsorted_input() {
+        let headers = [build_header("sample0")];
+
+        let inputs = vec![vec![
+            build_record("sq1", 1, "A", "C", "0/1"),
+            build_record("sq0", 1, "A", "C", "0/1"),
+        ]
+        .into_iter()];
+
+        let merger = Merger::new(&headers, inputs).unwrap();
+        assert!(merger.collect::<io::Result<Vec<_>>>().is_err());
+    }
+}
@@ -0,0 +1,566 @@
+//! PED/FAM pedigrees and pedigree-aware genotype checks.
+//!
+//! A pedigree file has one individual per line with six whitespace-separated columns: family ID,
+//! individual ID, paternal ID, maternal ID, sex, and phenotype. Any further columns, e.g., the
+//! genotypes of a PED file, are ignored.
+
+use std::{
+    error, fmt,
+    io::{self, BufRead},
+    ops::Deref,
+    str::FromStr,
+};
+
+use super::{
+    record::genotype::phasing::{self, Alleles},
+    Header, Record,
+};
+
+const COMMENT_PREFIX: char = '#';
+const MISSING_PARENT: &str = "0";
+
+/// The sex of an individual.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sex {
+    /// Male (`1`).
+    Male,
+    /// Female (`2`).
+    Female,
+    /// Unknown (`0` or any other value).
+    Unknown,
+}
+
+impl From<&str> for Sex {
+    fn from(s: &str) -> Self {
+        match s {
+            "1" => Self::Male,
+            "2" => Self::Female,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// An individual in a pedigree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Individual {
+    family_id: String,
+    id: String,
+    paternal_id: Option<String>,
+    maternal_id: Option<String>,
+    sex: Sex,
+    phenotype: String,
+}
+
+impl Individual {
+    /// Returns the family ID.
+    pub fn family_id(&self) -> &str {
+        &self.family_id
+    }
+
+    /// Returns the individual ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the paternal ID.
+    ///
+    /// This is `None` if the father is not in the pedigree (`0`).
+    pub fn paternal_id(&self) -> Option<&str> {
+        self.paternal_id.as_deref()
+    }
+
+    /// Returns the maternal ID.
+    ///
+    /// This is `None` if the mother is not in the pedigree (`0`).
+    pub fn maternal_id(&self) -> Option<&str> {
+        self.maternal_id.as_deref()
+    }
+
+    /// Returns the sex.
+    pub fn sex(&self) -> Sex {
+        self.sex
+    }
+
+    /// Returns the raw phenotype.
+    pub fn phenotype(&self) -> &str {
+        &self.phenotype
+    }
+}
+
+/// An error returned when a raw pedigree individual fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The family ID is missing.
+    MissingFamilyId,
+    /// The individual ID is missing.
+    MissingId,
+    /// The paternal ID is missing.
+    MissingPaternalId,
+    /// The maternal ID is missing.
+    MissingMaternalId,
+    /// The sex is missing.
+    MissingSex,
+    /// The phenotype is missing.
+    MissingPhenotype,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFamilyId => f.write_str("missing family ID"),
+            Self::MissingId => f.write_str("missing individual ID"),
+            Self::MissingPaternalId => f.write_str("missing paternal ID"),
+            Self::MissingMaternalId => f.write_str("missing maternal ID"),
+            Self::MissingSex => f.write_str("missing sex"),
+            Self::MissingPhenotype => f.write_str("missing phenotype"),
+        }
+    }
+}
+
+impl FromStr for Individual {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+
+        let family_id = fields.next().ok_or(ParseError::MissingFamilyId)?;
+        let id = fields.next().ok_or(ParseError::MissingId)?;
+        let paternal_id = fields.next().ok_or(ParseError::MissingPaternalId)?;
+        let maternal_id = fields.next().ok_or(ParseError::MissingMaternalId)?;
+        let sex = fields.next().ok_or(ParseError::MissingSex)?;
+        let phenotype = fields.next().ok_or(ParseError::MissingPhenotype)?;
+
+        Ok(Self {
+            family_id: family_id.into(),
+            id: id.into(),
+            paternal_id: parse_parent_id(paternal_id),
+            maternal_id: parse_parent_id(maternal_id),
+            sex: Sex::from(sex),
+            phenotype: phenotype.into(),
+        })
+    }
+}
+
+fn parse_parent_id(s: &str) -> Option<String> {
+    match s {
+        MISSING_PARENT => None,
+        _ => Some(s.into()),
+    }
+}
+
+/// A pedigree.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pedigree(Vec<Individual>);
+
+impl Pedigree {
+    /// Reads a pedigree from a PED or FAM file.
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::pedigree::{Pedigree, Sex};
+    ///
+    /// let data = b"fam0 father 0 0 1 1\nfam0 mother 0 0 2 1\nfam0 child father mother 1 2\n";
+    /// let pedigree = Pedigree::read(&data[..])?;
+    ///
+    /// assert_eq!(pedigree.len(), 3);
+    /// assert_eq!(pedigree[2].paternal_id(), Some("father"));
+    /// assert_eq!(pedigree[2].sex(), Sex::Male);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn read<R>(reader: R) -> io::Result<Self>
+    where
+        R: BufRead,
+    {
+        let mut individuals = Vec::new();
+
+        for result in reader.lines() {
+            let line = result?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+
+            let individual: Individual = line
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            individuals.push(individual);
+        }
+
+        Ok(Self(individuals))
+    }
+
+    /// Returns the individual with the given ID.
+    pub fn get(&self, id: &str) -> Option<&Individual> {
+        self.iter().find(|individual| individual.id() == id)
+    }
+
+    /// Returns the trios in the pedigree, i.e., each individual whose father and mother are both
+    /// in the pedigree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::pedigree::Pedigree;
+    ///
+    /// let data = b"fam0 father 0 0 1 1\nfam0 mother 0 0 2 1\nfam0 child father mother 1 2\n";
+    /// let pedigree = Pedigree::read(&data[..])?;
+    ///
+    /// let trios = pedigree.trios();
+    /// assert_eq!(trios.len(), 1);
+    /// assert_eq!(trios[0].child().id(), "child");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn trios(&self) -> Vec<Trio<'_>> {
+        self.iter()
+            .filter_map(|child| {
+                let father = child.paternal_id().and_then(|id| self.get(id))?;
+                let mother = child.maternal_id().and_then(|id| self.get(id))?;
+
+                Some(Trio {
+                    child,
+                    father,
+                    mother,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Deref for Pedigree {
+    type Target = [Individual];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Individual>> for Pedigree {
+    fn from(individuals: Vec<Individual>) -> Self {
+        Self(individuals)
+    }
+}
+
+/// A child and its parents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Trio<'a> {
+    child: &'a Individual,
+    father: &'a Individual,
+    mother: &'a Individual,
+}
+
+impl<'a> Trio<'a> {
+    /// Returns the child.
+    pub fn child(&self) -> &'a Individual {
+        self.child
+    }
+
+    /// Returns the father.
+    pub fn father(&self) -> &'a Individual {
+        self.father
+    }
+
+    /// Returns the mother.
+    pub fn mother(&self) -> &'a Individual {
+        self.mother
+    }
+
+    /// Returns whether the genotypes of the trio in a record are a Mendelian violation.
+    ///
+    /// Samples are matched to individuals by ID. This returns `None` if any member of the trio is
+    /// not a sample in the header or the check is not applicable. See [`is_mendelian_violation`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, pedigree::Pedigree, record::{Format, Genotype}};
+    ///
+    /// let data = b"fam0 father 0 0 1 1\nfam0 mother 0 0 2 1\nfam0 child father mother 1 2\n";
+    /// let pedigree = Pedigree::read(&data[..])?;
+    /// let trios = pedigree.trios();
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("child")
+    ///     .add_sample_name("father")
+    ///     .add_sample_name("mother")
+    ///     .build();
+    ///
+    /// let format: Format = "GT".parse()?;
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_alternate_bases("G".parse()?)
+    ///     .set_format(format.clone())
+    ///     .add_genotype(Genotype::from_str_format("1/1", &format)?)
+    ///     .add_genotype(Genotype::from_str_format("0/1", &format)?)
+    ///     .add_genotype(Genotype::from_str_format("0/0", &format)?)
+    ///     .build()?;
+    ///
+    /// assert_eq!(trios[0].is_mendelian_violation(&header, &record)?, Some(true));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_mendelian_violation(
+        &self,
+        header: &Header,
+        record: &Record,
+    ) -> Result<Option<bool>, phasing::ParseError> {
+        let child = sample_alleles(header, record, self.child.id())?;
+        let father = sample_alleles(header, record, self.father.id())?;
+        let mother = sample_alleles(header, record, self.mother.id())?;
+
+        let chromosome = record.chromosome().to_string();
+
+        match (child, father, mother) {
+            (Some(child), Some(father), Some(mother)) => Ok(is_mendelian_violation(
+                &chromosome,
+                self.child.sex(),
+                &child,
+                &father,
+                &mother,
+            )),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn sample_alleles(
+    header: &Header,
+    record: &Record,
+    id: &str,
+) -> Result<Option<Alleles>, phasing::ParseError> {
+    header
+        .sample_names()
+        .iter()
+        .position(|name| name == id)
+        .and_then(|i| record.genotypes().get(i))
+        .and_then(|genotype| genotype.alleles())
+        .transpose()
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ChromosomeKind {
+    Autosome,
+    X,
+    Y,
+    Mitochondrion,
+}
+
+impl ChromosomeKind {
+    fn from_name(name: &str) -> Self {
+        let name = match name.get(..3) {
+            Some(prefix) if name.len() > 3 && prefix.eq_ignore_ascii_case("chr") => &name[3..],
+            _ => name,
+        };
+
+        match name.to_ascii_uppercase().as_str() {
+            "X" => Self::X,
+            "Y" => Self::Y,
+            "M" | "MT" => Self::Mitochondrion,
+            _ => Self::Autosome,
+        }
+    }
+}
+
+/// Returns the expected ploidy of a chromosome for an individual of the given sex.
+///
+/// Chromosome names are matched with an optional `chr` prefix, ignoring case: `X`, `Y`, and `M`
+/// or `MT` are the sex and mitochondrial chromosomes, and all other names are autosomes.
+/// Pseudoautosomal regions are not considered.
+///
+/// This returns `None` if the ploidy depends on an unknown sex.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::pedigree::{ploidy, Sex};
+///
+/// assert_eq!(ploidy("chr1", Sex::Male), Some(2));
+/// assert_eq!(ploidy("chrX", Sex::Male), Some(1));
+/// assert_eq!(ploidy("chrX", Sex::Female), Some(2));
+/// assert_eq!(ploidy("chrY", Sex::Female), Some(0));
+/// assert_eq!(ploidy("MT", Sex::Female), Some(1));
+/// assert_eq!(ploidy("X", Sex::Unknown), None);
+/// ```
+pub fn ploidy(chromosome: &str, sex: Sex) -> Option<usize> {
+    match (ChromosomeKind::from_name(chromosome), sex) {
+        (ChromosomeKind::Autosome, _) => Some(2),
+        (ChromosomeKind::Mitochondrion, _) => Some(1),
+        (ChromosomeKind::X, Sex::Male) | (ChromosomeKind::Y, Sex::Male) => Some(1),
+        (ChromosomeKind::X, Sex::Female) => Some(2),
+        (ChromosomeKind::Y, Sex::Female) => Some(0),
+        (_, Sex::Unknown) => None,
+    }
+}
+
+/// Returns whether the genotypes of a child and its parents are a Mendelian violation.
+///
+/// On autosomes and on the X chromosome of a female child, the diploid child must have one allele
+/// from each parent. On the X chromosome of a male child and on the mitochondrial chromosome,
+/// each child allele must be a maternal allele. On the Y chromosome of a male child, each child
+/// allele must be a paternal allele.
+///
+/// This returns `None` if a genotype that the check uses has a missing allele, the child is not
+/// diploid where it must be, or the check is not applicable, e.g., on the Y chromosome of a
+/// female child or on a sex chromosome of a child with an unknown sex.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::pedigree::{is_mendelian_violation, Sex};
+///
+/// let father = "0/1".parse()?;
+/// let mother = "0/0".parse()?;
+///
+/// assert_eq!(
+///     is_mendelian_violation("chr1", Sex::Male, &"0/1".parse()?, &father, &mother),
+///     Some(false)
+/// );
+///
+/// assert_eq!(
+///     is_mendelian_violation("chr1", Sex::Male, &"1/1".parse()?, &father, &mother),
+///     Some(true)
+/// );
+///
+/// assert_eq!(
+///     is_mendelian_violation("chrX", Sex::Male, &"1".parse()?, &father, &mother),
+///     Some(true)
+/// );
+/// # Ok::<(), noodles_vcf::record::genotype::phasing::ParseError>(())
+/// ```
+pub fn is_mendelian_violation(
+    chromosome: &str,
+    child_sex: Sex,
+    child: &Alleles,
+    father: &Alleles,
+    mother: &Alleles,
+) -> Option<bool> {
+    let child = allele_positions(child)?;
+
+    match (ChromosomeKind::from_name(chromosome), child_sex) {
+        (ChromosomeKind::Autosome, _) | (ChromosomeKind::X, Sex::Female) => {
+            let father = allele_positions(father)?;
+            let mother = allele_positions(mother)?;
+
+            if child.len() != 2 {
+                return None;
+            }
+
+            let is_inherited = (father.contains(&child[0]) && mother.contains(&child[1]))
+                || (father.contains(&child[1]) && mother.contains(&child[0]));
+
+            Some(!is_inherited)
+        }
+        (ChromosomeKind::X, Sex::Male) | (ChromosomeKind::Mitochondrion, _) => {
+            let mother = allele_positions(mother)?;
+            Some(!child.iter().all(|position| mother.contains(position)))
+        }
+        (ChromosomeKind::Y, Sex::Male) => {
+            let father = allele_positions(father)?;
+            Some(!child.iter().all(|position| father.contains(position)))
+        }
+        _ => None,
+    }
+}
+
+fn allele_positions(alleles: &Alleles) -> Option<Vec<usize>> {
+    alleles.iter().map(|allele| allele.position()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_for_individual() {
+        assert_eq!(
+            "fam0\tchild\tfather\t0\t2\t-9\tA\tG".parse(),
+            Ok(Individual {
+                family_id: String::from("fam0"),
+                id: String::from("child"),
+                paternal_id: Some(String::from("father")),
+                maternal_id: None,
+                sex: Sex::Female,
+                phenotype: String::from("-9"),
+            })
+        );
+
+        assert_eq!(
+            "fam0 child 0 0 0".parse::<Individual>(),
+            Err(ParseError::MissingPhenotype)
+        );
+        assert_eq!("".parse::<Individual>(), Err(ParseError::MissingFamilyId));
+    }
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        let data = b"# comment\nfam0 father 0 0 1 1\n\nfam0 child father mother 2 1\n";
+        let pedigree = Pedigree::read(&data[..])?;
+
+        assert_eq!(pedigree.len(), 2);
+        assert_eq!(pedigree[1].maternal_id(), Some("mother"));
+
+        // The mother is not in the pedigree.
+        assert!(pedigree.trios().is_empty());
+
+        assert!(Pedigree::read(&b"fam0 child\n"[..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chromosome_kind_from_name() {
+        assert_eq!(ChromosomeKind::from_name("1"), ChromosomeKind::Autosome);
+        assert_eq!(ChromosomeKind::from_name("chr"), ChromosomeKind::Autosome);
+        assert_eq!(ChromosomeKind::from_name("chrX"), ChromosomeKind::X);
+        assert_eq!(ChromosomeKind::from_name("CHRy"), ChromosomeKind::Y);
+        assert_eq!(
+            ChromosomeKind::from_name("chrM"),
+            ChromosomeKind::Mitochondrion
+        );
+        assert_eq!(
+            ChromosomeKind::from_name("MT"),
+            ChromosomeKind::Mitochondrion
+        );
+    }
+
+    #[test]
+    fn test_is_mendelian_violation() -> Result<(), phasing::ParseError> {
+        let check = |chromosome, child_sex, child: &str, father: &str, mother: &str| {
+            Ok::<_, phasing::ParseError>(is_mendelian_violation(
+                chromosome,
+                child_sex,
+                &child.parse()?,
+                &father.parse()?,
+                &mother.parse()?,
+            ))
+        };
+
+        assert_eq!(check("1", Sex::Male, "0/1", "0/0", "1/1")?, Some(false));
+        assert_eq!(check("1", Sex::Male, "1|0", "0/0", "1/1")?, Some(false));
+        assert_eq!(check("1", Sex::Male, "0/0", "0/0", "1/1")?, Some(true));
+        assert_eq!(check("1", Sex::Male, "0/1", "./0", "1/1")?, None);
+        assert_eq!(check("1", Sex::Male, "1", "0/1", "1/1")?, None);
+
+        assert_eq!(check("X", Sex::Female, "0/1", "1", "0/0")?, Some(false));
+        assert_eq!(check("X", Sex::Male, "1", "0", "0/1")?, Some(false));
+        assert_eq!(check("X", Sex::Male, "1", ".", "0/0")?, Some(true));
+        assert_eq!(check("X", Sex::Unknown, "1", "0", "0/1")?, None);
+
+        assert_eq!(check("Y", Sex::Male, "1", "1", "./.")?, Some(false));
+        assert_eq!(check("Y", Sex::Male, "1", "0", "./.")?, Some(true));
+        assert_eq!(check("Y", Sex::Female, "1", "0", "./.")?, None);
+
+        assert_eq!(check("MT", Sex::Male, "1", "0", "1")?, Some(false));
+        assert_eq!(check("MT", Sex::Male, "1", "1", "0")?, Some(true));
+
+        Ok(())
+    }
+}
@@ -7,6 +7,10 @@ pub use self::records::Records;
 use std::io::{self, BufRead, BufReader, Read, Seek};
 
 use noodles_bgzf as bgzf;
+use noodles_core::{
+    limits::{self, Limit, Limits},
+    progress::{self, ProgressObserver},
+};
 
 const LINE_FEED: char = '\n';
 const CARRIAGE_RETURN: char = '\r';
@@ -43,6 +47,8 @@ const HEADER_PREFIX: u8 = b'#';
 #[derive(Debug)]
 pub struct Reader<R> {
     inner: R,
+    progress: progress::Tracker,
+    limits: Limits,
 }
 
 impl<R> Reader<R>
@@ -64,7 +70,75 @@ where
     /// let reader = vcf::Reader::new(&data[..]);
     /// ```
     pub fn new(inner: R) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            progress: progress::Tracker::default(),
+            limits: Limits::default(),
+        }
+    }
+
+    /// Sets the limits on the header size and record line length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_core::limits::{Limit, LimitExceeded, Limits};
+    /// use noodles_vcf as vcf;
+    ///
+    /// let data = b"##fileformat=VCFv4.3
+    /// #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+    /// ";
+    ///
+    /// let mut reader = vcf::Reader::new(&data[..]);
+    /// reader.set_limits(Limits::default().set_max_header_size(32));
+    ///
+    /// let e = reader.read_header().unwrap_err();
+    ///
+    /// assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    /// assert_eq!(
+    ///     e.get_ref().and_then(|e| e.downcast_ref::<LimitExceeded>()),
+    ///     Some(&LimitExceeded::new(Limit::HeaderSize, 32))
+    /// );
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Sets an observer that is notified after each record is read.
+    ///
+    /// The reader does not know whether its input is compressed, so the reported compressed
+    /// position is always `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_core::progress::Progress;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let data = b"##fileformat=VCFv4.3
+    /// #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+    /// sq0\t1\t.\tA\t.\t.\tPASS\t.
+    /// ";
+    ///
+    /// let mut reader = vcf::Reader::new(&data[..]);
+    /// reader.set_progress_observer(|progress: Progress| {
+    ///     eprintln!("{} records read", progress.records_read());
+    /// });
+    ///
+    /// reader.read_header()?;
+    ///
+    /// for result in reader.records() {
+    ///     let _record = result?;
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_progress_observer<O>(&mut self, observer: O)
+    where
+        O: ProgressObserver + Send + 'static,
+    {
+        self.progress.set_observer(Box::new(observer));
     }
 
     /// Reads the raw VCF header.
@@ -105,7 +179,7 @@ where
                 Err(e) => return Err(e),
             };
 
-            if eol && !buf.is_empty() && buf[0] != HEADER_PREFIX {
+            if buf.is_empty() || (eol && buf[0] != HEADER_PREFIX) {
                 break;
             }
 
@@ -122,6 +196,12 @@ where
 
             eol = read_eol;
             self.inner.consume(len);
+
+            limits::check(
+                Limit::HeaderSize,
+                self.limits.max_header_size(),
+                header_buf.len(),
+            )?;
         }
 
         String::from_utf8(header_buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
@@ -162,7 +242,21 @@ where
     /// # Ok::<(), io::Error>(())
     /// ```
     pub fn read_record(&mut self, buf: &mut String) -> io::Result<usize> {
-        read_line(&mut self.inner, buf)
+        let n = match self.limits.max_line_length() {
+            Some(max_line_length) => {
+                let mut reader = self.inner.by_ref().take(max_line_length as u64 + 1);
+                let n = read_line(&mut reader, buf)?;
+                limits::check(Limit::LineLength, Some(max_line_length), n)?;
+                n
+            }
+            None => read_line(&mut self.inner, buf)?,
+        };
+
+        if n > 0 {
+            self.progress.record_read(n, None);
+        }
+
+        Ok(n)
     }
 
     /// Returns an iterator over records starting from the current stream position.
@@ -302,6 +396,17 @@ sq0\t13
         Ok(())
     }
 
+    #[test]
+    fn test_read_header_with_no_records() -> io::Result<()> {
+        let data = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+        let mut reader = Reader::new(&data[..]);
+
+        let actual = reader.read_header()?;
+        assert_eq!(actual.as_bytes(), &data[..]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_record() -> io::Result<()> {
         let mut reader = Reader::new(DATA);
@@ -325,6 +430,22 @@ sq0\t13
         Ok(())
     }
 
+    #[test]
+    fn test_read_record_with_max_line_length() -> io::Result<()> {
+        let mut reader = Reader::new(DATA);
+        reader.set_limits(Limits::default().set_max_line_length(6));
+        reader.read_header()?;
+
+        let mut buf = String::new();
+        assert_eq!(reader.read_record(&mut buf)?, 6);
+
+        buf.clear();
+        let e = reader.read_record(&mut buf).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_line() -> io::Result<()> {
         let mut buf = String::new();
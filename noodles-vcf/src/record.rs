@@ -1,5 +1,7 @@
 //! VCF record and fields.
 
+pub mod allele_counts;
+pub mod allele_match;
 pub mod alternate_bases;
 pub mod builder;
 pub mod chromosome;
@@ -9,17 +11,29 @@ pub mod format;
 pub mod genotype;
 pub mod ids;
 pub mod info;
+pub mod normalize;
 pub mod quality_score;
 pub mod reference_bases;
+pub mod structural_variant;
 pub(crate) mod value;
 
 pub use self::{
     alternate_bases::AlternateBases, builder::Builder, chromosome::Chromosome, field::Field,
     filter_status::FilterStatus, format::Format, genotype::Genotype, ids::Ids, info::Info,
-    quality_score::QualityScore, reference_bases::ReferenceBases,
+    normalize::normalize, quality_score::QualityScore, reference_bases::ReferenceBases,
 };
 
-use std::{error, fmt, num, str::FromStr};
+use std::{
+    error, fmt,
+    io::{self, Write},
+    num,
+    str::{self, FromStr},
+};
+
+use noodles_core::{
+    codec::{Decode, Encode},
+    scan,
+};
 
 pub(crate) const MISSING_FIELD: &str = ".";
 pub(crate) const FIELD_DELIMITER: char = '\t';
@@ -42,7 +56,7 @@ pub(crate) const FIELD_DELIMITER: char = '\t';
 #[derive(Clone, Debug, PartialEq)]
 pub struct Record {
     chromosome: Chromosome,
-    position: i32,
+    position: i64,
     ids: Ids,
     reference_bases: ReferenceBases,
     alternate_bases: AlternateBases,
@@ -110,10 +124,80 @@ impl Record {
     /// assert_eq!(record.position(), 8);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn position(&self) -> i32 {
+    pub fn position(&self) -> i64 {
         self.position
     }
 
+    /// Returns the end position of the record.
+    ///
+    /// VCF positions are 1-based, and the end position is inclusive. The end position is, in
+    /// order of precedence,
+    ///
+    ///   1. the information field end position (`END`);
+    ///   2. for a record with a symbolic deletion, duplication, inversion, or copy number
+    ///      variation allele, the start position plus the largest absolute SV length (`SVLEN`);
+    ///      or
+    ///   3. the position of the last reference base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf as vcf;
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(8)
+    ///     .set_reference_bases("ACGT".parse()?)
+    ///     .build()?;
+    ///
+    /// assert_eq!(record.end(), 11);
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(8)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_alternate_bases("<DEL>".parse()?)
+    ///     .set_info("SVLEN=-5".parse()?)
+    ///     .build()?;
+    ///
+    /// assert_eq!(record.end(), 13);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn end(&self) -> i64 {
+        let end_position = self
+            .info()
+            .iter()
+            .find(|field| field.key() == &info::field::Key::EndPosition)
+            .and_then(|field| match field.value() {
+                info::field::Value::Integer(n) => Some(i64::from(*n)),
+                _ => None,
+            });
+
+        if let Some(end) = end_position {
+            return end;
+        }
+
+        if self.alternate_bases().iter().any(is_spanning_symbol) {
+            let sv_len = self
+                .info()
+                .iter()
+                .find(|field| field.key() == &info::field::Key::SvLengths)
+                .and_then(|field| match field.value() {
+                    info::field::Value::Integer(n) => Some(i64::from(*n).abs()),
+                    info::field::Value::IntegerArray(values) => {
+                        values.iter().map(|&n| i64::from(n).abs()).max()
+                    }
+                    _ => None,
+                });
+
+            if let Some(len) = sv_len {
+                return self.position() + len;
+            }
+        }
+
+        self.position() + self.reference_bases().len() as i64 - 1
+    }
+
     /// Returns a list of IDs of the record.
     ///
     /// # Examples
@@ -135,6 +219,27 @@ impl Record {
         &self.ids
     }
 
+    /// Returns a mutable reference to the IDs of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(8)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// *record.ids_mut() = "nd0".parse()?;
+    /// assert_eq!(**record.ids(), [String::from("nd0")]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn ids_mut(&mut self) -> &mut Ids {
+        &mut self.ids
+    }
+
     /// Returns the reference bases of the record.
     ///
     /// This is a required field and guaranteed to be nonempty.
@@ -188,6 +293,27 @@ impl Record {
         &self.alternate_bases
     }
 
+    /// Returns a mutable reference to the alternate bases of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// *record.alternate_bases_mut() = "C".parse()?;
+    /// assert_eq!(record.alternate_bases().to_string(), "C");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn alternate_bases_mut(&mut self) -> &mut AlternateBases {
+        &mut self.alternate_bases
+    }
+
     /// Returns the quality score of the record.
     ///
     /// The quality score is a [Phred quality score].
@@ -238,6 +364,28 @@ impl Record {
         &self.filter_status
     }
 
+    /// Returns a mutable reference to the filter status of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::FilterStatus};
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// *record.filter_status_mut() = FilterStatus::Pass;
+    ///
+    /// assert_eq!(record.filter_status(), &FilterStatus::Pass);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn filter_status_mut(&mut self) -> &mut FilterStatus {
+        &mut self.filter_status
+    }
+
     /// Returns the addition information of the record.
     ///
     /// # Examples
@@ -266,6 +414,29 @@ impl Record {
         &self.info
     }
 
+    /// Returns a mutable reference to the information fields of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::{info::{field::{Key, Value}, Field}, Info}};
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// let info = Info::from(vec![Field::new(Key::SamplesWithDataCount, Value::Integer(2))]);
+    /// *record.info_mut() = info.clone();
+    ///
+    /// assert_eq!(record.info(), &info);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn info_mut(&mut self) -> &mut Info {
+        &mut self.info
+    }
+
     /// Returns the format of the genotypes of the record.
     ///
     /// # Examples
@@ -297,6 +468,29 @@ impl Record {
         self.format.as_ref()
     }
 
+    /// Returns a mutable reference to the format of the genotypes of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::Format};
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// let format: Format = "GT".parse()?;
+    /// *record.format_mut() = Some(format.clone());
+    ///
+    /// assert_eq!(record.format(), Some(&format));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn format_mut(&mut self) -> &mut Option<Format> {
+        &mut self.format
+    }
+
     /// Returns the genotypes of the record.
     ///
     /// # Examples
@@ -329,6 +523,83 @@ impl Record {
     pub fn genotypes(&self) -> &[Genotype] {
         &self.genotypes
     }
+
+    /// Returns a mutable reference to the genotypes of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::{Format, Genotype}};
+    ///
+    /// let format: Format = "GT".parse()?;
+    ///
+    /// let mut record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_format(format.clone())
+    ///     .add_genotype(Genotype::from_str_format("0|0", &format)?)
+    ///     .build()?;
+    ///
+    /// record.genotypes_mut().clear();
+    /// assert!(record.genotypes().is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn genotypes_mut(&mut self) -> &mut Vec<Genotype> {
+        &mut self.genotypes
+    }
+}
+
+impl scan::Locus for Record {
+    fn interval(&self) -> io::Result<Option<(i64, i64)>> {
+        Ok(Some((self.position(), self.end())))
+    }
+}
+
+impl Encode for Record {
+    fn encode(&self, dst: &mut Vec<u8>) -> io::Result<()> {
+        write_record(dst, self)
+    }
+}
+
+impl Decode for Record {
+    fn decode(src: &[u8]) -> io::Result<Self> {
+        str::from_utf8(src)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|s| {
+                s.parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+    }
+}
+
+// Writes a record as a VCF line without the line terminator.
+pub(crate) fn write_record<W>(writer: &mut W, record: &Record) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(
+        writer,
+        "{chrom}\t{pos}\t{id}\t{ref}\t{alt}\t{qual}\t{filter}\t{info}",
+        chrom = record.chromosome(),
+        pos = record.position(),
+        id = record.ids(),
+        r#ref = record.reference_bases(),
+        alt = record.alternate_bases(),
+        qual = record.quality_score(),
+        filter = record.filter_status(),
+        info = record.info(),
+    )?;
+
+    if let Some(format) = record.format() {
+        write!(writer, "\t{}", format)?;
+
+        for field in record.genotypes() {
+            write!(writer, "\t{}", field)?;
+        }
+    }
+
+    Ok(())
 }
 
 /// An error returned when a raw VCF record fails to parse.
@@ -438,6 +709,19 @@ impl FromStr for Record {
     }
 }
 
+// Returns whether the allele is a symbolic structural variant that spans reference bases.
+fn is_spanning_symbol(allele: &alternate_bases::Allele) -> bool {
+    use alternate_bases::allele::{symbol::structural_variant::Type, Symbol};
+
+    match allele {
+        alternate_bases::Allele::Symbol(Symbol::StructuralVariant(sv)) => matches!(
+            sv.ty(),
+            Type::Deletion | Type::Duplication | Type::Inversion | Type::CopyNumberVariation
+        ),
+        _ => false,
+    }
+}
+
 fn parse_string<'a, I>(fields: &mut I, field: Field) -> Result<&'a str, ParseError>
 where
     I: Iterator<Item = &'a str>,
@@ -477,6 +761,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_encode_and_decode() -> io::Result<()> {
+        let src = b"chr1\t13\tnd0\tATCG\tA\t5.8\tPASS\tSVTYPE=DEL\tGT\t0|1";
+        let record = Record::decode(src)?;
+
+        let mut buf = Vec::new();
+        record.encode(&mut buf)?;
+        assert_eq!(buf, &src[..]);
+
+        assert_eq!(
+            Record::decode(b"chr1\t13").map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_from_str_with_genotype_info() -> Result<(), ParseError> {
         let s = "chr1\t13\tnd0\tATCG\tA\t5.8\tPASS\tSVTYPE=DEL\tGT:GQ\t0|1:13";
@@ -506,4 +807,32 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_from_str_with_large_position() -> Result<(), ParseError> {
+        let s = "chr1\t4294967296\t.\tA\t.\t.\t.\t.";
+        let record: Record = s.parse()?;
+        assert_eq!(record.position(), 4294967296);
+        Ok(())
+    }
+
+    #[test]
+    fn test_end() -> Result<(), ParseError> {
+        let record: Record = "sq0\t8\t.\tACGT\tA\t.\tPASS\t.".parse()?;
+        assert_eq!(record.end(), 11);
+
+        let record: Record = "sq0\t8\t.\tA\t<DEL>\t.\tPASS\tEND=21;SVLEN=-5".parse()?;
+        assert_eq!(record.end(), 21);
+
+        let record: Record = "sq0\t8\t.\tA\t<DUP>,<DEL>\t.\tPASS\tSVLEN=3,-5".parse()?;
+        assert_eq!(record.end(), 13);
+
+        let record: Record = "sq0\t8\t.\tA\t<INS>\t.\tPASS\tSVLEN=5".parse()?;
+        assert_eq!(record.end(), 8);
+
+        let record: Record = "sq0\t8\t.\tA\t<DEL>\t.\tPASS\t.".parse()?;
+        assert_eq!(record.end(), 8);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,479 @@
+//! VCF record allele counts.
+
+use crate::header::{info::Type, Number};
+
+use super::{
+    genotype::{self, field::Key as GenotypeKey},
+    info::{
+        self,
+        field::{Key, Value},
+    },
+    Genotype, Info, Record,
+};
+
+/// The key of the minor allele frequency info field (`MAF`).
+pub const MINOR_ALLELE_FREQUENCY: &str = "MAF";
+
+/// The key of the call rate info field (`CR`).
+pub const CALL_RATE: &str = "CR";
+
+/// Allele counts computed from the genotypes of a VCF record.
+///
+/// Missing alleles (`.`) are not counted, and each genotype contributes as many alleles as its
+/// ploidy.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AlleleCounts {
+    allele_number: usize,
+    allele_counts: Vec<usize>,
+    sample_count: usize,
+    called_sample_count: usize,
+}
+
+impl AlleleCounts {
+    /// Counts the alleles called by the genotype (`GT`) fields of the given genotypes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{allele_counts::AlleleCounts, Format, Genotype};
+    ///
+    /// let format: Format = "GT".parse()?;
+    /// let genotypes = [
+    ///     Genotype::from_str_format("0|1", &format)?,
+    ///     Genotype::from_str_format("1", &format)?,
+    ///     Genotype::from_str_format("./.", &format)?,
+    /// ];
+    ///
+    /// let allele_counts = AlleleCounts::from_genotypes(&genotypes, 1);
+    ///
+    /// assert_eq!(allele_counts.allele_number(), 3);
+    /// assert_eq!(allele_counts.allele_counts(), [2]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_genotypes(genotypes: &[Genotype], alternate_bases_count: usize) -> Self {
+        let mut allele_counts = Self {
+            allele_number: 0,
+            allele_counts: vec![0; alternate_bases_count],
+            sample_count: genotypes.len(),
+            called_sample_count: 0,
+        };
+
+        for genotype in genotypes {
+            let alleles = match genotype_alleles(genotype) {
+                Some(alleles) => alleles,
+                None => continue,
+            };
+
+            let mut is_called = !alleles.is_empty();
+
+            for allele in alleles {
+                match allele {
+                    Some(i) => {
+                        allele_counts.allele_number += 1;
+
+                        if i > 0 {
+                            if let Some(n) = allele_counts.allele_counts.get_mut(i - 1) {
+                                *n += 1;
+                            }
+                        }
+                    }
+                    None => is_called = false,
+                }
+            }
+
+            if is_called {
+                allele_counts.called_sample_count += 1;
+            }
+        }
+
+        allele_counts
+    }
+
+    /// Returns the total number of called alleles (`AN`).
+    pub fn allele_number(&self) -> usize {
+        self.allele_number
+    }
+
+    /// Returns the number of each alternate allele (`AC`).
+    pub fn allele_counts(&self) -> &[usize] {
+        &self.allele_counts
+    }
+
+    /// Returns the frequency of each alternate allele (`AF`).
+    ///
+    /// This returns `None` if no alleles are called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{allele_counts::AlleleCounts, Format, Genotype};
+    ///
+    /// let format: Format = "GT".parse()?;
+    /// let genotypes = [Genotype::from_str_format("0|1", &format)?];
+    ///
+    /// let allele_counts = AlleleCounts::from_genotypes(&genotypes, 1);
+    /// assert_eq!(allele_counts.allele_frequencies(), Some(vec![0.5]));
+    ///
+    /// let allele_counts = AlleleCounts::from_genotypes(&[], 1);
+    /// assert!(allele_counts.allele_frequencies().is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn allele_frequencies(&self) -> Option<Vec<f32>> {
+        if self.allele_number == 0 {
+            return None;
+        }
+
+        let allele_number = self.allele_number as f32;
+
+        Some(
+            self.allele_counts
+                .iter()
+                .map(|&n| n as f32 / allele_number)
+                .collect(),
+        )
+    }
+
+    /// Returns the frequency of the second most frequent allele, including the reference allele.
+    ///
+    /// This returns `None` if no alleles are called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{allele_counts::AlleleCounts, Format, Genotype};
+    ///
+    /// let format: Format = "GT".parse()?;
+    /// let genotypes = [
+    ///     Genotype::from_str_format("1|1", &format)?,
+    ///     Genotype::from_str_format("0|1", &format)?,
+    /// ];
+    ///
+    /// let allele_counts = AlleleCounts::from_genotypes(&genotypes, 1);
+    /// assert_eq!(allele_counts.minor_allele_frequency(), Some(0.25));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn minor_allele_frequency(&self) -> Option<f32> {
+        if self.allele_number == 0 {
+            return None;
+        }
+
+        let alternate_allele_count: usize = self.allele_counts.iter().sum();
+        let reference_allele_count = self.allele_number.saturating_sub(alternate_allele_count);
+
+        let mut counts: Vec<_> = self.allele_counts.clone();
+        counts.push(reference_allele_count);
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        let minor_allele_count = counts.get(1).copied().unwrap_or_default();
+
+        Some(minor_allele_count as f32 / self.allele_number as f32)
+    }
+
+    /// Returns the fraction of samples with fully called genotypes.
+    ///
+    /// This returns `None` if there are no samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{allele_counts::AlleleCounts, Format, Genotype};
+    ///
+    /// let format: Format = "GT".parse()?;
+    /// let genotypes = [
+    ///     Genotype::from_str_format("0|1", &format)?,
+    ///     Genotype::from_str_format("./1", &format)?,
+    /// ];
+    ///
+    /// let allele_counts = AlleleCounts::from_genotypes(&genotypes, 1);
+    /// assert_eq!(allele_counts.call_rate(), Some(0.5));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn call_rate(&self) -> Option<f32> {
+        if self.sample_count == 0 {
+            None
+        } else {
+            Some(self.called_sample_count as f32 / self.sample_count as f32)
+        }
+    }
+}
+
+/// Options for updating the allele count information fields of a VCF record.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UpdateOptions {
+    minor_allele_frequency: bool,
+    call_rate: bool,
+}
+
+impl UpdateOptions {
+    /// Returns whether the minor allele frequency (`MAF`) is set.
+    pub fn minor_allele_frequency(&self) -> bool {
+        self.minor_allele_frequency
+    }
+
+    /// Sets whether to set the minor allele frequency (`MAF`).
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::allele_counts::UpdateOptions;
+    /// let options = UpdateOptions::default().set_minor_allele_frequency(true);
+    /// assert!(options.minor_allele_frequency());
+    /// ```
+    pub fn set_minor_allele_frequency(mut self, minor_allele_frequency: bool) -> Self {
+        self.minor_allele_frequency = minor_allele_frequency;
+        self
+    }
+
+    /// Returns whether the call rate (`CR`) is set.
+    pub fn call_rate(&self) -> bool {
+        self.call_rate
+    }
+
+    /// Sets whether to set the call rate (`CR`).
+    ///
+    /// By default, this is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::allele_counts::UpdateOptions;
+    /// let options = UpdateOptions::default().set_call_rate(true);
+    /// assert!(options.call_rate());
+    /// ```
+    pub fn set_call_rate(mut self, call_rate: bool) -> Self {
+        self.call_rate = call_rate;
+        self
+    }
+}
+
+/// Recomputes and sets the allele count information fields of a VCF record from its genotypes.
+///
+/// This sets the allele count (`AC`), total allele count (`AN`), and allele frequencies (`AF`),
+/// replacing existing fields or appending new ones. Allele frequencies are removed when there are
+/// no called alleles.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{
+///     self as vcf,
+///     record::{allele_counts::{self, UpdateOptions}, Format, Genotype},
+/// };
+///
+/// let format: Format = "GT".parse()?;
+///
+/// let mut record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .set_alternate_bases("C".parse()?)
+///     .set_info("NS=2;AC=3".parse()?)
+///     .set_format(format.clone())
+///     .add_genotype(Genotype::from_str_format("0|1", &format)?)
+///     .add_genotype(Genotype::from_str_format("./.", &format)?)
+///     .build()?;
+///
+/// allele_counts::update(&mut record, &UpdateOptions::default());
+/// assert_eq!(record.info().to_string(), "NS=2;AC=1;AN=2;AF=0.5");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn update(record: &mut Record, options: &UpdateOptions) {
+    let allele_counts =
+        AlleleCounts::from_genotypes(record.genotypes(), record.alternate_bases().len());
+
+    let mut fields = record.info().to_vec();
+
+    set_field(
+        &mut fields,
+        Key::AlleleCount,
+        Some(Value::IntegerArray(
+            allele_counts
+                .allele_counts()
+                .iter()
+                .map(|&n| n as i32)
+                .collect(),
+        )),
+    );
+
+    set_field(
+        &mut fields,
+        Key::TotalAlleleCount,
+        Some(Value::Integer(allele_counts.allele_number() as i32)),
+    );
+
+    set_field(
+        &mut fields,
+        Key::AlleleFrequencies,
+        allele_counts.allele_frequencies().map(Value::FloatArray),
+    );
+
+    if options.minor_allele_frequency() {
+        set_field(
+            &mut fields,
+            Key::Other(MINOR_ALLELE_FREQUENCY.into(), Number::Count(1), Type::Float),
+            allele_counts.minor_allele_frequency().map(Value::Float),
+        );
+    }
+
+    if options.call_rate() {
+        set_field(
+            &mut fields,
+            Key::Other(CALL_RATE.into(), Number::Count(1), Type::Float),
+            allele_counts.call_rate().map(Value::Float),
+        );
+    }
+
+    *record.info_mut() = Info::from(fields);
+}
+
+/// Recomputes the existing allele count information fields (`AC`, `AN`, and `AF`) of the given
+/// information using the given allele counts.
+///
+/// Fields that are not already present are not added. Allele frequencies are removed when there
+/// are no called alleles.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::record::{allele_counts::{self, AlleleCounts}, Format, Genotype, Info};
+///
+/// let format: Format = "GT".parse()?;
+/// let genotypes = [Genotype::from_str_format("1|1", &format)?];
+/// let allele_counts = AlleleCounts::from_genotypes(&genotypes, 1);
+///
+/// let info: Info = "AN=4;AF=0.25".parse()?;
+/// let info = allele_counts::recompute_info(&info, &allele_counts);
+///
+/// assert_eq!(info.to_string(), "AN=2;AF=1");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn recompute_info(info: &Info, allele_counts: &AlleleCounts) -> Info {
+    let fields: Vec<_> = info
+        .iter()
+        .filter_map(|field| {
+            let value = match field.key() {
+                Key::AlleleCount => Value::IntegerArray(
+                    allele_counts
+                        .allele_counts()
+                        .iter()
+                        .map(|&n| n as i32)
+                        .collect(),
+                ),
+                Key::TotalAlleleCount => Value::Integer(allele_counts.allele_number() as i32),
+                Key::AlleleFrequencies => Value::FloatArray(allele_counts.allele_frequencies()?),
+                _ => return Some(field.clone()),
+            };
+
+            Some(info::Field::new(field.key().clone(), value))
+        })
+        .collect();
+
+    Info::from(fields)
+}
+
+fn set_field(fields: &mut Vec<info::Field>, key: Key, value: Option<Value>) {
+    let i = fields.iter().position(|field| field.key() == &key);
+
+    match (i, value) {
+        (Some(i), Some(value)) => fields[i] = info::Field::new(key, value),
+        (Some(i), None) => {
+            fields.remove(i);
+        }
+        (None, Some(value)) => fields.push(info::Field::new(key, value)),
+        (None, None) => {}
+    }
+}
+
+// Returns the allele indices of the genotype (`GT`) field, if present.
+fn genotype_alleles(genotype: &Genotype) -> Option<Vec<Option<usize>>> {
+    use genotype::field::Value;
+
+    let field = genotype
+        .first()
+        .filter(|field| field.key() == &GenotypeKey::Genotype)?;
+
+    match field.value() {
+        Some(Value::String(s)) => Some(
+            s.split(['/', '|'])
+                .map(|allele| allele.parse().ok())
+                .collect(),
+        ),
+        _ => Some(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::Format;
+
+    use super::*;
+
+    fn build_genotypes() -> Result<Vec<Genotype>, Box<dyn std::error::Error>> {
+        let format: Format = "GT".parse()?;
+
+        let genotypes = vec![
+            Genotype::from_str_format("0/1", &format)?,
+            Genotype::from_str_format("2/2", &format)?,
+            Genotype::from_str_format("1", &format)?,
+            Genotype::from_str_format("./0", &format)?,
+            Genotype::from_str_format(".", &format)?,
+        ];
+
+        Ok(genotypes)
+    }
+
+    #[test]
+    fn test_from_genotypes() -> Result<(), Box<dyn std::error::Error>> {
+        let genotypes = build_genotypes()?;
+        let allele_counts = AlleleCounts::from_genotypes(&genotypes, 2);
+
+        assert_eq!(allele_counts.allele_number(), 6);
+        assert_eq!(allele_counts.allele_counts(), [2, 2]);
+        assert_eq!(
+            allele_counts.allele_frequencies(),
+            Some(vec![2.0 / 6.0, 2.0 / 6.0])
+        );
+        assert_eq!(allele_counts.minor_allele_frequency(), Some(2.0 / 6.0));
+        assert_eq!(allele_counts.call_rate(), Some(0.6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_genotypes_with_no_genotypes() {
+        let allele_counts = AlleleCounts::from_genotypes(&[], 1);
+
+        assert_eq!(allele_counts.allele_number(), 0);
+        assert_eq!(allele_counts.allele_counts(), [0]);
+        assert!(allele_counts.allele_frequencies().is_none());
+        assert!(allele_counts.minor_allele_frequency().is_none());
+        assert!(allele_counts.call_rate().is_none());
+    }
+
+    #[test]
+    fn test_update() -> Result<(), Box<dyn std::error::Error>> {
+        let format: Format = "GT".parse()?;
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(1)
+            .set_reference_bases("A".parse()?)
+            .set_alternate_bases("C".parse()?)
+            .set_info("AF=0.5".parse()?)
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format("./.", &format)?)
+            .build()?;
+
+        let options = UpdateOptions::default()
+            .set_minor_allele_frequency(true)
+            .set_call_rate(true);
+
+        update(&mut record, &options);
+
+        assert_eq!(record.info().to_string(), "AC=0;AN=0;CR=0");
+
+        Ok(())
+    }
+}
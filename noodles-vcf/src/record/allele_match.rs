@@ -0,0 +1,310 @@
+//! VCF record allele matching.
+//!
+//! Records are compared by their decomposed variants: each alternate allele is paired with the
+//! reference bases, and the bases shared by both alleles are trimmed. This makes, e.g., `ACT>AGT`
+//! at position 1 and `C>G` at position 2 the same variant. Alleles are not left-aligned; records
+//! should first be [normalized](super::normalize::normalize) when comparing indels in repeats.
+//!
+//! Symbolic alleles and breakends are not trimmed. Symbolic alleles match when their symbols are
+//! equal, and breakends match when their raw values are equal, ignoring case.
+
+use std::cmp;
+
+use super::{alternate_bases::Allele, reference_bases::Base, Chromosome, Record};
+
+/// The category of a match between two variants.
+///
+/// Categories are ordered from the weakest to the strongest match.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Match {
+    /// The variants are at different sites.
+    Mismatch,
+    /// The variants are at the same site but have different alleles.
+    PositionOnly,
+    /// The reference and alternate alleles of one variant are the alternate and reference
+    /// alleles of the other.
+    AlleleSwap,
+    /// The variants have the same site and alleles.
+    Exact,
+}
+
+/// A biallelic variant decomposed from a VCF record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Variant {
+    chromosome: Chromosome,
+    position: i64,
+    reference_bases: Vec<Base>,
+    alternate_allele: Allele,
+}
+
+impl Variant {
+    /// Returns the chromosome.
+    pub fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    /// Returns the start position of the trimmed alleles.
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns the trimmed reference bases.
+    pub fn reference_bases(&self) -> &[Base] {
+        &self.reference_bases
+    }
+
+    /// Returns the trimmed alternate allele.
+    pub fn alternate_allele(&self) -> &Allele {
+        &self.alternate_allele
+    }
+
+    /// Compares this variant to another variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::allele_match::{decompose, Match}};
+    ///
+    /// let a = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_alternate_bases("G".parse()?)
+    ///     .build()?;
+    ///
+    /// let b = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(1)
+    ///     .set_reference_bases("G".parse()?)
+    ///     .set_alternate_bases("A".parse()?)
+    ///     .build()?;
+    ///
+    /// assert_eq!(decompose(&a)[0].compare(&decompose(&b)[0]), Match::AlleleSwap);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn compare(&self, other: &Self) -> Match {
+        if self.chromosome != other.chromosome || self.position != other.position {
+            return Match::Mismatch;
+        }
+
+        match (&self.alternate_allele, &other.alternate_allele) {
+            (Allele::Bases(a), Allele::Bases(b)) => {
+                if self.reference_bases == other.reference_bases && a == b {
+                    Match::Exact
+                } else if &self.reference_bases == b && a == &other.reference_bases {
+                    Match::AlleleSwap
+                } else {
+                    Match::PositionOnly
+                }
+            }
+            (Allele::Symbol(a), Allele::Symbol(b)) if a == b => Match::Exact,
+            (Allele::Breakend(a), Allele::Breakend(b)) if a.eq_ignore_ascii_case(b) => Match::Exact,
+            _ => Match::PositionOnly,
+        }
+    }
+}
+
+/// Decomposes a VCF record into biallelic variants, one for each alternate allele.
+///
+/// Bases shared by the reference and alternate alleles are trimmed, first from the end and then
+/// from the start, leaving at least one base in each allele. Overlapping deletions (`*`) are
+/// skipped.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::allele_match::decompose};
+///
+/// let record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("ACT".parse()?)
+///     .set_alternate_bases("AGT,A".parse()?)
+///     .build()?;
+///
+/// let variants = decompose(&record);
+/// assert_eq!(variants.len(), 2);
+///
+/// assert_eq!(variants[0].position(), 2);
+/// assert_eq!(variants[0].alternate_allele().to_string(), "G");
+///
+/// assert_eq!(variants[1].position(), 1);
+/// assert_eq!(variants[1].alternate_allele().to_string(), "A");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn decompose(record: &Record) -> Vec<Variant> {
+    record
+        .alternate_bases()
+        .iter()
+        .filter(|allele| !matches!(allele, Allele::OverlappingDeletion))
+        .map(|allele| {
+            let mut position = record.position();
+            let mut reference_bases = record.reference_bases().to_vec();
+
+            let alternate_allele = match allele {
+                Allele::Bases(bases) => {
+                    let mut bases = bases.clone();
+                    position += trim(&mut reference_bases, &mut bases) as i64;
+                    Allele::Bases(bases)
+                }
+                _ => allele.clone(),
+            };
+
+            Variant {
+                chromosome: record.chromosome().clone(),
+                position,
+                reference_bases,
+                alternate_allele,
+            }
+        })
+        .collect()
+}
+
+/// Compares the alleles of two VCF records.
+///
+/// The records are decomposed, and the strongest match between any pair of variants is
+/// returned.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::allele_match::{self, Match}};
+///
+/// let a = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("AT".parse()?)
+///     .set_alternate_bases("C,ATT".parse()?)
+///     .build()?;
+///
+/// let b = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .set_alternate_bases("AT".parse()?)
+///     .build()?;
+///
+/// assert_eq!(allele_match::compare(&a, &b), Match::Exact);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn compare(a: &Record, b: &Record) -> Match {
+    let b_variants = decompose(b);
+
+    decompose(a)
+        .iter()
+        .flat_map(|u| b_variants.iter().map(move |v| u.compare(v)))
+        .max()
+        .unwrap_or(Match::Mismatch)
+}
+
+// Trims shared bases and returns the number of bases trimmed from the start.
+fn trim(a: &mut Vec<Base>, b: &mut Vec<Base>) -> usize {
+    while a.len() > 1 && b.len() > 1 && a.last() == b.last() {
+        a.pop();
+        b.pop();
+    }
+
+    let n = a
+        .iter()
+        .zip(b.iter())
+        .take(cmp::min(a.len(), b.len()) - 1)
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    a.drain(..n);
+    b.drain(..n);
+
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_record(position: i64, reference_bases: &str, alternate_bases: &str) -> Record {
+        Record::builder()
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(position)
+            .set_reference_bases(reference_bases.parse().unwrap())
+            .set_alternate_bases(alternate_bases.parse().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_decompose() {
+        let record = build_record(1, "ACT", "AGT,A,*,<DEL>");
+        let variants = decompose(&record);
+
+        let actual: Vec<_> = variants
+            .iter()
+            .map(|v| {
+                let reference_bases: String =
+                    v.reference_bases().iter().map(|&b| char::from(b)).collect();
+                (
+                    v.position(),
+                    reference_bases,
+                    v.alternate_allele().to_string(),
+                )
+            })
+            .collect();
+
+        let expected = [
+            (2, String::from("C"), String::from("G")),
+            (1, String::from("ACT"), String::from("A")),
+            (1, String::from("ACT"), String::from("<DEL>")),
+        ];
+
+        assert_eq!(actual, expected);
+
+        assert!(decompose(&build_record(1, "A", ".")).is_empty());
+    }
+
+    #[test]
+    fn test_compare() {
+        let a = build_record(1, "A", "G");
+        assert_eq!(compare(&a, &build_record(1, "A", "G")), Match::Exact);
+        assert_eq!(compare(&a, &build_record(1, "G", "A")), Match::AlleleSwap);
+        assert_eq!(compare(&a, &build_record(1, "A", "T")), Match::PositionOnly);
+        assert_eq!(compare(&a, &build_record(2, "A", "G")), Match::Mismatch);
+        assert_eq!(compare(&a, &build_record(1, "A", "T,G")), Match::Exact);
+
+        let a = build_record(1, "A", "AT");
+        assert_eq!(compare(&a, &build_record(1, "AT", "A")), Match::AlleleSwap);
+        assert_eq!(compare(&a, &build_record(1, "AC", "ACT")), Match::Mismatch);
+        assert_eq!(
+            compare(&a, &build_record(1, "A", "AC")),
+            Match::PositionOnly
+        );
+
+        let a = build_record(1, "A", "<DEL>");
+        assert_eq!(compare(&a, &build_record(1, "A", "<DEL>")), Match::Exact);
+        assert_eq!(
+            compare(&a, &build_record(1, "A", "<DUP>")),
+            Match::PositionOnly
+        );
+        assert_eq!(compare(&a, &build_record(1, "A", "C")), Match::PositionOnly);
+
+        let a = build_record(1, "A", "A[sq1:8[");
+        assert_eq!(compare(&a, &build_record(1, "A", "a[SQ1:8[")), Match::Exact);
+        assert_eq!(
+            compare(&a, &build_record(1, "A", "A[sq1:9[")),
+            Match::PositionOnly
+        );
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut a = vec![Base::A, Base::C, Base::T];
+        let mut b = vec![Base::A, Base::G, Base::T];
+        assert_eq!(trim(&mut a, &mut b), 1);
+        assert_eq!(a, [Base::C]);
+        assert_eq!(b, [Base::G]);
+
+        let mut a = vec![Base::A, Base::A];
+        let mut b = vec![Base::A];
+        assert_eq!(trim(&mut a, &mut b), 0);
+        assert_eq!(a, [Base::A, Base::A]);
+        assert_eq!(b, [Base::A]);
+    }
+}
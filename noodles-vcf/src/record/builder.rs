@@ -11,7 +11,7 @@ use super::{
 #[derive(Debug, Default, PartialEq)]
 pub struct Builder {
     chromosome: Option<Chromosome>,
-    position: Option<i32>,
+    position: Option<i64>,
     ids: Ids,
     reference_bases: Vec<Base>,
     alternate_bases: AlternateBases,
@@ -83,7 +83,7 @@ impl Builder {
     /// assert_eq!(record.position(), 8);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn set_position(mut self, position: i32) -> Self {
+    pub fn set_position(mut self, position: i64) -> Self {
         self.position = Some(position);
         self
     }
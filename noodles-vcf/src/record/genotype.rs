@@ -1,6 +1,8 @@
 //! VCF record genotype and field.
 
 pub mod field;
+pub mod likelihoods;
+pub mod phasing;
 
 pub use self::field::Field;
 
@@ -73,6 +75,55 @@ impl Genotype {
             }
         }
     }
+
+    /// Parses the alleles of the genotype (`GT`) field value.
+    ///
+    /// This returns `None` if the genotype field is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{Format, Genotype};
+    ///
+    /// let format: Format = "GT:PS".parse()?;
+    /// let genotype = Genotype::from_str_format("0|1:8", &format)?;
+    ///
+    /// let alleles = genotype.alleles().transpose()?;
+    /// assert!(alleles.map(|a| a.is_phased()).unwrap_or(false));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn alleles(&self) -> Option<Result<phasing::Alleles, phasing::ParseError>> {
+        self.iter()
+            .find(|field| field.key() == &field::Key::Genotype)
+            .and_then(|field| field.value())
+            .map(|value| match value {
+                field::Value::String(s) => s.parse(),
+                _ => Err(phasing::ParseError::Empty),
+            })
+    }
+
+    /// Returns the phase set (`PS`) field value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{Format, Genotype};
+    ///
+    /// let format: Format = "GT:PS".parse()?;
+    /// let genotype = Genotype::from_str_format("0|1:8", &format)?;
+    ///
+    /// assert_eq!(genotype.phase_set(), Some(8));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn phase_set(&self) -> Option<i32> {
+        self.iter()
+            .find(|field| field.key() == &field::Key::PhaseSet)
+            .and_then(|field| field.value())
+            .and_then(|value| match value {
+                field::Value::Integer(n) => Some(*n),
+                _ => None,
+            })
+    }
 }
 
 impl Deref for Genotype {
@@ -0,0 +1,413 @@
+//! VCF record genotype likelihoods.
+
+use std::{error, fmt};
+
+use super::{
+    field::{Key, Value},
+    Genotype,
+};
+
+/// VCF record genotype likelihoods.
+///
+/// Likelihoods are stored as log10-scaled values (`GL`) and indexed by genotype in the order
+/// defined by the VCF specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenotypeLikelihoods {
+    allele_count: usize,
+    ploidy: usize,
+    values: Vec<Option<f32>>,
+}
+
+/// An error returned when VCF genotype likelihoods fail to convert.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TryFromValuesError {
+    /// The number of values does not match the number of genotypes.
+    LengthMismatch {
+        /// The expected number of values.
+        expected: usize,
+        /// The actual number of values.
+        actual: usize,
+    },
+    /// The value type is invalid.
+    InvalidType,
+}
+
+impl error::Error for TryFromValuesError {}
+
+impl fmt::Display for TryFromValuesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "length mismatch: expected {}, got {}", expected, actual)
+            }
+            Self::InvalidType => f.write_str("invalid type"),
+        }
+    }
+}
+
+impl GenotypeLikelihoods {
+    /// Creates genotype likelihoods from log10-scaled values (`GL`).
+    ///
+    /// The number of values must equal the number of genotypes for the given allele count, which
+    /// includes the reference allele, and ploidy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::genotype::likelihoods::{GenotypeLikelihoods, TryFromValuesError};
+    ///
+    /// let values = vec![Some(-0.1), Some(-1.0), Some(-2.0)];
+    /// assert!(GenotypeLikelihoods::new(2, 2, values.clone()).is_ok());
+    ///
+    /// assert_eq!(
+    ///     GenotypeLikelihoods::new(3, 2, values),
+    ///     Err(TryFromValuesError::LengthMismatch { expected: 6, actual: 3 })
+    /// );
+    /// ```
+    pub fn new(
+        allele_count: usize,
+        ploidy: usize,
+        values: Vec<Option<f32>>,
+    ) -> Result<Self, TryFromValuesError> {
+        let expected = genotype_count(allele_count, ploidy);
+
+        if values.len() == expected {
+            Ok(Self {
+                allele_count,
+                ploidy,
+                values,
+            })
+        } else {
+            Err(TryFromValuesError::LengthMismatch {
+                expected,
+                actual: values.len(),
+            })
+        }
+    }
+
+    /// Creates genotype likelihoods from Phred-scaled values (`PL`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::genotype::likelihoods::GenotypeLikelihoods;
+    ///
+    /// let likelihoods = GenotypeLikelihoods::from_phred_scaled(2, 2, &[Some(0), Some(10), None])?;
+    /// assert_eq!(likelihoods.values(), [Some(0.0), Some(-1.0), None]);
+    /// # Ok::<(), noodles_vcf::record::genotype::likelihoods::TryFromValuesError>(())
+    /// ```
+    pub fn from_phred_scaled(
+        allele_count: usize,
+        ploidy: usize,
+        values: &[Option<i32>],
+    ) -> Result<Self, TryFromValuesError> {
+        let values = values
+            .iter()
+            .map(|value| value.map(|n| -(n as f32) / 10.0))
+            .collect();
+
+        Self::new(allele_count, ploidy, values)
+    }
+
+    /// Creates genotype likelihoods from the `GL` or, if missing, the `PL` field of a genotype.
+    ///
+    /// The ploidy is determined by the genotype (`GT`) field and is otherwise assumed to be
+    /// diploid. This returns `None` if the genotype has neither field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::{genotype::likelihoods::GenotypeLikelihoods, Format, Genotype};
+    ///
+    /// let format: Format = "GT:PL".parse()?;
+    /// let genotype = Genotype::from_str_format("0/1:10,0,20", &format)?;
+    ///
+    /// let likelihoods = GenotypeLikelihoods::from_genotype(&genotype, 2)?;
+    /// assert_eq!(likelihoods.and_then(|l| l.most_likely_genotype()), Some(vec![0, 1]));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_genotype(
+        genotype: &Genotype,
+        allele_count: usize,
+    ) -> Result<Option<Self>, TryFromValuesError> {
+        let ploidy = genotype
+            .iter()
+            .find(|field| field.key() == &Key::Genotype)
+            .and_then(|field| match field.value() {
+                Some(Value::String(s)) => Some(s.split(['/', '|']).count()),
+                _ => None,
+            })
+            .unwrap_or(2);
+
+        let find_value = |key: Key| {
+            genotype
+                .iter()
+                .find(|field| field.key() == &key)
+                .and_then(|field| field.value())
+        };
+
+        if let Some(value) = find_value(Key::GenotypeLikelihoods) {
+            match value {
+                Value::FloatArray(values) => {
+                    Self::new(allele_count, ploidy, values.clone()).map(Some)
+                }
+                Value::Float(n) => Self::new(allele_count, ploidy, vec![Some(*n)]).map(Some),
+                _ => Err(TryFromValuesError::InvalidType),
+            }
+        } else if let Some(value) = find_value(Key::RoundedGenotypeLikelihoods) {
+            match value {
+                Value::IntegerArray(values) => {
+                    Self::from_phred_scaled(allele_count, ploidy, values).map(Some)
+                }
+                Value::Integer(n) => {
+                    Self::from_phred_scaled(allele_count, ploidy, &[Some(*n)]).map(Some)
+                }
+                _ => Err(TryFromValuesError::InvalidType),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the number of alleles, including the reference allele.
+    pub fn allele_count(&self) -> usize {
+        self.allele_count
+    }
+
+    /// Returns the ploidy.
+    pub fn ploidy(&self) -> usize {
+        self.ploidy
+    }
+
+    /// Returns the log10-scaled values (`GL`).
+    pub fn values(&self) -> &[Option<f32>] {
+        &self.values
+    }
+
+    /// Returns the values as rounded Phred-scaled values (`PL`).
+    ///
+    /// The values are not normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::genotype::likelihoods::GenotypeLikelihoods;
+    ///
+    /// let likelihoods = GenotypeLikelihoods::new(2, 2, vec![Some(-0.12), Some(-1.0), None])?;
+    /// assert_eq!(likelihoods.phred_scaled(), [Some(1), Some(10), None]);
+    /// # Ok::<(), noodles_vcf::record::genotype::likelihoods::TryFromValuesError>(())
+    /// ```
+    pub fn phred_scaled(&self) -> Vec<Option<i32>> {
+        self.values
+            .iter()
+            .map(|value| value.map(|n| (-10.0 * n).round() as i32))
+            .collect()
+    }
+
+    /// Returns the log10-scaled likelihood of the genotype with the given alleles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::genotype::likelihoods::GenotypeLikelihoods;
+    ///
+    /// let likelihoods = GenotypeLikelihoods::from_phred_scaled(
+    ///     3,
+    ///     2,
+    ///     &[Some(0), Some(10), Some(20), Some(30), Some(40), Some(50)],
+    /// )?;
+    ///
+    /// assert_eq!(likelihoods.get(&[0, 2]), Some(-3.0));
+    /// assert_eq!(likelihoods.get(&[2, 1]), Some(-4.0));
+    /// assert_eq!(likelihoods.get(&[3, 3]), None);
+    /// # Ok::<(), noodles_vcf::record::genotype::likelihoods::TryFromValuesError>(())
+    /// ```
+    pub fn get(&self, alleles: &[usize]) -> Option<f32> {
+        if alleles.len() != self.ploidy || alleles.iter().any(|&a| a >= self.allele_count) {
+            return None;
+        }
+
+        self.values.get(genotype_index(alleles)).copied().flatten()
+    }
+
+    /// Returns the alleles of the most likely genotype.
+    ///
+    /// This is the genotype with the largest log10-scaled value, i.e., the smallest Phred-scaled
+    /// value. This returns `None` if all values are missing.
+    pub fn most_likely_genotype(&self) -> Option<Vec<usize>> {
+        let mut max: Option<(usize, f32)> = None;
+
+        for (i, value) in self.values.iter().enumerate() {
+            if let Some(n) = value {
+                if max.map(|(_, m)| *n > m).unwrap_or(true) {
+                    max = Some((i, *n));
+                }
+            }
+        }
+
+        max.and_then(|(i, _)| genotype_alleles(i, self.allele_count, self.ploidy))
+    }
+}
+
+/// Returns the number of genotypes for the given number of alleles and ploidy.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::record::genotype::likelihoods::genotype_count;
+/// assert_eq!(genotype_count(2, 2), 3);
+/// assert_eq!(genotype_count(3, 2), 6);
+/// assert_eq!(genotype_count(2, 1), 2);
+/// assert_eq!(genotype_count(3, 3), 10);
+/// ```
+pub fn genotype_count(allele_count: usize, ploidy: usize) -> usize {
+    if allele_count == 0 {
+        0
+    } else {
+        binomial(allele_count + ploidy - 1, ploidy)
+    }
+}
+
+/// Returns the index of the genotype with the given alleles.
+///
+/// § 1.6.2 Genotype fields (2020-06-25): "...the ordering of genotypes for the likelihoods is
+/// given by: F(j/k) = (k*(k+1)/2)+j. In other words, for biallelic sites the ordering is: AA,AB,BB;
+/// for triallelic sites the ordering is: AA,AB,BB,AC,BC,CC, etc." For other ploidies, the ordering
+/// generalizes to the sum of C(a_i + i - 1, i) for each allele a_i, with alleles in ascending order
+/// and i starting at 1.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::record::genotype::likelihoods::genotype_index;
+/// assert_eq!(genotype_index(&[0, 0]), 0);
+/// assert_eq!(genotype_index(&[1, 0]), 1);
+/// assert_eq!(genotype_index(&[0, 2]), 3);
+/// assert_eq!(genotype_index(&[2]), 2);
+/// assert_eq!(genotype_index(&[0, 0, 1]), 1);
+/// ```
+pub fn genotype_index(alleles: &[usize]) -> usize {
+    let mut alleles = alleles.to_vec();
+    alleles.sort_unstable();
+
+    alleles
+        .iter()
+        .enumerate()
+        .map(|(i, &a)| binomial(a + i, i + 1))
+        .sum()
+}
+
+/// Returns the alleles of the genotype at the given index.
+///
+/// The alleles are in ascending order. This returns `None` if the index is out of range.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::record::genotype::likelihoods::genotype_alleles;
+/// assert_eq!(genotype_alleles(3, 3, 2), Some(vec![0, 2]));
+/// assert_eq!(genotype_alleles(6, 3, 2), None);
+/// ```
+pub fn genotype_alleles(index: usize, allele_count: usize, ploidy: usize) -> Option<Vec<usize>> {
+    if index >= genotype_count(allele_count, ploidy) {
+        return None;
+    }
+
+    let mut alleles = vec![0; ploidy];
+    let mut remaining = index;
+
+    for i in (1..=ploidy).rev() {
+        let mut a = 0;
+
+        while a + 1 < allele_count && binomial(a + i, i) <= remaining {
+            a += 1;
+        }
+
+        alleles[i - 1] = a;
+        remaining -= binomial(a + i - 1, i);
+    }
+
+    Some(alleles)
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genotype_index_and_alleles_round_trip() {
+        for &(allele_count, ploidy) in &[(2, 1), (2, 2), (3, 2), (4, 3)] {
+            for i in 0..genotype_count(allele_count, ploidy) {
+                let alleles = genotype_alleles(i, allele_count, ploidy).unwrap();
+                assert_eq!(genotype_index(&alleles), i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_genotype_alleles() {
+        let actual: Vec<_> = (0..6).filter_map(|i| genotype_alleles(i, 3, 2)).collect();
+
+        let expected = [
+            vec![0, 0],
+            vec![0, 1],
+            vec![1, 1],
+            vec![0, 2],
+            vec![1, 2],
+            vec![2, 2],
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_most_likely_genotype() -> Result<(), TryFromValuesError> {
+        let likelihoods = GenotypeLikelihoods::from_phred_scaled(
+            3,
+            2,
+            &[Some(30), None, Some(40), Some(0), Some(20), Some(50)],
+        )?;
+
+        assert_eq!(likelihoods.most_likely_genotype(), Some(vec![0, 2]));
+
+        let likelihoods = GenotypeLikelihoods::new(2, 1, vec![None, None])?;
+        assert!(likelihoods.most_likely_genotype().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_genotype() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::record::Format;
+
+        let format: Format = "GT:GL:PL".parse()?;
+        let genotype = Genotype::from_str_format("1:-2.0,-0.5:20,5", &format)?;
+        let likelihoods = GenotypeLikelihoods::from_genotype(&genotype, 2)?.unwrap();
+        assert_eq!(likelihoods.ploidy(), 1);
+        assert_eq!(likelihoods.values(), [Some(-2.0), Some(-0.5)]);
+
+        let format: Format = "GT:PL".parse()?;
+        let genotype = Genotype::from_str_format("0/1:0,10", &format)?;
+        assert_eq!(
+            GenotypeLikelihoods::from_genotype(&genotype, 2),
+            Err(TryFromValuesError::LengthMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        let format: Format = "GT".parse()?;
+        let genotype = Genotype::from_str_format("0/1", &format)?;
+        assert!(GenotypeLikelihoods::from_genotype(&genotype, 2)?.is_none());
+
+        Ok(())
+    }
+}
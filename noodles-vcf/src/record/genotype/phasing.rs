@@ -0,0 +1,493 @@
+//! VCF record genotype phasing and phase sets.
+
+use std::{error, fmt, io, num, str::FromStr};
+
+use crate::record::{alternate_bases::Allele as AlternateAllele, Record};
+
+const PHASED: char = '|';
+const UNPHASED: char = '/';
+const MISSING: &str = ".";
+
+/// The phasing of a genotype allele.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Phasing {
+    /// The allele is phased (`|`).
+    Phased,
+    /// The allele is unphased (`/`).
+    Unphased,
+}
+
+impl From<Phasing> for char {
+    fn from(phasing: Phasing) -> Self {
+        match phasing {
+            Phasing::Phased => PHASED,
+            Phasing::Unphased => UNPHASED,
+        }
+    }
+}
+
+/// A genotype allele.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Allele {
+    position: Option<usize>,
+    phasing: Phasing,
+}
+
+impl Allele {
+    /// Creates a genotype allele.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::record::genotype::phasing::{Allele, Phasing};
+    /// let allele = Allele::new(Some(1), Phasing::Phased);
+    /// ```
+    pub fn new(position: Option<usize>, phasing: Phasing) -> Self {
+        Self { position, phasing }
+    }
+
+    /// Returns the allele position, i.e., `0` for the reference allele and `n` for the `n`th
+    /// alternate allele.
+    ///
+    /// This is `None` if the allele is missing (`.`).
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Returns the phasing of the allele.
+    ///
+    /// The phasing of an allele is given by the separator that precedes it. The phasing of the
+    /// first allele is given by an optional prefix, e.g., `|0|1`. If it has no prefix, the first
+    /// allele is phased if all other alleles are phased, e.g., `0|1` or `1`.
+    pub fn phasing(&self) -> Phasing {
+        self.phasing
+    }
+}
+
+/// The alleles of a genotype (`GT`) field value.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::record::genotype::phasing::{Alleles, Phasing};
+///
+/// let alleles: Alleles = "0|1".parse()?;
+/// assert!(alleles.is_phased());
+/// assert_eq!(alleles[1].position(), Some(1));
+/// assert_eq!(alleles[1].phasing(), Phasing::Phased);
+///
+/// let alleles: Alleles = "0|1/.".parse()?;
+/// assert!(!alleles.is_phased());
+/// assert_eq!(alleles[0].phasing(), Phasing::Unphased);
+/// assert_eq!(alleles[2].position(), None);
+/// # Ok::<(), noodles_vcf::record::genotype::phasing::ParseError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alleles(Vec<Allele>);
+
+impl Alleles {
+    /// Returns whether all alleles are phased.
+    pub fn is_phased(&self) -> bool {
+        self.iter()
+            .all(|allele| allele.phasing() == Phasing::Phased)
+    }
+
+    /// Returns whether any allele is missing.
+    pub fn has_missing(&self) -> bool {
+        self.iter().any(|allele| allele.position().is_none())
+    }
+
+    fn implied_first_phasing(&self) -> Phasing {
+        if self.iter().skip(1).all(|a| a.phasing() == Phasing::Phased) {
+            Phasing::Phased
+        } else {
+            Phasing::Unphased
+        }
+    }
+}
+
+impl std::ops::Deref for Alleles {
+    type Target = [Allele];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Alleles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, allele) in self.iter().enumerate() {
+            if i > 0 || allele.phasing() != self.implied_first_phasing() {
+                write!(f, "{}", char::from(allele.phasing()))?;
+            }
+
+            match allele.position() {
+                Some(position) => write!(f, "{}", position)?,
+                None => f.write_str(MISSING)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error returned when raw genotype alleles fail to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// An allele position is invalid.
+    InvalidPosition(num::ParseIntError),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidPosition(e) => write!(f, "invalid position: {}", e),
+        }
+    }
+}
+
+impl FromStr for Alleles {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let (first_phasing, s) = match s.chars().next() {
+            Some(PHASED) => (Some(Phasing::Phased), &s[1..]),
+            Some(UNPHASED) => (Some(Phasing::Unphased), &s[1..]),
+            _ => (None, s),
+        };
+
+        let mut alleles = Vec::new();
+        // The phasing of the first allele is set after all alleles are parsed.
+        let mut phasing = Phasing::Unphased;
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let next_phasing = match c {
+                PHASED => Phasing::Phased,
+                UNPHASED => Phasing::Unphased,
+                _ => continue,
+            };
+
+            alleles.push(Allele::new(parse_position(&s[start..i])?, phasing));
+
+            phasing = next_phasing;
+            start = i + 1;
+        }
+
+        alleles.push(Allele::new(parse_position(&s[start..])?, phasing));
+
+        let mut alleles = Self(alleles);
+
+        alleles.0[0].phasing = first_phasing.unwrap_or_else(|| alleles.implied_first_phasing());
+
+        Ok(alleles)
+    }
+}
+
+fn parse_position(s: &str) -> Result<Option<usize>, ParseError> {
+    match s {
+        MISSING => Ok(None),
+        _ => s.parse().map(Some).map_err(ParseError::InvalidPosition),
+    }
+}
+
+/// A block of consecutive phased genotypes of a sample in the same phase set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PhaseBlock {
+    phase_set: Option<i32>,
+    positions: Vec<i64>,
+    haplotypes: Vec<String>,
+}
+
+impl PhaseBlock {
+    /// Returns the phase set (`PS`).
+    ///
+    /// This is `None` for phased genotypes without a phase set, which are all in the same phase
+    /// set.
+    pub fn phase_set(&self) -> Option<i32> {
+        self.phase_set
+    }
+
+    /// Returns the positions of the genotypes in the block.
+    pub fn positions(&self) -> &[i64] {
+        &self.positions
+    }
+
+    /// Returns the haplotype strings, one for each allele of the genotypes.
+    ///
+    /// A haplotype string is the concatenation of the called alleles of the haplotype at each
+    /// position, e.g., `AC` for `0|1` at a `A>G` site and `1|0` at a `T>C` site.
+    pub fn haplotypes(&self) -> &[String] {
+        &self.haplotypes
+    }
+}
+
+/// Returns an iterator over the phase blocks of a sample.
+///
+/// A phase block is ended by a genotype with a different phase set (`PS`) or ploidy. Genotypes
+/// that are unphased, have missing alleles, or are missing are skipped. Records are expected to
+/// be sorted by position, e.g., the records in a region.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{
+///     self as vcf,
+///     record::{genotype::phasing, Format, Genotype},
+/// };
+///
+/// let format: Format = "GT:PS".parse()?;
+///
+/// let build_record = |position, alternate_bases: &str, genotype| -> io::Result<vcf::Record> {
+///     vcf::Record::builder()
+///         .set_chromosome("sq0".parse().unwrap())
+///         .set_position(position)
+///         .set_reference_bases("A".parse().unwrap())
+///         .set_alternate_bases(alternate_bases.parse().unwrap())
+///         .set_format(format.clone())
+///         .add_genotype(Genotype::from_str_format(genotype, &format).unwrap())
+///         .build()
+///         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+/// };
+///
+/// let records = vec![
+///     build_record(1, "C", "0|1:1"),
+///     build_record(5, "G", "1|0:1"),
+///     build_record(8, "T", "0/1:."),
+/// ];
+///
+/// let blocks = phasing::phase_blocks(records, 0).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(blocks.len(), 1);
+/// assert_eq!(blocks[0].phase_set(), Some(1));
+/// assert_eq!(blocks[0].positions(), [1, 5]);
+/// assert_eq!(blocks[0].haplotypes(), [String::from("AG"), String::from("CA")]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn phase_blocks<I>(records: I, sample_index: usize) -> PhaseBlocks<I::IntoIter>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    PhaseBlocks {
+        inner: records.into_iter(),
+        sample_index,
+        block: None,
+    }
+}
+
+/// An iterator over the phase blocks of a sample.
+///
+/// This is created by calling [`phase_blocks`].
+pub struct PhaseBlocks<I> {
+    inner: I,
+    sample_index: usize,
+    block: Option<PhaseBlock>,
+}
+
+impl<I> Iterator for PhaseBlocks<I>
+where
+    I: Iterator<Item = io::Result<Record>>,
+{
+    type Item = io::Result<PhaseBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.inner.next() {
+                Some(Ok(record)) => record,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return self.block.take().map(Ok),
+            };
+
+            let genotype = match record.genotypes().get(self.sample_index) {
+                Some(genotype) => genotype,
+                None => continue,
+            };
+
+            let alleles = match genotype.alleles() {
+                Some(Ok(alleles)) => alleles,
+                Some(Err(e)) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+                None => continue,
+            };
+
+            if !alleles.is_phased() || alleles.has_missing() {
+                continue;
+            }
+
+            let allele_sequences = match allele_sequences(&record, &alleles) {
+                Ok(sequences) => sequences,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let phase_set = genotype.phase_set();
+
+            let is_same_block = self
+                .block
+                .as_ref()
+                .map(|b| b.phase_set == phase_set && b.haplotypes.len() == alleles.len())
+                .unwrap_or(false);
+
+            let finished_block = if is_same_block {
+                None
+            } else {
+                self.block.replace(PhaseBlock {
+                    phase_set,
+                    positions: Vec::new(),
+                    haplotypes: vec![String::new(); alleles.len()],
+                })
+            };
+
+            if let Some(block) = self.block.as_mut() {
+                block.positions.push(record.position());
+
+                for (haplotype, sequence) in block.haplotypes.iter_mut().zip(allele_sequences) {
+                    haplotype.push_str(&sequence);
+                }
+            }
+
+            if let Some(block) = finished_block {
+                return Some(Ok(block));
+            }
+        }
+    }
+}
+
+fn allele_sequences(record: &Record, alleles: &Alleles) -> io::Result<Vec<String>> {
+    alleles
+        .iter()
+        .filter_map(|allele| allele.position())
+        .map(|position| match position {
+            0 => Ok(record.reference_bases().to_string()),
+            i => record
+                .alternate_bases()
+                .get(i - 1)
+                .map(|allele| match allele {
+                    AlternateAllele::OverlappingDeletion => String::new(),
+                    _ => allele.to_string(),
+                })
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid allele position: {}", i),
+                    )
+                }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Format, Genotype};
+
+    use super::*;
+
+    #[test]
+    fn test_fmt_for_alleles() -> Result<(), ParseError> {
+        for s in &["0/1", "0|1", "1", "./.", "|0/1", "/0|1", "0|1/2"] {
+            let alleles: Alleles = s.parse()?;
+            assert_eq!(alleles.to_string(), *s);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_for_alleles() {
+        assert_eq!(
+            "0|1".parse(),
+            Ok(Alleles(vec![
+                Allele::new(Some(0), Phasing::Phased),
+                Allele::new(Some(1), Phasing::Phased),
+            ]))
+        );
+
+        assert_eq!(
+            "0/1".parse(),
+            Ok(Alleles(vec![
+                Allele::new(Some(0), Phasing::Unphased),
+                Allele::new(Some(1), Phasing::Unphased),
+            ]))
+        );
+
+        assert_eq!(
+            "/1".parse(),
+            Ok(Alleles(vec![Allele::new(Some(1), Phasing::Unphased)]))
+        );
+
+        assert_eq!(
+            ".|2".parse(),
+            Ok(Alleles(vec![
+                Allele::new(None, Phasing::Phased),
+                Allele::new(Some(2), Phasing::Phased),
+            ]))
+        );
+
+        assert_eq!("".parse::<Alleles>(), Err(ParseError::Empty));
+        assert!(matches!(
+            "0|n".parse::<Alleles>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+        assert!(matches!(
+            "0|".parse::<Alleles>(),
+            Err(ParseError::InvalidPosition(_))
+        ));
+    }
+
+    #[test]
+    fn test_phase_blocks() -> io::Result<()> {
+        let format: Format = "GT:PS".parse().unwrap();
+
+        let build_record = |position, genotype| -> io::Result<Record> {
+            Ok(Record::builder()
+                .set_chromosome("sq0".parse().unwrap())
+                .set_position(position)
+                .set_reference_bases("A".parse().unwrap())
+                .set_alternate_bases("C,*".parse().unwrap())
+                .set_format(format.clone())
+                .add_genotype(Genotype::from_str_format(genotype, &format).unwrap())
+                .build()
+                .unwrap())
+        };
+
+        let records = vec![
+            build_record(1, "0|1"),
+            build_record(2, "1|0"),
+            build_record(3, "0|1:3"),
+            build_record(4, "./."),
+            build_record(5, "2|1:3"),
+            build_record(6, "1"),
+        ];
+
+        let blocks: Vec<_> = phase_blocks(records, 0).collect::<io::Result<_>>()?;
+
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!(blocks[0].phase_set(), None);
+        assert_eq!(blocks[0].positions(), [1, 2]);
+        assert_eq!(
+            blocks[0].haplotypes(),
+            [String::from("AC"), String::from("CA")]
+        );
+
+        assert_eq!(blocks[1].phase_set(), Some(3));
+        assert_eq!(blocks[1].positions(), [3, 5]);
+        assert_eq!(
+            blocks[1].haplotypes(),
+            [String::from("A"), String::from("CC")]
+        );
+
+        assert_eq!(blocks[2].positions(), [6]);
+        assert_eq!(blocks[2].haplotypes(), [String::from("C")]);
+
+        Ok(())
+    }
+}
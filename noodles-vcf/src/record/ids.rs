@@ -36,6 +36,12 @@ impl fmt::Display for Ids {
     }
 }
 
+impl From<Vec<String>> for Ids {
+    fn from(ids: Vec<String>) -> Self {
+        Self(ids)
+    }
+}
+
 /// An error returned when a raw VCF record ID fails to parse.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ParseError {
@@ -0,0 +1,1094 @@
+//! VCF record normalization.
+
+use std::{
+    cmp,
+    convert::{TryFrom, TryInto},
+    error, fmt,
+    io::{self, Read, Seek},
+};
+
+use noodles_fasta as fasta;
+
+use crate::header::Number;
+
+use super::{
+    alternate_bases::Allele,
+    genotype::{self, field::Key as GenotypeKey},
+    info,
+    reference_bases::{self, base, Base},
+    AlternateBases, Chromosome, Genotype, Info, Record,
+};
+
+// The number of bases read before the current window when left-aligning.
+const WINDOW_LEN: i64 = 64;
+
+/// A source of reference sequences used to normalize records.
+///
+/// This is implemented for a list of FASTA records and for an indexed FASTA reader, which only
+/// reads the bases around each record.
+pub trait ReferenceSequenceProvider {
+    /// Returns the length of the reference sequence with the given name.
+    ///
+    /// This returns `None` if the reference sequence does not exist.
+    fn reference_sequence_len(&mut self, name: &str) -> Option<u64>;
+
+    /// Reads the bases of the reference sequence with the given name.
+    ///
+    /// `start` and `end` are 1-based and inclusive.
+    fn read_bases(&mut self, name: &str, start: i64, end: i64) -> io::Result<Vec<u8>>;
+}
+
+impl ReferenceSequenceProvider for [fasta::Record] {
+    fn reference_sequence_len(&mut self, name: &str) -> Option<u64> {
+        find_reference_sequence(self, name).map(|sequence| sequence.len() as u64)
+    }
+
+    fn read_bases(&mut self, name: &str, start: i64, end: i64) -> io::Result<Vec<u8>> {
+        find_reference_sequence(self, name)
+            .and_then(|sequence| {
+                let start = usize::try_from(start - 1).ok()?;
+                let end = usize::try_from(end).ok()?;
+                sequence.get(start..end)
+            })
+            .map(|bases| bases.to_vec())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid interval: {}:{}-{}", name, start, end),
+                )
+            })
+    }
+}
+
+impl<R> ReferenceSequenceProvider for fasta::IndexedReader<R>
+where
+    R: Read + Seek,
+{
+    fn reference_sequence_len(&mut self, name: &str) -> Option<u64> {
+        self.index()
+            .iter()
+            .find(|record| record.reference_sequence_name() == name)
+            .map(|record| record.len())
+    }
+
+    fn read_bases(&mut self, name: &str, start: i64, end: i64) -> io::Result<Vec<u8>> {
+        self.query(name, start, end)
+    }
+}
+
+fn find_reference_sequence<'a>(records: &'a [fasta::Record], name: &str) -> Option<&'a [u8]> {
+    records
+        .iter()
+        .find(|record| record.reference_sequence_name() == name)
+        .map(|record| record.sequence())
+}
+
+/// An error returned when a VCF record fails to normalize.
+#[derive(Debug)]
+pub enum NormalizeError {
+    /// The reference sequence is missing.
+    MissingReferenceSequence(String),
+    /// The position is invalid.
+    InvalidPosition(i64),
+    /// The reference bases do not match the reference sequence.
+    ReferenceBasesMismatch,
+    /// A base in the reference sequence is invalid.
+    InvalidReferenceSequenceBase(base::TryFromCharError),
+    /// The normalized reference bases are invalid.
+    InvalidReferenceBases(reference_bases::TryFromBaseVectorError),
+    /// An I/O error occurred while reading the reference sequence.
+    IoError(io::Error),
+}
+
+impl error::Error for NormalizeError {}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReferenceSequence(name) => {
+                write!(f, "missing reference sequence: {}", name)
+            }
+            Self::InvalidPosition(position) => write!(f, "invalid position: {}", position),
+            Self::ReferenceBasesMismatch => {
+                f.write_str("reference bases do not match the reference sequence")
+            }
+            Self::InvalidReferenceSequenceBase(e) => {
+                write!(f, "invalid reference sequence base: {}", e)
+            }
+            Self::InvalidReferenceBases(e) => write!(f, "invalid reference bases: {}", e),
+            Self::IoError(e) => e.fmt(f),
+        }
+    }
+}
+
+/// An error returned when VCF records fail to join.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum JoinError {
+    /// The input is empty.
+    Empty,
+    /// The records do not have the same chromosome, position, and reference bases.
+    SiteMismatch,
+    /// The records do not have the same number of genotypes.
+    GenotypeCountMismatch,
+    /// A joined genotype is invalid.
+    InvalidGenotype(genotype::TryFromFieldsError),
+}
+
+impl error::Error for JoinError {}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::SiteMismatch => f.write_str("site mismatch"),
+            Self::GenotypeCountMismatch => f.write_str("genotype count mismatch"),
+            Self::InvalidGenotype(e) => write!(f, "invalid genotype: {}", e),
+        }
+    }
+}
+
+/// Left-aligns and trims the alleles of a VCF record.
+///
+/// The reference sequence of the record is looked up by name in the given reference sequence
+/// provider, e.g., a list of FASTA records or an indexed FASTA reader. Alleles are shifted left
+/// while the last base of every allele is the same, and then trimmed from the left while the first
+/// base of every allele is the same, leaving at least one base in each allele. This is the same
+/// algorithm used by `bcftools norm`.
+///
+/// Records with symbolic alleles, breakends, or overlapping deletions are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_fasta as fasta;
+/// use noodles_vcf::{self as vcf, record::normalize};
+///
+/// let mut reference_sequences = [fasta::Record::new(
+///     fasta::record::Definition::new(String::from("sq0"), None),
+///     b"GCACACAT".to_vec(),
+/// )];
+///
+/// let mut record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(6)
+///     .set_reference_bases("C".parse()?)
+///     .set_alternate_bases("CAC".parse()?)
+///     .build()?;
+///
+/// normalize(&mut record, &mut reference_sequences[..])?;
+///
+/// assert_eq!(record.position(), 1);
+/// assert_eq!(record.reference_bases().to_string(), "G");
+/// assert_eq!(record.alternate_bases().to_string(), "GCA");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn normalize<P>(record: &mut Record, reference_sequences: &mut P) -> Result<(), NormalizeError>
+where
+    P: ReferenceSequenceProvider + ?Sized,
+{
+    let mut alleles = Vec::with_capacity(record.alternate_bases().len() + 1);
+    alleles.push(record.reference_bases().to_vec());
+
+    for allele in record.alternate_bases().iter() {
+        match allele {
+            Allele::Bases(bases) => alleles.push(bases.clone()),
+            _ => return Ok(()),
+        }
+    }
+
+    if alleles.len() < 2 || alleles[1..].iter().any(|bases| bases == &alleles[0]) {
+        return Ok(());
+    }
+
+    let name = match record.chromosome() {
+        Chromosome::Name(name) | Chromosome::Symbol(name) => name,
+    };
+
+    let reference_sequence_len = reference_sequences
+        .reference_sequence_len(name)
+        .ok_or_else(|| NormalizeError::MissingReferenceSequence(name.clone()))?;
+
+    let position = record.position();
+    let end = position + alleles[0].len() as i64 - 1;
+
+    if position < 1 || end as u64 > reference_sequence_len {
+        return Err(NormalizeError::InvalidPosition(position));
+    }
+
+    // The bases of the reference sequence starting at `window_start` (1-based) that have been
+    // read so far.
+    let mut window = reference_sequences
+        .read_bases(name, position, end)
+        .map_err(NormalizeError::IoError)?;
+    let mut window_start = position;
+
+    if !alleles[0]
+        .iter()
+        .zip(&window)
+        .all(|(&a, &b)| char::from(a) == char::from(b).to_ascii_uppercase())
+    {
+        return Err(NormalizeError::ReferenceBasesMismatch);
+    }
+
+    let mut start = position;
+
+    // Left-align by removing a shared last base, extending the alleles to the left when one would
+    // become empty.
+    while alleles_share_base(&alleles, |bases| bases.last()) {
+        if alleles.iter().any(|bases| bases.len() == 1) {
+            if start == 1 {
+                break;
+            }
+
+            start -= 1;
+
+            if start < window_start {
+                let chunk_start = cmp::max(1, window_start - WINDOW_LEN);
+
+                let mut bases = reference_sequences
+                    .read_bases(name, chunk_start, window_start - 1)
+                    .map_err(NormalizeError::IoError)?;
+                bases.extend(window);
+
+                window = bases;
+                window_start = chunk_start;
+            }
+
+            let b = window[(start - window_start) as usize];
+            let base = Base::try_from(char::from(b).to_ascii_uppercase())
+                .map_err(NormalizeError::InvalidReferenceSequenceBase)?;
+
+            for bases in &mut alleles {
+                bases.insert(0, base);
+            }
+        }
+
+        for bases in &mut alleles {
+            bases.pop();
+        }
+    }
+
+    while alleles.iter().all(|bases| bases.len() > 1)
+        && alleles_share_base(&alleles, |bases| bases.first())
+    {
+        for bases in &mut alleles {
+            bases.remove(0);
+        }
+
+        start += 1;
+    }
+
+    let mut alleles = alleles.into_iter();
+
+    let reference_bases = alleles.next().unwrap_or_default();
+
+    record.position = start;
+    record.reference_bases = reference_bases
+        .try_into()
+        .map_err(NormalizeError::InvalidReferenceBases)?;
+    record.alternate_bases = AlternateBases::from(alleles.map(Allele::Bases).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+fn alleles_share_base<F>(alleles: &[Vec<Base>], f: F) -> bool
+where
+    F: Fn(&Vec<Base>) -> Option<&Base>,
+{
+    let mut bases = alleles.iter().map(f);
+
+    match bases.next() {
+        Some(Some(first)) => bases.all(|base| base == Some(first)),
+        _ => false,
+    }
+}
+
+/// Splits a multiallelic VCF record into biallelic records.
+///
+/// Each alternate allele is given its own record. Information and genotype fields with a number
+/// of `A`, `R`, or `G` are subset to the alleles of the new record, and genotypes (`GT`) calling
+/// other alternate alleles are set to the reference allele.
+///
+/// Records with fewer than two alternate alleles are returned as-is.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::normalize};
+///
+/// let record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .set_alternate_bases("C,G".parse()?)
+///     .set_info("AC=1,2".parse()?)
+///     .build()?;
+///
+/// let records = normalize::split(&record)?;
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[0].alternate_bases().to_string(), "C");
+/// assert_eq!(records[0].info().to_string(), "AC=1");
+/// assert_eq!(records[1].alternate_bases().to_string(), "G");
+/// assert_eq!(records[1].info().to_string(), "AC=2");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn split(record: &Record) -> Result<Vec<Record>, genotype::TryFromFieldsError> {
+    let alternate_bases_count = record.alternate_bases().len();
+
+    if alternate_bases_count < 2 {
+        return Ok(vec![record.clone()]);
+    }
+
+    (0..alternate_bases_count)
+        .map(|i| {
+            let allele_map = [0, i + 1];
+
+            let mut split_record = record.clone();
+
+            split_record.alternate_bases =
+                AlternateBases::from(vec![record.alternate_bases()[i].clone()]);
+
+            split_record.info = split_info(record.info(), &allele_map);
+
+            split_record.genotypes = record
+                .genotypes()
+                .iter()
+                .map(|genotype| split_genotype(genotype, &allele_map))
+                .collect::<Result<_, _>>()?;
+
+            Ok(split_record)
+        })
+        .collect()
+}
+
+/// Joins VCF records at the same site into a single multiallelic record.
+///
+/// The records must have the same chromosome, position, and reference bases. This is the inverse
+/// of [`split`]: alternate alleles are merged, in order and without duplicates; information and
+/// genotype fields with a number of `A`, `R`, or `G` are merged; and genotypes (`GT`) are
+/// combined. All other fields are taken from the first record.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::normalize};
+///
+/// let records = [
+///     vcf::Record::builder()
+///         .set_chromosome("sq0".parse()?)
+///         .set_position(1)
+///         .set_reference_bases("A".parse()?)
+///         .set_alternate_bases("C".parse()?)
+///         .build()?,
+///     vcf::Record::builder()
+///         .set_chromosome("sq0".parse()?)
+///         .set_position(1)
+///         .set_reference_bases("A".parse()?)
+///         .set_alternate_bases("G".parse()?)
+///         .build()?,
+/// ];
+///
+/// let record = normalize::join(&records)?;
+/// assert_eq!(record.alternate_bases().to_string(), "C,G");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn join(records: &[Record]) -> Result<Record, JoinError> {
+    let first_record = records.first().ok_or(JoinError::Empty)?;
+
+    for record in &records[1..] {
+        if record.chromosome() != first_record.chromosome()
+            || record.position() != first_record.position()
+            || record.reference_bases() != first_record.reference_bases()
+        {
+            return Err(JoinError::SiteMismatch);
+        } else if record.genotypes().len() != first_record.genotypes().len() {
+            return Err(JoinError::GenotypeCountMismatch);
+        }
+    }
+
+    let alternate_bases: Vec<_> = records.iter().map(|r| r.alternate_bases()).collect();
+    let (alternate_bases, allele_maps) = merge_alternate_bases(&alternate_bases);
+
+    let allele_count = alternate_bases.len() + 1;
+
+    let mut joined_record = first_record.clone();
+    joined_record.alternate_bases = AlternateBases::from(alternate_bases);
+
+    let infos: Vec<_> = records.iter().map(|r| r.info()).collect();
+    joined_record.info = join_info(&infos, &allele_maps, allele_count);
+
+    joined_record.genotypes = (0..first_record.genotypes().len())
+        .map(|i| {
+            let genotypes: Vec<_> = records.iter().map(|r| &r.genotypes()[i]).collect();
+            join_genotypes(&genotypes, &allele_maps, allele_count)
+                .map_err(JoinError::InvalidGenotype)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(joined_record)
+}
+
+// Merges lists of alternate bases, in order and without duplicates, and returns the merged
+// alternate bases and, for each list, a map of its allele indices to the merged allele indices.
+pub(crate) fn merge_alternate_bases(
+    alternate_bases: &[&AlternateBases],
+) -> (Vec<Allele>, Vec<Vec<usize>>) {
+    let mut merged_alternate_bases: Vec<Allele> = Vec::new();
+    let mut allele_maps = Vec::with_capacity(alternate_bases.len());
+
+    for alleles in alternate_bases {
+        let mut allele_map = vec![0];
+
+        for allele in alleles.iter() {
+            let i = match merged_alternate_bases.iter().position(|a| a == allele) {
+                Some(i) => i,
+                None => {
+                    merged_alternate_bases.push(allele.clone());
+                    merged_alternate_bases.len() - 1
+                }
+            };
+
+            allele_map.push(i + 1);
+        }
+
+        allele_maps.push(allele_map);
+    }
+
+    (merged_alternate_bases, allele_maps)
+}
+
+fn is_allele_number(number: Number) -> bool {
+    matches!(number, Number::A | Number::R | Number::G)
+}
+
+// Returns the number of values for a field with the given number.
+fn array_len(number: Number, allele_count: usize, ploidy: usize) -> usize {
+    match number {
+        Number::A => allele_count - 1,
+        Number::R => allele_count,
+        Number::G => match ploidy {
+            1 => allele_count,
+            2 => allele_count * (allele_count + 1) / 2,
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+// § 1.6.2 Genotype fields (2020-06-25): "...the ordering of genotypes for the likelihoods is
+// given by: F(j/k) = (k*(k+1)/2)+j."
+fn genotype_index(j: usize, k: usize) -> usize {
+    let (j, k) = if j <= k { (j, k) } else { (k, j) };
+    k * (k + 1) / 2 + j
+}
+
+// Returns pairs of (local, global) value indices for a field with the given number, where the
+// allele map maps local allele indices to global allele indices.
+fn index_pairs(number: Number, allele_map: &[usize], ploidy: usize) -> Vec<(usize, usize)> {
+    match (number, ploidy) {
+        (Number::A, _) => allele_map
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(a, &g)| (a - 1, g - 1))
+            .collect(),
+        (Number::R, _) | (Number::G, 1) => allele_map.iter().copied().enumerate().collect(),
+        (Number::G, 2) => {
+            let mut pairs = Vec::new();
+
+            for (k, &gk) in allele_map.iter().enumerate() {
+                for (j, &gj) in allele_map[..=k].iter().enumerate() {
+                    pairs.push((genotype_index(j, k), genotype_index(gj, gk)));
+                }
+            }
+
+            pairs
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn flip(pairs: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    pairs.into_iter().map(|(a, b)| (b, a)).collect()
+}
+
+// Copies values from the source arrays to a new array of the given length using pairs of (source,
+// destination) indices. The first value copied to a destination wins.
+fn merge_arrays<T>(
+    arrays: &[&[T]],
+    index_pairs: &[Vec<(usize, usize)>],
+    len: usize,
+) -> Vec<Option<T>>
+where
+    T: Clone,
+{
+    let mut merged = vec![None; len];
+
+    for (array, pairs) in arrays.iter().zip(index_pairs) {
+        for &(src, dst) in pairs {
+            if let (Some(value), Some(slot)) = (array.get(src), merged.get_mut(dst)) {
+                if slot.is_none() {
+                    *slot = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    merged
+}
+
+fn merge_info_values(
+    values: &[&info::field::Value],
+    index_pairs: &[Vec<(usize, usize)>],
+    len: usize,
+) -> Option<info::field::Value> {
+    use info::field::Value;
+
+    match values.first()? {
+        Value::IntegerArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::IntegerArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            merged
+                .into_iter()
+                .collect::<Option<_>>()
+                .map(Value::IntegerArray)
+        }
+        Value::FloatArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::FloatArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            merged
+                .into_iter()
+                .collect::<Option<_>>()
+                .map(Value::FloatArray)
+        }
+        Value::CharacterArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::CharacterArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            merged
+                .into_iter()
+                .collect::<Option<_>>()
+                .map(Value::CharacterArray)
+        }
+        Value::StringArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::StringArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            merged
+                .into_iter()
+                .collect::<Option<_>>()
+                .map(Value::StringArray)
+        }
+        _ => None,
+    }
+}
+
+fn merge_genotype_values(
+    values: &[&genotype::field::Value],
+    index_pairs: &[Vec<(usize, usize)>],
+    len: usize,
+) -> Option<genotype::field::Value> {
+    use genotype::field::Value;
+
+    match values.first()? {
+        Value::IntegerArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::IntegerArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            Some(Value::IntegerArray(
+                merged.into_iter().map(Option::flatten).collect(),
+            ))
+        }
+        Value::FloatArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::FloatArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            Some(Value::FloatArray(
+                merged.into_iter().map(Option::flatten).collect(),
+            ))
+        }
+        Value::CharacterArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::CharacterArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            Some(Value::CharacterArray(
+                merged.into_iter().map(Option::flatten).collect(),
+            ))
+        }
+        Value::StringArray(_) => {
+            let arrays = values
+                .iter()
+                .map(|value| match value {
+                    Value::StringArray(array) => Some(&array[..]),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let merged = merge_arrays(&arrays, index_pairs, len);
+            Some(Value::StringArray(
+                merged.into_iter().map(Option::flatten).collect(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn split_info(info: &Info, allele_map: &[usize]) -> Info {
+    const PLOIDY: usize = 2;
+
+    let fields: Vec<_> = info
+        .iter()
+        .map(|field| {
+            let number = field.key().number();
+
+            if !is_allele_number(number) {
+                return field.clone();
+            }
+
+            let pairs = flip(index_pairs(number, allele_map, PLOIDY));
+            let len = array_len(number, allele_map.len(), PLOIDY);
+
+            match merge_info_values(&[field.value()], &[pairs], len) {
+                Some(value) => info::Field::new(field.key().clone(), value),
+                None => field.clone(),
+            }
+        })
+        .collect();
+
+    Info::from(fields)
+}
+
+fn split_genotype(
+    genotype: &Genotype,
+    allele_map: &[usize],
+) -> Result<Genotype, genotype::TryFromFieldsError> {
+    use genotype::field::Value;
+
+    let ploidy = genotype_ploidy(genotype);
+
+    let fields: Vec<_> = genotype
+        .iter()
+        .map(|field| {
+            let key = field.key();
+
+            let value = match field.value() {
+                Some(value) => value,
+                None => return field.clone(),
+            };
+
+            if key == &GenotypeKey::Genotype {
+                if let Value::String(s) = value {
+                    let (alleles, separators) = parse_alleles(s);
+
+                    let alleles: Vec<_> = alleles
+                        .into_iter()
+                        .map(|allele| {
+                            allele.map(|a| allele_map.iter().position(|&g| g == a).unwrap_or(0))
+                        })
+                        .collect();
+
+                    let value = Value::String(format_alleles(&alleles, &separators));
+                    return genotype::Field::new(key.clone(), Some(value));
+                }
+            }
+
+            let number = key.number();
+
+            if !is_allele_number(number) {
+                return field.clone();
+            }
+
+            let pairs = flip(index_pairs(number, allele_map, ploidy));
+            let len = array_len(number, allele_map.len(), ploidy);
+
+            match merge_genotype_values(&[value], &[pairs], len) {
+                Some(value) => genotype::Field::new(key.clone(), Some(value)),
+                None => field.clone(),
+            }
+        })
+        .collect();
+
+    Genotype::try_from(fields)
+}
+
+pub(crate) fn join_info(infos: &[&Info], allele_maps: &[Vec<usize>], allele_count: usize) -> Info {
+    const PLOIDY: usize = 2;
+
+    let fields: Vec<_> = infos[0]
+        .iter()
+        .filter_map(|field| {
+            let key = field.key();
+            let number = key.number();
+
+            if !is_allele_number(number) {
+                return Some(field.clone());
+            }
+
+            let mut values = Vec::with_capacity(infos.len());
+            let mut pairs = Vec::with_capacity(infos.len());
+
+            for (info, allele_map) in infos.iter().zip(allele_maps) {
+                let value = info.iter().find(|f| f.key() == key)?.value();
+                values.push(value);
+                pairs.push(index_pairs(number, allele_map, PLOIDY));
+            }
+
+            let len = array_len(number, allele_count, PLOIDY);
+
+            merge_info_values(&values, &pairs, len)
+                .map(|value| info::Field::new(key.clone(), value))
+        })
+        .collect();
+
+    Info::from(fields)
+}
+
+pub(crate) fn join_genotypes(
+    genotypes: &[&Genotype],
+    allele_maps: &[Vec<usize>],
+    allele_count: usize,
+) -> Result<Genotype, genotype::TryFromFieldsError> {
+    use genotype::field::Value;
+    let ploidy = genotype_ploidy(genotypes[0]);
+
+    let fields: Vec<_> = genotypes[0]
+        .iter()
+        .map(|field| {
+            let key = field.key();
+
+            let values: Vec<_> = genotypes
+                .iter()
+                .map(|genotype| {
+                    genotype
+                        .iter()
+                        .find(|f| f.key() == key)
+                        .and_then(|f| f.value())
+                })
+                .collect();
+
+            if key == &GenotypeKey::Genotype {
+                if let Some(Value::String(s)) = values[0] {
+                    let (mut alleles, separators) = parse_alleles(s);
+
+                    for (value, allele_map) in values.iter().zip(allele_maps) {
+                        let s = match value {
+                            Some(Value::String(s)) => s,
+                            _ => continue,
+                        };
+
+                        let (record_alleles, _) = parse_alleles(s);
+
+                        for (allele, record_allele) in alleles.iter_mut().zip(record_alleles) {
+                            if let Some(a) = record_allele {
+                                if a > 0 || allele.is_none() {
+                                    *allele = allele_map.get(a).copied();
+                                }
+                            }
+                        }
+                    }
+
+                    let value = Value::String(format_alleles(&alleles, &separators));
+                    return genotype::Field::new(key.clone(), Some(value));
+                }
+            }
+
+            let number = key.number();
+
+            if !is_allele_number(number) {
+                return field.clone();
+            }
+
+            let mut present_values = Vec::with_capacity(values.len());
+            let mut pairs = Vec::with_capacity(values.len());
+
+            for (value, allele_map) in values.into_iter().zip(allele_maps) {
+                if let Some(value) = value {
+                    present_values.push(value);
+                    pairs.push(index_pairs(number, allele_map, ploidy));
+                }
+            }
+
+            let len = array_len(number, allele_count, ploidy);
+            let value = merge_genotype_values(&present_values, &pairs, len);
+
+            genotype::Field::new(key.clone(), value)
+        })
+        .collect();
+
+    Genotype::try_from(fields)
+}
+
+fn genotype_ploidy(genotype: &Genotype) -> usize {
+    use genotype::field::Value;
+
+    match genotype
+        .first()
+        .filter(|field| field.key() == &GenotypeKey::Genotype)
+        .and_then(|field| field.value())
+    {
+        Some(Value::String(s)) => parse_alleles(s).0.len(),
+        _ => 2,
+    }
+}
+
+// Parses a raw genotype (`GT`) value into its allele indices and separators.
+fn parse_alleles(s: &str) -> (Vec<Option<usize>>, Vec<char>) {
+    let mut alleles = Vec::new();
+    let mut separators = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        if c == '/' || c == '|' {
+            alleles.push(s[start..i].parse().ok());
+            separators.push(c);
+            start = i + 1;
+        }
+    }
+
+    alleles.push(s[start..].parse().ok());
+
+    (alleles, separators)
+}
+
+fn format_alleles(alleles: &[Option<usize>], separators: &[char]) -> String {
+    let mut s = String::new();
+
+    for (i, allele) in alleles.iter().enumerate() {
+        if i > 0 {
+            if let Some(separator) = separators.get(i - 1) {
+                s.push(*separator);
+            }
+        }
+
+        match allele {
+            Some(a) => s.push_str(&a.to_string()),
+            None => s.push('.'),
+        }
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::Format;
+
+    use super::*;
+
+    fn build_reference_sequences() -> Vec<fasta::Record> {
+        vec![fasta::Record::new(
+            fasta::record::Definition::new(String::from("sq0"), None),
+            b"GCACACAT".to_vec(),
+        )]
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reference_sequences = build_reference_sequences();
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(2)
+            .set_reference_bases("CAC".parse()?)
+            .set_alternate_bases("CGC".parse()?)
+            .build()?;
+
+        normalize(&mut record, &mut reference_sequences[..])?;
+
+        assert_eq!(record.position(), 3);
+        assert_eq!(record.reference_bases().to_string(), "A");
+        assert_eq!(record.alternate_bases().to_string(), "G");
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(5)
+            .set_reference_bases("ACA".parse()?)
+            .set_alternate_bases("A".parse()?)
+            .build()?;
+
+        normalize(&mut record, &mut reference_sequences[..])?;
+
+        assert_eq!(record.position(), 1);
+        assert_eq!(record.reference_bases().to_string(), "GCA");
+        assert_eq!(record.alternate_bases().to_string(), "G");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_with_indexed_reader() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::Cursor;
+
+        use noodles_fasta::fai;
+
+        // The repeat spans more than one window before the record.
+        let sequence = format!("G{}T", "CA".repeat(64));
+        let data = format!(">sq0\n{}\n", sequence);
+        let index = fai::index(data.as_bytes())?;
+        let mut reader = fasta::IndexedReader::new(Cursor::new(data.into_bytes()), index);
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(127)
+            .set_reference_bases("ACA".parse()?)
+            .set_alternate_bases("A".parse()?)
+            .build()?;
+
+        normalize(&mut record, &mut reader)?;
+
+        assert_eq!(record.position(), 1);
+        assert_eq!(record.reference_bases().to_string(), "GCA");
+        assert_eq!(record.alternate_bases().to_string(), "G");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_with_unnormalizable_records() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reference_sequences = build_reference_sequences();
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(1)
+            .set_reference_bases("G".parse()?)
+            .set_alternate_bases("<DEL>".parse()?)
+            .build()?;
+
+        let expected = record.clone();
+        normalize(&mut record, &mut reference_sequences[..])?;
+        assert_eq!(record, expected);
+
+        let mut record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(1)
+            .set_reference_bases("T".parse()?)
+            .set_alternate_bases("C".parse()?)
+            .build()?;
+
+        assert!(matches!(
+            normalize(&mut record, &mut reference_sequences[..]),
+            Err(NormalizeError::ReferenceBasesMismatch)
+        ));
+
+        let mut record = Record::builder()
+            .set_chromosome("sq1".parse()?)
+            .set_position(1)
+            .set_reference_bases("T".parse()?)
+            .set_alternate_bases("C".parse()?)
+            .build()?;
+
+        assert!(matches!(
+            normalize(&mut record, &mut reference_sequences[..]),
+            Err(NormalizeError::MissingReferenceSequence(name)) if name == "sq1"
+        ));
+
+        Ok(())
+    }
+
+    fn build_multiallelic_record() -> Result<Record, Box<dyn std::error::Error>> {
+        let format: Format = "GT:PL".parse()?;
+
+        let record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(1)
+            .set_reference_bases("A".parse()?)
+            .set_alternate_bases("C,G".parse()?)
+            .set_info("AC=1,2;AN=4".parse()?)
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format("0/1:0,1,2,3,4,5", &format)?)
+            .add_genotype(Genotype::from_str_format("2/2:5,4,3,2,1,0", &format)?)
+            .build()?;
+
+        Ok(record)
+    }
+
+    #[test]
+    fn test_split() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_multiallelic_record()?;
+        let records = split(&record)?;
+
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].alternate_bases().to_string(), "C");
+        assert_eq!(records[0].info().to_string(), "AC=1;AN=4");
+        assert_eq!(records[0].genotypes()[0].to_string(), "0/1:0,1,2");
+        assert_eq!(records[0].genotypes()[1].to_string(), "0/0:5,4,3");
+
+        assert_eq!(records[1].alternate_bases().to_string(), "G");
+        assert_eq!(records[1].info().to_string(), "AC=2;AN=4");
+        assert_eq!(records[1].genotypes()[0].to_string(), "0/0:0,3,5");
+        assert_eq!(records[1].genotypes()[1].to_string(), "1/1:5,2,0");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join() -> Result<(), Box<dyn std::error::Error>> {
+        let record = build_multiallelic_record()?;
+        let records = split(&record)?;
+
+        let actual = join(&records)?;
+
+        assert_eq!(actual.alternate_bases(), record.alternate_bases());
+        assert_eq!(actual.info(), record.info());
+        assert_eq!(actual.genotypes()[0].to_string(), "0/1:0,1,2,3,.,5");
+        assert_eq!(actual.genotypes()[1].to_string(), "2/2:5,4,3,2,.,0");
+
+        assert_eq!(join(&[]), Err(JoinError::Empty));
+
+        let mut other_record = records[1].clone();
+        other_record.position = 2;
+
+        assert_eq!(
+            join(&[records[0].clone(), other_record]),
+            Err(JoinError::SiteMismatch)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+//! VCF record structural variants.
+//!
+//! A [`StructuralVariant`] is a typed view of the structural variant information of a record:
+//! its kind, span, length (`SVLEN`), confidence intervals (`CIPOS` and `CIEND`), and, for
+//! breakends, the mate breakend.
+
+use std::{error, fmt, str::FromStr};
+
+use super::{
+    alternate_bases::{
+        allele::{symbol::structural_variant::Type, Symbol},
+        Allele,
+    },
+    info::field::{Key, Value},
+    Record,
+};
+
+/// The kind of a structural variant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// A deletion (`DEL`).
+    Deletion,
+    /// An insertion (`INS`).
+    Insertion,
+    /// A duplication (`DUP`).
+    Duplication,
+    /// An inversion (`INV`).
+    Inversion,
+    /// A copy number variation (`CNV`).
+    CopyNumberVariation,
+    /// A breakend (`BND`).
+    Breakend,
+}
+
+impl From<Type> for Kind {
+    fn from(ty: Type) -> Self {
+        match ty {
+            Type::Deletion => Self::Deletion,
+            Type::Insertion => Self::Insertion,
+            Type::Duplication => Self::Duplication,
+            Type::Inversion => Self::Inversion,
+            Type::CopyNumberVariation => Self::CopyNumberVariation,
+            Type::Breakend => Self::Breakend,
+        }
+    }
+}
+
+/// An error returned when a raw structural variant kind fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError(String);
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid structural variant kind: expected {{DEL, INS, DUP, INV, CNV, BND}}, got {}",
+            self.0
+        )
+    }
+}
+
+impl FromStr for Kind {
+    type Err = ParseError;
+
+    /// Parses a raw structural variant kind, e.g., from an `SVTYPE` value.
+    ///
+    /// Subtypes are ignored, e.g., `DUP:TANDEM` is a duplication.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ty = s.split(':').next().unwrap_or(s);
+
+        match ty {
+            "DEL" => Ok(Self::Deletion),
+            "INS" => Ok(Self::Insertion),
+            "DUP" => Ok(Self::Duplication),
+            "INV" => Ok(Self::Inversion),
+            "CNV" => Ok(Self::CopyNumberVariation),
+            "BND" => Ok(Self::Breakend),
+            _ => Err(ParseError(s.into())),
+        }
+    }
+}
+
+/// The mate of a breakend, as given by a breakend alternate allele.
+///
+/// A breakend allele has one of four forms, where `t` are the local bases and `p` is the mate
+/// position: `t[p[`, `t]p]`, `]p]t`, and `[p[t`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mate {
+    chromosome: String,
+    position: i64,
+    follows_local_bases: bool,
+    extends_right: bool,
+}
+
+impl Mate {
+    /// Returns the chromosome of the mate.
+    pub fn chromosome(&self) -> &str {
+        &self.chromosome
+    }
+
+    /// Returns the position of the mate.
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Returns whether the mate sequence is joined after the local bases, i.e., `t[p[` or
+    /// `t]p]`.
+    pub fn follows_local_bases(&self) -> bool {
+        self.follows_local_bases
+    }
+
+    /// Returns whether the joined mate sequence extends to the right of the mate position, i.e.,
+    /// `t[p[` or `[p[t`.
+    pub fn extends_right(&self) -> bool {
+        self.extends_right
+    }
+}
+
+impl FromStr for Mate {
+    type Err = ParseMateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let i = s.find(['[', ']']).ok_or(ParseMateError::MissingBracket)?;
+
+        let bracket = char::from(s.as_bytes()[i]);
+        let rest = &s[i + 1..];
+        let j = rest.find(bracket).ok_or(ParseMateError::MissingBracket)?;
+        let locus = &rest[..j];
+
+        let k = locus.rfind(':').ok_or(ParseMateError::InvalidLocus)?;
+        let (chromosome, raw_position) = (&locus[..k], &locus[k + 1..]);
+
+        if chromosome.is_empty() {
+            return Err(ParseMateError::InvalidLocus);
+        }
+
+        let position = raw_position
+            .parse()
+            .map_err(|_| ParseMateError::InvalidLocus)?;
+
+        Ok(Self {
+            chromosome: chromosome.into(),
+            position,
+            follows_local_bases: i > 0,
+            extends_right: bracket == '[',
+        })
+    }
+}
+
+/// An error returned when a raw breakend allele fails to parse as a mate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseMateError {
+    /// A bracket (`[` or `]`) is missing, e.g., for a single breakend.
+    MissingBracket,
+    /// The mate locus is invalid.
+    InvalidLocus,
+}
+
+impl error::Error for ParseMateError {}
+
+impl fmt::Display for ParseMateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBracket => f.write_str("missing bracket"),
+            Self::InvalidLocus => f.write_str("invalid locus"),
+        }
+    }
+}
+
+/// A structural variant.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructuralVariant {
+    id: Option<String>,
+    kind: Kind,
+    chromosome: String,
+    start: i64,
+    end: i64,
+    length: Option<i64>,
+    position_confidence_interval: Option<(i64, i64)>,
+    end_confidence_interval: Option<(i64, i64)>,
+    is_imprecise: bool,
+    mate: Option<Mate>,
+    mate_ids: Vec<String>,
+}
+
+impl StructuralVariant {
+    /// Creates a structural variant from a VCF record.
+    ///
+    /// The kind is taken from the first alternate allele if it is symbolic or a breakend and
+    /// otherwise from the structural variant type (`SVTYPE`). This returns `None` if the record
+    /// is not a structural variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     record::structural_variant::{Kind, StructuralVariant},
+    /// };
+    ///
+    /// let record = vcf::Record::builder()
+    ///     .set_chromosome("sq0".parse()?)
+    ///     .set_position(8)
+    ///     .set_reference_bases("A".parse()?)
+    ///     .set_alternate_bases("<DEL>".parse()?)
+    ///     .set_info("SVLEN=-5;CIPOS=-2,3;IMPRECISE".parse()?)
+    ///     .build()?;
+    ///
+    /// let sv = StructuralVariant::from_record(&record).unwrap();
+    /// assert_eq!(sv.kind(), Kind::Deletion);
+    /// assert_eq!(sv.end(), 13);
+    /// assert_eq!(sv.length(), Some(-5));
+    /// assert_eq!(sv.position_confidence_interval(), Some((-2, 3)));
+    /// assert!(sv.is_imprecise());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_record(record: &Record) -> Option<Self> {
+        let (kind, mate) = match record.alternate_bases().first() {
+            Some(Allele::Symbol(Symbol::StructuralVariant(sv))) => (Kind::from(sv.ty()), None),
+            Some(Allele::Breakend(s)) => (Kind::Breakend, s.parse().ok()),
+            _ => {
+                let kind = match find_value(record, &Key::SvType) {
+                    Some(Value::String(s)) => s.parse().ok()?,
+                    _ => return None,
+                };
+
+                (kind, None)
+            }
+        };
+
+        let length = match find_value(record, &Key::SvLengths) {
+            Some(Value::Integer(n)) => Some(i64::from(*n)),
+            Some(Value::IntegerArray(values)) => values.first().map(|&n| i64::from(n)),
+            _ => None,
+        };
+
+        let mate_ids = match find_value(record, &Key::MateBreakendIds) {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::StringArray(values)) => values.clone(),
+            _ => Vec::new(),
+        };
+
+        Some(Self {
+            id: record.ids().first().cloned(),
+            kind,
+            chromosome: record.chromosome().to_string(),
+            start: record.position(),
+            end: record.end(),
+            length,
+            position_confidence_interval: confidence_interval(
+                record,
+                &Key::PositionConfidenceIntervals,
+            ),
+            end_confidence_interval: confidence_interval(record, &Key::EndConfidenceIntervals),
+            is_imprecise: find_value(record, &Key::IsImprecise).is_some(),
+            mate,
+            mate_ids,
+        })
+    }
+
+    /// Returns the first ID of the record.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns the kind of structural variant.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Returns the chromosome.
+    pub fn chromosome(&self) -> &str {
+        &self.chromosome
+    }
+
+    /// Returns the start position, i.e., the record position.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the end position.
+    ///
+    /// See [`Record::end`].
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Returns the first SV length (`SVLEN`).
+    pub fn length(&self) -> Option<i64> {
+        self.length
+    }
+
+    /// Returns the confidence interval around the start position (`CIPOS`).
+    pub fn position_confidence_interval(&self) -> Option<(i64, i64)> {
+        self.position_confidence_interval
+    }
+
+    /// Returns the confidence interval around the end position (`CIEND`).
+    pub fn end_confidence_interval(&self) -> Option<(i64, i64)> {
+        self.end_confidence_interval
+    }
+
+    /// Returns whether the structural variant is imprecise (`IMPRECISE`).
+    pub fn is_imprecise(&self) -> bool {
+        self.is_imprecise
+    }
+
+    /// Returns the mate of a breakend.
+    ///
+    /// This is `None` if the structural variant is not a breakend or is a single breakend.
+    pub fn mate(&self) -> Option<&Mate> {
+        self.mate.as_ref()
+    }
+
+    /// Returns the IDs of the mate breakends (`MATEID`).
+    pub fn mate_ids(&self) -> &[String] {
+        &self.mate_ids
+    }
+
+    fn is_mate_of(&self, other: &Self) -> bool {
+        if let Some(id) = other.id() {
+            if self.mate_ids.iter().any(|mate_id| mate_id == id) {
+                return true;
+            }
+        }
+
+        let points_to = |a: &Self, b: &Self| {
+            a.mate()
+                .map(|m| m.chromosome() == b.chromosome() && m.position() == b.start())
+                .unwrap_or(false)
+        };
+
+        points_to(self, other) && points_to(other, self)
+    }
+}
+
+/// Pairs breakends with their mates.
+///
+/// Two breakends are mates if the mate IDs (`MATEID`) of one include the ID of the other or if
+/// each breakend allele points to the position of the other. The pairs are returned as indices
+/// into `variants`, each pair ordered and listed once.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::structural_variant::{self, StructuralVariant}};
+///
+/// let build_record = |chromosome: &str, position, alternate_bases: &str| {
+///     vcf::Record::builder()
+///         .set_chromosome(chromosome.parse().unwrap())
+///         .set_position(position)
+///         .set_reference_bases("A".parse().unwrap())
+///         .set_alternate_bases(alternate_bases.parse().unwrap())
+///         .build()
+/// };
+///
+/// let records = [
+///     build_record("sq0", 8, "A[sq1:13[")?,
+///     build_record("sq0", 21, "<DEL>")?,
+///     build_record("sq1", 13, "]sq0:8]A")?,
+/// ];
+///
+/// let variants: Vec<_> = records
+///     .iter()
+///     .filter_map(StructuralVariant::from_record)
+///     .collect();
+///
+/// assert_eq!(structural_variant::pair_mates(&variants), [(0, 2)]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn pair_mates(variants: &[StructuralVariant]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+
+    for (i, a) in variants.iter().enumerate() {
+        if a.kind() != Kind::Breakend {
+            continue;
+        }
+
+        for (j, b) in variants.iter().enumerate().skip(i + 1) {
+            if b.kind() == Kind::Breakend && (a.is_mate_of(b) || b.is_mate_of(a)) {
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    pairs
+}
+
+fn find_value<'a>(record: &'a Record, key: &Key) -> Option<&'a Value> {
+    record
+        .info()
+        .iter()
+        .find(|field| field.key() == key)
+        .map(|field| field.value())
+}
+
+fn confidence_interval(record: &Record, key: &Key) -> Option<(i64, i64)> {
+    match find_value(record, key) {
+        Some(Value::IntegerArray(values)) if values.len() == 2 => {
+            Some((i64::from(values[0]), i64::from(values[1])))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_record(position: i64, ids: &str, alternate_bases: &str, info: &str) -> Record {
+        Record::builder()
+            .set_chromosome("sq0".parse().unwrap())
+            .set_position(position)
+            .set_ids(ids.parse().unwrap())
+            .set_reference_bases("A".parse().unwrap())
+            .set_alternate_bases(alternate_bases.parse().unwrap())
+            .set_info(info.parse().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_str_for_kind() {
+        assert_eq!("DEL".parse(), Ok(Kind::Deletion));
+        assert_eq!("DUP:TANDEM".parse(), Ok(Kind::Duplication));
+        assert_eq!("BND".parse(), Ok(Kind::Breakend));
+        assert_eq!("SNV".parse::<Kind>(), Err(ParseError(String::from("SNV"))));
+    }
+
+    #[test]
+    fn test_from_str_for_mate() {
+        let mate: Mate = "G]sq1:13]".parse().unwrap();
+        assert_eq!(mate.chromosome(), "sq1");
+        assert_eq!(mate.position(), 13);
+        assert!(mate.follows_local_bases());
+        assert!(!mate.extends_right());
+
+        let mate: Mate = "[HLA-A*01:01:01:01:8[T".parse().unwrap();
+        assert_eq!(mate.chromosome(), "HLA-A*01:01:01:01");
+        assert_eq!(mate.position(), 8);
+        assert!(!mate.follows_local_bases());
+        assert!(mate.extends_right());
+
+        assert_eq!("G.".parse::<Mate>(), Err(ParseMateError::MissingBracket));
+        assert_eq!("G[sq1[".parse::<Mate>(), Err(ParseMateError::InvalidLocus));
+    }
+
+    #[test]
+    fn test_from_record() {
+        let record = build_record(8, "sv0", "A[sq1:13[", "MATEID=sv1;CIEND=-1,1");
+        let sv = StructuralVariant::from_record(&record).unwrap();
+
+        assert_eq!(sv.id(), Some("sv0"));
+        assert_eq!(sv.kind(), Kind::Breakend);
+        assert_eq!(sv.mate().map(|m| m.position()), Some(13));
+        assert_eq!(sv.mate_ids(), [String::from("sv1")]);
+        assert_eq!(sv.end_confidence_interval(), Some((-1, 1)));
+        assert!(!sv.is_imprecise());
+
+        let record = build_record(8, ".", "ACGT", "SVTYPE=INS;SVLEN=3");
+        let sv = StructuralVariant::from_record(&record).unwrap();
+        assert_eq!(sv.kind(), Kind::Insertion);
+        assert_eq!(sv.length(), Some(3));
+
+        let record = build_record(8, ".", "C", ".");
+        assert!(StructuralVariant::from_record(&record).is_none());
+    }
+
+    #[test]
+    fn test_pair_mates() {
+        let records = [
+            build_record(8, "sv0", "A[sq1:13[", "MATEID=sv1"),
+            build_record(13, "sv2", "A.", "."),
+            build_record(21, "sv1", "]sq0:5]A", "MATEID=sv0"),
+        ];
+
+        let variants: Vec<_> = records
+            .iter()
+            .filter_map(StructuralVariant::from_record)
+            .collect();
+
+        assert_eq!(pair_mates(&variants), [(0, 2)]);
+    }
+}
@@ -0,0 +1,252 @@
+//! VCF parsing from byte slices.
+//!
+//! These are entry points for parsing VCF data that is already in memory, e.g., a response body
+//! in a browser. Unlike the reader, they do not depend on [`std::io`], so they are available when
+//! the crate is built without the `io` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use noodles_vcf::slice;
+//!
+//! let data = b"##fileformat=VCFv4.3
+//! #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+//! sq0\t1\t.\tA\t.\t.\tPASS\t.
+//! ";
+//!
+//! let (header, records) = slice::parse(data)?;
+//!
+//! assert_eq!(header.file_format(), "VCFv4.3");
+//! assert_eq!(records.count(), 1);
+//! # Ok::<(), slice::ParseError>(())
+//! ```
+
+use std::{error, fmt, str};
+
+use super::{header, record, Header, Record};
+
+const HEADER_PREFIX: u8 = b'#';
+const LINE_FEED: u8 = b'\n';
+const CARRIAGE_RETURN: u8 = b'\r';
+
+/// An error returned when VCF data in a byte slice fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is not valid UTF-8.
+    InvalidUtf8(str::Utf8Error),
+    /// The header is invalid.
+    InvalidHeader(header::ParseError),
+    /// A record is invalid.
+    ///
+    /// This includes the 1-based line number of the record.
+    InvalidRecord(usize, record::ParseError),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8(e) => write!(f, "invalid UTF-8: {}", e),
+            Self::InvalidHeader(e) => write!(f, "invalid header: {}", e),
+            Self::InvalidRecord(line_number, e) => {
+                write!(f, "invalid record at line {}: {}", line_number, e)
+            }
+        }
+    }
+}
+
+/// Splits VCF data into the raw header and the remaining data.
+///
+/// The header is the leading lines that start with `#`, i.e., the meta lines and the header line
+/// (`#CHROM`...).
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::slice;
+///
+/// let data = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\nsq0\t1\t.\tA\t.\t.\tPASS\t.\n";
+/// let (header, rest) = slice::split_header(data);
+///
+/// assert_eq!(header, b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n");
+/// assert_eq!(rest, b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n");
+/// ```
+pub fn split_header(src: &[u8]) -> (&[u8], &[u8]) {
+    let mut end = 0;
+
+    while src.get(end) == Some(&HEADER_PREFIX) {
+        end = match src[end..].iter().position(|&b| b == LINE_FEED) {
+            Some(i) => end + i + 1,
+            None => src.len(),
+        };
+    }
+
+    src.split_at(end)
+}
+
+/// Parses a raw VCF header.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::slice;
+///
+/// let header = slice::parse_header(b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n")?;
+/// assert_eq!(header.file_format(), "VCFv4.3");
+/// # Ok::<(), slice::ParseError>(())
+/// ```
+pub fn parse_header(src: &[u8]) -> Result<Header, ParseError> {
+    let s = str::from_utf8(src).map_err(ParseError::InvalidUtf8)?;
+    s.parse().map_err(ParseError::InvalidHeader)
+}
+
+/// Parses a raw VCF record.
+///
+/// The input is a single line, optionally with a trailing line ending.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::slice;
+///
+/// let record = slice::parse_record(b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n")?;
+/// assert_eq!(record.position(), 1);
+/// # Ok::<(), slice::ParseError>(())
+/// ```
+pub fn parse_record(src: &[u8]) -> Result<Record, ParseError> {
+    parse_line(1, trim_line_ending(src))
+}
+
+/// Returns an iterator over records in VCF data.
+///
+/// Header lines are skipped, but the input may also start directly with records. Empty lines are
+/// skipped.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::slice;
+///
+/// let data = b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n";
+/// let mut records = slice::records(data);
+///
+/// assert!(records.next().transpose()?.is_some());
+/// assert!(records.next().is_none());
+/// # Ok::<(), slice::ParseError>(())
+/// ```
+pub fn records(src: &[u8]) -> Records<'_> {
+    let (header, rest) = split_header(src);
+    let line_number = header.iter().filter(|&&b| b == LINE_FEED).count();
+    Records::new(rest, line_number)
+}
+
+/// Parses the header of VCF data and returns it with an iterator over the records.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::slice;
+///
+/// let data = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+/// let (header, mut records) = slice::parse(data)?;
+///
+/// assert_eq!(header.file_format(), "VCFv4.3");
+/// assert!(records.next().is_none());
+/// # Ok::<(), slice::ParseError>(())
+/// ```
+pub fn parse(src: &[u8]) -> Result<(Header, Records<'_>), ParseError> {
+    let (raw_header, rest) = split_header(src);
+    let header = parse_header(raw_header)?;
+    let line_number = raw_header.iter().filter(|&&b| b == LINE_FEED).count();
+    Ok((header, Records::new(rest, line_number)))
+}
+
+/// An iterator over records in VCF data.
+///
+/// This is created by calling [`records`] or [`parse`].
+pub struct Records<'a> {
+    src: &'a [u8],
+    line_number: usize,
+}
+
+impl<'a> Records<'a> {
+    fn new(src: &'a [u8], line_number: usize) -> Self {
+        Self { src, line_number }
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.src.is_empty() {
+                return None;
+            }
+
+            let (line, rest) = match self.src.iter().position(|&b| b == LINE_FEED) {
+                Some(i) => (&self.src[..i], &self.src[i + 1..]),
+                None => (self.src, &[][..]),
+            };
+
+            self.src = rest;
+            self.line_number += 1;
+
+            let line = trim_line_ending(line);
+
+            if !line.is_empty() {
+                return Some(parse_line(self.line_number, line));
+            }
+        }
+    }
+}
+
+fn trim_line_ending(mut src: &[u8]) -> &[u8] {
+    if let Some(buf) = src.strip_suffix(&[LINE_FEED]) {
+        src = buf;
+    }
+
+    src.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(src)
+}
+
+fn parse_line(line_number: usize, src: &[u8]) -> Result<Record, ParseError> {
+    let s = str::from_utf8(src).map_err(ParseError::InvalidUtf8)?;
+    s.parse()
+        .map_err(|e| ParseError::InvalidRecord(line_number, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records() {
+        let data = b"##fileformat=VCFv4.3\r\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\r\nsq0\t1\t.\tA\t.\t.\tPASS\t.\r\n\nsq0\t8\t.\tA\t.\t.\tPASS\t.";
+        let records: Vec<_> = records(data).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].position(), 8);
+    }
+
+    #[test]
+    fn test_records_with_invalid_record() {
+        let data = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\nsq0\t1\t.\tA\t.\t.\tPASS\t.\nsq0\tnoodles\n";
+        let mut records = records(data);
+
+        assert!(records.next().transpose().unwrap().is_some());
+        assert!(matches!(
+            records.next(),
+            Some(Err(ParseError::InvalidRecord(4, _)))
+        ));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_with_invalid_utf8() {
+        assert!(matches!(
+            parse(b"##fileformat=VCFv4.3\xff\n"),
+            Err(ParseError::InvalidUtf8(_))
+        ));
+    }
+}
@@ -0,0 +1,573 @@
+//! VCF record sorting.
+//!
+//! Records are sorted by the order of their contigs in the header (`contig`) and then by
+//! position. Records at the same site keep their input order. This is the order required to
+//! compress the output with bgzip and index it with tabix.
+//!
+//! [`sort`] sorts records in memory. [`Sorter`] sorts any number of records: when the number of
+//! buffered records reaches a limit, they are sorted and written to a temporary file, and the
+//! temporary files are merged when the sorter is finished.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Write},
+    mem,
+    path::PathBuf,
+    process,
+    sync::atomic::{self, AtomicUsize},
+    vec,
+};
+
+use noodles_core::framing;
+
+use super::{Header, Record};
+
+const DEFAULT_MAX_RECORDS_IN_MEMORY: usize = 1 << 20;
+
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+type SiteKey = (usize, i64);
+
+/// Sorts records in memory.
+///
+/// This is a stable sort. It returns an error if a record is on a contig that is not in the
+/// header.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, header::Contig, sort};
+///
+/// let header = vcf::Header::builder()
+///     .add_contig(Contig::new(String::from("sq0")))
+///     .add_contig(Contig::new(String::from("sq1")))
+///     .build();
+///
+/// let build_record = |chromosome: &str, position| {
+///     vcf::Record::builder()
+///         .set_chromosome(chromosome.parse().unwrap())
+///         .set_position(position)
+///         .set_reference_bases("A".parse().unwrap())
+///         .build()
+/// };
+///
+/// let mut records = vec![
+///     build_record("sq1", 8)?,
+///     build_record("sq0", 13)?,
+///     build_record("sq0", 5)?,
+/// ];
+///
+/// sort::sort(&header, &mut records)?;
+///
+/// let sites: Vec<_> = records
+///     .iter()
+///     .map(|record| (record.chromosome().to_string(), record.position()))
+///     .collect();
+///
+/// assert_eq!(sites, [
+///     (String::from("sq0"), 5),
+///     (String::from("sq0"), 13),
+///     (String::from("sq1"), 8),
+/// ]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn sort(header: &Header, records: &mut [Record]) -> io::Result<()> {
+    let contig_indices = build_contig_indices(header);
+
+    for record in records.iter() {
+        site_key(&contig_indices, record)?;
+    }
+
+    records.sort_by_cached_key(|record| (contig_index(&contig_indices, record), record.position()));
+
+    Ok(())
+}
+
+/// A VCF record sorter that spills to temporary files.
+///
+/// Records are pushed in any order. At most [`Self::set_max_records_in_memory`] records are
+/// buffered in memory; when the buffer is full, it is sorted and written to a temporary file (a
+/// run). [`Self::finish`] returns an iterator over all records in sorted order, merging the runs.
+/// Temporary files are removed when the iterator is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// use noodles_vcf::{self as vcf, header::Contig, sort::Sorter};
+///
+/// let header = vcf::Header::builder()
+///     .add_contig(Contig::new(String::from("sq0")))
+///     .build();
+///
+/// let mut sorter = Sorter::new(&header).set_max_records_in_memory(1);
+///
+/// for position in &[13, 8, 5] {
+///     let record = vcf::Record::builder()
+///         .set_chromosome("sq0".parse().unwrap())
+///         .set_position(*position)
+///         .set_reference_bases("A".parse().unwrap())
+///         .build()
+///         .unwrap();
+///
+///     sorter.push(record)?;
+/// }
+///
+/// let positions = sorter
+///     .finish()?
+///     .map(|result| result.map(|record| record.position()))
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(positions, [5, 8, 13]);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Sorter {
+    contig_indices: HashMap<String, usize>,
+    max_records_in_memory: usize,
+    temp_dir: PathBuf,
+    buf: Vec<(SiteKey, Record)>,
+    runs: Vec<Run>,
+}
+
+impl Sorter {
+    /// Creates a sorter for records described by the given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, sort::Sorter};
+    /// let header = vcf::Header::default();
+    /// let sorter = Sorter::new(&header);
+    /// ```
+    pub fn new(header: &Header) -> Self {
+        Self {
+            contig_indices: build_contig_indices(header),
+            max_records_in_memory: DEFAULT_MAX_RECORDS_IN_MEMORY,
+            temp_dir: env::temp_dir(),
+            buf: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Sets the maximum number of records to buffer in memory.
+    ///
+    /// The default is 1048576. A value of 0 is treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, sort::Sorter};
+    /// let header = vcf::Header::default();
+    /// let sorter = Sorter::new(&header).set_max_records_in_memory(1024);
+    /// ```
+    pub fn set_max_records_in_memory(mut self, max_records_in_memory: usize) -> Self {
+        self.max_records_in_memory = max_records_in_memory.max(1);
+        self
+    }
+
+    /// Sets the directory to write temporary files to.
+    ///
+    /// The default is the system temporary directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, sort::Sorter};
+    /// let header = vcf::Header::default();
+    /// let sorter = Sorter::new(&header).set_temp_dir("/tmp");
+    /// ```
+    pub fn set_temp_dir<P>(mut self, temp_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Adds a record to the sorter.
+    ///
+    /// This returns an error if the record is on a contig that is not in the header or if a
+    /// temporary file fails to be written.
+    pub fn push(&mut self, record: Record) -> io::Result<()> {
+        let key = site_key(&self.contig_indices, &record)?;
+        self.buf.push((key, record));
+
+        if self.buf.len() >= self.max_records_in_memory {
+            self.spill()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of temporary files written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, sort::Sorter};
+    /// let header = vcf::Header::default();
+    /// let sorter = Sorter::new(&header);
+    /// assert_eq!(sorter.run_count(), 0);
+    /// ```
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Finishes sorting and returns an iterator over the records in sorted order.
+    pub fn finish(mut self) -> io::Result<Sorted> {
+        sort_buf(&mut self.buf);
+
+        let mut sources = Vec::with_capacity(self.runs.len() + 1);
+
+        for run in self.runs.drain(..) {
+            sources.push(Source::Run(run.open()?));
+        }
+
+        sources.push(Source::Memory(mem::take(&mut self.buf).into_iter()));
+
+        Sorted::new(mem::take(&mut self.contig_indices), sources)
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        sort_buf(&mut self.buf);
+
+        let id = RUN_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        let path = self
+            .temp_dir
+            .join(format!("noodles-vcf-sort-{}-{}.tmp", process::id(), id));
+
+        // The run is tracked before it is written, so the file is removed on failure.
+        let (run, file) = Run::create(path)?;
+        self.runs.push(run);
+
+        let mut writer = BufWriter::new(file);
+        let mut frame = Vec::new();
+
+        for (_, record) in self.buf.drain(..) {
+            frame.clear();
+            framing::write_frame(&mut frame, &record)?;
+            writer.write_all(&frame)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// An iterator over sorted records.
+///
+/// This is created by calling [`Sorter::finish`].
+#[derive(Debug)]
+pub struct Sorted {
+    sources: Vec<Source>,
+    heap: BinaryHeap<Reverse<HeapEntry>>,
+    contig_indices: HashMap<String, usize>,
+}
+
+impl Sorted {
+    fn new(contig_indices: HashMap<String, usize>, sources: Vec<Source>) -> io::Result<Self> {
+        let mut sorted = Self {
+            sources,
+            heap: BinaryHeap::new(),
+            contig_indices,
+        };
+
+        for i in 0..sorted.sources.len() {
+            sorted.fill(i)?;
+        }
+
+        Ok(sorted)
+    }
+
+    // Reads the next record of a source onto the heap.
+    fn fill(&mut self, i: usize) -> io::Result<()> {
+        let next = match &mut self.sources[i] {
+            Source::Memory(iter) => iter.next(),
+            Source::Run(run) => run.read_record(&self.contig_indices)?,
+        };
+
+        if let Some((key, record)) = next {
+            self.heap.push(Reverse(HeapEntry {
+                key,
+                source: i,
+                record,
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for Sorted {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(entry) = self.heap.pop()?;
+
+        match self.fill(entry.source) {
+            Ok(()) => Some(Ok(entry.record)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Source {
+    Memory(vec::IntoIter<(SiteKey, Record)>),
+    Run(Run),
+}
+
+// A sorted run in a temporary file. The file is removed when the run is dropped.
+#[derive(Debug)]
+struct Run {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+}
+
+impl Run {
+    // The file is created exclusively so that an existing file is never truncated or, when the
+    // run is dropped, removed.
+    fn create(path: PathBuf) -> io::Result<(Self, File)> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        Ok((Self { path, reader: None }, file))
+    }
+
+    fn open(mut self) -> io::Result<Self> {
+        self.reader = Some(File::open(&self.path).map(BufReader::new)?);
+        Ok(self)
+    }
+
+    fn read_record(
+        &mut self,
+        contig_indices: &HashMap<String, usize>,
+    ) -> io::Result<Option<(SiteKey, Record)>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+
+        let mut buf = Vec::new();
+
+        match framing::read_frame_from::<_, Record>(reader, &mut buf)? {
+            Some(record) => {
+                // Records in runs were checked when they were pushed.
+                let key = (contig_index(contig_indices, &record), record.position());
+                Ok(Some((key, record)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        self.reader = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[derive(Debug)]
+struct HeapEntry {
+    key: SiteKey,
+    source: usize,
+    record: Record,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    // Ties are broken by source, which are in input order, to keep the sort stable.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.key, self.source).cmp(&(other.key, other.source))
+    }
+}
+
+fn build_contig_indices(header: &Header) -> HashMap<String, usize> {
+    header
+        .contigs()
+        .iter()
+        .enumerate()
+        .map(|(i, contig)| (contig.id().into(), i))
+        .collect()
+}
+
+fn site_key(contig_indices: &HashMap<String, usize>, record: &Record) -> io::Result<SiteKey> {
+    let name = record.chromosome().to_string();
+
+    let i = contig_indices.get(&name).copied().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("missing contig: {}", name),
+        )
+    })?;
+
+    Ok((i, record.position()))
+}
+
+fn contig_index(contig_indices: &HashMap<String, usize>, record: &Record) -> usize {
+    contig_indices
+        .get(&record.chromosome().to_string())
+        .copied()
+        .unwrap_or(usize::MAX)
+}
+
+// Stable sort by site.
+fn sort_buf(buf: &mut [(SiteKey, Record)]) {
+    buf.sort_by_key(|(key, _)| *key);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::Contig;
+
+    use super::*;
+
+    fn build_header() -> Header {
+        Header::builder()
+            .add_contig(Contig::new(String::from("sq1")))
+            .add_contig(Contig::new(String::from("sq0")))
+            .build()
+    }
+
+    fn build_record(chromosome: &str, position: i64, id: &str) -> Record {
+        Record::builder()
+            .set_chromosome(chromosome.parse().unwrap())
+            .set_position(position)
+            .set_ids(id.parse().unwrap())
+            .set_reference_bases("A".parse().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn sites(records: &[Record]) -> Vec<(String, i64, String)> {
+        records
+            .iter()
+            .map(|record| {
+                (
+                    record.chromosome().to_string(),
+                    record.position(),
+                    record.ids().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn build_records() -> Vec<Record> {
+        vec![
+            build_record("sq0", 8, "r0"),
+            build_record("sq1", 13, "r1"),
+            build_record("sq0", 5, "r2"),
+            build_record("sq1", 2, "r3"),
+            build_record("sq0", 8, "r4"),
+            build_record("sq1", 21, "r5"),
+            build_record("sq0", 1, "r6"),
+        ]
+    }
+
+    fn expected_sites() -> Vec<(String, i64, String)> {
+        vec![
+            (String::from("sq1"), 2, String::from("r3")),
+            (String::from("sq1"), 13, String::from("r1")),
+            (String::from("sq1"), 21, String::from("r5")),
+            (String::from("sq0"), 1, String::from("r6")),
+            (String::from("sq0"), 5, String::from("r2")),
+            (String::from("sq0"), 8, String::from("r0")),
+            (String::from("sq0"), 8, String::from("r4")),
+        ]
+    }
+
+    #[test]
+    fn test_sort() -> io::Result<()> {
+        let header = build_header();
+
+        let mut records = build_records();
+        sort(&header, &mut records)?;
+        assert_eq!(sites(&records), expected_sites());
+
+        let mut records = vec![build_record("sq2", 1, "r0")];
+        assert_eq!(
+            sort(&header, &mut records).map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorter() -> io::Result<()> {
+        let header = build_header();
+
+        for &max_records_in_memory in &[1, 2, 3, 100] {
+            let mut sorter = Sorter::new(&header).set_max_records_in_memory(max_records_in_memory);
+
+            for record in build_records() {
+                sorter.push(record)?;
+            }
+
+            let expected_run_count = 7 / max_records_in_memory;
+            assert_eq!(sorter.run_count(), expected_run_count);
+
+            let paths: Vec<_> = sorter.runs.iter().map(|run| run.path.clone()).collect();
+
+            let records = sorter.finish()?.collect::<io::Result<Vec<_>>>()?;
+            assert_eq!(sites(&records), expected_sites());
+
+            for path in paths {
+                assert!(!path.exists());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_create_with_existing_file() -> io::Result<()> {
+        let path = env::temp_dir().join(format!(
+            "noodles-vcf-sort-test-{}-existing.tmp",
+            process::id()
+        ));
+        fs::write(&path, b"noodles")?;
+
+        assert_eq!(
+            Run::create(path.clone()).map(|_| ()).map_err(|e| e.kind()),
+            Err(io::ErrorKind::AlreadyExists)
+        );
+
+        assert_eq!(fs::read(&path)?, b"noodles");
+        fs::remove_file(&path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorter_with_missing_contig() {
+        let header = build_header();
+        let mut sorter = Sorter::new(&header);
+
+        assert_eq!(
+            sorter
+                .push(build_record("sq2", 1, "r0"))
+                .map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+    }
+}
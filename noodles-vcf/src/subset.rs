@@ -0,0 +1,439 @@
+//! VCF sample subsetting.
+
+use std::{convert::TryFrom, error, fmt};
+
+use super::{
+    record::{
+        allele_counts, format,
+        genotype::{self, field::Key},
+        info, Format, Genotype, Info,
+    },
+    Header, Record,
+};
+
+/// How the allele count information fields (`AC`, `AN`, and `AF`) are handled when subsetting.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AlleleCounts {
+    /// Keep the fields as-is.
+    Keep,
+    /// Recompute the existing fields from the genotypes of the selected samples.
+    #[default]
+    Recompute,
+    /// Remove the fields.
+    Drop,
+}
+
+/// A VCF sample subsetter.
+///
+/// A subsetter selects a list of samples from VCF records, in the given order, and rewrites the
+/// genotype columns accordingly. Optionally, the genotype fields can be projected to a list of
+/// format keys.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, record::{Format, Genotype}, subset::Subsetter};
+///
+/// let header = vcf::Header::builder()
+///     .add_sample_name("sample0")
+///     .add_sample_name("sample1")
+///     .build();
+///
+/// let subsetter = Subsetter::new(&header, ["sample1"])?;
+/// assert_eq!(subsetter.header().sample_names(), [String::from("sample1")]);
+///
+/// let format: Format = "GT".parse()?;
+/// let record = vcf::Record::builder()
+///     .set_chromosome("sq0".parse()?)
+///     .set_position(1)
+///     .set_reference_bases("A".parse()?)
+///     .set_format(format.clone())
+///     .add_genotype(Genotype::from_str_format("0|0", &format)?)
+///     .add_genotype(Genotype::from_str_format("0|1", &format)?)
+///     .build()?;
+///
+/// let record = subsetter.subset(&record)?;
+/// assert_eq!(record.genotypes(), [Genotype::from_str_format("0|1", &format)?]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Subsetter {
+    header: Header,
+    sample_indices: Vec<usize>,
+    format_keys: Option<Vec<Key>>,
+    allele_counts: AlleleCounts,
+}
+
+/// An error returned when a VCF sample subsetter fails to be created.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NewError {
+    /// A sample name is not in the header.
+    MissingSample(String),
+    /// A sample name is selected more than once.
+    DuplicateSample(String),
+}
+
+impl error::Error for NewError {}
+
+impl fmt::Display for NewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSample(name) => write!(f, "missing sample: {}", name),
+            Self::DuplicateSample(name) => write!(f, "duplicate sample: {}", name),
+        }
+    }
+}
+
+/// An error returned when a VCF record fails to be subset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubsetError {
+    /// The record is missing the genotype for the sample at the given index.
+    MissingGenotype(usize),
+    /// The projected format is invalid.
+    InvalidFormat(format::TryFromKeyVectorError),
+    /// A projected genotype is invalid.
+    InvalidGenotype(genotype::TryFromFieldsError),
+}
+
+impl error::Error for SubsetError {}
+
+impl fmt::Display for SubsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingGenotype(i) => write!(f, "missing genotype for sample {}", i),
+            Self::InvalidFormat(e) => write!(f, "invalid format: {}", e),
+            Self::InvalidGenotype(e) => write!(f, "invalid genotype: {}", e),
+        }
+    }
+}
+
+impl Subsetter {
+    /// Creates a subsetter that selects the given sample names from records described by the
+    /// given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, subset::{NewError, Subsetter}};
+    ///
+    /// let header = vcf::Header::builder().add_sample_name("sample0").build();
+    ///
+    /// assert!(Subsetter::new(&header, ["sample0"]).is_ok());
+    /// assert_eq!(
+    ///     Subsetter::new(&header, ["sample1"]).unwrap_err(),
+    ///     NewError::MissingSample(String::from("sample1"))
+    /// );
+    /// ```
+    pub fn new<I, S>(header: &Header, sample_names: I) -> Result<Self, NewError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut sample_indices = Vec::new();
+        let mut selected_sample_names = Vec::new();
+
+        for sample_name in sample_names {
+            let name = sample_name.as_ref();
+
+            let i = header
+                .sample_names()
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| NewError::MissingSample(name.into()))?;
+
+            if sample_indices.contains(&i) {
+                return Err(NewError::DuplicateSample(name.into()));
+            }
+
+            sample_indices.push(i);
+            selected_sample_names.push(name.into());
+        }
+
+        let mut header = header.clone();
+        *header.sample_names_mut() = selected_sample_names;
+
+        Ok(Self {
+            header,
+            sample_indices,
+            format_keys: None,
+            allele_counts: AlleleCounts::default(),
+        })
+    }
+
+    /// Sets how the allele count information fields are handled.
+    ///
+    /// By default, the existing fields are recomputed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, subset::{AlleleCounts, Subsetter}};
+    /// let header = vcf::Header::default();
+    /// let subsetter = Subsetter::new(&header, Vec::<String>::new())?
+    ///     .set_allele_counts(AlleleCounts::Drop);
+    /// # Ok::<(), noodles_vcf::subset::NewError>(())
+    /// ```
+    pub fn set_allele_counts(mut self, allele_counts: AlleleCounts) -> Self {
+        self.allele_counts = allele_counts;
+        self
+    }
+
+    /// Sets the format keys to keep in the genotype columns.
+    ///
+    /// By default, all genotype fields are kept. Since the genotype (`GT`) must be the first
+    /// field, it must be included if any field is to be kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, record::genotype::field::Key, subset::Subsetter};
+    /// let header = vcf::Header::default();
+    /// let subsetter = Subsetter::new(&header, Vec::<String>::new())?
+    ///     .set_format_keys(vec![Key::Genotype]);
+    /// # Ok::<(), noodles_vcf::subset::NewError>(())
+    /// ```
+    pub fn set_format_keys(mut self, format_keys: Vec<Key>) -> Self {
+        self.format_keys = Some(format_keys);
+        self
+    }
+
+    /// Returns the header with only the selected sample names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{self as vcf, subset::Subsetter};
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .build();
+    ///
+    /// let subsetter = Subsetter::new(&header, ["sample1", "sample0"])?;
+    ///
+    /// assert_eq!(subsetter.header().sample_names(), [
+    ///     String::from("sample1"),
+    ///     String::from("sample0"),
+    /// ]);
+    /// # Ok::<(), noodles_vcf::subset::NewError>(())
+    /// ```
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns a copy of the given record with only the genotypes of the selected samples.
+    pub fn subset(&self, record: &Record) -> Result<Record, SubsetError> {
+        let mut record = record.clone();
+
+        let mut genotypes = self
+            .sample_indices
+            .iter()
+            .map(|&i| {
+                record
+                    .genotypes()
+                    .get(i)
+                    .cloned()
+                    .ok_or(SubsetError::MissingGenotype(i))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match self.allele_counts {
+            AlleleCounts::Keep => {}
+            AlleleCounts::Recompute => {
+                let counts = allele_counts::AlleleCounts::from_genotypes(
+                    &genotypes,
+                    record.alternate_bases().len(),
+                );
+
+                let info = allele_counts::recompute_info(record.info(), &counts);
+                *record.info_mut() = info;
+            }
+            AlleleCounts::Drop => {
+                let info = drop_allele_counts(record.info());
+                *record.info_mut() = info;
+            }
+        }
+
+        let mut format = record.format().cloned();
+
+        if let (Some(keys), Some(f)) = (&self.format_keys, format.as_ref()) {
+            let projected_keys: Vec<_> = f.iter().filter(|k| keys.contains(k)).cloned().collect();
+
+            if projected_keys.is_empty() {
+                format = None;
+                genotypes.clear();
+            } else {
+                format = Format::try_from(projected_keys)
+                    .map(Some)
+                    .map_err(SubsetError::InvalidFormat)?;
+
+                genotypes = genotypes
+                    .into_iter()
+                    .map(|genotype| project_genotype(&genotype, keys))
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        if genotypes.is_empty() {
+            format = None;
+        }
+
+        *record.format_mut() = format;
+        *record.genotypes_mut() = genotypes;
+
+        Ok(record)
+    }
+}
+
+fn project_genotype(genotype: &Genotype, keys: &[Key]) -> Result<Genotype, SubsetError> {
+    let fields: Vec<_> = genotype
+        .iter()
+        .filter(|field| keys.contains(field.key()))
+        .cloned()
+        .collect();
+
+    Genotype::try_from(fields).map_err(SubsetError::InvalidGenotype)
+}
+
+fn is_allele_count_key(key: &info::field::Key) -> bool {
+    use info::field::Key;
+
+    matches!(
+        key,
+        Key::AlleleCount | Key::TotalAlleleCount | Key::AlleleFrequencies
+    )
+}
+
+fn drop_allele_counts(info: &Info) -> Info {
+    let fields: Vec<_> = info
+        .iter()
+        .filter(|field| !is_allele_count_key(field.key()))
+        .cloned()
+        .collect();
+
+    Info::from(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_header() -> Header {
+        Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .add_sample_name("sample2")
+            .build()
+    }
+
+    fn build_record() -> Result<Record, Box<dyn std::error::Error>> {
+        let format: Format = "GT:GQ".parse()?;
+
+        let record = Record::builder()
+            .set_chromosome("sq0".parse()?)
+            .set_position(1)
+            .set_reference_bases("A".parse()?)
+            .set_alternate_bases("C".parse()?)
+            .set_info("NS=3;AC=3;AN=6;AF=0.5".parse()?)
+            .set_format(format.clone())
+            .add_genotype(Genotype::from_str_format("0|1:13", &format)?)
+            .add_genotype(Genotype::from_str_format("1|1:21", &format)?)
+            .add_genotype(Genotype::from_str_format("0|0:34", &format)?)
+            .build()?;
+
+        Ok(record)
+    }
+
+    #[test]
+    fn test_new() {
+        let header = build_header();
+
+        assert!(Subsetter::new(&header, ["sample2", "sample0"]).is_ok());
+
+        assert_eq!(
+            Subsetter::new(&header, ["sample3"]).unwrap_err(),
+            NewError::MissingSample(String::from("sample3"))
+        );
+
+        assert_eq!(
+            Subsetter::new(&header, ["sample0", "sample0"]).unwrap_err(),
+            NewError::DuplicateSample(String::from("sample0"))
+        );
+    }
+
+    #[test]
+    fn test_subset() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_header();
+        let record = build_record()?;
+
+        let subsetter = Subsetter::new(&header, ["sample2", "sample0"])?;
+        let actual = subsetter.subset(&record)?;
+
+        let format = record.format().unwrap();
+        let expected_genotypes = [
+            Genotype::from_str_format("0|0:34", format)?,
+            Genotype::from_str_format("0|1:13", format)?,
+        ];
+
+        assert_eq!(actual.genotypes(), expected_genotypes);
+        assert_eq!(actual.info().to_string(), "NS=3;AC=1;AN=4;AF=0.25");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subset_with_allele_counts_policies() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_header();
+        let record = build_record()?;
+
+        let subsetter = Subsetter::new(&header, ["sample1"])?.set_allele_counts(AlleleCounts::Keep);
+        let actual = subsetter.subset(&record)?;
+        assert_eq!(actual.info(), record.info());
+
+        let subsetter = Subsetter::new(&header, ["sample1"])?.set_allele_counts(AlleleCounts::Drop);
+        let actual = subsetter.subset(&record)?;
+        assert_eq!(actual.info().to_string(), "NS=3");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subset_with_format_keys() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_header();
+        let record = build_record()?;
+
+        let subsetter = Subsetter::new(&header, ["sample1"])?.set_format_keys(vec![Key::Genotype]);
+        let actual = subsetter.subset(&record)?;
+
+        let format: Format = "GT".parse()?;
+        assert_eq!(actual.format(), Some(&format));
+        assert_eq!(
+            actual.genotypes(),
+            [Genotype::from_str_format("1|1", &format)?]
+        );
+
+        let subsetter = Subsetter::new(&header, ["sample1"])?
+            .set_format_keys(vec![Key::ConditionalGenotypeQuality]);
+        assert!(matches!(
+            subsetter.subset(&record),
+            Err(SubsetError::InvalidFormat(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subset_with_no_samples() -> Result<(), Box<dyn std::error::Error>> {
+        let header = build_header();
+        let record = build_record()?;
+
+        let subsetter = Subsetter::new(&header, Vec::<String>::new())?;
+        let actual = subsetter.subset(&record)?;
+
+        assert!(actual.format().is_none());
+        assert!(actual.genotypes().is_empty());
+        assert_eq!(actual.info().to_string(), "NS=3;AC=0;AN=0");
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::{self, Write};
 
-use super::{Header, Record};
+use super::{record, Header, Record};
 
 /// A VCF writer.
 ///
@@ -69,6 +69,40 @@ where
         &self.inner
     }
 
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// This can be used to finish a compressed stream, e.g., to write the BGZF EOF marker and
+    /// check for errors, which are otherwise ignored when the writer is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bgzf as bgzf;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut writer = vcf::Writer::new(bgzf::Writer::new(Vec::new()));
+    /// writer.write_header(&vcf::Header::default())?;
+    /// writer.get_mut().try_finish()?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf as vcf;
+    /// let writer = vcf::Writer::new(Vec::new());
+    /// assert!(writer.into_inner().is_empty());
+    /// ```
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
     /// Writes a VCF header.
     ///
     /// # Examples
@@ -105,27 +139,7 @@ where
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
-        write!(
-            self.inner,
-            "{chrom}\t{pos}\t{id}\t{ref}\t{alt}\t{qual}\t{filter}\t{info}",
-            chrom = record.chromosome(),
-            pos = record.position(),
-            id = record.ids(),
-            r#ref = record.reference_bases(),
-            alt = record.alternate_bases(),
-            qual = record.quality_score(),
-            filter = record.filter_status(),
-            info = record.info(),
-        )?;
-
-        if let Some(format) = record.format() {
-            write!(self.inner, "\t{}", format)?;
-
-            for field in record.genotypes() {
-                write!(self.inner, "\t{}", field)?;
-            }
-        }
-
+        record::write_record(&mut self.inner, record)?;
         writeln!(self.inner)
     }
 }
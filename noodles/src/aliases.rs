@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+
+use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+
+use super::{region::ParseError, Region};
+
+// UCSC, Ensembl/GRC, and RefSeq names of the GRCh37 (hg19) primary assembly.
+static GRCH37_NAMES: &[[&str; 3]] = &[
+    ["chr1", "1", "NC_000001.10"],
+    ["chr2", "2", "NC_000002.11"],
+    ["chr3", "3", "NC_000003.11"],
+    ["chr4", "4", "NC_000004.11"],
+    ["chr5", "5", "NC_000005.9"],
+    ["chr6", "6", "NC_000006.11"],
+    ["chr7", "7", "NC_000007.13"],
+    ["chr8", "8", "NC_000008.10"],
+    ["chr9", "9", "NC_000009.11"],
+    ["chr10", "10", "NC_000010.10"],
+    ["chr11", "11", "NC_000011.9"],
+    ["chr12", "12", "NC_000012.11"],
+    ["chr13", "13", "NC_000013.10"],
+    ["chr14", "14", "NC_000014.8"],
+    ["chr15", "15", "NC_000015.9"],
+    ["chr16", "16", "NC_000016.9"],
+    ["chr17", "17", "NC_000017.10"],
+    ["chr18", "18", "NC_000018.9"],
+    ["chr19", "19", "NC_000019.9"],
+    ["chr20", "20", "NC_000020.10"],
+    ["chr21", "21", "NC_000021.8"],
+    ["chr22", "22", "NC_000022.10"],
+    ["chrX", "X", "NC_000023.10"],
+    ["chrY", "Y", "NC_000024.9"],
+    ["chrM", "MT", "NC_012920.1"],
+];
+
+// UCSC, Ensembl/GRC, and RefSeq names of the GRCh38 (hg38) primary assembly.
+static GRCH38_NAMES: &[[&str; 3]] = &[
+    ["chr1", "1", "NC_000001.11"],
+    ["chr2", "2", "NC_000002.12"],
+    ["chr3", "3", "NC_000003.12"],
+    ["chr4", "4", "NC_000004.12"],
+    ["chr5", "5", "NC_000005.10"],
+    ["chr6", "6", "NC_000006.12"],
+    ["chr7", "7", "NC_000007.14"],
+    ["chr8", "8", "NC_000008.11"],
+    ["chr9", "9", "NC_000009.12"],
+    ["chr10", "10", "NC_000010.11"],
+    ["chr11", "11", "NC_000011.10"],
+    ["chr12", "12", "NC_000012.12"],
+    ["chr13", "13", "NC_000013.11"],
+    ["chr14", "14", "NC_000014.9"],
+    ["chr15", "15", "NC_000015.10"],
+    ["chr16", "16", "NC_000016.10"],
+    ["chr17", "17", "NC_000017.11"],
+    ["chr18", "18", "NC_000018.10"],
+    ["chr19", "19", "NC_000019.10"],
+    ["chr20", "20", "NC_000020.11"],
+    ["chr21", "21", "NC_000021.9"],
+    ["chr22", "22", "NC_000022.11"],
+    ["chrX", "X", "NC_000023.11"],
+    ["chrY", "Y", "NC_000024.10"],
+    ["chrM", "MT", "NC_012920.1"],
+];
+
+/// A table of reference sequence name aliases.
+///
+/// Aliases are groups of names that refer to the same reference sequence, e.g., `chr1`, `1`, and
+/// `NC_000001.11`. Aliasing is transitive: adding `a = b` and `b = c` makes `a`, `b`, and `c`
+/// aliases of each other.
+///
+/// The table is used to resolve names from one naming convention to the one used by a
+/// reference sequence dictionary, e.g., to query a BAM file that uses `chr1` with a region on
+/// `1`, or to match reference sequences of BAM, VCF, and FASTA files that use different naming
+/// conventions.
+///
+/// # Examples
+///
+/// ```
+/// use noodles::Aliases;
+///
+/// let aliases = Aliases::grch38();
+/// assert!(aliases.is_alias("chr1", "1"));
+/// assert!(aliases.is_alias("MT", "chrM"));
+/// assert!(!aliases.is_alias("chr1", "chr2"));
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Aliases {
+    groups: HashMap<String, usize>,
+    next_group: usize,
+}
+
+impl Aliases {
+    /// Creates an empty alias table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// let aliases = Aliases::new();
+    /// assert!(aliases.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an alias table with the UCSC (`chr1`), Ensembl/GRC (`1`), and RefSeq
+    /// (`NC_000001.10`) names of the GRCh37 (hg19) chromosomes.
+    ///
+    /// Note that the hg19 `chrM` is a different sequence (NC_001807) than the GRCh37 `MT`
+    /// (NC_012920), but they are aliased for convenience. They have different lengths, which
+    /// [`Self::map_reference_sequences`] checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// let aliases = Aliases::grch37();
+    /// assert!(aliases.is_alias("chrX", "NC_000023.10"));
+    /// ```
+    pub fn grch37() -> Self {
+        Self::from_names(GRCH37_NAMES)
+    }
+
+    /// Creates an alias table with the UCSC (`chr1`), Ensembl/GRC (`1`), and RefSeq
+    /// (`NC_000001.11`) names of the GRCh38 (hg38) chromosomes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// let aliases = Aliases::grch38();
+    /// assert!(aliases.is_alias("chrX", "NC_000023.11"));
+    /// ```
+    pub fn grch38() -> Self {
+        Self::from_names(GRCH38_NAMES)
+    }
+
+    fn from_names(names: &[[&str; 3]]) -> Self {
+        let mut aliases = Self::new();
+
+        for [ucsc_name, grc_name, refseq_name] in names {
+            aliases.insert(ucsc_name, grc_name);
+            aliases.insert(ucsc_name, refseq_name);
+        }
+
+        aliases
+    }
+
+    /// Returns the number of names in the table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    ///
+    /// let mut aliases = Aliases::new();
+    /// aliases.insert("chr1", "1");
+    ///
+    /// assert_eq!(aliases.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns whether the table has no names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// let aliases = Aliases::new();
+    /// assert!(aliases.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Adds two names as aliases of each other.
+    ///
+    /// If either name already has aliases, the groups are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    ///
+    /// let mut aliases = Aliases::new();
+    /// aliases.insert("chr1", "1");
+    /// aliases.insert("1", "NC_000001.11");
+    ///
+    /// assert!(aliases.is_alias("chr1", "NC_000001.11"));
+    /// ```
+    pub fn insert(&mut self, a: &str, b: &str) {
+        match (self.groups.get(a).copied(), self.groups.get(b).copied()) {
+            (Some(group_a), Some(group_b)) => {
+                if group_a != group_b {
+                    for group in self.groups.values_mut() {
+                        if *group == group_b {
+                            *group = group_a;
+                        }
+                    }
+                }
+            }
+            (Some(group), None) => {
+                self.groups.insert(b.into(), group);
+            }
+            (None, Some(group)) => {
+                self.groups.insert(a.into(), group);
+            }
+            (None, None) => {
+                let group = self.next_group;
+                self.next_group += 1;
+                self.groups.insert(a.into(), group);
+                self.groups.insert(b.into(), group);
+            }
+        }
+    }
+
+    /// Returns whether two names refer to the same reference sequence.
+    ///
+    /// A name is always an alias of itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    ///
+    /// let mut aliases = Aliases::new();
+    /// aliases.insert("chr1", "1");
+    ///
+    /// assert!(aliases.is_alias("chr1", "1"));
+    /// assert!(aliases.is_alias("chr2", "chr2"));
+    /// assert!(!aliases.is_alias("chr2", "2"));
+    /// ```
+    pub fn is_alias(&self, a: &str, b: &str) -> bool {
+        if a == b {
+            return true;
+        }
+
+        match (self.groups.get(a), self.groups.get(b)) {
+            (Some(group_a), Some(group_b)) => group_a == group_b,
+            _ => false,
+        }
+    }
+
+    /// Resolves a name to the name used in a reference sequence dictionary.
+    ///
+    /// If the name is in the dictionary, it is returned as is. Otherwise, the first reference
+    /// sequence in the dictionary that is an alias of the name is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences =
+    ///     vec![(String::from("chr1"), ReferenceSequence::new(String::from("chr1"), 8))]
+    ///         .into_iter()
+    ///         .collect();
+    ///
+    /// let aliases = Aliases::grch38();
+    /// assert_eq!(aliases.resolve("1", &reference_sequences), Some("chr1"));
+    /// assert_eq!(aliases.resolve("2", &reference_sequences), None);
+    /// ```
+    pub fn resolve<'a>(
+        &self,
+        name: &str,
+        reference_sequences: &'a ReferenceSequences,
+    ) -> Option<&'a str> {
+        if let Some((resolved_name, _)) = reference_sequences.get_key_value(name) {
+            return Some(resolved_name);
+        }
+
+        let group = self.groups.get(name)?;
+
+        reference_sequences
+            .keys()
+            .find(|candidate| self.groups.get(candidate.as_str()) == Some(group))
+            .map(|resolved_name| resolved_name.as_str())
+    }
+
+    /// Resolves the reference sequence name of a region to the name used in a reference sequence
+    /// dictionary.
+    ///
+    /// Unmapped regions and regions representing all records are returned as is. This returns
+    /// `None` if the reference sequence name cannot be resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Aliases, Region};
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences =
+    ///     vec![(String::from("chrM"), ReferenceSequence::new(String::from("chrM"), 16569))]
+    ///         .into_iter()
+    ///         .collect();
+    ///
+    /// let aliases = Aliases::grch38();
+    ///
+    /// assert_eq!(
+    ///     aliases.resolve_region(&Region::mapped("MT", 8, 13), &reference_sequences),
+    ///     Some(Region::mapped("chrM", 8, 13))
+    /// );
+    /// ```
+    pub fn resolve_region(
+        &self,
+        region: &Region,
+        reference_sequences: &ReferenceSequences,
+    ) -> Option<Region> {
+        match region {
+            Region::Mapped { name, start, end } => self
+                .resolve(name, reference_sequences)
+                .map(|resolved_name| Region::mapped(resolved_name, *start, *end)),
+            Region::Unmapped | Region::All => Some(region.clone()),
+        }
+    }
+
+    /// Parses a string to a region, resolving its reference sequence name.
+    ///
+    /// This is the same as [`Region::from_str_reference_sequences`] but also accepts aliases of
+    /// the names in the reference sequence dictionary. The resulting region uses the name in the
+    /// dictionary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Aliases, Region};
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences =
+    ///     vec![(String::from("chr1"), ReferenceSequence::new(String::from("chr1"), 8))]
+    ///         .into_iter()
+    ///         .collect();
+    ///
+    /// let aliases = Aliases::grch38();
+    ///
+    /// assert_eq!(
+    ///     aliases.parse_region("1:3-5", &reference_sequences),
+    ///     Ok(Region::mapped("chr1", 3, 5))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     aliases.parse_region("1", &reference_sequences),
+    ///     Ok(Region::mapped("chr1", 1, 8))
+    /// );
+    /// ```
+    pub fn parse_region(
+        &self,
+        s: &str,
+        reference_sequences: &ReferenceSequences,
+    ) -> Result<Region, ParseError> {
+        match Region::from_str_reference_sequences(s, reference_sequences) {
+            Err(ParseError::Invalid) => {}
+            result => return result,
+        }
+
+        // Adds the aliases of each reference sequence to a copy of the dictionary, so the
+        // region parser can match them.
+        let mut aliased_reference_sequences = ReferenceSequences::new();
+
+        for (name, reference_sequence) in reference_sequences {
+            if let Some(group) = self.groups.get(name) {
+                for (alias, _) in self.groups.iter().filter(|(_, g)| *g == group) {
+                    aliased_reference_sequences
+                        .entry(alias.clone())
+                        .or_insert_with(|| {
+                            ReferenceSequence::new(alias.clone(), reference_sequence.len())
+                        });
+                }
+            }
+        }
+
+        Region::from_str_reference_sequences(s, &aliased_reference_sequences).map(|region| {
+            self.resolve_region(&region, reference_sequences)
+                .unwrap_or(region)
+        })
+    }
+
+    /// Maps each reference sequence in one dictionary to an equivalent one in another.
+    ///
+    /// Two reference sequences are equivalent if their names are aliases and their lengths are
+    /// equal. The returned list is parallel to `src` and holds the index of the equivalent
+    /// reference sequence in `dst`, if any.
+    ///
+    /// This can be used to check whether, e.g., the dictionaries of a BAM file and a VCF file
+    /// that use different naming conventions are compatible, or to translate reference sequence
+    /// IDs between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::Aliases;
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let build = |entries: &[(&str, i32)]| -> ReferenceSequences {
+    ///     entries
+    ///         .iter()
+    ///         .map(|&(name, len)| (name.into(), ReferenceSequence::new(name.into(), len)))
+    ///         .collect()
+    /// };
+    ///
+    /// let src = build(&[("chr1", 13), ("chr2", 8), ("chrM", 16571)]);
+    /// let dst = build(&[("1", 13), ("2", 8), ("MT", 16569)]);
+    ///
+    /// let aliases = Aliases::grch37();
+    /// assert_eq!(aliases.map_reference_sequences(&src, &dst), [Some(0), Some(1), None]);
+    /// ```
+    pub fn map_reference_sequences(
+        &self,
+        src: &ReferenceSequences,
+        dst: &ReferenceSequences,
+    ) -> Vec<Option<usize>> {
+        src.values()
+            .map(|reference_sequence| {
+                self.resolve(reference_sequence.name(), dst)
+                    .and_then(|name| dst.get_full(name))
+                    .filter(|(_, _, candidate)| candidate.len() == reference_sequence.len())
+                    .map(|(i, _, _)| i)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_reference_sequences(entries: &[(&str, i32)]) -> ReferenceSequences {
+        entries
+            .iter()
+            .map(|&(name, len)| (name.into(), ReferenceSequence::new(name.into(), len)))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut aliases = Aliases::new();
+        aliases.insert("a", "b");
+        aliases.insert("c", "d");
+        assert!(!aliases.is_alias("a", "d"));
+
+        aliases.insert("b", "c");
+        assert!(aliases.is_alias("a", "d"));
+        assert_eq!(aliases.len(), 4);
+    }
+
+    #[test]
+    fn test_builtins() {
+        for aliases in &[Aliases::grch37(), Aliases::grch38()] {
+            assert_eq!(aliases.len(), 75);
+
+            for n in 1..=22 {
+                assert!(aliases.is_alias(&format!("chr{}", n), &n.to_string()));
+            }
+
+            assert!(aliases.is_alias("chrX", "X"));
+            assert!(aliases.is_alias("chrY", "Y"));
+            assert!(aliases.is_alias("chrM", "MT"));
+            assert!(aliases.is_alias("MT", "NC_012920.1"));
+        }
+
+        assert!(Aliases::grch37().is_alias("1", "NC_000001.10"));
+        assert!(!Aliases::grch37().is_alias("1", "NC_000001.11"));
+        assert!(Aliases::grch38().is_alias("1", "NC_000001.11"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let reference_sequences = build_reference_sequences(&[("chr1", 8), ("2", 13)]);
+        let aliases = Aliases::grch38();
+
+        assert_eq!(aliases.resolve("chr1", &reference_sequences), Some("chr1"));
+        assert_eq!(aliases.resolve("1", &reference_sequences), Some("chr1"));
+        assert_eq!(
+            aliases.resolve("NC_000001.11", &reference_sequences),
+            Some("chr1")
+        );
+        assert_eq!(aliases.resolve("chr2", &reference_sequences), Some("2"));
+        assert_eq!(aliases.resolve("chr3", &reference_sequences), None);
+        assert_eq!(aliases.resolve("sq0", &reference_sequences), None);
+    }
+
+    #[test]
+    fn test_parse_region() {
+        let reference_sequences = build_reference_sequences(&[("chr1", 8), ("2", 13)]);
+        let aliases = Aliases::grch38();
+
+        assert_eq!(
+            aliases.parse_region("chr1:3-5", &reference_sequences),
+            Ok(Region::mapped("chr1", 3, 5))
+        );
+        assert_eq!(
+            aliases.parse_region("chr2:5", &reference_sequences),
+            Ok(Region::mapped("2", 5, 13))
+        );
+        assert_eq!(
+            aliases.parse_region("*", &reference_sequences),
+            Ok(Region::Unmapped)
+        );
+        assert_eq!(
+            aliases.parse_region("chr3", &reference_sequences),
+            Err(ParseError::Invalid)
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Genome build detection.
+
+use std::{error, fmt, str::FromStr};
+
+use noodles_sam::header::ReferenceSequences;
+
+use super::Aliases;
+
+// The hg19 mitochondrial sequence (NC_001807) is 2 bases longer than the revised Cambridge
+// Reference Sequence (NC_012920) used by GRCh37.
+const HG19_CHRM_LENGTH: i32 = 16571;
+
+// Names and lengths of chromosomes 1, 2, and X of each build. The lengths are distinct across
+// builds.
+static CHROMOSOMES: &[(GenomeBuild, [(&str, i32); 3])] = &[
+    (
+        GenomeBuild::Grch37,
+        [("1", 249250621), ("2", 243199373), ("X", 155270560)],
+    ),
+    (
+        GenomeBuild::Grch38,
+        [("1", 248956422), ("2", 242193529), ("X", 156040895)],
+    ),
+    (
+        GenomeBuild::T2tChm13,
+        [("1", 248387328), ("2", 242696752), ("X", 154259566)],
+    ),
+    (
+        GenomeBuild::Grcm38,
+        [("1", 195471971), ("2", 182113224), ("X", 171031299)],
+    ),
+    (
+        GenomeBuild::Grcm39,
+        [("1", 195154279), ("2", 181755017), ("X", 169476592)],
+    ),
+];
+
+// MD5 checksums (`M5`) of chromosome 1.
+static MD5_CHECKSUMS: &[(GenomeBuild, &str)] = &[
+    (GenomeBuild::Grch37, "1b22b98cdeb4a9304cb5d48026a85128"),
+    (GenomeBuild::Grch38, "6aef897c3d6ff0c78aff06ac189178dd"),
+];
+
+/// A genome build.
+///
+/// The UCSC hg38 and mm10/mm39 assemblies have the same sequences as GRCh38 and GRCm38/GRCm39,
+/// respectively, and are not distinguished. hg19 differs from GRCh37 in its mitochondrial
+/// sequence.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GenomeBuild {
+    /// Human GRCh37.
+    Grch37,
+    /// Human hg19 (UCSC).
+    Hg19,
+    /// Human GRCh38 (hg38).
+    Grch38,
+    /// Human T2T-CHM13v2.0.
+    T2tChm13,
+    /// Mouse GRCm38 (mm10).
+    Grcm38,
+    /// Mouse GRCm39 (mm39).
+    Grcm39,
+}
+
+impl GenomeBuild {
+    /// Detects the genome build of a reference sequence dictionary.
+    ///
+    /// This is a heuristic. Each reference sequence votes for a build when its name and length
+    /// match a known chromosome of the build, when its MD5 checksum (`M5`) matches a known
+    /// checksum, or when its assembly ID (`AS`) names the build. Chromosome names are matched
+    /// using the build's alias table (see [`Self::aliases`]), so UCSC (`chr1`), Ensembl/GRC
+    /// (`1`), and RefSeq (`NC_000001.11`) names are recognized. Builds without an alias table
+    /// match UCSC and Ensembl/GRC names. GRCh37 is reported as hg19 when the dictionary has the
+    /// hg19 mitochondrial sequence or uses UCSC (`chr`) names.
+    ///
+    /// This returns `None` if no build matches or if the best matches are tied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::GenomeBuild;
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences = vec![
+    ///     (String::from("1"), ReferenceSequence::new(String::from("1"), 248956422)),
+    ///     (String::from("X"), ReferenceSequence::new(String::from("X"), 156040895)),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// assert_eq!(GenomeBuild::detect(&reference_sequences), Some(GenomeBuild::Grch38));
+    /// assert_eq!(GenomeBuild::detect(&ReferenceSequences::new()), None);
+    /// ```
+    pub fn detect(reference_sequences: &ReferenceSequences) -> Option<Self> {
+        let aliases: Vec<_> = CHROMOSOMES
+            .iter()
+            .map(|(build, chromosomes)| {
+                build.aliases().unwrap_or_else(|| ucsc_aliases(chromosomes))
+            })
+            .collect();
+
+        let mut votes = vec![0; CHROMOSOMES.len()];
+
+        for reference_sequence in reference_sequences.values() {
+            let name = reference_sequence.name();
+            let len = reference_sequence.len();
+
+            for (i, (_, chromosomes)) in CHROMOSOMES.iter().enumerate() {
+                if chromosomes
+                    .iter()
+                    .any(|&(n, l)| l == len && aliases[i].is_alias(name, n))
+                {
+                    votes[i] += 1;
+                }
+            }
+
+            if let Some(md5_checksum) = reference_sequence.md5_checksum() {
+                let md5_checksum = md5_checksum.to_string();
+
+                for (build, _) in MD5_CHECKSUMS.iter().filter(|(_, c)| *c == md5_checksum) {
+                    votes[family_index(*build)] += 1;
+                }
+            }
+
+            if let Some(build) = reference_sequence
+                .assemby_id()
+                .and_then(|id| id.parse::<Self>().ok())
+            {
+                votes[family_index(build)] += 1;
+            }
+        }
+
+        let max_votes = votes.iter().copied().max().unwrap_or(0);
+
+        if max_votes == 0 || votes.iter().filter(|&&n| n == max_votes).count() > 1 {
+            return None;
+        }
+
+        let i = votes.iter().position(|&n| n == max_votes)?;
+
+        match CHROMOSOMES[i].0 {
+            Self::Grch37 if is_hg19(reference_sequences) => Some(Self::Hg19),
+            build => Some(build),
+        }
+    }
+
+    /// Returns the built-in alias table for the build, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::GenomeBuild;
+    ///
+    /// let aliases = GenomeBuild::Grch38.aliases().unwrap();
+    /// assert!(aliases.is_alias("chr1", "1"));
+    ///
+    /// assert!(GenomeBuild::Grcm39.aliases().is_none());
+    /// ```
+    pub fn aliases(self) -> Option<Aliases> {
+        match self {
+            Self::Grch37 | Self::Hg19 => Some(Aliases::grch37()),
+            Self::Grch38 => Some(Aliases::grch38()),
+            Self::T2tChm13 | Self::Grcm38 | Self::Grcm39 => None,
+        }
+    }
+}
+
+// Returns an alias table that maps the UCSC (`chr`) names of the chromosomes to their
+// Ensembl/GRC names.
+fn ucsc_aliases(chromosomes: &[(&str, i32)]) -> Aliases {
+    let mut aliases = Aliases::new();
+
+    for (name, _) in chromosomes {
+        aliases.insert(&format!("chr{}", name), name);
+    }
+
+    aliases
+}
+
+// Returns the index of the build in `CHROMOSOMES`. hg19 is in the GRCh37 family.
+fn family_index(build: GenomeBuild) -> usize {
+    let build = match build {
+        GenomeBuild::Hg19 => GenomeBuild::Grch37,
+        build => build,
+    };
+
+    CHROMOSOMES
+        .iter()
+        .position(|(b, _)| *b == build)
+        .expect("missing build")
+}
+
+fn is_hg19(reference_sequences: &ReferenceSequences) -> bool {
+    let chromosomes = &CHROMOSOMES[family_index(GenomeBuild::Grch37)].1;
+
+    reference_sequences.values().any(|reference_sequence| {
+        let name = reference_sequence.name();
+        let len = reference_sequence.len();
+
+        (name == "chrM" && len == HG19_CHRM_LENGTH)
+            || (name.starts_with("chr") && chromosomes.iter().any(|&(_, l)| l == len))
+    })
+}
+
+impl fmt::Display for GenomeBuild {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Grch37 => "GRCh37",
+            Self::Hg19 => "hg19",
+            Self::Grch38 => "GRCh38",
+            Self::T2tChm13 => "T2T-CHM13",
+            Self::Grcm38 => "GRCm38",
+            Self::Grcm39 => "GRCm39",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// An error returned when a raw genome build fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The input is invalid.
+    Invalid,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::Invalid => f.write_str("invalid input"),
+        }
+    }
+}
+
+impl FromStr for GenomeBuild {
+    type Err = ParseError;
+
+    /// Parses a genome build name.
+    ///
+    /// Names are case-insensitive. Common synonyms are accepted, e.g., `b37` for GRCh37, `hg38`
+    /// for GRCh38, and `mm10` for GRCm38.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "grch37" | "b37" | "hs37d5" => Ok(Self::Grch37),
+            "hg19" => Ok(Self::Hg19),
+            "grch38" | "hg38" => Ok(Self::Grch38),
+            "t2t-chm13" | "t2t-chm13v2.0" | "chm13" | "hs1" => Ok(Self::T2tChm13),
+            "grcm38" | "mm10" => Ok(Self::Grcm38),
+            "grcm39" | "mm39" => Ok(Self::Grcm39),
+            _ => Err(ParseError::Invalid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::header::{reference_sequence::Md5Checksum, ReferenceSequence};
+
+    use super::*;
+
+    fn build_reference_sequences(entries: &[(&str, i32)]) -> ReferenceSequences {
+        entries
+            .iter()
+            .map(|&(name, len)| (name.into(), ReferenceSequence::new(name.into(), len)))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        let detect =
+            |entries: &[(&str, i32)]| GenomeBuild::detect(&build_reference_sequences(entries));
+
+        assert_eq!(
+            detect(&[("1", 249250621), ("2", 243199373), ("MT", 16569)]),
+            Some(GenomeBuild::Grch37)
+        );
+        assert_eq!(
+            detect(&[("chr1", 249250621), ("chrM", 16571)]),
+            Some(GenomeBuild::Hg19)
+        );
+        assert_eq!(
+            detect(&[("chr1", 248956422), ("chrM", 16569)]),
+            Some(GenomeBuild::Grch38)
+        );
+        assert_eq!(
+            detect(&[("NC_000001.11", 248956422)]),
+            Some(GenomeBuild::Grch38)
+        );
+        assert_eq!(
+            detect(&[("chr1", 248387328), ("chrX", 154259566)]),
+            Some(GenomeBuild::T2tChm13)
+        );
+        assert_eq!(detect(&[("1", 195471971)]), Some(GenomeBuild::Grcm38));
+        assert_eq!(detect(&[("chr1", 195154279)]), Some(GenomeBuild::Grcm39));
+
+        assert_eq!(detect(&[("sq0", 8)]), None);
+
+        // Lengths only match chromosomes with the same name.
+        assert_eq!(detect(&[("sq0", 248956422)]), None);
+        assert_eq!(detect(&[("2", 248956422)]), None);
+        assert_eq!(detect(&[("NC_000001.10", 248956422)]), None);
+
+        // Ties are ambiguous.
+        assert_eq!(detect(&[("1", 249250621), ("2", 242193529)]), None);
+    }
+
+    #[test]
+    fn test_detect_with_md5_checksum_and_assembly_id() -> Result<(), Box<dyn std::error::Error>> {
+        let md5_checksum: Md5Checksum = "6aef897c3d6ff0c78aff06ac189178dd".parse()?;
+
+        let reference_sequence = ReferenceSequence::builder()
+            .set_name("sq0")
+            .set_length(8)
+            .set_md5_checksum(md5_checksum)
+            .build();
+
+        let reference_sequences: ReferenceSequences =
+            vec![(String::from("sq0"), reference_sequence)]
+                .into_iter()
+                .collect();
+
+        assert_eq!(
+            GenomeBuild::detect(&reference_sequences),
+            Some(GenomeBuild::Grch38)
+        );
+
+        let reference_sequence = ReferenceSequence::builder()
+            .set_name("sq0")
+            .set_length(8)
+            .set_assembly_id("mm10")
+            .build();
+
+        let reference_sequences: ReferenceSequences =
+            vec![(String::from("sq0"), reference_sequence)]
+                .into_iter()
+                .collect();
+
+        assert_eq!(
+            GenomeBuild::detect(&reference_sequences),
+            Some(GenomeBuild::Grcm38)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(GenomeBuild::Grch38.to_string(), "GRCh38");
+        assert_eq!(GenomeBuild::Hg19.to_string(), "hg19");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("GRCh37".parse(), Ok(GenomeBuild::Grch37));
+        assert_eq!("hg19".parse(), Ok(GenomeBuild::Hg19));
+        assert_eq!("hg38".parse(), Ok(GenomeBuild::Grch38));
+        assert_eq!("CHM13".parse(), Ok(GenomeBuild::T2tChm13));
+        assert_eq!("mm10".parse(), Ok(GenomeBuild::Grcm38));
+        assert_eq!("GRCm39".parse(), Ok(GenomeBuild::Grcm39));
+
+        assert_eq!("".parse::<GenomeBuild>(), Err(ParseError::Empty));
+        assert_eq!("hg17".parse::<GenomeBuild>(), Err(ParseError::Invalid));
+    }
+}
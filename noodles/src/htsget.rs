@@ -0,0 +1,490 @@
+//! htsget client.
+//!
+//! [htsget] is a protocol to stream slices of alignment and variant files over HTTP. A request for
+//! a region of a file is resolved to a ticket, which lists the URLs of the data blocks that make
+//! up the slice. The blocks are concatenated to form a complete file in the requested format, so
+//! the result can be read with the existing readers, e.g., `noodles_bam::Reader` or
+//! `noodles_vcf::Reader` (after BGZF decompression).
+//!
+//! [htsget]: https://samtools.github.io/hts-specs/htsget.html
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use std::io::{self, Read};
+//! use noodles::{htsget, Region};
+//!
+//! let client = htsget::Client::new("https://htsget.example.com/")?;
+//! let region = Region::mapped("sq0", 8, 13);
+//!
+//! let mut reader = client.query("sample", htsget::Format::Bam, Some(&region))?;
+//!
+//! let mut buf = Vec::new();
+//! reader.read_to_end(&mut buf)?;
+//! # Ok::<(), io::Error>(())
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Cursor, Read},
+};
+
+use reqwest::{
+    blocking::{self, Response},
+    header::ACCEPT,
+    IntoUrl, Url,
+};
+use serde::Deserialize;
+
+use super::Region;
+
+static TICKET_MEDIA_TYPE: &str = "application/vnd.ga4gh.htsget.v1.2.0+json";
+
+static DATA_URL_SCHEME: &str = "data:";
+static DATA_URL_BASE64_SUFFIX: &str = ";base64";
+
+static UNMAPPED_REFERENCE_NAME: &str = "*";
+
+/// An htsget data format.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Format {
+    /// BAM.
+    Bam,
+    /// CRAM.
+    Cram,
+    /// VCF.
+    Vcf,
+    /// BCF.
+    Bcf,
+}
+
+impl Format {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Bam => "BAM",
+            Self::Cram => "CRAM",
+            Self::Vcf => "VCF",
+            Self::Bcf => "BCF",
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Bam | Self::Cram => "reads",
+            Self::Vcf | Self::Bcf => "variants",
+        }
+    }
+}
+
+/// The class of an htsget data block.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Class {
+    /// The block contains only header data.
+    Header,
+    /// The block contains record data.
+    Body,
+}
+
+/// The location of an htsget data block.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct BlockUrl {
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    class: Option<Class>,
+}
+
+impl BlockUrl {
+    /// Returns the URL of the block.
+    ///
+    /// This is either an HTTP(S) URL or a data URL with inline data.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the HTTP headers to send when fetching the block.
+    pub fn headers(&self) -> &HashMap<String, String> {
+        &self.headers
+    }
+
+    /// Returns the class of the block, if set.
+    pub fn class(&self) -> Option<Class> {
+        self.class
+    }
+}
+
+/// An htsget ticket.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Ticket {
+    format: Option<Format>,
+    urls: Vec<BlockUrl>,
+    md5: Option<String>,
+}
+
+impl Ticket {
+    /// Returns the format of the data, if set.
+    pub fn format(&self) -> Option<Format> {
+        self.format
+    }
+
+    /// Returns the locations of the data blocks.
+    pub fn urls(&self) -> &[BlockUrl] {
+        &self.urls
+    }
+
+    /// Returns the MD5 checksum of the concatenated data, if set.
+    pub fn md5(&self) -> Option<&str> {
+        self.md5.as_deref()
+    }
+}
+
+#[derive(Deserialize)]
+struct TicketResponse {
+    htsget: Ticket,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    htsget: ErrorBody,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+    message: Option<String>,
+}
+
+/// An htsget client.
+pub struct Client {
+    http_client: blocking::Client,
+    base_url: Url,
+}
+
+impl Client {
+    /// Creates an htsget client with a default HTTP client.
+    ///
+    /// The base URL is the URL of the htsget service, i.e., the URL to which `reads/<id>` and
+    /// `variants/<id>` are appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles::htsget;
+    /// let client = htsget::Client::new("https://htsget.example.com/")?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new<U>(base_url: U) -> io::Result<Self>
+    where
+        U: IntoUrl,
+    {
+        let base_url = base_url
+            .into_url()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(Self::with_client(blocking::Client::new(), base_url))
+    }
+
+    /// Creates an htsget client with the given HTTP client.
+    ///
+    /// This can be used to configure the client, e.g., to set authorization headers.
+    pub fn with_client(http_client: blocking::Client, base_url: Url) -> Self {
+        Self {
+            http_client,
+            base_url,
+        }
+    }
+
+    /// Requests a ticket for a slice of a file.
+    ///
+    /// If no region is given, the entire file is requested.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io;
+    /// use noodles::htsget;
+    ///
+    /// let client = htsget::Client::new("https://htsget.example.com/")?;
+    /// let ticket = client.ticket("sample", htsget::Format::Vcf, None)?;
+    ///
+    /// for block_url in ticket.urls() {
+    ///     println!("{}", block_url.url());
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn ticket(&self, id: &str, format: Format, region: Option<&Region>) -> io::Result<Ticket> {
+        let url = build_ticket_url(&self.base_url, id, format, region)?;
+
+        let response = self
+            .http_client
+            .get(url)
+            .header(ACCEPT, TICKET_MEDIA_TYPE)
+            .send()
+            .map_err(into_io_error)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+
+            let message = match response.json::<ErrorResponse>() {
+                Ok(e) => match e.htsget.message {
+                    Some(message) => format!("{}: {}", e.htsget.error, message),
+                    None => e.htsget.error,
+                },
+                Err(_) => format!("unexpected HTTP status: {}", status),
+            };
+
+            return Err(io::Error::other(message));
+        }
+
+        response
+            .json::<TicketResponse>()
+            .map(|r| r.htsget)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Returns a reader over the concatenated data blocks of a ticket.
+    ///
+    /// Blocks are fetched one at a time as the reader is read.
+    pub fn blocks(&self, ticket: Ticket) -> Blocks<'_> {
+        Blocks {
+            client: self,
+            urls: ticket.urls.into_iter(),
+            current_block: None,
+        }
+    }
+
+    /// Requests a slice of a file and returns a reader over its data.
+    ///
+    /// This is a convenience method for [`Self::ticket`] and [`Self::blocks`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::{self, Read};
+    /// use noodles::{htsget, Region};
+    ///
+    /// let client = htsget::Client::new("https://htsget.example.com/")?;
+    /// let mut reader = client.query("sample", htsget::Format::Bam, Some(&Region::Unmapped))?;
+    ///
+    /// let mut buf = Vec::new();
+    /// reader.read_to_end(&mut buf)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn query(
+        &self,
+        id: &str,
+        format: Format,
+        region: Option<&Region>,
+    ) -> io::Result<Blocks<'_>> {
+        self.ticket(id, format, region)
+            .map(|ticket| self.blocks(ticket))
+    }
+
+    fn fetch(&self, block_url: &BlockUrl) -> io::Result<Block> {
+        if block_url.url.starts_with(DATA_URL_SCHEME) {
+            return decode_data_url(&block_url.url).map(|data| Block::Data(Cursor::new(data)));
+        }
+
+        let mut request = self.http_client.get(&block_url.url);
+
+        for (name, value) in &block_url.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let response = request.send().map_err(into_io_error)?;
+
+        if response.status().is_success() {
+            Ok(Block::Http(response))
+        } else {
+            Err(io::Error::other(format!(
+                "unexpected HTTP status: {}",
+                response.status()
+            )))
+        }
+    }
+}
+
+enum Block {
+    Data(Cursor<Vec<u8>>),
+    Http(Response),
+}
+
+impl Read for Block {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Data(data) => data.read(buf),
+            Self::Http(response) => response.read(buf),
+        }
+    }
+}
+
+/// A reader over the concatenated data blocks of an htsget ticket.
+///
+/// This is created by calling [`Client::blocks`] or [`Client::query`].
+pub struct Blocks<'a> {
+    client: &'a Client,
+    urls: std::vec::IntoIter<BlockUrl>,
+    current_block: Option<Block>,
+}
+
+impl<'a> Read for Blocks<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if let Some(block) = self.current_block.as_mut() {
+                match block.read(buf)? {
+                    0 => self.current_block = None,
+                    n => return Ok(n),
+                }
+            }
+
+            match self.urls.next() {
+                Some(block_url) => self.current_block = Some(self.client.fetch(&block_url)?),
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+fn build_ticket_url(
+    base_url: &Url,
+    id: &str,
+    format: Format,
+    region: Option<&Region>,
+) -> io::Result<Url> {
+    let mut url = base_url.clone();
+
+    url.path_segments_mut()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid base URL"))?
+        .pop_if_empty()
+        .push(format.endpoint())
+        .push(id);
+
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("format", format.as_str());
+
+        match region {
+            // htsget intervals are 0-based, half-open.
+            Some(Region::Mapped { name, start, end }) => {
+                query
+                    .append_pair("referenceName", name)
+                    .append_pair("start", &(start - 1).to_string())
+                    .append_pair("end", &end.to_string());
+            }
+            Some(Region::Unmapped) => {
+                query.append_pair("referenceName", UNMAPPED_REFERENCE_NAME);
+            }
+            Some(Region::All) | None => {}
+        }
+    }
+
+    Ok(url)
+}
+
+fn decode_data_url(url: &str) -> io::Result<Vec<u8>> {
+    let s = &url[DATA_URL_SCHEME.len()..];
+
+    let i = s
+        .find(',')
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid data URL"))?;
+
+    let (metadata, data) = (&s[..i], &s[i + 1..]);
+
+    if metadata.ends_with(DATA_URL_BASE64_SUFFIX) {
+        base64::decode(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unsupported data URL encoding",
+        ))
+    }
+}
+
+fn into_io_error(e: reqwest::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_ticket_url() -> Result<(), Box<dyn std::error::Error>> {
+        let base_url = Url::parse("https://htsget.example.com/v1/")?;
+
+        let url = build_ticket_url(&base_url, "sample", Format::Bam, None)?;
+        assert_eq!(
+            url.as_str(),
+            "https://htsget.example.com/v1/reads/sample?format=BAM"
+        );
+
+        let region = Region::mapped("sq0", 8, 13);
+        let url = build_ticket_url(&base_url, "sample", Format::Vcf, Some(&region))?;
+        assert_eq!(
+            url.as_str(),
+            "https://htsget.example.com/v1/variants/sample?format=VCF&referenceName=sq0&start=7&end=13"
+        );
+
+        let url = build_ticket_url(&base_url, "sample", Format::Cram, Some(&Region::Unmapped))?;
+        assert_eq!(
+            url.as_str(),
+            "https://htsget.example.com/v1/reads/sample?format=CRAM&referenceName=*"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_ticket() -> serde_json::Result<()> {
+        let data = r#"{
+            "htsget": {
+                "format": "BAM",
+                "urls": [
+                    { "url": "data:application/vnd.ga4gh.bam;base64,QkFNAQ==", "class": "header" },
+                    {
+                        "url": "https://example.com/sample.bam",
+                        "headers": { "Range": "bytes=65536-1003750" },
+                        "class": "body"
+                    }
+                ]
+            }
+        }"#;
+
+        let response: TicketResponse = serde_json::from_str(data)?;
+        let ticket = response.htsget;
+
+        assert_eq!(ticket.format(), Some(Format::Bam));
+        assert!(ticket.md5().is_none());
+
+        let urls = ticket.urls();
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].class(), Some(Class::Header));
+        assert!(urls[0].headers().is_empty());
+        assert_eq!(
+            urls[1].headers().get("Range").map(|s| s.as_str()),
+            Some("bytes=65536-1003750")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_data_url() -> io::Result<()> {
+        assert_eq!(
+            decode_data_url("data:application/vnd.ga4gh.bam;base64,QkFNAQ==")?,
+            b"BAM\x01"
+        );
+
+        assert!(decode_data_url("data:text/plain,BAM").is_err());
+        assert!(decode_data_url("data:QkFNAQ==").is_err());
+
+        Ok(())
+    }
+}
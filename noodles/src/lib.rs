@@ -1,3 +1,11 @@
-mod region;
+mod aliases;
+pub mod genome_build;
+#[cfg(feature = "htsget")]
+pub mod htsget;
+pub mod partition;
+pub mod region;
+mod region_set;
 
-pub use self::region::Region;
+pub use self::{
+    aliases::Aliases, genome_build::GenomeBuild, region::Region, region_set::RegionSet,
+};
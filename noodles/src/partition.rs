@@ -0,0 +1,355 @@
+//! Genomic interval partitioning for scatter-gather.
+//!
+//! A partition splits the reference sequences of a dictionary, or a set of target regions on
+//! them, into shards of roughly equal weight. Each shard is a list of regions that can be
+//! processed independently, e.g., by a task in a workflow engine, and the results gathered in
+//! shard order.
+//!
+//! Shards are contiguous: concatenating the shards in order yields the input regions in
+//! reference sequence dictionary order. A region may be split across two shards.
+
+use std::cmp;
+
+use noodles_sam::header::ReferenceSequences;
+
+use super::{Region, RegionSet};
+
+// Position coordinates are 1-based.
+const MIN_POSITION: i64 = 1;
+
+struct Segment<'a> {
+    name: &'a str,
+    start: i64,
+    end: i64,
+    weight: f64,
+}
+
+/// Partitions reference sequences into shards of roughly equal length.
+///
+/// If `targets` is given, only the target regions are partitioned. Targets on reference
+/// sequences not in the dictionary are ignored.
+///
+/// This returns at most `n` shards. Fewer are returned if there are not enough bases to fill
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// use noodles::{partition, Region};
+/// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+///
+/// let reference_sequences: ReferenceSequences = vec![
+///     (String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 8)),
+///     (String::from("sq1"), ReferenceSequence::new(String::from("sq1"), 4)),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// assert_eq!(
+///     partition::by_length(&reference_sequences, None, 2),
+///     [
+///         vec![Region::mapped("sq0", 1, 6)],
+///         vec![Region::mapped("sq0", 7, 8), Region::mapped("sq1", 1, 4)],
+///     ]
+/// );
+/// ```
+pub fn by_length(
+    reference_sequences: &ReferenceSequences,
+    targets: Option<&RegionSet>,
+    n: usize,
+) -> Vec<Vec<Region>> {
+    let segments = intervals(reference_sequences, targets)
+        .map(|(_, name, start, end)| Segment {
+            name,
+            start,
+            end,
+            weight: (end - start + 1) as f64,
+        })
+        .collect();
+
+    partition(segments, n)
+}
+
+/// Partitions reference sequences into shards of roughly equal weight.
+///
+/// The weight of each reference sequence is given by a list of weights of consecutive windows
+/// of `window_size` bases, e.g., estimated record counts from an index. `weights` is parallel to
+/// the reference sequence dictionary. Missing weights are 0. Weights are assumed to be uniformly
+/// distributed within a window.
+///
+/// If `targets` is given, only the target regions are partitioned. Targets on reference
+/// sequences not in the dictionary are ignored.
+///
+/// This returns at most `n` shards. Fewer are returned if there is not enough weight to fill
+/// them.
+///
+/// # Panics
+///
+/// This panics if `window_size` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use noodles::{partition, Region};
+/// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+///
+/// let reference_sequences: ReferenceSequences = vec![
+///     (String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 12)),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// let weights = [vec![8, 0, 8]];
+///
+/// assert_eq!(
+///     partition::by_window_weights(&reference_sequences, None, 4, &weights, 2),
+///     [vec![Region::mapped("sq0", 1, 8)], vec![Region::mapped("sq0", 9, 12)]]
+/// );
+/// ```
+pub fn by_window_weights(
+    reference_sequences: &ReferenceSequences,
+    targets: Option<&RegionSet>,
+    window_size: i64,
+    weights: &[Vec<u64>],
+    n: usize,
+) -> Vec<Vec<Region>> {
+    assert!(window_size > 0, "invalid window size: {}", window_size);
+
+    let mut segments = Vec::new();
+
+    for (id, name, start, end) in intervals(reference_sequences, targets) {
+        let window_weights = weights.get(id).map(|w| &w[..]).unwrap_or(&[]);
+
+        let mut window_start = start;
+
+        while window_start <= end {
+            let i = ((window_start - MIN_POSITION) / window_size) as usize;
+            let window_end = cmp::min((i as i64 + 1) * window_size, end);
+
+            let window_weight = window_weights.get(i).copied().unwrap_or(0) as f64;
+            let len = window_end - window_start + 1;
+
+            segments.push(Segment {
+                name,
+                start: window_start,
+                end: window_end,
+                weight: window_weight * (len as f64) / (window_size as f64),
+            });
+
+            window_start = window_end + 1;
+        }
+    }
+
+    partition(segments, n)
+}
+
+// Returns the intervals to partition in reference sequence dictionary order, with the index of
+// their reference sequences.
+fn intervals<'a>(
+    reference_sequences: &'a ReferenceSequences,
+    targets: Option<&'a RegionSet>,
+) -> impl Iterator<Item = (usize, &'a str, i64, i64)> + 'a {
+    reference_sequences
+        .iter()
+        .enumerate()
+        .flat_map(move |(id, (name, reference_sequence))| {
+            let len = i64::from(reference_sequence.len());
+
+            let intervals: Vec<_> = match targets {
+                Some(targets) => targets
+                    .intervals(name)
+                    .iter()
+                    .map(|&(start, end)| (cmp::max(start, MIN_POSITION), cmp::min(end, len)))
+                    .filter(|(start, end)| start <= end)
+                    .collect(),
+                None if len >= MIN_POSITION => vec![(MIN_POSITION, len)],
+                None => Vec::new(),
+            };
+
+            intervals
+                .into_iter()
+                .map(move |(start, end)| (id, name.as_str(), start, end))
+        })
+}
+
+// Greedily fills shards in order, splitting a segment when it crosses a shard boundary.
+fn partition(segments: Vec<Segment<'_>>, n: usize) -> Vec<Vec<Region>> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let total_weight: f64 = segments.iter().map(|segment| segment.weight).sum();
+
+    let mut shards = vec![Vec::new(); n];
+    let mut i = 0;
+    let mut cumulative_weight = 0.0;
+
+    for segment in segments {
+        let mut start = segment.start;
+        let mut weight = segment.weight;
+
+        loop {
+            let boundary = total_weight * ((i + 1) as f64) / (n as f64);
+
+            if i + 1 == n || cumulative_weight + weight <= boundary {
+                push(&mut shards[i], segment.name, start, segment.end);
+                cumulative_weight += weight;
+                break;
+            }
+
+            let len = segment.end - start + 1;
+            let taken_len = ((boundary - cumulative_weight) / weight * (len as f64)).round() as i64;
+
+            if taken_len >= len {
+                push(&mut shards[i], segment.name, start, segment.end);
+                cumulative_weight += weight;
+                break;
+            } else if taken_len > 0 {
+                let end = start + taken_len - 1;
+                push(&mut shards[i], segment.name, start, end);
+
+                let taken_weight = weight * (taken_len as f64) / (len as f64);
+                cumulative_weight += taken_weight;
+                weight -= taken_weight;
+
+                start = end + 1;
+            }
+
+            i += 1;
+        }
+    }
+
+    shards.retain(|shard| !shard.is_empty());
+    shards
+}
+
+// Adds a region to a shard, extending the last region if they are adjacent.
+fn push(shard: &mut Vec<Region>, name: &str, start: i64, end: i64) {
+    if let Some(Region::Mapped {
+        name: last_name,
+        end: last_end,
+        ..
+    }) = shard.last_mut()
+    {
+        if last_name == name && *last_end + 1 == start {
+            *last_end = end;
+            return;
+        }
+    }
+
+    shard.push(Region::mapped(name, start, end));
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::header::ReferenceSequence;
+
+    use super::*;
+
+    fn build_reference_sequences() -> ReferenceSequences {
+        vec![("sq0", 100), ("sq1", 50), ("sq2", 50)]
+            .into_iter()
+            .map(|(name, len)| {
+                (
+                    String::from(name),
+                    ReferenceSequence::new(String::from(name), len),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_by_length() {
+        let reference_sequences = build_reference_sequences();
+
+        assert_eq!(
+            by_length(&reference_sequences, None, 4),
+            [
+                vec![Region::mapped("sq0", 1, 50)],
+                vec![Region::mapped("sq0", 51, 100)],
+                vec![Region::mapped("sq1", 1, 50)],
+                vec![Region::mapped("sq2", 1, 50)],
+            ]
+        );
+
+        assert_eq!(
+            by_length(&reference_sequences, None, 1),
+            [vec![
+                Region::mapped("sq0", 1, 100),
+                Region::mapped("sq1", 1, 50),
+                Region::mapped("sq2", 1, 50),
+            ]]
+        );
+
+        assert!(by_length(&reference_sequences, None, 0).is_empty());
+    }
+
+    #[test]
+    fn test_by_length_with_targets() {
+        let reference_sequences = build_reference_sequences();
+
+        let targets: RegionSet = vec![
+            Region::mapped("sq2", 41, 60),
+            Region::mapped("sq0", 1, 10),
+            Region::mapped("sq9", 1, 10),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            by_length(&reference_sequences, Some(&targets), 2),
+            [
+                vec![Region::mapped("sq0", 1, 10)],
+                vec![Region::mapped("sq2", 41, 50)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_length_with_more_shards_than_bases() {
+        let reference_sequences = build_reference_sequences();
+        let targets: RegionSet = vec![Region::mapped("sq1", 8, 9)].into_iter().collect();
+
+        assert_eq!(
+            by_length(&reference_sequences, Some(&targets), 4),
+            [
+                vec![Region::mapped("sq1", 8, 8)],
+                vec![Region::mapped("sq1", 9, 9)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_window_weights() {
+        let reference_sequences = build_reference_sequences();
+
+        // sq0 has all of its weight in the first 10 bases. sq1 and sq2 have no weights.
+        let weights = [vec![30, 0, 0, 0, 0, 0, 0, 0, 0, 0]];
+
+        assert_eq!(
+            by_window_weights(&reference_sequences, None, 10, &weights, 3),
+            [
+                vec![Region::mapped("sq0", 1, 3)],
+                vec![Region::mapped("sq0", 4, 7)],
+                vec![
+                    Region::mapped("sq0", 8, 100),
+                    Region::mapped("sq1", 1, 50),
+                    Region::mapped("sq2", 1, 50),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partition_covers_input() {
+        let reference_sequences = build_reference_sequences();
+
+        for n in 1..=16 {
+            let shards = by_length(&reference_sequences, None, n);
+            assert!(shards.len() <= n);
+
+            let regions: RegionSet = shards.into_iter().flatten().collect();
+            assert_eq!(regions.base_count(), 200);
+        }
+    }
+}
@@ -1,9 +1,11 @@
-use std::{error, fmt, num};
+//! Genomic region and validation.
+
+use std::{cmp, error, fmt, num};
 
 use noodles_sam::header::ReferenceSequences;
 
 // Position coordinates are 1-based.
-const MIN_POSITION: i32 = 1;
+const MIN_POSITION: i64 = 1;
 
 static UNMAPPED_NAME: &str = "*";
 static ALL_NAME: &str = ".";
@@ -14,7 +16,7 @@ static ALL_NAME: &str = ".";
 /// all reads (.).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Region {
-    Mapped { name: String, start: i32, end: i32 },
+    Mapped { name: String, start: i64, end: i64 },
     Unmapped,
     All,
 }
@@ -52,7 +54,7 @@ impl Region {
                     if reference_sequences.contains_key(s) {
                         return Err(ParseError::Ambiguous);
                     } else {
-                        let resolved_end = end.unwrap_or(reference_sequence.len() as i32);
+                        let resolved_end = end.unwrap_or(reference_sequence.len() as i64);
                         return Ok(Self::mapped(prefix, start, resolved_end));
                     }
                 }
@@ -60,7 +62,7 @@ impl Region {
         }
 
         if let Some(reference_sequence) = reference_sequences.get(s) {
-            let end = reference_sequence.len() as i32;
+            let end = reference_sequence.len() as i64;
             Ok(Self::mapped(s, MIN_POSITION, end))
         } else {
             Err(ParseError::Invalid)
@@ -73,7 +75,9 @@ impl Region {
     /// reference sequence `name`. When `end` is `None`, most analyses will assume the end to be
     /// unbounded, i.e., until the end of the reference sequence.
     ///
-    /// Positions are assumed to be 1-based.
+    /// Positions are assumed to be 1-based. They are 64-bit integers to allow regions on reference
+    /// sequences longer than 2^31 - 1 bases. Formats that are limited to 32-bit positions (e.g.,
+    /// BAM) return an error when queried with a region that cannot be represented.
     ///
     /// # Examples
     ///
@@ -82,7 +86,7 @@ impl Region {
     /// let region = Region::mapped("sq0", 1, 5);
     /// assert!(matches!(region, Region::Mapped { name, start: 1, end: 5 }));
     /// ```
-    pub fn mapped<I>(name: I, start: i32, end: i32) -> Self
+    pub fn mapped<I>(name: I, start: i64, end: i64) -> Self
     where
         I: Into<String>,
     {
@@ -116,6 +120,126 @@ impl Region {
             Self::All => ALL_NAME,
         }
     }
+
+    /// Validates the region against a reference sequence dictionary.
+    ///
+    /// A mapped region is valid if its reference sequence is in the dictionary, its start
+    /// position is at least 1, its start position is not after its end position, and its end
+    /// position is within the length of the reference sequence. Unmapped regions and regions
+    /// representing all records are always valid.
+    ///
+    /// With [`ValidationPolicy::Clamp`], a region that overlaps the reference sequence but
+    /// extends past its bounds is clamped to them instead of being an error.
+    ///
+    /// This returns the validated region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{region::ValidationPolicy, Region};
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences =
+    ///     vec![(String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 8))]
+    ///         .into_iter()
+    ///         .collect();
+    ///
+    /// let region = Region::mapped("sq0", 5, 13);
+    /// assert!(region.validate(&reference_sequences, ValidationPolicy::Strict).is_err());
+    /// assert_eq!(
+    ///     region.validate(&reference_sequences, ValidationPolicy::Clamp),
+    ///     Ok(Region::mapped("sq0", 5, 8))
+    /// );
+    ///
+    /// let region = Region::mapped("sq1", 1, 5);
+    /// assert!(region.validate(&reference_sequences, ValidationPolicy::Clamp).is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        reference_sequences: &ReferenceSequences,
+        policy: ValidationPolicy,
+    ) -> Result<Self, ValidationError> {
+        let (name, start, end) = match self {
+            Self::Mapped { name, start, end } => (name, *start, *end),
+            Self::Unmapped | Self::All => return Ok(self.clone()),
+        };
+
+        let reference_sequence = reference_sequences
+            .get(name)
+            .ok_or_else(|| ValidationError::MissingReferenceSequence(name.clone()))?;
+
+        if start > end {
+            return Err(ValidationError::InvalidInterval(self.clone()));
+        }
+
+        let len = i64::from(reference_sequence.len());
+        let out_of_bounds = || ValidationError::OutOfBounds(self.clone(), len);
+
+        if start >= MIN_POSITION && end <= len {
+            return Ok(self.clone());
+        }
+
+        match policy {
+            ValidationPolicy::Strict => Err(out_of_bounds()),
+            ValidationPolicy::Clamp => {
+                if end < MIN_POSITION || start > len {
+                    Err(out_of_bounds())
+                } else {
+                    let start = cmp::max(start, MIN_POSITION);
+                    let end = cmp::min(end, len);
+                    Ok(Self::mapped(name.as_str(), start, end))
+                }
+            }
+        }
+    }
+}
+
+/// How [`Region::validate`] handles regions that extend past the bounds of their reference
+/// sequences.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationPolicy {
+    /// Out-of-bounds regions are an error.
+    Strict,
+    /// Out-of-bounds regions are clamped to the bounds of their reference sequences. Regions that
+    /// do not overlap their reference sequences are still an error.
+    Clamp,
+}
+
+/// An error returned when a region fails validation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// The reference sequence is not in the reference sequence dictionary.
+    MissingReferenceSequence(String),
+    /// The start position is after the end position.
+    InvalidInterval(Region),
+    /// The region is out of the bounds of its reference sequence, which has the given length.
+    OutOfBounds(Region, i64),
+}
+
+impl error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReferenceSequence(name) => write!(
+                f,
+                "reference sequence not in reference sequence dictionary: {}",
+                name
+            ),
+            Self::InvalidInterval(region) => write!(
+                f,
+                "invalid region: {}: start position is after end position",
+                region
+            ),
+            Self::OutOfBounds(region, len) => write!(
+                f,
+                "invalid region: {}: out of bounds of reference sequence {} (length {})",
+                region,
+                region.name(),
+                len
+            ),
+        }
+    }
 }
 
 impl fmt::Display for Region {
@@ -151,7 +275,7 @@ impl fmt::Display for ParseError {
     }
 }
 
-fn parse_interval(s: &str) -> Result<(i32, Option<i32>), ParseError> {
+fn parse_interval(s: &str) -> Result<(i64, Option<i64>), ParseError> {
     let mut components = s.splitn(2, '-');
 
     let start = match components.next() {
@@ -264,6 +388,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_str_reference_sequences_with_large_positions() {
+        let reference_sequences: ReferenceSequences =
+            vec![ReferenceSequence::new(String::from("sq0"), 8)]
+                .into_iter()
+                .map(|rs| (rs.name().into(), rs))
+                .collect();
+
+        assert_eq!(
+            Region::from_str_reference_sequences("sq0:4294967296-4294967306", &reference_sequences),
+            Ok(Region::Mapped {
+                name: String::from("sq0"),
+                start: 4294967296,
+                end: 4294967306,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let reference_sequences: ReferenceSequences =
+            vec![ReferenceSequence::new(String::from("sq0"), 8)]
+                .into_iter()
+                .map(|rs| (rs.name().into(), rs))
+                .collect();
+
+        let validate = |region: Region, policy| region.validate(&reference_sequences, policy);
+
+        for &policy in &[ValidationPolicy::Strict, ValidationPolicy::Clamp] {
+            assert_eq!(validate(Region::Unmapped, policy), Ok(Region::Unmapped));
+            assert_eq!(validate(Region::All, policy), Ok(Region::All));
+
+            assert_eq!(
+                validate(Region::mapped("sq0", 1, 8), policy),
+                Ok(Region::mapped("sq0", 1, 8))
+            );
+
+            assert_eq!(
+                validate(Region::mapped("sq1", 1, 8), policy),
+                Err(ValidationError::MissingReferenceSequence(String::from(
+                    "sq1"
+                )))
+            );
+
+            assert_eq!(
+                validate(Region::mapped("sq0", 5, 3), policy),
+                Err(ValidationError::InvalidInterval(Region::mapped(
+                    "sq0", 5, 3
+                )))
+            );
+
+            assert_eq!(
+                validate(Region::mapped("sq0", 13, 21), policy),
+                Err(ValidationError::OutOfBounds(
+                    Region::mapped("sq0", 13, 21),
+                    8
+                ))
+            );
+        }
+
+        assert_eq!(
+            validate(Region::mapped("sq0", 0, 13), ValidationPolicy::Strict),
+            Err(ValidationError::OutOfBounds(
+                Region::mapped("sq0", 0, 13),
+                8
+            ))
+        );
+
+        assert_eq!(
+            validate(Region::mapped("sq0", 0, 13), ValidationPolicy::Clamp),
+            Ok(Region::mapped("sq0", 1, 8))
+        );
+    }
+
+    #[test]
+    fn test_fmt_validation_error() {
+        assert_eq!(
+            ValidationError::OutOfBounds(Region::mapped("sq0", 13, 21), 8).to_string(),
+            "invalid region: sq0:13-21: out of bounds of reference sequence sq0 (length 8)"
+        );
+    }
+
     #[test]
     fn test_fmt() {
         assert_eq!(Region::mapped("sq0", 3, 5).to_string(), "sq0:3-5");
@@ -0,0 +1,570 @@
+use std::{cmp, collections::BTreeMap, iter::FromIterator, mem};
+
+use noodles_sam::header::ReferenceSequences;
+
+use super::Region;
+
+// Position coordinates are 1-based.
+const MIN_POSITION: i64 = 1;
+
+type Interval = (i64, i64);
+
+/// A set of mapped genomic regions.
+///
+/// Regions are normalized when added: overlapping and bookended (adjacent) regions on the same
+/// reference sequence are merged. Unmapped regions and regions representing all records are
+/// ignored.
+///
+/// Positions are 1-based and inclusive.
+///
+/// # Examples
+///
+/// ```
+/// use noodles::{Region, RegionSet};
+///
+/// let regions: RegionSet = vec![
+///     Region::mapped("sq0", 5, 8),
+///     Region::mapped("sq0", 1, 4),
+///     Region::mapped("sq1", 3, 5),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// assert_eq!(
+///     regions.regions().collect::<Vec<_>>(),
+///     [Region::mapped("sq0", 1, 8), Region::mapped("sq1", 3, 5)]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RegionSet {
+    intervals: BTreeMap<String, Vec<Interval>>,
+}
+
+impl RegionSet {
+    /// Creates an empty region set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::RegionSet;
+    /// let regions = RegionSet::new();
+    /// assert!(regions.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of disjoint regions in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let mut regions = RegionSet::new();
+    /// regions.insert(&Region::mapped("sq0", 1, 5));
+    /// regions.insert(&Region::mapped("sq0", 3, 8));
+    ///
+    /// assert_eq!(regions.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.intervals
+            .values()
+            .map(|intervals| intervals.len())
+            .sum()
+    }
+
+    /// Returns whether the set has no regions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::RegionSet;
+    /// let regions = RegionSet::new();
+    /// assert!(regions.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the total number of bases covered by the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let mut regions = RegionSet::new();
+    /// regions.insert(&Region::mapped("sq0", 1, 5));
+    /// regions.insert(&Region::mapped("sq1", 3, 8));
+    ///
+    /// assert_eq!(regions.base_count(), 11);
+    /// ```
+    pub fn base_count(&self) -> i64 {
+        self.intervals
+            .values()
+            .flatten()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Adds a region to the set.
+    ///
+    /// Unmapped regions, regions representing all records, and empty regions (where the end is
+    /// less than the start) are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let mut regions = RegionSet::new();
+    /// regions.insert(&Region::mapped("sq0", 1, 5));
+    /// regions.insert(&Region::Unmapped);
+    ///
+    /// assert_eq!(regions.len(), 1);
+    /// ```
+    pub fn insert(&mut self, region: &Region) {
+        if let Region::Mapped { name, start, end } = region {
+            if start <= end {
+                let intervals = self.intervals.entry(name.clone()).or_default();
+                intervals.push((*start, *end));
+                *intervals = normalize(mem::take(intervals));
+            }
+        }
+    }
+
+    /// Returns whether the given position is in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let mut regions = RegionSet::new();
+    /// regions.insert(&Region::mapped("sq0", 3, 5));
+    ///
+    /// assert!(regions.contains("sq0", 3));
+    /// assert!(!regions.contains("sq0", 8));
+    /// assert!(!regions.contains("sq1", 3));
+    /// ```
+    pub fn contains(&self, name: &str, position: i64) -> bool {
+        self.intervals
+            .get(name)
+            .map(|intervals| {
+                intervals
+                    .binary_search_by(|&(start, end)| {
+                        if end < position {
+                            cmp::Ordering::Less
+                        } else if start > position {
+                            cmp::Ordering::Greater
+                        } else {
+                            cmp::Ordering::Equal
+                        }
+                    })
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    }
+
+    // Returns the disjoint intervals on the given reference sequence.
+    pub(crate) fn intervals(&self, name: &str) -> &[(i64, i64)] {
+        self.intervals
+            .get(name)
+            .map(|intervals| &intervals[..])
+            .unwrap_or(&[])
+    }
+
+    /// Returns an iterator over the disjoint regions in the set.
+    ///
+    /// Regions are ordered by reference sequence name and then start position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let mut regions = RegionSet::new();
+    /// regions.insert(&Region::mapped("sq0", 1, 5));
+    ///
+    /// let mut iter = regions.regions();
+    /// assert_eq!(iter.next(), Some(Region::mapped("sq0", 1, 5)));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn regions(&self) -> impl Iterator<Item = Region> + '_ {
+        self.intervals.iter().flat_map(|(name, intervals)| {
+            intervals
+                .iter()
+                .map(move |&(start, end)| Region::mapped(name.as_str(), start, end))
+        })
+    }
+
+    /// Returns the union of this set and another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let a: RegionSet = vec![Region::mapped("sq0", 1, 5)].into_iter().collect();
+    /// let b: RegionSet = vec![Region::mapped("sq0", 6, 8)].into_iter().collect();
+    ///
+    /// assert_eq!(
+    ///     a.union(&b).regions().collect::<Vec<_>>(),
+    ///     [Region::mapped("sq0", 1, 8)]
+    /// );
+    /// ```
+    pub fn union(&self, other: &Self) -> Self {
+        let mut intervals = self.intervals.clone();
+
+        for (name, other_intervals) in &other.intervals {
+            let entry = intervals.entry(name.clone()).or_default();
+            entry.extend(other_intervals);
+            *entry = normalize(mem::take(entry));
+        }
+
+        Self { intervals }
+    }
+
+    /// Returns the intersection of this set and another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let a: RegionSet = vec![Region::mapped("sq0", 1, 5)].into_iter().collect();
+    /// let b: RegionSet = vec![Region::mapped("sq0", 3, 8)].into_iter().collect();
+    ///
+    /// assert_eq!(
+    ///     a.intersect(&b).regions().collect::<Vec<_>>(),
+    ///     [Region::mapped("sq0", 3, 5)]
+    /// );
+    /// ```
+    pub fn intersect(&self, other: &Self) -> Self {
+        let intervals = self
+            .intervals
+            .iter()
+            .filter_map(|(name, a)| {
+                other
+                    .intervals
+                    .get(name)
+                    .map(|b| (name.clone(), intersect(a, b)))
+            })
+            .filter(|(_, intervals)| !intervals.is_empty())
+            .collect();
+
+        Self { intervals }
+    }
+
+    /// Returns the regions of this set that are not in another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    ///
+    /// let a: RegionSet = vec![Region::mapped("sq0", 1, 8)].into_iter().collect();
+    /// let b: RegionSet = vec![Region::mapped("sq0", 3, 5)].into_iter().collect();
+    ///
+    /// assert_eq!(
+    ///     a.subtract(&b).regions().collect::<Vec<_>>(),
+    ///     [Region::mapped("sq0", 1, 2), Region::mapped("sq0", 6, 8)]
+    /// );
+    /// ```
+    pub fn subtract(&self, other: &Self) -> Self {
+        let intervals = self
+            .intervals
+            .iter()
+            .map(|(name, a)| match other.intervals.get(name) {
+                Some(b) => (name.clone(), subtract(a, b)),
+                None => (name.clone(), a.clone()),
+            })
+            .filter(|(_, intervals)| !intervals.is_empty())
+            .collect();
+
+        Self { intervals }
+    }
+
+    /// Returns this set with each region extended by `n` bases on both sides.
+    ///
+    /// Regions are clamped to the bounds of their reference sequences. Regions on reference
+    /// sequences not in the given dictionary are only clamped at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences =
+    ///     vec![(String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 13))]
+    ///         .into_iter()
+    ///         .collect();
+    ///
+    /// let regions: RegionSet = vec![Region::mapped("sq0", 3, 5), Region::mapped("sq0", 10, 11)]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     regions.pad(1, &reference_sequences).regions().collect::<Vec<_>>(),
+    ///     [Region::mapped("sq0", 2, 6), Region::mapped("sq0", 9, 12)]
+    /// );
+    /// ```
+    pub fn pad(&self, n: i64, reference_sequences: &ReferenceSequences) -> Self {
+        let intervals = self
+            .intervals
+            .iter()
+            .map(|(name, intervals)| {
+                let max_end = reference_sequences
+                    .get(name)
+                    .map(|rs| i64::from(rs.len()))
+                    .unwrap_or(i64::MAX);
+
+                let padded_intervals = intervals
+                    .iter()
+                    .map(|&(start, end)| {
+                        let start = cmp::max(start.saturating_sub(n), MIN_POSITION);
+                        let end = cmp::min(end.saturating_add(n), max_end);
+                        (start, end)
+                    })
+                    .filter(|(start, end)| start <= end)
+                    .collect();
+
+                (name.clone(), normalize(padded_intervals))
+            })
+            .filter(|(_, intervals)| !intervals.is_empty())
+            .collect();
+
+        Self { intervals }
+    }
+
+    /// Returns the regions of the given reference sequences that are not in this set.
+    ///
+    /// Regions on reference sequences not in the given dictionary are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles::{Region, RegionSet};
+    /// use noodles_sam::header::{ReferenceSequence, ReferenceSequences};
+    ///
+    /// let reference_sequences: ReferenceSequences = vec![
+    ///     (String::from("sq0"), ReferenceSequence::new(String::from("sq0"), 13)),
+    ///     (String::from("sq1"), ReferenceSequence::new(String::from("sq1"), 8)),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let regions: RegionSet = vec![Region::mapped("sq0", 3, 5)].into_iter().collect();
+    ///
+    /// assert_eq!(
+    ///     regions.complement(&reference_sequences).regions().collect::<Vec<_>>(),
+    ///     [
+    ///         Region::mapped("sq0", 1, 2),
+    ///         Region::mapped("sq0", 6, 13),
+    ///         Region::mapped("sq1", 1, 8),
+    ///     ]
+    /// );
+    /// ```
+    pub fn complement(&self, reference_sequences: &ReferenceSequences) -> Self {
+        let intervals = reference_sequences
+            .iter()
+            .map(|(name, reference_sequence)| {
+                let whole = [(MIN_POSITION, i64::from(reference_sequence.len()))];
+
+                let intervals = match self.intervals.get(name) {
+                    Some(intervals) => subtract(&whole, intervals),
+                    None => whole.to_vec(),
+                };
+
+                (name.clone(), intervals)
+            })
+            .filter(|(_, intervals)| !intervals.is_empty())
+            .collect();
+
+        Self { intervals }
+    }
+}
+
+impl FromIterator<Region> for RegionSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Region>,
+    {
+        let mut intervals: BTreeMap<String, Vec<Interval>> = BTreeMap::new();
+
+        for region in iter {
+            if let Region::Mapped { name, start, end } = region {
+                if start <= end {
+                    intervals.entry(name).or_default().push((start, end));
+                }
+            }
+        }
+
+        for list in intervals.values_mut() {
+            *list = normalize(mem::take(list));
+        }
+
+        Self { intervals }
+    }
+}
+
+// Sorts and merges overlapping and bookended intervals.
+fn normalize(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort_unstable();
+
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => {
+                last.1 = cmp::max(last.1, end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+// Both lists are expected to be normalized.
+fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let mut intervals = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        let start = cmp::max(a[i].0, b[j].0);
+        let end = cmp::min(a[i].1, b[j].1);
+
+        if start <= end {
+            intervals.push((start, end));
+        }
+
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    intervals
+}
+
+// Both lists are expected to be normalized.
+fn subtract(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let mut intervals = Vec::new();
+    let mut j = 0;
+
+    for &(start, end) in a {
+        let mut start = start;
+
+        while j < b.len() && b[j].1 < start {
+            j += 1;
+        }
+
+        let mut k = j;
+
+        while k < b.len() && b[k].0 <= end {
+            if b[k].0 > start {
+                intervals.push((start, b[k].0 - 1));
+            }
+
+            start = cmp::max(start, b[k].1.saturating_add(1));
+            k += 1;
+        }
+
+        if start <= end {
+            intervals.push((start, end));
+        }
+    }
+
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(vec![(8, 13), (1, 5), (3, 6), (7, 7), (21, 34)]),
+            [(1, 13), (21, 34)]
+        );
+        assert!(normalize(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_intersect() {
+        let a = [(1, 5), (8, 13), (21, 34)];
+        let b = [(3, 10), (13, 21), (40, 55)];
+        assert_eq!(intersect(&a, &b), [(3, 5), (8, 10), (13, 13), (21, 21)]);
+        assert!(intersect(&a, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = [(1, 13), (21, 34)];
+        let b = [(3, 5), (8, 8), (13, 22), (30, 40)];
+        assert_eq!(subtract(&a, &b), [(1, 2), (6, 7), (9, 12), (23, 29)]);
+        assert_eq!(subtract(&a, &[]), a);
+        assert!(subtract(&a, &[(1, 40)]).is_empty());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut regions = RegionSet::new();
+
+        regions.insert(&Region::mapped("sq0", 8, 13));
+        regions.insert(&Region::mapped("sq0", 1, 7));
+        regions.insert(&Region::mapped("sq1", 5, 3));
+        regions.insert(&Region::All);
+
+        assert_eq!(
+            regions.regions().collect::<Vec<_>>(),
+            [Region::mapped("sq0", 1, 13)]
+        );
+        assert_eq!(regions.base_count(), 13);
+    }
+
+    #[test]
+    fn test_contains() {
+        let regions: RegionSet = vec![Region::mapped("sq0", 3, 5), Region::mapped("sq0", 8, 13)]
+            .into_iter()
+            .collect();
+
+        assert!(!regions.contains("sq0", 2));
+        assert!(regions.contains("sq0", 3));
+        assert!(regions.contains("sq0", 5));
+        assert!(!regions.contains("sq0", 6));
+        assert!(regions.contains("sq0", 13));
+        assert!(!regions.contains("sq0", 14));
+    }
+
+    #[test]
+    fn test_pad() {
+        use noodles_sam::header::ReferenceSequence;
+
+        let reference_sequences: ReferenceSequences =
+            vec![ReferenceSequence::new(String::from("sq0"), 13)]
+                .into_iter()
+                .map(|rs| (rs.name().into(), rs))
+                .collect();
+
+        let regions: RegionSet = vec![
+            Region::mapped("sq0", 3, 4),
+            Region::mapped("sq0", 10, 11),
+            Region::mapped("sq1", 2, 3),
+        ]
+        .into_iter()
+        .collect();
+
+        let actual: Vec<_> = regions.pad(3, &reference_sequences).regions().collect();
+
+        assert_eq!(
+            actual,
+            [Region::mapped("sq0", 1, 13), Region::mapped("sq1", 1, 6)]
+        );
+    }
+}